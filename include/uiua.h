@@ -0,0 +1,62 @@
+/* C API for embedding Uiua
+ *
+ * Build the dynamic and static libraries with
+ *     cargo build --lib --release --features capi
+ *
+ * Functions that can fail return 0 on success and a nonzero value on failure.
+ * The message of the most recent failure can be retrieved with uiua_last_error.
+ * Panics in the interpreter are caught and reported as failures.
+ */
+
+#ifndef UIUA_H
+#define UIUA_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A Uiua runtime */
+typedef struct UiuaRuntime UiuaRuntime;
+
+/* Create a new runtime. It must be freed with uiua_free. Returns NULL on failure. */
+UiuaRuntime *uiua_new(void);
+/* Free a runtime */
+void uiua_free(UiuaRuntime *rt);
+
+/* Compile and run a string of Uiua code on the runtime's current stack */
+int uiua_run_str(UiuaRuntime *rt, const char *code);
+/* Get the message of the most recent failure, or NULL if the most recent call succeeded.
+ * The string is owned by the runtime and is valid until the next call with the runtime. */
+const char *uiua_last_error(const UiuaRuntime *rt);
+
+/* Get the number of values on the stack */
+size_t uiua_stack_len(const UiuaRuntime *rt);
+
+/* Push a number onto the stack */
+int uiua_push_number(UiuaRuntime *rt, double n);
+/* Push a string onto the stack */
+int uiua_push_string(UiuaRuntime *rt, const char *s);
+/* Push an array of numbers with the given shape onto the stack */
+int uiua_push_array(UiuaRuntime *rt, const double *data, const size_t *shape, size_t rank);
+
+/* Pop a scalar number from the stack */
+int uiua_pop_number(UiuaRuntime *rt, double *out);
+/* Get the rank of the value on top of the stack */
+size_t uiua_top_rank(const UiuaRuntime *rt);
+/* Write the shape of the value on top of the stack into out */
+int uiua_top_shape(UiuaRuntime *rt, size_t *out);
+/* Pop an array of len numbers from the stack into out */
+int uiua_pop_array(UiuaRuntime *rt, double *out, size_t len);
+/* Pop a string from the stack. Returns NULL on failure.
+ * The string must be freed with uiua_free_string. */
+char *uiua_pop_string(UiuaRuntime *rt);
+/* Free a string returned by uiua_pop_string */
+void uiua_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
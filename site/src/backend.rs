@@ -2,7 +2,6 @@ use std::{
     any::Any,
     cell::RefCell,
     collections::{HashMap, HashSet},
-    io::Cursor,
     path::{Path, PathBuf},
     sync::Mutex,
 };
@@ -10,7 +9,7 @@ use std::{
 use crate::{editor::get_ast_time, weewuh};
 use leptos::*;
 use leptos_query::{create_query, QueryOptions};
-use uiua::{Handle, Report, SysBackend, EXAMPLE_TXT, EXAMPLE_UA};
+use uiua::{Handle, Report, RichOutput, RichOutputKind, SysBackend, EXAMPLE_TXT, EXAMPLE_UA};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
@@ -21,6 +20,7 @@ pub struct WebBackend {
     pub trace: Mutex<String>,
     streams: Mutex<HashMap<Handle, VirtualStream>>,
     files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    secrets: Mutex<HashMap<String, String>>,
 }
 
 struct VirtualStream {
@@ -58,6 +58,7 @@ impl Default for WebBackend {
                 ]
                 .into(),
             ),
+            secrets: HashMap::new().into(),
         }
     }
 }
@@ -168,19 +169,21 @@ impl SysBackend for WebBackend {
             .prompt_with_message("Enter a line of text for stdin")
             .unwrap_or(None))
     }
-    fn show_image(&self, image: image::DynamicImage) -> Result<(), String> {
-        let mut bytes = Cursor::new(Vec::new());
-        image
-            .write_to(&mut bytes, image::ImageOutputFormat::Png)
-            .map_err(|e| format!("Failed to show image: {e}"))?;
-        self.stdout
-            .lock()
-            .unwrap()
-            .push(OutputItem::Image(bytes.into_inner()));
-        Ok(())
-    }
-    fn show_gif(&self, gif_bytes: Vec<u8>) -> Result<(), String> {
-        self.stdout.lock().unwrap().push(OutputItem::Gif(gif_bytes));
+    fn show_rich(&self, output: RichOutput) -> Result<(), String> {
+        let item = match output.kind {
+            RichOutputKind::Image => OutputItem::Image(output.data),
+            RichOutputKind::Animation => OutputItem::Gif(output.data),
+            RichOutputKind::Audio => OutputItem::Audio(output.data),
+            RichOutputKind::Plot => OutputItem::Svg(output.text().unwrap_or_default().into()),
+            RichOutputKind::Table => {
+                let mut stdout = self.stdout.lock().unwrap();
+                for line in output.text().unwrap_or_default().lines() {
+                    stdout.push(OutputItem::String(line.replace('\t', " ")));
+                }
+                return Ok(());
+            }
+        };
+        self.stdout.lock().unwrap().push(item);
         Ok(())
     }
     fn list_dir(&self, mut path: &str) -> Result<Vec<String>, String> {
@@ -212,6 +215,28 @@ impl SysBackend for WebBackend {
     fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
         self.file_mut(path, true, |file| *file = contents.to_vec())
     }
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        self.file_mut(path, true, |file| file.extend_from_slice(contents))
+    }
+    fn sync(&self, handle: Handle) -> Result<(), String> {
+        let streams = self.streams.lock().unwrap();
+        let stream = streams.get(&handle).ok_or("Invalid stream handle")?;
+        if let Some(path) = &stream.path {
+            self.file_write_all(path, &stream.contents)?;
+        }
+        Ok(())
+    }
+    fn lock_file(&self, handle: Handle, _exclusive: bool, _block: bool) -> Result<bool, String> {
+        // Nothing else can access the virtual filesystem, so locks are always available
+        if self.streams.lock().unwrap().contains_key(&handle) {
+            Ok(true)
+        } else {
+            Err("Invalid stream handle".into())
+        }
+    }
+    fn unlock_file(&self, handle: Handle) -> Result<(), String> {
+        self.lock_file(handle, false, false).map(drop)
+    }
     fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
         self.file(path, |contents| contents.to_vec())
     }
@@ -287,13 +312,13 @@ impl SysBackend for WebBackend {
     fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String> {
         let mut streams = self.streams.lock().unwrap();
         let stream = streams.get_mut(&handle).ok_or("Invalid stream handle")?;
-        let offset = stream.contents[stream.pos..]
+        let end = stream.contents[stream.pos..]
             .windows(delim.len())
             .position(|w| w == delim)
+            .map(|offset| stream.pos + offset + delim.len())
             .unwrap_or(stream.contents.len());
-        let end = stream.pos + offset;
         let data = stream.contents[stream.pos..end].to_vec();
-        stream.pos = (end + delim.len()).min(stream.contents.len());
+        stream.pos = end;
         Ok(data)
     }
     fn delete(&self, path: &str) -> Result<(), String> {
@@ -303,10 +328,6 @@ impl SysBackend for WebBackend {
     fn trash(&self, path: &str) -> Result<(), String> {
         self.delete(path)
     }
-    fn play_audio(&self, wav_bytes: Vec<u8>) -> Result<(), String> {
-        (self.stdout.lock().unwrap()).push(OutputItem::Audio(wav_bytes));
-        Ok(())
-    }
     fn stream_audio(&self, mut f: uiua::AudioStreamFn) -> Result<(), String> {
         let mut samples = Vec::new();
         let mut t = 0.0;
@@ -332,7 +353,7 @@ impl SysBackend for WebBackend {
             hound::SampleFormat::Int,
             SAMPLE_RATE,
         )?;
-        self.play_audio(bytes)
+        self.show_rich(RichOutput::new(RichOutputKind::Audio, "audio/wav", bytes))
     }
     fn set_clipboard(&self, contents: &str) -> Result<(), String> {
         _ = window()
@@ -342,11 +363,26 @@ impl SysBackend for WebBackend {
             .write_text(contents);
         Ok(())
     }
+    fn secret(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.secrets.lock().unwrap().get(name).cloned())
+    }
+    fn set_secret(&self, name: &str, secret: &str) -> Result<(), String> {
+        (self.secrets.lock().unwrap()).insert(name.into(), secret.into());
+        Ok(())
+    }
+    fn delete_secret(&self, name: &str) -> Result<(), String> {
+        self.secrets.lock().unwrap().remove(name);
+        Ok(())
+    }
     fn sleep(&self, seconds: f64) -> Result<(), String> {
         let start = instant::now();
         while (instant::now() - start) / 1000.0 < seconds {}
         Ok(())
     }
+    fn monotonic_time(&self) -> Result<f64, String> {
+        // On the web, this is `performance.now()`, which is monotonic
+        Ok(instant::now() / 1000.0)
+    }
     fn load_git_module(&self, url: &str, branch: Option<&str>) -> Result<PathBuf, String> {
         if branch.is_some() {
             return Err("Git branch specification is not supported in the web backend".into());
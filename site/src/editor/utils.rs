@@ -7,15 +7,13 @@ use std::{
 };
 
 use base64::engine::{general_purpose::URL_SAFE, Engine};
-use image::ImageOutputFormat;
 use leptos::*;
 
 use uiua::{
     ast::Item,
-    image_to_bytes,
     lsp::{spans_with_backend, BindingDocsKind},
-    value_to_gif_bytes, value_to_image, value_to_wav_bytes, Compiler, DiagnosticKind, Inputs,
-    Report, ReportFragment, ReportKind, SpanKind, SysBackend, Uiua, UiuaError, UiuaResult, Value,
+    Compiler, DiagnosticKind, Inputs, Report, ReportFragment, ReportKind, RichOutput,
+    RichOutputKind, SpanKind, SysBackend, Uiua, UiuaError, UiuaResult, Value,
 };
 use unicode_segmentation::UnicodeSegmentation;
 use wasm_bindgen::JsCast;
@@ -734,7 +732,11 @@ fn run_code_single(code: &str) -> (Vec<OutputItem>, Option<UiuaError>) {
     let mut error = None;
     let mut comp = Compiler::with_backend(WebBackend::default());
     let comp_backend;
-    let (mut values, io) = match comp.load_str(code).map(|comp| rt.run_compiler(comp)) {
+    let res = comp.load_str(code).map(|comp| {
+        let res = rt.run_compiler(comp);
+        res.and_then(|res| rt.run_exit_hooks().map(|()| res))
+    });
+    let (mut values, io) = match res {
         Ok(Ok(_)) => (
             rt.take_stack(),
             rt.downcast_backend::<WebBackend>().unwrap(),
@@ -761,46 +763,18 @@ fn run_code_single(code: &str) -> (Vec<OutputItem>, Option<UiuaError>) {
     let mut stack = Vec::new();
     let value_count = values.len();
     for (i, value) in values.into_iter().enumerate() {
-        // Try to convert the value to audio
-        if value.shape().last().is_some_and(|&n| n >= 44100 / 4) {
-            if let Ok(bytes) = value_to_wav_bytes(&value, io.audio_sample_rate()) {
-                stack.push(OutputItem::Audio(bytes));
-                continue;
-            }
-        }
-        // Try to convert the value to an image
-        const MIN_AUTO_IMAGE_DIM: usize = 30;
-        if let Ok(image) = value_to_image(&value) {
-            if image.width() >= MIN_AUTO_IMAGE_DIM as u32
-                && image.height() >= MIN_AUTO_IMAGE_DIM as u32
-            {
-                if let Ok(bytes) = image_to_bytes(&image, ImageOutputFormat::Png) {
-                    stack.push(OutputItem::Image(bytes));
-                    continue;
-                }
-            }
-        }
-        // Try to convert the value to a gif
-        if let Ok(bytes) = value_to_gif_bytes(&value, 16.0) {
-            match value.shape().dims() {
-                &[f, h, w] | &[f, h, w, _]
-                    if h >= MIN_AUTO_IMAGE_DIM && w >= MIN_AUTO_IMAGE_DIM && f >= 5 =>
-                {
-                    stack.push(OutputItem::Gif(bytes));
-                    continue;
-                }
-                _ => {}
-            }
-        }
-        // Try to convert the value to SVG
-        if let Ok(mut str) = value.as_string(&rt, "") {
-            if str.starts_with("<svg") && str.ends_with("</svg>") {
-                if !str.contains("xmlns") {
-                    str = str.replacen("<svg", "<svg xmlns=\"http://www.w3.org/2000/svg\"", 1);
-                }
-                stack.push(OutputItem::Svg(str));
+        // Try to show the value as rich output
+        match RichOutput::from_value(&value, io.audio_sample_rate()) {
+            Some(output) if output.kind != RichOutputKind::Table => {
+                stack.push(match output.kind {
+                    RichOutputKind::Image => OutputItem::Image(output.data),
+                    RichOutputKind::Animation => OutputItem::Gif(output.data),
+                    RichOutputKind::Audio => OutputItem::Audio(output.data),
+                    _ => OutputItem::Svg(output.text().unwrap_or_default().into()),
+                });
                 continue;
             }
+            _ => {}
         }
         // Otherwise, just show the value
         let class = if value_count == 1 {
@@ -1025,8 +999,12 @@ pub fn progressive_strings(input: &str) -> Vec<String> {
             Item::Binding(binding) => {
                 lines.push(vec![binding.span().as_str(&inputs, |s| s.into())])
             }
+            Item::Destructure(destructure) => {
+                lines.push(vec![destructure.span().as_str(&inputs, |s| s.into())])
+            }
             Item::TestScope(items) => lines.push(vec![items.span.as_str(&inputs, |s| s.into())]),
             Item::Import(import) => lines.push(vec![import.span().as_str(&inputs, |s| s.into())]),
+            Item::Data(data) => lines.push(vec![data.span().as_str(&inputs, |s| s.into())]),
         }
     }
     let mut strings = Vec::new();
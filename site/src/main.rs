@@ -153,7 +153,7 @@ pub fn Site() -> impl IntoView {
             <ScrollToHash/>
             <Routes>
                 <Route path="embedpad" view=EmbedPad/>
-                <Route path="embed" view=Embed/>
+                <Route path="embed" view=crate::Embed/>
                 <Route path="*" view=move || view! {
                     <main>
                         <div id="top">
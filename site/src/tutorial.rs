@@ -1715,7 +1715,7 @@ fn TutorialModules() -> impl IntoView {
         <Editor example="~ \"example.ua\" ~ RangeDiff"/> // Should fail
         <p>"To enter this arrow, you can put a "<code>"~"</code>" after a binding's normal "<code>"←"</code>" or "<code>"="</code>"."</p>
         <p>"Try formatting the following example to see how this works."</p>
-        <Editor example="A = +1\nB ← +2\nC =~ +3\nD ←~ +4"/>
+        <Editor example="A = +1\nB ← +2\nC =~ +3\nD ←~ +4\nD C 0"/>
 
         <Hd id="git-modules">"Git Modules"</Hd>
         <p>"Modules can be imported from Git repositories. Instead of a path, use a URL prefixed with "<code>"git:"</code>"."</p>
@@ -1724,6 +1724,8 @@ fn TutorialModules() -> impl IntoView {
         <p>"On the site, code is pulled from a "<code>"lib.ua"</code>" file at the root of the repository. Loading other files is not supported."</p>
         <p>"To use Git modules in the "<A href="/docs/install">"native interpreter"</A>", you must have Git installed. The repository is added as a Git submodule and the "<code>"lib.ua"</code>" file is loaded as the module's contents."</p>
         <p>"The native interpreter also supports adding an additional "<code>"branch: <branch-name>"</code>" specifier after the URL."</p>
+        <p>"The native interpreter can also import a module directly from an "<code>"https://"</code>" URL. The file is saved in the "<code>"uiua-modules"</code>" folder."</p>
+        <p>"If the "<code>"UIUA_HTTP_CACHE"</code>" environment variable is set to "<code>"1"</code>", responses are cached according to their HTTP caching headers, so repeated runs do not download the module again until it may have changed. If the server cannot be reached, the cached module is used with a warning."</p>
         <p>"You can find a curated list of Uiua modules "<a href="https://github.com/uiua-lang/uiua-modules">"here"</a>"."</p>
     }
 }
@@ -0,0 +1,204 @@
+//! A compact binary encoding of values
+//!
+//! Unlike the JSON and CBOR encodings, this encoding preserves the exact type, shape,
+//! flags, label, and map keys of every array, so decoding gives back the same value.
+
+use ecow::EcoVec;
+
+use crate::{Array, ArrayFlags, Boxed, Complex, Shape, Uiua, UiuaResult, Value};
+
+/// The version of the encoding, written as the first byte
+const VERSION: u8 = 0;
+
+/// Extra parts that follow an array's data
+const HAS_LABEL: u8 = 1;
+const HAS_MAP_KEYS: u8 = 2;
+
+impl Value {
+    /// Encode the value in a compact binary format
+    pub(crate) fn to_binary(&self, env: &Uiua) -> UiuaResult<Vec<u8>> {
+        let mut bytes = vec![VERSION];
+        encode(self, &mut bytes, env)?;
+        Ok(bytes)
+    }
+    /// Decode a value encoded with [`Value::to_binary`]
+    pub(crate) fn from_binary(bytes: &[u8], env: &Uiua) -> UiuaResult<Self> {
+        let mut decoder = Decoder { bytes, env };
+        let version = decoder.byte()?;
+        if version != VERSION {
+            return Err(env.error(format!("Unsupported binary encoding version {version}")));
+        }
+        let value = decoder.value()?;
+        if !decoder.bytes.is_empty() {
+            return Err(decoder.invalid());
+        }
+        Ok(value)
+    }
+}
+
+fn encode(value: &Value, bytes: &mut Vec<u8>, env: &Uiua) -> UiuaResult {
+    // Map keys are encoded after the values
+    let (keys, value) = if value.is_map() {
+        let (keys, values) = value.clone().unmap(env)?;
+        (Some(keys), values)
+    } else {
+        (None, value.clone())
+    };
+    let tag = match &value {
+        Value::Byte(_) => 0,
+        Value::Num(_) => 1,
+        Value::Int(_) => 2,
+        Value::Float(_) => 3,
+        Value::Complex(_) => 4,
+        Value::Char(_) => 5,
+        Value::Box(_) => 6,
+    };
+    let meta = value.meta();
+    let mut extras = 0;
+    if meta.label.is_some() {
+        extras |= HAS_LABEL;
+    }
+    if keys.is_some() {
+        extras |= HAS_MAP_KEYS;
+    }
+    bytes.extend([tag, meta.flags.bits(), extras]);
+    write_len(value.rank(), bytes);
+    for &dim in value.shape().iter() {
+        write_len(dim, bytes);
+    }
+    match &value {
+        Value::Byte(arr) => bytes.extend_from_slice(&arr.data),
+        Value::Num(arr) => arr.data.iter().for_each(|n| bytes.extend(n.to_le_bytes())),
+        Value::Int(arr) => arr.data.iter().for_each(|n| bytes.extend(n.to_le_bytes())),
+        Value::Float(arr) => arr.data.iter().for_each(|n| bytes.extend(n.to_le_bytes())),
+        Value::Complex(arr) => {
+            for c in &arr.data {
+                bytes.extend(c.re.to_le_bytes());
+                bytes.extend(c.im.to_le_bytes());
+            }
+        }
+        Value::Char(arr) => write_str(&arr.data.iter().collect::<String>(), bytes),
+        Value::Box(arr) => {
+            for Boxed(val) in &arr.data {
+                encode(val, bytes, env)?;
+            }
+        }
+    }
+    if let Some(label) = &meta.label {
+        write_str(label, bytes);
+    }
+    if let Some(keys) = &keys {
+        encode(keys, bytes, env)?;
+    }
+    Ok(())
+}
+
+/// Write a length as an unsigned LEB128 integer
+fn write_len(mut n: usize, bytes: &mut Vec<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            bytes.push(byte);
+            break;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn write_str(s: &str, bytes: &mut Vec<u8>) {
+    write_len(s.len(), bytes);
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    env: &'a Uiua,
+}
+
+impl<'a> Decoder<'a> {
+    fn invalid(&self) -> crate::UiuaError {
+        self.env.error("Invalid binary encoding of a value")
+    }
+    fn take(&mut self, n: usize) -> UiuaResult<&'a [u8]> {
+        if n > self.bytes.len() {
+            return Err(self.invalid());
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    fn byte(&mut self) -> UiuaResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+    fn len(&mut self) -> UiuaResult<usize> {
+        let mut n = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(self.invalid())
+    }
+    fn str(&mut self) -> UiuaResult<&'a str> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes).map_err(|_| self.invalid())
+    }
+    /// Decode elements that are each `N` little-endian bytes
+    fn elems<T: Clone, const N: usize>(
+        &mut self,
+        count: usize,
+        f: fn([u8; N]) -> T,
+    ) -> UiuaResult<EcoVec<T>> {
+        let bytes = self.take(count.checked_mul(N).ok_or_else(|| self.invalid())?)?;
+        Ok((bytes.chunks_exact(N))
+            .map(|chunk| f(chunk.try_into().unwrap()))
+            .collect())
+    }
+    fn value(&mut self) -> UiuaResult<Value> {
+        let [tag, flags, extras] = [self.byte()?, self.byte()?, self.byte()?];
+        let rank = self.len()?;
+        let shape: Shape = (0..rank).map(|_| self.len()).collect::<UiuaResult<_>>()?;
+        let count = shape.elements();
+        let mut value: Value = match tag {
+            0 => Array::new(shape, EcoVec::from(self.take(count)?)).into(),
+            1 => Array::new(shape, self.elems(count, f64::from_le_bytes)?).into(),
+            2 => Array::new(shape, self.elems(count, i64::from_le_bytes)?).into(),
+            3 => Array::new(shape, self.elems(count, f32::from_le_bytes)?).into(),
+            4 => {
+                let data = self.elems(count, |bytes: [u8; 16]| {
+                    let [re, im] = [&bytes[..8], &bytes[8..]]
+                        .map(|half| f64::from_le_bytes(half.try_into().unwrap()));
+                    Complex::new(re, im)
+                })?;
+                Array::new(shape, data).into()
+            }
+            5 => {
+                let data: EcoVec<char> = self.str()?.chars().collect();
+                if data.len() != count {
+                    return Err(self.invalid());
+                }
+                Array::new(shape, data).into()
+            }
+            6 => {
+                let data = (0..count)
+                    .map(|_| self.value().map(Boxed))
+                    .collect::<UiuaResult<EcoVec<_>>>()?;
+                Array::new(shape, data).into()
+            }
+            _ => return Err(self.invalid()),
+        };
+        value.meta_mut().flags = ArrayFlags::from_bits_truncate(flags);
+        if extras & HAS_LABEL != 0 {
+            value.meta_mut().label = Some(self.str()?.into());
+        }
+        if extras & HAS_MAP_KEYS != 0 {
+            let keys = self.value()?;
+            value.map(keys, self.env)?;
+        }
+        Ok(value)
+    }
+}
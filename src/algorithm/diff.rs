@@ -0,0 +1,136 @@
+//! Structural diffs between values
+
+use std::fmt::Write;
+
+use crate::{Uiua, UiuaResult, Value};
+
+/// Above this many row pairs, rows are compared by position rather than aligned
+const MAX_ALIGN_PAIRS: usize = 1 << 22;
+
+pub fn diff(env: &mut Uiua) -> UiuaResult {
+    let a = env.pop(1)?;
+    let b = env.pop(2)?;
+    let mut s = String::new();
+    if a != b {
+        if a.rank() > 0 && b.rank() > 0 && a.shape()[1..] == b.shape()[1..] {
+            diff_rows(&a, &b, &mut s, env)?;
+        } else if a.shape() == b.shape() {
+            s.push_str("~ ");
+            change(&a, &b, &mut s, env)?;
+        } else {
+            _ = write!(s, "- {}\n+ {}", a.representation(), b.representation());
+        }
+    }
+    env.push(s);
+    Ok(())
+}
+
+enum Edit {
+    Keep,
+    Remove(usize),
+    Add(usize),
+}
+
+fn diff_rows(a: &Value, b: &Value, s: &mut String, env: &Uiua) -> UiuaResult {
+    let a_rows: Vec<Value> = a.rows().collect();
+    let b_rows: Vec<Value> = b.rows().collect();
+    let edits = align(&a_rows, &b_rows);
+    let mut i = 0;
+    while i < edits.len() {
+        if let Edit::Keep = edits[i] {
+            i += 1;
+            continue;
+        }
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        while let Some(edit) = edits.get(i) {
+            match *edit {
+                Edit::Keep => break,
+                Edit::Remove(r) => removed.push(r),
+                Edit::Add(r) => added.push(r),
+            }
+            i += 1;
+        }
+        let changed = removed.len().min(added.len());
+        for (&r, &d) in removed.iter().zip(&added) {
+            line_start(s);
+            if r == d {
+                _ = write!(s, "~ {r}: ");
+            } else {
+                _ = write!(s, "~ {r}→{d}: ");
+            }
+            change(&a_rows[r], &b_rows[d], s, env)?;
+        }
+        for &r in &removed[changed..] {
+            line_start(s);
+            _ = write!(s, "- {r}: {}", a_rows[r].representation());
+        }
+        for &d in &added[changed..] {
+            line_start(s);
+            _ = write!(s, "+ {d}: {}", b_rows[d].representation());
+        }
+    }
+    Ok(())
+}
+
+fn line_start(s: &mut String) {
+    if !s.is_empty() {
+        s.push('\n');
+    }
+}
+
+/// Render a changed value, along with the difference if both are numbers
+fn change(a: &Value, b: &Value, s: &mut String, env: &Uiua) -> UiuaResult {
+    _ = write!(s, "{} → {}", a.representation(), b.representation());
+    let is_numeric = |val: &Value| !matches!(val, Value::Char(_) | Value::Box(_));
+    if is_numeric(a) && is_numeric(b) && a.shape() == b.shape() {
+        let delta = a.clone().sub(b.clone(), 0, 0, env)?;
+        _ = write!(s, " Δ{}", delta.representation());
+    }
+    Ok(())
+}
+
+/// Align rows by their longest common subsequence
+fn align(a: &[Value], b: &[Value]) -> Vec<Edit> {
+    let (n, m) = (a.len(), b.len());
+    if n.saturating_mul(m) > MAX_ALIGN_PAIRS {
+        let mut edits = Vec::with_capacity(n.max(m) * 2);
+        for i in 0..n.max(m) {
+            match (a.get(i), b.get(i)) {
+                (Some(x), Some(y)) if x == y => edits.push(Edit::Keep),
+                (x, y) => {
+                    edits.extend(x.map(|_| Edit::Remove(i)));
+                    edits.extend(y.map(|_| Edit::Add(i)));
+                }
+            }
+        }
+        return edits;
+    }
+    // lengths[i][j] is the length of the LCS of a[i..] and b[j..]
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if a[i] == b[j] {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+    let mut edits = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if j == m || i < n && lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            edits.push(Edit::Remove(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Add(j));
+            j += 1;
+        }
+    }
+    edits
+}
@@ -0,0 +1,503 @@
+//! Probability distributions
+
+use std::f64::consts::{PI, SQRT_2};
+
+use crate::{Array, Uiua, UiuaResult};
+
+/// The relative precision of series and continued fractions
+const EPS: f64 = 1e-15;
+/// A number near the smallest representable float
+const TINY: f64 = 1e-300;
+/// The maximum number of terms of series and continued fractions
+const MAX_TERMS: usize = 1000;
+
+#[derive(Clone, Copy)]
+enum Distribution {
+    Normal { mean: f64, sd: f64 },
+    T { df: f64 },
+    ChiSquare { df: f64 },
+    Binomial { n: f64, p: f64 },
+}
+
+#[derive(Clone, Copy)]
+enum Function {
+    Pdf,
+    Cdf,
+    Quantile,
+}
+
+impl Distribution {
+    fn from_args(name: &str, params: &[f64], env: &Uiua) -> UiuaResult<Self> {
+        let count = |expected: &str| {
+            env.error(format!(
+                "The {name} distribution takes {expected}, but {} were given",
+                params.len()
+            ))
+        };
+        let positive = |what: &str, n: f64| {
+            if n > 0.0 && n.is_finite() {
+                Ok(n)
+            } else {
+                Err(env.error(format!("{what} must be positive, but it is {n}")))
+            }
+        };
+        Ok(match name {
+            "normal" => match *params {
+                [] => Distribution::Normal { mean: 0.0, sd: 1.0 },
+                [mean, sd] if mean.is_finite() => Distribution::Normal {
+                    mean,
+                    sd: positive("Standard deviation", sd)?,
+                },
+                [mean, _] => {
+                    return Err(env.error(format!("Mean must be finite, but it is {mean}")))
+                }
+                _ => return Err(count("no parameters or a mean and standard deviation")),
+            },
+            "t" => match *params {
+                [df] => Distribution::T {
+                    df: positive("Degrees of freedom", df)?,
+                },
+                _ => return Err(count("degrees of freedom")),
+            },
+            "chisq" | "chi2" => match *params {
+                [df] => Distribution::ChiSquare {
+                    df: positive("Degrees of freedom", df)?,
+                },
+                _ => return Err(count("degrees of freedom")),
+            },
+            "binomial" => match *params {
+                [n, p] => {
+                    if n < 0.0 || n.fract() != 0.0 || !n.is_finite() {
+                        return Err(env.error(format!(
+                            "Number of trials must be a natural number, but it is {n}"
+                        )));
+                    }
+                    if !(0.0..=1.0).contains(&p) {
+                        return Err(env.error(format!(
+                            "Probability of success must be between 0 and 1, but it is {p}"
+                        )));
+                    }
+                    Distribution::Binomial { n, p }
+                }
+                _ => return Err(count("a number of trials and a probability of success")),
+            },
+            _ => {
+                return Err(env.error(format!(
+                    "Unknown distribution {name:?}. Valid distributions are \
+                    \"normal\", \"t\", \"chisq\", and \"binomial\"."
+                )))
+            }
+        })
+    }
+    fn pdf(self, x: f64) -> f64 {
+        match self {
+            Distribution::Normal { mean, sd } => {
+                let z = (x - mean) / sd;
+                (-0.5 * z * z).exp() / (sd * (2.0 * PI).sqrt())
+            }
+            Distribution::T { df } => (ln_gamma((df + 1.0) / 2.0)
+                - ln_gamma(df / 2.0)
+                - 0.5 * (df * PI).ln()
+                - (df + 1.0) / 2.0 * (x * x / df).ln_1p())
+            .exp(),
+            Distribution::ChiSquare { df } => {
+                if x < 0.0 {
+                    0.0
+                } else if x == 0.0 {
+                    match df.partial_cmp(&2.0) {
+                        Some(std::cmp::Ordering::Less) => f64::INFINITY,
+                        Some(std::cmp::Ordering::Equal) => 0.5,
+                        _ => 0.0,
+                    }
+                } else {
+                    let k = df / 2.0;
+                    ((k - 1.0) * x.ln() - x / 2.0 - k * 2f64.ln() - ln_gamma(k)).exp()
+                }
+            }
+            Distribution::Binomial { n, p } => {
+                if x.is_nan() {
+                    x
+                } else if x < 0.0 || x > n || x.fract() != 0.0 {
+                    0.0
+                } else {
+                    binomial_pmf(n, p, x)
+                }
+            }
+        }
+    }
+    fn cdf(self, x: f64) -> f64 {
+        match self {
+            Distribution::Normal { mean, sd } => 0.5 * erfc(-(x - mean) / (sd * SQRT_2)),
+            Distribution::T { df } => {
+                if x.is_infinite() {
+                    return if x > 0.0 { 1.0 } else { 0.0 };
+                }
+                let tail = 0.5 * beta_inc(df / 2.0, 0.5, df / (df + x * x));
+                if x > 0.0 {
+                    1.0 - tail
+                } else {
+                    tail
+                }
+            }
+            Distribution::ChiSquare { df } => gamma_p(df / 2.0, x / 2.0),
+            Distribution::Binomial { n, p } => {
+                let k = x.floor();
+                if x.is_nan() {
+                    x
+                } else if k < 0.0 {
+                    0.0
+                } else if k >= n {
+                    1.0
+                } else {
+                    beta_inc(n - k, k + 1.0, 1.0 - p)
+                }
+            }
+        }
+    }
+    fn quantile(self, p: f64) -> f64 {
+        match self {
+            Distribution::Normal { mean, sd } => mean + sd * normal_quantile(p),
+            Distribution::T { .. } => {
+                // The lower tail is more precise
+                if p > 0.5 {
+                    -self.invert_cdf(1.0 - p, f64::NEG_INFINITY)
+                } else if p == 0.5 {
+                    0.0
+                } else {
+                    self.invert_cdf(p, f64::NEG_INFINITY)
+                }
+            }
+            Distribution::ChiSquare { .. } => self.invert_cdf(p, 0.0),
+            Distribution::Binomial { n, p: success } => {
+                let mut k = 0.0;
+                let mut sum = 0.0;
+                while k < n {
+                    sum += binomial_pmf(n, success, k);
+                    if sum >= p {
+                        break;
+                    }
+                    k += 1.0;
+                }
+                k
+            }
+        }
+    }
+    /// Find where a continuous distribution's CDF reaches a probability
+    fn invert_cdf(self, p: f64, min: f64) -> f64 {
+        if p == 0.0 {
+            return min;
+        }
+        if p == 1.0 {
+            return f64::INFINITY;
+        }
+        // Bracket the solution
+        let mut lo = if min.is_finite() { min } else { -1.0 };
+        let mut hi = 1.0;
+        while self.cdf(lo) > p {
+            lo *= 2.0;
+        }
+        while self.cdf(hi) < p {
+            hi *= 2.0;
+        }
+        // Bisect it
+        loop {
+            let mid = lo + (hi - lo) / 2.0;
+            if mid == lo || mid == hi {
+                return mid;
+            }
+            let c = self.cdf(mid);
+            if c == p {
+                return mid;
+            } else if c < p {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+    }
+}
+
+fn binomial_pmf(n: f64, p: f64, k: f64) -> f64 {
+    if p == 0.0 {
+        return if k == 0.0 { 1.0 } else { 0.0 };
+    }
+    if p == 1.0 {
+        return if k == n { 1.0 } else { 0.0 };
+    }
+    // Use the exact binomial coefficient when it is representable
+    let terms = k.min(n - k);
+    if terms <= 1000.0 {
+        let mut choose = 1.0;
+        for i in 0..terms as u64 {
+            choose = choose * (n - i as f64) / (i + 1) as f64;
+        }
+        let prob = choose * p.powf(k) * (1.0 - p).powf(n - k);
+        if prob.is_normal() {
+            return prob;
+        }
+    }
+    (ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0)
+        + k * p.ln()
+        + (n - k) * (-p).ln_1p())
+    .exp()
+}
+
+/// The natural logarithm of the absolute value of the gamma function
+fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation with g = 7
+    const COEFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return (PI / (PI * x).sin()).abs().ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum =
+        (COEFS.iter().enumerate().skip(1)).fold(COEFS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The complementary error function
+fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x >= 0.0 {
+        gamma_q(0.5, x * x)
+    } else {
+        1.0 + gamma_p(0.5, x * x)
+    }
+}
+
+/// The regularized lower incomplete gamma function
+fn gamma_p(a: f64, x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_fraction(a, x)
+    }
+}
+
+/// The regularized upper incomplete gamma function
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x.is_nan() {
+        x
+    } else if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_fraction(a, x)
+    }
+}
+
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut ap = a;
+    for _ in 0..MAX_TERMS {
+        ap += 1.0;
+        term *= x / ap;
+        sum += term;
+        if term.abs() < sum.abs() * EPS {
+            break;
+        }
+    }
+    sum * (a * x.ln() - x - ln_gamma(a)).exp()
+}
+
+fn gamma_fraction(a: f64, x: f64) -> f64 {
+    if x.is_infinite() {
+        return 0.0;
+    }
+    // Modified Lentz's method
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_TERMS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    (a * x.ln() - x - ln_gamma(a)).exp() * h
+}
+
+/// The regularized incomplete beta function
+fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (-x).ln_1p()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    // Modified Lentz's method
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..MAX_TERMS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        for num in [
+            m * (b - m) * x / ((a + m2 - 1.0) * (a + m2)),
+            -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0)),
+        ] {
+            d = 1.0 + num * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + num / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// The quantile function of the standard normal distribution
+fn normal_quantile(p: f64) -> f64 {
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+    // Acklam's rational approximation
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    let poly = |coefs: &[f64], x: f64| coefs.iter().fold(0.0, |acc, c| acc * x + c);
+    let tail = |q: f64| poly(&C, q) / (poly(&D, q) * q + 1.0);
+    let x = if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (-p).ln_1p()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        q * poly(&A, r) / (poly(&B, r) * r + 1.0)
+    };
+    // Refine with a step of Halley's method
+    let e = 0.5 * erfc(-x / SQRT_2) - p;
+    let u = e * (2.0 * PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
+
+fn distribution_function(func: Function, env: &mut Uiua) -> UiuaResult {
+    let name = env
+        .pop(1)?
+        .as_string(env, "Distribution name must be a string")?;
+    let params = env
+        .pop(2)?
+        .as_nums(env, "Distribution parameters must be a list of numbers")?;
+    let dist = Distribution::from_args(&name, &params, env)?;
+    let mut xs: Array<f64> = env.pop(3)?.as_number_array(
+        env,
+        "Distribution functions can only be applied to numbers",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    for x in xs.data.as_mut_slice() {
+        *x = match func {
+            Function::Pdf => dist.pdf(*x),
+            Function::Cdf => dist.cdf(*x),
+            Function::Quantile => {
+                if !(0.0..=1.0).contains(x) {
+                    return Err(env.error(format!(
+                        "Quantiles can only be found for probabilities \
+                        between 0 and 1, but one is {x}"
+                    )));
+                }
+                dist.quantile(*x)
+            }
+        };
+    }
+    env.push(xs);
+    Ok(())
+}
+
+pub fn pdf(env: &mut Uiua) -> UiuaResult {
+    distribution_function(Function::Pdf, env)
+}
+
+pub fn cdf(env: &mut Uiua) -> UiuaResult {
+    distribution_function(Function::Cdf, env)
+}
+
+pub fn quantile(env: &mut Uiua) -> UiuaResult {
+    distribution_function(Function::Quantile, env)
+}
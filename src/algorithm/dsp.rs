@@ -0,0 +1,372 @@
+//! A small audio synthesis graph
+//!
+//! Nodes are given as boxed lists whose first item is the name of the node and whose remaining items are its parameters.
+//! Rendering a graph does not run any Uiua code, so it is much faster than computing each sample in a loop.
+
+use std::f64::consts::TAU;
+
+use crate::{Array, Uiua, UiuaResult, Value};
+
+#[derive(Debug, Clone, Copy)]
+enum Wave {
+    Sine,
+    Saw,
+    Square,
+    Triangle,
+}
+
+impl Wave {
+    fn sample(self, phase: f64) -> f64 {
+        match self {
+            Wave::Sine => (phase * TAU).sin(),
+            Wave::Saw => 2.0 * phase - 1.0,
+            Wave::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Wave::Triangle => 4.0 * (phase - 0.5).abs() - 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Const(f64),
+    Osc {
+        wave: Wave,
+        freq: Box<Node>,
+        phase: f64,
+    },
+    Noise(u64),
+    Adsr {
+        attack: f64,
+        decay: f64,
+        sustain: f64,
+        release: f64,
+        length: f64,
+        t: f64,
+    },
+    Seq {
+        step: f64,
+        values: Vec<f64>,
+        t: f64,
+    },
+    Loop {
+        period: f64,
+        init: Box<Node>,
+        node: Box<Node>,
+        t: f64,
+    },
+    Lowpass {
+        input: Box<Node>,
+        cutoff: Box<Node>,
+        y: f64,
+    },
+    Highpass {
+        input: Box<Node>,
+        cutoff: Box<Node>,
+        y: f64,
+    },
+    Delay {
+        input: Box<Node>,
+        buffer: Vec<f64>,
+        pos: usize,
+        feedback: f64,
+        mix: f64,
+    },
+    Sum(Vec<Node>),
+    Product(Vec<Node>),
+}
+
+const NODE_NAMES: &str = "\"sine\", \"saw\", \"square\", \"triangle\", \"noise\", \
+    \"adsr\", \"seq\", \"loop\", \"lowpass\", \"highpass\", \"delay\", \"+\", and \"×\"";
+
+impl Node {
+    fn from_value(val: &Value, sample_rate: f64, env: &Uiua) -> UiuaResult<Self> {
+        let items = match val {
+            Value::Box(arr) if arr.rank() == 0 => {
+                return Self::from_value(&arr.data[0].0, sample_rate, env)
+            }
+            Value::Box(arr) if arr.rank() == 1 && arr.row_count() > 0 => &arr.data,
+            val if val.rank() == 0 => {
+                return Ok(Node::Const(
+                    val.as_num(env, "Synth nodes must be numbers or boxed lists")?,
+                ))
+            }
+            val => {
+                return Err(env.error(format!(
+                    "Synth nodes must be numbers or boxed lists, \
+                    but this one is a {} array of shape {}",
+                    val.type_name(),
+                    val.shape()
+                )))
+            }
+        };
+        let name = (items[0].0).as_string(env, "Synth node name must be a string")?;
+        let args: Vec<&Value> = items[1..].iter().map(|b| &b.0).collect();
+        let arity = |min: usize, max: usize| -> UiuaResult<()> {
+            if (min..=max).contains(&args.len()) {
+                return Ok(());
+            }
+            let expected = if min == max {
+                format!("{min}")
+            } else {
+                format!("{min} to {max}")
+            };
+            Err(env.error(format!(
+                "Synth node {name:?} expects {expected} parameter{}, but it got {}",
+                if max == 1 { "" } else { "s" },
+                args.len()
+            )))
+        };
+        let num = |i: usize| -> UiuaResult<f64> {
+            let n = args[i].as_num(env, "Synth node parameter must be a number")?;
+            if n.is_finite() {
+                Ok(n)
+            } else {
+                Err(env.error(format!(
+                    "Synth node {name:?} parameter must be finite, but it is {n}"
+                )))
+            }
+        };
+        let node = |i: usize| Self::from_value(args[i], sample_rate, env).map(Box::new);
+        let osc = |wave: Wave| -> UiuaResult<Node> {
+            arity(1, 1)?;
+            Ok(Node::Osc {
+                wave,
+                freq: node(0)?,
+                phase: 0.0,
+            })
+        };
+        Ok(match name.as_str() {
+            "sine" => osc(Wave::Sine)?,
+            "saw" => osc(Wave::Saw)?,
+            "square" => osc(Wave::Square)?,
+            "triangle" => osc(Wave::Triangle)?,
+            "noise" => {
+                arity(0, 0)?;
+                Node::Noise(0x2545_f491_4f6c_dd1d)
+            }
+            "adsr" => {
+                arity(5, 5)?;
+                Node::Adsr {
+                    attack: num(0)?,
+                    decay: num(1)?,
+                    sustain: num(2)?,
+                    release: num(3)?,
+                    length: num(4)?,
+                    t: 0.0,
+                }
+            }
+            "seq" => {
+                arity(2, 2)?;
+                let values = args[1].as_nums(env, "Synth sequence must be a list of numbers")?;
+                if values.is_empty() {
+                    return Err(env.error("Synth sequence cannot be empty"));
+                }
+                Node::Seq {
+                    step: positive(num(0)?, &name, env)?,
+                    values,
+                    t: 0.0,
+                }
+            }
+            "loop" => {
+                arity(2, 2)?;
+                let node = node(1)?;
+                Node::Loop {
+                    period: positive(num(0)?, &name, env)?,
+                    init: node.clone(),
+                    node,
+                    t: 0.0,
+                }
+            }
+            "lowpass" | "highpass" => {
+                arity(2, 2)?;
+                let (input, cutoff) = (node(0)?, node(1)?);
+                if name == "lowpass" {
+                    Node::Lowpass {
+                        input,
+                        cutoff,
+                        y: 0.0,
+                    }
+                } else {
+                    Node::Highpass {
+                        input,
+                        cutoff,
+                        y: 0.0,
+                    }
+                }
+            }
+            "delay" => {
+                arity(2, 4)?;
+                let time = positive(num(1)?, &name, env)?;
+                let len = ((time * sample_rate).round() as usize).max(1);
+                Node::Delay {
+                    input: node(0)?,
+                    buffer: vec![0.0; len],
+                    pos: 0,
+                    feedback: if args.len() > 2 { num(2)? } else { 0.5 },
+                    mix: if args.len() > 3 { num(3)? } else { 0.5 },
+                }
+            }
+            "+" | "×" | "*" => {
+                let nodes = (0..args.len())
+                    .map(|i| node(i).map(|n| *n))
+                    .collect::<UiuaResult<_>>()?;
+                if name == "+" {
+                    Node::Sum(nodes)
+                } else {
+                    Node::Product(nodes)
+                }
+            }
+            _ => {
+                return Err(env.error(format!(
+                    "Unknown synth node {name:?}. Valid nodes are {NODE_NAMES}."
+                )))
+            }
+        })
+    }
+    /// Get the next sample
+    fn next(&mut self, dt: f64) -> f64 {
+        match self {
+            Node::Const(n) => *n,
+            Node::Osc { wave, freq, phase } => {
+                let sample = wave.sample(*phase);
+                *phase = (*phase + freq.next(dt) * dt).rem_euclid(1.0);
+                sample
+            }
+            Node::Noise(state) => {
+                // Xorshift
+                *state ^= *state << 13;
+                *state ^= *state >> 7;
+                *state ^= *state << 17;
+                (*state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+            }
+            &mut Node::Adsr {
+                attack,
+                decay,
+                sustain,
+                release,
+                length,
+                ref mut t,
+            } => {
+                let held = |t: f64| {
+                    if t < attack {
+                        t / attack
+                    } else if t < attack + decay {
+                        1.0 - (1.0 - sustain) * (t - attack) / decay
+                    } else {
+                        sustain
+                    }
+                };
+                let level = if *t < length {
+                    held(*t)
+                } else if *t < length + release {
+                    held(length) * (1.0 - (*t - length) / release)
+                } else {
+                    0.0
+                };
+                *t += dt;
+                level
+            }
+            Node::Seq { step, values, t } => {
+                let i = (*t / *step) as usize % values.len();
+                *t += dt;
+                values[i]
+            }
+            Node::Loop {
+                period,
+                init,
+                node,
+                t,
+            } => {
+                let sample = node.next(dt);
+                *t += dt;
+                if *t >= *period {
+                    *t -= *period;
+                    node.clone_from(init);
+                }
+                sample
+            }
+            Node::Lowpass { input, cutoff, y } => {
+                let x = input.next(dt);
+                *y += one_pole_alpha(cutoff.next(dt), dt) * (x - *y);
+                *y
+            }
+            Node::Highpass { input, cutoff, y } => {
+                let x = input.next(dt);
+                *y += one_pole_alpha(cutoff.next(dt), dt) * (x - *y);
+                x - *y
+            }
+            Node::Delay {
+                input,
+                buffer,
+                pos,
+                feedback,
+                mix,
+            } => {
+                let x = input.next(dt);
+                let delayed = buffer[*pos];
+                buffer[*pos] = x + delayed * *feedback;
+                *pos = (*pos + 1) % buffer.len();
+                x + delayed * *mix
+            }
+            Node::Sum(nodes) => nodes.iter_mut().map(|n| n.next(dt)).sum(),
+            Node::Product(nodes) => nodes.iter_mut().map(|n| n.next(dt)).product(),
+        }
+    }
+}
+
+fn positive(n: f64, name: &str, env: &Uiua) -> UiuaResult<f64> {
+    if n > 0.0 {
+        Ok(n)
+    } else {
+        Err(env.error(format!(
+            "Synth node {name:?} expects a positive time, but it is {n}"
+        )))
+    }
+}
+
+fn one_pole_alpha(cutoff: f64, dt: f64) -> f64 {
+    1.0 - (-TAU * cutoff.max(0.0) * dt).exp()
+}
+
+/// A synthesis graph that produces samples at a fixed sample rate
+#[derive(Debug, Clone)]
+pub(crate) struct Synth {
+    node: Node,
+    dt: f64,
+}
+
+impl Synth {
+    pub fn from_value(val: &Value, sample_rate: u32, env: &Uiua) -> UiuaResult<Self> {
+        let sample_rate = f64::from(sample_rate);
+        Ok(Synth {
+            node: Node::from_value(val, sample_rate, env)?,
+            dt: 1.0 / sample_rate,
+        })
+    }
+    /// Get the next sample
+    pub fn sample(&mut self) -> f64 {
+        self.node.next(self.dt)
+    }
+}
+
+pub fn synth(env: &mut Uiua) -> UiuaResult {
+    let node = env.pop(1)?;
+    let seconds = env.pop(2)?.as_num(env, "Duration must be a number")?;
+    if !(seconds >= 0.0 && seconds.is_finite()) {
+        return Err(env.error(format!(
+            "Duration must be a non-negative finite number, but it is {seconds}"
+        )));
+    }
+    let sample_rate = env.rt.backend.audio_sample_rate();
+    let mut synth = Synth::from_value(&node, sample_rate, env)?;
+    let len = (seconds * f64::from(sample_rate)).round() as usize;
+    let samples: Array<f64> = (0..len).map(|_| synth.sample()).collect();
+    env.push(samples);
+    Ok(())
+}
@@ -2,10 +2,10 @@
 
 use std::{cmp::Ordering, mem::take};
 
-use ecow::EcoVec;
+use ecow::{eco_format, EcoVec};
 
 use crate::{
-    algorithm::{max_shape, op2_bytes_retry_fill, validate_size_impl, FillContext},
+    algorithm::{max_shape, op2_bytes_retry_fill, FillContext},
     cowslice::cowslice,
     Array, ArrayValue, FormatShape, Primitive, Uiua, UiuaResult, Value,
 };
@@ -103,7 +103,7 @@ impl Value {
     fn join_impl<C: FillContext>(self, other: Self, ext: bool, ctx: &C) -> Result<Self, C::Error> {
         Ok(match (self, other) {
             (Value::Num(a), Value::Num(b)) => a.join_impl(b, ext, ctx)?.into(),
-            (Value::Byte(a), Value::Byte(b)) => op2_bytes_retry_fill::<_, C>(
+            (Value::Byte(a), Value::Byte(b)) => op2_bytes_retry_fill::<_, C, _>(
                 a,
                 b,
                 ctx,
@@ -118,6 +118,38 @@ impl Value {
             (Value::Num(a), Value::Complex(b)) => a.convert().join_impl(b, ext, ctx)?.into(),
             (Value::Complex(a), Value::Byte(b)) => a.join_impl(b.convert(), ext, ctx)?.into(),
             (Value::Byte(a), Value::Complex(b)) => a.convert().join_impl(b, ext, ctx)?.into(),
+            (Value::Int(a), Value::Int(b)) => op2_bytes_retry_fill::<_, C, _>(
+                a,
+                b,
+                ctx,
+                |a, b| Ok(a.join_impl(b, ext, ctx)?.into()),
+                |a, b| Ok(a.join_impl(b, ext, ctx)?.into()),
+            )?,
+            (Value::Int(a), Value::Byte(b)) => a.join_impl(b.convert(), ext, ctx)?.into(),
+            (Value::Byte(a), Value::Int(b)) => a.convert().join_impl(b, ext, ctx)?.into(),
+            (Value::Int(a), Value::Num(b)) => {
+                (a.convert_with(|n| n as f64).join_impl(b, ext, ctx)?).into()
+            }
+            (Value::Num(a), Value::Int(b)) => {
+                (a.join_impl(b.convert_with(|n| n as f64), ext, ctx)?).into()
+            }
+            (Value::Complex(a), Value::Int(b)) => a.join_impl(b.convert(), ext, ctx)?.into(),
+            (Value::Int(a), Value::Complex(b)) => a.convert().join_impl(b, ext, ctx)?.into(),
+            (Value::Float(a), Value::Float(b)) => a.join_impl(b, ext, ctx)?.into(),
+            (Value::Float(a), Value::Int(b)) => (a.convert_with(|n| n as f64))
+                .join_impl(b.convert_with(|n| n as f64), ext, ctx)?
+                .into(),
+            (Value::Int(a), Value::Float(b)) => (a.convert_with(|n| n as f64))
+                .join_impl(b.convert_with(|n| n as f64), ext, ctx)?
+                .into(),
+            (Value::Float(a), b @ (Value::Num(_) | Value::Byte(_))) => {
+                a.join_impl(b.into_f32_array(), ext, ctx)?.into()
+            }
+            (a @ (Value::Num(_) | Value::Byte(_)), Value::Float(b)) => {
+                a.into_f32_array().join_impl(b, ext, ctx)?.into()
+            }
+            (Value::Complex(a), Value::Float(b)) => a.join_impl(b.convert(), ext, ctx)?.into(),
+            (Value::Float(a), Value::Complex(b)) => a.convert().join_impl(b, ext, ctx)?.into(),
             (a, b) => a.bin_coerce_to_boxes(
                 b,
                 ctx,
@@ -135,7 +167,7 @@ impl Value {
         match (&mut *self, other) {
             (Value::Num(a), Value::Num(b)) => a.append(b, ext, ctx)?,
             (Value::Byte(a), Value::Byte(b)) => {
-                *self = op2_bytes_retry_fill::<_, C>(
+                *self = op2_bytes_retry_fill::<_, C, _>(
                     a.clone(),
                     b,
                     ctx,
@@ -169,6 +201,64 @@ impl Value {
                 a.append(b, ext, ctx)?;
                 *self = a.into();
             }
+            (Value::Int(a), Value::Int(b)) => {
+                *self = op2_bytes_retry_fill::<_, C, _>(
+                    a.clone(),
+                    b,
+                    ctx,
+                    |mut a, b| {
+                        a.append(b, ext, ctx)?;
+                        Ok(a.into())
+                    },
+                    |mut a, b| {
+                        a.append(b, ext, ctx)?;
+                        Ok(a.into())
+                    },
+                )?;
+            }
+            (Value::Int(a), Value::Byte(b)) => a.append(b.convert(), ext, ctx)?,
+            (Value::Byte(a), Value::Int(b)) => {
+                let mut a = a.convert_ref();
+                a.append(b, ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Int(a), Value::Num(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.append(b, ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Num(a), Value::Int(b)) => a.append(b.convert_with(|n| n as f64), ext, ctx)?,
+            (Value::Complex(a), Value::Int(b)) => a.append(b.convert(), ext, ctx)?,
+            (Value::Int(a), Value::Complex(b)) => {
+                let mut a = a.convert_ref();
+                a.append(b, ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Float(a), Value::Float(b)) => a.append(b, ext, ctx)?,
+            (Value::Float(a), Value::Int(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.append(b.convert_with(|n| n as f64), ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Int(a), Value::Float(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.append(b.convert_with(|n| n as f64), ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Float(a), b @ (Value::Num(_) | Value::Byte(_))) => {
+                a.append(b.into_f32_array(), ext, ctx)?
+            }
+            (a @ (Value::Num(_) | Value::Byte(_)), Value::Float(b)) => {
+                let mut a = take(a).into_f32_array();
+                a.append(b, ext, ctx)?;
+                *self = a.into();
+            }
+            (Value::Complex(a), Value::Float(b)) => a.append(b.convert(), ext, ctx)?,
+            (Value::Float(a), Value::Complex(b)) => {
+                let mut a = a.convert_ref();
+                a.append(b, ext, ctx)?;
+                *self = a.into();
+            }
             (a, b) => a.bin_coerce_to_boxes_mut(
                 b,
                 ctx,
@@ -193,6 +283,12 @@ impl Value {
             Value::Byte(a) => a
                 .undo_join(&a_shape, &b_shape, env)
                 .map(|(a, b)| (a.into(), b.into())),
+            Value::Int(a) => a
+                .undo_join(&a_shape, &b_shape, env)
+                .map(|(a, b)| (a.into(), b.into())),
+            Value::Float(a) => a
+                .undo_join(&a_shape, &b_shape, env)
+                .map(|(a, b)| (a.into(), b.into())),
             Value::Complex(a) => a
                 .undo_join(&a_shape, &b_shape, env)
                 .map(|(a, b)| (a.into(), b.into())),
@@ -229,6 +325,14 @@ impl Value {
                 arr.unjoin_depth(depth, env)
                     .map(|(a, b)| (a.into(), b.into()))
             },
+            |arr| {
+                arr.unjoin_depth(depth, env)
+                    .map(|(a, b)| (a.into(), b.into()))
+            },
+            |arr| {
+                arr.unjoin_depth(depth, env)
+                    .map(|(a, b)| (a.into(), b.into()))
+            },
         )
     }
     pub(crate) fn unjoin_shape(self, shape: &[usize], env: &Uiua) -> UiuaResult<(Self, Self)> {
@@ -253,6 +357,14 @@ impl Value {
                 arr.unjoin_shape(shape, env)
                     .map(|(a, b)| (a.into(), b.into()))
             },
+            |arr| {
+                arr.unjoin_shape(shape, env)
+                    .map(|(a, b)| (a.into(), b.into()))
+            },
+            |arr| {
+                arr.unjoin_shape(shape, env)
+                    .map(|(a, b)| (a.into(), b.into()))
+            },
         )
     }
 }
@@ -627,7 +739,7 @@ impl Value {
         match (&mut *self, other) {
             (Value::Num(a), Value::Num(b)) => a.couple_impl(b, ctx)?,
             (Value::Byte(a), Value::Byte(b)) => {
-                *self = op2_bytes_retry_fill::<_, C>(
+                *self = op2_bytes_retry_fill::<_, C, _>(
                     a.clone(),
                     b,
                     ctx,
@@ -662,6 +774,64 @@ impl Value {
                 a.couple_impl(b, ctx)?;
                 *self = a.into();
             }
+            (Value::Int(a), Value::Int(b)) => {
+                *self = op2_bytes_retry_fill::<_, C, _>(
+                    a.clone(),
+                    b,
+                    ctx,
+                    |mut a, b| {
+                        a.couple_impl(b, ctx)?;
+                        Ok(a.into())
+                    },
+                    |mut a, b| {
+                        a.couple_impl(b, ctx)?;
+                        Ok(a.into())
+                    },
+                )?
+            }
+            (Value::Int(a), Value::Byte(b)) => a.couple_impl(b.convert(), ctx)?,
+            (Value::Byte(a), Value::Int(b)) => {
+                let mut a = a.convert_ref();
+                a.couple_impl(b, ctx)?;
+                *self = a.into();
+            }
+            (Value::Int(a), Value::Num(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.couple_impl(b, ctx)?;
+                *self = a.into();
+            }
+            (Value::Num(a), Value::Int(b)) => a.couple_impl(b.convert_with(|n| n as f64), ctx)?,
+            (Value::Complex(a), Value::Int(b)) => a.couple_impl(b.convert(), ctx)?,
+            (Value::Int(a), Value::Complex(b)) => {
+                let mut a = a.convert_ref();
+                a.couple_impl(b, ctx)?;
+                *self = a.into();
+            }
+            (Value::Float(a), Value::Float(b)) => a.couple_impl(b, ctx)?,
+            (Value::Float(a), Value::Int(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.couple_impl(b.convert_with(|n| n as f64), ctx)?;
+                *self = a.into();
+            }
+            (Value::Int(a), Value::Float(b)) => {
+                let mut a = a.convert_ref_with(|n| n as f64);
+                a.couple_impl(b.convert_with(|n| n as f64), ctx)?;
+                *self = a.into();
+            }
+            (Value::Float(a), b @ (Value::Num(_) | Value::Byte(_))) => {
+                a.couple_impl(b.into_f32_array(), ctx)?
+            }
+            (a @ (Value::Num(_) | Value::Byte(_)), Value::Float(b)) => {
+                let mut a = take(a).into_f32_array();
+                a.couple_impl(b, ctx)?;
+                *self = a.into();
+            }
+            (Value::Complex(a), Value::Float(b)) => a.couple_impl(b.convert(), ctx)?,
+            (Value::Float(a), Value::Complex(b)) => {
+                let mut a = a.convert_ref();
+                a.couple_impl(b, ctx)?;
+                *self = a.into();
+            }
             (a, b) => a.bin_coerce_to_boxes_mut(
                 b,
                 ctx,
@@ -683,6 +853,12 @@ impl Value {
             Value::Byte(a) => a
                 .uncouple_depth(depth, env)
                 .map(|(a, b)| (a.into(), b.into())),
+            Value::Int(a) => a
+                .uncouple_depth(depth, env)
+                .map(|(a, b)| (a.into(), b.into())),
+            Value::Float(a) => a
+                .uncouple_depth(depth, env)
+                .map(|(a, b)| (a.into(), b.into())),
             Value::Complex(a) => a
                 .uncouple_depth(depth, env)
                 .map(|(a, b)| (a.into(), b.into())),
@@ -712,6 +888,10 @@ impl<T: ArrayValue> Array<T> {
     }
     fn couple_impl<C: FillContext>(&mut self, mut other: Self, ctx: &C) -> Result<(), C::Error> {
         crate::profile_function!();
+        let label = match (self.take_label(), other.take_label()) {
+            (Some(a), Some(b)) if a != b => Some(eco_format!("{a}_{b}")),
+            (a, b) => a.or(b),
+        };
         self.combine_meta(other.meta());
         if self.shape != other.shape {
             match ctx.scalar_fill::<T>() {
@@ -732,7 +912,9 @@ impl<T: ArrayValue> Array<T> {
         self.data.extend_from_cowslice(other.data);
         self.shape.insert(0, 2);
         self.validate_shape();
-        self.take_label();
+        if let Some(label) = label {
+            self.meta_mut().label = Some(label);
+        }
         Ok(())
     }
     /// Uncouple the array into two arrays
@@ -806,28 +988,13 @@ impl Value {
         V: IntoIterator<Item = Value>,
         C: FillContext,
     {
-        let mut row_values = values.into_iter();
-        let Some(mut value) = row_values.next() else {
-            return Ok(Value::default());
-        };
+        let row_values = values.into_iter();
         let (min, max) = row_values.size_hint();
-        let to_reserve = max.unwrap_or(min);
-        if let Some(row) = row_values.next() {
-            validate_size_impl(
-                row.elem_size(),
-                [to_reserve, value.shape().iter().product::<usize>()],
-            )
-            .map_err(|e| ctx.error(e))?;
-            let total_elements = to_reserve * value.shape().iter().product::<usize>();
-            value.reserve_min(total_elements);
-            value.couple_impl(row, ctx)?;
-            for row in row_values {
-                value.append(row, false, ctx)?;
-            }
-        } else {
-            value.shape_mut().insert(0, 1);
+        let mut builder = Value::builder(max.unwrap_or(min));
+        for row in row_values {
+            builder.add_row(row, ctx)?;
         }
-        Ok(value)
+        Ok(builder.finish())
     }
 }
 
@@ -0,0 +1,126 @@
+//! N-dimensional convolution
+
+use ecow::eco_vec;
+
+use crate::{array::*, value::Value, Primitive, Uiua, UiuaResult};
+
+/// How elements past the edges of an array are found
+#[derive(Clone, Copy)]
+enum Boundary {
+    Fill(f64),
+    Wrap,
+    Clamp,
+}
+
+impl Boundary {
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        if let Value::Char(_) = val {
+            return match val.as_string(env, "")?.as_str() {
+                "wrap" => Ok(Boundary::Wrap),
+                "clamp" => Ok(Boundary::Clamp),
+                name => Err(env.error(format!(
+                    "Unknown {} boundary {name:?}. \
+                    The boundary must be a number, \"wrap\", or \"clamp\".",
+                    Primitive::Convolve
+                ))),
+            };
+        }
+        val.as_num(
+            env,
+            "Convolution boundary must be a number, \"wrap\", or \"clamp\"",
+        )
+        .map(Boundary::Fill)
+    }
+    /// Get the index into an axis of length `len`, or `None` if the fill value should be used
+    fn index(self, i: isize, len: usize) -> Option<usize> {
+        let len = len as isize;
+        match self {
+            _ if (0..len).contains(&i) => Some(i as usize),
+            Boundary::Fill(_) => None,
+            Boundary::Wrap => Some(i.rem_euclid(len) as usize),
+            Boundary::Clamp => Some(i.clamp(0, len - 1) as usize),
+        }
+    }
+}
+
+impl Value {
+    /// Convolve the value with a kernel
+    pub(crate) fn convolve(self, kernel: Self, boundary: Self, env: &Uiua) -> UiuaResult<Self> {
+        let boundary = Boundary::from_value(&boundary, env)?;
+        let [xs, kernel] = [self, kernel].map(|val| {
+            val.as_number_array::<f64>(
+                env,
+                "Only numbers can be convolved",
+                |_| true,
+                |_| true,
+                |n| n,
+            )
+        });
+        Ok(xs?.convolve(&kernel?, boundary, env)?.into())
+    }
+}
+
+impl Array<f64> {
+    /// Convolve the first axes of the array with a kernel of the same rank
+    fn convolve(&self, kernel: &Self, boundary: Boundary, env: &Uiua) -> UiuaResult<Self> {
+        let rank = kernel.rank();
+        if rank > self.rank() {
+            return Err(env.error(format!(
+                "Cannot {} a rank {} array with a rank {rank} kernel",
+                Primitive::Convolve,
+                self.rank()
+            )));
+        }
+        let dims = &self.shape[..rank];
+        let cell_len: usize = self.shape[rank..].iter().product();
+        let mut data = eco_vec![0.0; self.data.len()];
+        if data.is_empty() {
+            return Ok(Array::new(self.shape.clone(), data));
+        }
+        // Each kernel element and its offset from the output element
+        // The kernel is flipped, so the last kernel element is applied to the first array element
+        let mut terms = Vec::with_capacity(kernel.data.len());
+        let mut index = vec![0usize; rank];
+        for &k in &kernel.data {
+            let offset: Vec<isize> = (index.iter().zip(&kernel.shape))
+                .map(|(&i, &len)| ((len - 1) / 2) as isize - i as isize)
+                .collect();
+            terms.push((k, offset));
+            increment(&mut index, &kernel.shape);
+        }
+        let mut pos = vec![0usize; rank];
+        for out_cell in data.make_mut().chunks_exact_mut(cell_len) {
+            'terms: for (k, offset) in &terms {
+                let mut src = 0;
+                for ((&p, &o), &len) in pos.iter().zip(offset).zip(dims) {
+                    match boundary.index(p as isize + o, len) {
+                        Some(i) => src = src * len + i,
+                        None => {
+                            if let Boundary::Fill(fill) = boundary {
+                                out_cell.iter_mut().for_each(|x| *x += k * fill);
+                            }
+                            continue 'terms;
+                        }
+                    }
+                }
+                let src_cell = &self.data[src * cell_len..][..cell_len];
+                for (x, s) in out_cell.iter_mut().zip(src_cell) {
+                    *x += k * s;
+                }
+            }
+            increment(&mut pos, dims);
+        }
+        Ok(Array::new(self.shape.clone(), data))
+    }
+}
+
+/// Increment a multidimensional index in row-major order
+fn increment(index: &mut [usize], shape: &[usize]) {
+    for (i, &len) in index.iter_mut().zip(shape).rev() {
+        *i += 1;
+        if *i < len {
+            return;
+        }
+        *i = 0;
+    }
+}
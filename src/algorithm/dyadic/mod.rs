@@ -1,6 +1,8 @@
 //! Algorithms for dyadic array operations
 
 mod combine;
+mod convolve;
+mod search;
 mod structure;
 
 use std::{
@@ -10,13 +12,17 @@ use std::{
     hash::{Hash, Hasher},
     iter::{once, repeat},
     mem::take,
+    ops::{AddAssign, Mul},
 };
 
 use ecow::{eco_vec, EcoVec};
 use rayon::prelude::*;
 
 use crate::{
-    algorithm::pervade::{self, bin_pervade_recursive, InfalliblePervasiveFn},
+    algorithm::{
+        pervade::{bin_pervade_recursive, InfalliblePervasiveFn},
+        pool,
+    },
     array::*,
     boxed::Boxed,
     cowslice::{cowslice, CowSlice},
@@ -24,7 +30,10 @@ use crate::{
     Shape, Uiua, UiuaResult,
 };
 
-use super::{pervade::ArrayRef, shape_prefixes_match, validate_size, ArrayCmpSlice, FillContext};
+use super::{
+    pervade::ArrayRef, shape_mismatch_note, shape_prefixes_match, validate_size, ArrayCmpSlice,
+    FillContext,
+};
 
 impl Value {
     pub(crate) fn bin_coerce_to_boxes<T, C: FillContext, E: ToString>(
@@ -102,11 +111,12 @@ impl<T: Clone + std::fmt::Debug + Send + Sync> Array<T> {
             if !a_prefix.iter().zip(b_prefix).all(|(a, b)| a == b) {
                 return Err(ctx.error(format!(
                     "Cannot combine arrays with shapes {} and {} \
-                    because shape prefixes {} and {} are not compatible",
+                    because shape prefixes {} and {} are not compatible{}",
                     a.shape(),
                     b.shape(),
                     FormatShape(a_prefix),
-                    FormatShape(b_prefix)
+                    FormatShape(b_prefix),
+                    shape_mismatch_note(a_prefix, b_prefix, false)
                 )));
             }
         }
@@ -171,12 +181,14 @@ impl Value {
         if shape.rank() == 0 {
             let n = target_shape[0];
             match self {
-                Value::Num(a) => a.reshape_scalar(n),
-                Value::Byte(a) => a.reshape_scalar(n),
-                Value::Complex(a) => a.reshape_scalar(n),
-                Value::Char(a) => a.reshape_scalar(n),
-                Value::Box(a) => a.reshape_scalar(n),
-            }
+                Value::Num(a) => a.reshape_scalar(n, env),
+                Value::Byte(a) => a.reshape_scalar(n, env),
+                Value::Int(a) => a.reshape_scalar(n, env),
+                Value::Float(a) => a.reshape_scalar(n, env),
+                Value::Complex(a) => a.reshape_scalar(n, env),
+                Value::Char(a) => a.reshape_scalar(n, env),
+                Value::Box(a) => a.reshape_scalar(n, env),
+            }?
         } else {
             match self {
                 Value::Num(a) => a.reshape(&target_shape, env),
@@ -190,6 +202,17 @@ impl Value {
                         a.reshape(&target_shape, env)
                     }
                 }
+                Value::Int(a) => {
+                    if env.num_scalar_fill().is_ok() && env.int_scalar_fill().is_err() {
+                        let mut arr: Array<f64> = a.convert_ref_with(|n| n as f64);
+                        arr.reshape(&target_shape, env)?;
+                        *self = arr.into();
+                        Ok(())
+                    } else {
+                        a.reshape(&target_shape, env)
+                    }
+                }
+                Value::Float(a) => a.reshape(&target_shape, env),
                 Value::Complex(a) => a.reshape(&target_shape, env),
                 Value::Char(a) => a.reshape(&target_shape, env),
                 Value::Box(a) => a.reshape(&target_shape, env),
@@ -218,10 +241,11 @@ impl Value {
 
 impl<T: Clone> Array<T> {
     /// `reshape` this array by replicating it as the rows of a new array
-    pub fn reshape_scalar(&mut self, count: Result<isize, bool>) {
+    pub fn reshape_scalar(&mut self, count: Result<isize, bool>, env: &Uiua) -> UiuaResult {
         self.take_map_keys();
         match count {
             Ok(count) => {
+                validate_size::<T>([count.unsigned_abs(), self.data.len()], env)?;
                 if count < 0 {
                     self.reverse();
                 }
@@ -233,6 +257,7 @@ impl<T: Clone> Array<T> {
                 }
             }
         }
+        Ok(())
     }
     pub(crate) fn reshape_scalar_integer(&mut self, count: usize) {
         if count == 0 {
@@ -465,6 +490,8 @@ impl Value {
                         Value::Byte(a) => {
                             a.convert::<f64>().keep_scalar_real(counts[0], env)?.into()
                         }
+                        Value::Int(a) => a.keep_scalar_real(counts[0], env)?.into(),
+                        Value::Float(a) => a.keep_scalar_real(counts[0], env)?.into(),
                         Value::Complex(a) => a.keep_scalar_real(counts[0], env)?.into(),
                         Value::Char(a) => a.keep_scalar_real(counts[0], env)?.into(),
                         Value::Box(a) => a.keep_scalar_real(counts[0], env)?.into(),
@@ -473,6 +500,8 @@ impl Value {
                     match kept {
                         Value::Num(a) => a.keep_list(counts, env)?.into(),
                         Value::Byte(a) => a.keep_list(counts, env)?.into(),
+                        Value::Int(a) => a.keep_list(counts, env)?.into(),
+                        Value::Float(a) => a.keep_list(counts, env)?.into(),
                         Value::Complex(a) => a.keep_list(counts, env)?.into(),
                         Value::Char(a) => a.keep_list(counts, env)?.into(),
                         Value::Box(a) => a.keep_list(counts, env)?.into(),
@@ -488,6 +517,8 @@ impl Value {
             |a| a.unkeep(env).map(|(a, b)| (a, b.into())),
             |a| a.unkeep(env).map(|(a, b)| (a, b.into())),
             |a| a.unkeep(env).map(|(a, b)| (a, b.into())),
+            |a| a.unkeep(env).map(|(a, b)| (a, b.into())),
+            |a| a.unkeep(env).map(|(a, b)| (a, b.into())),
         )
     }
     pub(crate) fn undo_keep(self, kept: Self, into: Self, env: &Uiua) -> UiuaResult<Self> {
@@ -508,6 +539,8 @@ impl Value {
                     |a, b| a.undo_keep(counts, b, env).map(Into::into),
                     |a, b| a.undo_keep(counts, b, env).map(Into::into),
                     |a, b| a.undo_keep(counts, b, env).map(Into::into),
+                    |a, b| a.undo_keep(counts, b, env).map(Into::into),
+                    |a, b| a.undo_keep(counts, b, env).map(Into::into),
                     |a, b| env.error(format!("Cannot unkeep {a} array with {b} array")),
                 )
             },
@@ -793,13 +826,19 @@ impl Value {
         }
         let by_ints = || self.as_integer_array(env, "Rotation amount must be an array of integers");
         if env.num_scalar_fill().is_ok() {
-            if let Value::Byte(bytes) = &rotated {
-                rotated = bytes.convert_ref::<f64>().into();
+            match &rotated {
+                Value::Byte(bytes) => rotated = bytes.convert_ref::<f64>().into(),
+                Value::Int(ints) if env.int_scalar_fill().is_err() => {
+                    rotated = ints.convert_ref_with(|n| n as f64).into()
+                }
+                _ => {}
             }
         }
         match &mut rotated {
             Value::Num(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
             Value::Byte(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
+            Value::Int(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
+            Value::Float(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
             Value::Complex(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
             Value::Char(a) => a.rotate_depth(by_ints()?, b_depth, a_depth, env)?,
             Value::Box(a) if a.rank() == a_depth => {
@@ -919,20 +958,133 @@ fn fill_shift<T: Clone>(by: &[isize], shape: &[usize], data: &mut [T], fill: T)
 impl Value {
     /// Use this array to `windows` another
     pub fn windows(&self, from: &Self, env: &Uiua) -> UiuaResult<Self> {
-        let size_spec = self.as_ints(env, "Window size must be an integer or list of integers")?;
+        let (size_spec, stride, dilation) = self.window_spec(env)?;
         Ok(match from {
-            Value::Num(a) => a.windows(&size_spec, env)?.into(),
-            Value::Byte(a) => a.windows(&size_spec, env)?.into(),
-            Value::Complex(a) => a.windows(&size_spec, env)?.into(),
-            Value::Char(a) => a.windows(&size_spec, env)?.into(),
-            Value::Box(a) => a.windows(&size_spec, env)?.into(),
+            Value::Num(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Byte(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Int(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Float(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Complex(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Char(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
+            Value::Box(a) => a
+                .strided_windows(&size_spec, &stride, &dilation, env)?
+                .into(),
         })
     }
+    /// Get the window sizes, strides, and dilations from a `windows` size spec
+    ///
+    /// Strides and dilations are given either as the later rows of a rank-2 spec or as the later items of a boxed list.
+    #[allow(clippy::type_complexity)]
+    fn window_spec(&self, env: &Uiua) -> UiuaResult<(Vec<isize>, Vec<usize>, Vec<usize>)> {
+        const REQUIREMENT: &str = "Window size must be an integer or list of integers";
+        let parts: Vec<Value> = match self {
+            Value::Box(arr) if matches!(*arr.shape, [2 | 3]) => {
+                arr.data.iter().map(|b| b.as_value().clone()).collect()
+            }
+            val if val.rank() == 2 && matches!(val.row_count(), 2 | 3) => {
+                val.clone().into_rows().collect()
+            }
+            val => return Ok((val.as_ints(env, REQUIREMENT)?, Vec::new(), Vec::new())),
+        };
+        let size = parts[0].as_ints(env, REQUIREMENT)?;
+        let factors = |i: usize, name: &str, requirement: &'static str| -> UiuaResult<_> {
+            let Some(part) = parts.get(i) else {
+                return Ok(Vec::new());
+            };
+            let factors = part.as_ints(env, requirement)?;
+            if size.len() != factors.len() {
+                return Err(env.error(format!(
+                    "Window size and {name} must have the same length, \
+                    but their lengths are {} and {}",
+                    size.len(),
+                    factors.len()
+                )));
+            }
+            (factors.into_iter())
+                .map(|f| {
+                    if f > 0 {
+                        Ok(f as usize)
+                    } else {
+                        Err(env.error(format!("Window {name} must be positive, but it is {f}")))
+                    }
+                })
+                .collect()
+        };
+        let stride = factors(
+            1,
+            "stride",
+            "Window stride must be an integer or list of integers",
+        )?;
+        let dilation = factors(
+            2,
+            "dilation",
+            "Window dilation must be an integer or list of integers",
+        )?;
+        Ok((size, stride, dilation))
+    }
+    /// Use this window size to reconstruct an array from its `windows`
+    pub(crate) fn unwindows(&self, windows: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.unwindows_impl(windows, true, env)
+    }
+    /// Use this window size to put modified `windows` back together
+    ///
+    /// Overlapping rows are taken from the first window and the ends of the others
+    pub(crate) fn undo_windows(&self, windows: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.unwindows_impl(windows, false, env)
+    }
+    fn unwindows_impl(&self, windows: Self, check: bool, env: &Uiua) -> UiuaResult<Self> {
+        let (_, stride, _) = self.window_spec(env)?;
+        if !stride.is_empty() {
+            return Err(env.error("Cannot invert strided or dilated windows"));
+        }
+        let size = match *self
+            .as_ints(env, "Window size must be an integer or list of integers")?
+            .as_slice()
+        {
+            [size] if size > 0 => size as usize,
+            [_] => return Err(env.error("Cannot invert windows with a non-positive size")),
+            _ => return Err(env.error("Cannot invert multi-dimensional windows")),
+        };
+        windows.generic_into(
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+            |arr| arr.unwindows(size, check, env).map(Into::into),
+        )
+    }
 }
 
 impl<T: ArrayValue> Array<T> {
     /// Get the `windows` of this array
     pub fn windows(&self, isize_spec: &[isize], env: &Uiua) -> UiuaResult<Self> {
+        self.strided_windows(isize_spec, &[], &[], env)
+    }
+    /// Get the `windows` of this array, with each axis advancing by a stride
+    /// and skipping elements within each window by a dilation
+    ///
+    /// An empty stride or dilation is 1 along every axis.
+    pub fn strided_windows(
+        &self,
+        isize_spec: &[isize],
+        stride: &[usize],
+        dilation: &[usize],
+        env: &Uiua,
+    ) -> UiuaResult<Self> {
         if isize_spec.iter().any(|&s| s == 0) {
             return Err(env.error("Window size cannot be zero"));
         }
@@ -945,8 +1097,17 @@ impl<T: ArrayValue> Array<T> {
 
         // Do filled windows if there is a fill value
         if let Ok(fill) = env.scalar_fill::<T>() {
+            if !stride.is_empty() || !dilation.is_empty() {
+                return Err(env.error("Strided or dilated windows cannot be filled"));
+            }
             return Ok(self.filled_windows(isize_spec, fill));
         }
+        let stride: Vec<usize> = (0..self.shape.len())
+            .map(|i| stride.get(i).copied().unwrap_or(1))
+            .collect();
+        let dilation: Vec<usize> = (0..self.shape.len())
+            .map(|i| dilation.get(i).copied().unwrap_or(1))
+            .collect();
 
         let mut size_spec = Vec::with_capacity(isize_spec.len());
         for (d, s) in self.shape.iter().zip(isize_spec) {
@@ -955,16 +1116,27 @@ impl<T: ArrayValue> Array<T> {
         // Determine the shape of the windows array
         let mut new_shape = Shape::with_capacity(self.shape.len() + size_spec.len());
         new_shape.extend(
-            self.shape
+            (self
+                .shape
                 .iter()
                 .zip(&size_spec)
-                .map(|(a, b)| ((*a as isize + 1) - *b).max(0) as usize),
+                .zip(&stride)
+                .zip(&dilation))
+            .map(|(((&a, &b), &s), &d)| {
+                // The number of elements a window spans
+                let extent = (b.max(1) as usize - 1) * d + 1;
+                if extent > a {
+                    0
+                } else {
+                    (a - extent) / s + 1
+                }
+            }),
         );
         new_shape.extend(size_spec.iter().map(|&s| s.max(0) as usize));
         new_shape.extend_from_slice(&self.shape[size_spec.len()..]);
         // Check if the window size is too large
-        for (size, sh) in size_spec.iter().zip(&self.shape) {
-            if *size <= 0 || *size > *sh as isize {
+        for ((size, sh), d) in size_spec.iter().zip(&self.shape).zip(&dilation) {
+            if *size <= 0 || (*size as usize - 1) * d + 1 > *sh {
                 return Ok(Self::new(new_shape, CowSlice::new()));
             }
         }
@@ -990,8 +1162,11 @@ impl<T: ArrayValue> Array<T> {
                 // Copy the current item
                 let mut src_index = 0;
                 let mut stride = 1;
-                for ((c, i), s) in corner.iter().zip(&curr).zip(&self.shape).rev() {
-                    src_index += (*c + *i) * stride;
+                for (((c, i), s), d) in (corner.iter().zip(&curr).zip(&self.shape))
+                    .zip(&dilation)
+                    .rev()
+                {
+                    src_index += (*c + *i * d) * stride;
                     stride *= s;
                 }
                 dst_slice[k] = self.data[src_index].clone();
@@ -1009,16 +1184,56 @@ impl<T: ArrayValue> Array<T> {
             }
             // Go to the next corner
             for i in (0..corner.len()).rev() {
-                if corner[i] == self.shape[i] - true_size[i] {
+                if corner[i] + stride[i] > self.shape[i] - ((true_size[i] - 1) * dilation[i] + 1) {
                     corner[i] = 0;
                 } else {
-                    corner[i] += 1;
+                    corner[i] += stride[i];
                     continue 'windows;
                 }
             }
             break Ok(Array::new(new_shape, dst));
         }
     }
+    /// Reconstruct an array from its `windows`
+    ///
+    /// If `check` is set, overlapping rows of adjacent windows must match
+    fn unwindows(self, size: usize, check: bool, env: &Uiua) -> UiuaResult<Self> {
+        if self.rank() < 2 || self.shape[1] != size {
+            return Err(env.error(format!(
+                "Cannot invert windows of size {size} from an array with shape {}",
+                self.shape()
+            )));
+        }
+        let window_count = self.shape[0];
+        let row_len: usize = self.shape[2..].iter().product();
+        let window_len = size * row_len;
+        let mut shape = self.shape.clone();
+        shape.remove(0);
+        if window_count == 0 {
+            shape[0] = 0;
+            return Ok(Array::new(shape, CowSlice::new()));
+        }
+        shape[0] = size + window_count - 1;
+        let mut data = EcoVec::with_capacity(shape.iter().product());
+        data.extend_from_slice(&self.data[..window_len]);
+        for (i, (prev, window)) in (self.data.chunks_exact(window_len))
+            .zip(self.data.chunks_exact(window_len).skip(1))
+            .enumerate()
+        {
+            if check
+                && !(prev[row_len..].iter())
+                    .zip(&window[..window_len - row_len])
+                    .all(|(a, b)| a.array_eq(b))
+            {
+                return Err(env.error(format!(
+                    "Cannot invert windows because windows {i} and {} do not overlap consistently",
+                    i + 1
+                )));
+            }
+            data.extend_from_slice(&window[window_len - row_len..]);
+        }
+        Ok(Array::new(shape, data))
+    }
     fn filled_windows(&self, isize_spec: &[isize], fill: T) -> Self {
         let mut true_size = Vec::with_capacity(isize_spec.len().max(self.shape.len()));
         for (d, s) in self.shape.iter().zip(isize_spec) {
@@ -1114,6 +1329,15 @@ impl<T: ArrayValue> Array<T> {
 impl Value {
     /// Try to `find` this value in another
     pub fn find(&self, searched: &Self, env: &Uiua) -> UiuaResult<Self> {
+        // Search strings and bytes with SIMD
+        let found = match (self, searched) {
+            (Value::Char(a), Value::Char(b)) => search::find_substring(a, b),
+            (Value::Byte(a), Value::Byte(b)) => search::find_substring(a, b),
+            _ => None,
+        };
+        if let Some(found) = found {
+            return Ok(found.into());
+        }
         self.generic_bin_ref(
             searched,
             |a, b| a.find(b, env).map(Into::into),
@@ -1121,6 +1345,8 @@ impl Value {
             |a, b| a.find(b, env).map(Into::into),
             |a, b| a.find(b, env).map(Into::into),
             |a, b| a.find(b, env).map(Into::into),
+            |a, b| a.find(b, env).map(Into::into),
+            |a, b| a.find(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot find {} in {} array",
@@ -1132,6 +1358,15 @@ impl Value {
     }
     /// Try to `mask` this value in another
     pub fn mask(&self, searched: &Self, env: &Uiua) -> UiuaResult<Self> {
+        // Search strings and bytes with SIMD
+        let masked = match (self, searched) {
+            (Value::Char(a), Value::Char(b)) => search::mask_substring(a, b),
+            (Value::Byte(a), Value::Byte(b)) => search::mask_substring(a, b),
+            _ => None,
+        };
+        if let Some(masked) = masked {
+            return Ok(masked);
+        }
         self.generic_bin_ref(
             searched,
             |a, b| a.mask(b, env).map(Into::into),
@@ -1139,6 +1374,8 @@ impl Value {
             |a, b| a.mask(b, env).map(Into::into),
             |a, b| a.mask(b, env).map(Into::into),
             |a, b| a.mask(b, env).map(Into::into),
+            |a, b| a.mask(b, env).map(Into::into),
+            |a, b| a.mask(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot mask {} in {} array",
@@ -1339,6 +1576,8 @@ impl Value {
             |a, b| a.member(b, env).map(Into::into),
             |a, b| a.member(b, env).map(Into::into),
             |a, b| a.member(b, env).map(Into::into),
+            |a, b| a.member(b, env).map(Into::into),
+            |a, b| a.member(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot look for members of {} array in {} array",
@@ -1407,6 +1646,8 @@ impl Value {
             |a, b| a.index_of(b, env).map(Into::into),
             |a, b| a.index_of(b, env).map(Into::into),
             |a, b| a.index_of(b, env).map(Into::into),
+            |a, b| a.index_of(b, env).map(Into::into),
+            |a, b| a.index_of(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot look for indices of {} array in {} array",
@@ -1425,6 +1666,8 @@ impl Value {
             |a, b| a.coordinate(b, env).map(Into::into),
             |a, b| a.coordinate(b, env).map(Into::into),
             |a, b| a.coordinate(b, env).map(Into::into),
+            |a, b| a.coordinate(b, env).map(Into::into),
+            |a, b| a.coordinate(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot look for coordinates of {} array in {} array",
@@ -1443,6 +1686,8 @@ impl Value {
             |a, b| a.progressive_index_of(b, env).map(Into::into),
             |a, b| a.progressive_index_of(b, env).map(Into::into),
             |a, b| a.progressive_index_of(b, env).map(Into::into),
+            |a, b| a.progressive_index_of(b, env).map(Into::into),
+            |a, b| a.progressive_index_of(b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot look for indices of {} array in {} array",
@@ -1643,7 +1888,10 @@ impl<T: ArrayValue> Array<T> {
     }
 }
 
-impl Array<f64> {
+impl<T> Array<T>
+where
+    T: ArrayValue + Copy + Default + Mul<Output = T> + AddAssign,
+{
     pub(crate) fn matrix_mul(&self, other: &Self, env: &Uiua) -> UiuaResult<Self> {
         let (a, b) = (self, other);
         let a_row_shape = a.shape().row();
@@ -1662,30 +1910,32 @@ impl Array<f64> {
         };
         let prod_row_shape = prod_shape.row();
         let prod_elems = prod_row_shape.elements();
-        let mut result_data = eco_vec![0.0; self.row_count() * other.row_count() * prod_elems];
+        let mut result_data =
+            eco_vec![T::default(); self.row_count() * other.row_count() * prod_elems];
         let result_slice = result_data.make_mut();
         let mut result_shape = Shape::from([a.row_count(), b.row_count()]);
         result_shape.extend(prod_row_shape.iter().copied());
-        let inner = |a_row: &[f64], res_row: &mut [f64]| {
-            let mut prod_row = vec![0.0; prod_shape.elements()];
-            let mut i = 0;
-            for b_row in b.row_slices() {
-                _ = bin_pervade_recursive(
-                    ArrayRef::new(&a_row_shape, a_row),
-                    ArrayRef::new(&b_row_shape, b_row),
-                    &mut prod_row,
-                    env,
-                    InfalliblePervasiveFn::new(pervade::mul::num_num),
-                );
-                let (sum, rest) = prod_row.split_at_mut(prod_elems);
-                for chunk in rest.chunks_exact(prod_elems) {
-                    for (a, b) in sum.iter_mut().zip(chunk.iter()) {
-                        *a += *b;
+        let inner = |a_row: &[T], res_row: &mut [T]| {
+            pool::with_buffer(prod_shape.elements(), T::default(), |prod_row| {
+                let mut i = 0;
+                for b_row in b.row_slices() {
+                    _ = bin_pervade_recursive(
+                        ArrayRef::new(&a_row_shape, a_row),
+                        ArrayRef::new(&b_row_shape, b_row),
+                        prod_row,
+                        env,
+                        InfalliblePervasiveFn::new(|a: T, b: T| b * a),
+                    );
+                    let (sum, rest) = prod_row.split_at_mut(prod_elems);
+                    for chunk in rest.chunks_exact(prod_elems) {
+                        for (a, b) in sum.iter_mut().zip(chunk.iter()) {
+                            *a += *b;
+                        }
                     }
+                    res_row[i..i + prod_elems].copy_from_slice(sum);
+                    i += prod_elems;
                 }
-                res_row[i..i + prod_elems].copy_from_slice(sum);
-                i += prod_elems;
-            }
+            })
         };
         let iter = (a.row_slices()).zip(result_slice.chunks_exact_mut(b.row_count() * prod_elems));
         if a.row_count() > 100 || b.row_count() > 100 {
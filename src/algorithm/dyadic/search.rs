@@ -0,0 +1,104 @@
+//! Fast substring search for `find` and `mask`
+
+use std::mem::{size_of, size_of_val};
+
+use ecow::EcoVec;
+use memchr::memmem::Finder;
+
+use crate::{array::*, value::Value};
+
+/// Elements whose data can be searched as bytes
+pub(super) trait SearchBytes: ArrayValue + Copy {
+    fn as_bytes(slice: &[Self]) -> &[u8];
+}
+
+impl SearchBytes for u8 {
+    fn as_bytes(slice: &[Self]) -> &[u8] {
+        slice
+    }
+}
+
+impl SearchBytes for char {
+    fn as_bytes(slice: &[Self]) -> &[u8] {
+        // Safety: chars are valid `u32`s, which have no padding bytes
+        unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const u8, size_of_val(slice)) }
+    }
+}
+
+/// Whether a needle can be searched for in a haystack with [`substring_starts`]
+fn is_substring<T: SearchBytes>(needle: &Array<T>, haystack: &Array<T>) -> bool {
+    needle.rank() == 1
+        && haystack.rank() == 1
+        && needle.row_count() > 0
+        && needle.row_count() <= haystack.row_count()
+}
+
+/// `find` a list in another list
+///
+/// Returns `None` if either array is not a list
+pub(super) fn find_substring<T: SearchBytes>(
+    needle: &Array<T>,
+    haystack: &Array<T>,
+) -> Option<Array<u8>> {
+    if !is_substring(needle, haystack) {
+        return None;
+    }
+    let mut data = EcoVec::from_elem(0, haystack.row_count());
+    let slice = data.make_mut();
+    for start in substring_starts(&needle.data, &haystack.data, true) {
+        slice[start] = 1;
+    }
+    let mut arr = Array::new(haystack.shape.clone(), data);
+    arr.meta_mut().flags.set(ArrayFlags::BOOLEAN, true);
+    Some(arr)
+}
+
+/// `mask` a list in another list
+///
+/// Returns `None` if either array is not a list
+pub(super) fn mask_substring<T: SearchBytes>(
+    needle: &Array<T>,
+    haystack: &Array<T>,
+) -> Option<Value> {
+    if !is_substring(needle, haystack) {
+        return None;
+    }
+    let mut data = EcoVec::from_elem(0.0, haystack.row_count());
+    let slice = data.make_mut();
+    let len = needle.row_count();
+    for (i, start) in substring_starts(&needle.data, &haystack.data, false).enumerate() {
+        slice[start..start + len].fill((i + 1) as f64);
+    }
+    let mut val: Value = Array::new(haystack.shape.clone(), data).into();
+    val.compress();
+    Some(val)
+}
+
+/// Get the starting indices of a needle in a haystack
+///
+/// If `overlapping` is not set, each occurrence starts after the end of the previous one.
+fn substring_starts<'a, T: SearchBytes>(
+    needle: &'a [T],
+    haystack: &'a [T],
+    overlapping: bool,
+) -> impl Iterator<Item = usize> + 'a {
+    let size = size_of::<T>();
+    let finder = Finder::new(T::as_bytes(needle));
+    let haystack = T::as_bytes(haystack);
+    let skip = if overlapping {
+        size
+    } else {
+        size_of_val(needle)
+    };
+    let mut pos = 0;
+    std::iter::from_fn(move || loop {
+        let found = pos + finder.find(&haystack[pos..])?;
+        // A match of multi-byte elements must start at an element boundary
+        if found % size != 0 {
+            pos = found + 1;
+            continue;
+        }
+        pos = found + skip;
+        break Some(found / size);
+    })
+}
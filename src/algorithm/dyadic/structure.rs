@@ -69,6 +69,25 @@ impl Value {
                 }
                 (&arr.shape, index_data)
             }
+            Value::Int(arr) => {
+                let mut index_data = Vec::with_capacity(arr.element_count());
+                for &n in &arr.data {
+                    index_data.push(n as isize);
+                }
+                (&arr.shape, index_data)
+            }
+            Value::Float(arr) => {
+                let mut index_data = Vec::with_capacity(arr.element_count());
+                for &n in &arr.data {
+                    if n.fract() != 0.0 {
+                        return Err(env.error(format!(
+                            "Index must be an array of integers, but {n} is not an integer"
+                        )));
+                    }
+                    index_data.push(n as isize);
+                }
+                (&arr.shape, index_data)
+            }
             value => {
                 return Err(env.error(format!(
                     "Index must be an array of integers, not {}",
@@ -91,6 +110,16 @@ impl Value {
                         |a| a.pick(index_shape, index_data, env).map(Into::into),
                         |a| a.pick(index_shape, index_data, env).map(Into::into),
                     )?,
+                    Value::Int(a) => op_bytes_retry_fill(
+                        a,
+                        |a| a.pick(index_shape, index_data, env).map(Into::into),
+                        |a| a.pick(index_shape, index_data, env).map(Into::into),
+                    )?,
+                    Value::Float(a) => op_bytes_retry_fill(
+                        a,
+                        |a| a.pick(index_shape, index_data, env).map(Into::into),
+                        |a| a.pick(index_shape, index_data, env).map(Into::into),
+                    )?,
                     Value::Complex(a) => Value::Complex(a.pick(index_shape, index_data, env)?),
                     Value::Char(a) => Value::Char(a.pick(index_shape, index_data, env)?),
                     Value::Box(a) => Value::Box(a.pick(index_shape, index_data, env)?),
@@ -145,6 +174,8 @@ impl Value {
             |a, b| a.undo_pick(idx_shape, &index_data, b, env).map(Into::into),
             |a, b| a.undo_pick(idx_shape, &index_data, b, env).map(Into::into),
             |a, b| a.undo_pick(idx_shape, &index_data, b, env).map(Into::into),
+            |a, b| a.undo_pick(idx_shape, &index_data, b, env).map(Into::into),
+            |a, b| a.undo_pick(idx_shape, &index_data, b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot unpick {} array from {} array",
@@ -325,6 +356,16 @@ impl Value {
                 |a| a.take(&index, env).map(Into::into),
                 |a| a.take(&index, env).map(Into::into),
             )?,
+            Value::Int(a) => op_bytes_retry_fill(
+                a,
+                |a| a.take(&index, env).map(Into::into),
+                |a| a.take(&index, env).map(Into::into),
+            )?,
+            Value::Float(a) => op_bytes_retry_fill(
+                a,
+                |a| a.take(&index, env).map(Into::into),
+                |a| a.take(&index, env).map(Into::into),
+            )?,
             Value::Complex(a) => Value::Complex(a.take(&index, env)?),
             Value::Char(a) => Value::Char(a.take(&index, env)?),
             Value::Box(a) => Value::Box(a.take(&index, env)?),
@@ -336,11 +377,28 @@ impl Value {
         Ok(match from {
             Value::Num(a) => Value::Num(a.drop(&index, env)?),
             Value::Byte(a) => Value::Byte(a.drop(&index, env)?),
+            Value::Int(a) => Value::Int(a.drop(&index, env)?),
+            Value::Float(a) => Value::Float(a.drop(&index, env)?),
             Value::Complex(a) => Value::Complex(a.drop(&index, env)?),
             Value::Char(a) => Value::Char(a.drop(&index, env)?),
             Value::Box(a) => Value::Box(a.drop(&index, env)?),
         })
     }
+    /// Use this value to `drop` from another, then fill the result back to the original shape
+    ///
+    /// Fill values are added to the opposite end from where rows were dropped
+    pub(crate) fn fill_drop(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        let index = self.as_ints_or_infs(env, "Index must be a list of integers or infinity")?;
+        let shape = from.shape().clone();
+        let dropped = self.drop(from, env)?;
+        let restore: Value = (index.iter().zip(&shape))
+            .map(|(i, &dim)| match i {
+                Ok(n) if *n < 0 => -(dim as f64),
+                _ => dim as f64,
+            })
+            .collect();
+        restore.take(dropped, env)
+    }
     pub(crate) fn undo_take(self, index: Self, into: Self, env: &Uiua) -> UiuaResult<Self> {
         let index = match index.as_ints(env, "") {
             Ok(indices) => indices,
@@ -361,6 +419,8 @@ impl Value {
             |a, b| a.undo_take(&index, b, env).map(Into::into),
             |a, b| a.undo_take(&index, b, env).map(Into::into),
             |a, b| a.undo_take(&index, b, env).map(Into::into),
+            |a, b| a.undo_take(&index, b, env).map(Into::into),
+            |a, b| a.undo_take(&index, b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot untake {} into {}",
@@ -390,6 +450,8 @@ impl Value {
             |a, b| a.undo_drop(&index, b, env).map(Into::into),
             |a, b| a.undo_drop(&index, b, env).map(Into::into),
             |a, b| a.undo_drop(&index, b, env).map(Into::into),
+            |a, b| a.undo_drop(&index, b, env).map(Into::into),
+            |a, b| a.undo_drop(&index, b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot undo drop {} into {}",
@@ -404,6 +466,8 @@ impl Value {
         match self {
             Value::Num(a) => a.drop_n(n),
             Value::Byte(a) => a.drop_n(n),
+            Value::Int(a) => a.drop_n(n),
+            Value::Float(a) => a.drop_n(n),
             Value::Complex(a) => a.drop_n(n),
             Value::Char(a) => a.drop_n(n),
             Value::Box(a) => a.drop_n(n),
@@ -799,6 +863,16 @@ impl Value {
                         |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
                         |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
                     )?,
+                    Value::Int(a) => op_bytes_ref_retry_fill(
+                        a,
+                        |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
+                        |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
+                    )?,
+                    Value::Float(a) => op_bytes_ref_retry_fill(
+                        a,
+                        |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
+                        |a| Ok(a.select(indices_shape, indices_data, env)?.into()),
+                    )?,
                     Value::Complex(a) => a.select(indices_shape, indices_data, env)?.into(),
                     Value::Char(a) => a.select(indices_shape, indices_data, env)?.into(),
                     Value::Box(a) => a.select(indices_shape, indices_data, env)?.into(),
@@ -838,6 +912,8 @@ impl Value {
             |a, b| a.undo_select_impl(idx_shape, &ind, b, env).map(Into::into),
             |a, b| a.undo_select_impl(idx_shape, &ind, b, env).map(Into::into),
             |a, b| a.undo_select_impl(idx_shape, &ind, b, env).map(Into::into),
+            |a, b| a.undo_select_impl(idx_shape, &ind, b, env).map(Into::into),
+            |a, b| a.undo_select_impl(idx_shape, &ind, b, env).map(Into::into),
             |a, b| {
                 env.error(format!(
                     "Cannot untake {} into {}",
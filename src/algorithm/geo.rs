@@ -0,0 +1,144 @@
+//! Algorithms for geographic coordinates
+//!
+//! Coordinates are lists of a latitude and a longitude in degrees, along the last axis of an array.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use ecow::EcoVec;
+
+use crate::{Array, Shape, Uiua, UiuaResult, Value};
+
+/// The mean radius of the Earth in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+/// The radius of the Earth used by Web Mercator in meters
+const MERCATOR_RADIUS: f64 = 6_378_137.0;
+
+/// Get an array of coordinate pairs
+fn coordinates(val: Value, env: &Uiua, what: &str) -> UiuaResult<Array<f64>> {
+    let arr = val.as_number_array(
+        env,
+        "Coordinates must be real numbers",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    if arr.shape.last() != Some(&2) {
+        return Err(env.error(format!(
+            "{what} must be pairs of numbers along the last axis, but the shape is {}",
+            arr.shape
+        )));
+    }
+    Ok(arr)
+}
+
+/// Apply a function to pairs of coordinates from two arrays
+///
+/// A single pair is paired with every pair in the other array.
+fn pairwise(env: &mut Uiua, f: impl Fn([f64; 2], [f64; 2]) -> f64) -> UiuaResult {
+    let a = coordinates(env.pop(1)?, env, "Coordinates")?;
+    let b = coordinates(env.pop(2)?, env, "Coordinates")?;
+    let shape = if a.shape == b.shape || b.rank() == 1 {
+        a.shape.clone()
+    } else if a.rank() == 1 {
+        b.shape.clone()
+    } else {
+        return Err(env.error(format!(
+            "Cannot pair coordinates of shapes {} and {}",
+            a.shape, b.shape
+        )));
+    };
+    let mut outer_shape = shape;
+    outer_shape.pop();
+    let a_step = if a.rank() == 1 { 0 } else { 2 };
+    let b_step = if b.rank() == 1 { 0 } else { 2 };
+    let data: EcoVec<f64> = (0..outer_shape.elements())
+        .map(|i| {
+            let a = &a.data[i * a_step..][..2];
+            let b = &b.data[i * b_step..][..2];
+            f([a[0], a[1]], [b[0], b[1]])
+        })
+        .collect();
+    env.push(Array::new(outer_shape, data));
+    Ok(())
+}
+
+pub fn haversine(env: &mut Uiua) -> UiuaResult {
+    pairwise(env, |a, b| {
+        let [lat1, lon1] = a.map(f64::to_radians);
+        let [lat2, lon2] = b.map(f64::to_radians);
+        let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+    })
+}
+
+pub fn bearing(env: &mut Uiua) -> UiuaResult {
+    pairwise(env, |a, b| {
+        let [lat1, lon1] = a.map(f64::to_radians);
+        let [lat2, lon2] = b.map(f64::to_radians);
+        let dlon = lon2 - lon1;
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    })
+}
+
+/// Apply a function to each coordinate pair in an array
+fn map_pairs(env: &mut Uiua, what: &str, f: impl Fn(f64, f64) -> [f64; 2]) -> UiuaResult {
+    let arr = coordinates(env.pop(1)?, env, what)?;
+    let mut data = EcoVec::with_capacity(arr.data.len());
+    for pair in arr.data.chunks_exact(2) {
+        data.extend(f(pair[0], pair[1]));
+    }
+    env.push(Array::new(arr.shape, data));
+    Ok(())
+}
+
+pub fn mercator(env: &mut Uiua) -> UiuaResult {
+    map_pairs(env, "Coordinates", |lat, lon| {
+        let lat = lat.to_radians().clamp(-FRAC_PI_2, FRAC_PI_2);
+        [
+            MERCATOR_RADIUS * lon.to_radians(),
+            MERCATOR_RADIUS * (FRAC_PI_4 + lat / 2.0).tan().ln(),
+        ]
+    })
+}
+
+pub fn unmercator(env: &mut Uiua) -> UiuaResult {
+    map_pairs(env, "Web Mercator points", |x, y| {
+        [
+            (2.0 * (y / MERCATOR_RADIUS).exp().atan() - FRAC_PI_2).to_degrees(),
+            (x / MERCATOR_RADIUS).to_degrees(),
+        ]
+    })
+}
+
+pub fn in_polygon(env: &mut Uiua) -> UiuaResult {
+    let polygon = coordinates(env.pop(1)?, env, "Polygon")?;
+    if polygon.rank() != 2 {
+        return Err(env.error(format!(
+            "Polygon must be a list of points, but its shape is {}",
+            polygon.shape
+        )));
+    }
+    let points = coordinates(env.pop(2)?, env, "Points")?;
+    let vertices: Vec<&[f64]> = polygon.data.chunks_exact(2).collect();
+    let data: EcoVec<u8> = (points.data.chunks_exact(2))
+        .map(|p| {
+            // Even-odd rule
+            let mut inside = false;
+            for (a, b) in vertices.iter().zip(vertices.iter().cycle().skip(1)) {
+                if (a[0] > p[0]) != (b[0] > p[0])
+                    && p[1] < (b[1] - a[1]) * (p[0] - a[0]) / (b[0] - a[0]) + a[1]
+                {
+                    inside = !inside;
+                }
+            }
+            inside as u8
+        })
+        .collect();
+    let mut shape: Shape = points.shape;
+    shape.pop();
+    env.push(Array::new(shape, data));
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Algorithms for graphs given as adjacency matrices or adjacency lists
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use ecow::EcoVec;
+
+use crate::{Array, Uiua, UiuaResult, Value};
+
+/// The outgoing edges of each node in a graph, sorted by neighbor
+struct Graph {
+    edges: Vec<Vec<(usize, f64)>>,
+}
+
+impl Graph {
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let edges = match val {
+            Value::Box(arr) => {
+                if arr.rank() != 1 {
+                    return Err(env.error(format!(
+                        "Adjacency list must be a list of boxed lists, but it has shape {}",
+                        arr.shape
+                    )));
+                }
+                let n = arr.row_count();
+                let mut edges = Vec::with_capacity(n);
+                for bx in &arr.data {
+                    let mut neighbors =
+                        (bx.0).as_nats(env, "Adjacency list neighbors must be natural numbers")?;
+                    if let Some(&i) = neighbors.iter().find(|&&i| i >= n) {
+                        return Err(env.error(format!(
+                            "Neighbor {i} is out of bounds of a graph with {n} nodes"
+                        )));
+                    }
+                    neighbors.sort_unstable();
+                    neighbors.dedup();
+                    edges.push(neighbors.into_iter().map(|i| (i, 1.0)).collect());
+                }
+                edges
+            }
+            val => {
+                let matrix = val.as_number_array(
+                    env,
+                    "Adjacency matrix must be a square matrix of numbers",
+                    |shape| shape.len() == 2 && shape[0] == shape[1],
+                    |_| true,
+                    |n| n,
+                )?;
+                let n = matrix.row_count();
+                (0..n)
+                    .map(|i| {
+                        (matrix.data[i * n..][..n].iter().enumerate())
+                            .filter(|(_, &w)| w != 0.0 && !w.is_nan())
+                            .map(|(j, &w)| (j, w))
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+        Ok(Graph { edges })
+    }
+    fn len(&self) -> usize {
+        self.edges.len()
+    }
+    fn neighbors(&self, node: usize) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.edges[node].iter().map(|&(i, _)| i)
+    }
+    fn node(&self, val: Value, env: &Uiua) -> UiuaResult<usize> {
+        let node = val.as_nat(env, "Start node must be a natural number")?;
+        if node >= self.len() {
+            return Err(env.error(format!(
+                "Start node {node} is out of bounds of a graph with {} nodes",
+                self.len()
+            )));
+        }
+        Ok(node)
+    }
+}
+
+fn push_nodes(nodes: impl IntoIterator<Item = usize>, env: &mut Uiua) {
+    let nodes: EcoVec<f64> = nodes.into_iter().map(|i| i as f64).collect();
+    env.push(Array::from(nodes));
+}
+
+pub fn components(env: &mut Uiua) -> UiuaResult {
+    let graph = Graph::from_value(&env.pop(1)?, env)?;
+    // Edges are followed in both directions
+    let mut undirected = vec![Vec::new(); graph.len()];
+    for (i, edges) in graph.edges.iter().enumerate() {
+        for &(j, _) in edges {
+            undirected[i].push(j);
+            undirected[j].push(i);
+        }
+    }
+    let mut labels = vec![usize::MAX; graph.len()];
+    let mut label = 0;
+    let mut stack = Vec::new();
+    for start in 0..graph.len() {
+        if labels[start] != usize::MAX {
+            continue;
+        }
+        labels[start] = label;
+        stack.push(start);
+        while let Some(node) = stack.pop() {
+            for &neighbor in &undirected[node] {
+                if labels[neighbor] == usize::MAX {
+                    labels[neighbor] = label;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        label += 1;
+    }
+    push_nodes(labels, env);
+    Ok(())
+}
+
+pub fn bfs(env: &mut Uiua) -> UiuaResult {
+    let start = env.pop(1)?;
+    let graph = Graph::from_value(&env.pop(2)?, env)?;
+    let start = graph.node(start, env)?;
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    visited[start] = true;
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for neighbor in graph.neighbors(node) {
+            if !visited[neighbor] {
+                visited[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    push_nodes(order, env);
+    Ok(())
+}
+
+pub fn dfs(env: &mut Uiua) -> UiuaResult {
+    let start = env.pop(1)?;
+    let graph = Graph::from_value(&env.pop(2)?, env)?;
+    let start = graph.node(start, env)?;
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if visited[node] {
+            continue;
+        }
+        visited[node] = true;
+        order.push(node);
+        // Push in reverse so that lower neighbors are visited first
+        stack.extend(graph.neighbors(node).rev().filter(|&i| !visited[i]));
+    }
+    push_nodes(order, env);
+    Ok(())
+}
+
+pub fn dijkstra(env: &mut Uiua) -> UiuaResult {
+    let start = env.pop(1)?;
+    let graph = Graph::from_value(&env.pop(2)?, env)?;
+    let start = graph.node(start, env)?;
+    if let Some(w) = (graph.edges.iter().flatten()).find_map(|&(_, w)| (w < 0.0).then_some(w)) {
+        return Err(env.error(format!(
+            "Shortest paths cannot be found in a graph with negative edge weights, \
+            but it has a weight of {w}"
+        )));
+    }
+    let mut dists = vec![f64::INFINITY; graph.len()];
+    dists[start] = 0.0;
+    // Distances are non-negative, so their bits order the same as the distances themselves
+    let mut queue = BinaryHeap::from([Reverse((0u64, start))]);
+    while let Some(Reverse((dist, node))) = queue.pop() {
+        let dist = f64::from_bits(dist);
+        if dist > dists[node] {
+            continue;
+        }
+        for &(neighbor, weight) in &graph.edges[node] {
+            let new_dist = dist + weight;
+            if new_dist < dists[neighbor] {
+                dists[neighbor] = new_dist;
+                queue.push(Reverse((new_dist.to_bits(), neighbor)));
+            }
+        }
+    }
+    env.push(Array::from(dists.into_iter().collect::<EcoVec<_>>()));
+    Ok(())
+}
+
+pub fn toposort(env: &mut Uiua) -> UiuaResult {
+    let graph = Graph::from_value(&env.pop(1)?, env)?;
+    let mut in_degrees = vec![0usize; graph.len()];
+    for neighbor in graph.edges.iter().flatten().map(|&(i, _)| i) {
+        in_degrees[neighbor] += 1;
+    }
+    // The lowest available node is always taken next so that the order is deterministic
+    let mut ready: BinaryHeap<Reverse<usize>> = (in_degrees.iter().enumerate())
+        .filter(|(_, &d)| d == 0)
+        .map(|(i, _)| Reverse(i))
+        .collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for neighbor in graph.neighbors(node) {
+            in_degrees[neighbor] -= 1;
+            if in_degrees[neighbor] == 0 {
+                ready.push(Reverse(neighbor));
+            }
+        }
+    }
+    if order.len() < graph.len() {
+        return Err(env.error("Cannot topologically sort a graph with a cycle"));
+    }
+    push_nodes(order, env);
+    Ok(())
+}
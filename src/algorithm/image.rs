@@ -0,0 +1,1153 @@
+//! Image processing algorithms
+//!
+//! Images are rank-2 arrays of grayscale values or rank-3 arrays of pixels with any number of channels.
+
+use std::collections::VecDeque;
+
+use ecow::EcoVec;
+use rayon::prelude::*;
+
+use crate::{Array, Boxed, Shape, Uiua, UiuaResult, Value};
+
+/// Images with at least this many pixels are processed in parallel
+const PAR_PIXELS: usize = 1 << 14;
+
+/// An image as a flat buffer of channel values
+struct Image {
+    data: Vec<f64>,
+    h: usize,
+    w: usize,
+    c: usize,
+    /// Whether the image had a channel axis
+    channels: bool,
+    /// Whether the image was a byte array
+    bytes: bool,
+}
+
+impl Image {
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let Some(data) = nums(val) else {
+            return Err(env.error(format!(
+                "Image must be a numeric array, but it is a {} array",
+                val.type_name()
+            )));
+        };
+        let (h, w, c, channels) = match *val.shape().dims() {
+            [h, w] => (h, w, 1, false),
+            [h, w, c] => (h, w, c, true),
+            _ => {
+                return Err(env.error(format!(
+                    "Image must be a rank 2 or 3 array, but its shape is {}",
+                    val.shape()
+                )))
+            }
+        };
+        Ok(Image {
+            data,
+            h,
+            w,
+            c,
+            channels,
+            bytes: matches!(val, Value::Byte(_)),
+        })
+    }
+    fn shape(&self, h: usize, w: usize) -> Shape {
+        let mut shape = Shape::from([h, w]);
+        if self.channels {
+            shape.push(self.c);
+        }
+        shape
+    }
+    /// Check if a point is within the image
+    fn contains(&self, y: f64, x: f64) -> bool {
+        (-0.5..self.h as f64 - 0.5).contains(&y) && (-0.5..self.w as f64 - 0.5).contains(&x)
+    }
+    /// Sample the image at a point, clamping to the edges
+    ///
+    /// Pixel centers are at integer coordinates.
+    fn sample(&self, y: f64, x: f64, interp: Interp, out: &mut [f64]) {
+        let c = self.c;
+        let clamp_y = |y: isize| y.clamp(0, self.h as isize - 1) as usize;
+        let clamp_x = |x: isize| x.clamp(0, self.w as isize - 1) as usize;
+        let pixel = |y: usize, x: usize| &self.data[(y * self.w + x) * c..][..c];
+        match interp {
+            Interp::Nearest => {
+                let p = pixel(clamp_y(y.round() as isize), clamp_x(x.round() as isize));
+                out.copy_from_slice(p);
+            }
+            Interp::Bilinear => {
+                let (y0, x0) = (y.floor(), x.floor());
+                let (fy, fx) = (y - y0, x - x0);
+                let (y0, x0) = (y0 as isize, x0 as isize);
+                let (ya, yb) = (clamp_y(y0), clamp_y(y0 + 1));
+                let (xa, xb) = (clamp_x(x0), clamp_x(x0 + 1));
+                let (p00, p01) = (pixel(ya, xa), pixel(ya, xb));
+                let (p10, p11) = (pixel(yb, xa), pixel(yb, xb));
+                for i in 0..c {
+                    let top = p00[i] + (p01[i] - p00[i]) * fx;
+                    let bottom = p10[i] + (p11[i] - p10[i]) * fx;
+                    out[i] = top + (bottom - top) * fy;
+                }
+            }
+            Interp::Bicubic => {
+                let (y0, x0) = (y.floor(), x.floor());
+                let wy = cubic_weights(y - y0);
+                let wx = cubic_weights(x - x0);
+                let (y0, x0) = (y0 as isize, x0 as isize);
+                out.fill(0.0);
+                for (j, wy) in wy.into_iter().enumerate() {
+                    let py = clamp_y(y0 + j as isize - 1);
+                    for (k, wx) in wx.into_iter().enumerate() {
+                        let p = pixel(py, clamp_x(x0 + k as isize - 1));
+                        for i in 0..c {
+                            out[i] += p[i] * wy * wx;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// Create a new image by sampling this one at the points given by a mapping from output coordinates
+    ///
+    /// Points outside the image get the background color, if there is one.
+    fn resample(
+        &self,
+        h: usize,
+        w: usize,
+        interp: Interp,
+        background: Option<&[f64]>,
+        map: impl Fn(f64, f64) -> (f64, f64) + Sync,
+    ) -> Array<f64> {
+        let c = self.c;
+        let data = for_each_row(h, w, c, |y, row| {
+            for (x, out) in row.chunks_exact_mut(c).enumerate() {
+                let (sy, sx) = map(y as f64, x as f64);
+                match background {
+                    Some(bg) if !self.contains(sy, sx) => out.copy_from_slice(bg),
+                    _ if self.data.is_empty() => out.fill(0.0),
+                    _ => self.sample(sy, sx, interp, out),
+                }
+            }
+        });
+        Array::new(self.shape(h, w), data)
+    }
+    /// Create a new image of the same size where each channel of each pixel
+    /// is computed from the values of that channel in its neighborhood
+    ///
+    /// Parts of the neighborhood that are outside the image are ignored.
+    /// If the whole neighborhood is outside the image, the pixel itself is used.
+    fn neighborhood(
+        &self,
+        element: &Element,
+        f: impl Fn(&mut Vec<f64>) -> f64 + Sync,
+    ) -> Array<f64> {
+        let (h, w, c) = (self.h, self.w, self.c);
+        let data = for_each_row(h, w, c, |y, row| {
+            let mut values = Vec::with_capacity(element.offsets.len());
+            for (x, out) in row.chunks_exact_mut(c).enumerate() {
+                for (i, out) in out.iter_mut().enumerate() {
+                    values.clear();
+                    for &(dy, dx) in &element.offsets {
+                        let (ny, nx) = (y as isize + dy, x as isize + dx);
+                        if (0..h as isize).contains(&ny) && (0..w as isize).contains(&nx) {
+                            values.push(self.data[(ny as usize * w + nx as usize) * c + i]);
+                        }
+                    }
+                    if values.is_empty() {
+                        values.push(self.data[(y * w + x) * c + i]);
+                    }
+                    *out = f(&mut values);
+                }
+            }
+        });
+        Array::new(self.shape(h, w), data)
+    }
+    /// Turn a result array into a value, keeping the type of byte images if possible
+    fn output(&self, arr: Array<f64>) -> Value {
+        if self.bytes && (arr.data.iter()).all(|&n| n.fract() == 0.0 && (0.0..=255.0).contains(&n))
+        {
+            arr.convert_with(|n| n as u8).into()
+        } else {
+            arr.into()
+        }
+    }
+}
+
+/// Create an image buffer and fill its rows, in parallel if it is large
+fn for_each_row(h: usize, w: usize, c: usize, f: impl Fn(usize, &mut [f64]) + Sync) -> EcoVec<f64> {
+    let mut data = EcoVec::from_elem(0.0, h * w * c);
+    if w * c > 0 {
+        let rows = data.make_mut().chunks_exact_mut(w * c).enumerate();
+        if h * w >= PAR_PIXELS {
+            rows.par_bridge().for_each(|(y, row)| f(y, row));
+        } else {
+            rows.for_each(|(y, row)| f(y, row));
+        }
+    }
+    data
+}
+
+/// Get the numbers of a numeric array of any rank
+fn nums(val: &Value) -> Option<Vec<f64>> {
+    match val {
+        Value::Num(arr) => Some(arr.data.to_vec()),
+        Value::Byte(arr) => Some(arr.data.iter().map(|&b| b as f64).collect()),
+        Value::Int(arr) => Some(arr.data.iter().map(|&i| i as f64).collect()),
+        Value::Float(arr) => Some(arr.data.iter().map(|&f| f as f64).collect()),
+        _ => None,
+    }
+}
+
+/// Weights of the 4 pixels around a point for Keys' cubic convolution
+fn cubic_weights(t: f64) -> [f64; 4] {
+    const A: f64 = -0.5;
+    let near = |t: f64| ((A + 2.0) * t - (A + 3.0)) * t * t + 1.0;
+    let far = |t: f64| ((A * t - 5.0 * A) * t + 8.0 * A) * t - 4.0 * A;
+    [far(1.0 + t), near(t), near(1.0 - t), far(2.0 - t)]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interp {
+    Nearest,
+    Bilinear,
+    Bicubic,
+}
+
+/// Split an argument into an interpolation method and a parameter
+///
+/// The argument is either just the parameter or a box list of a method name and the parameter.
+fn interp_and_param(val: Value, prim: &str, env: &Uiua) -> UiuaResult<(Interp, Value)> {
+    let Value::Box(arr) = &val else {
+        return Ok((Interp::Bilinear, val));
+    };
+    if arr.shape != [2] {
+        return Err(env.error(format!(
+            "Boxed {prim} argument must be a list of an interpolation method and a parameter, \
+            but its shape is {}",
+            arr.shape
+        )));
+    }
+    let name = (arr.data[0].0).as_string(env, "Interpolation method must be a string")?;
+    let interp = match name.as_str() {
+        "nearest" => Interp::Nearest,
+        "bilinear" => Interp::Bilinear,
+        "bicubic" => Interp::Bicubic,
+        _ => {
+            return Err(env.error(format!(
+                "Unknown interpolation method {name:?}. \
+                Valid methods are \"nearest\", \"bilinear\", and \"bicubic\"."
+            )))
+        }
+    };
+    Ok((interp, arr.data[1].0.clone()))
+}
+
+/// Get the color of pixels outside an image from the fill value
+fn background(c: usize, env: &Uiua) -> UiuaResult<Vec<f64>> {
+    if env.value_fill().is_none() {
+        return Ok(vec![0.0; c]);
+    }
+    match env.num_array_fill() {
+        Ok(fill) if fill.rank() == 0 => Ok(vec![fill.data[0]; c]),
+        Ok(fill) if fill.shape == [c] => Ok(fill.data.to_vec()),
+        _ => Err(env.error(format!(
+            "Fill value for an image with {c} channel{} must be a number or a list of {c} numbers",
+            if c == 1 { "" } else { "s" }
+        ))),
+    }
+}
+
+fn dimension(n: f64, env: &Uiua) -> UiuaResult<usize> {
+    if n >= 0.0 && n.is_finite() {
+        Ok(n.round() as usize)
+    } else {
+        Err(env.error(format!(
+            "Image dimensions must be non-negative, but one is {n}"
+        )))
+    }
+}
+
+pub fn resize(env: &mut Uiua) -> UiuaResult {
+    let (interp, size) = interp_and_param(env.pop(1)?, "resize", env)?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let size = size.as_nums(env, "Size must be a number or a list of 2 numbers")?;
+    let (h, w) = match *size.as_slice() {
+        [scale] => (
+            dimension(img.h as f64 * scale, env)?,
+            dimension(img.w as f64 * scale, env)?,
+        ),
+        [h, w] => (dimension(h, env)?, dimension(w, env)?),
+        _ => {
+            return Err(env.error(format!(
+                "Size must be a scale factor or a height and width, but it has {} numbers",
+                size.len()
+            )))
+        }
+    };
+    let (sy, sx) = (img.h as f64 / h as f64, img.w as f64 / w as f64);
+    let resized = img.resample(h, w, interp, None, |y, x| {
+        ((y + 0.5) * sy - 0.5, (x + 0.5) * sx - 0.5)
+    });
+    env.push(resized);
+    Ok(())
+}
+
+pub fn crop(env: &mut Uiua) -> UiuaResult {
+    let rect = env.pop(1)?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let rect = rect.as_ints(env, "Crop rectangle must be a list of 4 integers")?;
+    let &[y, x, h, w] = rect.as_slice() else {
+        return Err(env.error(format!(
+            "Crop rectangle must be a list of a row, column, height, and width, \
+            but it has {} numbers",
+            rect.len()
+        )));
+    };
+    let (h, w) = (dimension(h as f64, env)?, dimension(w as f64, env)?);
+    let in_bounds = y >= 0 && x >= 0 && y as usize + h <= img.h && x as usize + w <= img.w;
+    let bg = if in_bounds || env.value_fill().is_some() {
+        background(img.c, env)?
+    } else {
+        return Err(env.error(format!(
+            "Cannot crop a {h}×{w} rectangle at row {y}, column {x} \
+            from a {}×{} image without a fill value",
+            img.h, img.w
+        )));
+    };
+    let c = img.c;
+    let mut data = EcoVec::with_capacity(h * w * c);
+    for i in 0..h as isize {
+        for j in 0..w as isize {
+            let (sy, sx) = (y + i, x + j);
+            if (0..img.h as isize).contains(&sy) && (0..img.w as isize).contains(&sx) {
+                let start = (sy as usize * img.w + sx as usize) * c;
+                data.extend_from_slice(&img.data[start..][..c]);
+            } else {
+                data.extend_from_slice(&bg);
+            }
+        }
+    }
+    env.push(Array::new(img.shape(h, w), data));
+    Ok(())
+}
+
+pub fn turn(env: &mut Uiua) -> UiuaResult {
+    let (interp, angle) = interp_and_param(env.pop(1)?, "turn", env)?;
+    let angle = angle.as_num(env, "Angle must be a number")?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let bg = background(img.c, env)?;
+    let (sin, cos) = angle.sin_cos();
+    let (cy, cx) = ((img.h as f64 - 1.0) / 2.0, (img.w as f64 - 1.0) / 2.0);
+    let turned = img.resample(img.h, img.w, interp, Some(&bg), |y, x| {
+        let (dy, dx) = (y - cy, x - cx);
+        (cy + dx * sin + dy * cos, cx + dx * cos - dy * sin)
+    });
+    env.push(turned);
+    Ok(())
+}
+
+pub fn warp(env: &mut Uiua) -> UiuaResult {
+    let (interp, matrix) = interp_and_param(env.pop(1)?, "warp", env)?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let m = match nums(&matrix) {
+        Some(m) if matches!(matrix.shape().dims(), [2, 3] | [3, 3]) => m,
+        _ => {
+            return Err(env.error(format!(
+                "Warp matrix must be a 2×3 or 3×3 numeric affine matrix, \
+                but it is a {} array of shape {}",
+                matrix.type_name(),
+                matrix.shape()
+            )))
+        }
+    };
+    if m.len() == 9 && m[6..] != [0.0, 0.0, 1.0] {
+        return Err(env.error("The last row of a 3×3 warp matrix must be [0 0 1]"));
+    }
+    // Invert the matrix to map output pixels back to input pixels
+    let [a, b, ty, c, d, tx] = [m[0], m[1], m[2], m[3], m[4], m[5]];
+    let det = a * d - b * c;
+    if det == 0.0 || !det.is_finite() {
+        return Err(env.error("Warp matrix is not invertible"));
+    }
+    let [ia, ib, ic, id] = [d / det, -b / det, -c / det, a / det];
+    let bg = background(img.c, env)?;
+    let warped = img.resample(img.h, img.w, interp, Some(&bg), |y, x| {
+        let (y, x) = (y - ty, x - tx);
+        (ia * y + ib * x, ic * y + id * x)
+    });
+    env.push(warped);
+    Ok(())
+}
+
+/// A structuring element as the offsets of its cells from its center
+struct Element {
+    offsets: Vec<(isize, isize)>,
+}
+
+impl Element {
+    /// Get a structuring element from a size or a mask
+    ///
+    /// A size makes a square. The center of a mask is the middle cell, rounded up and left.
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let (h, w, mask) = match (val.shape().dims(), nums(val)) {
+            ([], Some(n)) => {
+                let size = dimension(n[0], env)?;
+                (size, size, vec![1.0; size * size])
+            }
+            (&[h, w], Some(mask)) => (h, w, mask),
+            _ => {
+                return Err(env.error(format!(
+                    "Structuring element must be a size or a rank 2 mask, \
+                    but it is a {} array of shape {}",
+                    val.type_name(),
+                    val.shape()
+                )))
+            }
+        };
+        let (cy, cx) = ((h as isize - 1) / 2, (w as isize - 1) / 2);
+        let offsets: Vec<_> = (0..h as isize)
+            .flat_map(|y| (0..w as isize).map(move |x| (y, x)))
+            .filter(|&(y, x)| mask[y as usize * w + x as usize] != 0.0)
+            .map(|(y, x)| (y - cy, x - cx))
+            .collect();
+        if offsets.is_empty() {
+            return Err(env.error("Structuring element cannot be empty"));
+        }
+        Ok(Element { offsets })
+    }
+}
+
+fn morphology(env: &mut Uiua, f: fn(f64, f64) -> f64, init: f64) -> UiuaResult {
+    let element = Element::from_value(&env.pop(1)?, env)?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let res = img.neighborhood(&element, |values| values.iter().copied().fold(init, f));
+    env.push(img.output(res));
+    Ok(())
+}
+
+pub fn erode(env: &mut Uiua) -> UiuaResult {
+    morphology(env, f64::min, f64::INFINITY)
+}
+
+pub fn dilate(env: &mut Uiua) -> UiuaResult {
+    morphology(env, f64::max, f64::NEG_INFINITY)
+}
+
+pub fn median_filter(env: &mut Uiua) -> UiuaResult {
+    let element = Element::from_value(&env.pop(1)?, env)?;
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let res = img.neighborhood(&element, |values| {
+        let mid = values.len() / 2;
+        *(values.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1)
+    });
+    env.push(img.output(res));
+    Ok(())
+}
+
+pub fn blur(env: &mut Uiua) -> UiuaResult {
+    let sigma = env.pop(1)?.as_num(env, "Blur radius must be a number")?;
+    if !(sigma >= 0.0 && sigma.is_finite()) {
+        return Err(env.error(format!(
+            "Blur radius must be a non-negative number, but it is {sigma}"
+        )));
+    }
+    let img = Image::from_value(&env.pop(2)?, env)?;
+    let (h, w, c) = (img.h, img.w, img.c);
+    // Build a normalized Gaussian kernel
+    let radius = (sigma * 3.0).ceil() as isize;
+    let mut kernel: Vec<f64> = if sigma == 0.0 {
+        vec![1.0]
+    } else {
+        (-radius..=radius)
+            .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect()
+    };
+    let sum: f64 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+    // The kernel is separable, so blur the rows and then the columns
+    let convolve = |src: &[f64], y: usize, row: &mut [f64], horizontal: bool| {
+        for x in 0..w {
+            for i in 0..c {
+                let mut acc = 0.0;
+                for (k, weight) in kernel.iter().enumerate() {
+                    let d = k as isize - radius;
+                    let (ny, nx) = if horizontal {
+                        (y, (x as isize + d).clamp(0, w as isize - 1) as usize)
+                    } else {
+                        ((y as isize + d).clamp(0, h as isize - 1) as usize, x)
+                    };
+                    acc += src[(ny * w + nx) * c + i] * weight;
+                }
+                row[x * c + i] = acc;
+            }
+        }
+    };
+    let rows = for_each_row(h, w, c, |y, row| convolve(&img.data, y, row, true));
+    let data = for_each_row(h, w, c, |y, row| convolve(&rows, y, row, false));
+    env.push(Array::new(img.shape(h, w), data));
+    Ok(())
+}
+
+/// An n-dimensional grid for traversing the cells of an array
+struct Grid {
+    dims: Vec<usize>,
+    offsets: Vec<Vec<isize>>,
+}
+
+impl Grid {
+    /// Create a grid where cells are connected to the given number of neighbors
+    ///
+    /// Cells can be connected to the neighbors that share a face, an edge, a corner, etc.
+    fn new(dims: &[usize], connectivity: usize, env: &Uiua) -> UiuaResult<Self> {
+        let rank = dims.len();
+        // All offsets in the 3^rank cube around a cell, except the cell itself
+        let mut cube: Vec<Vec<isize>> = vec![Vec::new()];
+        for _ in 0..rank {
+            cube = (cube.into_iter())
+                .flat_map(|off| {
+                    (-1..=1).map(move |d| {
+                        let mut off = off.clone();
+                        off.push(d);
+                        off
+                    })
+                })
+                .collect();
+        }
+        cube.retain(|off| off.iter().any(|&d| d != 0));
+        let distance = |off: &Vec<isize>| off.iter().filter(|&&d| d != 0).count();
+        let valid: Vec<usize> = (1..=rank)
+            .map(|max| cube.iter().filter(|off| distance(off) <= max).count())
+            .collect();
+        let Some(max) = valid.iter().position(|&n| n == connectivity) else {
+            return Err(env.error(format!(
+                "Connectivity for a rank {rank} array must be {}, but it is {connectivity}",
+                match valid.as_slice() {
+                    [] => "nothing".into(),
+                    [n] => n.to_string(),
+                    [a, b] => format!("{a} or {b}"),
+                    [init @ .., last] => format!(
+                        "{}, or {last}",
+                        init.iter()
+                            .map(|n| n.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            )));
+        };
+        cube.retain(|off| distance(off) <= max + 1);
+        Ok(Grid {
+            dims: dims.to_vec(),
+            offsets: cube,
+        })
+    }
+    fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+    /// Call a function on the index of each neighbor of a cell
+    fn neighbors(&self, index: usize, mut f: impl FnMut(usize)) {
+        let mut coords = vec![0; self.dims.len()];
+        let mut rem = index;
+        for (c, &d) in coords.iter_mut().zip(&self.dims).rev() {
+            *c = rem % d;
+            rem /= d;
+        }
+        'offsets: for off in &self.offsets {
+            let mut neighbor = 0;
+            for ((&c, &d), &o) in coords.iter().zip(&self.dims).zip(off) {
+                let n = c as isize + o;
+                if n < 0 || n >= d as isize {
+                    continue 'offsets;
+                }
+                neighbor = neighbor * d + n as usize;
+            }
+            f(neighbor);
+        }
+    }
+    /// Visit every cell reachable from the start cell through cells that pass a test
+    ///
+    /// The start cell is always visited.
+    fn flood(
+        &self,
+        start: usize,
+        visited: &mut [bool],
+        mut include: impl FnMut(usize) -> bool,
+        mut visit: impl FnMut(usize),
+    ) {
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+        while let Some(i) = queue.pop_front() {
+            visit(i);
+            self.neighbors(i, |n| {
+                if !visited[n] && include(n) {
+                    visited[n] = true;
+                    queue.push_back(n);
+                }
+            });
+        }
+    }
+}
+
+pub fn regions(env: &mut Uiua) -> UiuaResult {
+    let connectivity = env
+        .pop(1)?
+        .as_nat(env, "Connectivity must be a natural number")?;
+    let mask = env.pop(2)?;
+    let Some(data) = nums(&mask) else {
+        return Err(env.error(format!(
+            "Cannot find regions in a {} array",
+            mask.type_name()
+        )));
+    };
+    let grid = Grid::new(mask.shape().dims(), connectivity, env)?;
+    let mut labels = EcoVec::from_elem(0.0, grid.len());
+    let labels_slice = labels.make_mut();
+    let mut visited = vec![false; grid.len()];
+    let mut count = 0;
+    for start in 0..grid.len() {
+        if visited[start] || data[start] == 0.0 {
+            continue;
+        }
+        count += 1;
+        let label = count as f64;
+        let foreground = |i: usize| data[i] != 0.0;
+        grid.flood(start, &mut visited, foreground, |i| labels_slice[i] = label);
+    }
+    env.push(Array::new(mask.shape().clone(), labels));
+    env.push(count as f64);
+    Ok(())
+}
+
+/// Get comparable keys for the cells of a numeric or character array
+fn cell_keys(val: &Value) -> Option<Vec<u64>> {
+    match val {
+        Value::Char(arr) => Some(arr.data.iter().map(|&c| c as u64).collect()),
+        // Normalize negative zero
+        val => nums(val).map(|nums| nums.into_iter().map(|n| (n + 0.0).to_bits()).collect()),
+    }
+}
+
+pub fn flood_fill(env: &mut Uiua) -> UiuaResult {
+    let seeds = env.pop(1)?;
+    let arr = env.pop(2)?;
+    let Some(keys) = cell_keys(&arr) else {
+        return Err(env.error(format!("Cannot flood fill a {} array", arr.type_name())));
+    };
+    let dims = arr.shape().dims();
+    let rank = dims.len();
+    let seed_coords = match (seeds.shape().dims(), nums(&seeds)) {
+        (seed_dims, Some(coords)) if rank == 1 && seed_dims.len() <= 1 => coords,
+        (&[n], Some(coords)) if n == rank => coords,
+        (&[_, n], Some(coords)) if n == rank => coords,
+        _ => {
+            return Err(env.error(format!(
+                "Seeds for a rank {rank} array must be a list of {rank} indices \
+                or a list of such lists, but they have shape {}",
+                seeds.shape()
+            )))
+        }
+    };
+    let grid = Grid::new(dims, 2 * rank, env)?;
+    let mut visited = vec![false; grid.len()];
+    let mut mask = EcoVec::from_elem(0u8, grid.len());
+    let mask_slice = mask.make_mut();
+    for seed in seed_coords.chunks_exact(rank.max(1)) {
+        let mut start = 0;
+        for (&i, &d) in seed.iter().zip(dims) {
+            if i.fract() != 0.0 || i < 0.0 || i >= d as f64 {
+                return Err(env.error(format!(
+                    "Seed {} is out of bounds of an array of shape {}",
+                    Array::<f64>::from_iter(seed.iter().copied()).show(),
+                    arr.shape()
+                )));
+            }
+            start = start * d + i as usize;
+        }
+        if grid.len() == 0 || visited[start] {
+            continue;
+        }
+        let key = keys[start];
+        let same = |i: usize| keys[i] == key;
+        grid.flood(start, &mut visited, same, |i| mask_slice[i] = 1);
+    }
+    env.push(Array::new(arr.shape().clone(), mask));
+    Ok(())
+}
+
+/// Moore neighborhood offsets in clockwise order, starting from the west
+const MOORE: [(isize, isize); 8] = [
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+];
+
+pub fn contours(env: &mut Uiua) -> UiuaResult {
+    let mask = env.pop(1)?;
+    let (data, &[h, w]) = (nums(&mask), mask.shape().dims()) else {
+        return Err(env.error(format!(
+            "Contour mask must be a rank 2 array, but its shape is {}",
+            mask.shape()
+        )));
+    };
+    let Some(data) = data else {
+        return Err(env.error(format!(
+            "Cannot find contours in a {} array",
+            mask.type_name()
+        )));
+    };
+    let on = |y: isize, x: isize| {
+        (0..h as isize).contains(&y)
+            && (0..w as isize).contains(&x)
+            && data[y as usize * w + x as usize] != 0.0
+    };
+    let grid = Grid::new(&[h, w], 8, env)?;
+    let mut visited = vec![false; h * w];
+    let mut contours = Vec::new();
+    for start in 0..h * w {
+        if visited[start] || data[start] == 0.0 {
+            continue;
+        }
+        // Trace the outer boundary of the region with Moore neighbor tracing
+        let start_pos = ((start / w) as isize, (start % w) as isize);
+        // The cell to the west of the first cell of a region is never in the region
+        let (mut pos, mut back) = (start_pos, (start_pos.0, start_pos.1 - 1));
+        // The tracing is done when it would leave the start cell the same way it did the first time
+        let mut first_step = None;
+        let mut coords = EcoVec::new();
+        loop {
+            let back_dir = (MOORE.iter())
+                .position(|&(dy, dx)| (pos.0 + dy, pos.1 + dx) == back)
+                .unwrap();
+            let mut next = None;
+            for i in 1..=8 {
+                let (dy, dx) = MOORE[(back_dir + i) % 8];
+                let cand = (pos.0 + dy, pos.1 + dx);
+                if on(cand.0, cand.1) {
+                    next = Some(cand);
+                    break;
+                }
+                back = cand;
+            }
+            if pos == start_pos {
+                match first_step {
+                    None => first_step = next,
+                    Some(step) if next == Some(step) => break,
+                    Some(_) => {}
+                }
+            }
+            coords.extend([pos.0 as f64, pos.1 as f64]);
+            // An isolated cell has no neighbors
+            let Some(next) = next else {
+                break;
+            };
+            pos = next;
+        }
+        let foreground = |i: usize| data[i] != 0.0;
+        grid.flood(start, &mut visited, foreground, |_| {});
+        let n = coords.len() / 2;
+        contours.push(Boxed(Array::new([n, 2], coords).into()));
+    }
+    env.push(Array::from_iter(contours));
+    Ok(())
+}
+
+/// A shape that can be drawn into an image
+enum Figure {
+    Line {
+        a: (f64, f64),
+        b: (f64, f64),
+        width: f64,
+    },
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+        width: f64,
+    },
+    FilledCircle {
+        center: (f64, f64),
+        radius: f64,
+    },
+    Polygon {
+        points: Vec<(f64, f64)>,
+        width: f64,
+    },
+    FilledPolygon {
+        points: Vec<(f64, f64)>,
+    },
+}
+
+const SHAPE_NAMES: &str = "\"line\", \"circle\", \"fillcircle\", \"polygon\", \
+    \"fillpolygon\", and \"smooth\"";
+
+impl Figure {
+    /// Parse shapes from a value, along with whether each one is anti-aliased
+    fn parse(
+        val: &Value,
+        smooth: bool,
+        shapes: &mut Vec<(Figure, bool)>,
+        env: &Uiua,
+    ) -> UiuaResult {
+        let items = match val {
+            Value::Box(arr) if arr.rank() == 0 => {
+                return Self::parse(&arr.data[0].0, smooth, shapes, env)
+            }
+            Value::Box(arr) if arr.rank() == 1 => &arr.data,
+            val => {
+                return Err(env.error(format!(
+                    "Shapes must be boxed lists, but this one is a {} array of shape {}",
+                    val.type_name(),
+                    val.shape()
+                )))
+            }
+        };
+        // A list of shapes
+        let Some(Value::Char(_)) = items.first().map(|b| &b.0) else {
+            for item in items {
+                Self::parse(&item.0, smooth, shapes, env)?;
+            }
+            return Ok(());
+        };
+        let name = (items[0].0).as_string(env, "Shape name must be a string")?;
+        let args: Vec<&Value> = items[1..].iter().map(|b| &b.0).collect();
+        let arity = |min: usize, max: usize| -> UiuaResult<()> {
+            if (min..=max).contains(&args.len()) {
+                return Ok(());
+            }
+            Err(env.error(format!(
+                "Shape {name:?} expects {} parameters, but it got {}",
+                if min == max {
+                    min.to_string()
+                } else {
+                    format!("{min} to {max}")
+                },
+                args.len()
+            )))
+        };
+        let num = |i: usize, requirement: &'static str| -> UiuaResult<f64> {
+            let n = args[i].as_num(env, requirement)?;
+            if n >= 0.0 && n.is_finite() {
+                Ok(n)
+            } else {
+                Err(env.error(format!(
+                    "Shape {name:?} sizes must be non-negative, but one is {n}"
+                )))
+            }
+        };
+        let width = |i: usize| -> UiuaResult<f64> {
+            Ok(if args.len() > i {
+                num(i, "Line width must be a number")?
+            } else {
+                1.0
+            })
+        };
+        let points = |val: &Value| -> UiuaResult<Vec<(f64, f64)>> {
+            match (val.shape().dims(), nums(val)) {
+                (&[_, 2], Some(coords)) => {
+                    Ok(coords.chunks_exact(2).map(|p| (p[0], p[1])).collect())
+                }
+                _ => Err(env.error(format!(
+                    "Shape {name:?} points must be a list of rows and columns, \
+                    but they have shape {}",
+                    val.shape()
+                ))),
+            }
+        };
+        let point = |val: &Value| -> UiuaResult<(f64, f64)> {
+            match *(val.as_nums(env, "Shape coordinates must be a row and column")?).as_slice() {
+                [y, x] => Ok((y, x)),
+                _ => Err(env.error("Shape coordinates must be a row and column")),
+            }
+        };
+        let shape = match name.as_str() {
+            "smooth" => {
+                for arg in args {
+                    Self::parse(arg, true, shapes, env)?;
+                }
+                return Ok(());
+            }
+            "line" => {
+                arity(2, 3)?;
+                Figure::Line {
+                    a: point(args[0])?,
+                    b: point(args[1])?,
+                    width: width(2)?,
+                }
+            }
+            "circle" => {
+                arity(2, 3)?;
+                Figure::Circle {
+                    center: point(args[0])?,
+                    radius: num(1, "Circle radius must be a number")?,
+                    width: width(2)?,
+                }
+            }
+            "fillcircle" => {
+                arity(2, 2)?;
+                Figure::FilledCircle {
+                    center: point(args[0])?,
+                    radius: num(1, "Circle radius must be a number")?,
+                }
+            }
+            "polygon" => {
+                arity(1, 2)?;
+                Figure::Polygon {
+                    points: points(args[0])?,
+                    width: width(1)?,
+                }
+            }
+            "fillpolygon" => {
+                arity(1, 1)?;
+                Figure::FilledPolygon {
+                    points: points(args[0])?,
+                }
+            }
+            _ => {
+                return Err(env.error(format!(
+                    "Unknown shape {name:?}. Valid shapes are {SHAPE_NAMES}."
+                )))
+            }
+        };
+        shapes.push((shape, smooth));
+        Ok(())
+    }
+    /// Get the top, left, bottom, and right of the shape's bounding box
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        let of_points = |points: &[(f64, f64)], pad: f64| {
+            (points.iter()).fold(
+                (
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |(t, l, b, r), &(y, x)| {
+                    (
+                        t.min(y - pad),
+                        l.min(x - pad),
+                        b.max(y + pad),
+                        r.max(x + pad),
+                    )
+                },
+            )
+        };
+        match self {
+            Figure::Line { a, b, width } => of_points(&[*a, *b], width / 2.0),
+            Figure::Circle {
+                center,
+                radius,
+                width,
+            } => of_points(&[*center], radius + width / 2.0),
+            Figure::FilledCircle { center, radius } => of_points(&[*center], *radius),
+            Figure::Polygon { points, width } => of_points(points, width / 2.0),
+            Figure::FilledPolygon { points } => of_points(points, 0.0),
+        }
+    }
+    /// Get the signed distance from a point to the edge of the shape
+    ///
+    /// The distance is negative inside the shape.
+    fn distance(&self, p: (f64, f64)) -> f64 {
+        let dist = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+        let edges = |points: &[(f64, f64)]| {
+            (points.iter().zip(points.iter().cycle().skip(1)))
+                .map(|(&a, &b)| segment_distance(p, a, b))
+                .fold(f64::INFINITY, f64::min)
+        };
+        match self {
+            Figure::Line { a, b, width } => segment_distance(p, *a, *b) - width / 2.0,
+            Figure::Circle {
+                center,
+                radius,
+                width,
+            } => (dist(p, *center) - radius).abs() - width / 2.0,
+            Figure::FilledCircle { center, radius } => dist(p, *center) - radius,
+            Figure::Polygon { points, width } => edges(points) - width / 2.0,
+            Figure::FilledPolygon { points } => {
+                // Even-odd rule
+                let mut inside = false;
+                for (&a, &b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                    if (a.0 > p.0) != (b.0 > p.0)
+                        && p.1 < (b.1 - a.1) * (p.0 - a.0) / (b.0 - a.0) + a.1
+                    {
+                        inside = !inside;
+                    }
+                }
+                let d = edges(points);
+                if inside {
+                    -d
+                } else {
+                    d
+                }
+            }
+        }
+    }
+}
+
+/// Get the distance from a point to a line segment
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dy, dx) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dy * dy + dx * dx;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dy + (p.1 - a.1) * dx) / len2).clamp(0.0, 1.0)
+    };
+    (p.0 - a.0 - t * dy).hypot(p.1 - a.1 - t * dx)
+}
+
+pub fn draw(env: &mut Uiua) -> UiuaResult {
+    let shapes_val = env.pop(1)?;
+    let mut img = Image::from_value(&env.pop(2)?, env)?;
+    let mut shapes = Vec::new();
+    Figure::parse(&shapes_val, false, &mut shapes, env)?;
+    let color = if env.value_fill().is_some() {
+        background(img.c, env)?
+    } else {
+        vec![1.0; img.c]
+    };
+    let (h, w, c) = (img.h, img.w, img.c);
+    for (shape, smooth) in shapes {
+        let (top, left, bottom, right) = shape.bounds();
+        let clip = |n: f64, len: usize| n.clamp(0.0, len as f64) as usize;
+        let (y_start, y_end) = (clip(top.floor() - 1.0, h), clip(bottom.ceil() + 2.0, h));
+        let (x_start, x_end) = (clip(left.floor() - 1.0, w), clip(right.ceil() + 2.0, w));
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                let d = shape.distance((y as f64, x as f64));
+                let coverage = if smooth {
+                    (0.5 - d).clamp(0.0, 1.0)
+                } else if d <= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+                if coverage == 0.0 {
+                    continue;
+                }
+                let pixel = &mut img.data[(y * w + x) * c..][..c];
+                for (p, &col) in pixel.iter_mut().zip(&color) {
+                    *p += (col - *p) * coverage;
+                }
+            }
+        }
+    }
+    let arr = Array::new(img.shape(h, w), EcoVec::from(std::mem::take(&mut img.data)));
+    env.push(img.output(arr));
+    Ok(())
+}
+
+/// The font used by [`text`] when none is given
+#[cfg(feature = "font")]
+static DEFAULT_FONT: &[u8] = include_bytes!("../primitive/assets/Uiua386.ttf");
+
+#[cfg(not(feature = "font"))]
+pub fn text(env: &mut Uiua) -> UiuaResult {
+    Err(env.error("Text rendering is not available in this environment"))
+}
+
+#[cfg(feature = "font")]
+pub fn text(env: &mut Uiua) -> UiuaResult {
+    use ab_glyph::{FontRef, FontVec};
+
+    let font_arg = env.pop(1)?;
+    let string = env.pop(2)?.as_string(env, "Text must be a string")?;
+    // The font can be given as a box list of font file bytes and a size
+    let (font_bytes, size) = match &font_arg {
+        Value::Box(arr) if arr.shape == [2] => (
+            Some((arr.data[0].0).as_bytes(env, "Font must be a list of bytes")?),
+            arr.data[1].0.clone(),
+        ),
+        _ => (None, font_arg),
+    };
+    let size = size.as_num(env, "Text size must be a number")?;
+    if !(size > 0.0 && size.is_finite()) {
+        return Err(env.error(format!("Text size must be positive, but it is {size}")));
+    }
+    let (h, w, coverage) = if let Some(bytes) = font_bytes {
+        let font =
+            FontVec::try_from_vec(bytes).map_err(|e| env.error(format!("Invalid font: {e}")))?;
+        render_text(font, size as f32, &string)
+    } else {
+        render_text(
+            FontRef::try_from_slice(DEFAULT_FONT).unwrap(),
+            size as f32,
+            &string,
+        )
+    };
+    if env.value_fill().is_none() {
+        env.push(Array::new(
+            [h, w],
+            coverage.into_iter().collect::<EcoVec<_>>(),
+        ));
+        return Ok(());
+    }
+    // With a color, make an image with an alpha channel
+    let color = match env.num_array_fill() {
+        Ok(color) if color.rank() <= 1 => color.data.to_vec(),
+        _ => return Err(env.error("Text color must be a number or a list of numbers")),
+    };
+    let (mut color, alpha) = match color.len() {
+        4 => (color[..3].to_vec(), color[3]),
+        _ => (color, 1.0),
+    };
+    color.push(alpha);
+    let c = color.len();
+    let mut data = EcoVec::with_capacity(h * w * c);
+    for cov in coverage {
+        data.extend_from_slice(&color[..c - 1]);
+        data.push(alpha * cov);
+    }
+    env.push(Array::new([h, w, c], data));
+    Ok(())
+}
+
+/// Render text as a coverage mask, returning its height, width, and data
+#[cfg(feature = "font")]
+fn render_text(font: impl ab_glyph::Font, size: f32, text: &str) -> (usize, usize, Vec<f64>) {
+    use ab_glyph::{point, ScaleFont};
+
+    let font = font.as_scaled(size);
+    let line_height = font.ascent() - font.descent() + font.line_gap();
+    let lines: Vec<&str> = text.lines().collect();
+    // Lay out the glyphs
+    let mut glyphs = Vec::new();
+    let mut width = 0f32;
+    for (i, line) in lines.iter().enumerate() {
+        let y = font.ascent() + i as f32 * line_height;
+        let mut x = 0.0;
+        let mut prev = None;
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(prev) = prev {
+                x += font.kern(prev, id);
+            }
+            glyphs.push(id.with_scale_and_position(font.scale(), point(x, y)));
+            x += font.h_advance(id);
+            prev = Some(id);
+        }
+        width = width.max(x);
+    }
+    let height = lines.len() as f32 * line_height - font.line_gap();
+    let (h, w) = (height.ceil().max(0.0) as usize, width.ceil() as usize);
+    // Draw the glyphs
+    let mut data = vec![0.0; h * w];
+    for glyph in glyphs {
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, cov| {
+            let x = bounds.min.x as i64 + gx as i64;
+            let y = bounds.min.y as i64 + gy as i64;
+            if (0..w as i64).contains(&x) && (0..h as i64).contains(&y) {
+                let p = &mut data[y as usize * w + x as usize];
+                *p = (*p + cov as f64).min(1.0);
+            }
+        });
+    }
+    (h, w, data)
+}
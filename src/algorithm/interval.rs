@@ -0,0 +1,454 @@
+//! Interval arithmetic
+//!
+//! An interval array is a number array whose last axis holds lower and upper bounds.
+//! It is marked with [`ArrayFlags::INTERVAL`] so that pervasive operations know to treat each pair as a single number.
+//! Bounds are rounded outward, so the true result of an operation is always contained in the resulting interval.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use ecow::EcoVec;
+
+use crate::{
+    algorithm::pervade::{bin_pervade, InfalliblePervasiveFn},
+    Array, ArrayFlags, Complex, Uiua, UiuaResult, Value,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl From<Complex> for Interval {
+    fn from(c: Complex) -> Self {
+        Interval { lo: c.re, hi: c.im }
+    }
+}
+
+impl From<Interval> for Complex {
+    fn from(i: Interval) -> Self {
+        Complex::new(i.lo, i.hi)
+    }
+}
+
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
+
+/// Round a result toward an infinity if its error is in that direction
+fn direct(x: f64, err: f64, up: bool) -> f64 {
+    if up && err > 0.0 {
+        next_up(x)
+    } else if !up && err < 0.0 {
+        next_down(x)
+    } else {
+        x
+    }
+}
+
+fn add(a: f64, b: f64, up: bool) -> f64 {
+    let s = a + b;
+    if !s.is_finite() {
+        return s;
+    }
+    let bb = s - a;
+    direct(s, (a - (s - bb)) + (b - bb), up)
+}
+
+fn mul(a: f64, b: f64, up: bool) -> f64 {
+    let p = a * b;
+    if p.is_nan() && !a.is_nan() && !b.is_nan() {
+        // 0 × ∞
+        return 0.0;
+    }
+    if !p.is_finite() {
+        return p;
+    }
+    direct(p, a.mul_add(b, -p), up)
+}
+
+fn div(a: f64, b: f64, up: bool) -> f64 {
+    let q = a / b;
+    if !q.is_finite() || b == 0.0 {
+        return q;
+    }
+    let r = (-q).mul_add(b, a);
+    direct(q, if b < 0.0 { -r } else { r }, up)
+}
+
+fn sqrt(x: f64, up: bool) -> f64 {
+    let s = x.sqrt();
+    if !s.is_finite() {
+        return s;
+    }
+    direct(s, (-s).mul_add(s, x), up)
+}
+
+/// Raise a non-negative number to a non-negative integer power
+fn powi(x: f64, n: u64, up: bool) -> f64 {
+    let (mut base, mut n, mut acc) = (x, n, 1.0);
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = mul(acc, base, up);
+        }
+        base = mul(base, base, up);
+        n >>= 1;
+    }
+    acc
+}
+
+impl Interval {
+    fn point(n: f64) -> Self {
+        Interval { lo: n, hi: n }
+    }
+    /// Widen bounds computed without directed rounding by one unit in the last place
+    fn outward(lo: f64, hi: f64) -> Self {
+        Interval {
+            lo: next_down(lo),
+            hi: next_up(hi),
+        }
+    }
+    fn everything() -> Self {
+        Interval {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY,
+        }
+    }
+    fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+    fn contains(&self, n: f64) -> bool {
+        self.lo <= n && n <= self.hi
+    }
+    fn corners(self, other: Self, f: impl Fn(f64, f64, bool) -> f64) -> Self {
+        let pairs = [
+            (self.lo, other.lo),
+            (self.lo, other.hi),
+            (self.hi, other.lo),
+            (self.hi, other.hi),
+        ];
+        let lo = pairs
+            .iter()
+            .map(|&(a, b)| f(a, b, false))
+            .fold(f64::NAN, f64::min);
+        let hi = pairs
+            .iter()
+            .map(|&(a, b)| f(a, b, true))
+            .fold(f64::NAN, f64::max);
+        Interval { lo, hi }
+    }
+    fn neg(self) -> Self {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+    fn add(self, other: Self) -> Self {
+        Interval {
+            lo: add(self.lo, other.lo, false),
+            hi: add(self.hi, other.hi, true),
+        }
+    }
+    fn mul(self, other: Self) -> Self {
+        self.corners(other, mul)
+    }
+    fn div(self, other: Self) -> Self {
+        if other.contains(0.0) {
+            Interval::everything()
+        } else {
+            self.corners(other, div)
+        }
+    }
+    fn abs(self) -> Self {
+        if self.lo >= 0.0 {
+            self
+        } else if self.hi <= 0.0 {
+            self.neg()
+        } else {
+            Interval {
+                lo: 0.0,
+                hi: self.hi.max(-self.lo),
+            }
+        }
+    }
+    fn sqrt(self) -> Self {
+        Interval {
+            lo: sqrt(self.lo.max(0.0), false),
+            hi: sqrt(self.hi, true),
+        }
+    }
+    fn ln(self) -> Self {
+        Interval::outward(self.lo.ln(), self.hi.ln())
+    }
+    fn powi(self, n: u64) -> Self {
+        let even = n % 2 == 0;
+        if self.lo >= 0.0 {
+            Interval {
+                lo: powi(self.lo, n, false),
+                hi: powi(self.hi, n, true),
+            }
+        } else if self.hi <= 0.0 {
+            let mag = self.neg().powi(n);
+            if even {
+                mag
+            } else {
+                mag.neg()
+            }
+        } else if even {
+            Interval {
+                lo: 0.0,
+                hi: powi(-self.lo, n, true).max(powi(self.hi, n, true)),
+            }
+        } else {
+            Interval {
+                lo: -powi(-self.lo, n, true),
+                hi: powi(self.hi, n, true),
+            }
+        }
+    }
+    fn pow(self, exp: Self) -> Self {
+        if exp.is_point() && exp.lo.fract() == 0.0 && exp.lo.abs() < 2f64.powi(53) {
+            let p = self.powi(exp.lo.abs() as u64);
+            if exp.lo < 0.0 {
+                Interval::point(1.0).div(p)
+            } else {
+                p
+            }
+        } else if self.lo >= 0.0 {
+            let bounds = self.corners(exp, |a, b, _| a.powf(b));
+            Interval::outward(bounds.lo, bounds.hi)
+        } else {
+            Interval::point(f64::NAN)
+        }
+    }
+    fn modulus(self, m: Self) -> Self {
+        let whole = Interval {
+            lo: 0.0,
+            hi: m.lo.abs().max(m.hi.abs()),
+        };
+        if !m.is_point() || m.lo == 0.0 {
+            return whole;
+        }
+        let m = m.lo;
+        if (self.lo / m).floor() != (self.hi / m).floor() {
+            return whole;
+        }
+        let (a, b) = (self.lo.rem_euclid(m), self.hi.rem_euclid(m));
+        Interval::outward(a.min(b), a.max(b)).intersect(whole)
+    }
+    fn intersect(self, other: Self) -> Self {
+        Interval {
+            lo: self.lo.max(other.lo),
+            hi: self.hi.min(other.hi),
+        }
+    }
+    fn map_monotonic(self, f: impl Fn(f64) -> f64) -> Self {
+        Interval {
+            lo: f(self.lo),
+            hi: f(self.hi),
+        }
+    }
+    /// Get the range of a function with period τ that peaks at `max_at` and bottoms at `max_at + π`
+    fn periodic(self, f: impl Fn(f64) -> f64, max_at: f64) -> Self {
+        let unit = Interval { lo: -1.0, hi: 1.0 };
+        if !(self.lo.is_finite() && self.hi.is_finite()) || self.hi - self.lo >= TAU {
+            return unit;
+        }
+        let (a, b) = (f(self.lo), f(self.hi));
+        let mut res = Interval::outward(a.min(b), a.max(b));
+        // Extrema within rounding error of the ends are counted as hits so that the result is never too narrow
+        let hits = |at: f64| at + TAU * ((self.lo - at) / TAU - 1e-9).ceil() <= self.hi + 1e-9;
+        if hits(max_at) {
+            res.hi = 1.0;
+        }
+        if hits(max_at + PI) {
+            res.lo = -1.0;
+        }
+        res.intersect(unit)
+    }
+}
+
+fn sign(n: f64) -> f64 {
+    if n == 0.0 || n.is_nan() {
+        n
+    } else {
+        n.signum()
+    }
+}
+
+/// Get an array of intervals from a value
+///
+/// Values that are not intervals become intervals with equal bounds.
+fn intervals(val: &Value, env: &Uiua) -> UiuaResult<Array<Complex>> {
+    let requirement = "Intervals can only be combined with real numbers";
+    if !val.meta().flags.is_interval() {
+        let arr = val.as_number_array(env, requirement, |_| true, |_| true, |n| n)?;
+        let data: EcoVec<Complex> = arr.data.iter().map(|&n| Complex::new(n, n)).collect();
+        return Ok(Array::new(arr.shape, data));
+    }
+    let arr: Array<f64> = val.as_number_array(
+        env,
+        "Interval arrays must have a last axis of length 2",
+        |shape| shape.last() == Some(&2),
+        |_| true,
+        |n| n,
+    )?;
+    let data: EcoVec<Complex> = (arr.data.chunks_exact(2))
+        .map(|pair| Complex::new(pair[0], pair[1]))
+        .collect();
+    let mut shape = arr.shape;
+    shape.pop();
+    Ok(Array::new(shape, data))
+}
+
+fn to_value(arr: Array<Complex>) -> Value {
+    let mut shape = arr.shape;
+    shape.push(2);
+    let data: EcoVec<f64> = arr.data.iter().flat_map(|c| [c.re, c.im]).collect();
+    let mut val = Value::from(Array::new(shape, data));
+    val.meta_mut().flags = ArrayFlags::INTERVAL;
+    val
+}
+
+fn map(val: Value, env: &Uiua, f: impl Fn(Interval) -> Interval) -> UiuaResult<Value> {
+    let mut arr = intervals(&val, env)?;
+    for c in arr.data.as_mut_slice() {
+        *c = f((*c).into()).into();
+    }
+    Ok(to_value(arr))
+}
+
+/// Apply a monadic pervasive operation to an interval array
+pub(crate) fn monadic(name: &str, val: Value, env: &Uiua) -> UiuaResult<Value> {
+    let f: fn(Interval) -> Interval = match name {
+        "scalar_neg" => Interval::neg,
+        "not" => |i: Interval| Interval::point(1.0).add(i.neg()),
+        "scalar_abs" => Interval::abs,
+        "sign" => |i: Interval| i.map_monotonic(sign),
+        "sqrt" => Interval::sqrt,
+        "sin" => |i: Interval| i.periodic(f64::sin, FRAC_PI_2),
+        "cos" => |i: Interval| i.periodic(f64::cos, 0.0),
+        "asin" => |i: Interval| {
+            let res = Interval::outward(i.lo.asin(), i.hi.asin());
+            res.intersect(Interval {
+                lo: -FRAC_PI_2,
+                hi: FRAC_PI_2,
+            })
+        },
+        "floor" => |i: Interval| i.map_monotonic(f64::floor),
+        "ceil" => |i: Interval| i.map_monotonic(f64::ceil),
+        "round" => |i: Interval| i.map_monotonic(f64::round),
+        "complex_re" => |i| i,
+        "complex_im" => |_| Interval::point(0.0),
+        _ => {
+            return Err(env.error(format!(
+                "Cannot take the {} of an interval",
+                name.trim_start_matches("scalar_")
+            )))
+        }
+    };
+    map(val, env, f)
+}
+
+/// Apply a dyadic pervasive operation to arrays where at least one is an interval array
+///
+/// As with other pervasive operations, the result is `b op a`.
+pub(crate) fn dyadic(
+    name: &str,
+    a: Value,
+    b: Value,
+    a_depth: usize,
+    b_depth: usize,
+    env: &Uiua,
+) -> UiuaResult<Value> {
+    let (a, b) = (intervals(&a, env)?, intervals(&b, env)?);
+    let compare = |f: fn(Interval, Interval) -> bool| -> UiuaResult<Value> {
+        let f =
+            InfalliblePervasiveFn::new(move |a: Complex, b: Complex| f(a.into(), b.into()) as u8);
+        Ok(bin_pervade(a.clone(), b.clone(), a_depth, b_depth, env, f)?.into())
+    };
+    let f: fn(Interval, Interval) -> Interval = match name {
+        "add" => |a, b| b.add(a),
+        "sub" => |a, b| b.add(a.neg()),
+        "mul" => |a, b| b.mul(a),
+        "div" => |a, b| b.div(a),
+        "pow" => |a, b| b.pow(a),
+        "log" => |a, b| b.ln().div(a.ln()),
+        "modulus" => |a, b| b.modulus(a),
+        "min" => |a, b| Interval {
+            lo: a.lo.min(b.lo),
+            hi: a.hi.min(b.hi),
+        },
+        "max" => |a, b| Interval {
+            lo: a.lo.max(b.lo),
+            hi: a.hi.max(b.hi),
+        },
+        // Comparisons are only true if they are true for every pair of numbers in the intervals
+        "is_eq" => return compare(|a, b| a.is_point() && b.is_point() && a.lo == b.lo),
+        "is_ne" => return compare(|a, b| a.hi < b.lo || b.hi < a.lo),
+        "is_lt" => return compare(|a, b| b.hi < a.lo),
+        "is_le" => return compare(|a, b| b.hi <= a.lo),
+        "is_gt" => return compare(|a, b| b.lo > a.hi),
+        "is_ge" => return compare(|a, b| b.lo >= a.hi),
+        _ => return Err(env.error(format!("Cannot {name} intervals"))),
+    };
+    let f = InfalliblePervasiveFn::new(move |a: Complex, b: Complex| -> Complex {
+        f(a.into(), b.into()).into()
+    });
+    Ok(to_value(bin_pervade(a, b, a_depth, b_depth, env, f)?))
+}
+
+pub fn interval(env: &mut Uiua) -> UiuaResult {
+    let val = env.pop(1)?;
+    let arr: Array<f64> = val.as_number_array(
+        env,
+        "Interval bounds must be real numbers",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    let arr = if arr.rank() == 0 {
+        let n = arr.data[0];
+        Array::new(2, EcoVec::from([n, n]))
+    } else if arr.shape.last() == Some(&2) {
+        if let Some(pair) = (arr.data.chunks_exact(2)).find(|pair| pair[0] > pair[1]) {
+            return Err(env.error(format!(
+                "Interval lower bound {} is greater than its upper bound {}",
+                pair[0], pair[1]
+            )));
+        }
+        arr
+    } else {
+        return Err(env.error(format!(
+            "Interval bounds must be a scalar or have a last axis of length 2, \
+            but their shape is {}",
+            arr.shape
+        )));
+    };
+    let mut val = Value::from(arr);
+    val.meta_mut().flags = ArrayFlags::INTERVAL;
+    env.push(val);
+    Ok(())
+}
+
+pub fn uninterval(env: &mut Uiua) -> UiuaResult {
+    let mut val = env.pop(1)?;
+    if !val.meta().flags.is_interval() {
+        return Err(env.error("Value is not an interval"));
+    }
+    val.meta_mut().flags.remove(ArrayFlags::INTERVAL);
+    env.push(val);
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! Algorithms for invert and under
 
 use std::{
+    borrow::Cow,
     cell::RefCell,
     cmp::Ordering,
     collections::{HashMap, HashSet},
@@ -109,8 +110,18 @@ fn prim_inverse(prim: Primitive, span: usize) -> Option<Instr> {
         Sys(SysOp::ClipboardGet) => Instr::Prim(Sys(SysOp::ClipboardSet), span),
         Json => Instr::ImplPrim(UnJson, span),
         Csv => Instr::ImplPrim(UnCsv, span),
+        Ast => Instr::ImplPrim(UnAst, span),
         Xlsx => Instr::ImplPrim(UnXlsx, span),
+        Cbor => Instr::ImplPrim(UnCbor, span),
+        Xml => Instr::ImplPrim(UnXml, span),
+        Toml => Instr::ImplPrim(UnToml, span),
+        Yaml => Instr::ImplPrim(UnYaml, span),
+        Url => Instr::ImplPrim(UnUrl, span),
+        Percent => Instr::ImplPrim(UnPercent, span),
+        Ip => Instr::ImplPrim(UnIp, span),
         Fft => Instr::ImplPrim(UnFft, span),
+        Mercator => Instr::ImplPrim(UnMercator, span),
+        Interval => Instr::ImplPrim(UnInterval, span),
         _ => return None,
     })
 }
@@ -137,8 +148,18 @@ fn impl_prim_inverse(prim: ImplPrimitive, span: usize) -> Option<Instr> {
         UnBox => Instr::Prim(Box, span),
         UnJson => Instr::Prim(Json, span),
         UnCsv => Instr::Prim(Csv, span),
+        UnAst => Instr::Prim(Ast, span),
         UnXlsx => Instr::Prim(Xlsx, span),
+        UnCbor => Instr::Prim(Cbor, span),
+        UnXml => Instr::Prim(Xml, span),
+        UnToml => Instr::Prim(Toml, span),
+        UnYaml => Instr::Prim(Yaml, span),
+        UnUrl => Instr::Prim(Url, span),
+        UnPercent => Instr::Prim(Percent, span),
+        UnIp => Instr::Prim(Ip, span),
         UnFft => Instr::Prim(Fft, span),
+        UnMercator => Instr::Prim(Mercator, span),
+        UnInterval => Instr::Prim(Interval, span),
         TraceN(n, inverse) => Instr::ImplPrim(TraceN(n, !inverse), span),
         _ => return None,
     })
@@ -217,6 +238,8 @@ static LEFT_INVERT_PATTERNS: &[&dyn InvertPattern] = {
         &([Flip, Div], [Flip, Div]),
         &([Rotate], [Neg, Rotate]),
         &([Neg, Rotate], [Rotate]),
+        &([Windows], [UnWindows]),
+        &([Unit], [UnUnit]),
         &pat!(Pow, (1, Flip, Div, Pow)),
         &([Flip, Pow], [Log]),
         &([Log], [Flip, Pow]),
@@ -241,6 +264,7 @@ pub(crate) fn invert_instrs(instrs: &[Instr], comp: &mut Compiler) -> Option<Eco
     if instrs.is_empty() {
         return Some(EcoVec::new());
     }
+    let instrs = &*expand_views(instrs);
     if DEBUG {
         println!("inverting {:?}", FmtInstrs(instrs, &comp.asm));
     }
@@ -288,6 +312,22 @@ pub(crate) fn invert_instrs(instrs: &[Instr], comp: &mut Compiler) -> Option<Eco
     None
 }
 
+/// Expand strided views back into the instructions they were fused from
+fn expand_views(instrs: &[Instr]) -> Cow<'_, [Instr]> {
+    if !(instrs.iter()).any(|instr| matches!(instr, Instr::ImplPrim(ImplPrimitive::View(_), _))) {
+        return Cow::Borrowed(instrs);
+    }
+    let mut expanded = Vec::with_capacity(instrs.len());
+    for instr in instrs {
+        if let &Instr::ImplPrim(ImplPrimitive::View(chain), span) = instr {
+            expanded.extend(chain.ops().iter().map(|op| op.instr(span)));
+        } else {
+            expanded.push(instr.clone());
+        }
+    }
+    Cow::Owned(expanded)
+}
+
 type Under = (EcoVec<Instr>, EcoVec<Instr>);
 
 /// Calculate the "before" and "after" instructions for `under`ing a sequence of instructions.
@@ -304,6 +344,7 @@ pub(crate) fn under_instrs(
     if instrs.is_empty() {
         return Some((EcoVec::new(), EcoVec::new()));
     }
+    let instrs = &*expand_views(instrs);
     if instrs.len() > 30 {
         return None;
     }
@@ -458,6 +499,8 @@ pub(crate) fn under_instrs(
             (PopUnder(1), Flip, PopUnder(1), UndoKeep),
         )),
         &stash1!(Rotate, (Neg, Rotate)),
+        &maybe_val!(stash1!(Windows, UndoWindows)),
+        &stash1!(Unit, UnUnit),
         &maybe_val!(pat!(
             Join,
             (Over, Shape, Over, Shape, PushToUnder(2), Join),
@@ -535,15 +578,22 @@ pub(crate) fn under_instrs(
         ),
         // System stuff
         &pat!(Now, (Now, PushToUnder(1)), (PopUnder(1), Now, Flip, Sub)),
+        &pat!(
+            Sys(SysOp::MonotonicClock),
+            (Sys(SysOp::MonotonicClock), PushToUnder(1)),
+            (PopUnder(1), Sys(SysOp::MonotonicClock), Flip, Sub)
+        ),
         &maybe_val!(store1copy!(Sys(SysOp::FOpen), Sys(SysOp::Close))),
         &maybe_val!(store1copy!(Sys(SysOp::FCreate), Sys(SysOp::Close))),
+        &maybe_val!(store1copy!(Sys(SysOp::FLockShared), Sys(SysOp::FUnlock))),
+        &maybe_val!(store1copy!(Sys(SysOp::FLockExclusive), Sys(SysOp::FUnlock))),
         &maybe_val!(store1copy!(Sys(SysOp::TcpConnect), Sys(SysOp::Close))),
         &maybe_val!(store1copy!(Sys(SysOp::TlsConnect), Sys(SysOp::Close))),
         &maybe_val!(store1copy!(Sys(SysOp::TcpAccept), Sys(SysOp::Close))),
         &maybe_val!(store1copy!(Sys(SysOp::TcpListen), Sys(SysOp::Close))),
         &maybe_val!(store1copy!(Sys(SysOp::TlsListen), Sys(SysOp::Close))),
-        &maybe_val!(stash1!(Sys(SysOp::FReadAllStr), Sys(SysOp::FWriteAll))),
-        &maybe_val!(stash1!(Sys(SysOp::FReadAllBytes), Sys(SysOp::FWriteAll))),
+        &maybe_val!(stash1!(Sys(SysOp::FReadAllStr), UndoFReadAll)),
+        &maybe_val!(stash1!(Sys(SysOp::FReadAllBytes), UndoFReadAll)),
         &maybe_val!(pat!(
             Sys(SysOp::RunStream),
             (Sys(SysOp::RunStream), CopyToUnder(3)),
@@ -1256,7 +1306,7 @@ fn under_setund_pattern<'a>(
 
 fn under_setinv_setund_pattern<'a>(
     input: &'a [Instr],
-    _: Signature,
+    g_sig: Signature,
     comp: &mut Compiler,
 ) -> Option<(&'a [Instr], Under)> {
     let [Instr::PushFunc(_), Instr::PushFunc(normal), Instr::Prim(Primitive::SetInverse, _), input @ ..] =
@@ -1264,18 +1314,11 @@ fn under_setinv_setund_pattern<'a>(
     else {
         return None;
     };
-    let [Instr::PushFunc(after), Instr::PushFunc(before), Instr::PushFunc(_), Instr::Prim(Primitive::SetUnder, _)] =
-        normal.instrs(&comp.asm)
-    else {
+    let setund = EcoVec::from(normal.instrs(&comp.asm));
+    let ([], under) = under_setund_pattern(&setund, g_sig, comp)? else {
         return None;
     };
-    Some((
-        input,
-        (
-            before.instrs(&comp.asm).into(),
-            after.instrs(&comp.asm).into(),
-        ),
-    ))
+    Some((input, under))
 }
 
 type TempWrap<'a> = (&'a [Instr], &'a Instr, &'a [Instr], &'a Instr, usize);
@@ -2034,8 +2077,8 @@ fn invert_scan_pattern<'a>(
     let inverse = match f.as_flipped_primitive(&comp.asm) {
         Some((Primitive::Add, false)) if !un => eco_vec![Instr::Prim(Primitive::Sub, *span)],
         Some((Primitive::Mul, false)) if !un => eco_vec![Instr::Prim(Primitive::Div, *span)],
-        Some((Primitive::Sub, false)) if un => eco_vec![Instr::Prim(Primitive::Add, *span)],
-        Some((Primitive::Div, false)) if un => eco_vec![Instr::Prim(Primitive::Mul, *span)],
+        Some((Primitive::Sub, false)) => eco_vec![Instr::Prim(Primitive::Add, *span)],
+        Some((Primitive::Div, false)) => eco_vec![Instr::Prim(Primitive::Mul, *span)],
         Some((Primitive::Eq, false)) => eco_vec![Instr::Prim(Primitive::Eq, *span)],
         Some((Primitive::Ne, false)) => eco_vec![Instr::Prim(Primitive::Ne, *span)],
         _ => {
@@ -0,0 +1,182 @@
+//! IP addresses and CIDR networks
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ecow::EcoVec;
+
+use crate::{Array, ArrayFlags, Boxed, Uiua, UiuaResult, Value};
+
+/// Above this many subnets, [`subnets`] is an error
+const MAX_SUBNET_BITS: u32 = 24;
+
+/// An address as an integer, along with its width in bits
+#[derive(Clone, Copy)]
+struct Addr {
+    bits: u32,
+    n: u128,
+}
+
+impl Addr {
+    fn parse(s: &str, env: &Uiua) -> UiuaResult<Self> {
+        match s.trim().parse::<IpAddr>() {
+            Ok(IpAddr::V4(v4)) => Ok(Addr {
+                bits: 32,
+                n: u32::from(v4).into(),
+            }),
+            Ok(IpAddr::V6(v6)) => Ok(Addr {
+                bits: 128,
+                n: v6.into(),
+            }),
+            Err(_) => Err(env.error(format!("Invalid IP address {s:?}"))),
+        }
+    }
+    fn from_bytes(bytes: &[f64], env: &Uiua) -> UiuaResult<Self> {
+        if ![4, 16].contains(&bytes.len()) {
+            return Err(env.error(format!(
+                "IP addresses must have 4 or 16 bytes, but this one has {}",
+                bytes.len()
+            )));
+        }
+        let mut n = 0u128;
+        for &b in bytes {
+            if !(0.0..=255.0).contains(&b) || b.fract() != 0.0 {
+                return Err(env.error(format!("{b} is not a valid IP address byte")));
+            }
+            n = n << 8 | b as u128;
+        }
+        Ok(Addr {
+            bits: bytes.len() as u32 * 8,
+            n,
+        })
+    }
+    /// Get every address in a value
+    ///
+    /// The value can be a string, a list of bytes, or an array of these.
+    fn all_from_value(val: &Value, env: &Uiua) -> UiuaResult<Vec<Self>> {
+        match val {
+            Value::Box(arr) => (arr.data.iter())
+                .map(|Boxed(val)| Self::from_value(val, env))
+                .collect(),
+            _ if val.rank() == 2 => (val.rows())
+                .map(|row| Self::from_value(&row, env))
+                .collect(),
+            _ => Ok(vec![Self::from_value(val, env)?]),
+        }
+    }
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        match val {
+            Value::Char(_) => Self::parse(&val.as_string(env, "IP address must be a string")?, env),
+            Value::Box(arr) if arr.rank() == 0 => Self::from_value(&arr.data[0].0, env),
+            _ => Self::from_bytes(
+                &val.as_nums(env, "IP address must be a string or a list of bytes")?,
+                env,
+            ),
+        }
+    }
+    fn bytes(self) -> EcoVec<u8> {
+        let bytes = self.n.to_be_bytes();
+        bytes[16 - self.bits as usize / 8..].into()
+    }
+    fn mask(self, prefix: u32) -> u128 {
+        if prefix == 0 {
+            0
+        } else {
+            (u128::MAX << (self.bits - prefix)) & (u128::MAX >> (128 - self.bits))
+        }
+    }
+}
+
+impl std::fmt::Display for Addr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.bits == 32 {
+            Ipv4Addr::from(self.n as u32).fmt(f)
+        } else {
+            Ipv6Addr::from(self.n).fmt(f)
+        }
+    }
+}
+
+/// Parse a CIDR network, clearing the host bits of its address
+fn network(val: &Value, env: &Uiua) -> UiuaResult<(Addr, u32)> {
+    let s = val.as_string(env, "CIDR network must be a string")?;
+    let (addr, prefix) = match s.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (s.as_str(), None),
+    };
+    let mut addr = Addr::parse(addr, env)?;
+    let prefix = match prefix {
+        Some(prefix) => match prefix.trim().parse::<u32>() {
+            Ok(prefix) if prefix <= addr.bits => prefix,
+            _ => return Err(env.error(format!("Invalid CIDR prefix length {prefix:?}"))),
+        },
+        None => addr.bits,
+    };
+    addr.n &= addr.mask(prefix);
+    Ok((addr, prefix))
+}
+
+impl Value {
+    pub(crate) fn to_ip_string(&self, env: &Uiua) -> UiuaResult<String> {
+        let nums = self.as_nums(env, "IP address must be a list of bytes")?;
+        Ok(Addr::from_bytes(&nums, env)?.to_string())
+    }
+    pub(crate) fn parse_ip(&self, env: &Uiua) -> UiuaResult<EcoVec<u8>> {
+        let s = self.as_string(env, "IP address must be a string")?;
+        Ok(Addr::parse(&s, env)?.bytes())
+    }
+}
+
+pub fn cidr(env: &mut Uiua) -> UiuaResult {
+    let net = env.pop(1)?;
+    let addrs = env.pop(2)?;
+    let (net, prefix) = network(&net, env)?;
+    let contained: Vec<bool> = (Addr::all_from_value(&addrs, env)?.into_iter())
+        .map(|addr| addr.bits == net.bits && addr.n & net.mask(prefix) == net.n)
+        .collect();
+    let is_list = addrs.rank() == 2 || matches!(&addrs, Value::Box(arr) if arr.rank() > 0);
+    if is_list {
+        let mut arr = Array::<u8>::from(contained);
+        arr.meta_mut().flags.set(ArrayFlags::BOOLEAN, true);
+        env.push(arr);
+    } else {
+        env.push(contained[0]);
+    }
+    Ok(())
+}
+
+pub fn subnets(env: &mut Uiua) -> UiuaResult {
+    let new_prefix = env
+        .pop(1)?
+        .as_nat(env, "Subnet prefix length must be a natural number")?;
+    let net = env.pop(2)?;
+    let (net, prefix) = network(&net, env)?;
+    if new_prefix < prefix as usize || new_prefix > net.bits as usize {
+        return Err(env.error(format!(
+            "Subnet prefix length must be between {prefix} and {}, but it is {new_prefix}",
+            net.bits
+        )));
+    }
+    let new_prefix = new_prefix as u32;
+    if new_prefix - prefix > MAX_SUBNET_BITS {
+        return Err(env.error(format!(
+            "Splitting a /{prefix} network into /{new_prefix} subnets would make too many subnets"
+        )));
+    }
+    let count = 1u128 << (new_prefix - prefix);
+    let step = if new_prefix == 0 {
+        0
+    } else {
+        1u128 << (net.bits - new_prefix)
+    };
+    let subnets: EcoVec<Boxed> = (0..count)
+        .map(|i| {
+            let addr = Addr {
+                n: net.n + i * step,
+                ..net
+            };
+            Boxed(format!("{addr}/{new_prefix}").into())
+        })
+        .collect();
+    env.push(Array::from(subnets));
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Algorithms for functions that use [`Primitive::LazyFill`]
+//!
+//! Rather than filling every missing element with the same value,
+//! these functions pad their arrays with the results of calling the fill function
+//! with the index of each missing cell.
+
+use crate::{Function, Primitive, Shape, Uiua, UiuaResult, Value};
+
+/// `take` with a fill function
+pub(crate) fn take(fill: Function, env: &mut Uiua) -> UiuaResult {
+    let index = env.pop(1)?;
+    let from = env.pop(2)?;
+    let mut padding = None;
+    if let Ok(counts) = index.as_ints_or_infs(env, "") {
+        if counts.len() <= from.rank() && !from.is_map() {
+            let mut before = Vec::with_capacity(counts.len());
+            let mut after = Vec::with_capacity(counts.len());
+            for (&count, &dim) in counts.iter().zip(from.shape()) {
+                let missing = count.map_or(0, |n| n.unsigned_abs().saturating_sub(dim));
+                let backwards = count.is_ok_and(|n| n < 0);
+                before.push(if backwards { missing } else { 0 });
+                after.push(if backwards { 0 } else { missing });
+            }
+            padding = Some((before, after));
+        }
+    }
+    let from = match padding {
+        Some((before, after)) => pad(from, &before, &after, fill, env)?,
+        None => from,
+    };
+    let taken = index.take(from, env)?;
+    env.push(taken);
+    Ok(())
+}
+
+/// `windows` with a fill function
+pub(crate) fn windows(fill: Function, env: &mut Uiua) -> UiuaResult {
+    let size = env.pop(1)?;
+    let from = env.pop(2)?;
+    let mut sizes = None;
+    if let Ok(spec) = size.as_ints(env, "") {
+        if spec.len() <= from.rank() && !from.is_map() {
+            sizes = (spec.iter().zip(from.shape()))
+                .map(|(&s, &d)| {
+                    let size = if s >= 0 { s } else { d as isize + 1 + s };
+                    (size > 0).then_some(size as usize)
+                })
+                .collect::<Option<Vec<_>>>();
+        }
+    }
+    let windows = if let Some(sizes) = sizes {
+        // Filled windows are centered, so they need half of their size on either side
+        let padding: Vec<usize> = sizes.iter().map(|&s| s / 2).collect();
+        let from = pad(from, &padding, &padding, fill, env)?;
+        let size = Value::from_iter(sizes);
+        size.windows(&from, env)?
+    } else {
+        size.windows(&from, env)?
+    };
+    env.push(windows);
+    Ok(())
+}
+
+/// Pad the leading axes of a value
+///
+/// The fill function is called once for every missing cell
+fn pad(
+    from: Value,
+    before: &[usize],
+    after: &[usize],
+    fill: Function,
+    env: &mut Uiua,
+) -> UiuaResult<Value> {
+    if before.iter().chain(after).all(|&n| n == 0) {
+        return Ok(from);
+    }
+    let axes = before.len();
+    let dims = from.shape()[..axes].to_vec();
+    let cell_shape = Shape::from(&from.shape()[axes..]);
+    let padded: Vec<usize> = (dims.iter().zip(before).zip(after))
+        .map(|((d, b), a)| d + b + a)
+        .collect();
+    let mut shape = Shape::from(padded.as_slice());
+    shape.extend(cell_shape.iter().copied());
+    if padded.contains(&0) {
+        let mut from = from;
+        *from.shape_mut() = shape;
+        return Ok(from);
+    }
+    // View the array as a list of lines along the last padded axis
+    let last = axes - 1;
+    let mut lines = from.clone();
+    let line_count: usize = dims[..last].iter().product();
+    let mut line_shape = Shape::from([line_count, dims[last]].as_slice());
+    line_shape.extend(cell_shape.iter().copied());
+    *lines.shape_mut() = line_shape;
+
+    let mut padded_value: Option<Value> = None;
+    let mut index = vec![0isize; axes];
+    let mut outer = vec![0usize; last];
+    'lines: loop {
+        for (i, (&o, &b)) in outer.iter().zip(before).enumerate() {
+            index[i] = o as isize - b as isize;
+        }
+        let inside = (index[..last].iter().zip(&dims)).all(|(&i, &d)| i >= 0 && (i as usize) < d);
+        let mut pieces = Vec::new();
+        if inside {
+            for i in 0..before[last] {
+                index[last] = i as isize - before[last] as isize;
+                pieces.push(fill_cell(&fill, &index, &from, &cell_shape, env)?);
+            }
+            let mut line_index = 0;
+            for (&i, &d) in index[..last].iter().zip(&dims) {
+                line_index = line_index * d + i as usize;
+            }
+            pieces.push(lines.row(line_index));
+            for i in 0..after[last] {
+                index[last] = (dims[last] + i) as isize;
+                pieces.push(fill_cell(&fill, &index, &from, &cell_shape, env)?);
+            }
+        } else {
+            for i in 0..padded[last] {
+                index[last] = i as isize - before[last] as isize;
+                pieces.push(fill_cell(&fill, &index, &from, &cell_shape, env)?);
+            }
+        }
+        for piece in pieces {
+            padded_value = Some(match padded_value {
+                Some(value) => value.join(piece, false, env)?,
+                None => piece,
+            });
+        }
+        // Go to the next line
+        for i in (0..last).rev() {
+            if outer[i] + 1 < padded[i] {
+                outer[i] += 1;
+                continue 'lines;
+            }
+            outer[i] = 0;
+        }
+        break;
+    }
+    let mut padded_value = padded_value.unwrap_or_default();
+    *padded_value.shape_mut() = shape;
+    padded_value.validate_shape();
+    Ok(padded_value)
+}
+
+/// Call the fill function to get a single missing cell
+///
+/// The cell is returned with a leading axis of length 1
+fn fill_cell(
+    fill: &Function,
+    index: &[isize],
+    from: &Value,
+    cell_shape: &Shape,
+    env: &mut Uiua,
+) -> UiuaResult<Value> {
+    let mut cell = env.without_fill(|env| {
+        if fill.signature().args == 2 {
+            env.push(from.clone());
+        }
+        env.push(index.iter().map(|&i| i as f64).collect::<Value>());
+        env.call(fill.clone())?;
+        env.pop("fill value")
+    })?;
+    if cell.shape() != cell_shape {
+        if cell.rank() == 0 {
+            let shape = cell_shape.iter().copied().collect::<Value>();
+            cell.reshape(&shape, env)?;
+        } else {
+            return Err(env.error(format!(
+                "{} function returned an array of shape {}, \
+                but the missing cell has shape {}",
+                Primitive::LazyFill.format(),
+                cell.shape(),
+                cell_shape
+            )));
+        }
+    }
+    cell.shape_mut().insert(0, 1);
+    Ok(cell)
+}
@@ -16,7 +16,7 @@ use crate::{
     Boxed, FormatShape, Function, ImplPrimitive, Primitive, Shape, Signature, Uiua, UiuaResult,
 };
 
-use super::{multi_output, validate_size_impl};
+use super::{multi_output, par_call_each, row_seed, validate_size_impl, with_row_seed};
 
 pub fn flip<A, B, C>(f: impl Fn(A, B) -> C + Copy) -> impl Fn(B, A) -> C + Copy {
     move |b, a| f(a, b)
@@ -29,6 +29,8 @@ pub fn repeat(env: &mut Uiua) -> UiuaResult {
     let n = match n {
         Value::Num(n) => n,
         Value::Byte(n) => n.convert(),
+        Value::Int(n) => n.convert_with(|n| n as f64),
+        Value::Float(n) => n.convert(),
         val => {
             return Err(env.error(format!(
                 "Repetitions must be a scalar or list of \
@@ -221,6 +223,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.partition_groups(markers, env)?,
             Value::Byte(arr) => arr.partition_groups(markers, env)?,
+            Value::Int(arr) => arr.partition_groups(markers, env)?,
+            Value::Float(arr) => arr.partition_groups(markers, env)?,
             Value::Complex(arr) => arr.partition_groups(markers, env)?,
             Value::Char(arr) => arr.partition_groups(markers, env)?,
             Value::Box(arr) => arr.partition_groups(markers, env)?,
@@ -230,6 +234,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.partition_firsts(markers, env)?.into(),
             Value::Byte(arr) => arr.partition_firsts(markers, env)?.into(),
+            Value::Int(arr) => arr.partition_firsts(markers, env)?.into(),
+            Value::Float(arr) => arr.partition_firsts(markers, env)?.into(),
             Value::Complex(arr) => arr.partition_firsts(markers, env)?.into(),
             Value::Char(arr) => arr.partition_firsts(markers, env)?.into(),
             Value::Box(arr) => arr.partition_firsts(markers, env)?.into(),
@@ -239,6 +245,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.partition_lasts(markers, env)?.into(),
             Value::Byte(arr) => arr.partition_lasts(markers, env)?.into(),
+            Value::Int(arr) => arr.partition_lasts(markers, env)?.into(),
+            Value::Float(arr) => arr.partition_lasts(markers, env)?.into(),
             Value::Complex(arr) => arr.partition_lasts(markers, env)?.into(),
             Value::Char(arr) => arr.partition_lasts(markers, env)?.into(),
             Value::Box(arr) => arr.partition_lasts(markers, env)?.into(),
@@ -615,6 +623,8 @@ pub fn undo_partition_part2(env: &mut Uiua) -> UiuaResult {
                     |a, b| Ok(update_array_at(a, start, b.data.as_slice())),
                     |a, b| Ok(update_array_at(a, start, b.data.as_slice())),
                     |a, b| Ok(update_array_at(a, start, b.data.as_slice())),
+                    |a, b| Ok(update_array_at(a, start, b.data.as_slice())),
+                    |a, b| Ok(update_array_at(a, start, b.data.as_slice())),
                     |a, b| {
                         env.error(format!(
                             "Cannot unpartition {} array into {} array",
@@ -667,6 +677,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
             Value::Byte(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
+            Value::Int(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
+            Value::Float(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
             Value::Complex(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
             Value::Char(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
             Value::Box(arr) => arr.group_groups(indices, env)?.map(Into::into).collect(),
@@ -676,6 +688,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.group_firsts(indices, env)?.into(),
             Value::Byte(arr) => arr.group_firsts(indices, env)?.into(),
+            Value::Int(arr) => arr.group_firsts(indices, env)?.into(),
+            Value::Float(arr) => arr.group_firsts(indices, env)?.into(),
             Value::Complex(arr) => arr.group_firsts(indices, env)?.into(),
             Value::Char(arr) => arr.group_firsts(indices, env)?.into(),
             Value::Box(arr) => arr.group_firsts(indices, env)?.into(),
@@ -685,6 +699,8 @@ impl Value {
         Ok(match self {
             Value::Num(arr) => arr.group_lasts(indices, env)?.into(),
             Value::Byte(arr) => arr.group_lasts(indices, env)?.into(),
+            Value::Int(arr) => arr.group_lasts(indices, env)?.into(),
+            Value::Float(arr) => arr.group_lasts(indices, env)?.into(),
             Value::Complex(arr) => arr.group_lasts(indices, env)?.into(),
             Value::Char(arr) => arr.group_lasts(indices, env)?.into(),
             Value::Box(arr) => arr.group_lasts(indices, env)?.into(),
@@ -795,6 +811,198 @@ impl<T: ArrayValue> Array<T> {
     }
 }
 
+/// Reduce each group of rows in a single pass, without building the groups
+pub fn group_reduce(env: &mut Uiua) -> UiuaResult {
+    crate::profile_function!();
+    let f = env.pop_function()?;
+    let indices =
+        (env.pop(1)?).as_integer_array(env, "Group indices must be an array of integers")?;
+    let mut values = env.pop(2)?;
+    if !values.shape().starts_with(indices.shape()) {
+        return Err(env.error(format!(
+            "Cannot {} array of shape {} with indices of shape {}",
+            Primitive::GroupReduce.format(),
+            values.shape(),
+            indices.shape()
+        )));
+    }
+    let buckets = (indices.data.iter().max().copied().unwrap_or(-1) + 1).max(0) as usize;
+    let prim = f.as_primitive(&env.asm);
+    // Count
+    if prim == Some(Primitive::Len) {
+        let mut counts = eco_vec![0.0; buckets];
+        let slice = counts.make_mut();
+        for &index in &indices.data {
+            if index >= 0 {
+                slice[index as usize] += 1.0;
+            }
+        }
+        env.push(counts);
+        return Ok(());
+    }
+    let sig = f.signature();
+    if sig != (2, 1) {
+        return Err(env.error(format!(
+            "{}'s function must have signature {} or be {}, but its signature is {}",
+            Primitive::GroupReduce.format(),
+            Signature::new(2, 1),
+            Primitive::Len.format(),
+            sig
+        )));
+    }
+    // Treat the axes of the indices as a single axis
+    let row_shape = Shape::from(&values.shape()[indices.rank()..]);
+    let mut shape = row_shape.clone();
+    shape.insert(0, indices.element_count());
+    *values.shape_mut() = shape;
+    // Arithmetic on numbers
+    if let (Some(prim @ (Primitive::Add | Primitive::Max | Primitive::Min)), true) = (
+        prim,
+        matches!(
+            values,
+            Value::Num(_) | Value::Byte(_) | Value::Int(_) | Value::Float(_)
+        ),
+    ) {
+        let nums = values.as_number_array(env, "", |_| true, |_| true, |n| n)?;
+        let (identity, f): (f64, fn(f64, f64) -> f64) = match prim {
+            Primitive::Add => (0.0, |a, b| a + b),
+            Primitive::Max => (f64::NEG_INFINITY, f64::max),
+            _ => (f64::INFINITY, f64::min),
+        };
+        let row_len = row_shape.elements();
+        let mut data = eco_vec![identity; buckets * row_len];
+        if row_len > 0 {
+            let slice = data.make_mut();
+            for (&index, row) in indices.data.iter().zip(nums.data.chunks_exact(row_len)) {
+                if index >= 0 {
+                    let start = index as usize * row_len;
+                    for (acc, &n) in slice[start..start + row_len].iter_mut().zip(row) {
+                        *acc = f(*acc, n);
+                    }
+                }
+            }
+        }
+        let mut shape = row_shape;
+        shape.insert(0, buckets);
+        env.push(Array::new(shape, data));
+        return Ok(());
+    }
+    // Any other function
+    let fill = env.value_fill().cloned();
+    let mut accs: Vec<Option<Value>> = vec![None; buckets];
+    env.without_fill(|env| -> UiuaResult {
+        for (&index, row) in indices.data.iter().zip(values.into_rows()) {
+            if index < 0 {
+                continue;
+            }
+            let acc = &mut accs[index as usize];
+            *acc = Some(match acc.take() {
+                Some(acc) => {
+                    env.push(row);
+                    env.push(acc);
+                    env.call(f.clone())?;
+                    env.pop(|| format!("{}'s function result", Primitive::GroupReduce.format()))?
+                }
+                None => row,
+            });
+        }
+        Ok(())
+    })?;
+    let mut rows = Vec::with_capacity(buckets);
+    for acc in accs {
+        rows.push(match acc.or_else(|| fill.clone()) {
+            Some(row) => row,
+            None => {
+                return Err(env.error(format!(
+                    "Cannot {} an empty group without a fill value",
+                    Primitive::GroupReduce.format()
+                )))
+            }
+        });
+    }
+    let mut result = Value::from_row_values(rows, env)?;
+    if buckets == 0 {
+        let mut shape = row_shape;
+        shape.insert(0, 0);
+        *result.shape_mut() = shape;
+    }
+    env.push(result);
+    Ok(())
+}
+
+/// Group rows into a map from each marker to its boxed group
+///
+/// [`Primitive::GroupMap`] keeps non-negative indices and sorts the keys.
+/// [`Primitive::PartitionMap`] keeps positive markers in the order they first appear.
+pub fn keyed_groups(prim: Primitive, env: &mut Uiua) -> UiuaResult {
+    crate::profile_function!();
+    let indices = (env.pop(1)?).as_integer_array(
+        env,
+        match prim {
+            Primitive::PartitionMap => "Partition markers must be an array of integers",
+            _ => "Group indices must be an array of integers",
+        },
+    )?;
+    let values = env.pop(2)?;
+    if !values.shape().starts_with(indices.shape()) {
+        return Err(env.error(format!(
+            "Cannot {} array of shape {} with indices of shape {}",
+            prim.format(),
+            values.shape(),
+            indices.shape()
+        )));
+    }
+    let (keys, groups) = match values {
+        Value::Num(arr) => arr.keyed_groups(prim, indices),
+        Value::Byte(arr) => arr.keyed_groups(prim, indices),
+        Value::Int(arr) => arr.keyed_groups(prim, indices),
+        Value::Float(arr) => arr.keyed_groups(prim, indices),
+        Value::Complex(arr) => arr.keyed_groups(prim, indices),
+        Value::Char(arr) => arr.keyed_groups(prim, indices),
+        Value::Box(arr) => arr.keyed_groups(prim, indices),
+    };
+    let keys: Value = keys.into_iter().map(|key| key as f64).collect();
+    let mut groups: Value = groups.into_iter().map(Boxed).collect::<Array<_>>().into();
+    groups.map(keys, env)?;
+    env.push(groups);
+    Ok(())
+}
+
+impl<T: ArrayValue> Array<T>
+where
+    Array<T>: Into<Value>,
+{
+    fn keyed_groups(self, prim: Primitive, indices: Array<isize>) -> (Vec<isize>, Vec<Value>) {
+        let keep = |index: isize| match prim {
+            Primitive::PartitionMap => index > 0,
+            _ => index >= 0,
+        };
+        let mut keys = Vec::new();
+        let mut positions = HashMap::new();
+        let mut groups: Vec<Vec<Self>> = Vec::new();
+        let row_shape: Shape = self.shape()[indices.rank()..].into();
+        for (index, row) in (indices.data.into_iter()).zip(self.into_row_shaped_slices(row_shape)) {
+            if !keep(index) {
+                continue;
+            }
+            let pos = *positions.entry(index).or_insert_with(|| {
+                keys.push(index);
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[pos].push(row);
+        }
+        let mut pairs: Vec<_> = keys.into_iter().zip(groups).collect();
+        if prim == Primitive::GroupMap {
+            pairs.sort_by_key(|(key, _)| *key);
+        }
+        pairs
+            .into_iter()
+            .map(|(key, group)| (key, Array::from_row_arrays_infallible(group).into()))
+            .unzip()
+    }
+}
+
 pub fn undo_group_part1(env: &mut Uiua) -> UiuaResult {
     crate::profile_function!();
     let f = env.pop_function()?;
@@ -916,10 +1124,28 @@ where
 
             let groups = get_groups(values, indices, env)?.into_iter();
             let mut rows = multi_output(outputs, Vec::with_capacity(groups.len()));
+            if sig.args == 1 && env.should_call_in_parallel(&f, groups.len()) {
+                let groups: Vec<Value> = groups.collect();
+                let result_name = format!("{}'s function result", prim.format());
+                for outs in par_call_each(&f, groups, outputs, &result_name, env)? {
+                    for (i, value) in outs.into_iter().enumerate() {
+                        rows[i].push(value);
+                    }
+                }
+                for rows in rows.into_iter().rev() {
+                    env.push(Value::from_row_values(rows, env)?);
+                }
+                return Ok(());
+            }
+            let seed = if sig.args == 1 {
+                row_seed(&f, env)
+            } else {
+                None
+            };
             env.without_fill(|env| -> UiuaResult {
-                for group in groups {
+                for (j, group) in groups.enumerate() {
                     env.push(group);
-                    env.call(f.clone())?;
+                    with_row_seed(seed, j, || env.call(f.clone()))?;
                     for i in 0..outputs {
                         let value = env.pop(|| format!("{}'s function result", prim.format()))?;
                         rows[i].push(value);
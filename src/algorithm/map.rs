@@ -84,6 +84,8 @@ impl Value {
             |arr| arr.map_kv().map(|(k, v)| (k, v.into())).collect(),
             |arr| arr.map_kv().map(|(k, v)| (k, v.into())).collect(),
             |arr| arr.map_kv().map(|(k, v)| (k, v.into())).collect(),
+            |arr| arr.map_kv().map(|(k, v)| (k, v.into())).collect(),
+            |arr| arr.map_kv().map(|(k, v)| (k, v.into())).collect(),
         )
     }
     /// Create a map array
@@ -91,6 +93,8 @@ impl Value {
         match self {
             Value::Num(arr) => arr.map(keys, env),
             Value::Byte(arr) => arr.map(keys, env),
+            Value::Int(arr) => arr.map(keys, env),
+            Value::Float(arr) => arr.map(keys, env),
             Value::Complex(arr) => arr.map(keys, env),
             Value::Char(arr) => arr.map(keys, env),
             Value::Box(arr) => arr.map(keys, env),
@@ -166,6 +170,8 @@ impl Value {
                 |arr, value| Ok(arr.set_row(index, value)),
                 |arr, value| Ok(arr.set_row(index, value)),
                 |arr, value| Ok(arr.set_row(index, value)),
+                |arr, value| Ok(arr.set_row(index, value)),
+                |arr, value| Ok(arr.set_row(index, value)),
                 |a, b| {
                     env.error(format!(
                         "Cannot insert {} value into map with {} values",
@@ -210,6 +216,8 @@ impl Value {
                 |arr, value| Ok(arr.set_row(index, value)),
                 |arr, value| Ok(arr.set_row(index, value)),
                 |arr, value| Ok(arr.set_row(index, value)),
+                |arr, value| Ok(arr.set_row(index, value)),
+                |arr, value| Ok(arr.set_row(index, value)),
                 |a, b| {
                     env.error(format!(
                         "Cannot insert {} value into map with {} values",
@@ -226,6 +234,8 @@ impl Value {
                 |arr, value| Ok(arr.insert_row(index, value)),
                 |arr, value| Ok(arr.insert_row(index, value)),
                 |arr, value| Ok(arr.insert_row(index, value)),
+                |arr, value| Ok(arr.insert_row(index, value)),
+                |arr, value| Ok(arr.insert_row(index, value)),
                 |a, b| {
                     env.error(format!(
                         "Cannot insert {} value into map with {} values",
@@ -281,6 +291,8 @@ impl Value {
                 Value::Char(arr) => arr.remove_row(index),
                 Value::Box(arr) => arr.remove_row(index),
                 Value::Byte(arr) => arr.remove_row(index),
+                Value::Int(arr) => arr.remove_row(index),
+                Value::Float(arr) => arr.remove_row(index),
             }
         }
         Ok(())
@@ -315,8 +327,11 @@ impl MapKeys {
     }
     fn grow_to(&mut self, new_capacity: usize) {
         {
-            if let Value::Byte(keys) = &self.keys {
-                self.keys = Value::Num(keys.convert_ref());
+            match &self.keys {
+                Value::Byte(keys) => self.keys = Value::Num(keys.convert_ref()),
+                Value::Int(keys) => self.keys = Value::Num(keys.convert_ref_with(|n| n as f64)),
+                Value::Float(keys) => self.keys = Value::Num(keys.convert_ref()),
+                _ => {}
             }
         }
         match &mut self.keys {
@@ -324,7 +339,7 @@ impl MapKeys {
             Value::Complex(a) => Self::grow_impl(a, &mut self.indices, new_capacity),
             Value::Char(a) => Self::grow_impl(a, &mut self.indices, new_capacity),
             Value::Box(a) => Self::grow_impl(a, &mut self.indices, new_capacity),
-            Value::Byte(_) => unreachable!(),
+            Value::Byte(_) | Value::Int(_) | Value::Float(_) => unreachable!(),
         }
     }
     fn grow_impl<K>(keys: &mut Array<K>, indices: &mut Vec<usize>, new_capacity: usize)
@@ -441,6 +456,8 @@ impl MapKeys {
             Value::Char(a) => hash_start(a, self.capacity()),
             Value::Box(a) => hash_start(a, self.capacity()),
             Value::Byte(a) => hash_start(a, self.capacity()),
+            Value::Int(a) => hash_start(a, self.capacity()),
+            Value::Float(a) => hash_start(a, self.capacity()),
         };
         let mut key_index = start;
         loop {
@@ -589,6 +606,16 @@ impl MapKeys {
                 set_tombstones(&mut nums, dropped);
                 self.keys = Value::Num(nums);
             }
+            Value::Int(keys) => {
+                let mut nums = keys.convert_ref_with(|n| n as f64);
+                set_tombstones(&mut nums, dropped);
+                self.keys = Value::Num(nums);
+            }
+            Value::Float(keys) => {
+                let mut nums = keys.convert_ref_with(|n| n as f64);
+                set_tombstones(&mut nums, dropped);
+                self.keys = Value::Num(nums);
+            }
         }
         for &not_dropped in &present_indices[n..] {
             self.indices[not_dropped] -= n;
@@ -609,6 +636,16 @@ impl MapKeys {
                 set_tombstones(&mut nums, not_taken);
                 self.keys = Value::Num(nums);
             }
+            Value::Int(keys) => {
+                let mut nums = keys.convert_ref_with(|n| n as f64);
+                set_tombstones(&mut nums, not_taken);
+                self.keys = Value::Num(nums);
+            }
+            Value::Float(keys) => {
+                let mut nums = keys.convert_ref_with(|n| n as f64);
+                set_tombstones(&mut nums, not_taken);
+                self.keys = Value::Num(nums);
+            }
         }
         self.len = n;
     }
@@ -705,18 +742,24 @@ fn hash_start<T: ArrayValue>(arr: &Array<T>, capacity: usize) -> usize {
 
 fn coerce_values(
     a: &mut Value,
-    #[allow(unused_mut)] mut b: Value,
+    mut b: Value,
     action1: &'static str,
     action2: &'static str,
     action3: &'static str,
 ) -> Result<Value, String> {
     {
-        if let Value::Byte(keys) = a {
-            *a = Value::Num(keys.convert_ref());
-        }
-        if let Value::Byte(values) = b {
-            b = Value::Num(values.convert_ref());
-        }
+        match a {
+            Value::Byte(keys) => *a = Value::Num(keys.convert_ref()),
+            Value::Int(keys) => *a = Value::Num(keys.convert_ref_with(|n| n as f64)),
+            Value::Float(keys) => *a = Value::Num(keys.convert_ref()),
+            _ => {}
+        }
+        b = match b {
+            Value::Byte(values) => Value::Num(values.convert()),
+            Value::Int(values) => Value::Num(values.convert_with(|n| n as f64)),
+            Value::Float(values) => Value::Num(values.convert()),
+            b => b,
+        };
     }
     if a.shape() == [0] {
         let mut b_clone = b.clone();
@@ -864,6 +907,8 @@ impl MapItem for Value {
         match self {
             Value::Num(num) => num.data.iter().any(|v| v.is_any_empty_cell()),
             Value::Byte(_) => false,
+            Value::Int(_) => false,
+            Value::Float(_) => false,
             Value::Complex(num) => num.data.iter().any(|v| v.is_any_empty_cell()),
             Value::Char(num) => num.data.iter().any(|v| v.is_any_empty_cell()),
             Value::Box(num) => num.data.iter().any(|v| v.is_any_empty_cell()),
@@ -873,6 +918,8 @@ impl MapItem for Value {
         match self {
             Value::Num(num) => num.data.iter().any(|v| v.is_any_tombstone()),
             Value::Byte(_) => false,
+            Value::Int(_) => false,
+            Value::Float(_) => false,
             Value::Complex(num) => num.data.iter().any(|v| v.is_any_tombstone()),
             Value::Char(num) => num.data.iter().any(|v| v.is_any_tombstone()),
             Value::Box(num) => num.data.iter().any(|v| v.is_any_tombstone()),
@@ -882,6 +929,8 @@ impl MapItem for Value {
         match self {
             Value::Num(num) => num.data.iter().all(|v| v.is_any_empty_cell()),
             Value::Byte(_) => false,
+            Value::Int(_) => false,
+            Value::Float(_) => false,
             Value::Complex(num) => num.data.iter().all(|v| v.is_any_empty_cell()),
             Value::Char(num) => num.data.iter().all(|v| v.is_any_empty_cell()),
             Value::Box(num) => num.data.iter().all(|v| v.is_any_empty_cell()),
@@ -891,6 +940,8 @@ impl MapItem for Value {
         match self {
             Value::Num(num) => num.data.iter().all(|v| v.is_any_tombstone()),
             Value::Byte(_) => false,
+            Value::Int(_) => false,
+            Value::Float(_) => false,
             Value::Complex(num) => num.data.iter().all(|v| v.is_any_tombstone()),
             Value::Char(num) => num.data.iter().all(|v| v.is_any_tombstone()),
             Value::Box(num) => num.data.iter().all(|v| v.is_any_tombstone()),
@@ -10,21 +10,49 @@ use std::{
     option,
 };
 
+use ecow::EcoVec;
+use rayon::prelude::*;
 use tinyvec::TinyVec;
 
 use crate::{
-    Array, ArrayValue, CodeSpan, ExactDoubleIterator, Function, Inputs, PersistentMeta, Shape,
-    Signature, Span, TempStack, Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value,
+    function::{instrs_are_pure, instrs_are_pure_or_random, Purity},
+    Array, ArrayValue, Boxed, CodeSpan, ExactDoubleIterator, FormatShape, Function, Inputs,
+    PersistentMeta, PreEvalMode, Primitive, RealArrayValue, Shape, Signature, Span, TempStack,
+    Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value,
 };
 
+mod binary;
+pub(crate) mod diff;
+pub(crate) mod distribution;
+pub(crate) mod dsp;
 mod dyadic;
+pub(crate) mod geo;
+pub(crate) mod graph;
+pub(crate) mod image;
+pub(crate) mod interval;
 pub(crate) mod invert;
+pub(crate) mod ip;
+pub(crate) mod lazy_fill;
 pub mod loops;
 pub(crate) mod map;
 mod monadic;
+pub(crate) mod ode;
 pub mod pervade;
+pub(crate) mod poly;
+pub(crate) mod pool;
+pub(crate) mod quantity;
 pub mod reduce;
+pub(crate) mod schema;
+pub(crate) mod simd;
+pub(crate) mod stats;
 pub mod table;
+pub(crate) mod template;
+pub(crate) mod text_table;
+pub(crate) mod translate;
+pub(crate) mod url;
+pub(crate) mod vector;
+pub(crate) mod view;
+pub(crate) mod xml;
 pub mod zip;
 
 type MultiOutput<T> = TinyVec<[T; 1]>;
@@ -40,6 +68,68 @@ fn multi_output<T: Clone + Default>(n: usize, val: T) -> MultiOutput<T> {
     vec
 }
 
+/// Call a function on each of some arguments, splitting the calls across threads
+///
+/// The outputs of each call are returned in the order they are popped.
+/// If any calls fail, the error from the first failing argument is returned.
+///
+/// If the function generates random numbers, each call gets its own generator,
+/// as described in [`row_seed`].
+fn par_call_each(
+    f: &Function,
+    args: Vec<Value>,
+    outputs: usize,
+    result_name: &str,
+    env: &Uiua,
+) -> UiuaResult<Vec<MultiOutput<Value>>> {
+    let chunk_size = args.len().div_ceil(rayon::current_num_threads()).max(1);
+    let workers: Vec<Uiua> = (0..args.len().div_ceil(chunk_size))
+        .map(|_| env.clone_for_worker())
+        .collect();
+    let seed = row_seed(f, env);
+    let chunks: Vec<UiuaResult<Vec<MultiOutput<Value>>>> = (args.par_chunks(chunk_size))
+        .zip(workers)
+        .enumerate()
+        .map(|(c, (args, mut env))| {
+            let mut results = Vec::with_capacity(args.len());
+            for (i, arg) in args.iter().enumerate() {
+                env.push(arg.clone());
+                with_row_seed(seed, c * chunk_size + i, || env.call(f.clone()))?;
+                let mut outs = MultiOutput::with_capacity(outputs);
+                for _ in 0..outputs {
+                    outs.push(env.pop(result_name)?);
+                }
+                results.push(outs);
+            }
+            Ok(results)
+        })
+        .collect();
+    let mut results = Vec::with_capacity(args.len());
+    for chunk in chunks {
+        results.extend(chunk?);
+    }
+    Ok(results)
+}
+
+/// Draw a seed for calling a function on many rows, if the function generates random numbers
+///
+/// Each row gets its own generator, seeded from this draw and the index of the row,
+/// so results are the same whether or not the rows are called in parallel.
+fn row_seed(f: &Function, env: &Uiua) -> Option<u64> {
+    let instrs = f.instrs(&env.asm);
+    (!instrs_are_pure(instrs, &env.asm, Purity::Pure)
+        && instrs_are_pure_or_random(instrs, &env.asm))
+    .then(crate::random_seed)
+}
+
+/// Call a function with the generator for a row, if a seed was drawn with [`row_seed`]
+fn with_row_seed<T>(seed: Option<u64>, i: usize, f: impl FnOnce() -> T) -> T {
+    match seed {
+        Some(seed) => crate::with_random_seed(seed.wrapping_add(i as u64), f),
+        None => f(),
+    }
+}
+
 fn max_shape(a: &[usize], b: &[usize]) -> Shape {
     let shape_len = a.len().max(b.len());
     let mut new_shape = Shape::with_capacity(shape_len);
@@ -73,7 +163,13 @@ pub fn validate_size<T>(
     sizes: impl IntoIterator<Item = usize> + Clone,
     env: &Uiua,
 ) -> UiuaResult<usize> {
-    validate_size_impl(size_of::<T>(), sizes).map_err(|e| env.error(e))
+    let elements = validate_size_impl(size_of::<T>(), sizes).map_err(|e| env.error(e))?;
+    if let Some(max) = env.rt.max_array_bytes {
+        if elements.saturating_mul(size_of::<T>()) > max {
+            return Err(env.error(SizeError(elements as f64)));
+        }
+    }
+    Ok(elements)
 }
 
 pub(crate) fn validate_size_impl(
@@ -160,6 +256,7 @@ impl FillError for Infallible {
 pub trait FillContext: ErrorContext {
     fn scalar_fill<T: ArrayValue>(&self) -> Result<T, &'static str>;
     fn array_fill<T: ArrayValue>(&self) -> Result<Array<T>, &'static str>;
+    fn fill_is_set(&self) -> bool;
     fn fill_error(error: Self::Error) -> Self::Error;
     fn is_fill_error(error: &Self::Error) -> bool;
 }
@@ -171,6 +268,9 @@ impl FillContext for Uiua {
     fn array_fill<T: ArrayValue>(&self) -> Result<Array<T>, &'static str> {
         T::get_array_fill(self)
     }
+    fn fill_is_set(&self) -> bool {
+        self.value_fill().is_some()
+    }
     fn fill_error(error: Self::Error) -> Self::Error {
         error.fill()
     }
@@ -186,6 +286,9 @@ impl FillContext for () {
     fn array_fill<T: ArrayValue>(&self) -> Result<Array<T>, &'static str> {
         Err(". No fill is set.")
     }
+    fn fill_is_set(&self) -> bool {
+        false
+    }
     fn fill_error(error: Self::Error) -> Self::Error {
         error
     }
@@ -201,6 +304,9 @@ impl FillContext for (&CodeSpan, &Inputs) {
     fn array_fill<T: ArrayValue>(&self) -> Result<Array<T>, &'static str> {
         Err(". No fill is set.")
     }
+    fn fill_is_set(&self) -> bool {
+        false
+    }
     fn fill_error(error: Self::Error) -> Self::Error {
         error.fill()
     }
@@ -213,6 +319,72 @@ pub(crate) fn shape_prefixes_match(a: &[usize], b: &[usize]) -> bool {
     a.iter().zip(b).all(|(a, b)| a == b)
 }
 
+/// Whether two shapes' prefixes match if their length-1 axes are stretched
+pub(crate) fn shape_prefixes_broadcast(a: &[usize], b: &[usize]) -> bool {
+    a.iter().zip(b).all(|(&a, &b)| a == b || a == 1 || b == 1)
+}
+
+/// The shape of the result of broadcasting two shapes together
+///
+/// The shapes' prefixes must [broadcast](shape_prefixes_broadcast)
+pub(crate) fn broadcast_shape(a: &[usize], b: &[usize]) -> Shape {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let mut shape: Shape = (short.iter().zip(long))
+        .map(|(&s, &l)| if s == 1 { l } else { s })
+        .collect();
+    shape.extend(long[short.len()..].iter().copied());
+    shape
+}
+
+/// A diagram of two shapes that do not agree, marking the axes that differ,
+/// followed by hints about how to make them agree
+pub(crate) fn shape_mismatch_note(a: &[usize], b: &[usize], suggest_fill: bool) -> String {
+    let widths: Vec<usize> = (0..a.len().max(b.len()))
+        .map(|i| {
+            (a.get(i).into_iter().chain(b.get(i)))
+                .map(|dim| dim.to_string().len())
+                .max()
+                .unwrap_or(1)
+        })
+        .collect();
+    let row = |shape: &[usize]| {
+        let dims: Vec<String> = (shape.iter().zip(&widths))
+            .map(|(dim, &width)| format!("{dim:>width$}"))
+            .collect();
+        format!("\n  [{}]", dims.join(" × "))
+    };
+    // Leading 1s are fixed and agree with anything
+    let fixed = |shape: &[usize], i: usize| shape[..=i].iter().all(|&dim| dim == 1);
+    let mut marks = String::from("\n   ");
+    for (i, ((a_dim, b_dim), &width)) in a.iter().zip(b).zip(&widths).enumerate() {
+        if i > 0 {
+            marks.push_str("   ");
+        }
+        let differs = a_dim != b_dim && !fixed(a, i) && !fixed(b, i);
+        marks.push_str(&" ".repeat(width - 1));
+        marks.push(if differs { '^' } else { ' ' });
+    }
+    let mut note = row(a) + &row(b) + marks.trim_end();
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if !short.is_empty() && short.len() < long.len() && long.ends_with(short) {
+        note.push_str(&format!(
+            "\nHint: {} matches the last axes of {}, so raising its rank \
+            with `{}{}` ({}) would let them combine.",
+            FormatShape(short),
+            FormatShape(long),
+            Primitive::Rerank,
+            long.len() - 1,
+            Primitive::Rerank.name()
+        ));
+    } else if suggest_fill {
+        note.push_str(&format!(
+            "\nHint: {} can pad the smaller array to make the shapes match.",
+            Primitive::Fill.format()
+        ));
+    }
+    note
+}
+
 fn fill_value_shape<C>(
     val: &mut Value,
     target: &Shape,
@@ -249,6 +421,40 @@ where
             .map_err(|StaticFillError(e)| e)?;
             Ok(())
         }
+        Value::Int(arr) => {
+            *val = op_bytes_retry_fill(
+                arr.clone(),
+                |mut arr| -> Result<Value, StaticFillError> {
+                    fill_array_shape(&mut arr, target, expand_fixed, ctx)
+                        .map_err(StaticFillError)?;
+                    Ok(arr.into())
+                },
+                |mut arr| -> Result<Value, StaticFillError> {
+                    fill_array_shape(&mut arr, target, expand_fixed, ctx)
+                        .map_err(StaticFillError)?;
+                    Ok(arr.into())
+                },
+            )
+            .map_err(|StaticFillError(e)| e)?;
+            Ok(())
+        }
+        Value::Float(arr) => {
+            *val = op_bytes_retry_fill(
+                arr.clone(),
+                |mut arr| -> Result<Value, StaticFillError> {
+                    fill_array_shape(&mut arr, target, expand_fixed, ctx)
+                        .map_err(StaticFillError)?;
+                    Ok(arr.into())
+                },
+                |mut arr| -> Result<Value, StaticFillError> {
+                    fill_array_shape(&mut arr, target, expand_fixed, ctx)
+                        .map_err(StaticFillError)?;
+                    Ok(arr.into())
+                },
+            )
+            .map_err(|StaticFillError(e)| e)?;
+            Ok(())
+        }
         Value::Complex(arr) => fill_array_shape(arr, target, expand_fixed, ctx),
         Value::Char(arr) => fill_array_shape(arr, target, expand_fixed, ctx),
         Value::Box(arr) => fill_array_shape(arr, target, expand_fixed, ctx),
@@ -359,15 +565,17 @@ where
         Ok(())
     } else if let Some(e) = a_err.or(b_err) {
         Err(C::fill_error(ctx.error(format!(
-            "Shapes {} and {} do not match{e}",
+            "Shapes {} and {} do not match{e}{}",
             a.shape(),
             b.shape(),
+            shape_mismatch_note(a.shape(), b.shape(), !ctx.fill_is_set()),
         ))))
     } else {
         Err(C::fill_error(ctx.error(format!(
-            "Shapes {} and {} do not match",
+            "Shapes {} and {} do not match{}",
             a.shape(),
             b.shape(),
+            shape_mismatch_note(a.shape(), b.shape(), !ctx.fill_is_set()),
         ))))
     }
 }
@@ -398,21 +606,24 @@ where
             if shape_prefixes_match(&a.shape, &b.shape) {
                 Ok(())
             } else if let Some(e) = a_err.or(b_err) {
+                let note = shape_mismatch_note(&a_shape, &b_shape, !ctx.fill_is_set());
                 Err(C::fill_error(ctx.error(format!(
-                    "Shapes {a_shape} and {b_shape} do not match{e}"
+                    "Shapes {a_shape} and {b_shape} do not match{e}{note}"
                 ))))
             } else {
+                let note = shape_mismatch_note(&a_shape, &b_shape, !ctx.fill_is_set());
                 Err(C::fill_error(ctx.error(format!(
-                    "Shapes {a_shape} and {b_shape} do not match"
+                    "Shapes {a_shape} and {b_shape} do not match{note}"
                 ))))
             }
         }
         (_, _) => {
             if a.row_count() != b.row_count() {
                 return Err(C::fill_error(ctx.error(format!(
-                    "Shapes {} and {} do not match",
+                    "Shapes {} and {} do not match{}",
                     a.shape(),
                     b.shape(),
+                    shape_mismatch_note(a.shape(), b.shape(), !ctx.fill_is_set()),
                 ))));
             }
             if !shape_prefixes_match(&a.shape[a_depth..], &b.shape[b_depth..]) {
@@ -571,47 +782,197 @@ pub fn try_(env: &mut Uiua) -> UiuaResult {
     Ok(())
 }
 
-/// If a function fails on a byte array because no fill byte is defined,
-/// convert the byte array to a number array and try again.
-fn op_bytes_retry_fill<T, E: FillError>(
-    bytes: Array<u8>,
-    on_bytes: impl FnOnce(Array<u8>) -> Result<T, E>,
+/// The default maximum execution time of code run with `eval`, in milliseconds
+const EVAL_EXECUTION_LIMIT: f64 = 5000.0;
+/// The maximum size in bytes of any array created by code run with `eval`
+const EVAL_MAX_ARRAY_BYTES: usize = 64 * 1024 * 1024;
+
+pub fn eval(env: &mut Uiua) -> UiuaResult {
+    let code = env.pop(1)?.as_string(env, "Code must be a string")?;
+    let args = env.pop(2)?;
+    // The code is run in a fresh interpreter that cannot access the
+    // caller's bindings or the system
+    let mut sub = Uiua::with_safe_sys();
+    // It only gets the time the caller has left, and its arrays are capped
+    sub.rt.execution_limit = Some(match env.rt.execution_limit {
+        Some(limit) => (limit - (instant::now() - env.rt.execution_start)).max(0.0),
+        None => EVAL_EXECUTION_LIMIT,
+    });
+    sub.rt.max_array_bytes = Some(
+        env.rt
+            .max_array_bytes
+            .unwrap_or(usize::MAX)
+            .min(EVAL_MAX_ARRAY_BYTES),
+    );
+    let args: Vec<Value> = match args {
+        Value::Box(arr) if arr.rank() == 1 => arr.data.into_iter().map(|b| b.0).collect(),
+        args => vec![args],
+    };
+    for arg in args.into_iter().rev() {
+        sub.push(arg);
+    }
+    // Constants are not evaluated at compile time, where the limits would not apply
+    if let Err(e) = sub.compile_run(|comp| comp.pre_eval_mode(PreEvalMode::Lazy).load_str(&code)) {
+        return Err(env.error(format!("Error in evaluated code: {e}")));
+    }
+    let outputs: Array<Boxed> = sub.take_stack().into_iter().rev().map(Boxed).collect();
+    env.push(outputs);
+    Ok(())
+}
+
+/// The colors of the viridis colormap at evenly spaced points
+const VIRIDIS: [[u8; 3]; 11] = [
+    [0x44, 0x01, 0x54],
+    [0x48, 0x24, 0x75],
+    [0x41, 0x44, 0x87],
+    [0x35, 0x5f, 0x8d],
+    [0x2a, 0x78, 0x8e],
+    [0x21, 0x91, 0x8c],
+    [0x22, 0xa8, 0x84],
+    [0x44, 0xbf, 0x70],
+    [0x7a, 0xd1, 0x51],
+    [0xbd, 0xdf, 0x26],
+    [0xfd, 0xe7, 0x25],
+];
+
+pub fn colormap(env: &mut Uiua) -> UiuaResult {
+    fn nums(value: &Value) -> Option<Vec<f64>> {
+        match value {
+            Value::Num(arr) => Some(arr.data.to_vec()),
+            Value::Byte(arr) => Some(arr.data.iter().map(|&b| b as f64).collect()),
+            Value::Int(arr) => Some(arr.data.iter().map(|&i| i as f64).collect()),
+            Value::Float(arr) => Some(arr.data.iter().map(|&f| f as f64).collect()),
+            _ => None,
+        }
+    }
+    let colors = env.pop(1)?;
+    let arr = env.pop(2)?;
+    // Get the color stops
+    let (stops, channels): (Vec<f64>, usize) = match &colors {
+        Value::Char(_) => {
+            let name = colors.as_string(env, "Colormap name must be a string")?;
+            match name.as_str() {
+                "viridis" => (
+                    (VIRIDIS.iter().flatten())
+                        .map(|&c| c as f64 / 255.0)
+                        .collect(),
+                    3,
+                ),
+                "grayscale" | "gray" => (vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], 3),
+                _ => {
+                    return Err(env.error(format!(
+                        "Unknown colormap {name:?}. \
+                        Valid colormaps are \"viridis\" and \"grayscale\"."
+                    )))
+                }
+            }
+        }
+        _ if colors.rank() == 2
+            && colors.row_count() > 0
+            && matches!(colors.shape()[1], 3 | 4)
+            && nums(&colors).is_some() =>
+        {
+            (nums(&colors).unwrap(), colors.shape()[1])
+        }
+        _ => {
+            return Err(env.error(format!(
+                "Colormap must be a name or a list of RGB or RGBA colors, \
+                but it is a {} array of shape {}",
+                colors.type_name(),
+                colors.shape()
+            )))
+        }
+    };
+    let Some(values) = nums(&arr) else {
+        return Err(env.error(format!(
+            "Cannot apply a colormap to a {} array",
+            arr.type_name()
+        )));
+    };
+    // Get the range of values
+    let (min, max) = match env.value_fill() {
+        Some(_) => match env.num_array_fill() {
+            Ok(range) if range.shape == [2] => (range.data[0], range.data[1]),
+            _ => {
+                return Err(
+                    env.error("Colormap fill must be a list of the minimum and maximum values")
+                )
+            }
+        },
+        None => (values.iter().copied().filter(|v| !v.is_nan()))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            }),
+    };
+    // Map values to colors
+    let stop_count = stops.len() / channels;
+    let mut data = EcoVec::with_capacity(values.len() * channels);
+    for v in values {
+        let t = if max > min {
+            (v - min) / (max - min)
+        } else {
+            0.0
+        };
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let pos = t * (stop_count - 1) as f64;
+        let i = (pos.floor() as usize).min(stop_count.saturating_sub(2));
+        let frac = pos - i as f64;
+        for c in 0..channels {
+            let a = stops[i * channels + c];
+            let b = stops[(i + 1).min(stop_count - 1) * channels + c];
+            data.push(a + (b - a) * frac);
+        }
+    }
+    let mut shape = arr.shape().clone();
+    shape.push(channels);
+    env.push(Array::<f64>::new(shape, data));
+    Ok(())
+}
+
+/// If a function fails on a byte or integer array because no fill value is defined,
+/// convert the array to a number array and try again.
+fn op_bytes_retry_fill<N: RealArrayValue, T, E: FillError>(
+    bytes: Array<N>,
+    on_bytes: impl FnOnce(Array<N>) -> Result<T, E>,
     on_nums: impl FnOnce(Array<f64>) -> Result<T, E>,
 ) -> Result<T, E> {
     match on_bytes(bytes.clone()) {
         Ok(res) => Ok(res),
-        Err(err) if err.is_fill() => on_nums(bytes.convert()),
+        Err(err) if err.is_fill() => on_nums(bytes.convert_with(|n| n.to_f64())),
         Err(err) => Err(err),
     }
 }
 
-/// If a function fails on a byte array because no fill byte is defined,
-/// convert the byte array to a number array and try again.
-fn op_bytes_ref_retry_fill<T>(
-    bytes: &Array<u8>,
-    on_bytes: impl FnOnce(&Array<u8>) -> UiuaResult<T>,
+/// If a function fails on a byte or integer array because no fill value is defined,
+/// convert the array to a number array and try again.
+fn op_bytes_ref_retry_fill<N: RealArrayValue, T>(
+    bytes: &Array<N>,
+    on_bytes: impl FnOnce(&Array<N>) -> UiuaResult<T>,
     on_nums: impl FnOnce(&Array<f64>) -> UiuaResult<T>,
 ) -> UiuaResult<T> {
     match on_bytes(bytes) {
         Ok(res) => Ok(res),
-        Err(err) if err.is_fill() => on_nums(&bytes.clone().convert()),
+        Err(err) if err.is_fill() => on_nums(&bytes.convert_ref_with(|n| n.to_f64())),
         Err(err) => Err(err),
     }
 }
 
-/// If a function fails on 2 byte arrays because no fill byte is defined,
-/// convert the byte arrays to number arrays and try again.
-fn op2_bytes_retry_fill<T, C: FillContext>(
-    a: Array<u8>,
-    b: Array<u8>,
+/// If a function fails on 2 byte or integer arrays because no fill value is defined,
+/// convert the arrays to number arrays and try again.
+fn op2_bytes_retry_fill<T, C: FillContext, N: RealArrayValue>(
+    a: Array<N>,
+    b: Array<N>,
     ctx: &C,
-    on_bytes: impl FnOnce(Array<u8>, Array<u8>) -> Result<T, C::Error>,
+    on_bytes: impl FnOnce(Array<N>, Array<N>) -> Result<T, C::Error>,
     on_nums: impl FnOnce(Array<f64>, Array<f64>) -> Result<T, C::Error>,
 ) -> Result<T, C::Error> {
     if ctx.scalar_fill::<f64>().is_ok() {
         match on_bytes(a.clone(), b.clone()) {
             Ok(res) => Ok(res),
-            Err(err) if C::is_fill_error(&err) => on_nums(a.convert(), b.convert()),
+            Err(err) if C::is_fill_error(&err) => on_nums(
+                a.convert_with(|n| n.to_f64()),
+                b.convert_with(|n| n.to_f64()),
+            ),
             Err(err) => Err(err),
         }
     } else {
@@ -770,6 +1131,8 @@ fn fft_impl(
     let mut arr: Array<Complex> = match env.pop(1)? {
         Value::Num(arr) => arr.convert(),
         Value::Byte(arr) => arr.convert(),
+        Value::Int(arr) => arr.convert(),
+        Value::Float(arr) => arr.convert(),
         Value::Complex(arr) => arr,
         val => {
             return Err(env.error(format!("Cannot perform FFT on a {} array", val.type_name())));
@@ -811,8 +1174,6 @@ pub fn astar_first(env: &mut Uiua) -> UiuaResult {
 
 #[cfg(feature = "pathfinding")]
 pub fn astar(env: &mut Uiua) -> UiuaResult {
-    use ecow::EcoVec;
-
     use crate::Boxed;
 
     let (solution, cost) = astar_impl(env)?;
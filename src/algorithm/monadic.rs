@@ -15,13 +15,16 @@ use rayon::prelude::*;
 
 use crate::{
     array::*,
+    ast::{Item, Modifier, Word},
     cowslice::{cowslice, CowSlice},
+    format::{format_str, FormatConfig},
     grid_fmt::GridFmt,
+    parse::parse,
     value::Value,
-    Boxed, Complex, Primitive, Shape, Uiua, UiuaResult,
+    Boxed, CodeSpan, Complex, Inputs, Primitive, Shape, Signature, Sp, Uiua, UiuaResult,
 };
 
-use super::{op_bytes_retry_fill, validate_size, ArrayCmpSlice, FillContext};
+use super::{op_bytes_retry_fill, pool, validate_size, ArrayCmpSlice, FillContext};
 
 impl Value {
     /// Make the value 1-dimensional
@@ -32,6 +35,8 @@ impl Value {
         match self {
             Value::Num(n) => n.deshape_depth(depth),
             Value::Byte(b) => b.deshape_depth(depth),
+            Value::Int(i) => i.deshape_depth(depth),
+            Value::Float(f) => f.deshape_depth(depth),
             Value::Complex(c) => c.deshape_depth(depth),
             Value::Char(c) => c.deshape_depth(depth),
             Value::Box(b) => {
@@ -116,6 +121,20 @@ impl Value {
                     .collect();
                 Array::new(bytes.shape.clone(), new_data).into()
             }
+            Value::Int(ints) => {
+                let new_data: CowSlice<Boxed> = (ints.data.iter().map(|v| v.grid_string(false)))
+                    .map(Value::from)
+                    .map(Boxed)
+                    .collect();
+                Array::new(ints.shape.clone(), new_data).into()
+            }
+            Value::Float(floats) => {
+                let new_data: CowSlice<Boxed> = (floats.data.iter().map(|v| v.to_string()))
+                    .map(Value::from)
+                    .map(Boxed)
+                    .collect();
+                Array::new(floats.shape.clone(), new_data).into()
+            }
             Value::Complex(complexes) => {
                 let new_data: CowSlice<Boxed> = (complexes.data.iter().map(|v| v.to_string()))
                     .map(Value::from)
@@ -313,6 +332,20 @@ impl Value {
                     |a| a.first(env).map(Into::into),
                 )
             },
+            |a| {
+                op_bytes_retry_fill(
+                    a,
+                    |a| a.first(env).map(Into::into),
+                    |a| a.first(env).map(Into::into),
+                )
+            },
+            |a| {
+                op_bytes_retry_fill(
+                    a,
+                    |a| a.first(env).map(Into::into),
+                    |a| a.first(env).map(Into::into),
+                )
+            },
             |a| a.first(env).map(Into::into),
             |a| a.first(env).map(Into::into),
             |a| a.first(env).map(Into::into),
@@ -329,6 +362,20 @@ impl Value {
                     |a| a.last(env).map(Into::into),
                 )
             },
+            |a| {
+                op_bytes_retry_fill(
+                    a,
+                    |a| a.last(env).map(Into::into),
+                    |a| a.last(env).map(Into::into),
+                )
+            },
+            |a| {
+                op_bytes_retry_fill(
+                    a,
+                    |a| a.last(env).map(Into::into),
+                    |a| a.last(env).map(Into::into),
+                )
+            },
             |a| a.last(env).map(Into::into),
             |a| a.last(env).map(Into::into),
             |a| a.last(env).map(Into::into),
@@ -343,6 +390,8 @@ impl Value {
                 |a, b| a.unfirst(b, env).map(Into::into),
                 |a, b| a.unfirst(b, env).map(Into::into),
                 |a, b| a.unfirst(b, env).map(Into::into),
+                |a, b| a.unfirst(b, env).map(Into::into),
+                |a, b| a.unfirst(b, env).map(Into::into),
                 |a, b| {
                     env.error(format!(
                         "Cannot unfirst {} into {}",
@@ -362,6 +411,8 @@ impl Value {
                 |a, b| a.unlast(b, env).map(Into::into),
                 |a, b| a.unlast(b, env).map(Into::into),
                 |a, b| a.unlast(b, env).map(Into::into),
+                |a, b| a.unlast(b, env).map(Into::into),
+                |a, b| a.unlast(b, env).map(Into::into),
                 |a, b| {
                     env.error(format!(
                         "Cannot unlast {} into {}",
@@ -444,6 +495,8 @@ impl Value {
             |a| a.reverse_depth(depth),
             |a| a.reverse_depth(depth),
             |a| a.reverse_depth(depth),
+            |a| a.reverse_depth(depth),
+            |a| a.reverse_depth(depth),
         )
     }
 }
@@ -498,12 +551,16 @@ impl Value {
             Array::transpose,
             Array::transpose,
             Array::transpose,
+            Array::transpose,
+            Array::transpose,
         )
     }
     pub(crate) fn transpose_depth(&mut self, depth: usize, amnt: i32) {
         match self {
             Value::Num(n) => n.transpose_depth(depth, amnt),
             Value::Byte(b) => b.transpose_depth(depth, amnt),
+            Value::Int(i) => i.transpose_depth(depth, amnt),
+            Value::Float(f) => f.transpose_depth(depth, amnt),
             Value::Complex(c) => c.transpose_depth(depth, amnt),
             Value::Char(c) => c.transpose_depth(depth, amnt),
             Value::Box(b) => {
@@ -562,41 +619,15 @@ impl<T: ArrayValue> Array<T> {
         for data in data_slice.chunks_exact_mut(self.shape[depth..].iter().product()) {
             // Special in-place case for square matrices
             if square_matrix {
-                if subs > 500 {
-                    // This is pretty unsafe, but no indices should collide, so it's fine? 🤷
-                    let ptr: usize = data.as_mut_ptr() as usize;
-                    (0..s - 1).into_par_iter().for_each(|i| {
-                        let ptr: *mut T = ptr as *mut _;
-                        for j in i + 1..s {
-                            unsafe {
-                                ptr::swap_nonoverlapping(ptr.add(i * s + j), ptr.add(j * s + i), 1);
-                            }
-                        }
-                    });
-                } else {
-                    for i in 0..s - 1 {
-                        for j in i + 1..s {
-                            data.swap(i * s + j, j * s + i);
-                        }
-                    }
-                }
+                transpose_square_tiled(data, s);
                 continue;
             }
             let stride = data.len() / subs;
-            // The operation to perform on each subarray
-            let op = |(temp_chunk_i, chunk): (usize, &mut [T])| {
-                for (chunk_i, item) in chunk.iter_mut().enumerate() {
-                    *item = data[chunk_i * stride + temp_chunk_i].clone();
-                }
-            };
-            // Perform the operation on each subarray
-            let mut temp = data.to_vec();
-            if subs > 500 {
-                temp.par_chunks_mut(subs).enumerate().for_each(op);
-            } else {
-                temp.chunks_mut(subs).enumerate().for_each(op);
-            }
-            data.clone_from_slice(&temp);
+            // Every element of the buffer is overwritten, so it is initialized with any element
+            pool::with_buffer(data.len(), data[0].clone(), |temp| {
+                transpose_tiled(data, temp, subs, stride);
+                data.clone_from_slice(temp);
+            });
         }
         if forward {
             self.shape[depth..].rotate_left(trans_count);
@@ -606,6 +637,66 @@ impl<T: ArrayValue> Array<T> {
     }
 }
 
+/// The side length of the tiles that matrices are transposed in
+///
+/// Working one tile at a time keeps both the rows being read
+/// and the rows being written in the cache.
+const TRANSPOSE_TILE: usize = 32;
+/// The minimum number of elements for a transpose to be done in parallel
+const PAR_TRANSPOSE_LEN: usize = 1 << 16;
+
+/// Transpose a matrix of shape `[rows, cols]` from `src` into `dst`
+fn transpose_tiled<T: ArrayValue>(src: &[T], dst: &mut [T], rows: usize, cols: usize) {
+    // Each band of `TRANSPOSE_TILE` rows of the result is independent
+    let band = |(b, band): (usize, &mut [T])| {
+        let i0 = b * TRANSPOSE_TILE;
+        for j0 in (0..rows).step_by(TRANSPOSE_TILE) {
+            let j1 = (j0 + TRANSPOSE_TILE).min(rows);
+            for (di, dst_row) in band.chunks_exact_mut(rows).enumerate() {
+                let i = i0 + di;
+                for (j, item) in (j0..j1).zip(&mut dst_row[j0..j1]) {
+                    *item = src[j * cols + i].clone();
+                }
+            }
+        }
+    };
+    if src.len() >= PAR_TRANSPOSE_LEN {
+        (dst.par_chunks_mut(TRANSPOSE_TILE * rows).enumerate()).for_each(band);
+    } else {
+        dst.chunks_mut(TRANSPOSE_TILE * rows)
+            .enumerate()
+            .for_each(band);
+    }
+}
+
+/// Transpose a square matrix with side length `s` in place
+fn transpose_square_tiled<T: ArrayValue>(data: &mut [T], s: usize) {
+    // Swap the elements of each tile on or above the diagonal with its mirror
+    let swap_band = |ptr: *mut T, i0: usize| {
+        let i1 = (i0 + TRANSPOSE_TILE).min(s);
+        for j0 in (i0..s).step_by(TRANSPOSE_TILE) {
+            let j1 = (j0 + TRANSPOSE_TILE).min(s);
+            for i in i0..i1 {
+                for j in j0.max(i + 1)..j1 {
+                    // Safety: each pair of mirrored elements is swapped by exactly one band
+                    unsafe { ptr::swap_nonoverlapping(ptr.add(i * s + j), ptr.add(j * s + i), 1) };
+                }
+            }
+        }
+    };
+    if data.len() >= PAR_TRANSPOSE_LEN {
+        let ptr = data.as_mut_ptr() as usize;
+        (0..s.div_ceil(TRANSPOSE_TILE))
+            .into_par_iter()
+            .for_each(|b| swap_band(ptr as *mut T, b * TRANSPOSE_TILE));
+    } else {
+        let ptr = data.as_mut_ptr();
+        for i0 in (0..s).step_by(TRANSPOSE_TILE) {
+            swap_band(ptr, i0);
+        }
+    }
+}
+
 impl Value {
     /// Get the `rise` of the value
     pub fn rise(&self) -> Array<f64> {
@@ -615,6 +706,8 @@ impl Value {
             Array::rise,
             Array::rise,
             Array::rise,
+            Array::rise,
+            Array::rise,
         )
     }
     /// Get the `fall` of the value
@@ -625,6 +718,8 @@ impl Value {
             Array::fall,
             Array::fall,
             Array::fall,
+            Array::fall,
+            Array::fall,
         )
     }
     /// Sort the value ascending
@@ -635,6 +730,8 @@ impl Value {
             Array::sort_up,
             Array::sort_up,
             Array::sort_up,
+            Array::sort_up,
+            Array::sort_up,
         )
     }
     /// Sort the value descending
@@ -645,6 +742,8 @@ impl Value {
             Array::sort_down,
             Array::sort_down,
             Array::sort_down,
+            Array::sort_down,
+            Array::sort_down,
         )
     }
     /// `classify` the rows of the value
@@ -660,6 +759,8 @@ impl Value {
                 Array::classify,
                 Array::classify,
                 Array::classify,
+                Array::classify,
+                Array::classify,
             )
             .into_iter()
             .collect();
@@ -676,6 +777,8 @@ impl Value {
             |a| a.classify_depth(depth),
             |a| a.classify_depth(depth),
             |a| a.classify_depth(depth),
+            |a| a.classify_depth(depth),
+            |a| a.classify_depth(depth),
         );
         if let Some(map_keys) = map_keys {
             val.meta_mut().map_keys = Some(map_keys);
@@ -690,6 +793,8 @@ impl Value {
             |a| a.deduplicate(env),
             |a| a.deduplicate(env),
             |a| a.deduplicate(env),
+            |a| a.deduplicate(env),
+            |a| a.deduplicate(env),
         )
     }
     /// Mask the `unique` rows of the value
@@ -700,6 +805,8 @@ impl Value {
             Array::unique,
             Array::unique,
             Array::unique,
+            Array::unique,
+            Array::unique,
         )
         .into()
     }
@@ -711,6 +818,8 @@ impl Value {
             Array::count_unique,
             Array::count_unique,
             Array::count_unique,
+            Array::count_unique,
+            Array::count_unique,
         )
     }
 }
@@ -905,6 +1014,8 @@ impl Value {
     pub fn bits(&self, env: &Uiua) -> UiuaResult<Value> {
         match self {
             Value::Byte(n) => n.bits(env),
+            Value::Int(n) => n.bits(env),
+            Value::Float(n) => n.bits(env),
             Value::Num(n) => n.bits(env),
             _ => Err(env.error("Argument to bits must be an array of natural numbers")),
         }
@@ -913,6 +1024,8 @@ impl Value {
     pub fn unbits(&self, env: &Uiua) -> UiuaResult<Value> {
         match self {
             Value::Byte(n) => n.inverse_bits(env),
+            Value::Int(n) => n.inverse_bits(env),
+            Value::Float(n) => n.inverse_bits(env),
             Value::Num(n) => n.inverse_bits(env),
             _ => Err(env.error("Argument to inverse_bits must be an array of integers")),
         }
@@ -1071,10 +1184,28 @@ impl Value {
     }
     /// Get the `first` index `where` the value is nonzero
     pub fn first_where(&self, env: &Uiua) -> UiuaResult<Array<f64>> {
+        match self {
+            Value::Int(ints) => {
+                return Value::from(ints.convert_ref_with(|i| i as f64)).first_where(env)
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref::<f64>()).first_where(env)
+            }
+            _ => {}
+        }
         self.first_where_impl(env, identity, identity)
     }
     /// Get the last index `where` the value is nonzero
     pub fn last_where(&self, env: &Uiua) -> UiuaResult<Array<f64>> {
+        match self {
+            Value::Int(ints) => {
+                return Value::from(ints.convert_ref_with(|i| i as f64)).last_where(env)
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref::<f64>()).last_where(env)
+            }
+            _ => {}
+        }
         self.first_where_impl(env, Iterator::rev, Iterator::rev)
     }
     fn first_where_impl<'a, B, N>(
@@ -1283,6 +1414,8 @@ impl Value {
             Array::first_min_index,
             Array::first_min_index,
             Array::first_min_index,
+            Array::first_min_index,
+            Array::first_min_index,
             env,
         )
         .map(Into::into)
@@ -1294,6 +1427,8 @@ impl Value {
             Array::first_max_index,
             Array::first_max_index,
             Array::first_max_index,
+            Array::first_max_index,
+            Array::first_max_index,
             env,
         )
         .map(Into::into)
@@ -1305,6 +1440,8 @@ impl Value {
             Array::last_min_index,
             Array::last_min_index,
             Array::last_min_index,
+            Array::last_min_index,
+            Array::last_min_index,
             env,
         )
         .map(Into::into)
@@ -1316,6 +1453,8 @@ impl Value {
             Array::last_max_index,
             Array::last_max_index,
             Array::last_max_index,
+            Array::last_max_index,
+            Array::last_max_index,
             env,
         )
         .map(Into::into)
@@ -1402,6 +1541,8 @@ impl Value {
         match self {
             Value::Num(n) => n.primes(env),
             Value::Byte(b) => b.convert_ref::<f64>().primes(env),
+            Value::Int(i) => i.convert_ref_with(|i| i as f64).primes(env),
+            Value::Float(f) => f.convert_ref::<f64>().primes(env),
             value => Err(env.error(format!("Cannot get primes of {} array", value.type_name()))),
         }
     }
@@ -1486,6 +1627,10 @@ impl Value {
                     serde_json::Value::Number(b.into())
                 }
             }
+            Value::Int(ints) if ints.rank() == 0 => serde_json::Value::Number(ints.data[0].into()),
+            Value::Float(floats) if floats.rank() == 0 => {
+                Value::from(floats.convert_ref::<f64>()).to_json_value(env)?
+            }
             Value::Complex(_) => return Err(env.error("Cannot convert complex numbers to JSON")),
             Value::Char(c) if c.rank() == 0 => serde_json::Value::String(c.data[0].to_string()),
             Value::Char(c) if c.rank() == 1 => serde_json::Value::String(c.data.iter().collect()),
@@ -1573,6 +1718,383 @@ impl Value {
             }
         })
     }
+    pub(crate) fn to_cbor(&self, env: &Uiua) -> UiuaResult<Vec<u8>> {
+        #[cfg(not(feature = "cbor"))]
+        return Err(env.error("CBOR encoding is not enabled in this environment"));
+        #[cfg(feature = "cbor")]
+        {
+            let json = self.to_json_value(env)?;
+            let mut bytes = Vec::new();
+            ciborium::into_writer(&json, &mut bytes).map_err(|e| env.error(e))?;
+            Ok(bytes)
+        }
+    }
+    pub(crate) fn from_cbor(_cbor: &[u8], env: &Uiua) -> UiuaResult<Self> {
+        #[cfg(not(feature = "cbor"))]
+        return Err(env.error("CBOR decoding is not enabled in this environment"));
+        #[cfg(feature = "cbor")]
+        {
+            let cbor: ciborium::Value = ciborium::from_reader(_cbor).map_err(|e| env.error(e))?;
+            Self::from_json_value(cbor_to_json(cbor, env)?, env)
+        }
+    }
+    pub(crate) fn to_toml_string(&self, env: &Uiua) -> UiuaResult<String> {
+        let json = self.to_json_value(env)?;
+        toml::to_string(&json).map_err(|e| env.error(e))
+    }
+    pub(crate) fn from_toml_string(toml: &str, env: &Uiua) -> UiuaResult<Self> {
+        let toml: toml::Table = toml.parse().map_err(|e| env.error(e))?;
+        Self::from_json_value(toml_to_json(toml::Value::Table(toml)), env)
+    }
+    pub(crate) fn to_yaml_string(&self, env: &Uiua) -> UiuaResult<String> {
+        #[cfg(not(feature = "yaml"))]
+        return Err(env.error("YAML encoding is not enabled in this environment"));
+        #[cfg(feature = "yaml")]
+        {
+            let json = self.to_json_value(env)?;
+            serde_yaml::to_string(&json).map_err(|e| env.error(e))
+        }
+    }
+    pub(crate) fn from_yaml_string(_yaml: &str, env: &Uiua) -> UiuaResult<Self> {
+        #[cfg(not(feature = "yaml"))]
+        return Err(env.error("YAML decoding is not enabled in this environment"));
+        #[cfg(feature = "yaml")]
+        {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(_yaml).map_err(|e| env.error(e))?;
+            Self::from_json_value(yaml_to_json(yaml, env)?, env)
+        }
+    }
+}
+
+/// Convert a CBOR tree to the equivalent JSON tree
+#[cfg(feature = "cbor")]
+fn cbor_to_json(cbor: ciborium::Value, env: &Uiua) -> UiuaResult<serde_json::Value> {
+    use ciborium::Value as Cbor;
+    Ok(match cbor {
+        Cbor::Null => serde_json::Value::Null,
+        Cbor::Bool(b) => b.into(),
+        Cbor::Integer(i) => {
+            let i = i128::from(i);
+            if let Ok(i) = i64::try_from(i) {
+                i.into()
+            } else if let Ok(u) = u64::try_from(i) {
+                u.into()
+            } else {
+                (serde_json::Number::from_f64(i as f64)).map_or(serde_json::Value::Null, Into::into)
+            }
+        }
+        Cbor::Float(f) => {
+            serde_json::Number::from_f64(f).map_or(serde_json::Value::Null, Into::into)
+        }
+        Cbor::Text(s) => s.into(),
+        Cbor::Bytes(bytes) => bytes.into_iter().map(serde_json::Value::from).collect(),
+        Cbor::Tag(_, value) => cbor_to_json(*value, env)?,
+        Cbor::Array(items) => (items.into_iter())
+            .map(|item| cbor_to_json(item, env))
+            .collect::<UiuaResult<_>>()?,
+        Cbor::Map(entries) => {
+            let mut map = serde_json::Map::with_capacity(entries.len());
+            for (k, v) in entries {
+                let k = match k {
+                    Cbor::Text(s) => s,
+                    Cbor::Integer(i) => i128::from(i).to_string(),
+                    _ => return Err(env.error("CBOR map keys must be strings or integers")),
+                };
+                map.insert(k, cbor_to_json(v, env)?);
+            }
+            serde_json::Value::Object(map)
+        }
+        _ => return Err(env.error("Unsupported CBOR value")),
+    })
+}
+
+/// Convert a TOML tree to the equivalent JSON tree
+fn toml_to_json(toml: toml::Value) -> serde_json::Value {
+    match toml {
+        toml::Value::String(s) => s.into(),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => {
+            serde_json::Number::from_f64(f).map_or(serde_json::Value::Null, Into::into)
+        }
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(dt) => dt.to_string().into(),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => serde_json::Value::Object(
+            (table.into_iter())
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a YAML tree to the equivalent JSON tree
+#[cfg(feature = "yaml")]
+fn yaml_to_json(yaml: serde_yaml::Value, env: &Uiua) -> UiuaResult<serde_json::Value> {
+    use serde_yaml::Value as Yaml;
+    Ok(match yaml {
+        Yaml::Null => serde_json::Value::Null,
+        Yaml::Bool(b) => b.into(),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into()
+            } else if let Some(u) = n.as_u64() {
+                u.into()
+            } else {
+                (n.as_f64().and_then(serde_json::Number::from_f64))
+                    .map_or(serde_json::Value::Null, Into::into)
+            }
+        }
+        Yaml::String(s) => s.into(),
+        Yaml::Sequence(items) => (items.into_iter())
+            .map(|item| yaml_to_json(item, env))
+            .collect::<UiuaResult<_>>()?,
+        Yaml::Mapping(mapping) => {
+            let mut map = serde_json::Map::with_capacity(mapping.len());
+            for (k, v) in mapping {
+                let k = match k {
+                    Yaml::String(s) => s,
+                    Yaml::Number(n) => n.to_string(),
+                    Yaml::Bool(b) => b.to_string(),
+                    _ => {
+                        return Err(env.error("YAML map keys must be strings, numbers, or booleans"))
+                    }
+                };
+                map.insert(k, yaml_to_json(v, env)?);
+            }
+            serde_json::Value::Object(map)
+        }
+        Yaml::Tagged(tagged) => yaml_to_json(tagged.value, env)?,
+    })
+}
+
+/// Create a node of the array representation of the AST
+fn ast_node(kind: &str, parts: impl IntoIterator<Item = Value>) -> Value {
+    (iter::once(Boxed(kind.into())))
+        .chain(parts.into_iter().map(Boxed))
+        .collect::<Array<Boxed>>()
+        .into()
+}
+
+fn ast_list(nodes: impl IntoIterator<Item = Value>) -> Value {
+    nodes
+        .into_iter()
+        .map(Boxed)
+        .collect::<Array<Boxed>>()
+        .into()
+}
+
+impl Value {
+    pub(crate) fn from_code_ast(code: &str, env: &Uiua) -> UiuaResult<Self> {
+        let mut inputs = Inputs::default();
+        let (items, errors, _) = parse(code, (), &mut inputs);
+        if let Some(error) = errors.first() {
+            return Err(env.error(format!("Invalid code: {error}")));
+        }
+        let source = |span: &CodeSpan| span.as_str(&inputs, |s| Value::from(s));
+        let words = |words: &[Sp<Word>]| -> Value {
+            ast_list(words.iter().filter_map(|word| word_ast(word, &source)))
+        };
+        let mut nodes = Vec::new();
+        for item in &items {
+            match item {
+                Item::Words(lines) => {
+                    let lines = lines.iter().filter(|line| {
+                        (line.iter())
+                            .any(|w| w.value.is_code() || matches!(w.value, Word::Comment(_)))
+                    });
+                    for line in lines {
+                        nodes.push(ast_node("line", [words(line)]));
+                    }
+                }
+                Item::Binding(binding) if binding.public && !binding.array_macro => {
+                    let mut body = Vec::new();
+                    if let Some(sig) = &binding.signature {
+                        body.push(sig_ast(&sig.value));
+                    }
+                    body.extend(binding.words.iter().filter_map(|w| word_ast(w, &source)));
+                    nodes.push(ast_node(
+                        "binding",
+                        [binding.name.value.as_str().into(), ast_list(body)],
+                    ));
+                }
+                Item::Binding(binding) => nodes.push(ast_node("code", [source(&binding.span())])),
+                Item::Destructure(destructure) => {
+                    nodes.push(ast_node("code", [source(&destructure.span())]))
+                }
+                Item::Import(import) => nodes.push(ast_node("code", [source(&import.span())])),
+                Item::Data(data) => nodes.push(ast_node("code", [source(&data.span())])),
+                Item::TestScope(scope) => nodes.push(ast_node("code", [source(&scope.span)])),
+            }
+        }
+        Ok(ast_list(nodes))
+    }
+    pub(crate) fn to_code_ast(&self, env: &Uiua) -> UiuaResult<String> {
+        let mut code = String::new();
+        for node in self.rows() {
+            if !code.is_empty() {
+                code.push('\n');
+            }
+            code.push_str(&ast_code(&node.unboxed(), env)?);
+        }
+        let config = FormatConfig::default().with_trailing_newline(false);
+        Ok(format_str(&code, &config).map_or(code, |formatted| formatted.output))
+    }
+}
+
+fn sig_ast(sig: &Signature) -> Value {
+    ast_node("code", [format!("|{}.{}", sig.args, sig.outputs).into()])
+}
+
+fn word_ast(word: &Sp<Word>, source: &impl Fn(&CodeSpan) -> Value) -> Option<Value> {
+    let lines = |lines: &[Vec<Sp<Word>>], sig: &Option<Sp<Signature>>| -> Value {
+        let mut lines: Vec<Vec<Value>> = (lines.iter())
+            .map(|line| line.iter().filter_map(|w| word_ast(w, source)).collect())
+            .collect();
+        if let Some(sig) = sig {
+            let sig = sig_ast(&sig.value);
+            match lines.first_mut() {
+                Some(first) => first.insert(0, sig),
+                None => lines.push(vec![sig]),
+            }
+        }
+        ast_list(lines.into_iter().map(ast_list))
+    };
+    Some(match &word.value {
+        Word::Number(_, n) => ast_node("number", [(*n).into()]),
+        Word::Char(c) if c.chars().count() == 1 => {
+            ast_node("char", [c.chars().next().unwrap().into()])
+        }
+        Word::String(s) | Word::MultilineString(s) => ast_node("string", [s.as_str().into()]),
+        Word::Label(label) => ast_node("label", [label.as_str().into()]),
+        Word::Ref(r) => ast_node("ref", [r.to_string().into()]),
+        Word::Primitive(prim) => ast_node("primitive", [prim.name().into()]),
+        Word::Strand(items) => ast_node(
+            "strand",
+            [ast_list(items.iter().filter_map(|w| word_ast(w, source)))],
+        ),
+        Word::Array(arr) => ast_node(
+            if arr.boxes { "boxarray" } else { "array" },
+            [lines(&arr.lines, &arr.signature)],
+        ),
+        Word::Func(func) => ast_node("func", [lines(&func.lines, &func.signature)]),
+        Word::Pack(pack) => ast_node(
+            if pack.angled { "switch" } else { "pack" },
+            [ast_list(pack.branches.iter().map(|branch| {
+                ast_node(
+                    "func",
+                    [lines(&branch.value.lines, &branch.value.signature)],
+                )
+            }))],
+        ),
+        Word::Modified(m) => {
+            let modifier = match &m.modifier.value {
+                Modifier::Primitive(prim) => ast_node("primitive", [prim.name().into()]),
+                Modifier::Ref(r) => ast_node("ref", [r.to_string().into()]),
+            };
+            let operands = ast_list(m.operands.iter().filter_map(|w| word_ast(w, source)));
+            ast_node("modified", [modifier, operands])
+        }
+        Word::Comment(comment) => ast_node("comment", [comment.as_str().into()]),
+        Word::Spaces | Word::BreakLine | Word::UnbreakLine => return None,
+        _ => ast_node("code", [source(&word.span)]),
+    })
+}
+
+fn ast_code(node: &Value, env: &Uiua) -> UiuaResult<String> {
+    let parts: Vec<Value> = node.rows().map(Value::unboxed).collect();
+    let Some((kind, parts)) = parts.split_first() else {
+        return Err(env.error("AST node cannot be empty"));
+    };
+    let kind = kind.as_string(env, "AST node kind must be a string")?;
+    let part = |i: usize| {
+        parts
+            .get(i)
+            .ok_or_else(|| env.error(format!("AST node `{kind}` is missing a part")))
+    };
+    let words = |nodes: &Value| -> UiuaResult<String> {
+        let mut s = String::new();
+        for node in nodes.rows() {
+            push_ast_code(&mut s, &ast_code(&node.unboxed(), env)?);
+        }
+        Ok(s)
+    };
+    let lines = |i: usize| -> UiuaResult<String> {
+        let mut s = String::new();
+        for (j, line) in part(i)?.rows().enumerate() {
+            if j > 0 {
+                s.push('\n');
+            }
+            s.push_str(&words(&line.unboxed())?);
+        }
+        Ok(s)
+    };
+    Ok(match kind.as_str() {
+        "line" => words(part(0)?)?,
+        "binding" => {
+            let name = part(0)?.as_string(env, "Binding name must be a string")?;
+            format!("{name} ← {}", words(part(1)?)?)
+        }
+        "number" | "char" | "string" => part(0)?.representation(),
+        "label" => format!("${}", part(0)?.as_string(env, "Label must be a string")?),
+        "primitive" => {
+            let name = part(0)?.as_string(env, "Primitive name must be a string")?;
+            Primitive::from_name(&name)
+                .ok_or_else(|| env.error(format!("Unknown primitive `{name}`")))?
+                .to_string()
+        }
+        "ref" | "code" => part(0)?.as_string(env, "AST node text must be a string")?,
+        "comment" => format!("#{}", part(0)?.as_string(env, "Comment must be a string")?),
+        "strand" => {
+            let mut s = String::new();
+            for (i, node) in part(0)?.rows().enumerate() {
+                if i > 0 {
+                    s.push('_');
+                }
+                s.push_str(&ast_code(&node.unboxed(), env)?);
+            }
+            s
+        }
+        "array" => format!("[{}]", lines(0)?),
+        "boxarray" => format!("{{{}}}", lines(0)?),
+        "func" => format!("({})", lines(0)?),
+        "pack" | "switch" => {
+            let mut branches = Vec::new();
+            for (i, branch) in part(0)?.rows().enumerate() {
+                let branch = ast_code(&branch.unboxed(), env)?;
+                let mut branch = branch.strip_prefix('(').unwrap_or(&branch);
+                branch = branch.strip_suffix(')').unwrap_or(branch);
+                // Branch signatures after the first are attached to the separator
+                if i > 0 {
+                    branch = branch.strip_prefix('|').unwrap_or(branch);
+                }
+                branches.push(branch.to_string());
+            }
+            if kind == "pack" {
+                format!("({})", branches.join("|"))
+            } else {
+                format!("⟨{}⟩", branches.join("|"))
+            }
+        }
+        "modified" => {
+            let mut s = ast_code(part(0)?, env)?;
+            push_ast_code(&mut s, &words(part(1)?)?);
+            s
+        }
+        kind => return Err(env.error(format!("Unknown AST node kind `{kind}`"))),
+    })
+}
+
+type TableRow = Vec<String>;
+
+/// Append code, separating it from the previous code only if necessary
+fn push_ast_code(s: &mut String, code: &str) {
+    let word_like = |c: char| c.is_alphanumeric() || "_¯\"@$.&".contains(c);
+    if let (Some(prev), Some(next)) = (s.chars().last(), code.chars().next()) {
+        if word_like(prev) && word_like(next) || next == '#' {
+            s.push(' ');
+        }
+    }
+    s.push_str(code);
 }
 
 impl Value {
@@ -1611,6 +2133,113 @@ impl Value {
             Ok(s)
         }
     }
+    /// Get the optional header and rows of cells of a table
+    fn table_cells(&self, env: &Uiua) -> UiuaResult<(Option<TableRow>, Vec<TableRow>)> {
+        fn cell(value: Value) -> String {
+            match value.unboxed() {
+                Value::Char(arr) if arr.rank() <= 1 => arr.data.iter().collect(),
+                value => value.format(),
+            }
+        }
+        if self.is_map() {
+            let mut header = Vec::new();
+            let mut columns = Vec::new();
+            for (k, v) in self.map_kv() {
+                header.push(cell(k));
+                let v = v.unboxed();
+                columns.push(
+                    if v.rank() == 0 || matches!(&v, Value::Char(arr) if arr.rank() == 1) {
+                        vec![cell(v)]
+                    } else {
+                        v.into_rows().map(cell).collect()
+                    },
+                );
+            }
+            let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+            let rows = (0..height)
+                .map(|i| {
+                    (columns.iter())
+                        .map(|col| col.get(i).cloned().unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            return Ok((Some(header), rows));
+        }
+        let rows = match (self, self.rank()) {
+            (Value::Char(_), 0 | 1) | (_, 0) => vec![vec![cell(self.clone())]],
+            (Value::Char(_), 2) => self.rows().map(|row| vec![cell(row)]).collect(),
+            (_, 1) if self.row_count() == 0 => Vec::new(),
+            (_, 1) => vec![self.rows().map(cell).collect()],
+            (_, 2) => (self.rows())
+                .map(|row| row.into_rows().map(cell).collect())
+                .collect(),
+            (_, n) => return Err(env.error(format!("Cannot render a rank-{n} array as a table"))),
+        };
+        Ok((None, rows))
+    }
+    pub(crate) fn to_html_table(&self, env: &Uiua) -> UiuaResult<String> {
+        fn escape(cell: &str) -> String {
+            let mut escaped = String::with_capacity(cell.len());
+            for c in cell.chars() {
+                match c {
+                    '<' => escaped.push_str("&lt;"),
+                    '>' => escaped.push_str("&gt;"),
+                    '&' => escaped.push_str("&amp;"),
+                    '"' => escaped.push_str("&quot;"),
+                    '\n' => escaped.push_str("<br>"),
+                    c => escaped.push(c),
+                }
+            }
+            escaped
+        }
+        let (header, rows) = self.table_cells(env)?;
+        let mut html = String::from("<table>\n");
+        if let Some(header) = header {
+            html.push_str("<thead>\n<tr>");
+            for cell in header {
+                html.push_str(&format!("<th>{}</th>", escape(&cell)));
+            }
+            html.push_str("</tr>\n</thead>\n");
+        }
+        html.push_str("<tbody>\n");
+        for row in rows {
+            html.push_str("<tr>");
+            for cell in row {
+                html.push_str(&format!("<td>{}</td>", escape(&cell)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>");
+        Ok(html)
+    }
+    pub(crate) fn to_markdown_table(&self, env: &Uiua) -> UiuaResult<String> {
+        fn escape(cell: &str) -> String {
+            cell.replace('|', "\\|").replace('\n', "<br>")
+        }
+        let (header, rows) = self.table_cells(env)?;
+        let width = (header.iter().chain(&rows))
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let header = header.unwrap_or_else(|| vec![String::new(); width]);
+        let mut md = String::new();
+        let mut push_row = |row: &[String]| {
+            md.push('|');
+            for i in 0..width {
+                let cell = row.get(i).map(|cell| escape(cell)).unwrap_or_default();
+                md.push_str(&format!(" {cell} |"));
+            }
+            md.push('\n');
+        };
+        push_row(&header);
+        push_row(&vec!["---".into(); width]);
+        for row in &rows {
+            push_row(row);
+        }
+        md.pop();
+        Ok(md)
+    }
     pub(crate) fn to_xlsx(&self, env: &Uiua) -> UiuaResult<Vec<u8>> {
         #[cfg(not(feature = "simple_excel_writer"))]
         return Err(env.error("XLSX encoding is not enabled in this environment"));
@@ -1655,6 +2284,8 @@ impl Value {
                                 match cell {
                                     Value::Num(n) => sheet_row.add_cell(n.data[0]),
                                     Value::Byte(b) => sheet_row.add_cell(b.data[0] as f64),
+                                    Value::Int(i) => sheet_row.add_cell(i.data[0] as f64),
+                                    Value::Float(f) => sheet_row.add_cell(f.data[0] as f64),
                                     Value::Char(c) => sheet_row.add_cell(c.data[0].to_string()),
                                     Value::Complex(c) => sheet_row.add_cell(c.data[0].to_string()),
                                     Value::Box(b) => {
@@ -1796,6 +2427,8 @@ impl Value {
                         b.to_string()
                     }
                 }
+                Value::Int(arr) => arr.data[0].grid_string(false),
+                Value::Float(arr) => arr.data[0].grid_string(false),
                 Value::Complex(arr) => {
                     let c = arr.data[0];
                     if c == Complex::I {
@@ -0,0 +1,195 @@
+//! Ordinary differential equation integration
+
+use ecow::EcoVec;
+
+use crate::{Array, Function, Primitive, Shape, Uiua, UiuaResult};
+
+/// The relative tolerance of each step
+const RTOL: f64 = 1e-8;
+/// The absolute tolerance of each step
+const ATOL: f64 = 1e-10;
+/// The maximum number of steps before giving up
+const MAX_STEPS: usize = 1_000_000;
+
+// Dormand–Prince coefficients
+const C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const A: [&[f64]; 7] = [
+    &[],
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+/// The difference between the 5th and 4th order weights
+const E: [f64; 7] = [
+    35.0 / 384.0 - 5179.0 / 57600.0,
+    0.0,
+    500.0 / 1113.0 - 7571.0 / 16695.0,
+    125.0 / 192.0 - 393.0 / 640.0,
+    -2187.0 / 6784.0 + 92097.0 / 339200.0,
+    11.0 / 84.0 - 187.0 / 2100.0,
+    -1.0 / 40.0,
+];
+
+struct Derivative {
+    f: Function,
+    shape: Shape,
+    with_time: bool,
+}
+
+impl Derivative {
+    fn call(&self, t: f64, y: &[f64], env: &mut Uiua) -> UiuaResult<Vec<f64>> {
+        if self.with_time {
+            env.push(t);
+        }
+        env.push(Array::new(self.shape.clone(), EcoVec::from(y)));
+        env.call(self.f.clone())?;
+        let d = env.pop("derivative")?;
+        let d: Array<f64> = d.as_number_array(
+            env,
+            "Derivative must be an array of numbers",
+            |_| true,
+            |_| true,
+            |n| n,
+        )?;
+        if d.shape != self.shape {
+            return Err(env.error(format!(
+                "Derivative must have the same shape as the state, \
+                but their shapes are {} and {}",
+                d.shape, self.shape
+            )));
+        }
+        Ok(d.data.to_vec())
+    }
+}
+
+pub fn ode(env: &mut Uiua) -> UiuaResult {
+    let f = env.pop_function()?;
+    let sig = f.signature();
+    if sig.outputs != 1 || !(1..=2).contains(&sig.args) {
+        return Err(env.error(format!(
+            "{}'s function must have signature |1 or |2.1, but its signature is {sig}",
+            Primitive::Ode
+        )));
+    }
+    let y0 = env.pop(1)?;
+    let times = env.pop(2)?;
+    let times = times.as_nums(env, "Times must be a list of numbers")?;
+    let y0: Array<f64> = y0.as_number_array(
+        env,
+        "Initial state must be an array of numbers",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    if times.windows(2).any(|w| w[1] < w[0]) && times.windows(2).any(|w| w[1] > w[0]) {
+        return Err(env.error("Times must be sorted"));
+    }
+    if let Some(t) = times.iter().find(|t| !t.is_finite()) {
+        return Err(env.error(format!("Times must be finite, but one is {t}")));
+    }
+    let deriv = Derivative {
+        f,
+        shape: y0.shape.clone(),
+        with_time: sig.args == 2,
+    };
+    let mut shape = y0.shape.clone();
+    shape.insert(0, times.len());
+    let mut y = y0.data.to_vec();
+    let mut data = EcoVec::with_capacity(shape.elements());
+    if !times.is_empty() {
+        data.extend_from_slice(&y);
+    }
+    env.without_fill(|env| -> UiuaResult {
+        let mut k1 = None;
+        let mut h: f64 = 0.0;
+        let mut steps = 0;
+        for w in times.windows(2) {
+            let (mut t, end) = (w[0], w[1]);
+            if h == 0.0 || (end - t).signum() != h.signum() {
+                h = (end - t) / 100.0;
+            }
+            while t != end {
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return Err(env.error(format!(
+                        "{} took more than {MAX_STEPS} steps. The system may be stiff.",
+                        Primitive::Ode
+                    )));
+                }
+                // Land exactly on the next time point
+                let last = (t + h - end) * h.signum() >= 0.0;
+                let step = if last { end - t } else { h };
+                let k1v = match k1.take() {
+                    Some(k) => k,
+                    None => deriv.call(t, &y, env)?,
+                };
+                let mut ks = vec![k1v];
+                let mut y_next = vec![0.0; y.len()];
+                for i in 1..7 {
+                    for (j, yn) in y_next.iter_mut().enumerate() {
+                        *yn =
+                            y[j] + step * A[i].iter().zip(&ks).map(|(a, k)| a * k[j]).sum::<f64>();
+                    }
+                    ks.push(deriv.call(t + C[i] * step, &y_next, env)?);
+                }
+                // The 7th stage is evaluated at the 5th order solution, which is `y_next`
+                let mut err = 0.0;
+                for (j, yn) in y_next.iter().enumerate() {
+                    let e = step * E.iter().zip(&ks).map(|(e, k)| e * k[j]).sum::<f64>();
+                    let scale = ATOL + RTOL * y[j].abs().max(yn.abs());
+                    err += (e / scale).powi(2);
+                }
+                let err = (err / y.len().max(1) as f64).sqrt();
+                if !err.is_finite() {
+                    return Err(env.error(format!(
+                        "{} encountered a non-finite value at time {t}",
+                        Primitive::Ode
+                    )));
+                }
+                let factor = (0.9 * err.powf(-0.2)).clamp(0.2, 5.0);
+                if err <= 1.0 {
+                    t = if last { end } else { t + step };
+                    y = y_next;
+                    k1 = ks.pop();
+                    if !last {
+                        h *= factor;
+                    }
+                } else {
+                    k1 = Some(ks.swap_remove(0));
+                    h = step * factor;
+                    if (t + h) == t {
+                        return Err(env.error(format!(
+                            "{} step size became too small at time {t}",
+                            Primitive::Ode
+                        )));
+                    }
+                }
+            }
+            data.extend_from_slice(&y);
+        }
+        Ok(())
+    })?;
+    env.push(Array::new(shape, data));
+    Ok(())
+}
@@ -5,6 +5,7 @@ use std::{
     convert::Infallible,
     fmt::Display,
     marker::PhantomData,
+    mem::take,
     slice::{self, ChunksExact},
 };
 
@@ -13,7 +14,10 @@ use ecow::eco_vec;
 use crate::{array::*, Uiua, UiuaError, UiuaResult};
 use crate::{Complex, Shape};
 
-use super::{fill_array_shapes, FillContext};
+use super::{
+    broadcast_shape, fill_array_shapes, shape_mismatch_note, shape_prefixes_broadcast,
+    shape_prefixes_match, FillContext,
+};
 
 pub(crate) struct ArrayRef<'a, T> {
     shape: &'a [usize],
@@ -107,6 +111,14 @@ where
     F: PervasiveFn<A, B, Output = C> + Clone,
     F::Error: Into<UiuaError>,
 {
+    // Broadcast length-1 axes
+    if a_depth == 0 && b_depth == 0 && broadcasts(&a.shape, &b.shape, env) {
+        let shape = broadcast_shape(&a.shape, &b.shape);
+        let mut data = eco_vec![C::default(); shape.elements()];
+        bin_pervade_recursive((&a).into(), (&b).into(), data.make_mut(), env, f)
+            .map_err(Into::into)?;
+        return Ok(Array::new(shape, data));
+    }
     // Fast fixed cases
     if a_depth == 0 && b_depth == 0 && env.scalar_fill::<C>().is_err() {
         // A is fixed
@@ -119,9 +131,10 @@ where
                     .any(|(a, b)| a != b)
                 {
                     return Err(env.error(format!(
-                        "Shapes {} and {} do not match",
+                        "Shapes {} and {} do not match{}",
                         a.shape(),
-                        b.shape()
+                        b.shape(),
+                        shape_mismatch_note(a.shape(), b.shape(), !env.fill_is_set())
                     )));
                 }
                 let mut data = eco_vec![C::default(); b.element_count()];
@@ -155,9 +168,10 @@ where
                     .any(|(a, b)| a != b)
                 {
                     return Err(env.error(format!(
-                        "Shapes {} and {} do not match",
+                        "Shapes {} and {} do not match{}",
                         a.shape(),
-                        b.shape()
+                        b.shape(),
+                        shape_mismatch_note(a.shape(), b.shape(), !env.fill_is_set())
                     )));
                 }
                 let mut data = eco_vec![C::default(); a.element_count()];
@@ -222,9 +236,24 @@ where
                 bin_pervade_recursive(ArrayRef::new(&ash[1..], arow), b, crow, env, f.clone())?;
             }
         }
+        // A single row is stretched when broadcasting
+        (ash, bsh) if ash[0] == 1 && bsh[0] != 1 => {
+            let arow = ArrayRef::new(&ash[1..], a.data);
+            for (brow, crow) in b.rows().zip(c.chunks_exact_mut(c.len() / bsh[0])) {
+                let brow = ArrayRef::new(&bsh[1..], brow);
+                bin_pervade_recursive(arow, brow, crow, env, f.clone())?;
+            }
+        }
+        (ash, bsh) if ash[0] != 1 && bsh[0] == 1 => {
+            let brow = ArrayRef::new(&bsh[1..], b.data);
+            for (arow, crow) in a.rows().zip(c.chunks_exact_mut(c.len() / ash[0])) {
+                let arow = ArrayRef::new(&ash[1..], arow);
+                bin_pervade_recursive(arow, brow, crow, env, f.clone())?;
+            }
+        }
         (ash, bsh) => {
             for ((arow, brow), crow) in
-                (a.rows().zip(b.rows())).zip(c.chunks_exact_mut(a.row_len().max(b.row_len())))
+                (a.rows().zip(b.rows())).zip(c.chunks_exact_mut(c.len() / ash[0]))
             {
                 bin_pervade_recursive(
                     ArrayRef::new(&ash[1..], arow),
@@ -239,6 +268,13 @@ where
     Ok(())
 }
 
+/// Whether two arrays should be pervaded by [`Primitive::Broadcast`]ing them
+///
+/// [`Primitive::Broadcast`]: crate::Primitive::Broadcast
+fn broadcasts(a: &[usize], b: &[usize], env: &Uiua) -> bool {
+    env.broadcasting() && !shape_prefixes_match(a, b) && shape_prefixes_broadcast(a, b)
+}
+
 pub fn bin_pervade_mut<T>(
     mut a: Array<T>,
     b: &mut Array<T>,
@@ -250,6 +286,12 @@ pub fn bin_pervade_mut<T>(
 where
     T: ArrayValue + Copy,
 {
+    // Broadcasting may change the shape, so the result cannot be written in place
+    if a_depth == 0 && b_depth == 0 && broadcasts(&a.shape, &b.shape, env) {
+        let b_arr = take(b);
+        *b = bin_pervade(a, b_arr, 0, 0, env, InfalliblePervasiveFn::new(f))?;
+        return Ok(());
+    }
     // Fast case if A is fixed
     if a.row_count() == 1 && b.row_count() != 1 && env.scalar_fill::<T>().is_err() {
         let fix_count = a.shape.iter().take_while(|&&d| d == 1).count();
@@ -260,9 +302,10 @@ where
                 .any(|(a, b)| a != b)
             {
                 return Err(env.error(format!(
-                    "Shapes {} and {} do not match",
+                    "Shapes {} and {} do not match{}",
                     a.shape(),
-                    b.shape()
+                    b.shape(),
+                    shape_mismatch_note(a.shape(), b.shape(), !env.fill_is_set())
                 )));
             }
             let a_row_shape = &a.shape()[fix_count..];
@@ -439,9 +482,19 @@ pub mod not {
     pub fn num(a: f64) -> f64 {
         1.0 - a
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         num(a.into())
     }
+    pub fn int(a: i64, env: &Uiua) -> UiuaResult<i64> {
+        1i64.checked_sub(a)
+            .ok_or_else(|| env.error(format!("Cannot not {a} without overflow")))
+    }
+    pub fn wrapping_int(a: i64) -> i64 {
+        1i64.wrapping_sub(a)
+    }
     pub fn bool(a: u8) -> u8 {
         a ^ 1u8
     }
@@ -478,9 +531,18 @@ pub mod scalar_neg {
     pub fn num(a: f64) -> f64 {
         -a
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         -f64::from(a)
     }
+    pub fn int(a: i64, env: &Uiua) -> UiuaResult<i64> {
+        (a.checked_neg()).ok_or_else(|| env.error(format!("Cannot negate {a} without overflow")))
+    }
+    pub fn wrapping_int(a: i64) -> i64 {
+        a.wrapping_neg()
+    }
     pub fn char(a: char) -> char {
         toggle_char_case(a)
     }
@@ -496,9 +558,22 @@ pub mod scalar_abs {
     pub fn num(a: f64) -> f64 {
         a.abs()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> u8 {
         a
     }
+    pub fn int(a: i64, env: &Uiua) -> UiuaResult<i64> {
+        (a.checked_abs()).ok_or_else(|| {
+            env.error(format!(
+                "Cannot take the absolute value of {a} without overflow"
+            ))
+        })
+    }
+    pub fn wrapping_int(a: i64) -> i64 {
+        a.wrapping_abs()
+    }
     pub fn char(a: char) -> char {
         if a.is_lowercase() {
             let mut upper = a.to_uppercase();
@@ -540,9 +615,15 @@ pub mod sign {
             a.signum()
         }
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> u8 {
         (a > 0) as u8
     }
+    pub fn int(a: i64) -> i64 {
+        a.signum()
+    }
     pub fn char(a: char) -> f64 {
         character_sign(a)
     }
@@ -558,9 +639,15 @@ pub mod sqrt {
     pub fn num(a: f64) -> f64 {
         a.sqrt()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         f64::from(a).sqrt()
     }
+    pub fn int(a: i64) -> f64 {
+        (a as f64).sqrt()
+    }
     pub fn bool(a: u8) -> u8 {
         a
     }
@@ -576,9 +663,15 @@ pub mod sin {
     pub fn num(a: f64) -> f64 {
         a.sin()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         f64::from(a).sin()
     }
+    pub fn int(a: i64) -> f64 {
+        (a as f64).sin()
+    }
     pub fn com(a: Complex) -> Complex {
         a.sin()
     }
@@ -591,9 +684,15 @@ pub mod cos {
     pub fn num(a: f64) -> f64 {
         a.cos()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         f64::from(a).cos()
     }
+    pub fn int(a: i64) -> f64 {
+        (a as f64).cos()
+    }
     pub fn com(a: Complex) -> Complex {
         a.cos()
     }
@@ -606,9 +705,15 @@ pub mod asin {
     pub fn num(a: f64) -> f64 {
         a.asin()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> f64 {
         f64::from(a).asin()
     }
+    pub fn int(a: i64) -> f64 {
+        (a as f64).asin()
+    }
     pub fn com(a: Complex) -> Complex {
         a.asin()
     }
@@ -621,9 +726,15 @@ pub mod floor {
     pub fn num(a: f64) -> f64 {
         a.floor()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> u8 {
         a
     }
+    pub fn int(a: i64) -> i64 {
+        a
+    }
     pub fn com(a: Complex) -> Complex {
         a.floor()
     }
@@ -636,9 +747,15 @@ pub mod ceil {
     pub fn num(a: f64) -> f64 {
         a.ceil()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> u8 {
         a
     }
+    pub fn int(a: i64) -> i64 {
+        a
+    }
     pub fn com(a: Complex) -> Complex {
         a.ceil()
     }
@@ -651,9 +768,15 @@ pub mod round {
     pub fn num(a: f64) -> f64 {
         a.round()
     }
+    pub fn float(a: f32) -> f32 {
+        num(a.into()) as f32
+    }
     pub fn byte(a: u8) -> u8 {
         a
     }
+    pub fn int(a: i64) -> i64 {
+        a
+    }
     pub fn com(a: Complex) -> Complex {
         a.round()
     }
@@ -687,6 +810,12 @@ pub mod complex_im {
     pub fn byte(_a: u8) -> u8 {
         0
     }
+    pub fn int(_a: i64) -> i64 {
+        0
+    }
+    pub fn float(_a: f32) -> f32 {
+        0.0
+    }
     pub fn error<T: Display>(a: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot get the imaginary part of {a}"))
     }
@@ -717,6 +846,9 @@ macro_rules! eq_impl {
                         pub fn num_byte(a: f64, b: u8) -> u8 {
                 (f64::from(b).array_cmp(&a) $eq $ordering) as u8
             }
+            pub fn cross<A, B: ArrayCmp<A>>(a: A, b: B) -> u8 {
+                (b.array_cmp(&a) $eq $ordering) as u8
+            }
             pub fn generic<T: Ord>(a: T, b: T) -> u8 {
                 (b.cmp(&a) $eq $ordering).into()
             }
@@ -763,6 +895,9 @@ macro_rules! cmp_impl {
                         pub fn num_byte(a: f64, b: u8) -> u8 {
                 (f64::from(b).array_cmp(&a) $eq $ordering) as u8
             }
+            pub fn cross<A, B: ArrayCmp<A>>(a: A, b: B) -> u8 {
+                (b.array_cmp(&a) $eq $ordering) as u8
+            }
             pub fn generic<T: Ord>(a: T, b: T) -> u8 {
                 (b.cmp(&a) $eq $ordering).into()
             }
@@ -788,6 +923,9 @@ pub mod add {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b + a
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         f64::from(a) + f64::from(b)
     }
@@ -818,6 +956,13 @@ pub mod add {
     pub fn char_byte(a: char, b: u8) -> char {
         char::from_u32((b as i64 + a as i64) as u32).unwrap_or('\0')
     }
+    pub fn int_int(a: i64, b: i64, env: &Uiua) -> UiuaResult<i64> {
+        b.checked_add(a)
+            .ok_or_else(|| env.error(format!("Cannot add {a} and {b} without overflow")))
+    }
+    pub fn wrapping_int_int(a: i64, b: i64) -> i64 {
+        b.wrapping_add(a)
+    }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot add {a} and {b}"))
     }
@@ -828,6 +973,9 @@ pub mod sub {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b - a
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         f64::from(b) - f64::from(a)
     }
@@ -852,6 +1000,13 @@ pub mod sub {
     pub fn byte_char(a: u8, b: char) -> char {
         char::from_u32(((b as i64) - (a as i64)) as u32).unwrap_or('\0')
     }
+    pub fn int_int(a: i64, b: i64, env: &Uiua) -> UiuaResult<i64> {
+        b.checked_sub(a)
+            .ok_or_else(|| env.error(format!("Cannot subtract {a} from {b} without overflow")))
+    }
+    pub fn wrapping_int_int(a: i64, b: i64) -> i64 {
+        b.wrapping_sub(a)
+    }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot subtract {a} from {b}"))
     }
@@ -864,6 +1019,9 @@ macro_rules! bin_op_mod {
             pub fn num_num($a: f64, $b: f64) -> f64 {
                 $f
             }
+            pub fn float_float($a: f32, $b: f32) -> f32 {
+                num_num($a.into(), $b.into()) as f32
+            }
             pub fn byte_byte($a: u8, $b: u8) -> f64 {
                 let $a = $byte_convert($a);
                 let $b = $byte_convert($b);
@@ -899,6 +1057,9 @@ pub mod mul {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b * a
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         f64::from(b) * f64::from(a)
     }
@@ -937,6 +1098,13 @@ pub mod mul {
     pub fn x_com(a: impl Into<Complex>, b: Complex) -> Complex {
         b * a.into()
     }
+    pub fn int_int(a: i64, b: i64, env: &Uiua) -> UiuaResult<i64> {
+        b.checked_mul(a)
+            .ok_or_else(|| env.error(format!("Cannot multiply {a} and {b} without overflow")))
+    }
+    pub fn wrapping_int_int(a: i64, b: i64) -> i64 {
+        b.wrapping_mul(a)
+    }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot multiply {a} and {b}"))
     }
@@ -947,6 +1115,9 @@ pub mod div {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b / a
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         f64::from(b) / f64::from(a)
     }
@@ -982,6 +1153,9 @@ pub mod modulus {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b.rem_euclid(a)
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         num_num(a.into(), b.into())
     }
@@ -997,8 +1171,19 @@ pub mod modulus {
     pub fn com_x(a: Complex, b: impl Into<Complex>) -> Complex {
         b.into() % a
     }
-    pub fn x_com(a: impl Into<f64>, b: Complex) -> Complex {
-        b % a.into()
+    pub fn x_com(a: impl RealArrayValue, b: Complex) -> Complex {
+        b % a.to_f64()
+    }
+    pub fn int_int(a: i64, b: i64, env: &Uiua) -> UiuaResult<i64> {
+        match a {
+            0 => Err(env.error(format!("Cannot modulo integer {b} by 0"))),
+            -1 => Ok(0),
+            _ => Ok(b.rem_euclid(a)),
+        }
+    }
+    /// Whether integers must be converted to numbers, because modulo 0 is NaN
+    pub fn zero_divisor<T: RealArrayValue>(a: &Array<T>) -> bool {
+        a.data.iter().any(|a| a.to_f64() == 0.0)
     }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot modulo {a} and {b}"))
@@ -1018,6 +1203,9 @@ pub mod pow {
     pub fn num_num(a: f64, b: f64) -> f64 {
         b.powf(a)
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> f64 {
         f64::from(b).powf(f64::from(a))
     }
@@ -1077,6 +1265,9 @@ pub mod max {
     pub fn num_num(a: f64, b: f64) -> f64 {
         a.max(b)
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> u8 {
         a.max(b)
     }
@@ -1098,6 +1289,9 @@ pub mod max {
     pub fn x_com(a: impl Into<Complex>, b: Complex) -> Complex {
         a.into().max(b)
     }
+    pub fn int_int(a: i64, b: i64, _env: &Uiua) -> UiuaResult<i64> {
+        Ok(a.max(b))
+    }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot get the max of {a} and {b}"))
     }
@@ -1108,6 +1302,9 @@ pub mod min {
     pub fn num_num(a: f64, b: f64) -> f64 {
         a.min(b)
     }
+    pub fn float_float(a: f32, b: f32) -> f32 {
+        num_num(a.into(), b.into()) as f32
+    }
     pub fn byte_byte(a: u8, b: u8) -> u8 {
         a.min(b)
     }
@@ -1129,6 +1326,9 @@ pub mod min {
     pub fn x_com(a: impl Into<Complex>, b: Complex) -> Complex {
         a.into().min(b)
     }
+    pub fn int_int(a: i64, b: i64, _env: &Uiua) -> UiuaResult<i64> {
+        Ok(a.min(b))
+    }
     pub fn error<T: Display>(a: T, b: T, env: &Uiua) -> UiuaError {
         env.error(format!("Cannot get the min of {a} and {b}"))
     }
@@ -1244,9 +1444,10 @@ fn bin_pervade_recursive_generic<A: PervasiveInput, B: PervasiveInput, C>(
             let b_cells = b_shape[0];
             if a_cells != b_cells {
                 return Err(env.error(format!(
-                    "Shapes {} and {} do not match",
+                    "Shapes {} and {} do not match{}",
                     FormatShape(a_shape),
-                    FormatShape(b_shape)
+                    FormatShape(b_shape),
+                    shape_mismatch_note(a_shape, b_shape, !env.fill_is_set())
                 )));
             }
             let a_chunk_size = a.len() / a_cells;
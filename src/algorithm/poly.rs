@@ -0,0 +1,517 @@
+//! Polynomial algorithms
+//!
+//! Polynomials are lists of coefficients in ascending order of degree, so the coefficient at index `i` multiplies `xⁱ`.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use ecow::EcoVec;
+
+use crate::{Array, Complex, Uiua, UiuaResult, Value};
+
+trait Coef:
+    Copy
+    + Default
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+}
+
+impl Coef for f64 {}
+impl Coef for Complex {}
+
+enum Poly {
+    Real(Vec<f64>),
+    Complex(Vec<Complex>),
+}
+
+impl Poly {
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        match val {
+            Value::Complex(arr) if arr.rank() <= 1 => Ok(Poly::Complex(arr.data.to_vec())),
+            Value::Complex(arr) => Err(env.error(format!(
+                "Polynomial must be a list of coefficients, but its shape is {}",
+                arr.shape
+            ))),
+            val => Ok(Poly::Real(val.as_nums(
+                env,
+                "Polynomial must be a list of numeric coefficients",
+            )?)),
+        }
+    }
+    fn into_complex(self) -> Vec<Complex> {
+        match self {
+            Poly::Real(p) => p.into_iter().map(Complex::from).collect(),
+            Poly::Complex(p) => p,
+        }
+    }
+}
+
+/// Remove zero coefficients of the highest degrees
+fn trim<T: Coef>(mut p: Vec<T>) -> Vec<T> {
+    while p.len() > 1 && p.last() == Some(&T::default()) {
+        p.pop();
+    }
+    p
+}
+
+fn eval<T: Coef>(p: &[T], x: T) -> T {
+    p.iter().rev().fold(T::default(), |acc, &c| acc * x + c)
+}
+
+fn mul<T: Coef>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut prod = vec![T::default(); a.len() + b.len() - 1];
+    for (i, &a) in a.iter().enumerate() {
+        for (j, &b) in b.iter().enumerate() {
+            prod[i + j] = prod[i + j] + a * b;
+        }
+    }
+    prod
+}
+
+/// Divide `num` by `den`, returning the quotient and remainder
+fn div<T: Coef>(num: &[T], den: &[T], env: &Uiua) -> UiuaResult<(Vec<T>, Vec<T>)> {
+    let den = trim(den.to_vec());
+    let lead = *den.last().unwrap_or(&T::default());
+    if lead == T::default() {
+        return Err(env.error("Cannot divide by the zero polynomial"));
+    }
+    let mut rem = num.to_vec();
+    if rem.len() < den.len() {
+        return Ok((vec![T::default()], trim(rem)));
+    }
+    let mut quot = vec![T::default(); rem.len() - den.len() + 1];
+    for i in (0..quot.len()).rev() {
+        let q = rem[i + den.len() - 1] / lead;
+        quot[i] = q;
+        for (j, &d) in den.iter().enumerate() {
+            rem[i + j] = rem[i + j] - q * d;
+        }
+    }
+    rem.truncate(den.len() - 1);
+    if rem.is_empty() {
+        rem.push(T::default());
+    }
+    Ok((quot, trim(rem)))
+}
+
+pub fn polyval(env: &mut Uiua) -> UiuaResult {
+    let p = Poly::from_value(&env.pop(1)?, env)?;
+    let xs = env.pop(2)?;
+    let val: Value = match (p, xs) {
+        (Poly::Real(p), xs) if !matches!(xs, Value::Complex(_)) => {
+            let mut xs: Array<f64> = xs.as_number_array(
+                env,
+                "Polynomial can only be evaluated at numbers",
+                |_| true,
+                |_| true,
+                |n| n,
+            )?;
+            for x in xs.data.as_mut_slice() {
+                *x = eval(&p, *x);
+            }
+            xs.into()
+        }
+        (p, xs) => {
+            let p = p.into_complex();
+            let mut xs = match xs {
+                Value::Complex(arr) => arr,
+                xs => {
+                    let arr: Array<f64> = xs.as_number_array(
+                        env,
+                        "Polynomial can only be evaluated at numbers",
+                        |_| true,
+                        |_| true,
+                        |n| n,
+                    )?;
+                    let data: EcoVec<Complex> = arr.data.iter().map(|&x| x.into()).collect();
+                    Array::new(arr.shape, data)
+                }
+            };
+            for x in xs.data.as_mut_slice() {
+                *x = eval(&p, *x);
+            }
+            xs.into()
+        }
+    };
+    env.push(val);
+    Ok(())
+}
+
+pub fn polymul(env: &mut Uiua) -> UiuaResult {
+    let a = Poly::from_value(&env.pop(1)?, env)?;
+    let b = Poly::from_value(&env.pop(2)?, env)?;
+    let val: Value = match (a, b) {
+        (Poly::Real(a), Poly::Real(b)) => Array::from_iter(mul(&a, &b)).into(),
+        (a, b) => Array::from_iter(mul(&a.into_complex(), &b.into_complex())).into(),
+    };
+    env.push(val);
+    Ok(())
+}
+
+pub fn polydiv(env: &mut Uiua) -> UiuaResult {
+    let den = Poly::from_value(&env.pop(1)?, env)?;
+    let num = Poly::from_value(&env.pop(2)?, env)?;
+    let (quot, rem): (Value, Value) = match (den, num) {
+        (Poly::Real(den), Poly::Real(num)) => {
+            let (q, r) = div(&num, &den, env)?;
+            (Array::from_iter(q).into(), Array::from_iter(r).into())
+        }
+        (den, num) => {
+            let (q, r) = div(&num.into_complex(), &den.into_complex(), env)?;
+            (Array::from_iter(q).into(), Array::from_iter(r).into())
+        }
+    };
+    env.push(rem);
+    env.push(quot);
+    Ok(())
+}
+
+pub fn polyroots(env: &mut Uiua) -> UiuaResult {
+    let p = match Poly::from_value(&env.pop(1)?, env)? {
+        Poly::Real(p) => trim(p),
+        Poly::Complex(_) => {
+            return Err(env.error("Only roots of polynomials with real coefficients can be found"))
+        }
+    };
+    if p.iter().any(|c| !c.is_finite()) {
+        return Err(env.error("Polynomial coefficients must be finite"));
+    }
+    if p.iter().all(|&c| c == 0.0) {
+        return Err(env.error("Every number is a root of the zero polynomial"));
+    }
+    // Roots at zero
+    let zeros = p.iter().take_while(|&&c| c == 0.0).count().min(p.len() - 1);
+    let reduced = &p[zeros..];
+    let mut roots = vec![Complex::ZERO; zeros];
+    if reduced.len() > 1 {
+        let found = companion_eigenvalues(reduced)
+            .ok_or_else(|| env.error("Polynomial roots did not converge"))?;
+        let cp: Vec<Complex> = reduced.iter().map(|&c| c.into()).collect();
+        roots.extend(found.into_iter().map(|root| polish(&cp, root)));
+    }
+    roots.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+    let val: Value = if roots.iter().all(|r| r.im == 0.0) {
+        Array::from_iter(roots.into_iter().map(|r| r.re)).into()
+    } else {
+        Array::from_iter(roots).into()
+    };
+    env.push(val);
+    Ok(())
+}
+
+/// Refine a root with Newton's method as long as it improves
+fn polish(p: &[Complex], mut root: Complex) -> Complex {
+    let dp: Vec<Complex> = (p.iter().enumerate().skip(1))
+        .map(|(i, &c)| c * i as f64)
+        .collect();
+    let mut value = eval(p, root).abs();
+    for _ in 0..3 {
+        let d = eval(&dp, root);
+        if d.abs() == 0.0 {
+            break;
+        }
+        let next = root - eval(p, root) / d;
+        let next_value = eval(p, next).abs();
+        if next_value.is_nan() || next_value >= value {
+            break;
+        }
+        // Keep real roots real
+        root = if root.im == 0.0 {
+            Complex::new(next.re, 0.0)
+        } else {
+            next
+        };
+        value = next_value;
+    }
+    root
+}
+
+/// Find the eigenvalues of a polynomial's companion matrix
+///
+/// This uses balancing followed by the shifted QR algorithm for Hessenberg matrices.
+fn companion_eigenvalues(p: &[f64]) -> Option<Vec<Complex>> {
+    let n = p.len() - 1;
+    let lead = p[n];
+    // The matrix is 1-indexed to keep the algorithm readable
+    let w = n + 1;
+    let mut m = vec![0.0; w * w];
+    macro_rules! a {
+        ($i:expr, $j:expr) => {
+            m[($i) * w + ($j)]
+        };
+    }
+    for j in 1..=n {
+        a!(1, j) = -p[n - j] / lead;
+    }
+    for i in 2..=n {
+        a!(i, i - 1) = 1.0;
+    }
+    // Balance
+    const RADIX: f64 = 2.0;
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 1..=n {
+            let (mut c, mut r) = (0.0, 0.0);
+            for j in (1..=n).filter(|&j| j != i) {
+                c += f64::abs(a!(j, i));
+                r += f64::abs(a!(i, j));
+            }
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+            let s = c + r;
+            let mut f = 1.0;
+            let mut g = r / RADIX;
+            while c < g {
+                f *= RADIX;
+                c *= RADIX * RADIX;
+            }
+            g = r * RADIX;
+            while c > g {
+                f /= RADIX;
+                c /= RADIX * RADIX;
+            }
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                for j in 1..=n {
+                    a!(i, j) /= f;
+                    a!(j, i) *= f;
+                }
+            }
+        }
+    }
+    // Shifted QR
+    let sign = |a: f64, b: f64| if b >= 0.0 { a.abs() } else { -a.abs() };
+    let mut wr = vec![0.0; w];
+    let mut wi = vec![0.0; w];
+    let mut anorm = 0.0;
+    for i in 1..=n {
+        for j in i.saturating_sub(1).max(1)..=n {
+            anorm += f64::abs(a!(i, j));
+        }
+    }
+    let mut nn = n;
+    let mut t = 0.0;
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = f64::abs(a!(l - 1, l - 1)) + f64::abs(a!(l, l));
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if f64::abs(a!(l, l - 1)) + s == s {
+                    a!(l, l - 1) = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+            let mut x = a!(nn, nn);
+            if l == nn {
+                wr[nn] = x + t;
+                wi[nn] = 0.0;
+                nn -= 1;
+            } else {
+                let mut y = a!(nn - 1, nn - 1);
+                let mut ww = a!(nn, nn - 1) * a!(nn - 1, nn);
+                if l == nn - 1 {
+                    let p = 0.5 * (y - x);
+                    let q = p * p + ww;
+                    let mut z = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        z = p + sign(z, p);
+                        wr[nn - 1] = x + z;
+                        wr[nn] = if z != 0.0 { x - ww / z } else { x + z };
+                        wi[nn - 1] = 0.0;
+                        wi[nn] = 0.0;
+                    } else {
+                        wr[nn - 1] = x + p;
+                        wr[nn] = x + p;
+                        wi[nn - 1] = -z;
+                        wi[nn] = z;
+                    }
+                    nn -= 2;
+                } else {
+                    if its == 30 {
+                        return None;
+                    }
+                    if its == 10 || its == 20 {
+                        // Exceptional shift
+                        t += x;
+                        for i in 1..=nn {
+                            a!(i, i) -= x;
+                        }
+                        let s = f64::abs(a!(nn, nn - 1)) + f64::abs(a!(nn - 1, nn - 2));
+                        x = 0.75 * s;
+                        y = x;
+                        ww = -0.4375 * s * s;
+                    }
+                    its += 1;
+                    let (mut p, mut q, mut r, mut z);
+                    let mut mm = nn - 2;
+                    loop {
+                        z = a!(mm, mm);
+                        r = x - z;
+                        let s = y - z;
+                        p = (r * s - ww) / a!(mm + 1, mm) + a!(mm, mm + 1);
+                        q = a!(mm + 1, mm + 1) - z - r - s;
+                        r = a!(mm + 2, mm + 1);
+                        let s = p.abs() + q.abs() + r.abs();
+                        p /= s;
+                        q /= s;
+                        r /= s;
+                        if mm == l {
+                            break;
+                        }
+                        let u = f64::abs(a!(mm, mm - 1)) * (q.abs() + r.abs());
+                        let v = p.abs()
+                            * (f64::abs(a!(mm - 1, mm - 1))
+                                + z.abs()
+                                + f64::abs(a!(mm + 1, mm + 1)));
+                        if u + v == v {
+                            break;
+                        }
+                        mm -= 1;
+                    }
+                    for i in mm + 2..=nn {
+                        a!(i, i - 2) = 0.0;
+                        if i != mm + 2 {
+                            a!(i, i - 3) = 0.0;
+                        }
+                    }
+                    for k in mm..nn {
+                        if k != mm {
+                            p = a!(k, k - 1);
+                            q = a!(k + 1, k - 1);
+                            r = if k != nn - 1 { a!(k + 2, k - 1) } else { 0.0 };
+                            x = p.abs() + q.abs() + r.abs();
+                            if x != 0.0 {
+                                p /= x;
+                                q /= x;
+                                r /= x;
+                            }
+                        }
+                        let s = sign((p * p + q * q + r * r).sqrt(), p);
+                        if s == 0.0 {
+                            continue;
+                        }
+                        if k == mm {
+                            if l != mm {
+                                a!(k, k - 1) = -a!(k, k - 1);
+                            }
+                        } else {
+                            a!(k, k - 1) = -s * x;
+                        }
+                        p += s;
+                        x = p / s;
+                        y = q / s;
+                        z = r / s;
+                        q /= p;
+                        r /= p;
+                        for j in k..=nn {
+                            let mut p = a!(k, j) + q * a!(k + 1, j);
+                            if k != nn - 1 {
+                                p += r * a!(k + 2, j);
+                                a!(k + 2, j) -= p * z;
+                            }
+                            a!(k + 1, j) -= p * y;
+                            a!(k, j) -= p * x;
+                        }
+                        for i in l..=nn.min(k + 3) {
+                            let mut p = x * a!(i, k) + y * a!(i, k + 1);
+                            if k != nn - 1 {
+                                p += z * a!(i, k + 2);
+                                a!(i, k + 2) -= p * r;
+                            }
+                            a!(i, k + 1) -= p * q;
+                            a!(i, k) -= p;
+                        }
+                    }
+                }
+            }
+            if nn < 2 || l + 1 >= nn {
+                break;
+            }
+        }
+    }
+    Some((1..=n).map(|i| Complex::new(wr[i], wi[i])).collect())
+}
+
+pub fn polyfit(env: &mut Uiua) -> UiuaResult {
+    let degree = env.pop(1)?.as_nat(env, "Degree must be a natural number")?;
+    let xs = env
+        .pop(2)?
+        .as_nums(env, "X values must be a list of numbers")?;
+    let ys = env
+        .pop(3)?
+        .as_nums(env, "Y values must be a list of numbers")?;
+    if xs.len() != ys.len() {
+        return Err(env.error(format!(
+            "X and Y values must have the same length, but their lengths are {} and {}",
+            xs.len(),
+            ys.len()
+        )));
+    }
+    let cols = degree + 1;
+    let rows = xs.len();
+    if rows < cols {
+        return Err(env.error(format!(
+            "Fitting a polynomial of degree {degree} requires at least {cols} points, \
+            but there {} only {rows}",
+            if rows == 1 { "is" } else { "are" }
+        )));
+    }
+    // Least squares with Householder QR of the Vandermonde matrix
+    let mut a: Vec<Vec<f64>> = (0..cols)
+        .map(|j| xs.iter().map(|&x| x.powi(j as i32)).collect())
+        .collect();
+    let mut b = ys;
+    for k in 0..cols {
+        let norm = a[k][k..].iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return Err(
+                env.error("Cannot fit a polynomial because there are not enough distinct X values")
+            );
+        }
+        let alpha = if a[k][k] > 0.0 { -norm } else { norm };
+        let mut v: Vec<f64> = a[k][k..].to_vec();
+        v[0] -= alpha;
+        let vnorm2: f64 = v.iter().map(|x| x * x).sum();
+        if vnorm2 == 0.0 {
+            continue;
+        }
+        let reflect = |col: &mut [f64]| {
+            let dot: f64 = v.iter().zip(&col[k..]).map(|(v, c)| v * c).sum();
+            let f = 2.0 * dot / vnorm2;
+            for (c, v) in col[k..].iter_mut().zip(&v) {
+                *c -= f * v;
+            }
+        };
+        for col in a.iter_mut().skip(k) {
+            reflect(col);
+        }
+        reflect(&mut b);
+    }
+    let mut coefs = vec![0.0; cols];
+    for i in (0..cols).rev() {
+        let sum: f64 = (i + 1..cols).map(|j| a[j][i] * coefs[j]).sum();
+        let diag = a[i][i];
+        if diag.abs() <= 1e-12 * a[0][0].abs() {
+            return Err(
+                env.error("Cannot fit a polynomial because there are not enough distinct X values")
+            );
+        }
+        coefs[i] = (b[i] - sum) / diag;
+    }
+    env.push(Array::from_iter(coefs));
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! A pool of reusable scratch buffers for algorithms
+//!
+//! Some hot loops need a temporary buffer for every row they process.
+//! Taking those buffers from this pool rather than allocating them keeps
+//! the loops from hitting the global allocator every time.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    mem::size_of,
+};
+
+/// The maximum number of idle buffers kept for each element type
+const MAX_BUFFERS: usize = 8;
+/// The maximum size in bytes of a buffer that will be returned to the pool
+///
+/// Larger buffers are freed so that one big operation does not hold onto its memory.
+const MAX_BUFFER_BYTES: usize = 1 << 20;
+
+thread_local! {
+    static POOL: RefCell<HashMap<TypeId, Vec<Box<dyn Any>>>> = RefCell::new(HashMap::new());
+}
+
+/// Call a function with a temporary buffer of `len` copies of `init`
+///
+/// Each thread has its own pool, so this can be used inside parallel iterators.
+pub(crate) fn with_buffer<T: Clone + 'static, R>(
+    len: usize,
+    init: T,
+    f: impl FnOnce(&mut [T]) -> R,
+) -> R {
+    let mut buffer: Box<Vec<T>> = POOL
+        .with(|pool| pool.borrow_mut().get_mut(&TypeId::of::<T>())?.pop())
+        .and_then(|buffer| buffer.downcast().ok())
+        .unwrap_or_default();
+    buffer.resize(len, init);
+    // The pool is not borrowed while the function runs, so it may take buffers of its own
+    let res = f(&mut buffer);
+    // Pooled buffers are kept empty so that they do not keep their elements alive
+    buffer.clear();
+    if buffer.capacity() * size_of::<T>() <= MAX_BUFFER_BYTES {
+        POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let buffers = pool.entry(TypeId::of::<T>()).or_default();
+            if buffers.len() < MAX_BUFFERS {
+                buffers.push(buffer);
+            }
+        });
+    }
+    res
+}
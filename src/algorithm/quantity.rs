@@ -0,0 +1,375 @@
+//! Physical quantities
+//!
+//! A quantity is a number array whose metadata carries a [`Unit`].
+//! Values are always stored in SI base units, so units only need to be checked, not converted, during arithmetic.
+
+use std::fmt;
+
+use serde::*;
+
+use crate::{Array, Uiua, UiuaResult, Value};
+
+/// The SI base units, in the order their exponents are stored
+const BASE_UNITS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+/// The order in which base units are displayed
+const DISPLAY_ORDER: [usize; 7] = [1, 0, 2, 3, 4, 5, 6];
+
+/// The dimensions of a quantity as exponents of the SI base units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Unit([i8; 7]);
+
+const fn dims(m: i8, kg: i8, s: i8, a: i8) -> Unit {
+    Unit([m, kg, s, a, 0, 0, 0])
+}
+
+/// Named units as `(name, scale, dimensions, prefixable)`
+static NAMED_UNITS: &[(&str, f64, Unit, bool)] = &[
+    ("m", 1.0, dims(1, 0, 0, 0), true),
+    ("g", 1e-3, dims(0, 1, 0, 0), true),
+    ("s", 1.0, dims(0, 0, 1, 0), true),
+    ("A", 1.0, dims(0, 0, 0, 1), true),
+    ("K", 1.0, Unit([0, 0, 0, 0, 1, 0, 0]), true),
+    ("mol", 1.0, Unit([0, 0, 0, 0, 0, 1, 0]), true),
+    ("cd", 1.0, Unit([0, 0, 0, 0, 0, 0, 1]), true),
+    ("Hz", 1.0, dims(0, 0, -1, 0), true),
+    ("N", 1.0, dims(1, 1, -2, 0), true),
+    ("Pa", 1.0, dims(-1, 1, -2, 0), true),
+    ("J", 1.0, dims(2, 1, -2, 0), true),
+    ("W", 1.0, dims(2, 1, -3, 0), true),
+    ("C", 1.0, dims(0, 0, 1, 1), true),
+    ("V", 1.0, dims(2, 1, -3, -1), true),
+    ("Ω", 1.0, dims(2, 1, -3, -2), true),
+    ("ohm", 1.0, dims(2, 1, -3, -2), true),
+    ("L", 1e-3, dims(3, 0, 0, 0), true),
+    ("eV", 1.602_176_634e-19, dims(2, 1, -2, 0), true),
+    ("min", 60.0, dims(0, 0, 1, 0), false),
+    ("h", 3600.0, dims(0, 0, 1, 0), false),
+    ("day", 86400.0, dims(0, 0, 1, 0), false),
+    ("t", 1000.0, dims(0, 1, 0, 0), false),
+    ("in", 0.0254, dims(1, 0, 0, 0), false),
+    ("ft", 0.3048, dims(1, 0, 0, 0), false),
+    ("yd", 0.9144, dims(1, 0, 0, 0), false),
+    ("mi", 1609.344, dims(1, 0, 0, 0), false),
+    ("lb", 0.453_592_37, dims(0, 1, 0, 0), false),
+];
+
+static PREFIXES: &[(char, f64)] = &[
+    ('T', 1e12),
+    ('G', 1e9),
+    ('M', 1e6),
+    ('k', 1e3),
+    ('c', 1e-2),
+    ('m', 1e-3),
+    ('µ', 1e-6),
+    ('u', 1e-6),
+    ('n', 1e-9),
+    ('p', 1e-12),
+];
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+impl Unit {
+    /// Check if the unit has no dimensions
+    pub fn is_dimensionless(&self) -> bool {
+        self.0 == [0; 7]
+    }
+    fn zip(self, other: Self, f: impl Fn(i8, i8) -> i8) -> Self {
+        Unit(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+    fn mul(self, other: Self) -> Self {
+        self.zip(other, i8::saturating_add)
+    }
+    fn div(self, other: Self) -> Self {
+        self.zip(other, i8::saturating_sub)
+    }
+    fn powf(self, n: f64) -> Option<Self> {
+        let mut exps = [0; 7];
+        for (e, &base) in exps.iter_mut().zip(&self.0) {
+            let p = f64::from(base) * n;
+            if p.fract() != 0.0 || p.abs() > f64::from(i8::MAX) {
+                return None;
+            }
+            *e = p as i8;
+        }
+        Some(Unit(exps))
+    }
+    /// Parse a unit string into a scale to SI base units and dimensions
+    pub fn parse(s: &str) -> Result<(f64, Self), String> {
+        let mut scale = 1.0;
+        let mut unit = Unit::default();
+        let mut chars = s.trim().chars().peekable();
+        let mut divide = false;
+        while let Some(&c) = chars.peek() {
+            match c {
+                ' ' | '*' | '·' | '⋅' => {
+                    chars.next();
+                    continue;
+                }
+                '/' => {
+                    chars.next();
+                    divide = true;
+                    continue;
+                }
+                '1' => {
+                    chars.next();
+                    continue;
+                }
+                _ => {}
+            }
+            let mut name = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphabetic()) {
+                name.push(c);
+                chars.next();
+            }
+            if name.is_empty() {
+                return Err(format!("Unexpected {c:?} in unit {s:?}"));
+            }
+            let mut exp = String::new();
+            if chars.next_if_eq(&'^').is_some() {
+                if let Some(c) = chars.next_if(|&c| c == '-' || c == '¯') {
+                    exp.push(if c == '¯' { '-' } else { c });
+                }
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    exp.push(c);
+                }
+            } else {
+                if chars.next_if_eq(&'⁻').is_some() {
+                    exp.push('-');
+                }
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_digit() || SUPERSCRIPTS.contains(c))
+                {
+                    let d = SUPERSCRIPTS.iter().position(|&s| s == c);
+                    exp.push(d.map_or(c, |d| (b'0' + d as u8) as char));
+                }
+            }
+            let exp: i8 = if exp.is_empty() {
+                1
+            } else {
+                exp.parse()
+                    .map_err(|_| format!("Invalid exponent for {name} in unit {s:?}"))?
+            };
+            let exp = if divide { -exp } else { exp };
+            divide = false;
+            let (factor, dims) =
+                lookup(&name).ok_or_else(|| format!("Unknown unit {name:?} in {s:?}"))?;
+            scale *= factor.powi(exp.into());
+            unit = unit.mul(dims.powf(exp.into()).unwrap());
+        }
+        if divide {
+            return Err(format!("Unit {s:?} cannot end with a /"));
+        }
+        Ok((scale, unit))
+    }
+}
+
+fn lookup(name: &str) -> Option<(f64, Unit)> {
+    let named = |name: &str| NAMED_UNITS.iter().find(|(n, ..)| *n == name);
+    if let Some(&(_, scale, unit, _)) = named(name) {
+        return Some((scale, unit));
+    }
+    let mut chars = name.chars();
+    let prefix = chars.next()?;
+    let &(_, factor) = PREFIXES.iter().find(|(p, _)| *p == prefix)?;
+    let &(_, scale, unit, prefixable) = named(chars.as_str())?;
+    prefixable.then_some((factor * scale, unit))
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "1");
+        }
+        let write_exp = |f: &mut fmt::Formatter, exp: i8| -> fmt::Result {
+            if exp < 0 {
+                write!(f, "⁻")?;
+            }
+            if exp != 1 {
+                for d in exp.unsigned_abs().to_string().bytes() {
+                    write!(f, "{}", SUPERSCRIPTS[(d - b'0') as usize])?;
+                }
+            }
+            Ok(())
+        };
+        let numer = DISPLAY_ORDER.iter().filter(|&&i| self.0[i] > 0);
+        let denom = DISPLAY_ORDER.iter().filter(|&&i| self.0[i] < 0);
+        let has_numer = numer.clone().next().is_some();
+        for (j, &i) in numer.enumerate() {
+            if j > 0 {
+                write!(f, "·")?;
+            }
+            write!(f, "{}", BASE_UNITS[i])?;
+            write_exp(f, self.0[i])?;
+        }
+        for (j, &i) in denom.enumerate() {
+            if has_numer {
+                write!(f, "{}", if j == 0 { "/" } else { "·" })?;
+                write!(f, "{}", BASE_UNITS[i])?;
+                write_exp(f, -self.0[i])?;
+            } else {
+                if j > 0 {
+                    write!(f, "·")?;
+                }
+                write!(f, "{}", BASE_UNITS[i])?;
+                write_exp(f, self.0[i])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe(unit: Option<Unit>) -> String {
+    match unit {
+        Some(unit) => unit.to_string(),
+        None => "no unit".into(),
+    }
+}
+
+/// Set the unit of a value, removing it if it is dimensionless
+pub(crate) fn set_unit(val: &mut Value, unit: Option<Unit>) {
+    match unit.filter(|unit| !unit.is_dimensionless()) {
+        Some(unit) => val.meta_mut().unit = Some(unit),
+        None => {
+            if let Some(meta) = val.get_meta_mut() {
+                meta.unit = None;
+            }
+        }
+    }
+}
+
+/// Get the unit of the result of a monadic pervasive operation
+pub(crate) fn monadic_unit(name: &str, unit: Unit, env: &Uiua) -> UiuaResult<Option<Unit>> {
+    Ok(match name {
+        "scalar_neg" | "scalar_abs" | "floor" | "ceil" | "round" | "complex_re" | "complex_im" => {
+            Some(unit)
+        }
+        "sign" => None,
+        "sqrt" => Some(unit.powf(0.5).ok_or_else(|| {
+            env.error(format!(
+                "Cannot take the square root of a quantity in {unit}"
+            ))
+        })?),
+        _ => {
+            return Err(env.error(format!(
+                "Cannot take the {} of a quantity in {unit}",
+                name.trim_start_matches("scalar_")
+            )))
+        }
+    })
+}
+
+/// Get the unit of the result of a dyadic pervasive operation
+///
+/// `a` is the first argument, so `÷` divides `b` by `a`.
+pub(crate) fn dyadic_unit(
+    name: &str,
+    a: &Value,
+    a_unit: Option<Unit>,
+    b_unit: Option<Unit>,
+    env: &Uiua,
+) -> UiuaResult<Option<Unit>> {
+    let verb = match name {
+        "mul" => {
+            return Ok(Some(
+                b_unit.unwrap_or_default().mul(a_unit.unwrap_or_default()),
+            ))
+        }
+        "div" => {
+            return Ok(Some(
+                b_unit.unwrap_or_default().div(a_unit.unwrap_or_default()),
+            ))
+        }
+        "pow" => {
+            let Some(b_unit) = b_unit else {
+                return Err(env.error(format!(
+                    "Cannot raise a number to a power in {}",
+                    describe(a_unit)
+                )));
+            };
+            if a_unit.is_some() {
+                return Err(env.error(format!(
+                    "Cannot raise a quantity in {b_unit} to a power in {}",
+                    describe(a_unit)
+                )));
+            }
+            let n = a.as_num(env, "Quantities can only be raised to a scalar power")?;
+            return b_unit.powf(n).map(Some).ok_or_else(|| {
+                env.error(format!(
+                    "Cannot raise a quantity in {b_unit} to the power of {n}"
+                ))
+            });
+        }
+        "add" => "add",
+        "sub" => "subtract",
+        "modulus" => "take the modulus of",
+        "min" => "take the minimum of",
+        "max" => "take the maximum of",
+        "complex" => "make a complex number from",
+        "atan2" => "take the angle of",
+        "is_eq" | "is_ne" | "is_lt" | "is_le" | "is_gt" | "is_ge" => "compare",
+        _ => {
+            return Err(env.error(format!(
+                "Cannot {name} quantities in {} and {}",
+                describe(b_unit),
+                describe(a_unit)
+            )))
+        }
+    };
+    if a_unit != b_unit {
+        return Err(env.error(format!(
+            "Cannot {verb} quantities in {} and {}",
+            describe(b_unit),
+            describe(a_unit)
+        )));
+    }
+    Ok(match name {
+        "atan2" | "is_eq" | "is_ne" | "is_lt" | "is_le" | "is_gt" | "is_ge" => None,
+        _ => a_unit,
+    })
+}
+
+fn parse_unit(val: &Value, env: &Uiua) -> UiuaResult<(f64, Unit)> {
+    let s = val.as_string(env, "Unit must be a string")?;
+    Unit::parse(&s).map_err(|e| env.error(e))
+}
+
+pub fn unit(env: &mut Uiua) -> UiuaResult {
+    let (scale, unit) = parse_unit(&env.pop(1)?, env)?;
+    let mut val = env.pop(2)?;
+    let existing = val.take_unit().unwrap_or_default();
+    let mut arr = val.as_number_array::<f64>(
+        env,
+        "Only numbers can have units",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    for n in arr.data.as_mut_slice() {
+        *n *= scale;
+    }
+    let mut val = Value::from(arr);
+    set_unit(&mut val, Some(existing.mul(unit)));
+    env.push(val);
+    Ok(())
+}
+
+pub fn unit_inverse(env: &mut Uiua) -> UiuaResult {
+    let (scale, unit) = parse_unit(&env.pop(1)?, env)?;
+    let mut val = env.pop(2)?;
+    let existing = val.take_unit().unwrap_or_default();
+    if existing != unit {
+        return Err(env.error(format!("Cannot convert a quantity in {existing} to {unit}")));
+    }
+    let mut arr: Array<f64> = val.as_number_array(
+        env,
+        "Only numbers can have units",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    for n in arr.data.as_mut_slice() {
+        *n /= scale;
+    }
+    env.push(arr);
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Validation of values against schemas
+
+use ecow::EcoVec;
+
+use crate::{Array, Boxed, Shape, Uiua, UiuaResult, Value};
+
+pub fn validate(env: &mut Uiua) -> UiuaResult {
+    let schema = env.pop(1)?;
+    let val = env.pop(2)?;
+    let mut errors = Vec::new();
+    check(&schema, &val, "$", &mut errors, env)?;
+    let mut data = EcoVec::with_capacity(errors.len() * 2);
+    for (path, message) in &errors {
+        data.push(Boxed(path.as_str().into()));
+        data.push(Boxed(message.as_str().into()));
+    }
+    env.push(Array::new(Shape::from([errors.len(), 2]), data));
+    Ok(())
+}
+
+/// Remove scalar boxes
+fn unboxed(mut val: &Value) -> &Value {
+    while let Value::Box(arr) = val {
+        match arr.as_scalar() {
+            Some(Boxed(inner)) => val = inner,
+            None => break,
+        }
+    }
+    val
+}
+
+fn describe(val: &Value) -> String {
+    if val.rank() == 0 || matches!(val, Value::Char(arr) if arr.rank() == 1) {
+        val.representation()
+    } else {
+        format!("{} array of shape {}", val.type_name(), val.shape())
+    }
+}
+
+/// Check whether every element of a real number array satisfies a predicate
+fn all_reals(val: &Value, f: impl Fn(f64) -> bool) -> bool {
+    match val {
+        Value::Num(arr) => arr.data.iter().all(|&n| f(n)),
+        Value::Byte(arr) => arr.data.iter().all(|&n| f(n as f64)),
+        Value::Int(arr) => arr.data.iter().all(|&n| f(n as f64)),
+        Value::Float(arr) => arr.data.iter().all(|&n| f(n as f64)),
+        _ => false,
+    }
+}
+
+fn check(
+    schema: &Value,
+    val: &Value,
+    path: &str,
+    errors: &mut Vec<(String, String)>,
+    env: &Uiua,
+) -> UiuaResult {
+    let schema = unboxed(schema);
+    if let (Value::Char(_), false) = (schema, schema.is_map()) {
+        let ty = schema.as_string(env, "Schema type must be a string")?;
+        return check_type(&ty, val, path, errors, env);
+    }
+    if !schema.is_map() {
+        return Err(env.error(format!(
+            "Schema must be a type name or a map, but it is {}",
+            describe(schema)
+        )));
+    }
+    let mut ty = None;
+    let mut shape = None;
+    let mut rank = None;
+    let mut keys = None;
+    let mut optional = Vec::new();
+    let mut rows = None;
+    for (key, field) in schema.map_kv() {
+        let key = unboxed(&key).as_string(env, "Schema keys must be strings")?;
+        let field = unboxed(&field);
+        match key.as_str() {
+            "type" => ty = Some(field.as_string(env, "Schema type must be a string")?),
+            "shape" => shape = Some(field.as_nums(env, "Schema shape must be a list of numbers")?),
+            "rank" => rank = Some(field.as_nat(env, "Schema rank must be a natural number")?),
+            "keys" => {
+                if !field.is_map() {
+                    return Err(env.error("Schema keys must be a map"));
+                }
+                keys = Some(field.map_kv());
+            }
+            "optional" => {
+                optional = if matches!(field, Value::Char(arr) if arr.rank() <= 1) {
+                    vec![field.as_string(env, "")?]
+                } else {
+                    (field.rows())
+                        .map(|key| {
+                            (unboxed(&key)).as_string(env, "Schema optional keys must be strings")
+                        })
+                        .collect::<UiuaResult<_>>()?
+                }
+            }
+            "rows" => rows = Some(field.clone()),
+            key => return Err(env.error(format!("Unknown schema field {key:?}"))),
+        }
+    }
+    let start_errors = errors.len();
+    if let Some(ty) = &ty {
+        check_type(ty, val, path, errors, env)?;
+    }
+    let val = if ty.as_deref() == Some("box") {
+        val
+    } else {
+        unboxed(val)
+    };
+    if let Some(rank) = rank {
+        if val.rank() != rank {
+            let message = format!("expected rank {rank}, found rank {}", val.rank());
+            errors.push((path.into(), message));
+        }
+    }
+    if let Some(shape) = &shape {
+        let matches = shape.len() == val.rank()
+            && (shape.iter().zip(val.shape().dims())).all(|(&s, &d)| s < 0.0 || s == d as f64);
+        if !matches {
+            let expected: Vec<String> = (shape.iter())
+                .map(|&s| if s < 0.0 { "_".into() } else { s.to_string() })
+                .collect();
+            let message = format!(
+                "expected shape [{}], found shape {}",
+                expected.join(" × "),
+                val.shape()
+            );
+            errors.push((path.into(), message));
+        }
+    }
+    if errors.len() > start_errors {
+        return Ok(());
+    }
+    if let Some(keys) = keys {
+        if !val.is_map() {
+            errors.push((
+                path.into(),
+                format!("expected map, found {}", describe(val)),
+            ));
+            return Ok(());
+        }
+        for (key, key_schema) in keys {
+            let key = unboxed(&key);
+            let name = key.as_string(env, "Schema keys must be strings")?;
+            let key_path = format!("{path}.{name}");
+            let boxed_key = Value::from(Boxed(key.clone()));
+            let found = if val.has_key(key, env)? {
+                Some(val.get(key, env)?)
+            } else if val.has_key(&boxed_key, env)? {
+                Some(val.get(&boxed_key, env)?)
+            } else {
+                None
+            };
+            match found {
+                Some(field) => check(&key_schema, &field, &key_path, errors, env)?,
+                None if optional.contains(&name) => {}
+                None => errors.push((key_path, "missing required key".into())),
+            }
+        }
+    }
+    if let Some(row_schema) = rows {
+        if val.rank() == 0 {
+            errors.push((
+                path.into(),
+                format!("expected list, found {}", describe(val)),
+            ));
+            return Ok(());
+        }
+        for (i, row) in val.rows().enumerate() {
+            check(&row_schema, &row, &format!("{path}[{i}]"), errors, env)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_type(
+    ty: &str,
+    val: &Value,
+    path: &str,
+    errors: &mut Vec<(String, String)>,
+    env: &Uiua,
+) -> UiuaResult {
+    let val = if ty == "box" { val } else { unboxed(val) };
+    let matches = match ty {
+        "any" => true,
+        "number" => all_reals(val, |_| true),
+        "complex" => matches!(val, Value::Complex(_)) || all_reals(val, |_| true),
+        "integer" => all_reals(val, |n| n.fract() == 0.0),
+        "natural" => all_reals(val, |n| n.fract() == 0.0 && n >= 0.0),
+        "boolean" => all_reals(val, |n| n == 0.0 || n == 1.0),
+        "char" => matches!(val, Value::Char(_)),
+        "string" => matches!(val, Value::Char(arr) if arr.rank() == 1),
+        "box" => matches!(val, Value::Box(_)),
+        "map" => val.is_map(),
+        _ => return Err(env.error(format!("Unknown schema type {ty:?}"))),
+    };
+    if !matches {
+        errors.push((
+            path.into(),
+            format!("expected {ty}, found {}", describe(val)),
+        ));
+    }
+    Ok(())
+}
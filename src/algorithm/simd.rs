@@ -0,0 +1,337 @@
+//! SIMD fast paths for pervasive arithmetic
+//!
+//! `std::simd` is not stable, so the kernels here work on fixed-size chunks of lanes instead.
+//! Each chunk is processed by a branch-free loop over a plain array,
+//! which LLVM lowers to vector instructions on any target that has them.
+//! The last partial chunk is padded so that it can use the same kernel.
+//!
+//! Only `f64` and `u8` arrays whose shapes match exactly, or where one side is a scalar, take these paths.
+//! Everything else falls back to the general algorithms in [`pervade`](super::pervade).
+
+use ecow::EcoVec;
+
+use crate::{algorithm::pervade, Array, ArrayValue, Value};
+
+/// Lanes per chunk for `f64` kernels
+const F64_LANES: usize = 8;
+/// Lanes per chunk for `u8` kernels
+const U8_LANES: usize = 32;
+/// The minimum number of elements for which the kernels are worth using
+const MIN_LEN: usize = 16;
+
+/// An operation that has a SIMD kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Min,
+    Max,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Get the [`Op`] for the name of a pervasive function module, if it has one
+macro_rules! simd_op {
+    (add) => {
+        Some($crate::algorithm::simd::Op::Add)
+    };
+    (sub) => {
+        Some($crate::algorithm::simd::Op::Sub)
+    };
+    (mul) => {
+        Some($crate::algorithm::simd::Op::Mul)
+    };
+    (div) => {
+        Some($crate::algorithm::simd::Op::Div)
+    };
+    (min) => {
+        Some($crate::algorithm::simd::Op::Min)
+    };
+    (max) => {
+        Some($crate::algorithm::simd::Op::Max)
+    };
+    (is_eq) => {
+        Some($crate::algorithm::simd::Op::Eq)
+    };
+    (is_ne) => {
+        Some($crate::algorithm::simd::Op::Ne)
+    };
+    (is_lt) => {
+        Some($crate::algorithm::simd::Op::Lt)
+    };
+    (is_le) => {
+        Some($crate::algorithm::simd::Op::Le)
+    };
+    (is_gt) => {
+        Some($crate::algorithm::simd::Op::Gt)
+    };
+    (is_ge) => {
+        Some($crate::algorithm::simd::Op::Ge)
+    };
+    ($name:ident) => {
+        None
+    };
+}
+pub(crate) use simd_op;
+
+impl Op {
+    /// Whether boolean byte arrays have their own implementation of this operation
+    fn has_bool_impl(self) -> bool {
+        matches!(self, Op::Add | Op::Mul | Op::Min | Op::Max)
+    }
+}
+
+/// Try to apply a pervasive dyadic function with a SIMD kernel
+///
+/// The arguments are given back if there is no fast path for them.
+pub(crate) fn dyadic(
+    op: Option<Op>,
+    a: Value,
+    b: Value,
+    a_depth: usize,
+    b_depth: usize,
+) -> Result<Value, (Value, Value)> {
+    let Some(op) = op else {
+        return Err((a, b));
+    };
+    if a_depth > 0
+        || b_depth > 0
+        || a.element_count().max(b.element_count()) < MIN_LEN
+        || !(a.shape() == b.shape() || a.rank() == 0 || b.rank() == 0)
+    {
+        return Err((a, b));
+    }
+    match (a, b) {
+        (Value::Num(a), Value::Num(b)) => Ok(num_num(op, a, b).into()),
+        (Value::Byte(a), Value::Byte(b))
+            if !(op.has_bool_impl()
+                && a.meta().flags.is_boolean()
+                && b.meta().flags.is_boolean()) =>
+        {
+            Ok(byte_byte(op, a, b))
+        }
+        (a, b) => Err((a, b)),
+    }
+}
+
+fn num_num(op: Op, a: Array<f64>, b: Array<f64>) -> Array<f64> {
+    match op {
+        Op::Add => num_num_impl(a, b, pervade::add::num_num),
+        Op::Sub => num_num_impl(a, b, pervade::sub::num_num),
+        Op::Mul => num_num_impl(a, b, pervade::mul::num_num),
+        Op::Div => num_num_impl(a, b, pervade::div::num_num),
+        Op::Min => num_num_impl(a, b, pervade::min::num_num),
+        Op::Max => num_num_impl(a, b, pervade::max::num_num),
+        Op::Eq => num_cmp(a, b, |a, b| b == a, pervade::is_eq::same_type),
+        Op::Ne => num_cmp(a, b, |a, b| b != a, pervade::is_ne::same_type),
+        Op::Lt => num_cmp(a, b, |a, b| b < a, pervade::is_lt::same_type),
+        Op::Le => num_cmp(a, b, |a, b| b <= a, pervade::is_le::same_type),
+        Op::Gt => num_cmp(a, b, |a, b| b > a, pervade::is_gt::same_type),
+        Op::Ge => num_cmp(a, b, |a, b| b >= a, pervade::is_ge::same_type),
+    }
+}
+
+/// Compare numbers with IEEE comparisons
+///
+/// Chunks that contain a NaN are recomputed with the exact comparison,
+/// because NaNs are ordered when comparing arrays.
+fn num_cmp(
+    a: Array<f64>,
+    b: Array<f64>,
+    fast: impl Fn(f64, f64) -> bool + Copy,
+    exact: impl Fn(f64, f64) -> f64 + Copy,
+) -> Array<f64> {
+    num_num_chunked(a, b, move |a, b| {
+        let mut c = [0.0; F64_LANES];
+        let mut nan = false;
+        for i in 0..F64_LANES {
+            c[i] = fast(a[i], b[i]) as u8 as f64;
+            nan |= a[i].is_nan() | b[i].is_nan();
+        }
+        if nan {
+            for i in 0..F64_LANES {
+                c[i] = exact(a[i], b[i]);
+            }
+        }
+        c
+    })
+}
+
+fn num_num_impl(a: Array<f64>, b: Array<f64>, f: impl Fn(f64, f64) -> f64 + Copy) -> Array<f64> {
+    num_num_chunked(a, b, move |a, b| {
+        let mut c = [0.0; F64_LANES];
+        for i in 0..F64_LANES {
+            c[i] = f(a[i], b[i]);
+        }
+        c
+    })
+}
+
+/// Apply a chunk kernel to two number arrays
+///
+/// The result reuses the buffer of one of the arguments, chosen the same way as in [`pervade::bin_pervade_mut`].
+fn num_num_chunked(
+    mut a: Array<f64>,
+    mut b: Array<f64>,
+    f: impl Fn([f64; F64_LANES], [f64; F64_LANES]) -> [f64; F64_LANES] + Copy,
+) -> Array<f64> {
+    if a.rank() == 0 && b.rank() != 0 {
+        let a = [a.data[0]; F64_LANES];
+        map_in_place(b.data.as_mut_slice(), |b| f(a, b));
+        b
+    } else if b.rank() == 0 && a.rank() != 0 {
+        let b = [b.data[0]; F64_LANES];
+        map_in_place(a.data.as_mut_slice(), |a| f(a, b));
+        a
+    } else if a.data.is_copy_of(&b.data) {
+        drop(a);
+        map_in_place(b.data.as_mut_slice(), |b| f(b, b));
+        b
+    } else if a.data.is_unique() {
+        zip_in_place(a.data.as_mut_slice(), b.data.as_slice(), f);
+        a
+    } else {
+        zip_in_place(b.data.as_mut_slice(), a.data.as_slice(), |b, a| f(a, b));
+        b
+    }
+}
+
+fn byte_byte(op: Op, a: Array<u8>, b: Array<u8>) -> Value {
+    let mut val: Value = match op {
+        Op::Add => byte_byte_impl::<_, F64_LANES>(a, b, pervade::add::byte_byte).into(),
+        Op::Sub => byte_byte_impl::<_, F64_LANES>(a, b, pervade::sub::byte_byte).into(),
+        Op::Mul => byte_byte_impl::<_, F64_LANES>(a, b, pervade::mul::byte_byte).into(),
+        Op::Div => byte_byte_impl::<_, F64_LANES>(a, b, pervade::div::byte_byte).into(),
+        Op::Min => byte_byte_impl::<_, U8_LANES>(a, b, pervade::min::byte_byte).into(),
+        Op::Max => byte_byte_impl::<_, U8_LANES>(a, b, pervade::max::byte_byte).into(),
+        Op::Eq => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_eq::same_type).into(),
+        Op::Ne => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_ne::same_type).into(),
+        Op::Lt => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_lt::same_type).into(),
+        Op::Le => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_le::same_type).into(),
+        Op::Gt => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_gt::same_type).into(),
+        Op::Ge => byte_byte_impl::<_, U8_LANES>(a, b, pervade::is_ge::same_type).into(),
+    };
+    val.reset_meta_flags();
+    val
+}
+
+/// Apply an operation to two byte arrays, producing a new array
+fn byte_byte_impl<T, const N: usize>(
+    a: Array<u8>,
+    b: Array<u8>,
+    f: impl Fn(u8, u8) -> T + Copy,
+) -> Array<T>
+where
+    T: ArrayValue + Copy,
+{
+    let kernel = move |a: [u8; N], b: [u8; N]| {
+        let mut c = [T::default(); N];
+        for i in 0..N {
+            c[i] = f(a[i], b[i]);
+        }
+        c
+    };
+    if a.rank() == 0 && b.rank() != 0 {
+        let a = [a.data[0]; N];
+        let data = map_new(b.data.as_slice(), |b| kernel(a, b));
+        Array::new(b.shape, data)
+    } else if b.rank() == 0 && a.rank() != 0 {
+        let b = [b.data[0]; N];
+        let data = map_new(a.data.as_slice(), |a| kernel(a, b));
+        Array::new(a.shape, data)
+    } else {
+        let data = zip_new(a.data.as_slice(), b.data.as_slice(), kernel);
+        Array::new(b.shape, data)
+    }
+}
+
+/// Replace each chunk of `data` with the result of `f`
+fn map_in_place<T: Copy + Default, const N: usize>(data: &mut [T], f: impl Fn([T; N]) -> [T; N]) {
+    let mut chunks = data.chunks_exact_mut(N);
+    for chunk in &mut chunks {
+        let x: [T; N] = (*chunk).try_into().unwrap();
+        chunk.copy_from_slice(&f(x));
+    }
+    let rem = chunks.into_remainder();
+    if !rem.is_empty() {
+        let mut x = [T::default(); N];
+        x[..rem.len()].copy_from_slice(rem);
+        let len = rem.len();
+        rem.copy_from_slice(&f(x)[..len]);
+    }
+}
+
+/// Replace each chunk of `data` with the result of `f` on it and the corresponding chunk of `other`
+fn zip_in_place<T: Copy + Default, const N: usize>(
+    data: &mut [T],
+    other: &[T],
+    f: impl Fn([T; N], [T; N]) -> [T; N],
+) {
+    let mut chunks = data.chunks_exact_mut(N);
+    let mut other_chunks = other.chunks_exact(N);
+    for (chunk, other) in (&mut chunks).zip(&mut other_chunks) {
+        let x: [T; N] = (*chunk).try_into().unwrap();
+        let y: [T; N] = other.try_into().unwrap();
+        chunk.copy_from_slice(&f(x, y));
+    }
+    let rem = chunks.into_remainder();
+    if !rem.is_empty() {
+        let (mut x, mut y) = ([T::default(); N], [T::default(); N]);
+        x[..rem.len()].copy_from_slice(rem);
+        y[..rem.len()].copy_from_slice(other_chunks.remainder());
+        let len = rem.len();
+        rem.copy_from_slice(&f(x, y)[..len]);
+    }
+}
+
+/// Collect the results of `f` on each chunk of `data`
+fn map_new<T, U, const N: usize>(data: &[T], f: impl Fn([T; N]) -> [U; N]) -> EcoVec<U>
+where
+    T: Copy + Default,
+    U: Copy,
+{
+    let mut res = EcoVec::with_capacity(data.len());
+    let mut chunks = data.chunks_exact(N);
+    for chunk in &mut chunks {
+        res.extend_from_slice(&f(chunk.try_into().unwrap()));
+    }
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        let mut x = [T::default(); N];
+        x[..rem.len()].copy_from_slice(rem);
+        res.extend_from_slice(&f(x)[..rem.len()]);
+    }
+    res
+}
+
+/// Collect the results of `f` on corresponding chunks of `a` and `b`
+fn zip_new<T, U, const N: usize>(
+    a: &[T],
+    b: &[T],
+    f: impl Fn([T; N], [T; N]) -> [U; N],
+) -> EcoVec<U>
+where
+    T: Copy + Default,
+    U: Copy,
+{
+    let mut res = EcoVec::with_capacity(a.len());
+    let mut a_chunks = a.chunks_exact(N);
+    let mut b_chunks = b.chunks_exact(N);
+    for (a, b) in (&mut a_chunks).zip(&mut b_chunks) {
+        res.extend_from_slice(&f(a.try_into().unwrap(), b.try_into().unwrap()));
+    }
+    let (a_rem, b_rem) = (a_chunks.remainder(), b_chunks.remainder());
+    if !a_rem.is_empty() {
+        let (mut x, mut y) = ([T::default(); N], [T::default(); N]);
+        x[..a_rem.len()].copy_from_slice(a_rem);
+        y[..b_rem.len()].copy_from_slice(b_rem);
+        res.extend_from_slice(&f(x, y)[..a_rem.len()]);
+    }
+    res
+}
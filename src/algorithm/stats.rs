@@ -0,0 +1,139 @@
+//! Descriptive statistics
+//!
+//! Statistics are computed along the first axis, so each row is one observation.
+
+use std::cmp::Reverse;
+
+use ecow::EcoVec;
+
+use crate::{Array, Primitive, Shape, Uiua, UiuaResult};
+
+/// Running central moments, updated with Welford's method
+#[derive(Clone, Copy, Default)]
+struct Moments {
+    n: f64,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+}
+
+impl Moments {
+    fn push(&mut self, x: f64) {
+        let n1 = self.n;
+        self.n += 1.0;
+        let delta = x - self.mean;
+        let delta_n = delta / self.n;
+        let term = delta * delta_n * n1;
+        self.mean += delta_n;
+        self.m3 += term * delta_n * (self.n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+    }
+    /// The sample variance
+    fn variance(&self) -> f64 {
+        self.m2 / (self.n - 1.0)
+    }
+    fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+    /// The sample skewness
+    fn skewness(&self) -> f64 {
+        self.n.sqrt() * self.m3 / self.m2.powf(1.5)
+    }
+}
+
+/// Compute the moments of each column in a single pass over the rows
+fn moments(
+    prim: Primitive,
+    min_rows: usize,
+    env: &mut Uiua,
+) -> UiuaResult<(Array<f64>, Vec<Moments>)> {
+    let arr: Array<f64> = env.pop(1)?.as_number_array(
+        env,
+        "Statistics can only be computed for numbers",
+        |_| true,
+        |_| true,
+        |n| n,
+    )?;
+    let rows = if arr.rank() == 0 { 1 } else { arr.shape[0] };
+    if rows < min_rows {
+        return Err(env.error(format!(
+            "{prim} requires at least {min_rows} row{}, but the array has {rows}",
+            if min_rows == 1 { "" } else { "s" }
+        )));
+    }
+    let row_len = arr.shape.iter().skip(1).product::<usize>();
+    let mut moments = vec![Moments::default(); row_len];
+    for row in arr.data.chunks_exact(row_len.max(1)) {
+        for (m, &x) in moments.iter_mut().zip(row) {
+            m.push(x);
+        }
+    }
+    Ok((arr, moments))
+}
+
+fn column_statistic(
+    prim: Primitive,
+    min_rows: usize,
+    f: fn(&Moments) -> f64,
+    env: &mut Uiua,
+) -> UiuaResult {
+    let (arr, moments) = moments(prim, min_rows, env)?;
+    let shape: Shape = arr.shape.iter().skip(1).copied().collect();
+    let data: EcoVec<f64> = moments.iter().map(f).collect();
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+pub fn mean(env: &mut Uiua) -> UiuaResult {
+    column_statistic(Primitive::Mean, 1, |m| m.mean, env)
+}
+
+pub fn variance(env: &mut Uiua) -> UiuaResult {
+    column_statistic(Primitive::Variance, 2, Moments::variance, env)
+}
+
+pub fn stddev(env: &mut Uiua) -> UiuaResult {
+    column_statistic(Primitive::StdDev, 2, Moments::stddev, env)
+}
+
+pub fn skewness(env: &mut Uiua) -> UiuaResult {
+    column_statistic(Primitive::Skewness, 3, Moments::skewness, env)
+}
+
+pub fn zscore(env: &mut Uiua) -> UiuaResult {
+    let (mut arr, moments) = moments(Primitive::ZScore, 2, env)?;
+    let row_len = moments.len().max(1);
+    for row in arr.data.as_mut_slice().chunks_exact_mut(row_len) {
+        for (x, m) in row.iter_mut().zip(&moments) {
+            *x = (*x - m.mean) / m.stddev();
+        }
+    }
+    env.push(arr);
+    Ok(())
+}
+
+pub fn mode(env: &mut Uiua) -> UiuaResult {
+    let val = env.pop(1)?;
+    if val.rank() == 0 {
+        env.push(val);
+        return Ok(());
+    }
+    if val.row_count() == 0 {
+        return Err(env.error(format!(
+            "Cannot get the {} of an empty array",
+            Primitive::Mode
+        )));
+    }
+    let classes = val.classify().as_nats(env, "")?;
+    let mut counts = vec![0usize; classes.iter().max().map_or(0, |&c| c + 1)];
+    for &c in &classes {
+        counts[c] += 1;
+    }
+    // Classes are numbered in order of first appearance, so ties go to the first
+    let (best, _) = (counts.iter().enumerate())
+        .max_by_key(|&(i, &c)| (c, Reverse(i)))
+        .unwrap();
+    let index = classes.iter().position(|&c| c == best).unwrap();
+    env.push(val.row(index));
+    Ok(())
+}
@@ -41,10 +41,44 @@ pub fn table(env: &mut Uiua) -> UiuaResult {
                             }
                             (Value::Byte(a), Value::Byte(b)) => {
                                 return a
-                                    .convert_ref()
+                                    .convert_ref::<f64>()
                                     .matrix_mul(&b.convert_ref(), env)
                                     .map(|val| env.push(val))
                             }
+                            (Value::Float(a), Value::Float(b)) => {
+                                return a.matrix_mul(b, env).map(|val| env.push(val))
+                            }
+                            (Value::Float(a), b @ (Value::Num(_) | Value::Byte(_))) => {
+                                return (a.matrix_mul(&b.clone().into_f32_array(), env))
+                                    .map(|val| env.push(val))
+                            }
+                            (a @ (Value::Num(_) | Value::Byte(_)), Value::Float(b)) => {
+                                return (a.clone().into_f32_array().matrix_mul(b, env))
+                                    .map(|val| env.push(val))
+                            }
+                            (Value::Complex(a), Value::Complex(b)) => {
+                                return a.matrix_mul(b, env).map(|val| env.push(val))
+                            }
+                            (
+                                Value::Complex(a),
+                                b @ (Value::Num(_)
+                                | Value::Byte(_)
+                                | Value::Int(_)
+                                | Value::Float(_)),
+                            ) => {
+                                return (a.matrix_mul(&b.clone().into_complex_array(), env))
+                                    .map(|val| env.push(val))
+                            }
+                            (
+                                a @ (Value::Num(_)
+                                | Value::Byte(_)
+                                | Value::Int(_)
+                                | Value::Float(_)),
+                                Value::Complex(b),
+                            ) => {
+                                return (a.clone().into_complex_array().matrix_mul(b, env))
+                                    .map(|val| env.push(val))
+                            }
                             _ => {}
                         }
                     }
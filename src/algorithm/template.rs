@@ -0,0 +1,193 @@
+//! String templates
+//!
+//! Templates use a subset of Mustache syntax. Values are looked up by name in a stack of map contexts.
+
+use crate::{Uiua, UiuaResult, Value};
+
+enum Node {
+    Text(String),
+    Var {
+        name: String,
+        escape: bool,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// Parse a template into a tree of nodes
+fn parse(template: &str, env: &Uiua) -> UiuaResult<Vec<Node>> {
+    // Each open section has its name, whether it is inverted, and the nodes before it
+    let mut open: Vec<(String, bool, Vec<Node>)> = Vec::new();
+    let mut nodes = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].into()));
+        }
+        rest = &rest[start + 2..];
+        let (triple, close) = if rest.starts_with('{') {
+            rest = &rest[1..];
+            (true, "}}}")
+        } else {
+            (false, "}}")
+        };
+        let end = rest
+            .find(close)
+            .ok_or_else(|| env.error(format!("Template tag is missing its closing {close}")))?;
+        let tag = rest[..end].trim();
+        rest = &rest[end + close.len()..];
+        if triple {
+            nodes.push(Node::Var {
+                name: tag.into(),
+                escape: false,
+            });
+            continue;
+        }
+        let mut chars = tag.chars();
+        match chars.next() {
+            Some('!') => {}
+            Some('&') => nodes.push(Node::Var {
+                name: chars.as_str().trim().into(),
+                escape: false,
+            }),
+            Some(c @ ('#' | '^')) => {
+                let name = chars.as_str().trim().into();
+                open.push((name, c == '^', nodes));
+                nodes = Vec::new();
+            }
+            Some('/') => {
+                let name = chars.as_str().trim();
+                let Some((open_name, inverted, parent)) = open.pop() else {
+                    return Err(env.error(format!(
+                        "Template section {name:?} is closed but never opened"
+                    )));
+                };
+                if open_name != name {
+                    return Err(env.error(format!(
+                        "Template section {open_name:?} is closed by {name:?}"
+                    )));
+                }
+                let children = std::mem::replace(&mut nodes, parent);
+                nodes.push(Node::Section {
+                    name: open_name,
+                    inverted,
+                    children,
+                });
+            }
+            _ => nodes.push(Node::Var {
+                name: tag.into(),
+                escape: true,
+            }),
+        }
+    }
+    if let Some((name, ..)) = open.pop() {
+        return Err(env.error(format!("Template section {name:?} is never closed")));
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest.into()));
+    }
+    Ok(nodes)
+}
+
+/// Look up a name in the context stack
+fn lookup(name: &str, contexts: &[Value], env: &Uiua) -> UiuaResult<Option<Value>> {
+    if name == "." {
+        return Ok(contexts.last().cloned());
+    }
+    let key = Value::from(name.to_string());
+    for context in contexts.iter().rev() {
+        if context.is_map() && context.has_key(&key, env)? {
+            return context.get(&key, env).map(|val| Some(val.unboxed()));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether a section value should be rendered
+fn is_truthy(val: &Value) -> bool {
+    if val.rank() > 0 {
+        return val.row_count() > 0;
+    }
+    match val {
+        Value::Num(arr) => arr.data[0] != 0.0,
+        Value::Byte(arr) => arr.data[0] != 0,
+        Value::Int(arr) => arr.data[0] != 0,
+        Value::Float(arr) => arr.data[0] != 0.0,
+        _ => true,
+    }
+}
+
+fn escape_html(s: &str, out: &mut String) {
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn render(nodes: &[Node], contexts: &mut Vec<Value>, out: &mut String, env: &Uiua) -> UiuaResult {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { name, escape } => {
+                let val = lookup(name, contexts, env)?.ok_or_else(|| {
+                    env.error(format!("Template variable {name:?} is not in the context"))
+                })?;
+                let s = val.format();
+                if *escape {
+                    escape_html(&s, out);
+                } else {
+                    out.push_str(&s);
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let val = lookup(name, contexts, env)?;
+                let truthy = val.as_ref().is_some_and(is_truthy);
+                if *inverted {
+                    if !truthy {
+                        render(children, contexts, out, env)?;
+                    }
+                    continue;
+                }
+                let Some(val) = val.filter(|_| truthy) else {
+                    continue;
+                };
+                // Strings and maps are rendered once, and other lists are looped over
+                let items = if val.rank() == 0 || val.is_map() || matches!(val, Value::Char(_)) {
+                    vec![val]
+                } else {
+                    val.into_rows().map(Value::unboxed).collect()
+                };
+                for item in items {
+                    contexts.push(item);
+                    let res = render(children, contexts, out, env);
+                    contexts.pop();
+                    res?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn template(env: &mut Uiua) -> UiuaResult {
+    let template = env.pop(1)?.as_string(env, "Template must be a string")?;
+    let context = env.pop(2)?.unboxed();
+    let nodes = parse(&template, env)?;
+    let mut out = String::new();
+    render(&nodes, &mut vec![context], &mut out, env)?;
+    env.push(out);
+    Ok(())
+}
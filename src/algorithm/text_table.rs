@@ -0,0 +1,262 @@
+//! Laying out arrays as text tables
+
+use crate::{
+    grid_fmt::{GridFmt, GridFmtParams},
+    Primitive, Uiua, UiuaResult, Value,
+};
+
+struct TableOptions {
+    border: bool,
+    /// The maximum width of each column, or of all columns if there is only one
+    widths: Vec<usize>,
+}
+
+impl TableOptions {
+    fn from_value(val: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let mut options = TableOptions {
+            border: true,
+            widths: Vec::new(),
+        };
+        if !val.is_map() {
+            if val.row_count() == 0 {
+                return Ok(options);
+            }
+            return Err(env.error(format!(
+                "{} options must be a map or an empty list",
+                Primitive::TextTable
+            )));
+        }
+        for (key, value) in val.map_kv() {
+            let value = value.unboxed();
+            match key
+                .as_string(env, "Table option names must be strings")?
+                .as_str()
+            {
+                "border" => {
+                    options.border = value.as_bool(env, "Table border must be a boolean")?;
+                }
+                "width" => {
+                    options.widths = (value
+                        .as_nums(env, "Table width must be a list of numbers")?)
+                    .into_iter()
+                    .map(|w| {
+                        if w >= 1.0 {
+                            Ok(if w.is_infinite() {
+                                usize::MAX
+                            } else {
+                                w as usize
+                            })
+                        } else {
+                            Err(env.error(format!("Table width must be at least 1, but it is {w}")))
+                        }
+                    })
+                    .collect::<UiuaResult<_>>()?;
+                }
+                name => {
+                    return Err(env.error(format!(
+                        "Unknown table option {name:?}. Valid options are \"border\" and \"width\"."
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+    fn max_width(&self, col: usize) -> usize {
+        match self.widths.as_slice() {
+            [] => usize::MAX,
+            [w] => *w,
+            ws => ws.get(col).copied().unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// A table cell's lines and whether it is a number
+struct Cell {
+    lines: Vec<Vec<char>>,
+    numeric: bool,
+}
+
+impl Cell {
+    fn new(val: Value) -> Self {
+        let val = val.unboxed();
+        let numeric = val.rank() == 0 && !matches!(val, Value::Char(_) | Value::Box(_));
+        let lines = if let Value::Char(arr) = &val {
+            if arr.rank() <= 1 {
+                let s: String = arr.data.iter().collect();
+                s.lines().map(|line| line.chars().collect()).collect()
+            } else {
+                val.fmt_grid(GridFmtParams::default())
+            }
+        } else {
+            val.fmt_grid(GridFmtParams::default())
+        };
+        Cell { lines, numeric }
+    }
+    fn empty() -> Self {
+        Cell {
+            lines: Vec::new(),
+            numeric: false,
+        }
+    }
+    fn truncate(&mut self, max: usize) {
+        for line in &mut self.lines {
+            if line.len() > max {
+                line.truncate(max - 1);
+                line.push('…');
+            }
+        }
+    }
+    fn width(&self) -> usize {
+        self.lines.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+pub fn text_table(env: &mut Uiua) -> UiuaResult {
+    let options = TableOptions::from_value(&env.pop(1)?, env)?;
+    let val = env.pop(2)?;
+    // Get the header and columns
+    let (mut header, columns): (Option<Vec<Cell>>, Vec<Vec<Cell>>) = if val.is_map() {
+        let (keys, values): (Vec<Cell>, Vec<Vec<Cell>>) = (val.map_kv().into_iter())
+            .map(|(key, value)| {
+                let value = value.unboxed();
+                let cells = if value.rank() == 0 {
+                    vec![Cell::new(value)]
+                } else {
+                    value.into_rows().map(Cell::new).collect()
+                };
+                (Cell::new(key), cells)
+            })
+            .unzip();
+        (Some(keys), values)
+    } else {
+        match val.rank() {
+            0 => (None, vec![vec![Cell::new(val)]]),
+            1 => (None, vec![val.into_rows().map(Cell::new).collect()]),
+            2 => {
+                let col_count = val.shape()[1];
+                let mut columns: Vec<Vec<Cell>> = (0..col_count).map(|_| Vec::new()).collect();
+                for row in val.into_rows() {
+                    for (col, cell) in columns.iter_mut().zip(row.into_rows()) {
+                        col.push(Cell::new(cell));
+                    }
+                }
+                (None, columns)
+            }
+            rank => {
+                return Err(env.error(format!(
+                    "{} only works on maps and arrays of rank 2 or less, \
+                    but the array has rank {rank}",
+                    Primitive::TextTable
+                )))
+            }
+        }
+    };
+    let row_count = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let mut columns: Vec<Vec<Cell>> = columns
+        .into_iter()
+        .map(|mut col| {
+            col.resize_with(row_count, Cell::empty);
+            col
+        })
+        .collect();
+    // Truncate cells that are too wide
+    for (i, col) in columns.iter_mut().enumerate() {
+        let max = options.max_width(i);
+        col.iter_mut().for_each(|cell| cell.truncate(max));
+        if let Some(cell) = header.as_mut().and_then(|h| h.get_mut(i)) {
+            cell.truncate(max);
+        }
+    }
+    let widths: Vec<usize> = (columns.iter().enumerate())
+        .map(|(i, col)| {
+            let header_width = header.as_ref().map_or(0, |h| h[i].width());
+            col.iter()
+                .map(Cell::width)
+                .max()
+                .unwrap_or(0)
+                .max(header_width)
+        })
+        .collect();
+    // Numeric columns are right-aligned
+    let right: Vec<bool> = (columns.iter())
+        .map(|col| {
+            col.iter().any(|c| !c.lines.is_empty())
+                && col.iter().all(|c| c.numeric || c.lines.is_empty())
+        })
+        .collect();
+    // Render
+    let (left, sep, right_edge) = if options.border {
+        ("│ ", " │ ", " │")
+    } else {
+        ("", "  ", "")
+    };
+    let rule = |l: char, m: char, r: char, out: &mut String| {
+        if options.border {
+            out.push(l);
+            for (i, w) in widths.iter().enumerate() {
+                if i > 0 {
+                    out.push(m);
+                }
+                out.extend(std::iter::repeat('─').take(w + 2));
+            }
+            out.push(r);
+        } else {
+            for (i, w) in widths.iter().enumerate() {
+                if i > 0 {
+                    out.push_str("  ");
+                }
+                out.extend(std::iter::repeat('─').take(*w));
+            }
+        }
+        out.push('\n');
+    };
+    let row_lines = |cells: &[&Cell], out: &mut String| {
+        let height = cells
+            .iter()
+            .map(|c| c.lines.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for l in 0..height {
+            let mut line = String::from(left);
+            for (i, cell) in cells.iter().enumerate() {
+                if i > 0 {
+                    line.push_str(sep);
+                }
+                let text = cell.lines.get(l).map_or(&[][..], Vec::as_slice);
+                let pad = widths[i] - text.len();
+                if right[i] {
+                    line.extend(std::iter::repeat(' ').take(pad));
+                    line.extend(text);
+                } else {
+                    line.extend(text);
+                    line.extend(std::iter::repeat(' ').take(pad));
+                }
+            }
+            line.push_str(right_edge);
+            if !options.border {
+                line.truncate(line.trim_end().len());
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    };
+    let mut out = String::new();
+    if options.border {
+        rule('┌', '┬', '┐', &mut out);
+    }
+    if let Some(header) = &header {
+        row_lines(&header.iter().collect::<Vec<_>>(), &mut out);
+        rule('├', '┼', '┤', &mut out);
+    }
+    for r in 0..row_count {
+        let cells: Vec<&Cell> = columns.iter().map(|col| &col[r]).collect();
+        row_lines(&cells, &mut out);
+    }
+    if options.border {
+        rule('└', '┴', '┘', &mut out);
+    }
+    out.pop();
+    env.push(out);
+    Ok(())
+}
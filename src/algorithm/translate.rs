@@ -0,0 +1,930 @@
+//! Translating code from other array languages into Uiua
+//!
+//! Only a subset of APL, J, and BQN is supported: numbers, strings, strands, lists,
+//! primitive functions, and the most common primitive modifiers.
+//! Names, assignment, user-defined functions, and trains are not supported.
+//!
+//! APL is translated with `⎕IO←1`. J and BQN index from 0 like Uiua.
+
+use std::mem::take;
+
+use crate::{Primitive, Uiua, UiuaResult};
+
+#[derive(Clone, Copy)]
+enum Lang {
+    Apl,
+    J,
+    Bqn,
+}
+
+/// A primitive function's glyph and its monadic and dyadic translations
+///
+/// A dyadic translation is Uiua code and whether the arguments are flipped.
+/// `A f B` becomes `code A B`, or `code B A` if flipped.
+type FuncDef = (
+    &'static str,
+    Option<&'static str>,
+    Option<(&'static str, bool)>,
+);
+
+const APL_FUNCTIONS: &[FuncDef] = &[
+    ("+", Some("∘"), Some(("+", false))),
+    ("-", Some("¯"), Some(("-", true))),
+    ("×", Some("±"), Some(("×", false))),
+    ("÷", Some("÷:1"), Some(("÷", true))),
+    ("*", Some("ⁿ:e"), Some(("ⁿ", true))),
+    ("⍟", Some("ₙe"), Some(("ₙ", false))),
+    ("⌈", Some("⌈"), Some(("↥", false))),
+    ("⌊", Some("⌊"), Some(("↧", false))),
+    ("|", Some("⌵"), Some(("◿", false))),
+    ("○", Some("×π"), None),
+    ("~", Some("¬"), None),
+    ("=", None, Some(("=", false))),
+    ("≠", Some("◰"), Some(("≠", false))),
+    ("<", None, Some(("<", true))),
+    ("≤", None, Some(("≤", true))),
+    (">", None, Some((">", true))),
+    ("≥", None, Some(("≥", true))),
+    ("≡", None, Some(("≍", false))),
+    ("≢", Some("⧻"), Some(("¬≍", false))),
+    ("⍴", Some("△"), Some(("↯", false))),
+    // Catenation is along the last axis, which is the same as the first for lists
+    (",", Some("♭"), Some(("⊂", false))),
+    ("⍪", None, Some(("⊂", false))),
+    ("⍳", Some("+1⇡"), Some(("+1⊗", true))),
+    ("⍸", Some("+1⊚"), None),
+    ("⌽", Some("⍜°⍉⇌"), Some(("⍜⊙°⍉↻", false))),
+    ("⊖", Some("⇌"), Some(("↻", false))),
+    ("⍉", Some("⍉"), None),
+    ("↑", None, Some(("↙", false))),
+    ("↓", None, Some(("↘", false))),
+    ("⊂", Some("□"), None),
+    ("⊃", Some("⊢"), Some(("⊡-1", false))),
+    ("∊", Some("♭"), Some(("∊", false))),
+    ("⍷", None, Some(("⌕", false))),
+    ("∪", Some("◴"), None),
+    ("⍋", Some("+1⍏"), None),
+    ("⍒", Some("+1⍖"), None),
+    ("⊣", Some("∘"), Some(("⊙◌", false))),
+    ("⊢", Some("∘"), Some(("◌", false))),
+];
+
+/// APL modifiers that are functions when there is no function to their left
+const APL_MODIFIER_FUNCTIONS: &[FuncDef] = &[
+    ("/", None, Some(("⍜⊙°⍉▽", false))),
+    ("⌿", None, Some(("▽", false))),
+];
+
+const J_FUNCTIONS: &[FuncDef] = &[
+    ("+", Some("∘"), Some(("+", false))),
+    ("+:", Some("×2"), None),
+    ("-", Some("¯"), Some(("-", true))),
+    ("-.", Some("¬"), None),
+    ("-:", Some("÷2"), Some(("≍", false))),
+    ("*", Some("±"), Some(("×", false))),
+    ("*:", Some("×."), None),
+    ("%", Some("÷:1"), Some(("÷", true))),
+    ("%:", Some("√"), None),
+    ("^", Some("ⁿ:e"), Some(("ⁿ", true))),
+    ("^.", Some("ₙe"), Some(("ₙ", false))),
+    ("<.", Some("⌊"), Some(("↧", false))),
+    (">.", Some("⌈"), Some(("↥", false))),
+    ("|", Some("⌵"), Some(("◿", false))),
+    ("=", None, Some(("=", false))),
+    ("~:", Some("◰"), Some(("≠", false))),
+    ("<", Some("□"), Some(("<", true))),
+    ("<:", Some("-1"), Some(("≤", true))),
+    (">", Some("°□"), Some((">", true))),
+    (">:", Some("+1"), Some(("≥", true))),
+    ("#", Some("⧻"), Some(("▽", false))),
+    ("$", Some("△"), Some(("↯", false))),
+    (",", Some("♭"), Some(("⊂", false))),
+    ("|.", Some("⇌"), Some(("↻", false))),
+    ("|:", Some("⍉"), None),
+    ("{", None, Some(("⊏", false))),
+    ("{.", Some("⊢"), Some(("↙", false))),
+    ("}.", Some("↘1"), Some(("↘", false))),
+    ("{:", Some("⊢⇌"), None),
+    ("}:", Some("↘¯1"), None),
+    ("i.", Some("⇡"), Some(("⊗", true))),
+    ("e.", None, Some(("∊", false))),
+    ("~.", Some("◴"), None),
+    ("/:", Some("⍏"), None),
+    ("\\:", Some("⍖"), None),
+    ("[", Some("∘"), Some(("⊙◌", false))),
+    ("]", Some("∘"), Some(("◌", false))),
+];
+
+const BQN_FUNCTIONS: &[FuncDef] = &[
+    ("+", Some("∘"), Some(("+", false))),
+    ("-", Some("¯"), Some(("-", true))),
+    ("×", Some("±"), Some(("×", false))),
+    ("÷", Some("÷:1"), Some(("÷", true))),
+    ("⋆", Some("ⁿ:e"), Some(("ⁿ", true))),
+    ("√", Some("√"), None),
+    ("⌊", Some("⌊"), Some(("↧", false))),
+    ("⌈", Some("⌈"), Some(("↥", false))),
+    ("|", Some("⌵"), Some(("◿", false))),
+    ("¬", Some("¬"), None),
+    ("=", None, Some(("=", false))),
+    ("≠", Some("⧻"), Some(("≠", false))),
+    ("<", Some("□"), Some(("<", true))),
+    (">", None, Some((">", true))),
+    ("≤", None, Some(("≤", true))),
+    ("≥", None, Some(("≥", true))),
+    ("≡", None, Some(("≍", false))),
+    ("≢", Some("△"), Some(("¬≍", false))),
+    ("⊣", Some("∘"), Some(("⊙◌", false))),
+    ("⊢", Some("∘"), Some(("◌", false))),
+    ("⥊", Some("♭"), Some(("↯", false))),
+    ("∾", None, Some(("⊂", false))),
+    ("↑", None, Some(("↙", false))),
+    ("↓", None, Some(("↘", false))),
+    ("↕", Some("⇡"), Some(("◫", false))),
+    ("⌽", Some("⇌"), Some(("↻", false))),
+    ("⍉", Some("⍉"), None),
+    ("/", Some("⊚"), Some(("▽", false))),
+    ("⍋", Some("⍏"), None),
+    ("⍒", Some("⍖"), None),
+    ("⊏", Some("⊢"), Some(("⊏", false))),
+    ("⊑", Some("⊢"), Some(("⊡", false))),
+    ("⊐", Some("⊛"), Some(("⊗", true))),
+    ("∊", Some("◰"), Some(("∊", false))),
+    ("⍷", Some("◴"), Some(("⌕", false))),
+];
+
+#[derive(Clone, Copy)]
+enum Modifier {
+    /// A right fold, which makes a table when used dyadically if `table` is set
+    Reduce {
+        last_axis: bool,
+        table: bool,
+    },
+    /// A scan that is a left fold if `left` is set, or a right fold of each prefix otherwise
+    Scan {
+        last_axis: bool,
+        left: bool,
+    },
+    /// J's prefix adverb
+    Prefix,
+    Each,
+    Commute,
+    Table,
+}
+
+const APL_MODIFIERS: &[(&str, Modifier)] = &[
+    (
+        "/",
+        Modifier::Reduce {
+            last_axis: true,
+            table: false,
+        },
+    ),
+    (
+        "⌿",
+        Modifier::Reduce {
+            last_axis: false,
+            table: false,
+        },
+    ),
+    (
+        "\\",
+        Modifier::Scan {
+            last_axis: true,
+            left: false,
+        },
+    ),
+    (
+        "⍀",
+        Modifier::Scan {
+            last_axis: false,
+            left: false,
+        },
+    ),
+    ("¨", Modifier::Each),
+    ("⍨", Modifier::Commute),
+];
+
+const J_MODIFIERS: &[(&str, Modifier)] = &[
+    (
+        "/",
+        Modifier::Reduce {
+            last_axis: false,
+            table: true,
+        },
+    ),
+    ("\\", Modifier::Prefix),
+    ("~", Modifier::Commute),
+];
+
+const BQN_MODIFIERS: &[(&str, Modifier)] = &[
+    (
+        "´",
+        Modifier::Reduce {
+            last_axis: false,
+            table: false,
+        },
+    ),
+    (
+        "˝",
+        Modifier::Reduce {
+            last_axis: false,
+            table: false,
+        },
+    ),
+    (
+        "`",
+        Modifier::Scan {
+            last_axis: false,
+            left: true,
+        },
+    ),
+    ("¨", Modifier::Each),
+    ("⌜", Modifier::Table),
+    ("˜", Modifier::Commute),
+];
+
+impl Lang {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "apl" => Some(Lang::Apl),
+            "j" => Some(Lang::J),
+            "bqn" => Some(Lang::Bqn),
+            _ => None,
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Lang::Apl => "APL",
+            Lang::J => "J",
+            Lang::Bqn => "BQN",
+        }
+    }
+    fn functions(self) -> &'static [FuncDef] {
+        match self {
+            Lang::Apl => APL_FUNCTIONS,
+            Lang::J => J_FUNCTIONS,
+            Lang::Bqn => BQN_FUNCTIONS,
+        }
+    }
+    fn modifiers(self) -> &'static [(&'static str, Modifier)] {
+        match self {
+            Lang::Apl => APL_MODIFIERS,
+            Lang::J => J_MODIFIERS,
+            Lang::Bqn => BQN_MODIFIERS,
+        }
+    }
+    fn comment(self) -> &'static str {
+        match self {
+            Lang::Apl => "⍝",
+            Lang::J => "NB.",
+            Lang::Bqn => "#",
+        }
+    }
+    fn negative(self) -> char {
+        match self {
+            Lang::J => '_',
+            _ => '¯',
+        }
+    }
+}
+
+/// Whether a literal is a single number or character
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    Num,
+    Char,
+}
+
+/// Translated code that pushes a single array
+struct Noun {
+    code: String,
+    scalar: Option<Scalar>,
+    /// Whether the code is a single literal that does not need parentheses
+    atomic: bool,
+}
+
+impl Noun {
+    /// Make a list from some arrays
+    fn list(nouns: Vec<Noun>) -> Noun {
+        let kind = nouns.first().and_then(|n| n.scalar);
+        let code = match kind {
+            _ if nouns.is_empty() => "[]".into(),
+            Some(kind) if nouns.iter().any(|n| n.scalar != Some(kind)) => boxed(nouns),
+            Some(Scalar::Char) => {
+                let s: String = nouns.iter().map(|n| unescape_char(&n.code)).collect();
+                uiua_string(&s)
+            }
+            Some(Scalar::Num) => {
+                let items: Vec<&str> = nouns.iter().map(|n| n.code.as_str()).collect();
+                format!("[{}]", items.join(" "))
+            }
+            None => boxed(nouns),
+        };
+        Noun {
+            code,
+            scalar: None,
+            atomic: true,
+        }
+    }
+    /// Make a strand, which is just the array if there is only one
+    fn strand(mut nouns: Vec<Noun>) -> Noun {
+        if nouns.len() == 1 {
+            nouns.pop().unwrap()
+        } else {
+            Noun::list(nouns)
+        }
+    }
+}
+
+fn boxed(nouns: Vec<Noun>) -> String {
+    let items: Vec<String> = (nouns.into_iter())
+        .map(|n| if n.atomic { n.code } else { group(&n.code) })
+        .collect();
+    format!("{{{}}}", items.join(" "))
+}
+
+/// A dyadic translation
+#[derive(Clone)]
+struct Dyad {
+    code: String,
+    flip: bool,
+}
+
+impl Dyad {
+    /// The code as a function that expects the left argument on top of the stack
+    fn left_on_top(&self) -> String {
+        if self.flip {
+            format!("{}:", self.code)
+        } else {
+            self.code.clone()
+        }
+    }
+    /// The code as a function that expects the right argument on top of the stack
+    fn right_on_top(&self) -> String {
+        if self.flip {
+            self.code.clone()
+        } else {
+            format!("{}:", self.code)
+        }
+    }
+}
+
+/// A translated function
+#[derive(Clone)]
+struct Func {
+    /// The source code of the function, for error messages
+    name: String,
+    monad: Option<String>,
+    dyad: Option<Dyad>,
+    /// Whether the dyadic function is associative and commutative,
+    /// so folds do not need to preserve the order of the arguments
+    assoc: bool,
+    /// The scan for an insert, for J's prefix adverb
+    scan: Option<String>,
+}
+
+impl Func {
+    fn new(&(name, monad, dyad): &FuncDef) -> Self {
+        Func {
+            name: name.into(),
+            monad: monad.map(Into::into),
+            dyad: dyad.map(|(code, flip)| Dyad {
+                code: code.into(),
+                flip,
+            }),
+            assoc: matches!(dyad, Some(("+" | "×" | "↥" | "↧", _))),
+            scan: None,
+        }
+    }
+    fn derived(&self, name: &str) -> Self {
+        Func {
+            name: format!("{}{name}", self.name),
+            monad: None,
+            dyad: None,
+            assoc: false,
+            scan: None,
+        }
+    }
+    fn dyad(&self) -> Result<&Dyad, String> {
+        (self.dyad.as_ref()).ok_or_else(|| format!("Dyadic `{}` cannot be translated", self.name))
+    }
+    fn monad(&self) -> Result<&str, String> {
+        (self.monad.as_deref())
+            .ok_or_else(|| format!("Monadic `{}` cannot be translated", self.name))
+    }
+    fn call_monad(&self, x: Noun) -> Result<Noun, String> {
+        Ok(Noun {
+            code: join(&[self.monad()?, &x.code]),
+            scalar: None,
+            atomic: false,
+        })
+    }
+    fn call_dyad(&self, a: Noun, b: Noun) -> Result<Noun, String> {
+        let dyad = self.dyad()?;
+        let (first, second) = if dyad.flip { (b, a) } else { (a, b) };
+        Ok(Noun {
+            code: join(&[&dyad.code, &first.code, &second.code]),
+            scalar: None,
+            atomic: false,
+        })
+    }
+    fn modify(&self, glyph: &str, modifier: Modifier) -> Result<Self, String> {
+        let mut derived = self.derived(glyph);
+        match modifier {
+            Modifier::Reduce { last_axis, table } => {
+                let dyad = self.dyad()?;
+                let axis = if last_axis { "°⍉" } else { "" };
+                // Uiua's reduce is a left fold, so the list is reversed
+                // unless the order of the arguments does not matter
+                derived.monad = Some(if self.assoc {
+                    format!("/{}{axis}", group(&dyad.code))
+                } else {
+                    format!("/{}⇌{axis}", group(&dyad.right_on_top()))
+                });
+                if table {
+                    derived.dyad = Some(Dyad {
+                        code: format!("⊞{}", group(&dyad.left_on_top())),
+                        flip: false,
+                    });
+                }
+                if self.assoc {
+                    derived.scan = Some(format!("\\{}", group(&dyad.code)));
+                }
+            }
+            Modifier::Scan { last_axis, left } => {
+                let dyad = self.dyad()?;
+                let op = if left {
+                    group(&dyad.left_on_top())
+                } else if self.assoc {
+                    group(&dyad.code)
+                } else {
+                    return Err(format!(
+                        "`{}` cannot be translated because `{}` is not associative",
+                        derived.name, self.name
+                    ));
+                };
+                derived.monad = Some(if last_axis {
+                    format!("⍉\\{op}°⍉")
+                } else {
+                    format!("\\{op}")
+                });
+            }
+            Modifier::Prefix => {
+                derived.monad = Some(self.scan.clone().ok_or_else(|| {
+                    format!(
+                        "`{}` can only be translated if it is an insert \
+                        with an associative function",
+                        derived.name
+                    )
+                })?);
+            }
+            Modifier::Each => {
+                derived.monad = (self.monad.as_ref()).map(|monad| format!("∵{}", group(monad)));
+                derived.dyad = self.dyad.as_ref().map(|dyad| Dyad {
+                    code: format!("∵{}", group(&dyad.left_on_top())),
+                    flip: false,
+                });
+            }
+            Modifier::Commute => {
+                derived.monad = (self.dyad.as_ref()).map(|dyad| format!("{}.", group(&dyad.code)));
+                derived.dyad = self.dyad.as_ref().map(|dyad| Dyad {
+                    code: dyad.code.clone(),
+                    flip: !dyad.flip,
+                });
+            }
+            Modifier::Table => {
+                derived.dyad = Some(Dyad {
+                    code: format!("⊞{}", group(&self.dyad()?.left_on_top())),
+                    flip: false,
+                });
+            }
+        }
+        Ok(derived)
+    }
+}
+
+enum Token {
+    Noun(Noun),
+    Func(Func),
+    Modifier(&'static str, Modifier),
+    /// APL's outer product
+    Outer,
+    Open,
+    Close,
+    ListOpen,
+    ListClose,
+    /// A statement or list item separator
+    Sep,
+    /// BQN's strand ligature
+    Ligature,
+}
+
+fn tokenize(lang: Lang, src: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = src;
+    while let Some(c) = rest.chars().next() {
+        if c == '\n' || c == '⋄' || matches!(lang, Lang::Bqn) && c == ',' {
+            tokens.push(Token::Sep);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if rest.starts_with(lang.comment()) {
+            rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+            continue;
+        }
+        // Numbers
+        let after = rest[c.len_utf8()..].chars().next();
+        let starts_number = c.is_ascii_digit()
+            || c == lang.negative() && after.is_some_and(|c| c.is_ascii_digit() || c == '∞')
+            || matches!(lang, Lang::J) && c == '_'
+            || matches!(lang, Lang::Bqn) && matches!(c, 'π' | '∞');
+        if starts_number {
+            let end = (rest.char_indices())
+                .find(|&(_, c)| {
+                    !(c.is_ascii_digit()
+                        || matches!(c, '.' | 'e' | 'E' | 'π' | '∞')
+                        || c == lang.negative())
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            tokens.push(Token::Noun(number(lang, &rest[..end])?));
+            rest = &rest[end..];
+            continue;
+        }
+        // Strings and characters
+        let quote = match lang {
+            Lang::Bqn if c == '"' => Some('"'),
+            Lang::Bqn if c == '\'' => {
+                let mut chars = rest.chars().skip(1);
+                match (chars.next(), chars.next()) {
+                    (Some(ch), Some('\'')) => {
+                        tokens.push(Token::Noun(Noun {
+                            code: uiua_char(ch),
+                            scalar: Some(Scalar::Char),
+                            atomic: true,
+                        }));
+                        rest = &rest[2 + ch.len_utf8()..];
+                        continue;
+                    }
+                    _ => return Err("Unterminated BQN character literal".into()),
+                }
+            }
+            Lang::Apl | Lang::J if c == '\'' => Some('\''),
+            _ => None,
+        };
+        if let Some(quote) = quote {
+            let mut s = String::new();
+            let mut chars = rest.char_indices().skip(1).peekable();
+            let end = loop {
+                match chars.next() {
+                    Some((_, ch)) if ch == quote => {
+                        if chars.peek().is_some_and(|&(_, ch)| ch == quote) {
+                            chars.next();
+                            s.push(quote);
+                        } else {
+                            break chars.peek().map_or(rest.len(), |&(i, _)| i);
+                        }
+                    }
+                    Some((_, ch)) => s.push(ch),
+                    None => return Err(format!("Unterminated {} string", lang.name())),
+                }
+            };
+            let mut chars = s.chars();
+            // A single character in quotes is a scalar in APL and J
+            tokens.push(Token::Noun(match (quote, chars.next(), chars.next()) {
+                ('\'', Some(ch), None) => Noun {
+                    code: uiua_char(ch),
+                    scalar: Some(Scalar::Char),
+                    atomic: true,
+                },
+                _ => Noun {
+                    code: uiua_string(&s),
+                    scalar: None,
+                    atomic: true,
+                },
+            }));
+            rest = &rest[end..];
+            continue;
+        }
+        // Punctuation
+        let punct = match (lang, c) {
+            (_, '(') => Some(Token::Open),
+            (_, ')') => Some(Token::Close),
+            (Lang::Bqn, '⟨') => Some(Token::ListOpen),
+            (Lang::Bqn, '⟩') => Some(Token::ListClose),
+            (Lang::Bqn, '‿') => Some(Token::Ligature),
+            _ => None,
+        };
+        if let Some(token) = punct {
+            tokens.push(token);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if matches!(lang, Lang::Apl) && c == '⍬' {
+            tokens.push(Token::Noun(Noun {
+                code: "[]".into(),
+                scalar: None,
+                atomic: true,
+            }));
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if matches!(lang, Lang::Apl) && rest.starts_with("∘.") {
+            tokens.push(Token::Outer);
+            rest = &rest["∘.".len()..];
+            continue;
+        }
+        // Functions and modifiers, preferring the longest match
+        let func = (lang.functions().iter())
+            .filter(|def| rest.starts_with(def.0))
+            .max_by_key(|def| def.0.len());
+        let modifier = (lang.modifiers().iter())
+            .filter(|(glyph, _)| rest.starts_with(glyph))
+            .max_by_key(|(glyph, _)| glyph.len());
+        match (func, modifier) {
+            (Some(def), Some((glyph, _))) if def.0.len() >= glyph.len() => {
+                tokens.push(Token::Func(Func::new(def)));
+                rest = &rest[def.0.len()..];
+            }
+            (_, Some((glyph, modifier))) => {
+                tokens.push(Token::Modifier(glyph, *modifier));
+                rest = &rest[glyph.len()..];
+            }
+            (Some(def), None) => {
+                tokens.push(Token::Func(Func::new(def)));
+                rest = &rest[def.0.len()..];
+            }
+            (None, None) if c.is_alphabetic() || c == '_' || c == '𝕩' || c == '𝕨' => {
+                return Err(format!(
+                    "Names are not supported in translated {} code",
+                    lang.name()
+                ))
+            }
+            (None, None) => {
+                return Err(format!(
+                    "{} glyph `{c}` is unknown or cannot be translated",
+                    lang.name()
+                ))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn number(lang: Lang, text: &str) -> Result<Noun, String> {
+    let code = match (lang, text) {
+        (Lang::J, "_") => "∞".into(),
+        (Lang::J, "__") => "¯∞".into(),
+        _ => {
+            let code = text.replace(lang.negative(), "¯").replace('E', "e");
+            let valid = match code.trim_start_matches('¯') {
+                "π" | "∞" => true,
+                n => n.replace('¯', "-").parse::<f64>().is_ok(),
+            };
+            if !valid {
+                return Err(format!("Invalid {} number `{text}`", lang.name()));
+            }
+            code
+        }
+    };
+    Ok(Noun {
+        code,
+        scalar: Some(Scalar::Num),
+        atomic: true,
+    })
+}
+
+enum Item {
+    Noun(Noun),
+    Func(Func),
+}
+
+struct Parser {
+    lang: Lang,
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    /// Parse items until the end of a statement, parenthesized expression, or list item
+    ///
+    /// Adjacent arrays are collected into strands.
+    fn items(&mut self) -> Result<Vec<Item>, String> {
+        let mut items: Vec<Item> = Vec::new();
+        let mut strand: Vec<Noun> = Vec::new();
+        while let Some(token) = self
+            .tokens
+            .next_if(|token| !matches!(token, Token::Sep | Token::Close | Token::ListClose))
+        {
+            let item = match token {
+                Token::Noun(noun) => Item::Noun(noun),
+                Token::Open => {
+                    let inner = self.items()?;
+                    if !matches!(self.tokens.next(), Some(Token::Close)) {
+                        return Err("Unclosed parenthesis".into());
+                    }
+                    match <[Item; 1]>::try_from(inner) {
+                        Ok([Item::Func(f)]) => Item::Func(f),
+                        Ok([item]) => Item::Noun(evaluate(vec![item])?),
+                        Err(inner) => Item::Noun(evaluate(inner)?),
+                    }
+                }
+                Token::ListOpen => {
+                    let mut nouns = Vec::new();
+                    loop {
+                        let inner = self.items()?;
+                        if !inner.is_empty() {
+                            nouns.push(evaluate(inner)?);
+                        }
+                        match self.tokens.next() {
+                            Some(Token::Sep) => {}
+                            Some(Token::ListClose) => break,
+                            _ => return Err("Unclosed list".into()),
+                        }
+                    }
+                    Item::Noun(Noun::list(nouns))
+                }
+                // Arrays next to each other are already stranded
+                Token::Ligature if !strand.is_empty() => continue,
+                Token::Ligature => return Err("`‿` must follow an array".into()),
+                Token::Func(f) => Item::Func(f),
+                Token::Outer => match self.tokens.next() {
+                    Some(Token::Func(f)) => {
+                        let mut table = f.modify("", Modifier::Table)?;
+                        table.name = format!("∘.{}", f.name);
+                        Item::Func(table)
+                    }
+                    _ => return Err("`∘.` must be followed by a function".into()),
+                },
+                Token::Modifier(glyph, modifier) => match items.last_mut() {
+                    Some(Item::Func(f)) if strand.is_empty() => {
+                        *f = f.modify(glyph, modifier)?;
+                        continue;
+                    }
+                    _ => {
+                        let def = (matches!(self.lang, Lang::Apl))
+                            .then(|| APL_MODIFIER_FUNCTIONS.iter().find(|def| def.0 == glyph))
+                            .flatten()
+                            .ok_or_else(|| format!("`{glyph}` must follow a function"))?;
+                        Item::Func(Func::new(def))
+                    }
+                },
+                Token::Sep | Token::Close | Token::ListClose => unreachable!(),
+            };
+            match item {
+                Item::Noun(noun) => strand.push(noun),
+                Item::Func(f) => {
+                    if !strand.is_empty() {
+                        items.push(Item::Noun(Noun::strand(take(&mut strand))));
+                    }
+                    items.push(Item::Func(f));
+                }
+            }
+        }
+        if !strand.is_empty() {
+            items.push(Item::Noun(Noun::strand(strand)));
+        }
+        Ok(items)
+    }
+}
+
+/// Apply functions from right to left
+fn evaluate(mut items: Vec<Item>) -> Result<Noun, String> {
+    let mut right = match items.pop() {
+        Some(Item::Noun(noun)) => noun,
+        Some(Item::Func(f)) if items.is_empty() => {
+            // A lone function translates to its monadic form if it has one
+            let code = match (&f.monad, &f.dyad) {
+                (Some(monad), _) => monad.clone(),
+                (None, Some(dyad)) => dyad.left_on_top(),
+                (None, None) => return Err(format!("`{}` cannot be translated", f.name)),
+            };
+            return Ok(Noun {
+                code,
+                scalar: None,
+                atomic: false,
+            });
+        }
+        Some(Item::Func(_)) => return Err("Trains cannot be translated".into()),
+        None => return Err("Expected an expression".into()),
+    };
+    while let Some(item) = items.pop() {
+        let Item::Func(f) = item else {
+            return Err("Expected a function between arrays".into());
+        };
+        right = match items.pop() {
+            Some(Item::Noun(left)) => f.call_dyad(left, right)?,
+            prev => {
+                items.extend(prev);
+                f.call_monad(right)?
+            }
+        };
+    }
+    Ok(right)
+}
+
+/// Translate code to Uiua
+fn translate_code(lang: Lang, src: &str) -> Result<String, String> {
+    let mut parser = Parser {
+        lang,
+        tokens: tokenize(lang, src)?.into_iter().peekable(),
+    };
+    let mut lines = Vec::new();
+    loop {
+        let items = parser.items()?;
+        if !items.is_empty() {
+            lines.push(evaluate(items)?.code);
+        }
+        match parser.tokens.next() {
+            Some(Token::Sep) => {}
+            None => break,
+            Some(_) => return Err("Unmatched closing bracket".into()),
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn translate(env: &mut Uiua) -> UiuaResult {
+    let lang = env.pop(1)?.as_string(env, "Language must be a string")?;
+    let code = env
+        .pop(2)?
+        .as_string(env, "Code to translate must be a string")?;
+    let lang = Lang::from_name(&lang).ok_or_else(|| {
+        env.error(format!(
+            "Unknown {} language {lang:?}. \
+            Valid languages are \"apl\", \"j\", and \"bqn\".",
+            Primitive::Translate
+        ))
+    })?;
+    let translated = translate_code(lang, &code).map_err(|e| env.error(e))?;
+    env.push(translated);
+    Ok(())
+}
+
+/// Wrap code in parentheses if it is more than one glyph
+fn group(code: &str) -> String {
+    if code.chars().count() == 1 {
+        code.into()
+    } else {
+        format!("({code})")
+    }
+}
+
+/// Join pieces of code, adding spaces only where they are needed to keep tokens apart
+fn join(pieces: &[&str]) -> String {
+    let wordy = |c: char| c.is_ascii_alphanumeric() || "¯.∞π_@\"".contains(c);
+    let mut code = String::new();
+    for piece in pieces {
+        let needs_space = (code.chars().last())
+            .zip(piece.chars().next())
+            .is_some_and(|(a, b)| wordy(a) && wordy(b));
+        if needs_space {
+            code.push(' ');
+        }
+        code.push_str(piece);
+    }
+    code
+}
+
+fn escape(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\\' => "\\\\",
+        '"' => "\\\"",
+        '\n' => "\\n",
+        '\t' => "\\t",
+        '\r' => "\\r",
+        _ => return None,
+    })
+}
+
+fn uiua_string(s: &str) -> String {
+    let mut code = String::from('"');
+    for c in s.chars() {
+        match escape(c) {
+            Some(escaped) => code.push_str(escaped),
+            None => code.push(c),
+        }
+    }
+    code.push('"');
+    code
+}
+
+fn uiua_char(c: char) -> String {
+    match c {
+        '"' => "@\"".into(),
+        c => format!("@{}", escape(c).unwrap_or(&c.to_string())),
+    }
+}
+
+fn unescape_char(code: &str) -> char {
+    match code {
+        "@\\\\" => '\\',
+        "@\\n" => '\n',
+        "@\\t" => '\t',
+        "@\\r" => '\r',
+        _ => code.chars().nth(1).unwrap_or(' '),
+    }
+}
@@ -0,0 +1,223 @@
+//! URL parsing and percent-encoding
+
+use ecow::EcoVec;
+
+use crate::{Array, Boxed, Uiua, UiuaResult, Value};
+
+/// Characters that never need to be percent-encoded
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~".contains(&b)
+}
+
+/// Characters that can appear unencoded in a path segment
+fn is_path_safe(b: u8) -> bool {
+    is_unreserved(b) || b"!$&'()*+,;=:@".contains(&b)
+}
+
+fn encode(s: &str, safe: fn(u8) -> bool, encoded: &mut String) {
+    for &b in s.as_bytes() {
+        if safe(b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+}
+
+fn decode(s: &str, plus_is_space: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes.get(i + 1..i + 3))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+                continue;
+            }
+            (b'+', _) if plus_is_space => decoded.push(b' '),
+            (b, _) => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl Value {
+    pub(crate) fn percent_encode(&self, env: &Uiua) -> UiuaResult<String> {
+        let s = self.as_string(env, "Percent-encoding expects a string")?;
+        let mut encoded = String::with_capacity(s.len());
+        encode(&s, is_unreserved, &mut encoded);
+        Ok(encoded)
+    }
+    pub(crate) fn percent_decode(&self, env: &Uiua) -> UiuaResult<String> {
+        let s = self.as_string(env, "Percent-decoding expects a string")?;
+        Ok(decode(&s, false))
+    }
+    pub(crate) fn from_url_string(url: &str, env: &Uiua) -> UiuaResult<Self> {
+        let (rest, fragment) = url.split_once('#').unwrap_or((url, ""));
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        // A scheme must start with a letter and come before any slash
+        let (scheme, rest) = match rest.split_once(':') {
+            Some((scheme, rest))
+                if scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && (scheme.chars()).all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+            {
+                (scheme, rest)
+            }
+            _ => ("", rest),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => rest.split_at(rest.find('/').unwrap_or(rest.len())),
+            None => ("", rest),
+        };
+        let (user, host_port) = authority.rsplit_once('@').unwrap_or(("", authority));
+        let (host, port) = match host_port.rfind(':') {
+            Some(i) if !host_port[i..].contains(']') => (&host_port[..i], &host_port[i + 1..]),
+            _ => (host_port, ""),
+        };
+        let port = if port.is_empty() {
+            f64::NAN
+        } else {
+            port.parse::<u16>()
+                .map_err(|_| env.error(format!("Invalid URL port {port:?}")))?
+                .into()
+        };
+        let path: Value = if path.is_empty() {
+            EcoVec::<Boxed>::new().into()
+        } else {
+            (path.split('/'))
+                .map(|segment| Boxed(decode(segment, false).into()))
+                .collect::<EcoVec<_>>()
+                .into()
+        };
+        let mut query_keys = EcoVec::new();
+        let mut query_values = EcoVec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            query_keys.push(Boxed(decode(key, true).into()));
+            query_values.push(Boxed(decode(value, true).into()));
+        }
+        let mut query: Value = Array::from(query_values).into();
+        query.map(Array::from(query_keys).into(), env)?;
+        let keys: Value = [
+            "scheme", "user", "host", "port", "path", "query", "fragment",
+        ]
+        .into_iter()
+        .map(|key| Boxed(key.into()))
+        .collect::<Array<_>>()
+        .into();
+        let mut url: Value = Array::from(EcoVec::from([
+            Boxed(scheme.into()),
+            Boxed(decode(user, false).into()),
+            Boxed(host.into()),
+            Boxed(port.into()),
+            Boxed(path),
+            Boxed(query),
+            Boxed(decode(fragment, false).into()),
+        ]))
+        .into();
+        url.map(keys, env)?;
+        Ok(url)
+    }
+    pub(crate) fn to_url_string(&self, env: &Uiua) -> UiuaResult<String> {
+        if !self.is_map() {
+            return Err(env.error("URL must be a map"));
+        }
+        let string = |val: &Value, requirement| val.as_string(env, requirement);
+        let (mut scheme, mut user, mut host, mut fragment) = Default::default();
+        let (mut port, mut path, mut query) = (None, None, None);
+        for (key, value) in self.map_kv() {
+            let value = value.unboxed();
+            match key
+                .unboxed()
+                .as_string(env, "URL keys must be strings")?
+                .as_str()
+            {
+                "scheme" => scheme = string(&value, "URL scheme must be a string")?,
+                "user" => user = string(&value, "URL user must be a string")?,
+                "host" => host = string(&value, "URL host must be a string")?,
+                "port" => {
+                    let n = value.as_num(env, "URL port must be a number")?;
+                    port = (!n.is_nan()).then_some(n);
+                }
+                "path" => path = Some(value),
+                "query" => query = Some(value),
+                "fragment" => fragment = string(&value, "URL fragment must be a string")?,
+                key => return Err(env.error(format!("Unknown URL key {key:?}"))),
+            }
+        }
+        let mut url = String::new();
+        if !scheme.is_empty() {
+            url.push_str(&scheme);
+            url.push(':');
+        }
+        if !host.is_empty() || !user.is_empty() || port.is_some() || scheme == "file" {
+            url.push_str("//");
+            if !user.is_empty() {
+                encode(
+                    &user,
+                    |b| is_unreserved(b) || b":!$&'()*+,;=".contains(&b),
+                    &mut url,
+                );
+                url.push('@');
+            }
+            url.push_str(&host);
+            if let Some(port) = port {
+                url.push_str(&format!(":{port}"));
+            }
+        }
+        if let Some(path) = path {
+            let segments: Vec<String> = match &path {
+                Value::Char(arr) if arr.rank() <= 1 => {
+                    (string(&path, "URL path must be a string")?.split('/'))
+                        .map(Into::into)
+                        .collect()
+                }
+                _ => (path.rows())
+                    .map(|segment| string(&segment.unboxed(), "URL path segments must be strings"))
+                    .collect::<UiuaResult<_>>()?,
+            };
+            for (i, segment) in segments.iter().enumerate() {
+                if i > 0 {
+                    url.push('/');
+                }
+                encode(segment, is_path_safe, &mut url);
+            }
+        }
+        if let Some(query) = query.filter(|query| query.row_count() > 0) {
+            if !query.is_map() {
+                return Err(env.error("URL query must be a map"));
+            }
+            for (i, (key, value)) in query.map_kv().into_iter().enumerate() {
+                url.push(if i == 0 { '?' } else { '&' });
+                let key = key.unboxed();
+                encode(
+                    &string(&key, "URL query keys must be strings")?,
+                    is_unreserved,
+                    &mut url,
+                );
+                let value = value.unboxed();
+                let value = match &value {
+                    Value::Char(_) => string(&value, "URL query values must be strings")?,
+                    value if value.rank() == 0 => value.format(),
+                    _ => return Err(env.error("URL query values must be strings or scalars")),
+                };
+                url.push('=');
+                encode(&value, is_unreserved, &mut url);
+            }
+        }
+        if !fragment.is_empty() {
+            url.push('#');
+            encode(
+                &fragment,
+                |b| is_path_safe(b) || b"/?".contains(&b),
+                &mut url,
+            );
+        }
+        Ok(url)
+    }
+}
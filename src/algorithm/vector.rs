@@ -0,0 +1,178 @@
+//! Algorithms for geometric vector operations
+//!
+//! Vectors are always along the last axis of an array.
+
+use ecow::{eco_vec, EcoVec};
+use rayon::prelude::*;
+
+use crate::{Array, Shape, Uiua, UiuaResult, Value};
+
+fn real_vectors(val: &Value, env: &Uiua, requirement: &'static str) -> UiuaResult<Array<f64>> {
+    val.as_number_array(env, requirement, |_| true, |_| true, |n| n)
+}
+
+/// The length of the vectors in an array and the shape of the array without the vector axis
+fn split_vectors(shape: &Shape) -> (usize, Shape) {
+    let mut outer_shape = shape.clone();
+    let len = outer_shape.pop().unwrap_or(1);
+    (len, outer_shape)
+}
+
+pub fn cross(env: &mut Uiua) -> UiuaResult {
+    let a = env.pop(1)?;
+    let b = env.pop(2)?;
+    let a = real_vectors(&a, env, "Cross product arguments must be real numbers")?;
+    let b = real_vectors(&b, env, "Cross product arguments must be real numbers")?;
+    let len = a.shape.last().copied().unwrap_or(0);
+    if a.rank() == 0 || b.rank() == 0 || !matches!(len, 2 | 3) || b.shape.last() != Some(&len) {
+        return Err(env.error(format!(
+            "Cross product arguments must both be vectors of length 2 or 3, \
+            but their shapes are {} and {}",
+            a.shape, b.shape
+        )));
+    }
+    // A single vector is crossed with every vector in the other array
+    let shape = if a.shape == b.shape || b.rank() == 1 {
+        a.shape.clone()
+    } else if a.rank() == 1 {
+        b.shape.clone()
+    } else {
+        return Err(env.error(format!(
+            "Cannot get the cross product of arrays with shapes {} and {}",
+            a.shape, b.shape
+        )));
+    };
+    let (_, mut outer_shape) = split_vectors(&shape);
+    let count = outer_shape.elements();
+    let a_step = if a.rank() == 1 { 0 } else { len };
+    let b_step = if b.rank() == 1 { 0 } else { len };
+    let pairs = (0..count).map(|i| (&a.data[i * a_step..][..len], &b.data[i * b_step..][..len]));
+    let data: EcoVec<f64> = if len == 3 {
+        let mut data = EcoVec::with_capacity(count * 3);
+        for (a, b) in pairs {
+            data.extend([
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]);
+        }
+        outer_shape.push(3);
+        data
+    } else {
+        pairs.map(|(a, b)| a[0] * b[1] - a[1] * b[0]).collect()
+    };
+    env.push(Array::new(outer_shape, data));
+    Ok(())
+}
+
+pub fn norm(env: &mut Uiua) -> UiuaResult {
+    let order = env.pop(1)?.as_num(env, "Norm order must be a number")?;
+    if order.is_nan() || order <= 0.0 {
+        return Err(env.error(format!("Norm order must be positive, but it is {order}")));
+    }
+    let arr = match env.pop(2)? {
+        Value::Complex(arr) => arr.convert_with(|c| c.abs()),
+        val => real_vectors(&val, env, "Norm argument must be numbers")?,
+    };
+    let (len, shape) = split_vectors(&arr.shape);
+    let data: EcoVec<f64> = (0..shape.elements())
+        .map(|i| {
+            let vector = arr.data[i * len..][..len].iter().map(|n| n.abs());
+            if order == 1.0 {
+                vector.sum()
+            } else if order == 2.0 {
+                vector.map(|n| n * n).sum::<f64>().sqrt()
+            } else if order == f64::INFINITY {
+                vector.fold(0.0, f64::max)
+            } else {
+                vector
+                    .map(|n| n.powf(order))
+                    .sum::<f64>()
+                    .powf(order.recip())
+            }
+        })
+        .collect();
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+pub fn normalize(env: &mut Uiua) -> UiuaResult {
+    let val = env.pop(1)?;
+    let mut arr = real_vectors(&val, env, "Normalize argument must be real numbers")?;
+    let (len, _) = split_vectors(&arr.shape);
+    if len > 0 {
+        for vector in arr.data.as_mut_slice().chunks_exact_mut(len) {
+            let norm = vector.iter().map(|n| n * n).sum::<f64>().sqrt();
+            if norm != 0.0 {
+                for n in vector {
+                    *n /= norm;
+                }
+            }
+        }
+    }
+    env.push(arr);
+    Ok(())
+}
+
+/// The number of vectors from each argument in a block of the distance kernel
+///
+/// Blocks of this size keep the vectors of the inner loop in cache.
+const DISTANCE_BLOCK: usize = 64;
+
+pub fn distances(env: &mut Uiua) -> UiuaResult {
+    let a = env.pop(1)?;
+    let b = env.pop(2)?;
+    let a = real_vectors(&a, env, "Distance arguments must be real numbers")?;
+    let b = real_vectors(&b, env, "Distance arguments must be real numbers")?;
+    for arr in [&a, &b] {
+        if !matches!(arr.rank(), 1 | 2) {
+            return Err(env.error(format!(
+                "Distance arguments must be vectors or lists of vectors, \
+                but one has shape {}",
+                arr.shape
+            )));
+        }
+    }
+    let len = a.shape[a.rank() - 1];
+    if b.shape[b.rank() - 1] != len {
+        return Err(env.error(format!(
+            "Cannot get distances between vectors of length {len} and {}",
+            b.shape[b.rank() - 1]
+        )));
+    }
+    let a_count = if a.rank() == 2 { a.shape[0] } else { 1 };
+    let b_count = if b.rank() == 2 { b.shape[0] } else { 1 };
+    let mut data = eco_vec![0.0; a_count * b_count];
+    if !data.is_empty() {
+        let kernel = |(block, out): (usize, &mut [f64])| {
+            let a_start = block * DISTANCE_BLOCK;
+            let a_rows = out.len() / b_count;
+            for b_start in (0..b_count).step_by(DISTANCE_BLOCK) {
+                let b_end = (b_start + DISTANCE_BLOCK).min(b_count);
+                for i in 0..a_rows {
+                    let x = &a.data[(a_start + i) * len..][..len];
+                    for j in b_start..b_end {
+                        let y = &b.data[j * len..][..len];
+                        let sum: f64 = x.iter().zip(y).map(|(x, y)| (x - y) * (x - y)).sum();
+                        out[i * b_count + j] = sum.sqrt();
+                    }
+                }
+            }
+        };
+        let blocks = data.make_mut().chunks_mut(DISTANCE_BLOCK * b_count);
+        if a_count > DISTANCE_BLOCK {
+            blocks.enumerate().par_bridge().for_each(kernel);
+        } else {
+            blocks.enumerate().for_each(kernel);
+        }
+    }
+    let mut shape = Shape::from([a_count, b_count]);
+    if b.rank() == 1 {
+        shape.remove(1);
+    }
+    if a.rank() == 1 {
+        shape.remove(0);
+    }
+    env.push(Array::new(shape, data));
+    Ok(())
+}
@@ -0,0 +1,270 @@
+//! Fused chains of strided operations
+//!
+//! [`transpose`], [`reverse`], [`first`], and [`last`] only change the shape,
+//! strides, and offset used to index an array's data, so when the compiler finds
+//! adjacent ones, it fuses them into a [`ViewChain`] that copies only the needed
+//! elements once rather than copying the whole array for each operation.
+//!
+//! Arrays themselves are always stored contiguously. A chain is only fused at
+//! compile time, and its result is a new array, so a lone transpose still copies.
+//!
+//! [`transpose`]: crate::Primitive::Transpose
+//! [`reverse`]: crate::Primitive::Reverse
+//! [`first`]: crate::Primitive::First
+//! [`last`]: crate::Primitive::Last
+
+use std::fmt;
+
+use ecow::EcoVec;
+use serde::*;
+
+use crate::{
+    cowslice::CowSlice, Array, ArrayValue, ImplPrimitive, Instr, Primitive, Shape, Uiua,
+    UiuaResult, Value,
+};
+
+/// An operation that can be applied to a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ViewOp {
+    Transpose,
+    UnTranspose,
+    Reverse,
+    First,
+    Last,
+}
+
+/// The maximum number of operations in a [`ViewChain`]
+const MAX_VIEW_OPS: usize = 8;
+
+/// A sequence of view operations, in the order they are applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ViewChain {
+    ops: [ViewOp; MAX_VIEW_OPS],
+    len: u8,
+}
+
+impl ViewChain {
+    /// Create a chain from operations
+    ///
+    /// Returns `None` if there are too many operations
+    pub fn new(ops: impl IntoIterator<Item = ViewOp>) -> Option<Self> {
+        let mut chain = ViewChain {
+            ops: [ViewOp::Transpose; MAX_VIEW_OPS],
+            len: 0,
+        };
+        for op in ops {
+            chain.push(op)?;
+        }
+        Some(chain)
+    }
+    /// Add an operation to the end of the chain
+    ///
+    /// Returns `None` if the chain is full
+    pub fn push(&mut self, op: ViewOp) -> Option<()> {
+        if let (ViewOp::First, Some(prev @ ViewOp::Reverse)) = (op, self.last_mut()) {
+            *prev = ViewOp::Last;
+            return Some(());
+        }
+        *self.ops.get_mut(self.len as usize)? = op;
+        self.len += 1;
+        Some(())
+    }
+    /// The operations in the chain
+    pub fn ops(&self) -> &[ViewOp] {
+        &self.ops[..self.len as usize]
+    }
+    fn last_mut(&mut self) -> Option<&mut ViewOp> {
+        self.ops[..self.len as usize].last_mut()
+    }
+    /// Whether the chain does anything other than transpose
+    pub fn is_transpose_only(&self) -> bool {
+        (self.ops().iter()).all(|op| matches!(op, ViewOp::Transpose | ViewOp::UnTranspose))
+    }
+}
+
+impl ViewOp {
+    /// The instruction that performs this operation on its own
+    pub(crate) fn instr(self, span: usize) -> Instr {
+        match self {
+            ViewOp::Transpose => Instr::Prim(Primitive::Transpose, span),
+            ViewOp::UnTranspose => Instr::ImplPrim(ImplPrimitive::TransposeN(-1), span),
+            ViewOp::Reverse => Instr::Prim(Primitive::Reverse, span),
+            ViewOp::First => Instr::Prim(Primitive::First, span),
+            ViewOp::Last => Instr::ImplPrim(ImplPrimitive::Last, span),
+        }
+    }
+    /// Perform this operation on its own
+    pub(crate) fn run(self, env: &mut Uiua) -> UiuaResult {
+        match self {
+            ViewOp::Transpose => Primitive::Transpose.run(env),
+            ViewOp::UnTranspose => ImplPrimitive::TransposeN(-1).run(env),
+            ViewOp::Reverse => Primitive::Reverse.run(env),
+            ViewOp::First => Primitive::First.run(env),
+            ViewOp::Last => ImplPrimitive::Last.run(env),
+        }
+    }
+}
+
+impl fmt::Display for ViewChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Primitive::*;
+        for op in self.ops().iter().rev() {
+            match op {
+                ViewOp::Transpose => write!(f, "{Transpose}")?,
+                ViewOp::UnTranspose => write!(f, "{Un}{Transpose}")?,
+                ViewOp::Reverse => write!(f, "{Reverse}")?,
+                ViewOp::First => write!(f, "{First}")?,
+                ViewOp::Last => write!(f, "{First}{Reverse}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A strided view of an array's data
+struct Strided<'a, T> {
+    data: &'a CowSlice<T>,
+    shape: Shape,
+    strides: Vec<isize>,
+    offset: usize,
+}
+
+impl<'a, T: ArrayValue> Strided<'a, T> {
+    fn new(arr: &'a Array<T>) -> Self {
+        let mut strides = vec![1isize; arr.rank()];
+        for i in (0..arr.rank().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * arr.shape[i + 1] as isize;
+        }
+        Strided {
+            data: &arr.data,
+            shape: arr.shape.clone(),
+            strides,
+            offset: 0,
+        }
+    }
+    /// Apply an operation to the view
+    ///
+    /// The view must not have any empty axes
+    fn apply(&mut self, op: ViewOp) {
+        let rank = self.shape.len();
+        match op {
+            ViewOp::Transpose if rank > 1 => {
+                self.shape.rotate_left(1);
+                self.strides.rotate_left(1);
+            }
+            ViewOp::UnTranspose if rank > 1 => {
+                self.shape.rotate_right(1);
+                self.strides.rotate_right(1);
+            }
+            ViewOp::Reverse if rank > 0 => {
+                self.offset = self.index(self.shape[0] - 1, 0);
+                self.strides[0] = -self.strides[0];
+            }
+            ViewOp::First if rank > 0 => {
+                self.shape.remove(0);
+                self.strides.remove(0);
+            }
+            ViewOp::Last if rank > 0 => {
+                self.offset = self.index(self.shape[0] - 1, 0);
+                self.shape.remove(0);
+                self.strides.remove(0);
+            }
+            _ => {}
+        }
+    }
+    /// The data index of a row along an axis
+    fn index(&self, row: usize, axis: usize) -> usize {
+        (self.offset as isize + row as isize * self.strides[axis]) as usize
+    }
+    /// Whether the view is a contiguous run of its data in row-major order
+    fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for (&dim, &stride) in self.shape.iter().zip(&self.strides).rev() {
+            if dim > 1 && stride != expected {
+                return false;
+            }
+            expected *= dim as isize;
+        }
+        true
+    }
+    /// Copy the view's elements into a new array
+    fn materialize(self) -> Array<T> {
+        let len = self.shape.elements();
+        let data = if self.is_contiguous() {
+            self.data.slice(self.offset..self.offset + len)
+        } else {
+            let mut data = EcoVec::with_capacity(len);
+            self.gather(0, self.offset, &mut data);
+            data.into()
+        };
+        Array::new(self.shape, data)
+    }
+    fn gather(&self, axis: usize, offset: usize, data: &mut EcoVec<T>) {
+        let stride = self.strides[axis];
+        let dim = self.shape[axis];
+        if axis + 1 == self.shape.len() {
+            data.extend(
+                (0..dim)
+                    .map(|i| self.data[(offset as isize + i as isize * stride) as usize].clone()),
+            );
+        } else {
+            for i in 0..dim {
+                let offset = (offset as isize + i as isize * stride) as usize;
+                self.gather(axis + 1, offset, data);
+            }
+        }
+    }
+}
+
+impl<T: ArrayValue> Array<T> {
+    /// Apply a chain of view operations, copying the data at most once
+    ///
+    /// Returns `None` if the chain cannot be applied as a view
+    fn view_chain(&self, chain: ViewChain) -> Option<Self> {
+        if self.is_map() || self.shape.contains(&0) {
+            return None;
+        }
+        let mut view = Strided::new(self);
+        let mut selects = false;
+        for &op in chain.ops() {
+            view.apply(op);
+            selects |= matches!(op, ViewOp::First | ViewOp::Last);
+        }
+        let mut arr = view.materialize();
+        arr.meta = self.meta.clone();
+        if selects {
+            arr.take_label();
+        }
+        Some(arr)
+    }
+}
+
+impl Value {
+    /// Apply a chain of view operations, copying the data at most once
+    ///
+    /// Returns `None` if the chain cannot be applied as a view,
+    /// in which case the operations should be applied one at a time
+    pub(crate) fn view_chain(&self, chain: ViewChain) -> Option<Value> {
+        Some(match self {
+            Value::Num(arr) => arr.view_chain(chain)?.into(),
+            Value::Byte(arr) => arr.view_chain(chain)?.into(),
+            Value::Int(arr) => arr.view_chain(chain)?.into(),
+            Value::Float(arr) => arr.view_chain(chain)?.into(),
+            Value::Complex(arr) => arr.view_chain(chain)?.into(),
+            Value::Char(arr) => arr.view_chain(chain)?.into(),
+            Value::Box(arr) => {
+                // Operations on boxed scalars apply to the inner value,
+                // so the view must not select down to a scalar before the end
+                let ops = chain.ops();
+                let selects = (ops.iter())
+                    .filter(|op| matches!(op, ViewOp::First | ViewOp::Last))
+                    .count();
+                let ends_in_select = matches!(ops.last(), Some(ViewOp::First | ViewOp::Last));
+                if arr.rank() < selects || arr.rank() == selects && !ends_in_select {
+                    return None;
+                }
+                arr.view_chain(chain)?.into()
+            }
+        })
+    }
+}
@@ -0,0 +1,391 @@
+//! XML and HTML parsing and serialization
+
+use ecow::EcoVec;
+
+use crate::{Array, Boxed, Uiua, UiuaResult, Value};
+
+/// HTML elements that never have children or a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// HTML elements whose content is not parsed as markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// HTML elements that are implicitly closed by the start of a sibling with the same tag
+const SIBLING_CLOSED_ELEMENTS: &[&str] = &["dd", "dt", "li", "option", "p", "td", "th", "tr"];
+
+enum Node {
+    Text(String),
+    Element(Element),
+}
+
+struct Element {
+    tag: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Value {
+    pub(crate) fn from_xml_string(xml: &str, env: &Uiua) -> UiuaResult<Self> {
+        let nodes = Parser::new(xml).parse().map_err(|e| env.error(e))?;
+        nodes_value(nodes, env)
+    }
+    pub(crate) fn to_xml_string(&self, env: &Uiua) -> UiuaResult<String> {
+        let mut xml = String::new();
+        write_node(self, false, &mut xml, env)?;
+        Ok(xml)
+    }
+}
+
+fn nodes_value(nodes: Vec<Node>, env: &Uiua) -> UiuaResult<Value> {
+    let mut values = EcoVec::with_capacity(nodes.len());
+    for node in nodes {
+        values.push(Boxed(match node {
+            Node::Text(text) => text.into(),
+            Node::Element(elem) => element_value(elem, env)?,
+        }));
+    }
+    Ok(Array::from(values).into())
+}
+
+fn element_value(elem: Element, env: &Uiua) -> UiuaResult<Value> {
+    let mut attr_keys = EcoVec::with_capacity(elem.attrs.len());
+    let mut attr_values = EcoVec::with_capacity(elem.attrs.len());
+    for (key, value) in elem.attrs {
+        attr_keys.push(Boxed(key.into()));
+        attr_values.push(Boxed(value.into()));
+    }
+    let mut attrs: Value = Array::from(attr_values).into();
+    attrs.map(Array::from(attr_keys).into(), env)?;
+    let keys: Value = ["tag", "attributes", "children"]
+        .into_iter()
+        .map(|key| Boxed(key.into()))
+        .collect::<Array<_>>()
+        .into();
+    let mut value: Value = Array::from(EcoVec::from([
+        Boxed(elem.tag.into()),
+        Boxed(attrs),
+        Boxed(nodes_value(elem.children, env)?),
+    ]))
+    .into();
+    value.map(keys, env)?;
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Whether HTML rules for void and raw text elements apply
+    html: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            html: !input.trim_start().starts_with("<?xml"),
+        }
+    }
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+    fn skip_past(&mut self, end: &str, what: &str) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let i = rest
+            .find(end)
+            .ok_or_else(|| format!("Unterminated {what}"))?;
+        self.pos += i + end.len();
+        Ok(&rest[..i])
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    fn name(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "/>=\"'<".contains(c))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+    fn parse(mut self) -> Result<Vec<Node>, String> {
+        // Open elements, with the document root at the bottom
+        let mut stack = vec![Element {
+            tag: String::new(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }];
+        while self.pos < self.input.len() {
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.pos += 4;
+                self.skip_past("-->", "comment")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let text = self.skip_past("]]>", "CDATA section")?;
+                push_text(&mut stack, text.into());
+            } else if rest.starts_with("<?") || rest.starts_with("<!") {
+                self.skip_past(">", "declaration")?;
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.name();
+                self.skip_past(">", &format!("closing tag </{name}"))?;
+                // Close the matching element along with any unclosed elements inside it
+                let found = (stack.iter().skip(1)).rposition(|elem| elem.tag == name);
+                if let Some(i) = found {
+                    while stack.len() > i + 1 {
+                        close(&mut stack);
+                    }
+                }
+            } else if rest.starts_with('<')
+                && (rest[1..].chars().next()).is_some_and(|c| c.is_alphabetic() || c == '_')
+            {
+                self.pos += 1;
+                let elem = self.start_tag(&mut stack)?;
+                if let Some(elem) = elem {
+                    stack.push(elem);
+                }
+            } else {
+                let first = rest.chars().next().unwrap().len_utf8();
+                let len = rest[first..].find('<').map_or(rest.len(), |i| i + first);
+                self.pos += len;
+                let text = &rest[..len];
+                if !text.trim().is_empty() {
+                    push_text(&mut stack, decode_entities(text));
+                }
+            }
+        }
+        while stack.len() > 1 {
+            close(&mut stack);
+        }
+        Ok(stack.pop().unwrap().children)
+    }
+    /// Parse a start tag, returning the element if it is left open
+    fn start_tag(&mut self, stack: &mut Vec<Element>) -> Result<Option<Element>, String> {
+        let tag = self.name().to_string();
+        let mut attrs: Vec<(String, String)> = Vec::new();
+        let self_closing = loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                break true;
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                break false;
+            } else if rest.is_empty() {
+                return Err(format!("Unterminated tag <{tag}"));
+            }
+            let name = self.name();
+            if name.is_empty() {
+                // Skip stray characters
+                self.pos += rest.chars().next().unwrap().len_utf8();
+                continue;
+            }
+            self.skip_whitespace();
+            let value = if self.rest().starts_with('=') {
+                self.pos += 1;
+                self.skip_whitespace();
+                let rest = self.rest();
+                match rest.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        self.pos += 1;
+                        let what = format!("attribute {name} of <{tag}");
+                        decode_entities(self.skip_past(&quote.to_string(), &what)?)
+                    }
+                    _ => {
+                        let len = (rest.find(|c: char| c.is_whitespace() || c == '>'))
+                            .unwrap_or(rest.len());
+                        self.pos += len;
+                        decode_entities(&rest[..len])
+                    }
+                }
+            } else {
+                String::new()
+            };
+            match attrs.iter_mut().find(|(n, _)| n == name) {
+                Some((_, v)) => *v = value,
+                None => attrs.push((name.into(), value)),
+            }
+        };
+        let mut elem = Element {
+            tag,
+            attrs,
+            children: Vec::new(),
+        };
+        let lower = elem.tag.to_ascii_lowercase();
+        if self.html && SIBLING_CLOSED_ELEMENTS.contains(&lower.as_str()) {
+            // Close an open sibling, along with elements inside it that are also implicitly closed
+            let open = (stack.iter().skip(1).rev())
+                .take_while(|e| {
+                    SIBLING_CLOSED_ELEMENTS.contains(&e.tag.to_ascii_lowercase().as_str())
+                })
+                .position(|e| e.tag.eq_ignore_ascii_case(&lower));
+            if let Some(depth) = open {
+                for _ in 0..=depth {
+                    close(stack);
+                }
+            }
+        }
+        if self_closing || self.html && VOID_ELEMENTS.contains(&lower.as_str()) {
+            stack.last_mut().unwrap().children.push(Node::Element(elem));
+            return Ok(None);
+        }
+        if self.html && RAW_TEXT_ELEMENTS.contains(&lower.as_str()) {
+            let rest = self.rest();
+            let end = rest.to_ascii_lowercase().find(&format!("</{lower}"));
+            let len = end.unwrap_or(rest.len());
+            self.pos += len;
+            if !rest[..len].trim().is_empty() {
+                elem.children.push(Node::Text(rest[..len].into()));
+            }
+        }
+        Ok(Some(elem))
+    }
+}
+
+fn push_text(stack: &mut [Element], text: String) {
+    let children = &mut stack.last_mut().unwrap().children;
+    if let Some(Node::Text(prev)) = children.last_mut() {
+        prev.push_str(&text);
+    } else {
+        children.push(Node::Text(text));
+    }
+}
+
+fn close(stack: &mut Vec<Element>) {
+    let elem = stack.pop().unwrap();
+    stack.last_mut().unwrap().children.push(Node::Element(elem));
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let c = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn escape(s: &str, xml: &mut String) {
+    for c in s.chars() {
+        match c {
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '&' => xml.push_str("&amp;"),
+            '"' => xml.push_str("&quot;"),
+            c => xml.push(c),
+        }
+    }
+}
+
+/// Get the text of a string or scalar value
+fn text(val: &Value, env: &Uiua, requirement: &'static str) -> UiuaResult<String> {
+    match val {
+        Value::Char(arr) if arr.rank() <= 1 => Ok(arr.data.iter().collect()),
+        Value::Char(_) | Value::Box(_) => Err(env.error(requirement)),
+        val if val.rank() == 0 => Ok(val.format()),
+        _ => Err(env.error(requirement)),
+    }
+}
+
+/// Write a node, leaving text unescaped if it is `raw`
+fn write_node(val: &Value, raw: bool, xml: &mut String, env: &Uiua) -> UiuaResult {
+    match val {
+        Value::Box(arr) if arr.rank() == 0 => write_node(&arr.data[0].0, raw, xml, env)?,
+        val if val.is_map() => {
+            let mut tag = None;
+            let mut attrs = None;
+            let mut children = None;
+            for (key, value) in val.map_kv() {
+                let value = value.unboxed();
+                match key
+                    .unboxed()
+                    .as_string(env, "XML element keys must be strings")?
+                {
+                    key if key == "tag" => {
+                        tag = Some(text(&value, env, "XML element tag must be a string")?)
+                    }
+                    key if key == "attributes" => attrs = Some(value),
+                    key if key == "children" => children = Some(value),
+                    key => return Err(env.error(format!("Unknown XML element key {key:?}"))),
+                }
+            }
+            let tag = tag.ok_or_else(|| env.error("XML element must have a tag"))?;
+            xml.push('<');
+            xml.push_str(&tag);
+            if let Some(attrs) = attrs.filter(|attrs| attrs.row_count() > 0) {
+                if !attrs.is_map() {
+                    return Err(env.error("XML element attributes must be a map"));
+                }
+                for (key, value) in attrs.map_kv() {
+                    let key = key.unboxed();
+                    let key = text(&key, env, "XML attribute names must be strings")?;
+                    let value = text(
+                        &value.unboxed(),
+                        env,
+                        "XML attribute values must be strings",
+                    )?;
+                    xml.push(' ');
+                    xml.push_str(&key);
+                    xml.push_str("=\"");
+                    escape(&value, xml);
+                    xml.push('"');
+                }
+            }
+            match children.filter(|children| children.row_count() > 0) {
+                Some(children) => {
+                    xml.push('>');
+                    let raw = RAW_TEXT_ELEMENTS.contains(&tag.to_ascii_lowercase().as_str());
+                    write_node(&children, raw, xml, env)?;
+                    xml.push_str("</");
+                    xml.push_str(&tag);
+                    xml.push('>');
+                }
+                None => xml.push_str("/>"),
+            }
+        }
+        Value::Char(arr) if arr.rank() <= 1 && raw => xml.extend(&arr.data),
+        Value::Char(arr) if arr.rank() <= 1 => escape(&arr.data.iter().collect::<String>(), xml),
+        val if val.rank() == 0 => escape(&val.format(), xml),
+        val => {
+            for row in val.rows() {
+                write_node(&row, raw, xml, env)?;
+            }
+        }
+    }
+    Ok(())
+}
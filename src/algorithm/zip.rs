@@ -7,10 +7,13 @@ use ecow::{eco_vec, EcoVec};
 use crate::{
     algorithm::pervade::bin_pervade_generic, cowslice::CowSlice, function::Function, random,
     value::Value, Array, ArrayValue, Boxed, Complex, ImplPrimitive, Instr, PersistentMeta,
-    Primitive, Shape, Uiua, UiuaResult,
+    Primitive, Shape, Signature, Uiua, UiuaResult,
 };
 
-use super::{fill_value_shapes, fixed_rows, multi_output, FillContext, FixedRowsData, MultiOutput};
+use super::{
+    fill_value_shapes, fixed_rows, multi_output, par_call_each, row_seed, with_row_seed,
+    FillContext, FixedRowsData, MultiOutput,
+};
 
 type ValueUnFn = Box<dyn Fn(Value, usize, &mut Uiua) -> UiuaResult<Value>>;
 type ValueUn2Fn = Box<dyn Fn(Value, usize, &mut Uiua) -> UiuaResult<(Value, Value)>>;
@@ -143,6 +146,8 @@ impl Value {
             |arr| arr.repeat_shape(Shape::from(&self.shape()[..depth])),
             |arr| arr.repeat_shape(Shape::from(&self.shape()[..depth])),
             |arr| arr.repeat_shape(Shape::from(&self.shape()[..depth])),
+            |arr| arr.repeat_shape(Shape::from(&self.shape()[..depth])),
+            |arr| arr.repeat_shape(Shape::from(&self.shape()[..depth])),
         );
         replacement
     }
@@ -268,31 +273,40 @@ fn each1(f: Function, mut xs: Value, env: &mut Uiua) -> UiuaResult {
     }
     let outputs = f.signature().outputs;
     let is_scalar = xs.rank() == 0;
-    let mut new_values = multi_output(outputs, Vec::with_capacity(xs.element_count()));
+    let mut new_values = multi_output(outputs, Value::builder(xs.element_count()));
     let new_shape = xs.shape().clone();
     let is_empty = outputs > 0 && xs.row_count() == 0;
     let per_meta = xs.take_per_meta();
-    env.without_fill(|env| -> UiuaResult {
-        if is_empty {
+    if is_empty {
+        env.without_fill(|env| -> UiuaResult {
             env.push(xs.proxy_scalar(env));
             _ = env.call_maintain_sig(f);
             for i in 0..outputs {
-                new_values[i].push(env.pop("each's function result")?);
+                new_values[i].add_row(env.pop("each's function result")?, env)?;
             }
-        } else {
-            for val in xs.into_elements() {
-                env.push(val);
-                env.call(f.clone())?;
-                for i in 0..outputs {
-                    new_values[i].push(env.pop("each's function result")?);
-                }
+            Ok(())
+        })?;
+    } else if env.should_call_in_parallel(&f, xs.element_count()) {
+        let vals: Vec<Value> = xs.into_elements().collect();
+        for outs in par_call_each(&f, vals, outputs, "each's function result", env)? {
+            for (i, val) in outs.into_iter().enumerate() {
+                new_values[i].add_row(val, env)?;
             }
         }
-        Ok(())
-    })?;
+    } else {
+        let seed = row_seed(&f, env);
+        for (j, val) in xs.into_elements().enumerate() {
+            env.push(val);
+            with_row_seed(seed, j, || env.without_fill(|env| env.call(f.clone())))?;
+            // Results are added outside of the function call so that the fill applies to them
+            for i in 0..outputs {
+                new_values[i].add_row(env.pop("each's function result")?, env)?;
+            }
+        }
+    }
     for new_values in new_values.into_iter().rev() {
         let mut new_shape = new_shape.clone();
-        let mut eached = Value::from_row_values(new_values, env)?;
+        let mut eached = new_values.finish();
         if is_scalar {
             eached.undo_fix();
         } else if is_empty {
@@ -400,7 +414,7 @@ fn eachn(f: Function, mut args: Vec<Value>, env: &mut Uiua) -> UiuaResult {
     let outputs = f.signature().outputs;
     let is_empty = outputs > 0 && args.iter().any(|v| v.row_count() == 0);
     let elem_count = args.iter().map(Value::element_count).max().unwrap() + is_empty as usize;
-    let mut new_values = multi_output(outputs, Vec::with_capacity(elem_count));
+    let mut new_values = multi_output(outputs, Value::builder(elem_count));
     let new_shape = args
         .iter()
         .map(Value::shape)
@@ -408,39 +422,39 @@ fn eachn(f: Function, mut args: Vec<Value>, env: &mut Uiua) -> UiuaResult {
         .unwrap()
         .clone();
     let per_meta = PersistentMeta::xor_all(args.iter_mut().map(|v| v.take_per_meta()));
-    env.without_fill(|env| -> UiuaResult {
-        if is_empty {
+    if is_empty {
+        env.without_fill(|env| -> UiuaResult {
             for arg in args.into_iter().rev() {
                 env.push(arg.proxy_scalar(env));
             }
             _ = env.call_maintain_sig(f);
             for i in 0..outputs {
-                new_values[i].push(env.pop("each's function result")?);
+                new_values[i].add_row(env.pop("each's function result")?, env)?;
             }
-        } else {
-            let mut arg_elems: Vec<_> = args
-                .into_iter()
-                .map(|val| {
-                    let repetitions = elem_count / val.element_count();
-                    val.into_elements()
-                        .flat_map(move |elem| repeat(elem).take(repetitions))
-                })
-                .collect();
-            for _ in 0..elem_count {
-                for arg in arg_elems.iter_mut().rev() {
-                    env.push(arg.next().unwrap());
-                }
-                env.call(f.clone())?;
-                for i in 0..outputs {
-                    new_values[i].push(env.pop("each's function result")?);
-                }
+            Ok(())
+        })?;
+    } else {
+        let mut arg_elems: Vec<_> = args
+            .into_iter()
+            .map(|val| {
+                let repetitions = elem_count / val.element_count();
+                val.into_elements()
+                    .flat_map(move |elem| repeat(elem).take(repetitions))
+            })
+            .collect();
+        for _ in 0..elem_count {
+            for arg in arg_elems.iter_mut().rev() {
+                env.push(arg.next().unwrap());
+            }
+            env.without_fill(|env| env.call(f.clone()))?;
+            for i in 0..outputs {
+                new_values[i].add_row(env.pop("each's function result")?, env)?;
             }
         }
-        Ok(())
-    })?;
+    }
     for new_values in new_values.into_iter().rev() {
         let mut new_shape = new_shape.clone();
-        let mut eached = Value::from_row_values(new_values, env)?;
+        let mut eached = new_values.finish();
         if is_empty {
             eached.pop_row();
         }
@@ -491,31 +505,37 @@ pub fn rows1(f: Function, mut xs: Value, env: &mut Uiua) -> UiuaResult {
     let outputs = f.signature().outputs;
     let is_scalar = xs.rank() == 0;
     let is_empty = outputs > 0 && xs.row_count() == 0;
-    let mut new_rows = multi_output(
-        outputs,
-        Vec::with_capacity(xs.row_count() + is_empty as usize),
-    );
+    let mut new_rows = multi_output(outputs, Value::builder(xs.row_count() + is_empty as usize));
     let per_meta = xs.take_per_meta();
-    env.without_fill(|env| -> UiuaResult {
-        if is_empty {
+    if is_empty {
+        env.without_fill(|env| -> UiuaResult {
             env.push(xs.proxy_row(env));
             _ = env.call_maintain_sig(f);
             for i in 0..outputs {
-                new_rows[i].push(env.pop("rows' function result")?);
+                new_rows[i].add_row(env.pop("rows' function result")?, env)?;
             }
-        } else {
-            for row in xs.into_rows() {
-                env.push(row);
-                env.call(f.clone())?;
-                for i in 0..outputs {
-                    new_rows[i].push(env.pop("rows' function result")?);
-                }
+            Ok(())
+        })?;
+    } else if env.should_call_in_parallel(&f, xs.row_count()) {
+        let rows: Vec<Value> = xs.into_rows().collect();
+        for outs in par_call_each(&f, rows, outputs, "rows' function result", env)? {
+            for (i, row) in outs.into_iter().enumerate() {
+                new_rows[i].add_row(row, env)?;
             }
         }
-        Ok(())
-    })?;
+    } else {
+        let seed = row_seed(&f, env);
+        for (j, row) in xs.into_rows().enumerate() {
+            env.push(row);
+            with_row_seed(seed, j, || env.without_fill(|env| env.call(f.clone())))?;
+            // Results are added outside of the function call so that the fill applies to them
+            for i in 0..outputs {
+                new_rows[i].add_row(env.pop("rows' function result")?, env)?;
+            }
+        }
+    }
     for new_rows in new_rows.into_iter().rev() {
-        let mut val = Value::from_row_values(new_rows, env)?;
+        let mut val = new_rows.finish();
         if is_scalar {
             val.undo_fix();
         } else if is_empty {
@@ -535,30 +555,30 @@ fn rows2(f: Function, mut xs: Value, mut ys: Value, env: &mut Uiua) -> UiuaResul
         (_, 1) if !ys.length_is_fillable(env) => {
             ys.undo_fix();
             let is_empty = outputs > 0 && xs.row_count() == 0;
-            let mut new_rows = multi_output(outputs, Vec::with_capacity(xs.row_count()));
+            let mut new_rows = multi_output(outputs, Value::builder(xs.row_count()));
             let per_meta = xs.take_per_meta();
-            env.without_fill(|env| -> UiuaResult {
-                if is_empty {
+            if is_empty {
+                env.without_fill(|env| -> UiuaResult {
                     env.push(ys.proxy_row(env));
                     env.push(xs.proxy_row(env));
                     _ = env.call_maintain_sig(f);
                     for i in 0..outputs {
-                        new_rows[i].push(env.pop("rows's function result")?);
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
-                } else {
-                    for x in xs.into_rows() {
-                        env.push(ys.clone());
-                        env.push(x);
-                        env.call(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?);
-                        }
+                    Ok(())
+                })?;
+            } else {
+                for x in xs.into_rows() {
+                    env.push(ys.clone());
+                    env.push(x);
+                    env.without_fill(|env| env.call(f.clone()))?;
+                    for i in 0..outputs {
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
                 }
-                Ok(())
-            })?;
+            }
             for new_rows in new_rows.into_iter().rev() {
-                let mut val = Value::from_row_values(new_rows, env)?;
+                let mut val = new_rows.finish();
                 if both_scalar {
                     val.undo_fix();
                 } else if is_empty {
@@ -572,30 +592,30 @@ fn rows2(f: Function, mut xs: Value, mut ys: Value, env: &mut Uiua) -> UiuaResul
         (1, _) if !xs.length_is_fillable(env) => {
             xs.undo_fix();
             let is_empty = outputs > 0 && ys.row_count() == 0;
-            let mut new_rows = multi_output(outputs, Vec::with_capacity(ys.row_count()));
+            let mut new_rows = multi_output(outputs, Value::builder(ys.row_count()));
             let per_meta = ys.take_per_meta();
-            env.without_fill(|env| -> UiuaResult {
-                if is_empty {
+            if is_empty {
+                env.without_fill(|env| -> UiuaResult {
                     env.push(ys.proxy_row(env));
                     env.push(xs.proxy_row(env));
                     _ = env.call_maintain_sig(f);
                     for i in 0..outputs {
-                        new_rows[i].push(env.pop("rows's function result")?);
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
-                } else {
-                    for y in ys.into_rows() {
-                        env.push(y);
-                        env.push(xs.clone());
-                        env.call(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?);
-                        }
+                    Ok(())
+                })?;
+            } else {
+                for y in ys.into_rows() {
+                    env.push(y);
+                    env.push(xs.clone());
+                    env.without_fill(|env| env.call(f.clone()))?;
+                    for i in 0..outputs {
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
                 }
-                Ok(())
-            })?;
+            }
             for new_rows in new_rows.into_iter().rev() {
-                let mut val = Value::from_row_values(new_rows, env)?;
+                let mut val = new_rows.finish();
                 if both_scalar {
                     val.undo_fix();
                 } else if is_empty {
@@ -624,33 +644,31 @@ fn rows2(f: Function, mut xs: Value, mut ys: Value, env: &mut Uiua) -> UiuaResul
                 return Ok(());
             }
             let is_empty = outputs > 0 && (xs.row_count() == 0 || ys.row_count() == 0);
-            let mut new_rows = multi_output(
-                outputs,
-                Vec::with_capacity(xs.row_count() + is_empty as usize),
-            );
+            let mut new_rows =
+                multi_output(outputs, Value::builder(xs.row_count() + is_empty as usize));
             let per_meta = xs.take_per_meta().xor(ys.take_per_meta());
-            env.without_fill(|env| -> UiuaResult {
-                if is_empty {
+            if is_empty {
+                env.without_fill(|env| -> UiuaResult {
                     env.push(ys.proxy_row(env));
                     env.push(xs.proxy_row(env));
                     _ = env.call_maintain_sig(f);
                     for i in 0..outputs {
-                        new_rows[i].push(env.pop("rows's function result")?);
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
-                } else {
-                    for (x, y) in xs.into_rows().zip(ys.into_rows()) {
-                        env.push(y);
-                        env.push(x);
-                        env.call(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?);
-                        }
+                    Ok(())
+                })?;
+            } else {
+                for (x, y) in xs.into_rows().zip(ys.into_rows()) {
+                    env.push(y);
+                    env.push(x);
+                    env.without_fill(|env| env.call(f.clone()))?;
+                    for i in 0..outputs {
+                        new_rows[i].add_row(env.pop("rows's function result")?, env)?;
                     }
                 }
-                Ok(())
-            })?;
+            }
             for new_rows in new_rows.into_iter().rev() {
-                let mut val = Value::from_row_values(new_rows, env)?;
+                let mut val = new_rows.finish();
                 if both_scalar {
                     val.undo_fix();
                 } else if is_empty {
@@ -673,24 +691,21 @@ fn rowsn(f: Function, args: Vec<Value>, env: &mut Uiua) -> UiuaResult {
         all_scalar,
         per_meta,
     } = fixed_rows(Primitive::Rows.format(), outputs, args, env)?;
-    let mut new_values = multi_output(outputs, Vec::new());
-    env.without_fill(|env| -> UiuaResult {
-        for _ in 0..row_count {
-            for arg in rows.iter_mut().rev() {
-                match arg {
-                    Ok(rows) => env.push(rows.next().unwrap()),
-                    Err(row) => env.push(row.clone()),
-                }
-            }
-            env.call(f.clone())?;
-            for i in 0..outputs {
-                new_values[i].push(env.pop("rows's function result")?);
+    let mut new_values = multi_output(outputs, Value::builder(row_count));
+    for _ in 0..row_count {
+        for arg in rows.iter_mut().rev() {
+            match arg {
+                Ok(rows) => env.push(rows.next().unwrap()),
+                Err(row) => env.push(row.clone()),
             }
         }
-        Ok(())
-    })?;
+        env.without_fill(|env| env.call(f.clone()))?;
+        for i in 0..outputs {
+            new_values[i].add_row(env.pop("rows's function result")?, env)?;
+        }
+    }
     for new_values in new_values.into_iter().rev() {
-        let mut rowsed = Value::from_row_values(new_values, env)?;
+        let mut rowsed = new_values.finish();
         if all_scalar {
             rowsed.undo_fix();
         } else if is_empty {
@@ -703,6 +718,51 @@ fn rowsn(f: Function, args: Vec<Value>, env: &mut Uiua) -> UiuaResult {
     Ok(())
 }
 
+/// Apply a function to each 1-dimensional lane of an array along an axis
+pub fn along(env: &mut Uiua) -> UiuaResult {
+    crate::profile_function!();
+    let f = env.pop_function()?;
+    let sig = f.signature();
+    if sig != (1, 1) {
+        return Err(env.error(format!(
+            "{}'s function must have signature {}, but its signature is {}",
+            Primitive::Along.format(),
+            Signature::new(1, 1),
+            sig
+        )));
+    }
+    let axis = env.pop(1)?.as_int(env, "Axis must be an integer")?;
+    let mut xs = env.pop(2)?;
+    let rank = xs.rank();
+    let axis = if axis < 0 { axis + rank as isize } else { axis };
+    if axis < 0 || axis >= rank as isize {
+        return Err(env.error(format!(
+            "Axis {} is out of bounds for an array of rank {rank}",
+            axis
+        )));
+    }
+    let axis = axis as usize;
+    xs.take_map_keys();
+    // Move the axis to the end and flatten the others so that each row is a lane
+    xs.transpose_depth(axis, 1);
+    let mut outer = xs.shape().clone();
+    let lane_len = outer.pop().unwrap();
+    *xs.shape_mut() = [outer.elements(), lane_len].into();
+    rows1(f, xs, env)?;
+    let mut val = env.pop("along's function result")?;
+    // Put the result's axes where the original axis was
+    let lane_rank = val.rank() - 1;
+    let mut shape = outer;
+    shape.extend(val.shape()[1..].iter().copied());
+    *val.shape_mut() = shape;
+    if lane_rank > 0 {
+        val.transpose_depth(axis, -(lane_rank as i32));
+    }
+    val.validate_shape();
+    env.push(val);
+    Ok(())
+}
+
 pub fn inventory(env: &mut Uiua) -> UiuaResult {
     crate::profile_function!();
     let f = env.pop_function()?;
@@ -1167,6 +1227,8 @@ impl Value {
         match self {
             Value::Num(_) => ctx.scalar_fill::<f64>().is_ok(),
             Value::Byte(_) => ctx.scalar_fill::<u8>().is_ok(),
+            Value::Int(_) => ctx.scalar_fill::<i64>().is_ok(),
+            Value::Float(_) => ctx.scalar_fill::<f32>().is_ok(),
             Value::Complex(_) => ctx.scalar_fill::<Complex>().is_ok(),
             Value::Char(_) => ctx.scalar_fill::<char>().is_ok(),
             Value::Box(_) => ctx.scalar_fill::<Boxed>().is_ok(),
@@ -1179,6 +1241,8 @@ impl Value {
         match self {
             Value::Num(arr) => arr.fill_length_to(len, ctx),
             Value::Byte(arr) => arr.fill_length_to(len, ctx),
+            Value::Int(arr) => arr.fill_length_to(len, ctx),
+            Value::Float(arr) => arr.fill_length_to(len, ctx),
             Value::Complex(arr) => arr.fill_length_to(len, ctx),
             Value::Char(arr) => arr.fill_length_to(len, ctx),
             Value::Box(arr) => arr.fill_length_to(len, ctx),
@@ -11,7 +11,10 @@ use ecow::{EcoString, EcoVec};
 use serde::{de::DeserializeOwned, *};
 
 use crate::{
-    algorithm::map::{MapKeys, EMPTY_NAN, TOMBSTONE_NAN},
+    algorithm::{
+        map::{MapKeys, EMPTY_NAN, TOMBSTONE_NAN},
+        quantity::Unit,
+    },
     cowslice::{cowslice, CowSlice},
     grid_fmt::GridFmt,
     Boxed, Complex, HandleKind, Shape, Uiua, Value,
@@ -52,6 +55,9 @@ pub struct ArrayMeta {
     /// The kind of system handle
     #[serde(skip)]
     pub handle_kind: Option<HandleKind>,
+    /// The unit of a quantity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Unit>,
 }
 
 /// Array pointer metadata
@@ -103,6 +109,12 @@ bitflags! {
         const BOOLEAN = 1;
         /// The array was *created from* a boolean
         const BOOLEAN_LITERAL = 2;
+        /// The array's storage type was set explicitly and should not be changed
+        const FIXED_STORAGE = 4;
+        /// The array's last axis holds the bounds of intervals
+        const INTERVAL = 8;
+        /// The array's integer arithmetic wraps around on overflow instead of erroring
+        const WRAPPING = 16;
     }
 }
 
@@ -111,6 +123,14 @@ impl ArrayFlags {
     pub fn is_boolean(self) -> bool {
         self.contains(Self::BOOLEAN)
     }
+    /// Check if the array is an interval array
+    pub fn is_interval(self) -> bool {
+        self.contains(Self::INTERVAL)
+    }
+    /// Check if the array's integer arithmetic wraps on overflow
+    pub fn is_wrapping(self) -> bool {
+        self.contains(Self::WRAPPING)
+    }
     /// Reset all flags
     pub fn reset(&mut self) {
         *self = Self::NONE;
@@ -124,6 +144,7 @@ pub static DEFAULT_META: ArrayMeta = ArrayMeta {
     map_keys: None,
     pointer: None,
     handle_kind: None,
+    unit: None,
 };
 
 /// Array metadata that can be persisted across operations
@@ -298,6 +319,10 @@ impl<T> Array<T> {
     pub fn take_map_keys(&mut self) -> Option<MapKeys> {
         self.get_meta_mut().and_then(|meta| meta.map_keys.take())
     }
+    /// Take the unit from the metadata
+    pub fn take_unit(&mut self) -> Option<Unit> {
+        self.get_meta_mut().and_then(|meta| meta.unit.take())
+    }
     /// The the persistent metadata of the array
     pub fn take_per_meta(&mut self) -> PersistentMeta {
         if let Some(meta) = self.get_meta_mut() {
@@ -810,6 +835,49 @@ impl ArrayValue for u8 {
     }
 }
 
+impl ArrayValue for i64 {
+    const NAME: &'static str = "number";
+    const SYMBOL: char = 'ℤ';
+    const TYPE_ID: u8 = 0;
+    fn get_scalar_fill(env: &Uiua) -> Result<Self, &'static str> {
+        env.int_scalar_fill()
+    }
+    fn get_array_fill(env: &Uiua) -> Result<Array<Self>, &'static str> {
+        env.int_array_fill()
+    }
+    fn array_hash<H: Hasher>(&self, hasher: &mut H) {
+        // Integers that are exactly representable hash the same as the equivalent number
+        let f = *self as f64;
+        if f as i64 == *self {
+            f.to_bits().hash(hasher)
+        } else {
+            self.hash(hasher)
+        }
+    }
+    fn proxy() -> Self {
+        0
+    }
+}
+
+impl ArrayValue for f32 {
+    const NAME: &'static str = "number";
+    const SYMBOL: char = 'ℝ';
+    const TYPE_ID: u8 = 0;
+    fn get_scalar_fill(env: &Uiua) -> Result<Self, &'static str> {
+        env.float_scalar_fill()
+    }
+    fn get_array_fill(env: &Uiua) -> Result<Array<Self>, &'static str> {
+        env.float_array_fill()
+    }
+    fn array_hash<H: Hasher>(&self, hasher: &mut H) {
+        // Every `f32` is exactly representable as an `f64`
+        (*self as f64).array_hash(hasher)
+    }
+    fn proxy() -> Self {
+        0.0
+    }
+}
+
 impl ArrayValue for char {
     const NAME: &'static str = "character";
     const SYMBOL: char = '@';
@@ -917,6 +985,24 @@ impl RealArrayValue for u8 {
     }
 }
 
+impl RealArrayValue for f32 {
+    fn is_int(&self) -> bool {
+        self.fract().abs() < f32::EPSILON
+    }
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
+impl RealArrayValue for i64 {
+    fn is_int(&self) -> bool {
+        true
+    }
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
 /// Trait for comparing array elements
 pub trait ArrayCmp<U = Self> {
     /// Compare two elements
@@ -946,6 +1032,18 @@ impl ArrayCmp for u8 {
     }
 }
 
+impl ArrayCmp for i64 {
+    fn array_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+}
+
+impl ArrayCmp for f32 {
+    fn array_cmp(&self, other: &Self) -> Ordering {
+        (*self as f64).array_cmp(&(*other as f64))
+    }
+}
+
 impl ArrayCmp for Complex {
     fn array_cmp(&self, other: &Self) -> Ordering {
         self.partial_cmp(other).unwrap_or_else(|| {
@@ -981,6 +1079,53 @@ impl ArrayCmp<u8> for f64 {
     }
 }
 
+impl ArrayCmp<f64> for i64 {
+    fn array_cmp(&self, other: &f64) -> Ordering {
+        // Rounding is monotonic, so only equal results need an exact check
+        match (*self as f64).array_cmp(other) {
+            Ordering::Equal if other.is_finite() => (*self as i128).cmp(&(*other as i128)),
+            ordering => ordering,
+        }
+    }
+}
+
+impl ArrayCmp<i64> for f64 {
+    fn array_cmp(&self, other: &i64) -> Ordering {
+        other.array_cmp(self).reverse()
+    }
+}
+
+impl ArrayCmp<u8> for i64 {
+    fn array_cmp(&self, other: &u8) -> Ordering {
+        self.cmp(&i64::from(*other))
+    }
+}
+
+impl ArrayCmp<i64> for u8 {
+    fn array_cmp(&self, other: &i64) -> Ordering {
+        i64::from(*self).cmp(other)
+    }
+}
+
+macro_rules! f32_cmp {
+    ($($other:ty),*) => {
+        $(
+            impl ArrayCmp<$other> for f32 {
+                fn array_cmp(&self, other: &$other) -> Ordering {
+                    f64::from(*self).array_cmp(other)
+                }
+            }
+            impl ArrayCmp<f32> for $other {
+                fn array_cmp(&self, other: &f32) -> Ordering {
+                    self.array_cmp(&f64::from(*other))
+                }
+            }
+        )*
+    };
+}
+
+f32_cmp!(f64, u8, i64);
+
 /// A formattable shape
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct FormatShape<'a>(pub &'a [usize]);
@@ -1079,6 +1224,8 @@ macro_rules! array_value_ser {
 }
 
 array_value_ser!(u8);
+array_value_ser!(i64);
+array_value_ser!(f32);
 array_value_ser!(isize);
 array_value_ser!(usize);
 array_value_ser!(Boxed);
@@ -1,13 +1,13 @@
-use std::{fmt, iter::once, path::PathBuf, sync::Arc};
+use std::{fmt, hash::Hasher, iter::once, path::PathBuf, sync::Arc};
 
 use dashmap::DashMap;
 use ecow::{eco_vec, EcoString, EcoVec};
 use serde::*;
 
 use crate::{
-    is_ident_char, CodeSpan, DynamicFunction, FuncSlice, Function, Ident, ImplPrimitive, InputSrc,
-    Instr, IntoInputSrc, LocalName, Primitive, Signature, Span, StackSwizzle, TempStack, Uiua,
-    UiuaResult, Value,
+    is_ident_char, primitive::StableHasher, CodeSpan, DynamicFunction, FuncSlice, Function, Ident,
+    ImplPrimitive, InputSrc, Instr, IntoInputSrc, LocalName, Primitive, Signature, Span,
+    StackSwizzle, SysBackend, TempStack, Uiua, UiuaResult, Value,
 };
 
 /// A compiled Uiua assembly
@@ -21,6 +21,8 @@ pub struct Assembly {
     pub(crate) spans: EcoVec<Span>,
     pub(crate) inputs: Inputs,
     pub(crate) dynamic_functions: EcoVec<DynFn>,
+    /// Files whose contents were embedded with `embed`
+    pub(crate) assets: EcoVec<Asset>,
 }
 
 type DynFn = Arc<dyn Fn(&mut Uiua) -> UiuaResult + Send + Sync + 'static>;
@@ -34,6 +36,7 @@ impl Default for Assembly {
             bindings: EcoVec::new(),
             dynamic_functions: EcoVec::new(),
             inputs: Inputs::default(),
+            assets: EcoVec::new(),
         }
     }
 }
@@ -104,6 +107,129 @@ impl Assembly {
     pub fn remove_top_level(&mut self) {
         self.top_slices.clear();
     }
+    /// Get the files whose contents were embedded into the assembly
+    pub fn assets(&self) -> &[Asset] {
+        &self.assets
+    }
+    /// Get the embedded files whose contents have changed since the assembly was compiled
+    ///
+    /// If any assets have changed, the assembly should be recompiled.
+    pub fn changed_assets<'a>(&'a self, backend: &dyn SysBackend) -> Vec<&'a Asset> {
+        (self.assets.iter())
+            .filter(|asset| {
+                backend
+                    .file_read_all(&asset.path)
+                    .map_or(true, |bytes| asset_hash(&bytes) != asset.hash)
+            })
+            .collect()
+    }
+    /// Disassemble the assembly into a human-readable listing
+    ///
+    /// The listing contains the top-level code, the body of every function,
+    /// the global bindings, and the constants pushed by the code.
+    /// Each instruction is shown with its index and, if it has one, its source span.
+    ///
+    /// The listing is meant for reading. Its format is not stable.
+    pub fn disassemble(&self) -> String {
+        use std::fmt::Write;
+        let mut functions: Vec<&Function> = (self.instrs.iter())
+            .filter_map(|instr| match instr {
+                Instr::PushFunc(func) => Some(func),
+                _ => None,
+            })
+            .chain(
+                self.bindings
+                    .iter()
+                    .filter_map(|binding| match &binding.kind {
+                        BindingKind::Func(func) => Some(func),
+                        _ => None,
+                    }),
+            )
+            .collect();
+        functions.sort_by_key(|func| func.slice);
+        functions.dedup_by_key(|func| func.slice);
+
+        let mut s = String::new();
+        s.push_str("TOP LEVEL\n");
+        for slice in &self.top_slices {
+            _ = writeln!(s, "  [{}..{}]", slice.start, slice.end());
+            self.disassemble_slice(&mut s, *slice);
+        }
+
+        s.push_str("\nFUNCTIONS\n");
+        for func in functions {
+            let slice = func.slice;
+            _ = write!(
+                s,
+                "  {} {} [{}..{}]",
+                func.id,
+                func.signature(),
+                slice.start,
+                slice.end()
+            );
+            if func.recursive {
+                s.push_str(" recursive");
+            }
+            s.push('\n');
+            self.disassemble_slice(&mut s, slice);
+        }
+
+        s.push_str("\nBINDINGS\n");
+        for (i, binding) in self.bindings.iter().enumerate() {
+            let span = &binding.span;
+            let name = (self.inputs)
+                .try_get_with(&span.src, |src| {
+                    src.get(span.byte_range()).map(EcoString::from)
+                })
+                .flatten()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "_".into());
+            let vis = if binding.public { "" } else { "private " };
+            _ = write!(s, "  {i:>4}  {vis}{name} ");
+            match &binding.kind {
+                BindingKind::Const(Some(val)) => {
+                    _ = write!(s, "const {} {}", val.type_name(), val.shape())
+                }
+                BindingKind::Const(None) => s.push_str("const (unevaluated)"),
+                BindingKind::Func(func) => {
+                    let slice = func.slice;
+                    _ = write!(
+                        s,
+                        "func {} [{}..{}]",
+                        func.signature(),
+                        slice.start,
+                        slice.end()
+                    );
+                }
+                BindingKind::Module(path) => _ = write!(s, "module {}", path.display()),
+                BindingKind::Macro => s.push_str("macro"),
+            }
+            s.push('\n');
+        }
+
+        s.push_str("\nCONSTANTS\n");
+        for (i, instr) in self.instrs.iter().enumerate() {
+            if let Instr::Push(val) = instr {
+                let ty = format!("{} {}", val.type_name(), val.shape());
+                _ = writeln!(s, "  {i:>4}  {ty:<16}  {instr:?}");
+            }
+        }
+        s
+    }
+    fn disassemble_slice(&self, s: &mut String, slice: FuncSlice) {
+        use std::fmt::Write;
+        for (i, instr) in self.instrs(slice).iter().enumerate() {
+            let line = format!("    {:>4}  {instr:?}", slice.start + i);
+            let span = instr.span().and_then(|span| self.spans.get(span));
+            match span {
+                Some(span @ Span::Code(_)) => {
+                    let pad = 40usize.saturating_sub(line.chars().count());
+                    _ = writeln!(s, "{line}{:pad$}  # {span}", "");
+                }
+                _ => _ = writeln!(s, "{line}"),
+            }
+        }
+    }
     /// Parse a `.uasm` file into an assembly
     pub fn from_uasm(src: &str) -> Result<Self, String> {
         let rest = src;
@@ -114,6 +240,7 @@ impl Assembly {
         let (top_slices_src, rest) = rest.split_once("BINDINGS").ok_or("No bindings")?;
         let (bindings_src, rest) = rest.trim().split_once("SPANS").ok_or("No spans")?;
         let (spans_src, rest) = rest.trim().split_once("FILES").ok_or("No files")?;
+        let (rest, assets_src) = rest.rsplit_once("\nASSETS\n").unwrap_or((rest, ""));
         let (files_src, rest) = rest
             .trim()
             .split_once("STRING INPUTS")
@@ -197,6 +324,14 @@ impl Assembly {
             strings.push(src);
         }
 
+        let mut assets = EcoVec::new();
+        for line in assets_src.lines().filter(|line| !line.trim().is_empty()) {
+            let (hash, path) = line.split_once(' ').ok_or("No asset path")?;
+            let hash = u64::from_str_radix(hash, 16).map_err(|e| e.to_string())?;
+            let path: PathBuf = serde_json::from_str(path).map_err(|e| e.to_string())?;
+            assets.push(Asset { path, hash });
+        }
+
         Ok(Self {
             instrs,
             top_slices,
@@ -208,6 +343,7 @@ impl Assembly {
                 ..Inputs::default()
             },
             dynamic_functions: EcoVec::new(),
+            assets,
         })
     }
     /// Serialize the assembly into a `.uasm` file
@@ -288,10 +424,34 @@ impl Assembly {
             }
         }
 
+        if !self.assets.is_empty() {
+            uasm.push_str("\nASSETS\n");
+            for asset in &self.assets {
+                let path = serde_json::to_string(&asset.path).unwrap();
+                uasm.push_str(&format!("{:016x} {path}\n", asset.hash));
+            }
+        }
+
         uasm
     }
 }
 
+/// A file whose contents were embedded into an assembly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    /// The path of the file
+    pub path: PathBuf,
+    /// A hash of the file's contents when it was embedded
+    pub hash: u64,
+}
+
+/// Hash the contents of an embedded file
+pub(crate) fn asset_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = StableHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
 impl AsRef<Assembly> for Assembly {
     fn as_ref(&self) -> &Self {
         self
@@ -517,6 +677,9 @@ pub struct Inputs {
     /// A map of spans to macro strings
     #[serde(skip)]
     pub macros: DashMap<CodeSpan, EcoString>,
+    /// A map of macro call spans to the names and definition spans of the macros
+    #[serde(skip)]
+    pub macro_defs: DashMap<CodeSpan, (Ident, CodeSpan)>,
 }
 
 impl Inputs {
@@ -17,8 +17,12 @@ pub enum Item {
     Words(Vec<Vec<Sp<Word>>>),
     /// A binding
     Binding(Binding),
+    /// A destructuring binding
+    Destructure(Destructure),
     /// An import
     Import(Import),
+    /// A data definition
+    Data(DataDef),
     /// A test scope
     TestScope(Sp<Vec<Item>>),
 }
@@ -51,6 +55,34 @@ impl Binding {
     }
 }
 
+/// A binding that unpacks an array into several names
+#[derive(Debug, Clone)]
+pub struct Destructure {
+    /// The names to bind
+    pub names: Vec<Sp<Ident>>,
+    /// Whether the rows are unboxed
+    pub boxes: bool,
+    /// The span of the brackets around the names
+    pub pattern_span: CodeSpan,
+    /// The span of the arrow
+    pub arrow_span: CodeSpan,
+    /// Whether the bindings are public
+    pub public: bool,
+    /// The code
+    pub words: Vec<Sp<Word>>,
+}
+
+impl Destructure {
+    /// Get the span of this destructuring binding
+    pub fn span(&self) -> CodeSpan {
+        (self.pattern_span.clone()).merge(if let Some(last_word) = self.words.last() {
+            last_word.span.clone()
+        } else {
+            self.arrow_span.clone()
+        })
+    }
+}
+
 /// An import
 #[derive(Debug, Clone)]
 pub struct Import {
@@ -64,6 +96,48 @@ pub struct Import {
     pub lines: Vec<Option<ImportLine>>,
 }
 
+/// A data definition with tagged variants
+#[derive(Debug, Clone)]
+pub struct DataDef {
+    /// The span of the ~
+    pub tilde_span: CodeSpan,
+    /// The name of the data type
+    pub name: Sp<Ident>,
+    /// The variants
+    pub variants: Vec<DataVariant>,
+}
+
+/// A variant of a data definition
+#[derive(Debug, Clone)]
+pub struct DataVariant {
+    /// The span of the |
+    pub bar_span: CodeSpan,
+    /// The name of the variant
+    pub name: Sp<Ident>,
+    /// The names of the variant's fields
+    pub fields: Vec<Sp<Ident>>,
+    /// The span of the braces around the fields
+    pub fields_span: Option<CodeSpan>,
+}
+
+impl DataDef {
+    /// Get the span of this data definition
+    pub fn span(&self) -> CodeSpan {
+        let last = (self.variants.last())
+            .map(|v| v.span())
+            .unwrap_or_else(|| self.name.span.clone());
+        self.tilde_span.clone().merge(last)
+    }
+}
+
+impl DataVariant {
+    /// Get the span of this variant
+    pub fn span(&self) -> CodeSpan {
+        let last = (self.fields_span.clone()).unwrap_or_else(|| self.name.span.clone());
+        self.bar_span.clone().merge(last)
+    }
+}
+
 #[derive(Debug, Clone)]
 /// A line of imported items
 pub struct ImportLine {
@@ -0,0 +1,300 @@
+//! A C API for embedding Uiua
+//!
+//! Enable the `capi` feature to build the library as a C dynamic and static library with
+//! ```sh
+//! cargo build --lib --release --features capi
+//! ```
+//! The matching C header is at `include/uiua.h`.
+//!
+//! Every function that can fail returns `0` on success and a nonzero value on failure.
+//! The message of the most recent failure can be retrieved with [`uiua_last_error`].
+//! Panics do not unwind into C. They are caught and reported as failures.
+
+use std::{
+    any::Any,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{Array, Shape, Uiua, Value};
+
+/// A Uiua runtime
+pub struct UiuaRuntime {
+    uiua: Uiua,
+    error: Option<CString>,
+}
+
+impl UiuaRuntime {
+    fn result(&mut self, res: Result<(), String>) -> c_int {
+        match res {
+            Ok(()) => {
+                self.error = None;
+                0
+            }
+            Err(e) => {
+                self.error = Some(CString::new(e.replace('\0', "")).unwrap_or_default());
+                1
+            }
+        }
+    }
+    /// Run a function, turning a panic into a failure
+    fn catching(&mut self, f: impl FnOnce(&mut Self) -> Result<(), String>) -> c_int {
+        let res =
+            catch_unwind(AssertUnwindSafe(|| f(self))).unwrap_or_else(|e| Err(panic_message(e)));
+        self.result(res)
+    }
+    fn top(&self) -> Result<&Value, String> {
+        (self.uiua.stack().last()).ok_or_else(|| "The stack is empty".to_string())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = (payload.downcast_ref::<&str>().copied())
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Uiua panicked: {message}")
+}
+
+fn top_nums(value: &Value) -> Result<Vec<f64>, String> {
+    match value {
+        Value::Num(arr) => Ok(arr.data.to_vec()),
+        Value::Byte(arr) => Ok(arr.data.iter().map(|&b| b as f64).collect()),
+        Value::Int(arr) => Ok(arr.data.iter().map(|&i| i as f64).collect()),
+        Value::Float(arr) => Ok(arr.data.iter().map(|&f| f as f64).collect()),
+        value => Err(format!(
+            "Expected the top of the stack to be numbers, but it is {}",
+            value.type_name_plural()
+        )),
+    }
+}
+
+/// Create a new runtime
+///
+/// The runtime does not have access to the file system or other system resources.
+/// It must be freed with [`uiua_free`]. Returns null on failure.
+#[no_mangle]
+pub extern "C" fn uiua_new() -> *mut UiuaRuntime {
+    catch_unwind(|| {
+        Box::into_raw(Box::new(UiuaRuntime {
+            uiua: Uiua::with_safe_sys(),
+            error: None,
+        }))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Free a runtime created with [`uiua_new`]
+///
+/// # Safety
+/// `rt` must be a pointer returned by [`uiua_new`] that has not already been freed, or null
+#[no_mangle]
+pub unsafe extern "C" fn uiua_free(rt: *mut UiuaRuntime) {
+    if !rt.is_null() {
+        _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(rt))));
+    }
+}
+
+/// Compile and run a null-terminated string of Uiua code
+///
+/// The code runs on the runtime's current stack.
+///
+/// # Safety
+/// `rt` must be a valid runtime and `code` must be a valid null-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn uiua_run_str(rt: *mut UiuaRuntime, code: *const c_char) -> c_int {
+    (*rt).catching(|rt| match CStr::from_ptr(code).to_str() {
+        Ok(code) => rt
+            .uiua
+            .run_str(code)
+            .map(drop)
+            .map_err(|e| e.report().color(false).to_string()),
+        Err(e) => Err(format!("Code is not valid UTF-8: {e}")),
+    })
+}
+
+/// Get the message of the most recent failure, or null if the most recent call succeeded
+///
+/// The string is owned by the runtime and is valid until the next call with the runtime.
+///
+/// # Safety
+/// `rt` must be a valid runtime
+#[no_mangle]
+pub unsafe extern "C" fn uiua_last_error(rt: *const UiuaRuntime) -> *const c_char {
+    match &(*rt).error {
+        Some(e) => e.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// Get the number of values on the stack
+///
+/// # Safety
+/// `rt` must be a valid runtime
+#[no_mangle]
+pub unsafe extern "C" fn uiua_stack_len(rt: *const UiuaRuntime) -> usize {
+    catch_unwind(AssertUnwindSafe(|| (*rt).uiua.stack().len())).unwrap_or(0)
+}
+
+/// Push a number onto the stack
+///
+/// # Safety
+/// `rt` must be a valid runtime
+#[no_mangle]
+pub unsafe extern "C" fn uiua_push_number(rt: *mut UiuaRuntime, n: f64) -> c_int {
+    (*rt).catching(|rt| {
+        rt.uiua.push(n);
+        Ok(())
+    })
+}
+
+/// Push a null-terminated string onto the stack
+///
+/// # Safety
+/// `rt` must be a valid runtime and `s` must be a valid null-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn uiua_push_string(rt: *mut UiuaRuntime, s: *const c_char) -> c_int {
+    (*rt).catching(|rt| match CStr::from_ptr(s).to_str() {
+        Ok(s) => {
+            rt.uiua.push(s);
+            Ok(())
+        }
+        Err(e) => Err(format!("String is not valid UTF-8: {e}")),
+    })
+}
+
+/// Push an array of numbers onto the stack
+///
+/// `data` must contain as many numbers as the product of the `rank` dimensions in `shape`.
+///
+/// # Safety
+/// `rt` must be a valid runtime, `shape` must point to `rank` dimensions,
+/// and `data` must point to the number of elements they describe
+#[no_mangle]
+pub unsafe extern "C" fn uiua_push_array(
+    rt: *mut UiuaRuntime,
+    data: *const f64,
+    shape: *const usize,
+    rank: usize,
+) -> c_int {
+    (*rt).catching(|rt| {
+        let shape: Shape = if rank == 0 {
+            Shape::default()
+        } else {
+            slice::from_raw_parts(shape, rank).into()
+        };
+        let len = shape.elements();
+        let data = if len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(data, len)
+        };
+        rt.uiua.push(Array::new(shape, data));
+        Ok(())
+    })
+}
+
+/// Pop a number from the stack
+///
+/// Fails if the stack is empty or the top value is not a scalar number.
+///
+/// # Safety
+/// `rt` must be a valid runtime and `out` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn uiua_pop_number(rt: *mut UiuaRuntime, out: *mut f64) -> c_int {
+    (*rt).catching(|rt| {
+        let top = rt.top()?;
+        if top.rank() != 0 {
+            return Err(format!(
+                "Expected the top of the stack to be a scalar, but its shape is {}",
+                top.shape()
+            ));
+        }
+        let nums = top_nums(top)?;
+        _ = rt.uiua.pop(());
+        *out = nums[0];
+        Ok(())
+    })
+}
+
+/// Get the rank of the value on top of the stack, or `0` if the stack is empty
+///
+/// # Safety
+/// `rt` must be a valid runtime
+#[no_mangle]
+pub unsafe extern "C" fn uiua_top_rank(rt: *const UiuaRuntime) -> usize {
+    catch_unwind(AssertUnwindSafe(|| {
+        (*rt).top().map(|top| top.rank()).unwrap_or(0)
+    }))
+    .unwrap_or(0)
+}
+
+/// Write the shape of the value on top of the stack into `out`
+///
+/// `out` must have room for [`uiua_top_rank`] dimensions.
+///
+/// # Safety
+/// `rt` must be a valid runtime and `out` must point to enough space for the shape
+#[no_mangle]
+pub unsafe extern "C" fn uiua_top_shape(rt: *mut UiuaRuntime, out: *mut usize) -> c_int {
+    (*rt).catching(|rt| {
+        for (i, &dim) in rt.top()?.shape().iter().enumerate() {
+            *out.add(i) = dim;
+        }
+        Ok(())
+    })
+}
+
+/// Pop an array of numbers from the stack into `out`
+///
+/// `len` must be the number of elements in the array, which is the product of its shape.
+///
+/// # Safety
+/// `rt` must be a valid runtime and `out` must point to space for `len` numbers
+#[no_mangle]
+pub unsafe extern "C" fn uiua_pop_array(rt: *mut UiuaRuntime, out: *mut f64, len: usize) -> c_int {
+    (*rt).catching(|rt| {
+        let nums = top_nums(rt.top()?)?;
+        if nums.len() != len {
+            return Err(format!(
+                "The array has {} elements, but the buffer has room for {len}",
+                nums.len()
+            ));
+        }
+        _ = rt.uiua.pop(());
+        ptr::copy_nonoverlapping(nums.as_ptr(), out, len);
+        Ok(())
+    })
+}
+
+/// Pop a string from the stack
+///
+/// Returns null on failure. The string must be freed with [`uiua_free_string`].
+///
+/// # Safety
+/// `rt` must be a valid runtime
+#[no_mangle]
+pub unsafe extern "C" fn uiua_pop_string(rt: *mut UiuaRuntime) -> *mut c_char {
+    let mut string = ptr::null_mut();
+    (*rt).catching(|rt| {
+        let s = (rt.top()?)
+            .as_string(&rt.uiua, "Expected the top of the stack to be a string")
+            .map_err(|e| e.to_string())?;
+        let s = CString::new(s).map_err(|e| e.to_string())?;
+        _ = rt.uiua.pop(());
+        string = s.into_raw();
+        Ok(())
+    });
+    string
+}
+
+/// Free a string returned by [`uiua_pop_string`]
+///
+/// # Safety
+/// `s` must be a string returned by [`uiua_pop_string`] that has not already been freed, or null
+#[no_mangle]
+pub unsafe extern "C" fn uiua_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        _ = catch_unwind(AssertUnwindSafe(|| drop(CString::from_raw(s))));
+    }
+}
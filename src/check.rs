@@ -157,6 +157,8 @@ impl BasicValue {
             BasicValue::Arr(match value {
                 Value::Num(n) => n.data.iter().map(|n| BasicValue::Num(*n)).collect(),
                 Value::Byte(b) => b.data.iter().map(|b| BasicValue::Num(*b as f64)).collect(),
+                Value::Int(i) => i.data.iter().map(|i| BasicValue::Num(*i as f64)).collect(),
+                Value::Float(f) => f.data.iter().map(|f| BasicValue::Num(*f as f64)).collect(),
                 Value::Complex(c) => c.data.iter().map(|_| BasicValue::Other).collect(),
                 Value::Char(c) => c.data.iter().map(|_| BasicValue::Other).collect(),
                 Value::Box(b) => b.data.iter().map(|_| BasicValue::Other).collect(),
@@ -365,6 +367,10 @@ impl<'a> VirtualEnv<'a> {
                     let sig = self.pop_func()?.signature();
                     self.handle_sig(sig)?;
                 }
+                Ode => {
+                    let _sig = self.pop_func()?.signature();
+                    self.handle_args_outputs(2, 1)?;
+                }
                 Group | Partition => {
                     let sig = self.pop_func()?.signature();
                     self.handle_args_outputs(2, sig.outputs)?;
@@ -483,7 +489,12 @@ impl<'a> VirtualEnv<'a> {
                     let f = self.pop_func()?;
                     self.handle_sig(f.signature())?;
                 }
-                Content | Memo | Comptime => {
+                LazyFill => {
+                    let _fill_sig = self.pop_func()?.signature();
+                    let f = self.pop_func()?;
+                    self.handle_sig(f.signature())?;
+                }
+                Content | Memo | Comptime | Broadcast => {
                     let f = self.pop_func()?;
                     self.handle_sig(f.signature())?;
                 }
@@ -6,6 +6,23 @@ impl Compiler {
     pub(super) fn binding(&mut self, binding: Binding, comment: Option<EcoString>) -> UiuaResult {
         let public = binding.public;
 
+        // Warn about shadowing a private binding that was never used
+        if let Some(prev) = self.scope.names.get(&binding.name.value) {
+            let prev = prev.index;
+            if !self.asm.bindings[prev].public
+                && !(self.code_meta.global_references.values()).any(|&index| index == prev)
+            {
+                self.emit_diagnostic(
+                    format!(
+                        "{} shadows a previous private binding that is never used",
+                        binding.name.value
+                    ),
+                    DiagnosticKind::Warning,
+                    binding.name.span.clone(),
+                );
+            }
+        }
+
         // Alias re-bound imports
         if binding.words.iter().filter(|w| w.value.is_code()).count() == 1 {
             if let Some(r) = binding.words.iter().find_map(|w| match &w.value {
@@ -396,6 +413,107 @@ impl Compiler {
         }
         Ok(())
     }
+    pub(super) fn destructure(
+        &mut self,
+        destructure: Destructure,
+        comment: Option<EcoString>,
+    ) -> UiuaResult {
+        let span = destructure.pattern_span.clone();
+        self.experimental_error(&span, || {
+            "Destructuring bindings are experimental. To use them, add \
+            `# Experimental!` to the top of the file."
+        });
+        let spandex = self.add_span(span.clone());
+        let count = destructure.names.len();
+        let binds_above = !destructure.words.iter().any(|w| w.value.is_code());
+
+        // Compile the code
+        let mut instrs = self.compile_words(destructure.words, true)?;
+        match instrs_signature(&instrs) {
+            Ok(sig) if binds_above || sig == (0, 1) => {}
+            Ok(sig) => {
+                return Err(self.fatal_error(
+                    span,
+                    format!(
+                        "A destructuring binding's code must have signature |0.1, \
+                        but its signature is {sig}"
+                    ),
+                ))
+            }
+            Err(e) => {
+                return Err(self.fatal_error(
+                    span,
+                    format!("Cannot infer destructuring binding signature: {e}"),
+                ))
+            }
+        }
+        let names: Array<Boxed> = (destructure.names.iter())
+            .map(|name| Boxed(name.value.as_str().into()))
+            .collect();
+        instrs.extend([
+            Instr::push(names),
+            Instr::ImplPrim(ImplPrimitive::Destructure, spandex),
+            Instr::Unpack {
+                count,
+                span: spandex,
+                unbox: destructure.boxes,
+            },
+        ]);
+
+        // Try to evaluate at compile time
+        let vals = if binds_above {
+            None
+        } else {
+            match self.comptime_instrs(instrs.clone()) {
+                Ok(vals) => vals,
+                Err(e) => {
+                    self.errors.push(e);
+                    None
+                }
+            }
+        };
+
+        // Bind the names
+        let is_const = vals.is_some();
+        let mut vals = vals.into_iter().flatten().rev();
+        for name in destructure.names {
+            let local = LocalName {
+                index: self.next_global,
+                public: destructure.public,
+            };
+            self.next_global += 1;
+            let name_spandex = self.add_span(name.span.clone());
+            if !is_const {
+                instrs.push(Instr::BindGlobal {
+                    span: name_spandex,
+                    index: local.index,
+                });
+            }
+            self.compile_bind_const(
+                &name.value,
+                local,
+                vals.next(),
+                name_spandex,
+                comment.as_deref(),
+            );
+            self.scope.names.insert(name.value, local);
+        }
+        if !is_const {
+            if binds_above {
+                if let Ok(height) = &mut self.scope.stack_height {
+                    *height = height.saturating_sub(1);
+                }
+            }
+            let start = self.asm.instrs.len();
+            (self.asm.instrs).extend(optimize_instrs(instrs, true, &self.asm));
+            let end = self.asm.instrs.len();
+            self.asm.top_slices.push(FuncSlice {
+                start,
+                len: end - start,
+            });
+        }
+        Ok(())
+    }
     pub(super) fn import(
         &mut self,
         import: crate::ast::Import,
@@ -452,4 +570,159 @@ impl Compiler {
         }
         Ok(())
     }
+    pub(super) fn data_def(&mut self, data: DataDef, comment: Option<EcoString>) -> UiuaResult {
+        self.experimental_error(&data.tilde_span, || {
+            "Data definitions are experimental. To use them, add \
+            `# Experimental!` to the top of the file."
+        });
+        let type_name = data.name.value.clone();
+
+        // Validate names
+        let mut names: HashMap<&str, &CodeSpan> = HashMap::new();
+        for variant in &data.variants {
+            for name in [&variant.name].into_iter().chain(&variant.fields) {
+                if name.value.contains(['!', '‼']) {
+                    return Err(self.fatal_error(
+                        name.span.clone(),
+                        format!("`{}` cannot be a variant or field name", name.value),
+                    ));
+                }
+            }
+            if let Some(prev) = names.insert(&variant.name.value, &variant.name.span) {
+                return Err(self.fatal_error(
+                    variant.name.span.clone(),
+                    format!(
+                        "`{}` is already defined in `{type_name}` at {prev}",
+                        variant.name.value
+                    ),
+                ));
+            }
+        }
+        for field in data.variants.iter().flat_map(|v| &v.fields) {
+            if let Some(variant) = (data.variants.iter()).find(|v| v.name.value == field.value) {
+                return Err(self.fatal_error(
+                    field.span.clone(),
+                    format!(
+                        "Field `{}` has the same name as a variant at {}",
+                        field.value, variant.name.span
+                    ),
+                ));
+            }
+        }
+
+        // Generate the module's bindings
+        let module = self.in_scope(ScopeKind::File, |comp| {
+            for (tag, variant) in data.variants.iter().enumerate() {
+                // Constructor
+                let name = &variant.name.value;
+                let (code, comment) = match variant.fields.len() {
+                    0 => (
+                        format!("{name} ← {{{tag}}}"),
+                        format!("The `{name}` variant of `{type_name}`"),
+                    ),
+                    n => (
+                        format!("{name} ← {{{tag} {}∘}}", "⊙".repeat(n - 1)),
+                        format!(
+                            "Make a `{name}` variant of `{type_name}` from its fields: {}",
+                            (variant.fields.iter().map(|f| f.value.as_str()))
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                        ),
+                    ),
+                };
+                comp.data_binding(&code, &variant.name.span, comment)?;
+            }
+            // Field getters
+            let mut fields: IndexMap<&Ident, (&CodeSpan, Vec<usize>)> = IndexMap::new();
+            for (tag, variant) in data.variants.iter().enumerate() {
+                for (i, field) in variant.fields.iter().enumerate() {
+                    let (_, positions) = (fields.entry(&field.value))
+                        .or_insert_with(|| (&field.span, vec![0; data.variants.len()]));
+                    if positions[tag] == 0 {
+                        positions[tag] = i + 1;
+                    }
+                }
+            }
+            for (name, (span, positions)) in fields {
+                // The first row is the tag, so 0 marks a variant without the field
+                let code = if positions.iter().all(|&p| p == positions[0]) {
+                    format!("{name} ← °□⊡{}", positions[0])
+                } else {
+                    let positions: Vec<String> = positions.iter().map(|p| p.to_string()).collect();
+                    format!(
+                        "{name} ← °□⊡⍤{:?}±.⊡:[{}]°□⊢.",
+                        format!("This {type_name} variant has no {name} field"),
+                        positions.join(" ")
+                    )
+                };
+                let comment = format!("Get the `{name}` field of a `{type_name}`");
+                comp.data_binding(&code, span, comment)?;
+            }
+            // Match
+            let local = LocalName {
+                index: comp.next_global,
+                public: true,
+            };
+            comp.next_global += 1;
+            let variant_names: Vec<&str> = (data.variants.iter())
+                .map(|v| v.name.value.as_str())
+                .collect();
+            let comment = format!(
+                "Call the function for a `{type_name}`'s variant on its fields\n\
+                The functions are for {}, in that order",
+                variant_names.join(", ")
+            );
+            comp.asm.add_global_at(
+                local,
+                BindingKind::Macro,
+                Some(data.tilde_span.clone()),
+                Some(DocComment::from(comment.as_str())),
+            );
+            comp.scope.names.insert("Match!".into(), local);
+            let variants = (data.variants.iter())
+                .map(|v| (v.name.value.clone(), v.fields.len()))
+                .collect();
+            let data_match = DataMatch {
+                name: type_name.clone(),
+                variants,
+            };
+            comp.data_matches.insert(local.index, data_match);
+            Ok(())
+        })?;
+
+        // Bind the module
+        let module_path = PathBuf::from(format!("{type_name} at {}", data.name.span));
+        self.imports.insert(module_path.clone(), module);
+        let local = LocalName {
+            index: self.next_global,
+            public: true,
+        };
+        self.next_global += 1;
+        self.asm.add_global_at(
+            local,
+            BindingKind::Module(module_path),
+            Some(data.name.span.clone()),
+            comment.map(|text| DocComment::from(text.as_str())),
+        );
+        self.scope.names.insert(type_name, local);
+        Ok(())
+    }
+    /// Compile a binding generated for a data definition
+    fn data_binding(&mut self, code: &str, span: &CodeSpan, comment: String) -> UiuaResult {
+        let (items, errors, _) = parse_with_aliases(
+            code,
+            InputSrc::Macro(span.clone().into()),
+            &mut self.asm.inputs,
+            &self.primitive_aliases,
+        );
+        if !errors.is_empty() {
+            return Err(UiuaErrorKind::Parse(errors, self.asm.inputs.clone().into()).error());
+        }
+        for item in items {
+            if let Item::Binding(binding) = item {
+                self.binding(binding, Some(comment.as_str().into()))?;
+            }
+        }
+        Ok(())
+    }
 }
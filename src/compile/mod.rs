@@ -1,5 +1,6 @@
 mod binding;
 mod modifier;
+mod type_check;
 
 use std::{
     cell::RefCell,
@@ -21,6 +22,8 @@ use instant::Duration;
 
 use crate::{
     algorithm::invert::{invert_instrs, under_instrs},
+    array::ArrayFlags,
+    assembly::asset_hash,
     ast::*,
     check::{instrs_all_signatures, instrs_signature, SigCheckError, SigCheckErrorKind},
     format::format_word,
@@ -28,9 +31,10 @@ use crate::{
     ident_modifier_args,
     lex::{CodeSpan, Sp, Span},
     lsp::{CodeMeta, SigDecl},
-    optimize::{optimize_instrs, optimize_instrs_mut},
-    parse::{count_placeholders, parse, split_words, unsplit_words},
-    Array, Assembly, BindingKind, Boxed, Diagnostic, DiagnosticKind, DocComment, Ident,
+    optimize::{missed_optimizations, optimize_instrs, optimize_instrs_mut},
+    parse::{count_placeholders, parse_with_aliases, split_words, unsplit_words},
+    sys::read_asset,
+    Array, Assembly, Asset, BindingKind, Boxed, Diagnostic, DiagnosticKind, DocComment, Ident,
     ImplPrimitive, InputSrc, IntoInputSrc, IntoSysBackend, Primitive, RunMode, SemanticComment,
     SysBackend, Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value, CONSTANTS, EXAMPLE_UA, VERSION,
 };
@@ -60,6 +64,11 @@ pub struct Compiler {
     stack_macros: HashMap<usize, StackMacro>,
     /// Unexpanded array macros
     array_macros: HashMap<usize, ArrayMacro>,
+    /// The match modifiers of data definitions
+    data_matches: HashMap<usize, DataMatch>,
+    /// Whether an array macro is used only in code that was skipped,
+    /// so the bindings referenced by its expansion are unknown
+    skipped_array_macro: bool,
     /// The depth of macro expansion
     macro_depth: usize,
     /// Whether the compiler is in an inverse
@@ -68,6 +77,10 @@ pub struct Compiler {
     errors: Vec<UiuaError>,
     /// Primitives that have emitted errors because they are deprecated
     deprecated_prim_errors: HashSet<Primitive>,
+    /// Alternative names for primitives
+    primitive_aliases: HashMap<Ident, PrimitiveAlias>,
+    /// Aliases that have emitted warnings because they are deprecated
+    deprecated_alias_warnings: HashSet<Ident>,
     /// Accumulated diagnostics
     diagnostics: BTreeSet<Diagnostic>,
     /// Print diagnostics as they are encountered
@@ -76,8 +89,18 @@ pub struct Compiler {
     comptime: bool,
     /// The comptime mode
     pre_eval_mode: PreEvalMode,
+    /// Whether to check for type and shape mismatches
+    type_check: bool,
+    /// Whether to turn warnings into errors
+    deny_warnings: bool,
+    /// Whether to report optimizations that could not be applied
+    optimization_report: bool,
+    /// Lines on which warnings are suppressed
+    allowed_warning_lines: HashSet<(InputSrc, usize)>,
     /// The interpreter used for comptime code
     macro_env: Uiua,
+    /// Array constants that have been compiled, so that identical ones can share data
+    constants: HashSet<Value>,
 }
 
 impl Default for Compiler {
@@ -95,19 +118,39 @@ impl Default for Compiler {
             imports: HashMap::new(),
             stack_macros: HashMap::new(),
             array_macros: HashMap::new(),
+            data_matches: HashMap::new(),
+            skipped_array_macro: false,
             macro_depth: 0,
             in_inverse: false,
             errors: Vec::new(),
             deprecated_prim_errors: HashSet::new(),
+            primitive_aliases: HashMap::new(),
+            deprecated_alias_warnings: HashSet::new(),
             diagnostics: BTreeSet::new(),
             print_diagnostics: false,
             comptime: true,
             pre_eval_mode: PreEvalMode::default(),
+            type_check: false,
+            deny_warnings: false,
+            optimization_report: false,
+            allowed_warning_lines: HashSet::new(),
             macro_env: Uiua::default(),
+            constants: HashSet::new(),
         }
     }
 }
 
+/// An alternative name for a primitive
+///
+/// Aliases are registered with [`Compiler::alias_primitive`] and [`Compiler::deprecated_alias`]
+#[derive(Debug, Clone)]
+pub struct PrimitiveAlias {
+    /// The primitive the alias refers to
+    pub primitive: Primitive,
+    /// If the alias is deprecated, a suggestion for what to use instead, which may be empty
+    pub deprecation: Option<EcoString>,
+}
+
 /// An imported module
 #[derive(Clone)]
 pub struct Import {
@@ -125,6 +168,14 @@ struct StackMacro {
     names: IndexMap<Ident, LocalName>,
 }
 
+#[derive(Clone)]
+struct DataMatch {
+    /// The name of the data type
+    name: Ident,
+    /// The names of the variants and how many fields each has
+    variants: Vec<(Ident, usize)>,
+}
+
 #[derive(Clone)]
 struct ArrayMacro {
     function: Function,
@@ -285,6 +336,33 @@ impl Compiler {
         self.pre_eval_mode = mode;
         self
     }
+    /// Set whether to check for type and shape mismatches
+    ///
+    /// Mismatches that can be detected at compile time are emitted as warnings
+    ///
+    /// Defaults to false
+    pub fn type_check(&mut self, type_check: bool) -> &mut Self {
+        self.type_check = type_check;
+        self
+    }
+    /// Set whether to turn warnings into errors
+    ///
+    /// Warnings suppressed with `# Allow warnings!` are not turned into errors
+    ///
+    /// Defaults to false
+    pub fn deny_warnings(&mut self, deny_warnings: bool) -> &mut Self {
+        self.deny_warnings = deny_warnings;
+        self
+    }
+    /// Set whether to report optimizations that could not be applied
+    ///
+    /// Each report is emitted as advice on the code that was not optimized, explaining why
+    ///
+    /// Defaults to false
+    pub fn optimization_report(&mut self, optimization_report: bool) -> &mut Self {
+        self.optimization_report = optimization_report;
+        self
+    }
     /// Set whether to print diagnostics as they are encountered
     ///
     /// If this is set to false, diagnostics will be accumulated and can be retrieved with [`Compiler::take_diagnostics`]
@@ -294,6 +372,39 @@ impl Compiler {
         self.print_diagnostics = print_diagnostics;
         self
     }
+    /// Register an alternative name for a primitive
+    ///
+    /// The alias can be used anywhere the primitive's name can be, including as a modifier.
+    /// An alias takes precedence over primitive names and bindings with the same name.
+    pub fn alias_primitive(&mut self, name: impl Into<Ident>, prim: Primitive) -> &mut Self {
+        let alias = PrimitiveAlias {
+            primitive: prim,
+            deprecation: None,
+        };
+        self.primitive_aliases.insert(name.into(), alias);
+        self
+    }
+    /// Register a deprecated alternative name for a primitive
+    ///
+    /// Using the alias emits a warning the first time it is compiled.
+    /// The suggestion is added to the warning if it is not empty.
+    pub fn deprecated_alias(
+        &mut self,
+        name: impl Into<Ident>,
+        prim: Primitive,
+        suggestion: impl Into<EcoString>,
+    ) -> &mut Self {
+        let alias = PrimitiveAlias {
+            primitive: prim,
+            deprecation: Some(suggestion.into()),
+        };
+        self.primitive_aliases.insert(name.into(), alias);
+        self
+    }
+    /// Get the registered primitive aliases
+    pub fn primitive_aliases(&self) -> &HashMap<Ident, PrimitiveAlias> {
+        &self.primitive_aliases
+    }
     /// Set the run mode
     pub fn mode(&mut self, mode: RunMode) -> &mut Self {
         self.mode = mode;
@@ -362,7 +473,13 @@ impl Compiler {
     fn load_impl(&mut self, input: &str, src: InputSrc) -> UiuaResult<&mut Self> {
         let instrs_start = self.asm.instrs.len();
         let top_slices_start = self.asm.top_slices.len();
-        let (items, errors, diagnostics) = parse(input, src.clone(), &mut self.asm.inputs);
+        let bindings_start = self.asm.bindings.len();
+        let (items, errors, diagnostics) = parse_with_aliases(
+            input,
+            src.clone(),
+            &mut self.asm.inputs,
+            &self.primitive_aliases,
+        );
         if self.print_diagnostics {
             for diagnostic in diagnostics {
                 println!("{}", diagnostic.report());
@@ -387,6 +504,17 @@ impl Compiler {
 
         let res = self.catching_crash(input, |env| env.items(items, false));
 
+        if matches!(res, Ok(Ok(_))) {
+            self.unused_binding_warnings(bindings_start);
+            if self.type_check {
+                self.type_check_code(top_slices_start, bindings_start);
+            }
+            if self.optimization_report {
+                self.report_missed_optimizations(top_slices_start, bindings_start);
+            }
+        }
+        self.handle_warnings();
+
         if self.print_diagnostics {
             for diagnostic in self.take_diagnostics() {
                 eprintln!("{}", diagnostic.report());
@@ -501,10 +629,26 @@ code:
                 let prev_com = prev_comment.take();
                 if can_run || words_should_run_anyway(&binding.words) {
                     self.binding(binding, prev_com)?;
+                } else {
+                    self.reference_skipped(&binding.words);
+                }
+                return Ok(());
+            }
+            Item::Destructure(destructure) => {
+                let can_run = match self.mode {
+                    RunMode::Normal => !in_test,
+                    RunMode::All | RunMode::Test => true,
+                };
+                let prev_com = prev_comment.take();
+                if can_run || words_should_run_anyway(&destructure.words) {
+                    self.destructure(destructure, prev_com)?;
+                } else {
+                    self.reference_skipped(&destructure.words);
                 }
                 return Ok(());
             }
             Item::Import(import) => return self.import(import, prev_comment.take()),
+            Item::Data(data) => return self.data_def(data, prev_comment.take()),
         };
 
         // Compile top-level words
@@ -533,7 +677,11 @@ code:
         };
         lines = unsplit_words(lines.into_iter().flat_map(split_words).collect());
         for line in lines {
-            if line.is_empty() || !can_run && !words_should_run_anyway(&line) {
+            if line.is_empty() {
+                continue;
+            }
+            if !can_run && !words_should_run_anyway(&line) {
+                self.reference_skipped(&line);
                 continue;
             }
             let span =
@@ -611,6 +759,7 @@ code:
             if !line_eval_errored {
                 self.errors.extend(pre_eval_errors);
             }
+            self.intern_constants(&mut instrs);
             let start = self.asm.instrs.len();
             (self.asm.instrs).extend(instrs);
             let end = self.asm.instrs.len();
@@ -628,8 +777,9 @@ code:
         sig: Signature,
         instrs: EcoVec<Instr>,
     ) -> Function {
-        let (instrs, errors) = self.pre_eval_instrs(instrs);
+        let (mut instrs, errors) = self.pre_eval_instrs(instrs);
         self.errors.extend(errors);
+        self.intern_constants(&mut instrs);
         let len = instrs.len();
         if len > 1 {
             (self.asm.instrs).push(Instr::Comment(format!("({id}").into()));
@@ -644,6 +794,34 @@ code:
         }
         Function::new(id, sig, FuncSlice { start, len }, hash)
     }
+    /// Make array constants share data with identical constants that were already compiled
+    ///
+    /// This is done across every module in the compilation, so repeated literals
+    /// and the output of macros only take up memory once.
+    fn intern_constants(&mut self, instrs: &mut EcoVec<Instr>) {
+        let is_array = |instr: &Instr| matches!(instr, Instr::Push(val) if val.rank() > 0);
+        if !instrs.iter().any(is_array) {
+            return;
+        }
+        for instr in instrs.make_mut() {
+            let Instr::Push(val) = instr else {
+                continue;
+            };
+            if val.rank() == 0 {
+                continue;
+            }
+            match self.constants.get(val) {
+                Some(interned) => {
+                    if identical_constants(interned, val) {
+                        *val = interned.clone();
+                    }
+                }
+                None => {
+                    self.constants.insert(val.clone());
+                }
+            }
+        }
+    }
     fn compile_bind_function(
         &mut self,
         name: &Ident,
@@ -729,6 +907,16 @@ code:
             self.backend()
                 .load_git_module(&url, branch)
                 .map_err(|e| self.fatal_error(span.clone(), e))?
+        } else if path_str.starts_with("http://") {
+            return Err(self.fatal_error(
+                span.clone(),
+                "Modules can only be imported from https:// URLs",
+            ));
+        } else if path_str.starts_with("https://") {
+            // URL import
+            self.backend()
+                .load_url_module(path_str.trim())
+                .map_err(|e| self.fatal_error(span.clone(), e))?
         } else {
             // Normal import
             self.resolve_import_path(Path::new(path_str))
@@ -1295,6 +1483,12 @@ code:
                     }
                     self.push_instr(instr);
                 }
+                SemanticComment::AllowWarnings => {
+                    let line = word.span.start.line as usize;
+                    for line in [line, line + 1] {
+                        (self.allowed_warning_lines).insert((word.span.src.clone(), line));
+                    }
+                }
                 SemanticComment::Boo => {
                     self.add_error(word.span.clone(), "The compiler is scared!")
                 }
@@ -1375,10 +1569,13 @@ code:
             if let Some(local) = self.imports[&module].names.get(&r.name.value).copied() {
                 Ok((path_locals, local))
             } else {
+                let names = self.imports[&module].names.keys();
+                let suggestion =
+                    did_you_mean(&r.name.value, names.map(|name| (name.as_str(), None)));
                 Err(self.fatal_error(
                     r.name.span.clone(),
                     format!(
-                        "Item `{}` not found in module `{}`",
+                        "Item `{}` not found in module `{}`{suggestion}",
                         r.name.value,
                         module.display()
                     ),
@@ -1387,9 +1584,10 @@ code:
         } else if let Some(local) = self.find_name(&r.name.value, r.in_macro_arg) {
             Ok((Vec::new(), local))
         } else {
+            let suggestion = self.did_you_mean(&r.name.value, r.in_macro_arg);
             Err(self.fatal_error(
                 r.name.span.clone(),
-                format!("Unknown identifier `{}`", r.name.value),
+                format!("Unknown identifier `{}`{suggestion}", r.name.value),
             ))
         }
     }
@@ -1413,6 +1611,31 @@ code:
         }
         None
     }
+    /// Suggest names in scope and primitives that are similar to an unknown name
+    fn did_you_mean(&self, name: &str, skip_local: bool) -> String {
+        let mut scopes = Vec::new();
+        if !skip_local {
+            scopes.push(&self.scope);
+        }
+        let mut hit_file = false;
+        for scope in self.higher_scopes.iter().rev() {
+            if scope.kind == ScopeKind::File {
+                if hit_file || self.scope.kind == ScopeKind::File {
+                    break;
+                }
+                hit_file = true;
+            }
+            scopes.push(scope);
+        }
+        let names = (scopes.into_iter())
+            .flat_map(|scope| scope.names.keys())
+            .map(|name| (name.as_str(), None))
+            .chain(CONSTANTS.iter().map(|c| (c.name, None)));
+        let prims = (Primitive::non_deprecated())
+            .filter(|_| name.starts_with(|c: char| c.is_ascii_lowercase()))
+            .map(|prim| (prim.name(), Some(prim.format().to_string())));
+        did_you_mean(name, names.chain(prims))
+    }
     fn ref_path(
         &self,
         path: &[RefComponent],
@@ -1425,9 +1648,10 @@ code:
         let module_local = self
             .find_name(&first.module.value, skip_local)
             .ok_or_else(|| {
+                let suggestion = self.did_you_mean(&first.module.value, skip_local);
                 self.fatal_error(
                     first.module.span.clone(),
-                    format!("Unknown import `{}`", first.module.value),
+                    format!("Unknown import `{}`{suggestion}", first.module.value),
                 )
             })?;
         path_locals.push(module_local);
@@ -1554,7 +1778,8 @@ code:
                 self.push_instr(Instr::PushFunc(f));
             }
         } else {
-            return Err(self.fatal_error(span, format!("Unknown identifier `{ident}`")));
+            let suggestion = self.did_you_mean(&ident, skip_local);
+            return Err(self.fatal_error(span, format!("Unknown identifier `{ident}`{suggestion}")));
         }
         Ok(())
     }
@@ -1855,6 +2080,35 @@ code:
             );
         }
     }
+    fn handle_alias_deprecation(&mut self, span: &CodeSpan) {
+        if self.primitive_aliases.is_empty() {
+            return;
+        }
+        let Some((name, alias)) = span.as_str(&self.asm.inputs, |s| {
+            (self.primitive_aliases.get_key_value(s)).map(|(k, v)| (k.clone(), v.clone()))
+        }) else {
+            return;
+        };
+        let Some(suggestion) = alias.deprecation else {
+            return;
+        };
+        if !self.deprecated_alias_warnings.insert(name.clone()) {
+            return;
+        }
+        let suggestion = if suggestion.is_empty() {
+            String::new()
+        } else {
+            format!(", {suggestion}")
+        };
+        self.emit_diagnostic(
+            format!(
+                "`{name}` is a deprecated alias for {} and will be removed in the future{suggestion}",
+                alias.primitive.format()
+            ),
+            DiagnosticKind::Warning,
+            span.clone(),
+        );
+    }
     fn handle_primitive_experimental(&mut self, prim: Primitive, span: &CodeSpan) {
         if prim.is_experimental() {
             self.experimental_error(span, || {
@@ -1869,6 +2123,10 @@ code:
     fn primitive(&mut self, prim: Primitive, span: CodeSpan, call: bool) -> UiuaResult {
         self.handle_primitive_experimental(prim, &span);
         self.handle_primitive_deprecation(prim, &span);
+        self.handle_alias_deprecation(&span);
+        if prim == Primitive::Embed && call && self.embed_asset(&span)? {
+            return Ok(());
+        }
         let span_i = self.add_span(span.clone());
         if call {
             self.push_instr(Instr::Prim(prim, span_i));
@@ -1880,6 +2138,37 @@ code:
         }
         Ok(())
     }
+    /// Replace a constant path and `embed` with the contents of the file
+    ///
+    /// Returns whether the path was constant
+    fn embed_asset(&mut self, span: &CodeSpan) -> UiuaResult<bool> {
+        let instrs = self.new_functions.last().unwrap();
+        let Some(Instr::Push(Value::Char(path))) = instrs.last() else {
+            return Ok(false);
+        };
+        if path.rank() != 1 {
+            return Ok(false);
+        }
+        let path: String = path.data.iter().collect();
+        let path = match self.current_imports.last().and_then(|p| p.parent()) {
+            Some(parent) => parent.join(path),
+            None => PathBuf::from(path),
+        };
+        let bytes = read_asset(&*self.backend(), &path).map_err(|e| {
+            self.fatal_error(
+                span.clone(),
+                format!("Failed to embed {}: {e}", path.display()),
+            )
+        })?;
+        let hash = asset_hash(&bytes);
+        if !self.asm.assets.iter().any(|asset| asset.path == path) {
+            self.asm.assets.push(Asset { path, hash });
+        }
+        let instrs = self.new_functions.last_mut().unwrap();
+        instrs.pop();
+        self.push_instr(Instr::push(Array::<u8>::from_iter(bytes)));
+        Ok(true)
+    }
     fn stack_swizzle(&mut self, swiz: StackSwizzle, span: CodeSpan, call: bool) {
         self.experimental_error(&span, || {
             "Swizzles are experimental. To use them, add \
@@ -2035,6 +2324,118 @@ code:
         self.diagnostics
             .insert(Diagnostic::new(message.into(), span, kind, inputs));
     }
+    /// Suppress allowed warnings, and turn the rest into errors if they are denied
+    fn handle_warnings(&mut self) {
+        let allowed = take(&mut self.allowed_warning_lines);
+        self.diagnostics.retain(|diag| {
+            diag.kind != DiagnosticKind::Warning
+                || !allowed.contains(&(diag.span.src.clone(), diag.span.start.line as usize))
+        });
+        self.allowed_warning_lines = allowed;
+        if self.deny_warnings {
+            let (warnings, rest) = take(&mut self.diagnostics)
+                .into_iter()
+                .partition(|diag| diag.kind == DiagnosticKind::Warning);
+            self.diagnostics = rest;
+            for warning in warnings {
+                self.add_error(warning.span, warning.message);
+            }
+        }
+    }
+    /// Report places where optimizations could not be applied
+    fn report_missed_optimizations(&mut self, top_slices_start: usize, bindings_start: usize) {
+        let mut missed = Vec::new();
+        for binding in &self.asm.bindings[bindings_start..] {
+            if let BindingKind::Func(f) = &binding.kind {
+                missed.extend(missed_optimizations(f.instrs(&self.asm), &self.asm));
+            }
+        }
+        for slice in &self.asm.top_slices[top_slices_start..] {
+            missed.extend(missed_optimizations(self.asm.instrs(*slice), &self.asm));
+        }
+        for (span, message) in missed {
+            if let Some(Span::Code(span)) = self.asm.spans.get(span).cloned() {
+                self.emit_diagnostic(message, DiagnosticKind::Advice, span);
+            }
+        }
+    }
+    /// Report that some code was not evaluated at compile time because it is not pure
+    fn report_impure_section(&mut self, section: &[Instr]) {
+        let impure = section.iter().find_map(|instr| match instr {
+            Instr::Prim(prim, span) if prim.purity() < Purity::Pure => {
+                Some((prim.format().to_string(), *span))
+            }
+            Instr::ImplPrim(prim, span) if prim.purity() < Purity::Pure => {
+                Some((prim.to_string(), *span))
+            }
+            _ => None,
+        });
+        let Some((name, span)) = impure else {
+            return;
+        };
+        if let Some(Span::Code(span)) = self.asm.spans.get(span).cloned() {
+            let message =
+                format!("This code was not evaluated at compile time because {name} is not pure");
+            self.emit_diagnostic(message, DiagnosticKind::Advice, span);
+        }
+    }
+    /// Mark the bindings referenced by code that is skipped in the current run mode as used
+    fn reference_skipped(&mut self, words: &[Sp<Word>]) {
+        let mut words = words.to_vec();
+        recurse_words(&mut words, &mut |word| {
+            let r = match &word.value {
+                Word::Ref(r) => r,
+                Word::Modified(m) => match &m.modifier.value {
+                    Modifier::Ref(r) => r,
+                    Modifier::Primitive(_) => return,
+                },
+                _ => return,
+            };
+            if let Ok((path_locals, local)) = self.ref_local(r) {
+                if self.array_macros.contains_key(&local.index) {
+                    self.skipped_array_macro = true;
+                }
+                (self.code_meta.global_references).insert(r.name.clone(), local.index);
+                for (local, comp) in path_locals.into_iter().zip(&r.path) {
+                    (self.code_meta.global_references).insert(comp.module.clone(), local.index);
+                }
+            }
+        });
+    }
+    /// Warn about private bindings that are never used
+    fn unused_binding_warnings(&mut self, bindings_start: usize) {
+        if take(&mut self.skipped_array_macro) {
+            return;
+        }
+        let referenced: HashSet<usize> =
+            (self.code_meta.global_references.values().copied()).collect();
+        let mut unused = Vec::new();
+        for (i, binding) in self.asm.bindings.iter().enumerate().skip(bindings_start) {
+            if binding.public
+                || referenced.contains(&i)
+                || matches!(binding.kind, BindingKind::Module(_))
+            {
+                continue;
+            }
+            // Bindings that failed to compile have no name
+            let span = &binding.span;
+            let Some(name) = (self.asm.inputs)
+                .try_get_with(&span.src, |src| src.get(span.byte_range()).map(Ident::from))
+                .flatten()
+                .filter(|name| !name.is_empty())
+            else {
+                continue;
+            };
+            // Shadowed bindings have already been warned about
+            if (self.scope.names.get(&name)).is_some_and(|local| local.index != i) {
+                continue;
+            }
+            unused.push((format!("{name} is never used"), binding.span.clone()));
+        }
+        for (message, span) in unused {
+            self.emit_diagnostic(message, DiagnosticKind::Warning, span);
+        }
+    }
     fn add_error(&mut self, span: impl Into<Span>, message: impl ToString) {
         let e = UiuaErrorKind::Run(
             span.into().sp(message.to_string()),
@@ -2149,11 +2550,22 @@ code:
                 if !instrs_can_pre_eval(section, &self.asm) {
                     continue;
                 }
-                if instrs_are_pure(section, &self.asm, Purity::Pure)
-                    && instrs_all_signatures(section).is_ok_and(|(sig, temps)| {
+                let is_constant = |section: &[Instr]| {
+                    instrs_all_signatures(section).is_ok_and(|(sig, temps)| {
                         sig.args == 0 && sig.outputs > 0 && temps.iter().all(|&sig| sig == (0, 0))
                     })
-                {
+                };
+                if !instrs_are_pure(section, &self.asm, Purity::Pure) {
+                    if self.optimization_report
+                        && section.len() > 1
+                        && is_constant(section)
+                        && instrs_are_limit_bounded(section, &self.asm)
+                    {
+                        self.report_impure_section(section);
+                    }
+                    continue;
+                }
+                if is_constant(section) {
                     // println!("section: {section:?}");
                     let mut success = false;
                     match self.comptime_instrs(section.into()) {
@@ -2203,14 +2615,27 @@ code:
                     .collect(),
             ));
         }
+        type CacheKey = (EcoVec<Instr>, Vec<(&'static str, bool)>);
         thread_local! {
-            static CACHE: RefCell<HashMap<EcoVec<Instr>, Option<Vec<Value>>>> = RefCell::new(HashMap::new());
+            static CACHE: RefCell<HashMap<CacheKey, Option<Vec<Value>>>> = RefCell::new(HashMap::new());
         }
         CACHE.with(|cache| {
             let instrs = optimize_instrs(instrs, true, &self.asm);
-            if let Some(stack) = cache.borrow().get(&instrs) {
+            // Equal values may have different storage, which `storage` can observe
+            let storage = (instrs.iter())
+                .filter_map(|instr| match instr {
+                    Instr::Push(val) => Some((
+                        val.storage_name(),
+                        val.meta().flags.contains(ArrayFlags::FIXED_STORAGE),
+                    )),
+                    _ => None,
+                })
+                .collect();
+            let key = (instrs, storage);
+            if let Some(stack) = cache.borrow().get(&key) {
                 return Ok(stack.clone());
             }
+            let (instrs, _) = &key;
             let mut asm = self.asm.clone();
             asm.top_slices.clear();
             let start = asm.instrs.len();
@@ -2236,11 +2661,11 @@ code:
                     } else {
                         Some(stack)
                     };
-                    cache.borrow_mut().insert(instrs, res.clone());
+                    cache.borrow_mut().insert(key, res.clone());
                     Ok(res)
                 }
                 Err(e) if matches!(e.kind, UiuaErrorKind::Timeout(..)) => {
-                    cache.borrow_mut().insert(instrs, None);
+                    cache.borrow_mut().insert(key, None);
                     Ok(None)
                 }
                 Err(e) => Err(e),
@@ -2257,6 +2682,52 @@ code:
     }
 }
 
+/// Format a suggestion of the candidates closest to an unknown name
+///
+/// Each candidate may have a different way it should be shown
+fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = (&'a str, Option<String>)>,
+) -> String {
+    let len = name.chars().count();
+    let max_dist = (len / 3).max(1);
+    let mut close: Vec<(usize, String)> = (candidates.into_iter())
+        .filter_map(|(cand, shown)| {
+            let dist = edit_distance(name, cand);
+            // A candidate that shares no characters is not similar
+            let similar = dist <= max_dist && dist < len.max(cand.chars().count());
+            similar.then(|| (dist, shown.unwrap_or_else(|| cand.into())))
+        })
+        .collect();
+    close.sort();
+    close.dedup_by(|a, b| a.1 == b.1);
+    let close: Vec<String> = (close.into_iter().take(3))
+        .map(|(_, shown)| format!("`{shown}`"))
+        .collect();
+    match close.as_slice() {
+        [] => String::new(),
+        [a] => format!(". Did you mean {a}?"),
+        [a, b] => format!(". Did you mean {a} or {b}?"),
+        [init @ .., last] => format!(". Did you mean {}, or {last}?", init.join(", ")),
+    }
+}
+
+/// The Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ac) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &bc) in b.iter().enumerate() {
+            let sub = prev[j] + (ac != bc) as usize;
+            curr[j + 1] = sub.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
 fn instrs_can_pre_eval(instrs: &[Instr], asm: &Assembly) -> bool {
     use Primitive::*;
     if instrs.is_empty() {
@@ -2302,6 +2773,30 @@ fn instrs_can_pre_eval(instrs: &[Instr], asm: &Assembly) -> bool {
     true
 }
 
+/// Whether two constants are exactly the same, down to the bits of their elements
+///
+/// This is stricter than [`Value`]'s equality, which treats `0` and `¯0` as equal.
+fn identical_constants(a: &Value, b: &Value) -> bool {
+    fn bits_eq<T>(a: &[T], b: &[T], bits: impl Fn(&T) -> u64) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| bits(a) == bits(b))
+    }
+    if a.shape() != b.shape() || a.meta() != b.meta() {
+        return false;
+    }
+    match (a, b) {
+        (Value::Num(a), Value::Num(b)) => bits_eq(&a.data, &b.data, |n| n.to_bits()),
+        (Value::Byte(a), Value::Byte(b)) => bits_eq(&a.data, &b.data, |&n| n as u64),
+        (Value::Int(a), Value::Int(b)) => bits_eq(&a.data, &b.data, |&n| n as u64),
+        (Value::Float(a), Value::Float(b)) => bits_eq(&a.data, &b.data, |n| n.to_bits() as u64),
+        (Value::Complex(a), Value::Complex(b)) => {
+            bits_eq(&a.data, &b.data, |c| c.re.to_bits())
+                && bits_eq(&a.data, &b.data, |c| c.im.to_bits())
+        }
+        (Value::Char(a), Value::Char(b)) => bits_eq(&a.data, &b.data, |&c| c as u64),
+        _ => false,
+    }
+}
+
 fn words_look_pervasive(words: &[Sp<Word>]) -> bool {
     use Primitive::*;
     words.iter().all(|word| match &word.value {
@@ -94,6 +94,7 @@ impl Compiler {
                 }
                 Ok(Some(new))
             }
+            Modifier::Primitive(Primitive::Dispatch | Primitive::Obverse) => Ok(None),
             m if m.args() >= 2 => {
                 if pack.branches.len() != m.args() {
                     return Err(self.fatal_error(
@@ -120,7 +121,9 @@ impl Compiler {
             m => 'blk: {
                 if let Modifier::Ref(name) = m {
                     if let Ok((_, local)) = self.ref_local(name) {
-                        if self.array_macros.contains_key(&local.index) {
+                        if self.array_macros.contains_key(&local.index)
+                            || self.data_matches.contains_key(&local.index)
+                        {
                             break 'blk Ok(None);
                         }
                     }
@@ -186,7 +189,10 @@ impl Compiler {
 
         // Handle macros
         let prim = match modified.modifier.value {
-            Modifier::Primitive(prim) => prim,
+            Modifier::Primitive(prim) => {
+                self.handle_alias_deprecation(&modified.modifier.span);
+                prim
+            }
             Modifier::Ref(r) => {
                 let (path_locals, local) = self.ref_local(&r)?;
                 self.validate_local(&r.name.value, local, &r.name.span);
@@ -196,6 +202,11 @@ impl Compiler {
                 for (local, comp) in path_locals.into_iter().zip(&r.path) {
                     (self.code_meta.global_references).insert(comp.module.clone(), local.index);
                 }
+                // Data matches
+                if let Some(data) = self.data_matches.get(&local.index).cloned() {
+                    let span = modified.modifier.span.clone();
+                    return self.data_match(data, span, modified.operands, call);
+                }
                 // Handle recursion depth
                 self.macro_depth += 1;
                 const MAX_MACRO_DEPTH: usize = if cfg!(debug_assertions) { 10 } else { 20 };
@@ -336,6 +347,10 @@ impl Compiler {
                     self.code_meta
                         .macro_expansions
                         .insert(full_span, (r.name.value.clone(), code.clone()));
+                    if let Some(binding) = self.asm.bindings.get(local.index) {
+                        let def = (r.name.value.clone(), binding.span.clone());
+                        (self.asm.inputs.macro_defs).insert(modified.modifier.span.clone(), def);
+                    }
                     self.suppress_diagnostics(|comp| {
                         comp.temp_scope(mac.names, |comp| {
                             comp.quote(&code, &modified.modifier.span, call)
@@ -751,6 +766,82 @@ impl Compiler {
                     normal_sig
                 )
             }
+            Obverse => {
+                let operand = modified.code_operands().next().unwrap().clone();
+                let branches = match operand.value {
+                    Word::Pack(pack) if (2..=4).contains(&pack.branches.len()) => pack.branches,
+                    _ => {
+                        return Err(self.fatal_error(
+                            operand.span,
+                            format!(
+                                "{}'s operand must be a function pack of 2 to 4 functions",
+                                Obverse.format()
+                            ),
+                        ))
+                    }
+                };
+                let old_in_inverse = replace(&mut self.in_inverse, false);
+                let funcs: UiuaResult<Vec<Function>> = (branches.into_iter())
+                    .map(|branch| self.compile_func(branch.value, branch.span))
+                    .collect();
+                self.in_inverse = old_in_inverse;
+                let mut funcs = funcs?.into_iter();
+                let normal = funcs.next().unwrap();
+                let normal_sig = normal.signature();
+                let span = self.add_span(modified.modifier.span.clone());
+                let (inverse, under) = match (funcs.next(), funcs.next(), funcs.next()) {
+                    (Some(inverse), None, None) => (Some(inverse), None),
+                    (Some(before), Some(after), None) => {
+                        // Without context, the undo function is also the un-inverse
+                        let inverse = (before.signature() == normal_sig).then(|| after.clone());
+                        (inverse, Some((before, after)))
+                    }
+                    (Some(inverse), Some(before), Some(after)) => {
+                        (Some(inverse), Some((before, after)))
+                    }
+                    _ => unreachable!(),
+                };
+                let mut instrs = match under {
+                    Some((before, after)) => eco_vec![
+                        Instr::PushFunc(after),
+                        Instr::PushFunc(before),
+                        Instr::PushFunc(normal),
+                        Instr::Prim(Primitive::SetUnder, span),
+                    ],
+                    None => eco_vec![Instr::PushFunc(normal)],
+                };
+                if let Some(inverse) = inverse {
+                    let inverse_sig = inverse.signature();
+                    let opposite = Signature::new(inverse_sig.outputs, inverse_sig.args);
+                    if !normal_sig.is_compatible_with(opposite) {
+                        self.emit_diagnostic(
+                            format!(
+                                "{}'s inverse must have the opposite signature of the \
+                                normal function, but their signatures are \
+                                {normal_sig} and {inverse_sig}",
+                                Obverse.format()
+                            ),
+                            DiagnosticKind::Warning,
+                            modified.modifier.span.clone(),
+                        );
+                    }
+                    let normal = if let [Instr::PushFunc(normal)] = instrs.as_slice() {
+                        normal.clone()
+                    } else {
+                        self.make_function(
+                            modified.modifier.span.clone().into(),
+                            normal_sig,
+                            instrs,
+                        )
+                    };
+                    instrs = eco_vec![
+                        Instr::PushFunc(inverse),
+                        Instr::PushFunc(normal),
+                        Instr::Prim(Primitive::SetInverse, span),
+                    ];
+                }
+                finish!(instrs, normal_sig)
+            }
             Try => {
                 let mut operands = modified.code_operands().cloned();
                 let tried = operands.next().unwrap();
@@ -847,7 +938,7 @@ impl Compiler {
                     }
                 }
             }
-            Fill => {
+            Fill | LazyFill => {
                 let mut operands = modified.code_operands().rev().cloned();
                 if !call {
                     self.new_functions.push(EcoVec::new());
@@ -872,7 +963,7 @@ impl Compiler {
                         fill_span,
                         format!(
                             "{} function can have at most 1 output, but its signature is {}",
-                            Primitive::Fill.format(),
+                            prim.format(),
                             fill_sig
                         ),
                     );
@@ -885,7 +976,7 @@ impl Compiler {
                 self.push_instr(Instr::PushFunc(fill_func));
 
                 let span = self.add_span(modified.modifier.span.clone());
-                self.push_instr(Instr::Prim(Primitive::Fill, span));
+                self.push_instr(Instr::Prim(prim, span));
                 if !call {
                     let instrs = self.new_functions.pop().unwrap();
                     let sig = self.sig_of(&instrs, &modified.modifier.span)?;
@@ -1058,12 +1149,253 @@ impl Compiler {
                 ];
                 finish!(instrs, Signature::new(0, 2));
             }
+            Reflect => {
+                let operand = modified.code_operands().next().unwrap().clone();
+                let (name, span) = match &operand.value {
+                    Word::Ref(r) => {
+                        let (_, local) = self.ref_local(r)?;
+                        let span = self.asm.bindings[local.index].span.clone();
+                        (r.name.value.to_string(), span)
+                    }
+                    Word::Primitive(prim) => (prim.name().into(), operand.span.clone()),
+                    _ => (String::new(), operand.span.clone()),
+                };
+                let (_, sig) = self.compile_operand_word(operand)?;
+                let keys: Array<Boxed> = ["name", "args", "outputs", "span"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                let mut info: Value = Array::<Boxed>::from_iter([
+                    Boxed(name.into()),
+                    Boxed(sig.args.into()),
+                    Boxed(sig.outputs.into()),
+                    Boxed(span.to_string().into()),
+                ])
+                .into();
+                info.map(keys.into(), &self.macro_env)?;
+                finish!(eco_vec![Instr::push(info)], Signature::new(0, 1));
+            }
+            Dispatch => {
+                let operand = modified.code_operands().next().unwrap().clone();
+                let mut names = Vec::new();
+                let mut functions = Vec::new();
+                match operand.value {
+                    Word::Ref(r) => {
+                        let (_, local) = self.ref_local(&r)?;
+                        self.validate_local(&r.name.value, local, &r.name.span);
+                        (self.code_meta.global_references).insert(r.name.clone(), local.index);
+                        let BindingKind::Module(path) = &self.asm.bindings[local.index].kind else {
+                            return Err(self.fatal_error(
+                                operand.span,
+                                format!(
+                                    "{}'s operand must be a module or a function pack, \
+                                    but `{}` is not a module",
+                                    Dispatch.format(),
+                                    r.name.value
+                                ),
+                            ));
+                        };
+                        for (name, local) in &self.imports[path].names {
+                            if !local.public {
+                                continue;
+                            }
+                            if let BindingKind::Func(f) = &self.asm.bindings[local.index].kind {
+                                names.push(name.clone());
+                                functions.push((f.clone(), operand.span.clone()));
+                            }
+                        }
+                    }
+                    Word::Pack(pack) => {
+                        for branch in pack.branches {
+                            let mut words =
+                                (branch.value.lines.iter().flatten()).filter(|w| w.value.is_code());
+                            let (
+                                Some(Sp {
+                                    value: Word::Ref(r),
+                                    ..
+                                }),
+                                None,
+                            ) = (words.next(), words.next())
+                            else {
+                                return Err(self.fatal_error(
+                                    branch.span,
+                                    format!(
+                                        "{}'s function pack may only contain named functions",
+                                        Dispatch.format()
+                                    ),
+                                ));
+                            };
+                            names.push(r.name.value.clone());
+                            let f = self.compile_func(branch.value, branch.span.clone())?;
+                            functions.push((f, branch.span));
+                        }
+                    }
+                    _ => {
+                        return Err(self.fatal_error(
+                            operand.span,
+                            format!(
+                                "{}'s operand must be a module or a function pack",
+                                Dispatch.format()
+                            ),
+                        ))
+                    }
+                }
+                if functions.is_empty() {
+                    return Err(self.fatal_error(
+                        modified.modifier.span.clone(),
+                        format!("{} has no functions to dispatch to", Dispatch.format()),
+                    ));
+                }
+                // Unify signatures the same way switch functions do
+                let mut sig = functions[0].0.signature();
+                for (f, span) in &functions[1..] {
+                    let f_sig = f.signature();
+                    if f_sig.is_compatible_with(sig) {
+                        sig = sig.max_with(f_sig);
+                    } else if f_sig.outputs == sig.outputs {
+                        sig.args = sig.args.max(f_sig.args);
+                    } else {
+                        self.add_error(
+                            span.clone(),
+                            format!(
+                                "Dispatched function's signature {f_sig} is \
+                                incompatible with previous functions {sig}",
+                            ),
+                        );
+                    }
+                }
+                let count = functions.len();
+                let span = self.add_span(modified.modifier.span.clone());
+                let names: Array<Boxed> = names.iter().map(|n| Boxed(n.as_str().into())).collect();
+                let mut instrs = eco_vec![
+                    Instr::push(names),
+                    Instr::ImplPrim(ImplPrimitive::DispatchIndex, span),
+                ];
+                instrs.extend(functions.into_iter().map(|(f, _)| Instr::PushFunc(f)));
+                instrs.push(Instr::Switch {
+                    count,
+                    sig,
+                    span,
+                    under_cond: false,
+                });
+                finish!(instrs, Signature::new(sig.args + 1, sig.outputs));
+            }
             _ => return Ok(false),
         }
         self.handle_primitive_experimental(prim, &modified.modifier.span);
         self.handle_primitive_deprecation(prim, &modified.modifier.span);
         Ok(true)
     }
+    /// Compile a data definition's match modifier
+    fn data_match(
+        &mut self,
+        data: DataMatch,
+        span: CodeSpan,
+        operands: Vec<Sp<Word>>,
+        call: bool,
+    ) -> UiuaResult {
+        let Some(operand) = (operands.into_iter()).find(|w| w.value.is_code()) else {
+            return Err(self.fatal_error(
+                span,
+                format!(
+                    "`{}~Match!` requires a function for each variant",
+                    data.name
+                ),
+            ));
+        };
+        let branches = match operand.value {
+            Word::Pack(pack) => pack.branches,
+            Word::Func(func) => vec![operand.span.sp(func)],
+            word => vec![operand.span.clone().sp(Func {
+                id: FunctionId::Anonymous(operand.span.clone()),
+                signature: None,
+                lines: vec![vec![operand.span.sp(word)]],
+                closed: true,
+            })],
+        };
+        // Check exhaustiveness
+        if branches.len() != data.variants.len() {
+            let names = |variants: &[(Ident, usize)]| {
+                (variants.iter().map(|(name, _)| name.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let message = if branches.len() < data.variants.len() {
+                format!(
+                    "`{}` has {} variants, but this match has {} functions. \
+                    Missing functions for: {}",
+                    data.name,
+                    data.variants.len(),
+                    branches.len(),
+                    names(&data.variants[branches.len()..])
+                )
+            } else {
+                format!(
+                    "`{}` has {} variants ({}), but this match has {} functions",
+                    data.name,
+                    data.variants.len(),
+                    names(&data.variants),
+                    branches.len(),
+                )
+            };
+            return Err(self.fatal_error(span, message));
+        }
+        // Each branch unpacks its variant's fields before being called
+        let mut new_branches = Vec::with_capacity(branches.len());
+        for ((_, field_count), branch) in data.variants.iter().zip(branches) {
+            let unpack = match field_count {
+                0 => "◌".into(),
+                1 => "°□⊡1".into(),
+                n => format!(
+                    "⊃({})",
+                    (1..=*n)
+                        .map(|i| format!("°□⊡{i}"))
+                        .collect::<Vec<_>>()
+                        .join("|")
+                ),
+            };
+            let mut line = vec![branch.span.clone().sp(Word::Func(branch.value))];
+            line.extend(self.generated_words(&unpack, &span)?);
+            new_branches.push(branch.span.clone().sp(Func {
+                id: FunctionId::Anonymous(branch.span),
+                signature: None,
+                lines: vec![line],
+                closed: true,
+            }));
+        }
+        // Switch on the tag
+        let mut line = vec![span.clone().sp(Word::Pack(FunctionPack {
+            branches: new_branches,
+            closed: true,
+            angled: false,
+        }))];
+        line.extend(self.generated_words("°□⊢.", &span)?);
+        let word = span.clone().sp(Word::Func(Func {
+            id: FunctionId::Named(format!("{}~Match!", data.name).into()),
+            signature: None,
+            lines: vec![line],
+            closed: true,
+        }));
+        self.words(vec![word], call)
+    }
+    /// Parse some generated code into words
+    fn generated_words(&mut self, code: &str, span: &CodeSpan) -> UiuaResult<Vec<Sp<Word>>> {
+        let (items, errors, _) = parse_with_aliases(
+            code,
+            InputSrc::Macro(span.clone().into()),
+            &mut self.asm.inputs,
+            &self.primitive_aliases,
+        );
+        if !errors.is_empty() {
+            return Err(UiuaErrorKind::Parse(errors, self.asm.inputs.clone().into()).error());
+        }
+        Ok((items.into_iter())
+            .flat_map(|item| match item {
+                Item::Words(lines) => lines.into_iter().flatten().collect(),
+                _ => Vec::new(),
+            })
+            .collect())
+    }
     /// Expand a stack macro
     fn expand_stack_macro(
         &mut self,
@@ -1142,10 +1474,11 @@ impl Compiler {
         Ok(())
     }
     fn quote(&mut self, code: &str, span: &CodeSpan, call: bool) -> UiuaResult {
-        let (items, errors, _) = parse(
+        let (items, errors, _) = parse_with_aliases(
             code,
             InputSrc::Macro(span.clone().into()),
             &mut self.asm.inputs,
+            &self.primitive_aliases,
         );
         if !errors.is_empty() {
             return Err(UiuaErrorKind::Parse(errors, self.asm.inputs.clone().into())
@@ -1165,9 +1498,15 @@ impl Compiler {
                 Item::Binding(binding) => self
                     .binding(binding, None)
                     .map_err(|e| e.trace_macro(span.clone()))?,
+                Item::Destructure(destructure) => self
+                    .destructure(destructure, None)
+                    .map_err(|e| e.trace_macro(span.clone()))?,
                 Item::Import(import) => self
                     .import(import, None)
                     .map_err(|e| e.trace_macro(span.clone()))?,
+                Item::Data(data) => self
+                    .data_def(data, None)
+                    .map_err(|e| e.trace_macro(span.clone()))?,
                 Item::TestScope(_) => {
                     self.add_error(span.clone(), "Macros may not generate test scopes")
                 }
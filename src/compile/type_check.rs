@@ -0,0 +1,431 @@
+//! An optional pass that checks for type and shape mismatches at compile time
+
+use enum_iterator::Sequence;
+
+use super::*;
+use crate::{PrimClass, Shape};
+
+/// What is statically known about a value
+#[derive(Debug, Clone, Default)]
+enum Known {
+    /// Nothing is known about the value
+    #[default]
+    Unknown,
+    /// The value's type and shape are known
+    Array { ty: &'static str, shape: Shape },
+}
+
+impl Known {
+    fn of(val: &Value) -> Self {
+        Known::Array {
+            ty: val.type_name(),
+            shape: val.shape().clone(),
+        }
+    }
+}
+
+/// What is known about the current fill value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FillState {
+    /// No fill value is set
+    None,
+    /// A fill value is set, but its type is unknown
+    Unknown,
+    /// A fill value of a known type is set
+    Known(&'static str),
+}
+
+/// The maximum depth of function calls the checker will follow
+const MAX_CALL_DEPTH: usize = 16;
+
+struct TypeChecker<'a> {
+    asm: &'a Assembly,
+    warnings: Vec<(String, CodeSpan)>,
+    depth: usize,
+}
+
+impl Compiler {
+    /// Check the code compiled since the given starting points for type and shape mismatches
+    pub(super) fn type_check_code(&mut self, top_slices_start: usize, bindings_start: usize) {
+        let mut checker = TypeChecker {
+            asm: &self.asm,
+            warnings: Vec::new(),
+            depth: 0,
+        };
+        for binding in &self.asm.bindings[bindings_start..] {
+            if let BindingKind::Func(f) = &binding.kind {
+                checker.check(f.instrs(checker.asm), &mut Vec::new(), FillState::None);
+            }
+        }
+        for slice in &self.asm.top_slices[top_slices_start..] {
+            checker.check(self.asm.instrs(*slice), &mut Vec::new(), FillState::None);
+        }
+        for (message, span) in checker.warnings {
+            self.emit_diagnostic(message, DiagnosticKind::Warning, span);
+        }
+        self.check_top_level_signatures(top_slices_start);
+    }
+    /// Warn about top-level code that takes more values than the code before it leaves on the stack
+    ///
+    /// Only the main file is checked, as it is the only one that starts with an empty stack.
+    fn check_top_level_signatures(&mut self, top_slices_start: usize) {
+        if !self.higher_scopes.is_empty() {
+            return;
+        }
+        let mut height = 0;
+        for slice in &self.asm.top_slices[top_slices_start..] {
+            let instrs = self.asm.instrs(*slice);
+            let Ok(sig) = instrs_signature(instrs) else {
+                return;
+            };
+            if sig.args > height {
+                let span =
+                    instrs
+                        .iter()
+                        .find_map(|instr| match self.asm.spans.get(instr.span()?) {
+                            Some(Span::Code(span)) => Some(span.clone()),
+                            _ => None,
+                        });
+                if let Some(span) = span {
+                    let message = format!(
+                        "This code takes {} value(s), but only {height} will be \
+                        on the stack, so it will fail at runtime",
+                        sig.args
+                    );
+                    self.emit_diagnostic(message, DiagnosticKind::Warning, span);
+                }
+                return;
+            }
+            height = height - sig.args + sig.outputs;
+        }
+    }
+}
+
+impl<'a> TypeChecker<'a> {
+    fn warn(&mut self, span: usize, message: String) {
+        if let Some(Span::Code(span)) = self.asm.spans.get(span) {
+            self.warnings.push((message, span.clone()));
+        }
+    }
+    /// Check some instructions
+    ///
+    /// Returns `false` if the checker could not follow the instructions
+    fn check(&mut self, instrs: &'a [Instr], stack: &mut Vec<Known>, fill: FillState) -> bool {
+        let mut temps: [Vec<Known>; TempStack::CARDINALITY] = Default::default();
+        let mut arrays: Vec<usize> = Vec::new();
+        let mut funcs: Vec<&'a Function> = Vec::new();
+        for instr in instrs {
+            match instr {
+                Instr::Comment(_)
+                | Instr::PushSig(_)
+                | Instr::PopSig
+                | Instr::NoInline
+                | Instr::SetOutputComment { .. }
+                | Instr::TouchStack { .. }
+                | Instr::Label { .. } => {}
+                Instr::Push(val) => stack.push(Known::of(val)),
+                Instr::CallGlobal { index, call } => {
+                    if *call {
+                        stack.push(match &self.asm.bindings[*index].kind {
+                            BindingKind::Const(Some(val)) => Known::of(val),
+                            _ => Known::Unknown,
+                        });
+                    }
+                }
+                Instr::BindGlobal { .. } => {
+                    stack.pop();
+                }
+                Instr::BeginArray => arrays.push(stack.len()),
+                Instr::EndArray { boxed, span } => {
+                    let Some(start) = arrays.pop() else {
+                        return false;
+                    };
+                    let underflow = start > stack.len();
+                    let mut elems = stack.split_off(start.min(stack.len()));
+                    elems.reverse();
+                    let known = if *boxed || underflow {
+                        Known::Unknown
+                    } else {
+                        self.combine(&elems, *span, fill, "array")
+                    };
+                    stack.push(known);
+                }
+                Instr::PushFunc(f) => funcs.push(f),
+                Instr::Call(_) => {
+                    let Some(f) = funcs.pop() else {
+                        return false;
+                    };
+                    if !self.call(f, stack, fill) {
+                        return false;
+                    }
+                }
+                Instr::Prim(Primitive::Fill, _) => {
+                    let (Some(fill_fn), Some(f)) = (funcs.pop(), funcs.pop()) else {
+                        return false;
+                    };
+                    let fill_instrs = fill_fn.instrs(self.asm);
+                    let new_fill = match fill_instrs {
+                        [Instr::Push(val)] => FillState::Known(val.type_name()),
+                        _ if fill_fn.signature().outputs == 0 => FillState::None,
+                        _ => FillState::Unknown,
+                    };
+                    let mut fill_stack = Vec::new();
+                    if !self.check(fill_instrs, &mut fill_stack, fill) {
+                        return false;
+                    }
+                    for _ in 0..fill_fn.signature().args {
+                        stack.pop();
+                    }
+                    if !self.call(f, stack, new_fill) {
+                        return false;
+                    }
+                }
+                Instr::Prim(prim @ (Primitive::Dup | Primitive::Over), _) => {
+                    let n = if let Primitive::Dup = prim { 1 } else { 2 };
+                    let val = (stack.len().checked_sub(n))
+                        .map(|i| stack[i].clone())
+                        .unwrap_or_default();
+                    stack.push(val);
+                }
+                Instr::Prim(Primitive::Flip, _) => {
+                    let a = stack.pop().unwrap_or_default();
+                    let b = stack.pop().unwrap_or_default();
+                    stack.push(a);
+                    stack.push(b);
+                }
+                Instr::Prim(Primitive::Pop, _) => {
+                    stack.pop();
+                }
+                Instr::Prim(Primitive::Identity, _) => {}
+                Instr::Prim(Primitive::Couple, span) => {
+                    let a = stack.pop().unwrap_or_default();
+                    let b = stack.pop().unwrap_or_default();
+                    let known = self.combine(&[a, b], *span, fill, "couple");
+                    stack.push(known);
+                }
+                Instr::Prim(prim, span) if prim.class() == PrimClass::DyadicPervasive => {
+                    let a = stack.pop().unwrap_or_default();
+                    let b = stack.pop().unwrap_or_default();
+                    let known = self.pervasive(*prim, a, b, *span, fill);
+                    stack.push(known);
+                }
+                Instr::Prim(prim, _) if prim.class() == PrimClass::MonadicPervasive => {
+                    let known = match stack.pop().unwrap_or_default() {
+                        Known::Array {
+                            ty: "number",
+                            shape,
+                        } => Known::Array {
+                            ty: "number",
+                            shape,
+                        },
+                        _ => Known::Unknown,
+                    };
+                    stack.push(known);
+                }
+                Instr::PushTemp {
+                    stack: ts, count, ..
+                } => {
+                    let vals = stack.split_off(stack.len().saturating_sub(*count));
+                    temps[*ts as usize].extend(vals);
+                }
+                Instr::PopTemp {
+                    stack: ts, count, ..
+                } => {
+                    let temp = &mut temps[*ts as usize];
+                    let vals = temp.split_off(temp.len().saturating_sub(*count));
+                    for _ in vals.len()..*count {
+                        stack.push(Known::Unknown);
+                    }
+                    stack.extend(vals);
+                }
+                Instr::CopyToTemp {
+                    stack: ts, count, ..
+                } => {
+                    let vals = &stack[stack.len().saturating_sub(*count)..];
+                    temps[*ts as usize].extend(vals.iter().cloned());
+                }
+                instr => {
+                    // Fall back to the instruction's signature
+                    let func_count = match instr {
+                        Instr::Prim(prim, _) => prim.modifier_args().unwrap_or(0),
+                        Instr::ImplPrim(prim, _) => prim.modifier_args().unwrap_or(0),
+                        Instr::Switch { count, .. } => *count,
+                        _ => 0,
+                    };
+                    if funcs.len() < func_count {
+                        return false;
+                    }
+                    let consumed = funcs.split_off(funcs.len() - func_count);
+                    for f in &consumed {
+                        self.check_standalone(f, fill);
+                    }
+                    let mut sig_instrs: Vec<Instr> = (consumed.into_iter())
+                        .map(|f| Instr::PushFunc(f.clone()))
+                        .collect();
+                    sig_instrs.push(instr.clone());
+                    let Ok(sig) = instrs_signature(&sig_instrs) else {
+                        return false;
+                    };
+                    for _ in 0..sig.args {
+                        stack.pop();
+                    }
+                    stack.extend((0..sig.outputs).map(|_| Known::Unknown));
+                }
+            }
+        }
+        for f in funcs {
+            self.check_standalone(f, fill);
+        }
+        true
+    }
+    /// Check a function called with the current stack
+    fn call(&mut self, f: &'a Function, stack: &mut Vec<Known>, fill: FillState) -> bool {
+        let sig = f.signature();
+        let mut args = stack.split_off(stack.len().saturating_sub(sig.args));
+        if self.depth < MAX_CALL_DEPTH {
+            self.depth += 1;
+            let missing = sig.args - args.len();
+            args.splice(0..0, (0..missing).map(|_| Known::Unknown));
+            let followed = self.check(f.instrs(self.asm), &mut args, fill);
+            self.depth -= 1;
+            if followed && args.len() == sig.outputs {
+                stack.extend(args);
+                return true;
+            }
+        }
+        stack.extend((0..sig.outputs).map(|_| Known::Unknown));
+        true
+    }
+    /// Check a function whose arguments are unknown
+    fn check_standalone(&mut self, f: &'a Function, fill: FillState) {
+        if self.depth < MAX_CALL_DEPTH {
+            self.depth += 1;
+            self.check(f.instrs(self.asm), &mut Vec::new(), fill);
+            self.depth -= 1;
+        }
+    }
+    /// Check the combination of arrays into a new array
+    fn combine(&mut self, elems: &[Known], span: usize, fill: FillState, verb: &str) -> Known {
+        let mut known = Vec::with_capacity(elems.len());
+        for elem in elems {
+            match elem {
+                Known::Array { ty, shape } => known.push((*ty, shape)),
+                Known::Unknown => return Known::Unknown,
+            }
+        }
+        let Some(&(first_ty, first_shape)) = known.first() else {
+            return Known::Unknown;
+        };
+        let mut ty = first_ty;
+        for &(elem_ty, _) in &known[1..] {
+            match (ty, elem_ty) {
+                (a, b) if a == b => {}
+                ("box", _) | (_, "box") => ty = "box",
+                ("complex", "number") | ("number", "complex") => ty = "complex",
+                (a, b) => {
+                    self.warn(
+                        span,
+                        format!(
+                            "Cannot {verb} {a} array with {b} array. \
+                            This will be an error at runtime."
+                        ),
+                    );
+                    return Known::Unknown;
+                }
+            }
+        }
+        if let Some(&(_, shape)) = known.iter().find(|(_, shape)| *shape != first_shape) {
+            match fill {
+                FillState::None => self.warn(
+                    span,
+                    format!(
+                        "Cannot {verb} arrays with shapes {first_shape} and {shape}. \
+                        This will be an error at runtime."
+                    ),
+                ),
+                FillState::Known(fill_ty) if fill_ty != ty && ty != "box" => self.warn(
+                    span,
+                    format!(
+                        "Cannot {verb} arrays with shapes {first_shape} and {shape}. \
+                        A {fill_ty} fill is set, but the array is {}, \
+                        so the fill will not be used.",
+                        plural(ty)
+                    ),
+                ),
+                _ => {}
+            }
+            return Known::Unknown;
+        }
+        let mut shape = first_shape.clone();
+        shape.insert(0, known.len());
+        Known::Array { ty, shape }
+    }
+    /// Check a dyadic pervasive operation
+    fn pervasive(
+        &mut self,
+        prim: Primitive,
+        a: Known,
+        b: Known,
+        span: usize,
+        fill: FillState,
+    ) -> Known {
+        let (
+            Known::Array {
+                ty: a_ty,
+                shape: a_shape,
+            },
+            Known::Array {
+                ty: b_ty,
+                shape: b_shape,
+            },
+        ) = (a, b)
+        else {
+            return Known::Unknown;
+        };
+        if prim == Primitive::Add && a_ty == "character" && b_ty == "character" {
+            self.warn(
+                span,
+                "Cannot add character and character. \
+                This will be an error at runtime."
+                    .into(),
+            );
+            return Known::Unknown;
+        }
+        if !a_shape.iter().zip(&b_shape).all(|(a, b)| a == b) {
+            if fill == FillState::None {
+                self.warn(
+                    span,
+                    format!(
+                        "Shapes {a_shape} and {b_shape} do not match. \
+                        This will be an error at runtime."
+                    ),
+                );
+            }
+            return Known::Unknown;
+        }
+        let shape = if a_shape.len() >= b_shape.len() {
+            a_shape
+        } else {
+            b_shape
+        };
+        let ty = match prim {
+            Primitive::Eq
+            | Primitive::Ne
+            | Primitive::Lt
+            | Primitive::Le
+            | Primitive::Gt
+            | Primitive::Ge => "number",
+            _ if a_ty == "number" && b_ty == "number" => "number",
+            _ => return Known::Unknown,
+        };
+        Known::Array { ty, shape }
+    }
+}
+
+fn plural(ty: &str) -> String {
+    match ty {
+        "box" => "boxes".into(),
+        "complex" => "complexes".into(),
+        ty => format!("{ty}s"),
+    }
+}
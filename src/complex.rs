@@ -197,6 +197,18 @@ impl From<u8> for Complex {
     }
 }
 
+impl From<i64> for Complex {
+    fn from(value: i64) -> Self {
+        (value as f64).into()
+    }
+}
+
+impl From<f32> for Complex {
+    fn from(value: f32) -> Self {
+        f64::from(value).into()
+    }
+}
+
 impl fmt::Display for Complex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.im == 0.0 {
@@ -237,6 +249,13 @@ impl Add<Complex> for f64 {
     }
 }
 
+impl AddAssign for Complex {
+    fn add_assign(&mut self, rhs: Self) {
+        self.re += rhs.re;
+        self.im += rhs.im;
+    }
+}
+
 impl Sub for Complex {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
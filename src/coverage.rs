@@ -0,0 +1,136 @@
+//! Code coverage reports
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{Assembly, InputSrc, Span};
+
+/// Counts of how many times each instruction of an [`Assembly`] was executed
+///
+/// Counting is enabled with [`Uiua::with_coverage`](crate::Uiua::with_coverage).
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    counts: Vec<u64>,
+}
+
+impl Coverage {
+    pub(crate) fn hit(&mut self, instr: usize) {
+        if instr >= self.counts.len() {
+            self.counts.resize(instr + 1, 0);
+        }
+        self.counts[instr] += 1;
+    }
+    /// Get the number of times the instruction at an index was executed
+    pub fn count(&self, instr: usize) -> u64 {
+        self.counts.get(instr).copied().unwrap_or(0)
+    }
+    /// Get the execution count of each line of code in each input
+    ///
+    /// Only lines that compiled to instructions are included.
+    /// A line's count is the greatest count of the instructions that start on it.
+    /// Code generated by macros counts toward the line where the macro is called.
+    pub fn lines(&self, asm: &Assembly) -> BTreeMap<InputSrc, BTreeMap<usize, u64>> {
+        let mut lines: BTreeMap<InputSrc, BTreeMap<usize, u64>> = BTreeMap::new();
+        for (i, instr) in asm.instrs.iter().enumerate() {
+            let Some(Span::Code(span)) = instr.span().map(|span| &asm.spans[span]) else {
+                continue;
+            };
+            let mut span = span;
+            while let InputSrc::Macro(call) = &span.src {
+                span = call;
+            }
+            let count = (lines.entry(span.src.clone()).or_default())
+                .entry(span.start.line as usize)
+                .or_default();
+            *count = (*count).max(self.count(i));
+        }
+        lines
+    }
+    /// Generate a report in the LCOV tracefile format
+    ///
+    /// Only code from files is included.
+    pub fn lcov(&self, asm: &Assembly) -> String {
+        let mut lcov = String::new();
+        for (src, lines) in self.lines(asm) {
+            let InputSrc::File(path) = src else {
+                continue;
+            };
+            _ = writeln!(lcov, "TN:");
+            _ = writeln!(lcov, "SF:{}", path.display());
+            for (line, count) in &lines {
+                _ = writeln!(lcov, "DA:{line},{count}");
+            }
+            _ = writeln!(lcov, "LF:{}", lines.len());
+            _ = writeln!(lcov, "LH:{}", lines.values().filter(|&&c| c > 0).count());
+            _ = writeln!(lcov, "end_of_record");
+        }
+        lcov
+    }
+    /// Generate a standalone HTML page showing the code of each input with its execution counts
+    pub fn html(&self, asm: &Assembly) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n\
+            <html>\n\
+            <head>\n\
+            <meta charset=\"utf-8\">\n\
+            <title>Uiua Coverage</title>\n\
+            <style>\n\
+            body { font-family: sans-serif; }\n\
+            table { border-collapse: collapse; font-family: monospace; white-space: pre; }\n\
+            td { padding: 0 0.5em; }\n\
+            td.n { text-align: right; color: gray; }\n\
+            tr.hit { background-color: #cfc; }\n\
+            tr.miss { background-color: #fcc; }\n\
+            </style>\n\
+            </head>\n\
+            <body>\n",
+        );
+        for (src, lines) in self.lines(asm) {
+            let name = match &src {
+                InputSrc::File(path) => path.display().to_string(),
+                InputSrc::Str(i) => format!("<input {}>", i + 1),
+                InputSrc::Macro(_) => continue,
+            };
+            let hit = lines.values().filter(|&&c| c > 0).count();
+            _ = writeln!(
+                html,
+                "<h2>{} - {hit}/{} lines ({:.1}%)</h2>",
+                escape_html(&name),
+                lines.len(),
+                hit as f64 / lines.len().max(1) as f64 * 100.0
+            );
+            html.push_str("<table>\n");
+            let text = asm.inputs.try_get_with(&src, |s| s.to_string());
+            for (i, code) in text.iter().flat_map(|s| s.lines()).enumerate() {
+                let line = i + 1;
+                let (class, count) = match lines.get(&line) {
+                    Some(0) => (" class=\"miss\"", "0".into()),
+                    Some(count) => (" class=\"hit\"", count.to_string()),
+                    None => ("", String::new()),
+                };
+                _ = writeln!(
+                    html,
+                    "<tr{class}><td class=\"n\">{line}</td>\
+                    <td class=\"n\">{count}</td><td>{}</td></tr>",
+                    escape_html(code)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
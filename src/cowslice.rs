@@ -5,8 +5,10 @@ use std::{
     cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{align_of, needs_drop, size_of, MaybeUninit},
     ops::{Bound, Deref, RangeBounds},
-    ptr,
+    ptr, slice,
 };
 
 use serde::*;
@@ -30,10 +32,86 @@ use ecow::EcoVec;
 ///
 /// `CowSlice`s are reference-counted buffers that also have associated start and end indices.
 /// This allows them to be split into chunks without copying the data.
+///
+/// Buffers of a few small elements are stored inline so that scalars do not need to allocate.
 pub struct CowSlice<T> {
-    data: EcoVec<T>,
-    start: usize,
-    end: usize,
+    repr: Repr<T>,
+}
+
+enum Repr<T> {
+    /// A range of a shared buffer
+    Heap {
+        data: EcoVec<T>,
+        start: usize,
+        end: usize,
+    },
+    /// Elements stored in place
+    Inline(Inline<T>),
+}
+
+/// The number of bytes of elements that can be stored inline
+const INLINE_BYTES: usize = 16;
+
+/// A small buffer of elements that do not need to be dropped
+struct Inline<T> {
+    buf: [MaybeUninit<u64>; INLINE_BYTES / size_of::<u64>()],
+    len: u8,
+    _elem: PhantomData<T>,
+}
+
+impl<T> Inline<T> {
+    /// Whether `len` elements can be stored inline
+    const fn fits(len: usize) -> bool {
+        !needs_drop::<T>()
+            && size_of::<T>() > 0
+            && align_of::<T>() <= align_of::<u64>()
+            && size_of::<T>() * len <= INLINE_BYTES
+    }
+    fn new() -> Self {
+        Inline {
+            buf: [MaybeUninit::uninit(); INLINE_BYTES / size_of::<u64>()],
+            len: 0,
+            _elem: PhantomData,
+        }
+    }
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+    fn as_slice(&self) -> &[T] {
+        // Safety: the first `len` elements are initialized
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len()) }
+    }
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        // Safety: the first `len` elements are initialized
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len()) }
+    }
+    /// Add an element to the end
+    ///
+    /// The caller must ensure that it [fits](Self::fits)
+    fn push(&mut self, item: T) {
+        debug_assert!(Self::fits(self.len() + 1));
+        // Safety: `fits` guarantees that there is room and that `T` is aligned
+        unsafe {
+            (self.buf.as_mut_ptr() as *mut T)
+                .add(self.len())
+                .write(item)
+        };
+        self.len += 1;
+    }
+    fn truncate(&mut self, len: usize) {
+        // Elements do not need to be dropped
+        self.len = self.len.min(len as u8);
+    }
+}
+
+impl<T: Clone> Inline<T> {
+    fn from_slice(slice: &[T]) -> Self {
+        let mut inline = Inline::new();
+        for item in slice {
+            inline.push(item.clone());
+        }
+        inline
+    }
 }
 
 impl<T> CowSlice<T> {
@@ -42,50 +120,111 @@ impl<T> CowSlice<T> {
     }
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
+        if Inline::<T>::fits(capacity) {
+            Self::inline(Inline::new())
+        } else {
+            Self::heap(EcoVec::with_capacity(capacity), 0, 0)
+        }
+    }
+    fn heap(data: EcoVec<T>, start: usize, end: usize) -> Self {
+        Self {
+            repr: Repr::Heap { data, start, end },
+        }
+    }
+    fn inline(inline: Inline<T>) -> Self {
         Self {
-            data: EcoVec::with_capacity(capacity),
-            start: 0,
-            end: 0,
+            repr: Repr::Inline(inline),
         }
     }
     #[inline]
     pub fn as_slice(&self) -> &[T] {
-        &self.data[self.start..self.end]
+        match &self.repr {
+            Repr::Heap { data, start, end } => &data[*start..*end],
+            Repr::Inline(inline) => inline.as_slice(),
+        }
     }
     #[inline]
     pub fn len(&self) -> usize {
-        self.end - self.start
+        match &self.repr {
+            Repr::Heap { start, end, .. } => end - start,
+            Repr::Inline(inline) => inline.len(),
+        }
     }
     #[inline]
     pub fn is_unique(&mut self) -> bool {
-        self.data.is_unique()
+        match &mut self.repr {
+            Repr::Heap { data, .. } => data.is_unique(),
+            Repr::Inline(_) => true,
+        }
     }
     pub fn is_copy_of(&self, other: &Self) -> bool {
-        ptr::eq(self.data.as_ptr(), other.data.as_ptr())
-            && self.start == other.start
-            && self.end == other.end
+        match (&self.repr, &other.repr) {
+            (
+                Repr::Heap { data, start, end },
+                Repr::Heap {
+                    data: other_data,
+                    start: other_start,
+                    end: other_end,
+                },
+            ) => {
+                ptr::eq(data.as_ptr(), other_data.as_ptr())
+                    && start == other_start
+                    && end == other_end
+            }
+            _ => false,
+        }
+    }
+    /// Try to add elements to inline storage without allocating
+    ///
+    /// Returns the iterator if the elements might not fit
+    fn try_extend_inline<I: Iterator<Item = T>>(&mut self, iter: I) -> Result<(), I> {
+        let Repr::Inline(inline) = &mut self.repr else {
+            return Err(iter);
+        };
+        match iter.size_hint() {
+            (_, Some(upper)) if Inline::<T>::fits(inline.len() + upper) => {
+                for item in iter.take(upper) {
+                    inline.push(item);
+                }
+                Ok(())
+            }
+            _ => Err(iter),
+        }
     }
 }
 
 impl<T: Clone> CowSlice<T> {
     pub fn truncate(&mut self, len: usize) {
-        if self.is_unique() {
-            self.data.truncate(self.start + len);
+        let unique = self.is_unique();
+        match &mut self.repr {
+            Repr::Heap { data, start, end } => {
+                if unique {
+                    data.truncate(*start + len);
+                }
+                *end = (*start + len).min(*end);
+            }
+            Repr::Inline(inline) => inline.truncate(len),
         }
-        self.end = (self.start + len).min(self.end);
     }
     pub fn as_mut_slice(&mut self) -> &mut [T] {
-        if !self.data.is_unique() {
-            let mut new_data = EcoVec::with_capacity(self.len());
-            new_data.extend_from_slice(&*self);
-            self.data = new_data;
-            self.start = 0;
-            self.end = self.data.len();
+        match &mut self.repr {
+            Repr::Heap { data, start, end } => {
+                if !data.is_unique() {
+                    let mut new_data = EcoVec::with_capacity(*end - *start);
+                    new_data.extend_from_slice(&data[*start..*end]);
+                    *data = new_data;
+                    *start = 0;
+                    *end = data.len();
+                }
+                &mut data.make_mut()[*start..*end]
+            }
+            Repr::Inline(inline) => inline.as_mut_slice(),
         }
-        &mut self.data.make_mut()[self.start..self.end]
     }
     pub fn extend_from_slice(&mut self, other: &[T]) {
-        self.modify(|vec| vec.extend_from_slice(other))
+        if self.try_extend_inline(other.iter().cloned()).is_err() {
+            self.modify(|vec| vec.extend_from_slice(other))
+        }
     }
     #[track_caller]
     pub fn slice<R>(&self, range: R) -> Self
@@ -93,21 +232,22 @@ impl<T: Clone> CowSlice<T> {
         R: RangeBounds<usize>,
     {
         let start = match range.start_bound() {
-            Bound::Included(&start) => self.start + start,
-            Bound::Excluded(&start) => self.start + start + 1,
-            Bound::Unbounded => self.start,
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
         };
         let end = match range.end_bound() {
-            Bound::Included(&end) => self.start + end + 1,
-            Bound::Excluded(&end) => self.start + end,
-            Bound::Unbounded => self.end,
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
         };
         assert!(start <= end);
-        assert!(end <= self.end);
-        Self {
-            data: self.data.clone(),
-            start,
-            end,
+        assert!(end <= self.len());
+        match &self.repr {
+            Repr::Heap { data, start: s, .. } => Self::heap(data.clone(), s + start, s + end),
+            Repr::Inline(inline) => {
+                Self::inline(Inline::from_slice(&inline.as_slice()[start..end]))
+            }
         }
     }
     /// Get an iterator over slices with the given size
@@ -124,29 +264,26 @@ impl<T: Clone> CowSlice<T> {
             assert!(self.len() % size == 0);
             self.len() / size
         };
-        (0..count).map(move |i| {
-            let start = self.start + (i * size);
-            Self {
-                data: self.data.clone(),
-                start,
-                end: start + size,
-            }
-        })
+        (0..count).map(move |i| self.slice(i * size..(i + 1) * size))
     }
     #[track_caller]
     fn modify<F, R>(&mut self, f: F) -> R
     where
         F: FnOnce(&mut EcoVec<T>) -> R,
     {
-        if self.data.is_unique() && self.start == 0 && self.end == self.data.len() {
-            let res = f(&mut self.data);
-            self.end = self.data.len();
-            res
-        } else {
-            let mut vec = EcoVec::from(&**self);
-            let res = f(&mut vec);
-            *self = vec.into();
-            res
+        let unique = self.is_unique();
+        match &mut self.repr {
+            Repr::Heap { data, start, end } if unique && *start == 0 && *end == data.len() => {
+                let res = f(data);
+                *end = data.len();
+                res
+            }
+            _ => {
+                let mut vec = EcoVec::from(&**self);
+                let res = f(&mut vec);
+                *self = vec.into();
+                res
+            }
         }
     }
     #[track_caller]
@@ -154,34 +291,45 @@ impl<T: Clone> CowSlice<T> {
     where
         F: FnOnce(&mut EcoVec<T>) -> R,
     {
-        if self.data.is_unique() && self.end == self.data.len() {
-            let res = f(&mut self.data);
-            self.end = self.data.len();
-            res
-        } else {
-            let mut vec = EcoVec::from(&**self);
-            let res = f(&mut vec);
-            *self = vec.into();
-            res
+        let unique = self.is_unique();
+        match &mut self.repr {
+            Repr::Heap { data, end, .. } if unique && *end == data.len() => {
+                let res = f(data);
+                *end = data.len();
+                res
+            }
+            _ => {
+                let mut vec = EcoVec::from(&**self);
+                let res = f(&mut vec);
+                *self = vec.into();
+                res
+            }
         }
     }
     /// Clear the buffer
     pub fn clear(&mut self) {
-        if self.is_unique() {
+        if let Repr::Inline(inline) = &mut self.repr {
+            inline.truncate(0);
+        } else if self.is_unique() {
             self.modify(|vec| vec.clear());
         } else {
-            self.data = EcoVec::new();
+            *self = Self::default();
         }
-        self.start = 0;
-        self.end = 0;
     }
     /// Reserve space for at least `additional` more elements
     pub fn reserve(&mut self, additional: usize) {
-        self.modify(|vec| vec.reserve(additional))
+        if !matches!(&self.repr, Repr::Inline(_) if Inline::<T>::fits(self.len() + additional)) {
+            self.modify(|vec| vec.reserve(additional))
+        }
     }
     /// Ensure that the capacity is at least `min`
     pub fn reserve_min(&mut self, min: usize) {
-        if self.data.capacity() < min {
+        let capacity = match &self.repr {
+            Repr::Heap { data, .. } => data.capacity(),
+            Repr::Inline(_) if Inline::<T>::fits(min) => min,
+            Repr::Inline(inline) => inline.len(),
+        };
+        if capacity < min {
             self.modify(|vec| vec.reserve(min - vec.len()))
         }
     }
@@ -196,29 +344,39 @@ impl<T: Clone> CowSlice<T> {
     where
         R: RangeBounds<usize>,
     {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end);
+        if let Repr::Inline(inline) = &mut self.repr {
+            inline.as_mut_slice().rotate_left(start);
+            inline.truncate(len - (end - start));
+            return;
+        }
         self.modify(|data| {
-            let start = match range.start_bound() {
-                Bound::Included(&start) => start,
-                Bound::Excluded(&start) => start + 1,
-                Bound::Unbounded => 0,
-            };
-            let end = match range.end_bound() {
-                Bound::Included(&end) => end + 1,
-                Bound::Excluded(&end) => end,
-                Bound::Unbounded => data.len(),
-            };
-            assert!(start <= end);
             data.make_mut().rotate_left(start);
             data.truncate(data.len() - (end - start));
         })
     }
     #[track_caller]
     pub fn extend_from_array<const N: usize>(&mut self, array: [T; N]) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(array) })
+        if let Err(iter) = self.try_extend_inline(array.into_iter()) {
+            self.modify_end(|data| unsafe { data.extend_from_trusted(iter) })
+        }
     }
     #[track_caller]
     pub fn extend_from_vec(&mut self, vec: Vec<T>) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(vec) })
+        if let Err(iter) = self.try_extend_inline(vec.into_iter()) {
+            self.modify_end(|data| unsafe { data.extend_from_trusted(iter) })
+        }
     }
     #[track_caller]
     pub fn extend_from_ecovec(&mut self, vec: EcoVec<T>) {
@@ -226,7 +384,9 @@ impl<T: Clone> CowSlice<T> {
     }
     #[track_caller]
     pub fn extend_from_cowslice(&mut self, slice: CowSlice<T>) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(slice) })
+        if let Err(iter) = self.try_extend_inline(slice.into_iter()) {
+            self.modify_end(|data| unsafe { data.extend_from_trusted(iter) })
+        }
     }
     #[track_caller]
     pub unsafe fn extend_from_trusted<I>(&mut self, iter: I)
@@ -234,7 +394,9 @@ impl<T: Clone> CowSlice<T> {
         I: IntoIterator<Item = T>,
         I::IntoIter: ExactSizeIterator,
     {
-        self.modify_end(|data| data.extend_from_trusted(iter))
+        if let Err(iter) = self.try_extend_inline(iter.into_iter()) {
+            self.modify_end(|data| data.extend_from_trusted(iter))
+        }
     }
 }
 
@@ -252,20 +414,15 @@ fn cow_slice_modify() {
 
 impl<T> Default for CowSlice<T> {
     fn default() -> Self {
-        Self {
-            data: EcoVec::new(),
-            start: 0,
-            end: 0,
-        }
+        Self::with_capacity(0)
     }
 }
 
 impl<T: Clone> Clone for CowSlice<T> {
     fn clone(&self) -> Self {
-        Self {
-            data: self.data.clone(),
-            start: self.start,
-            end: self.end,
+        match &self.repr {
+            Repr::Heap { data, start, end } => Self::heap(data.clone(), *start, *end),
+            Repr::Inline(inline) => Self::inline(Inline::from_slice(inline.as_slice())),
         }
     }
 }
@@ -289,43 +446,60 @@ fn cow_slice_deref_mut() {
     assert_eq!(sub, [7, 5]);
 }
 
+#[test]
+fn cow_slice_inline() {
+    let mut slice = CowSlice::from([1.0, 2.0]);
+    assert!(matches!(slice.repr, Repr::Inline(_)));
+    slice.extend_from_slice(&[3.0, 4.0]);
+    assert!(matches!(slice.repr, Repr::Heap { .. }));
+    assert_eq!(slice, [1.0, 2.0, 3.0, 4.0]);
+
+    let mut sub = slice.slice(1..3);
+    sub.as_mut_slice()[0] = 5.0;
+    assert_eq!(slice, [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(sub, [5.0, 3.0]);
+
+    let mut bytes: CowSlice<u8> = (0..16).collect();
+    assert!(matches!(bytes.repr, Repr::Inline(_)));
+    bytes.truncate(3);
+    assert_eq!(bytes.split_off(1), [1, 2]);
+    assert_eq!(bytes, [0]);
+}
+
 impl<T: Clone> From<CowSlice<T>> for Vec<T> {
     fn from(mut slice: CowSlice<T>) -> Self {
-        if slice.data.is_unique() && slice.start == 0 && slice.end == slice.data.len() {
-            slice.data.into_iter().collect()
-        } else {
-            slice.to_vec()
+        let unique = slice.is_unique();
+        match slice.repr {
+            Repr::Heap { data, start, end } if unique && start == 0 && end == data.len() => {
+                data.into_iter().collect()
+            }
+            _ => slice.to_vec(),
         }
     }
 }
 
 impl<T: Clone> From<EcoVec<T>> for CowSlice<T> {
     fn from(data: EcoVec<T>) -> Self {
-        Self {
-            start: 0,
-            end: data.len(),
-            data,
-        }
+        let end = data.len();
+        Self::heap(data, 0, end)
     }
 }
 
 impl<'a, T: Clone> From<&'a [T]> for CowSlice<T> {
     fn from(slice: &'a [T]) -> Self {
-        Self {
-            start: 0,
-            end: slice.len(),
-            data: slice.into(),
+        if Inline::<T>::fits(slice.len()) {
+            Self::inline(Inline::from_slice(slice))
+        } else {
+            Self::heap(slice.into(), 0, slice.len())
         }
     }
 }
 
 impl<T: Clone, const N: usize> From<[T; N]> for CowSlice<T> {
     fn from(array: [T; N]) -> Self {
-        Self {
-            start: 0,
-            end: N,
-            data: array.into(),
-        }
+        let mut slice = Self::with_capacity(N);
+        slice.extend_from_array(array);
+        slice
     }
 }
 
@@ -396,33 +570,27 @@ impl<T: Clone> IntoIterator for CowSlice<T> {
     type IntoIter = CowSliceIntoIter<T>;
     fn into_iter(self) -> Self::IntoIter {
         CowSliceIntoIter {
-            data: self.data,
-            start: self.start,
-            end: self.end,
+            slice: self,
+            index: 0,
         }
     }
 }
 
 /// An iterator over a CowSlice
 pub struct CowSliceIntoIter<T> {
-    data: EcoVec<T>,
-    start: usize,
-    end: usize,
+    slice: CowSlice<T>,
+    index: usize,
 }
 
 impl<T: Clone> Iterator for CowSliceIntoIter<T> {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.start >= self.end {
-            None
-        } else {
-            let item = unsafe { self.data.get_unchecked(self.start) }.clone();
-            self.start += 1;
-            Some(item)
-        }
+        let item = self.slice.get(self.index)?.clone();
+        self.index += 1;
+        Some(item)
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.end - self.start;
+        let len = self.slice.len() - self.index;
         (len, Some(len))
     }
 }
@@ -447,15 +615,17 @@ impl<'a, T: Clone> IntoIterator for &'a mut CowSlice<T> {
 
 impl<T: Clone> FromIterator<T> for CowSlice<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut data = EcoVec::new();
-        data.extend(iter);
-        data.into()
+        let mut slice = Self::new();
+        slice.extend(iter);
+        slice
     }
 }
 
 impl<T: Clone> Extend<T> for CowSlice<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        self.modify_end(|vec| vec.extend(iter))
+        if let Err(iter) = self.try_extend_inline(iter.into_iter()) {
+            self.modify_end(|vec| vec.extend(iter))
+        }
     }
 }
 
@@ -1,4 +1,11 @@
-use std::{convert::Infallible, error::Error, fmt, io, mem::take, path::PathBuf, sync::Arc};
+use std::{
+    convert::Infallible,
+    error::Error,
+    fmt, io,
+    mem::{replace, take},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use colored::*;
 
@@ -7,7 +14,7 @@ use crate::{
     lex::{Sp, Span},
     parse::ParseError,
     value::Value,
-    CodeSpan, InputSrc, Inputs,
+    CodeSpan, InputSrc, Inputs, Shape,
 };
 
 /// An error produced when running/compiling/formatting a Uiua program
@@ -26,6 +33,106 @@ pub struct UiuaError {
     pub multi: Vec<Self>,
     /// Additional info about the error
     pub infos: Vec<(String, Option<Span>)>,
+    /// A snapshot of the value stack at the point of failure, bottom first
+    ///
+    /// Only captured if enabled with [`Uiua::with_stack_snapshots`](crate::Uiua::with_stack_snapshots)
+    pub stack_snapshot: Option<Vec<ValueSnapshot>>,
+    /// The most recently executed instructions before the error, oldest first
+    ///
+    /// Only recorded if enabled with [`Uiua::with_instr_history`](crate::Uiua::with_instr_history)
+    pub instr_history: Option<Vec<InstrRecord>>,
+}
+
+/// A record of an instruction that was executed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstrRecord {
+    /// The instruction, formatted
+    pub instr: String,
+    /// The span of the code that compiled to the instruction, if it has one
+    pub span: Option<CodeSpan>,
+    /// The shapes of the values at the top of the stack after the instruction was executed, top first
+    pub shapes: Vec<Shape>,
+}
+
+impl fmt::Display for InstrRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.instr)?;
+        if !self.shapes.is_empty() {
+            write!(f, " →")?;
+            for shape in &self.shapes {
+                write!(f, " {shape}")?;
+            }
+        }
+        if let Some(span) = &self.span {
+            write!(f, " at {span}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A summary of a value on the stack when an error occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueSnapshot {
+    /// The name of the value's type
+    pub type_name: &'static str,
+    /// The shape of the value
+    pub shape: Shape,
+    /// A truncated, single-line preview of the value's elements
+    pub preview: String,
+}
+
+impl ValueSnapshot {
+    const MAX_ELEMS: usize = 8;
+    const MAX_CHARS: usize = 40;
+    /// Take a snapshot of a value
+    pub fn new(value: &Value) -> Self {
+        let mut preview = Self::preview(value);
+        if preview.chars().count() > Self::MAX_CHARS {
+            preview = preview.chars().take(Self::MAX_CHARS - 1).collect();
+            preview.push('…');
+        }
+        ValueSnapshot {
+            type_name: value.type_name(),
+            shape: value.shape().clone(),
+            preview,
+        }
+    }
+    fn preview(value: &Value) -> String {
+        match value {
+            Value::Box(arr) if arr.rank() == 0 => {
+                format!("□{}", Self::preview(&arr.data[0].0))
+            }
+            Value::Char(arr) if arr.rank() == 1 && arr.row_count() > Self::MAX_CHARS => {
+                let s: String = arr.data.iter().take(Self::MAX_CHARS).collect();
+                format!("{s:?}")
+            }
+            value if value.rank() == 0 || matches!(value, Value::Char(arr) if arr.rank() == 1) => {
+                value.format()
+            }
+            value => {
+                let mut flat = value.clone();
+                flat.deshape();
+                let mut preview = String::from("[");
+                for (i, row) in flat.rows().take(Self::MAX_ELEMS).enumerate() {
+                    if i > 0 {
+                        preview.push(' ');
+                    }
+                    preview.push_str(&Self::preview(&row));
+                }
+                if flat.row_count() > Self::MAX_ELEMS {
+                    preview.push_str(" …");
+                }
+                preview.push(']');
+                preview
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValueSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.type_name, self.shape, self.preview)
+    }
 }
 
 /// The kind of an error produced when running/compiling/formatting a Uiua program
@@ -63,6 +170,8 @@ impl From<UiuaErrorKind> for UiuaError {
             is_case: false,
             multi: Vec::new(),
             infos: Vec::new(),
+            stack_snapshot: None,
+            instr_history: None,
         }
     }
 }
@@ -247,6 +356,34 @@ impl UiuaError {
                 report.fragments.push(ReportFragment::Plain(info.into()));
             }
         }
+        if let Some(snapshot) = &self.stack_snapshot {
+            report.fragments.push(ReportFragment::Newline);
+            report
+                .fragments
+                .push(ReportFragment::Faint(if snapshot.is_empty() {
+                    "Stack at error: (empty)".into()
+                } else {
+                    "Stack at error (top first):".into()
+                }));
+            for value in snapshot.iter().rev() {
+                report.fragments.push(ReportFragment::Newline);
+                report
+                    .fragments
+                    .push(ReportFragment::Faint(format!("  {value}")));
+            }
+        }
+        if let Some(history) = &self.instr_history {
+            report.fragments.push(ReportFragment::Newline);
+            report.fragments.push(ReportFragment::Faint(
+                "Recent instructions (oldest first):".into(),
+            ));
+            for record in history {
+                report.fragments.push(ReportFragment::Newline);
+                report
+                    .fragments
+                    .push(ReportFragment::Faint(format!("  {record}")));
+            }
+        }
         report
     }
 }
@@ -430,10 +567,8 @@ impl Report {
                 }
                 fragments.push(ReportFragment::Plain(line.into()));
             }
-            if let Span::Code(mut span) = span {
-                while let InputSrc::Macro(inner) = span.src {
-                    span = *inner;
-                }
+            if let Span::Code(span) = span {
+                let (span, expansions) = macro_expansions(span);
                 fragments.push(ReportFragment::Newline);
                 fragments.push(ReportFragment::Fainter("  at ".into()));
                 if let InputSrc::File(path) = &span.src {
@@ -443,39 +578,13 @@ impl Report {
                     "{}:{}",
                     span.start.line, span.start.col
                 )));
-                fragments.push(ReportFragment::Newline);
-                let line_prefix = format!("{} | ", span.start.line);
-                fragments.push(ReportFragment::Plain(line_prefix.clone()));
-                let input = inputs.get(&span.src);
-                let line = input
-                    .lines()
-                    .nth(span.start.line as usize - 1)
-                    .unwrap_or("");
-                let start_char_pos = span.start.col - 1;
-                let end_char_pos = if span.start.line == span.end.line {
-                    span.end.col - 1
-                } else {
-                    line.chars().count() as u16
-                };
-                let pre_color: String = line.chars().take(start_char_pos as usize).collect();
-                let color: String = line
-                    .chars()
-                    .skip(start_char_pos as usize)
-                    .take(end_char_pos.saturating_sub(start_char_pos).max(1) as usize)
-                    .collect();
-                let post_color: String = line.chars().skip(end_char_pos as usize).collect();
-                fragments.push(ReportFragment::Faint(pre_color));
-                fragments.push(ReportFragment::Colored(color, kind));
-                fragments.push(ReportFragment::Faint(post_color));
-                fragments.push(ReportFragment::Newline);
-                fragments.push(ReportFragment::Plain(
-                    " ".repeat(line_prefix.chars().count()),
-                ));
-                fragments.push(ReportFragment::Plain(" ".repeat(start_char_pos as usize)));
-                fragments.push(ReportFragment::Colored(
-                    "─".repeat(end_char_pos.saturating_sub(start_char_pos).max(1) as usize),
-                    kind,
-                ));
+                push_snippet(&mut fragments, inputs, &span, kind);
+                for generated in expansions {
+                    fragments.push(ReportFragment::Newline);
+                    let message = macro_expansion_message(inputs, &generated);
+                    fragments.push(ReportFragment::Fainter(format!("  {message}")));
+                    push_snippet(&mut fragments, inputs, &generated, kind);
+                }
             }
         }
         Self {
@@ -485,6 +594,73 @@ impl Report {
     }
 }
 
+/// Split a span in macro-generated code into the outermost call site
+/// and the spans in each level of generated code, outermost first
+pub(crate) fn macro_expansions(mut span: CodeSpan) -> (CodeSpan, Vec<CodeSpan>) {
+    let mut expansions = Vec::new();
+    while let InputSrc::Macro(call) = &span.src {
+        let call = (**call).clone();
+        expansions.push(replace(&mut span, call));
+    }
+    expansions.reverse();
+    (span, expansions)
+}
+
+/// Describe the macro expansion that produced a span of generated code
+pub(crate) fn macro_expansion_message(inputs: &Inputs, generated: &CodeSpan) -> String {
+    let InputSrc::Macro(call) = &generated.src else {
+        return String::new();
+    };
+    match inputs.macro_defs.get(&**call) {
+        Some(entry) => {
+            let (name, def) = entry.value();
+            format!("in code generated by {name} (defined at {def})")
+        }
+        None => "in code generated by a macro".into(),
+    }
+}
+
+fn push_snippet(
+    fragments: &mut Vec<ReportFragment>,
+    inputs: &Inputs,
+    span: &CodeSpan,
+    kind: ReportKind,
+) {
+    fragments.push(ReportFragment::Newline);
+    let line_prefix = format!("{} | ", span.start.line);
+    fragments.push(ReportFragment::Plain(line_prefix.clone()));
+    let input = inputs.get(&span.src);
+    let line = input
+        .lines()
+        .nth(span.start.line as usize - 1)
+        .unwrap_or("");
+    let start_char_pos = span.start.col - 1;
+    let end_char_pos = if span.start.line == span.end.line {
+        span.end.col - 1
+    } else {
+        line.chars().count() as u16
+    };
+    let pre_color: String = line.chars().take(start_char_pos as usize).collect();
+    let color: String = line
+        .chars()
+        .skip(start_char_pos as usize)
+        .take(end_char_pos.saturating_sub(start_char_pos).max(1) as usize)
+        .collect();
+    let post_color: String = line.chars().skip(end_char_pos as usize).collect();
+    fragments.push(ReportFragment::Faint(pre_color));
+    fragments.push(ReportFragment::Colored(color, kind));
+    fragments.push(ReportFragment::Faint(post_color));
+    fragments.push(ReportFragment::Newline);
+    fragments.push(ReportFragment::Plain(
+        " ".repeat(line_prefix.chars().count()),
+    ));
+    fragments.push(ReportFragment::Plain(" ".repeat(start_char_pos as usize)));
+    fragments.push(ReportFragment::Colored(
+        "─".repeat(end_char_pos.saturating_sub(start_char_pos).max(1) as usize),
+        kind,
+    ));
+}
+
 impl fmt::Display for Report {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for frag in &self.fragments {
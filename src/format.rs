@@ -573,6 +573,25 @@ impl<'a> Formatter<'a> {
             self.output = new_output;
         }
     }
+    fn format_binding_words(&mut self, words: &[Sp<Word>], span: CodeSpan) {
+        let mut lines = unsplit_words(split_words(words.to_vec()));
+        if lines.len() == 1 {
+            self.format_words(&lines[0], true, 0, true);
+        } else {
+            lines.push(Vec::new());
+            self.format_words(
+                &[span.clone().sp(Word::Func(Func {
+                    id: FunctionId::Anonymous(span),
+                    signature: None,
+                    lines,
+                    closed: true,
+                }))],
+                true,
+                0,
+                true,
+            );
+        }
+    }
     fn format_item(&mut self, item: &Item) {
         match item {
             Item::TestScope(items) => {
@@ -622,23 +641,29 @@ impl<'a> Formatter<'a> {
                     .map(|(first, last)| first.span.clone().merge(last.span.clone()))
                     .or_else(|| binding.signature.as_ref().map(|sig| sig.span.clone()))
                     .unwrap_or_else(|| binding.arrow_span.clone());
-                let mut lines = unsplit_words(split_words(binding.words.clone()));
-                if lines.len() == 1 {
-                    self.format_words(&lines[0], true, 0, true);
-                } else {
-                    lines.push(Vec::new());
-                    self.format_words(
-                        &[span.clone().sp(Word::Func(Func {
-                            id: FunctionId::Anonymous(span),
-                            signature: None,
-                            lines,
-                            closed: true,
-                        }))],
-                        true,
-                        0,
-                        true,
-                    );
+                self.format_binding_words(&binding.words, span);
+            }
+            Item::Destructure(destructure) => {
+                self.prev_import_function = None;
+                self.output.push(if destructure.boxes { '{' } else { '[' });
+                for (i, name) in destructure.names.iter().enumerate() {
+                    if i > 0 {
+                        self.output.push(' ');
+                    }
+                    self.output
+                        .push_str(&crate::parse::canonicalize_exclams(&name.value));
                 }
+                self.output.push(if destructure.boxes { '}' } else { ']' });
+                self.output
+                    .push_str(if destructure.public { " ←" } else { " ↚" });
+                if !destructure.words.is_empty() {
+                    self.output.push(' ');
+                }
+                let span = (destructure.words.first())
+                    .zip(destructure.words.last())
+                    .map(|(first, last)| first.span.clone().merge(last.span.clone()))
+                    .unwrap_or_else(|| destructure.arrow_span.clone());
+                self.format_binding_words(&destructure.words, span);
             }
             Item::Import(import) => {
                 self.prev_import_function = None;
@@ -698,6 +723,36 @@ impl<'a> Formatter<'a> {
                     }
                 }
             }
+            Item::Data(data) => {
+                self.prev_import_function = None;
+                self.push(&data.tilde_span, "~");
+                self.push(&data.name.span, &data.name.value);
+                // Variants stay on one line only if they started on one line
+                let one_line = (data.variants.iter())
+                    .all(|v| v.bar_span.start.line == data.name.span.start.line);
+                for variant in &data.variants {
+                    if one_line {
+                        self.output.push(' ');
+                    } else {
+                        self.output.push('\n');
+                        for _ in 0..self.config.multiline_indent {
+                            self.output.push(' ');
+                        }
+                    }
+                    self.push(&variant.bar_span, "|");
+                    self.push(&variant.name.span, &variant.name.value);
+                    if variant.fields_span.is_some() {
+                        self.output.push_str(" {");
+                        for (i, field) in variant.fields.iter().enumerate() {
+                            if i > 0 {
+                                self.output.push(' ');
+                            }
+                            self.push(&field.span, &field.value);
+                        }
+                        self.output.push('}');
+                    }
+                }
+            }
         }
     }
     fn format_signature(&mut self, init_char: char, sig: Signature, trailing_space: bool) {
@@ -1218,7 +1273,7 @@ impl<'a> Formatter<'a> {
             #[cfg(feature = "native_sys")]
             crate::sys_native::set_output_enabled(enabled);
 
-            let mut values = env.rt.output_comments;
+            let mut values = std::mem::take(&mut env.rt.output_comments);
             if let Err(e) = res {
                 let next = (0..).take_while(|i| values.contains_key(i)).count();
                 values.insert(next, vec![vec![e.to_string().into()]]);
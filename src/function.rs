@@ -247,6 +247,38 @@ impl Instr {
     pub(crate) fn is_code(&self) -> bool {
         !matches!(self, Self::NoInline)
     }
+    /// Get the index of the instruction's span, if it has one
+    pub(crate) fn span(&self) -> Option<usize> {
+        match self {
+            Self::BindGlobal { span, .. }
+            | Self::EndArray { span, .. }
+            | Self::Prim(_, span)
+            | Self::ImplPrim(_, span)
+            | Self::Call(span)
+            | Self::CallRecursive(span)
+            | Self::Recur(span)
+            | Self::Switch { span, .. }
+            | Self::Format { span, .. }
+            | Self::MatchFormatPattern { span, .. }
+            | Self::StackSwizzle(_, span)
+            | Self::Label { span, .. }
+            | Self::Unpack { span, .. }
+            | Self::TouchStack { span, .. }
+            | Self::PushTemp { span, .. }
+            | Self::PopTemp { span, .. }
+            | Self::CopyToTemp { span, .. } => Some(*span),
+            Self::Comment(_)
+            | Self::Push(_)
+            | Self::CallGlobal { .. }
+            | Self::BeginArray
+            | Self::PushFunc(_)
+            | Self::Dynamic(_)
+            | Self::SetOutputComment { .. }
+            | Self::PushSig(_)
+            | Self::PopSig
+            | Self::NoInline => None,
+        }
+    }
 }
 
 pub(crate) struct FmtInstrs<'a>(pub &'a [Instr], pub &'a Assembly);
@@ -382,6 +414,15 @@ pub enum Purity {
 
 /// Whether some instructions are pure
 pub(crate) fn instrs_are_pure(instrs: &[Instr], asm: &Assembly, min_purity: Purity) -> bool {
+    instrs_purity_impl(instrs, asm, min_purity, false)
+}
+
+/// Whether some instructions are pure, other than using the random number generator
+pub(crate) fn instrs_are_pure_or_random(instrs: &[Instr], asm: &Assembly) -> bool {
+    instrs_purity_impl(instrs, asm, Purity::Pure, true)
+}
+
+fn instrs_purity_impl(instrs: &[Instr], asm: &Assembly, min_purity: Purity, random: bool) -> bool {
     'instrs: for (i, instr) in instrs.iter().enumerate() {
         match instr {
             Instr::CallGlobal { index, .. } => {
@@ -389,7 +430,7 @@ pub(crate) fn instrs_are_pure(instrs: &[Instr], asm: &Assembly, min_purity: Puri
                     match &binding.kind {
                         BindingKind::Const(Some(_)) => {}
                         BindingKind::Func(f) => {
-                            if !instrs_are_pure(f.instrs(asm), asm, min_purity) {
+                            if !instrs_purity_impl(f.instrs(asm), asm, min_purity, random) {
                                 return false;
                             }
                         }
@@ -402,7 +443,7 @@ pub(crate) fn instrs_are_pure(instrs: &[Instr], asm: &Assembly, min_purity: Puri
                 for j in (0..i).rev() {
                     let frag = &prev[j..i];
                     if instrs_signature(frag).is_ok_and(|sig| sig == (0, 1))
-                        && instrs_are_pure(frag, asm, min_purity)
+                        && instrs_purity_impl(frag, asm, min_purity, random)
                     {
                         continue 'instrs;
                     }
@@ -410,17 +451,17 @@ pub(crate) fn instrs_are_pure(instrs: &[Instr], asm: &Assembly, min_purity: Puri
                 return false;
             }
             Instr::Prim(prim, _) => {
-                if prim.purity() < min_purity {
+                if prim.purity() < min_purity && !(random && prim.is_random()) {
                     return false;
                 }
             }
             Instr::ImplPrim(prim, _) => {
-                if prim.purity() < min_purity {
+                if prim.purity() < min_purity && !(random && prim.is_random()) {
                     return false;
                 }
             }
             Instr::PushFunc(f) => {
-                if !instrs_are_pure(f.instrs(asm), asm, min_purity) {
+                if !instrs_purity_impl(f.instrs(asm), asm, min_purity, random) {
                     return false;
                 }
             }
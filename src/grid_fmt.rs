@@ -53,6 +53,23 @@ impl GridFmt for u8 {
     }
 }
 
+impl GridFmt for i64 {
+    fn fmt_grid(&self, params: GridFmtParams) -> Grid {
+        let boxed = boxed_scalar(params.boxed);
+        let minus = if *self < 0 { "¯" } else { "" };
+        let s = format!("{minus}{}", self.unsigned_abs());
+        vec![boxed.chain(s.chars()).collect()]
+    }
+}
+
+impl GridFmt for f32 {
+    fn fmt_grid(&self, params: GridFmtParams) -> Grid {
+        // Go through the shortest decimal form so that, for example, `0.1` is not shown as `0.10000000149011612`
+        let f: f64 = self.to_string().parse().unwrap_or(*self as f64);
+        f.fmt_grid(params)
+    }
+}
+
 impl GridFmt for f64 {
     fn fmt_grid(&self, params: GridFmtParams) -> Grid {
         let f = *self;
@@ -205,6 +222,8 @@ impl GridFmt for Value {
         match self {
             Value::Num(n) => n.fmt_grid(params),
             Value::Byte(b) => b.fmt_grid(params),
+            Value::Int(i) => i.fmt_grid(params),
+            Value::Float(f) => f.fmt_grid(params),
             Value::Complex(c) => c.fmt_grid(params),
             Value::Box(v) => v.fmt_grid(params),
             Value::Char(c) => c.fmt_grid(params),
@@ -252,6 +271,8 @@ impl GridFmt for Boxed {
         let mut grid = match self.as_value() {
             Value::Num(array) => array.fmt_grid(subparams),
             Value::Byte(array) => array.fmt_grid(subparams),
+            Value::Int(array) => array.fmt_grid(subparams),
+            Value::Float(array) => array.fmt_grid(subparams),
             Value::Complex(array) => array.fmt_grid(subparams),
             Value::Char(array) => array.fmt_grid(subparams),
             Value::Box(array) => array.fmt_grid(subparams),
@@ -302,6 +323,8 @@ impl<T: GridFmt + ArrayValue> GridFmt for Array<T> {
                     let mut row = match &keys.keys {
                         Value::Num(_) => shape_row::<f64>(&keys_row_shape),
                         Value::Byte(_) => shape_row::<u8>(&keys_row_shape),
+                        Value::Int(_) => shape_row::<i64>(&keys_row_shape),
+                        Value::Float(_) => shape_row::<f32>(&keys_row_shape),
                         Value::Complex(_) => shape_row::<Complex>(&keys_row_shape),
                         Value::Char(_) => shape_row::<char>(&keys_row_shape),
                         Value::Box(_) => shape_row::<Boxed>(&keys_row_shape),
@@ -413,6 +436,20 @@ impl<T: GridFmt + ArrayValue> GridFmt for Array<T> {
             }
         }
 
+        // Add unit
+        if let Some(unit) = &self.meta().unit {
+            let unit: Vec<char> = unit.to_string().chars().collect();
+            let multiline = grid.len() > 1;
+            let last = grid.last_mut().unwrap();
+            let end = last.len() - 1;
+            if multiline && end > unit.len() + 1 {
+                last.splice(end - unit.len() - 1..end, [' '].into_iter().chain(unit));
+            } else {
+                last.push(' ');
+                last.extend(unit);
+            }
+        }
+
         // Add pointer
         if let Some(pointer) = self.meta().pointer.filter(|p| !p.raw) {
             if grid.len() == 1 {
@@ -450,6 +487,8 @@ impl<T: ArrayValue> Array<T> {
             let mut s: String = match keys.keys {
                 Value::Num(_) => shape_row::<f64>(&keys_shape),
                 Value::Byte(_) => shape_row::<u8>(&keys_shape),
+                Value::Int(_) => shape_row::<i64>(&keys_shape),
+                Value::Float(_) => shape_row::<f32>(&keys_shape),
                 Value::Complex(_) => shape_row::<Complex>(&keys_shape),
                 Value::Char(_) => shape_row::<char>(&keys_shape),
                 Value::Box(_) => shape_row::<Boxed>(&keys_shape),
@@ -474,6 +513,8 @@ impl Value {
             Array::shape_string,
             Array::shape_string,
             Array::shape_string,
+            Array::shape_string,
+            Array::shape_string,
         )
     }
 }
@@ -0,0 +1,261 @@
+//! An opt-in on-disk cache of HTTP responses
+//!
+//! Responses to GET requests are kept according to their `Cache-Control`, `Age`, `Vary`, `ETag`, and `Last-Modified` headers.
+//! A fresh response is returned without making a request.
+//! A stale response is revalidated with a conditional request.
+//! If the server cannot be reached, the stale response is returned with a `Warning: 111` header marking it as stale.
+
+use std::{
+    env, fs,
+    hash::Hasher,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::primitive::StableHasher;
+
+/// Request headers that may be part of a cached request
+///
+/// Requests with any other headers may carry credentials or change the response, so they are not cached.
+const CACHEABLE_HEADERS: &[&str] = &[
+    "host",
+    "user-agent",
+    "accept",
+    "accept-language",
+    "accept-encoding",
+    "connection",
+];
+
+/// A cached response
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// The origin and request the response is for
+    key: String,
+    /// When the response was stored or last revalidated, in seconds since the Unix epoch
+    stored: u64,
+    /// How many seconds the response is fresh for after it is stored
+    max_age: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    response: String,
+}
+
+/// The parsed status line and headers of an HTTP response
+pub(crate) struct ResponseHead {
+    pub status: u16,
+    /// The byte index where the body starts
+    pub body_start: usize,
+    pub location: Option<String>,
+    max_age: u64,
+    /// Whether the response must not be stored
+    ///
+    /// This is true for `no-store` and `private` responses, responses that set cookies,
+    /// and responses that vary on request headers that are not part of the cache key.
+    no_store: bool,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Whether the response is a stale one returned from the cache
+    pub stale: bool,
+}
+
+impl ResponseHead {
+    pub fn parse(response: &str) -> Option<Self> {
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut res = httparse::Response::new(&mut headers);
+        let httparse::Status::Complete(body_start) = res.parse(response.as_bytes()).ok()? else {
+            return None;
+        };
+        let mut head = ResponseHead {
+            status: res.code?,
+            body_start,
+            location: None,
+            max_age: 0,
+            no_store: false,
+            etag: None,
+            last_modified: None,
+            stale: false,
+        };
+        let mut no_cache = false;
+        let mut age = 0;
+        for header in res.headers.iter() {
+            let value = String::from_utf8_lossy(header.value).trim().to_string();
+            match header.name.to_ascii_lowercase().as_str() {
+                "cache-control" => {
+                    for directive in value.to_ascii_lowercase().split(',') {
+                        match directive.trim().split_once('=') {
+                            Some(("max-age", secs)) => {
+                                head.max_age = secs.trim_matches('"').parse().unwrap_or(0)
+                            }
+                            Some(("private", _)) => head.no_store = true,
+                            None if ["no-store", "private"].contains(&directive.trim()) => {
+                                head.no_store = true
+                            }
+                            None if directive.trim() == "no-cache" => no_cache = true,
+                            _ => {}
+                        }
+                    }
+                }
+                "age" => age = value.parse().unwrap_or(0),
+                "set-cookie" => head.no_store = true,
+                "vary" => {
+                    let varies_on_key = |name: &str| {
+                        let name = name.trim().to_ascii_lowercase();
+                        CACHEABLE_HEADERS.contains(&name.as_str())
+                    };
+                    if !value.split(',').all(varies_on_key) {
+                        head.no_store = true;
+                    }
+                }
+                "warning" => head.stale |= value.starts_with("110") || value.starts_with("111"),
+                "etag" => head.etag = Some(value),
+                "last-modified" => head.last_modified = Some(value),
+                "location" => head.location = Some(value),
+                _ => {}
+            }
+        }
+        // A response that is already old when it arrives is fresh for less time
+        head.max_age = if no_cache {
+            0
+        } else {
+            head.max_age.saturating_sub(age)
+        };
+        Some(head)
+    }
+}
+
+/// Get the directory that cached HTTP responses are stored in
+///
+/// This is an `http` folder in `UIUA_CACHE_DIR` if it is set, or in a `uiua` folder in the platform's cache directory.
+/// Returns `None` unless caching is enabled by setting `UIUA_HTTP_CACHE` to `1`.
+pub fn http_cache_dir() -> Option<PathBuf> {
+    if !env::var("UIUA_HTTP_CACHE").is_ok_and(|val| val == "1") {
+        return None;
+    }
+    let dir = if let Some(dir) = env::var_os("UIUA_CACHE_DIR") {
+        PathBuf::from(dir)
+    } else if cfg!(windows) {
+        PathBuf::from(env::var_os("LOCALAPPDATA")?).join("uiua")
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(env::var_os("HOME")?).join("Library/Caches/uiua")
+    } else if let Some(dir) = env::var_os("XDG_CACHE_HOME") {
+        PathBuf::from(dir).join("uiua")
+    } else {
+        PathBuf::from(env::var_os("HOME")?).join(".cache/uiua")
+    };
+    Some(dir.join("http"))
+}
+
+/// Send an HTTP request through the cache in `dir`, if there is one
+///
+/// `origin` is the scheme, host, and port the request is sent to.
+/// `send` sends a request and returns the raw response.
+/// Only GET requests without a query string and with only [`CACHEABLE_HEADERS`] are cached. Others are always sent.
+pub(crate) fn cached_request(
+    dir: Option<&Path>,
+    origin: &str,
+    request: &str,
+    send: impl FnOnce(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let (Some("GET"), Some(target)) = (request_line.next(), request_line.next()) else {
+        return send(request);
+    };
+    // Query strings often carry API keys, which should not be written to disk
+    let cacheable = !target.contains('?')
+        && lines.filter(|line| !line.trim().is_empty()).all(|line| {
+            let name = line.split(':').next().unwrap_or("").trim();
+            CACHEABLE_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+        });
+    let Some(dir) = dir.filter(|_| cacheable) else {
+        return send(request);
+    };
+    let key = format!("{origin}\n{request}");
+    // The hash must be stable across runs and Rust versions to find cached files
+    let mut hasher = StableHasher::default();
+    hasher.write(key.as_bytes());
+    let path = dir.join(format!("{:016x}.json", hasher.finish()));
+    let entry = (fs::read(&path).ok())
+        .and_then(|bytes| serde_json::from_slice::<Entry>(&bytes).ok())
+        .filter(|entry| entry.key == key);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if let Some(entry) = &entry {
+        if now < entry.stored.saturating_add(entry.max_age) {
+            return Ok(entry.response.clone());
+        }
+    }
+    let response = match &entry {
+        Some(entry) => send(&with_validators(request, entry)),
+        None => send(request),
+    };
+    let response = match response {
+        Ok(response) => response,
+        // The server cannot be reached, so a stale response is better than nothing,
+        // but it is marked so that callers can tell
+        Err(e) => return entry.map(|entry| mark_stale(&entry.response)).ok_or(e),
+    };
+    let Some(head) = ResponseHead::parse(&response) else {
+        return Ok(response);
+    };
+    let mut entry = match (head.status, entry) {
+        (304, Some(entry)) => entry,
+        (200, _) => Entry {
+            key,
+            stored: now,
+            max_age: 0,
+            etag: None,
+            last_modified: None,
+            response,
+        },
+        (_, _) => return Ok(response),
+    };
+    if head.no_store {
+        _ = fs::remove_file(&path);
+        return Ok(entry.response);
+    }
+    entry.stored = now;
+    entry.max_age = head.max_age;
+    if head.etag.is_some() {
+        entry.etag = head.etag;
+    }
+    if head.last_modified.is_some() {
+        entry.last_modified = head.last_modified;
+    }
+    // The cache is only an optimization, so failing to write it is not an error
+    // Entries are written to a temporary file first so that other processes never see a partial entry
+    if let Ok(json) = serde_json::to_vec(&entry) {
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        _ = fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&temp, json))
+            .and_then(|_| fs::rename(&temp, &path));
+    }
+    Ok(entry.response)
+}
+
+/// Add a `Warning` header to a response to mark it as stale
+fn mark_stale(response: &str) -> String {
+    let warning = "Warning: 111 - \"Revalidation Failed\"\r\n";
+    match response.find("\r\n") {
+        Some(i) => format!("{}{warning}{}", &response[..i + 2], &response[i + 2..]),
+        None => response.into(),
+    }
+}
+
+/// Add conditional headers to a request so the server can respond with `304 Not Modified`
+fn with_validators(request: &str, entry: &Entry) -> String {
+    let mut headers = String::new();
+    if let Some(etag) = &entry.etag {
+        headers.push_str(&format!("If-None-Match: {etag}\r\n"));
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        headers.push_str(&format!("If-Modified-Since: {last_modified}\r\n"));
+    }
+    match request.find("\r\n") {
+        Some(i) => format!("{}{headers}{}", &request[..i + 2], &request[i + 2..]),
+        None => request.into(),
+    }
+}
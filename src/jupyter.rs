@@ -0,0 +1,777 @@
+//! A Jupyter kernel for Uiua
+//!
+//! The kernel speaks the [Jupyter messaging protocol](https://jupyter-client.readthedocs.io/en/latest/messaging.html)
+//! over ZeroMQ. Run `uiua kernel --install` to register it with Jupyter.
+//!
+//! Values left on the stack are shown with rich output where possible.
+//! Image arrays are shown as inline PNGs, audio arrays as playable widgets, and matrices as tables.
+
+use std::{
+    env, fmt::Write, fs, path::Path, path::PathBuf, sync::Arc, thread, time::Duration,
+    time::SystemTime,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Map, Value as Json};
+use sha2::Sha256;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use zeromq::{PubSocket, RepSocket, RouterSocket, Socket, SocketRecv, SocketSend, ZmqMessage};
+
+use crate::{
+    format::{format_str, FormatConfig},
+    Compiler, FfiType, Handle, NativeSys, Primitive, RichOutput, RichOutputKind, SysBackend, Uiua,
+    Value,
+};
+
+const PROTOCOL_VERSION: &str = "5.3";
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+
+/// The contents of the connection file Jupyter passes to the kernel
+#[derive(Deserialize)]
+struct ConnectionInfo {
+    transport: String,
+    ip: String,
+    shell_port: u16,
+    iopub_port: u16,
+    control_port: u16,
+    hb_port: u16,
+    key: String,
+    signature_scheme: String,
+}
+
+impl ConnectionInfo {
+    fn endpoint(&self, port: u16) -> String {
+        format!("{}://{}:{}", self.transport, self.ip, port)
+    }
+}
+
+/// Install the kernel spec into the user's Jupyter data directory
+///
+/// Returns the directory the spec was written to.
+pub fn install_kernel_spec() -> Result<PathBuf, String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let dir = jupyter_data_dir()
+        .ok_or("Unable to find the Jupyter data directory")?
+        .join("kernels")
+        .join("uiua");
+    let spec = json!({
+        "argv": [exe, "kernel", "{connection_file}"],
+        "display_name": "Uiua",
+        "language": "uiua",
+    });
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let spec = serde_json::to_string_pretty(&spec).map_err(|e| e.to_string())?;
+    fs::write(dir.join("kernel.json"), spec).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn jupyter_data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("JUPYTER_DATA_DIR") {
+        return Some(dir.into());
+    }
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("jupyter"));
+    }
+    let home = PathBuf::from(env::var_os("HOME")?);
+    if cfg!(target_os = "macos") {
+        return Some(home.join("Library").join("Jupyter"));
+    }
+    Some(match env::var_os("XDG_DATA_HOME") {
+        Some(dir) => PathBuf::from(dir).join("jupyter"),
+        None => home.join(".local").join("share").join("jupyter"),
+    })
+}
+
+/// Run the kernel with the given connection file
+///
+/// This function only returns if the kernel fails to start.
+pub fn run_kernel(connection_file: &Path) -> Result<(), String> {
+    let info = fs::read_to_string(connection_file).map_err(|e| e.to_string())?;
+    let info: ConnectionInfo = serde_json::from_str(&info).map_err(|e| e.to_string())?;
+    if !info.key.is_empty() && info.signature_scheme != "hmac-sha256" {
+        return Err(format!(
+            "Unsupported signature scheme {}",
+            info.signature_scheme
+        ));
+    }
+    let session = Arc::new(Session {
+        key: info.key.clone().into_bytes(),
+        id: random_id(),
+    });
+
+    // Heartbeat
+    let hb_endpoint = info.endpoint(info.hb_port);
+    spawn_with_runtime(async move {
+        let mut socket = RepSocket::new();
+        socket.bind(&hb_endpoint).await.map_err(|e| e.to_string())?;
+        loop {
+            let msg = socket.recv().await.map_err(|e| e.to_string())?;
+            socket.send(msg).await.map_err(|e| e.to_string())?;
+        }
+    });
+
+    // Control
+    let control_endpoint = info.endpoint(info.control_port);
+    let control_session = session.clone();
+    spawn_with_runtime(async move {
+        let session = control_session;
+        let mut socket = RouterSocket::new();
+        (socket.bind(&control_endpoint).await).map_err(|e| e.to_string())?;
+        loop {
+            let msg = socket.recv().await.map_err(|e| e.to_string())?;
+            let Some(msg) = session.parse(msg) else {
+                continue;
+            };
+            let reply = match msg.msg_type() {
+                "kernel_info_request" => session.reply(&msg, "kernel_info_reply", kernel_info()),
+                "shutdown_request" => {
+                    let content = json!({"status": "ok", "restart": msg.content["restart"]});
+                    let reply = session.reply(&msg, "shutdown_reply", content);
+                    _ = socket.send(reply).await;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    std::process::exit(0);
+                }
+                "interrupt_request" => {
+                    let content = json!({
+                        "status": "error",
+                        "ename": "Unsupported",
+                        "evalue": "The Uiua kernel cannot interrupt execution",
+                        "traceback": [],
+                    });
+                    session.reply(&msg, "interrupt_reply", content)
+                }
+                _ => continue,
+            };
+            socket.send(reply).await.map_err(|e| e.to_string())?;
+        }
+    });
+
+    // Shell and IOPub
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?
+        .block_on(async move {
+            let mut shell = RouterSocket::new();
+            (shell.bind(&info.endpoint(info.shell_port)).await).map_err(|e| e.to_string())?;
+            let mut iopub = PubSocket::new();
+            (iopub.bind(&info.endpoint(info.iopub_port)).await).map_err(|e| e.to_string())?;
+            let mut kernel = Kernel {
+                session,
+                iopub,
+                executor: Executor::spawn(),
+                execution_count: 0,
+            };
+            loop {
+                let msg = shell.recv().await.map_err(|e| e.to_string())?;
+                let Some(msg) = kernel.session.parse(msg) else {
+                    continue;
+                };
+                kernel
+                    .publish(&msg, "status", json!({"execution_state": "busy"}))
+                    .await;
+                if let Some(reply) = kernel.handle_shell(&msg).await {
+                    shell.send(reply).await.map_err(|e| e.to_string())?;
+                }
+                kernel
+                    .publish(&msg, "status", json!({"execution_state": "idle"}))
+                    .await;
+                if msg.msg_type() == "shutdown_request" {
+                    std::process::exit(0);
+                }
+            }
+        })
+}
+
+fn spawn_with_runtime(f: impl std::future::Future<Output = Result<(), String>> + Send + 'static) {
+    thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to start the kernel's async runtime");
+        if let Err(e) = rt.block_on(f) {
+            eprintln!("Uiua kernel error: {e}");
+            std::process::exit(1);
+        }
+    });
+}
+
+fn kernel_info() -> Json {
+    json!({
+        "status": "ok",
+        "protocol_version": PROTOCOL_VERSION,
+        "implementation": "uiua",
+        "implementation_version": crate::VERSION,
+        "language_info": {
+            "name": "uiua",
+            "version": crate::VERSION,
+            "mimetype": "text/x-uiua",
+            "file_extension": ".ua",
+        },
+        "banner": format!("Uiua {}", crate::VERSION),
+        "help_links": [{"text": "Uiua Documentation", "url": "https://uiua.org/docs"}],
+    })
+}
+
+/// A message received from a Jupyter client
+struct Message {
+    identities: Vec<Vec<u8>>,
+    header: Json,
+    content: Json,
+}
+
+impl Message {
+    fn msg_type(&self) -> &str {
+        self.header["msg_type"].as_str().unwrap_or("")
+    }
+}
+
+/// Signing and identity for messages sent by the kernel
+struct Session {
+    key: Vec<u8>,
+    id: String,
+}
+
+impl Session {
+    fn mac(&self, frames: &[&[u8]]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key");
+        for frame in frames {
+            mac.update(frame);
+        }
+        mac
+    }
+    fn sign(&self, frames: &[&[u8]]) -> String {
+        if self.key.is_empty() {
+            return String::new();
+        }
+        let mut sig = String::new();
+        for byte in self.mac(frames).finalize().into_bytes() {
+            write!(sig, "{byte:02x}").unwrap();
+        }
+        sig
+    }
+    /// Check a hex signature in constant time
+    fn verify(&self, frames: &[&[u8]], sig: &[u8]) -> bool {
+        if self.key.is_empty() {
+            return true;
+        }
+        let Some(sig) = decode_hex(sig) else {
+            return false;
+        };
+        self.mac(frames).verify_slice(&sig).is_ok()
+    }
+    /// Parse and verify a message, ignoring invalid ones
+    fn parse(&self, msg: ZmqMessage) -> Option<Message> {
+        let frames: Vec<Vec<u8>> = msg.into_vec().into_iter().map(|f| f.to_vec()).collect();
+        let delim = frames.iter().position(|f| f == DELIMITER)?;
+        let [sig, header, parent, metadata, content] = frames.get(delim + 1..delim + 6)? else {
+            return None;
+        };
+        if !self.verify(&[header, parent, metadata, content], sig) {
+            eprintln!("Uiua kernel received a message with an invalid signature");
+            return None;
+        }
+        Some(Message {
+            identities: frames[..delim].to_vec(),
+            header: serde_json::from_slice(header).ok()?,
+            content: serde_json::from_slice(content).ok()?,
+        })
+    }
+    /// Build a message in response to `parent`
+    fn message(
+        &self,
+        identities: Vec<Vec<u8>>,
+        parent: &Message,
+        msg_type: &str,
+        content: Json,
+    ) -> ZmqMessage {
+        let header = json!({
+            "msg_id": random_id(),
+            "session": self.id,
+            "username": "kernel",
+            "date": iso_timestamp(),
+            "msg_type": msg_type,
+            "version": PROTOCOL_VERSION,
+        });
+        let header = header.to_string().into_bytes();
+        let parent = parent.header.to_string().into_bytes();
+        let metadata = b"{}".to_vec();
+        let content = content.to_string().into_bytes();
+        let sig = self.sign(&[&header, &parent, &metadata, &content]);
+        let mut frames = identities;
+        frames.push(DELIMITER.to_vec());
+        frames.extend([sig.into_bytes(), header, parent, metadata, content]);
+        let mut frames = frames.into_iter();
+        let mut msg = ZmqMessage::from(frames.next().unwrap());
+        for frame in frames {
+            msg.push_back(frame.into());
+        }
+        msg
+    }
+    fn reply(&self, parent: &Message, msg_type: &str, content: Json) -> ZmqMessage {
+        self.message(parent.identities.clone(), parent, msg_type, content)
+    }
+}
+
+/// The state of the shell channel
+struct Kernel {
+    session: Arc<Session>,
+    iopub: PubSocket,
+    executor: Executor,
+    execution_count: usize,
+}
+
+impl Kernel {
+    async fn publish(&mut self, parent: &Message, msg_type: &str, content: Json) {
+        let topic = format!("kernel.{}.{msg_type}", self.session.id).into_bytes();
+        let msg = (self.session).message(vec![topic], parent, msg_type, content);
+        if let Err(e) = self.iopub.send(msg).await {
+            eprintln!("Uiua kernel failed to publish: {e}");
+        }
+    }
+    async fn handle_shell(&mut self, msg: &Message) -> Option<ZmqMessage> {
+        let (msg_type, content) = match msg.msg_type() {
+            "kernel_info_request" => ("kernel_info_reply", kernel_info()),
+            "execute_request" => ("execute_reply", self.execute(msg).await),
+            "is_complete_request" => ("is_complete_reply", json!({"status": "complete"})),
+            "complete_request" => ("complete_reply", complete(&msg.content)),
+            "inspect_request" => ("inspect_reply", inspect(&msg.content)),
+            "history_request" => ("history_reply", json!({"status": "ok", "history": []})),
+            "comm_info_request" => ("comm_info_reply", json!({"status": "ok", "comms": {}})),
+            "shutdown_request" => {
+                let content = json!({"status": "ok", "restart": msg.content["restart"]});
+                let reply = self.session.reply(msg, "shutdown_reply", content);
+                return Some(reply);
+            }
+            _ => return None,
+        };
+        Some(self.session.reply(msg, msg_type, content))
+    }
+    async fn execute(&mut self, msg: &Message) -> Json {
+        let code = msg.content["code"].as_str().unwrap_or("").to_string();
+        let silent = msg.content["silent"].as_bool().unwrap_or(false);
+        if !silent {
+            self.execution_count += 1;
+            let content = json!({"code": code, "execution_count": self.execution_count});
+            self.publish(msg, "execute_input", content).await;
+        }
+        let mut events = self.executor.execute(code);
+        let mut payload = Vec::new();
+        while let Some(event) = events.recv().await {
+            match event {
+                Event::Stdout(text) => {
+                    let content = json!({"name": "stdout", "text": text});
+                    self.publish(msg, "stream", content).await;
+                }
+                Event::Stderr(text) => {
+                    let content = json!({"name": "stderr", "text": text});
+                    self.publish(msg, "stream", content).await;
+                }
+                Event::Display(data) => {
+                    let content = json!({"data": data, "metadata": {}, "transient": {}});
+                    self.publish(msg, "display_data", content).await;
+                }
+                Event::Formatted(text) => payload.push(json!({
+                    "source": "set_next_input",
+                    "text": text,
+                    "replace": true,
+                })),
+                Event::Done(Ok(stack)) => {
+                    let count = stack.len();
+                    for (i, data) in stack.into_iter().enumerate() {
+                        if i + 1 == count && !silent {
+                            let content = json!({
+                                "execution_count": self.execution_count,
+                                "data": data,
+                                "metadata": {},
+                            });
+                            self.publish(msg, "execute_result", content).await;
+                        } else {
+                            let content = json!({"data": data, "metadata": {}, "transient": {}});
+                            self.publish(msg, "display_data", content).await;
+                        }
+                    }
+                    return json!({
+                        "status": "ok",
+                        "execution_count": self.execution_count,
+                        "payload": payload,
+                        "user_expressions": {},
+                    });
+                }
+                Event::Done(Err(report)) => {
+                    let evalue = report.lines().next().unwrap_or("").to_string();
+                    let traceback: Vec<&str> = report.lines().collect();
+                    let error = json!({
+                        "ename": "Error",
+                        "evalue": evalue,
+                        "traceback": traceback,
+                    });
+                    self.publish(msg, "error", error.clone()).await;
+                    let mut reply = error;
+                    reply["status"] = "error".into();
+                    reply["execution_count"] = self.execution_count.into();
+                    return reply;
+                }
+            }
+        }
+        json!({
+            "status": "error",
+            "ename": "Error",
+            "evalue": "The Uiua executor stopped",
+            "traceback": [],
+            "execution_count": self.execution_count,
+        })
+    }
+}
+
+/// The word of ASCII letters ending at a cursor position
+fn word_at(content: &Json) -> (String, usize, usize) {
+    let code = content["code"].as_str().unwrap_or("");
+    let chars: Vec<char> = code.chars().collect();
+    let cursor = (content["cursor_pos"].as_u64().unwrap_or(0) as usize).min(chars.len());
+    let mut start = cursor;
+    while start > 0 && chars[start - 1].is_ascii_alphabetic() {
+        start -= 1;
+    }
+    let mut end = cursor;
+    while end < chars.len() && chars[end].is_ascii_alphabetic() {
+        end += 1;
+    }
+    (chars[start..end].iter().collect(), start, end)
+}
+
+fn complete(content: &Json) -> Json {
+    let (word, start, _) = word_at(content);
+    let cursor = start + word.chars().count();
+    let prefix = word.to_lowercase();
+    let matches: Vec<&str> = if prefix.is_empty() {
+        Vec::new()
+    } else {
+        Primitive::non_deprecated()
+            .map(|prim| prim.name())
+            .filter(|name| name.starts_with(&prefix))
+            .collect()
+    };
+    json!({
+        "status": "ok",
+        "matches": matches,
+        "cursor_start": start,
+        "cursor_end": cursor,
+        "metadata": {},
+    })
+}
+
+fn inspect(content: &Json) -> Json {
+    let (word, ..) = word_at(content);
+    let prim = Primitive::from_name(&word).or_else(|| Primitive::from_format_name(&word));
+    let Some(prim) = prim else {
+        return json!({"status": "ok", "found": false, "data": {}, "metadata": {}});
+    };
+    let text = match prim.glyph() {
+        Some(glyph) => format!("{glyph} {}\n{}", prim.name(), prim.doc().short_text()),
+        None => format!("{}\n{}", prim.name(), prim.doc().short_text()),
+    };
+    json!({
+        "status": "ok",
+        "found": true,
+        "data": {"text/plain": text},
+        "metadata": {},
+    })
+}
+
+/// An event produced while executing a cell
+enum Event {
+    Stdout(String),
+    Stderr(String),
+    Display(Map<String, Json>),
+    Formatted(String),
+    Done(Result<Vec<Map<String, Json>>, String>),
+}
+
+/// Runs code on its own thread so the kernel can publish output while code runs
+struct Executor {
+    code_send: crossbeam_channel::Sender<(String, UnboundedSender<Event>)>,
+}
+
+impl Executor {
+    fn spawn() -> Self {
+        let (code_send, code_recv) =
+            crossbeam_channel::unbounded::<(String, UnboundedSender<Event>)>();
+        thread::spawn(move || {
+            let events = Arc::new(parking_lot::Mutex::new(None));
+            let backend = KernelSys {
+                events: events.clone(),
+            };
+            let mut comp = Compiler::with_backend(backend);
+            let mut env = Uiua::with_backend(comp.backend());
+            let config = FormatConfig {
+                trailing_newline: false,
+                ..Default::default()
+            };
+            for (code, send) in code_recv {
+                *events.lock() = Some(send.clone());
+                let res = execute(&mut env, &mut comp, &config, &code, &send);
+                _ = send.send(Event::Done(res));
+                *events.lock() = None;
+            }
+        });
+        Executor { code_send }
+    }
+    fn execute(&self, code: String) -> tokio::sync::mpsc::UnboundedReceiver<Event> {
+        let (send, recv) = unbounded_channel();
+        _ = self.code_send.send((code, send));
+        recv
+    }
+}
+
+fn execute(
+    env: &mut Uiua,
+    comp: &mut Compiler,
+    config: &FormatConfig,
+    code: &str,
+    events: &UnboundedSender<Event>,
+) -> Result<Vec<Map<String, Json>>, String> {
+    let mut code = code.to_string();
+    if let Ok(formatted) = format_str(&code, config) {
+        if formatted.output != code {
+            code = formatted.output;
+            _ = events.send(Event::Formatted(code.clone()));
+        }
+    }
+    let backup = comp.clone();
+    let res = (comp.load_str(&code).map(drop)).and_then(|()| env.run_asm(comp.finish()));
+    let stack = env.take_stack();
+    let mut asm = env.take_asm();
+    match res {
+        Ok(()) => {
+            asm.remove_top_level();
+            *comp.assembly_mut() = asm;
+            let sample_rate = env.backend().audio_sample_rate();
+            Ok(stack
+                .iter()
+                .map(|val| value_mime_bundle(val, sample_rate))
+                .collect())
+        }
+        Err(e) => {
+            *comp = backup;
+            Err(e.report().to_string())
+        }
+    }
+}
+
+/// Get the richest available representations of a value
+fn value_mime_bundle(value: &Value, sample_rate: u32) -> Map<String, Json> {
+    let Some(output) = RichOutput::from_value(value, sample_rate) else {
+        let mut data = Map::new();
+        data.insert("text/plain".into(), value.show().into());
+        return data;
+    };
+    let mut data = rich_mime_bundle(&output);
+    let text = match output.kind {
+        RichOutputKind::Table => value.show(),
+        RichOutputKind::Image => format!("<image {}>", value.shape()),
+        RichOutputKind::Animation => format!("<animation {}>", value.shape()),
+        RichOutputKind::Audio => format!("<audio {}>", value.shape()),
+        RichOutputKind::Plot => format!("<plot {}>", value.shape()),
+    };
+    data.insert("text/plain".into(), text.into());
+    data
+}
+
+/// Convert rich output to the representations Jupyter frontends understand
+fn rich_mime_bundle(output: &RichOutput) -> Map<String, Json> {
+    let mut data = Map::new();
+    match output.kind {
+        RichOutputKind::Audio => {
+            let html = format!(
+                r#"<audio controls src="data:{};base64,{}"></audio>"#,
+                output.mime,
+                BASE64.encode(&output.data)
+            );
+            data.insert("text/html".into(), html.into());
+        }
+        RichOutputKind::Table => {
+            let mut html = String::from("<table>");
+            for row in output.text().unwrap_or_default().lines() {
+                html.push_str("<tr>");
+                for cell in row.split('\t') {
+                    write!(html, "<td>{}</td>", escape_html(cell)).unwrap();
+                }
+                html.push_str("</tr>");
+            }
+            html.push_str("</table>");
+            data.insert("text/html".into(), html.into());
+        }
+        RichOutputKind::Plot => {
+            let svg = output.text().unwrap_or_default();
+            data.insert(output.mime.into(), svg.into());
+        }
+        RichOutputKind::Image | RichOutputKind::Animation => {
+            data.insert(output.mime.into(), BASE64.encode(&output.data).into());
+        }
+    }
+    data
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A system backend that sends output to the notebook
+///
+/// Everything else is handled by [`NativeSys`], except reading from stdin and exiting.
+struct KernelSys {
+    events: Arc<parking_lot::Mutex<Option<UnboundedSender<Event>>>>,
+}
+
+impl KernelSys {
+    fn send(&self, event: Event) -> Result<(), String> {
+        match &*self.events.lock() {
+            Some(send) => send.send(event).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+}
+
+macro_rules! delegate_to_native {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(fn $name(&self $(, $arg: $ty)*) -> $ret {
+            NativeSys.$name($($arg),*)
+        })*
+    };
+}
+
+impl SysBackend for KernelSys {
+    fn any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    fn print_str_stdout(&self, s: &str) -> Result<(), String> {
+        self.send(Event::Stdout(s.into()))
+    }
+    fn print_str_stderr(&self, s: &str) -> Result<(), String> {
+        self.send(Event::Stderr(s.into()))
+    }
+    fn print_str_trace(&self, s: &str) {
+        _ = self.send(Event::Stderr(s.into()));
+    }
+    fn show_rich(&self, output: RichOutput) -> Result<(), String> {
+        self.send(Event::Display(rich_mime_bundle(&output)))
+    }
+    delegate_to_native! {
+        fn var(&self, name: &str) -> Option<String>;
+        fn file_exists(&self, path: &str) -> bool;
+        fn list_dir(&self, path: &str) -> Result<Vec<String>, String>;
+        fn is_file(&self, path: &str) -> Result<bool, String>;
+        fn delete(&self, path: &str) -> Result<(), String>;
+        fn trash(&self, path: &str) -> Result<(), String>;
+        fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String>;
+        fn read_all(&self, handle: Handle) -> Result<Vec<u8>, String>;
+        fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String>;
+        fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String>;
+        fn create_file(&self, path: &Path) -> Result<Handle, String>;
+        fn open_file(&self, path: &Path, write: bool) -> Result<Handle, String>;
+        fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String>;
+        fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String>;
+        fn clipboard(&self) -> Result<String, String>;
+        fn set_clipboard(&self, contents: &str) -> Result<(), String>;
+        fn secret(&self, name: &str) -> Result<Option<String>, String>;
+        fn set_secret(&self, name: &str, secret: &str) -> Result<(), String>;
+        fn delete_secret(&self, name: &str) -> Result<(), String>;
+        fn shared_memory_open(&self, name: &str, size: usize) -> Result<Handle, String>;
+        fn shared_memory_read(&self, handle: Handle, offset: usize, count: Option<usize>) -> Result<Vec<u8>, String>;
+        fn shared_memory_write(&self, handle: Handle, offset: usize, bytes: &[u8]) -> Result<(), String>;
+        fn shared_memory_delete(&self, name: &str) -> Result<(), String>;
+        fn kv_open(&self, path: &Path) -> Result<Handle, String>;
+        fn kv_get(&self, handle: Handle, key: &str) -> Result<Option<Vec<u8>>, String>;
+        fn kv_put(&self, handle: Handle, key: &str, value: &[u8]) -> Result<(), String>;
+        fn kv_delete(&self, handle: Handle, key: &str) -> Result<(), String>;
+        fn kv_scan(&self, handle: Handle, prefix: &str) -> Result<Vec<(String, Vec<u8>)>, String>;
+        fn osc_send(&self, addr: &str, packet: &[u8]) -> Result<(), String>;
+        fn osc_listen(&self, addr: &str) -> Result<Handle, String>;
+        fn osc_addr(&self, handle: Handle) -> Result<std::net::SocketAddr, String>;
+        fn osc_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String>;
+        fn midi_ports(&self) -> Result<(Vec<String>, Vec<String>), String>;
+        fn midi_open_input(&self, port: &str) -> Result<(Handle, String), String>;
+        fn midi_open_output(&self, port: &str) -> Result<(Handle, String), String>;
+        fn midi_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String>;
+        fn midi_send(&self, handle: Handle, message: &[u8]) -> Result<(), String>;
+        fn sleep(&self, seconds: f64) -> Result<(), String>;
+        fn monotonic_time(&self) -> Result<f64, String>;
+        fn audio_sample_rate(&self) -> u32;
+        fn tcp_listen(&self, addr: &str) -> Result<Handle, String>;
+        fn tls_listen(&self, addr: &str, cert: &[u8], key: &[u8]) -> Result<Handle, String>;
+        fn tcp_accept(&self, handle: Handle) -> Result<Handle, String>;
+        fn tcp_connect(&self, addr: &str) -> Result<Handle, String>;
+        fn tls_connect(&self, addr: &str) -> Result<Handle, String>;
+        fn tcp_addr(&self, handle: Handle) -> Result<std::net::SocketAddr, String>;
+        fn tcp_set_non_blocking(&self, handle: Handle, non_blocking: bool) -> Result<(), String>;
+        fn tcp_set_read_timeout(&self, handle: Handle, timeout: Option<Duration>) -> Result<(), String>;
+        fn tcp_set_write_timeout(&self, handle: Handle, timeout: Option<Duration>) -> Result<(), String>;
+        fn close(&self, handle: Handle) -> Result<(), String>;
+        fn invoke(&self, path: &str) -> Result<(), String>;
+        fn run_command_capture(&self, command: &str, args: &[&str]) -> Result<(i32, String, String), String>;
+        fn run_command_stream(&self, command: &str, args: &[&str]) -> Result<[Handle; 3], String>;
+        fn change_directory(&self, path: &str) -> Result<(), String>;
+        fn https_get(&self, request: &str, handle: Handle) -> Result<String, String>;
+        fn ffi(&self, file: &str, result_ty: FfiType, name: &str, arg_tys: &[FfiType], args: &[Value]) -> Result<Value, String>;
+        fn mem_copy(&self, ty: FfiType, ptr: *const (), len: usize) -> Result<Value, String>;
+        fn mem_free(&self, ptr: *const ()) -> Result<(), String>;
+        fn load_git_module(&self, url: &str, branch: Option<&str>) -> Result<PathBuf, String>;
+        fn load_url_module(&self, url: &str) -> Result<PathBuf, String>;
+    }
+}
+
+fn random_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// The current UTC time in ISO 8601 format
+fn iso_timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+    // Convert days since the epoch to a civil date
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        now.subsec_micros()
+    )
+}
+
+fn decode_hex(hex: &[u8]) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let digit = |b: u8| (b as char).to_digit(16);
+    (hex.chunks_exact(2))
+        .map(|pair| Some((digit(pair[0])? * 16 + digit(pair[1])?) as u8))
+        .collect()
+}
@@ -1,7 +1,7 @@
 //! The Uiua lexer
 
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     error::Error,
     fmt,
     hash::Hash,
@@ -16,17 +16,31 @@ use serde::*;
 use serde_tuple::*;
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{ast::PlaceholderOp, ArraySwizzle, Inputs, Primitive, StackSwizzle, WILDCARD_CHAR};
+use crate::{
+    ast::PlaceholderOp, ArraySwizzle, Ident, Inputs, Primitive, PrimitiveAlias, StackSwizzle,
+    WILDCARD_CHAR,
+};
 
 /// Lex a Uiua source file
 pub fn lex(
     input: &str,
     src: impl IntoInputSrc,
     inputs: &mut Inputs,
+) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>, InputSrc) {
+    lex_with_aliases(input, src, inputs, &HashMap::new())
+}
+
+/// Lex a Uiua source file, resolving the given names to primitives
+pub(crate) fn lex_with_aliases(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &mut Inputs,
+    aliases: &HashMap<Ident, PrimitiveAlias>,
 ) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>, InputSrc) {
     let src = inputs.add_src(src, input);
     let (tokens, errors) = Lexer {
         input,
+        aliases,
         input_segments: input.graphemes(true).collect(),
         loc: Loc {
             char_pos: 0,
@@ -685,6 +699,8 @@ pub enum SemanticComment {
     Experimental,
     /// Prevent the containing function from being inlined
     NoInline,
+    /// Suppress warnings on this line and the next
+    AllowWarnings,
     #[doc(hidden)]
     Boo,
 }
@@ -694,6 +710,7 @@ impl fmt::Display for SemanticComment {
         match self {
             SemanticComment::Experimental => write!(f, "# Experimental!"),
             SemanticComment::NoInline => write!(f, "# No inline!"),
+            SemanticComment::AllowWarnings => write!(f, "# Allow warnings!"),
             SemanticComment::Boo => write!(f, "# Boo!"),
         }
     }
@@ -701,6 +718,7 @@ impl fmt::Display for SemanticComment {
 
 struct Lexer<'a> {
     input: &'a str,
+    aliases: &'a HashMap<Ident, PrimitiveAlias>,
     input_segments: Vec<&'a str>,
     loc: Loc,
     src: InputSrc,
@@ -1027,6 +1045,15 @@ impl<'a> Lexer<'a> {
                     if ambiguous_ne {
                         ident.pop();
                     }
+                    // Try to parse as an alias
+                    if let Some(alias) = self.aliases.get(ident.as_str()) {
+                        if ambiguous_ne {
+                            self.loc.char_pos -= 1;
+                            self.loc.byte_pos -= 1;
+                        }
+                        self.end(Glyph(alias.primitive), start);
+                        continue;
+                    }
                     // Try to parse as primitives
                     let lowercase_end = ident
                         .char_indices()
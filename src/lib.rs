@@ -109,6 +109,30 @@ let res = uiua.pop_int().unwrap();
 assert_eq!(res, 5);
 ```
 
+To run the same code on several threads, compile and run it once, then create a runtime for each thread with [`Uiua::clone_for_thread`].
+[`Uiua`] is [`Send`], and the clones share the compiled code, so nothing is recompiled.
+```rust
+use uiua::*;
+
+let mut uiua = Uiua::with_native_sys();
+uiua.run_str("F ← ×2").unwrap();
+let f = uiua.bound_functions().remove("F").unwrap();
+
+let handles: Vec<_> = (0..4)
+    .map(|i| {
+        let mut uiua = uiua.clone_for_thread();
+        let f = f.clone();
+        std::thread::spawn(move || {
+            uiua.push(i);
+            uiua.call(f).unwrap();
+            uiua.pop_int().unwrap()
+        })
+    })
+    .collect();
+let res: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+assert_eq!(res, [0, 2, 4, 6]);
+```
+
 You can format Uiua code with the [`mod@format`] module.
 ```rust
 use uiua::format::*;
@@ -133,6 +157,10 @@ The `uiua` crate has the following noteable feature flags:
 - `invoke`: Enables the `&invk` system function
 - `trash`: Enables the `&ftr` system function
 - `raw_mode`: Enables the `&raw` system function
+- `capi`: Enables the C API in the `capi` module
+- `python`: Enables the Python bindings in the `python` module
+- `jupyter`: Enables the Jupyter kernel in the `jupyter` module and the `uiua kernel` command
+- `stand`: Enables bundling programs into standalone executables in the `stand` module and the `uiua stand` command
 */
 
 #![allow(clippy::single_match, clippy::needless_range_loop)]
@@ -143,26 +171,37 @@ mod array;
 mod assembly;
 pub mod ast;
 mod boxed;
+#[cfg(feature = "capi")]
+pub mod capi;
 mod check;
 mod compile;
 mod complex;
+mod coverage;
 mod cowslice;
 mod error;
 mod ffi;
 pub mod format;
 mod function;
 mod grid_fmt;
+#[cfg(all(feature = "native_sys", feature = "tls"))]
+mod http_cache;
+#[cfg(feature = "jupyter")]
+pub mod jupyter;
 mod lex;
 pub mod lsp;
 mod optimize;
+mod osc;
 mod parse;
 mod primitive;
 #[doc(hidden)]
 pub mod profile;
+#[cfg(feature = "python")]
+pub mod python;
 mod run;
 mod shape;
+#[cfg(feature = "smtp")]
+mod smtp;
 #[cfg(feature = "stand")]
-#[doc(hidden)]
 pub mod stand;
 mod sys;
 #[cfg(feature = "native_sys")]
@@ -175,6 +214,7 @@ pub use self::{
     assembly::*,
     boxed::*,
     compile::*,
+    coverage::*,
     error::*,
     ffi::*,
     function::*,
@@ -226,6 +266,7 @@ mod tests {
             let mut env = Uiua::with_native_sys();
             let mut comp = Compiler::new();
             if let Err(e) = comp
+                .type_check(true)
                 .load_str_src(&code, &path)
                 .and_then(|comp| env.run_asm(&comp.asm))
             {
@@ -241,6 +282,20 @@ mod tests {
             if !env.take_stack().is_empty() {
                 panic!("{} had a non-empty stack", path.display());
             }
+            // Code skipped in other run modes should not cause warnings
+            for mode in [RunMode::Normal, RunMode::Test] {
+                let mut comp = Compiler::new();
+                comp.mode(mode).load_str_src(&code, &path).unwrap();
+                if let Some(diag) = (comp.take_diagnostics().into_iter())
+                    .find(|d| d.kind == DiagnosticKind::Warning)
+                {
+                    panic!(
+                        "Test failed in {} in {mode:?} mode:\n{}",
+                        path.display(),
+                        diag.report()
+                    );
+                }
+            }
         }
         _ = std::fs::remove_file("example.ua");
     }
@@ -282,6 +337,592 @@ mod tests {
         }
     }
 
+    #[test]
+    fn type_check() {
+        use super::*;
+        for code in [
+            "F ← +[1 2 3]\nF [1 2]",
+            "F ← ⊟ 1_2\nF [1 2 3]",
+            "F ← ⬚@ ⊟ [1 2]\nF 3",
+            "X ← [1 2]\n⧻+X [1 2 3]",
+        ] {
+            let mut comp = Compiler::new();
+            _ = comp.type_check(true).load_str(code);
+            if !(comp.take_diagnostics().iter()).any(|diag| diag.kind == DiagnosticKind::Warning) {
+                panic!("Type check did not warn for:\n{code}");
+            }
+        }
+    }
+
+    #[test]
+    fn constant_interning() {
+        use super::*;
+        let mut comp = Compiler::new();
+        let code = "F ← ⊂\"interned\"\n\"interned\" \"interned\"\n[1.5 0 0] ×¯1 [¯1.5 0 0]";
+        comp.load_str(code).unwrap();
+        let consts: Vec<&Value> = (comp.asm.instrs.iter())
+            .filter_map(|instr| match instr {
+                Instr::Push(val) => Some(val),
+                _ => None,
+            })
+            .collect();
+        let [Value::Char(a), Value::Char(b), Value::Char(c), Value::Num(d), Value::Num(e)] =
+            consts.as_slice()
+        else {
+            panic!("Unexpected constants: {consts:?}");
+        };
+        assert!(a.data.is_copy_of(&b.data) && b.data.is_copy_of(&c.data));
+        assert!(!d.data.is_copy_of(&e.data));
+    }
+
+    #[test]
+    #[cfg(feature = "stand")]
+    fn stand_bundle() {
+        use super::*;
+        let interpreter = b"not really an interpreter".as_slice();
+        assert!(stand::read_asm(interpreter).unwrap().is_none());
+        let asm = Compiler::new().load_str("+1 2").unwrap().finish();
+        let exe = stand::bundle(interpreter, &asm);
+        assert!(exe.starts_with(interpreter));
+        let mut env = Uiua::with_safe_sys();
+        env.run_asm(stand::read_asm(&exe).unwrap().unwrap())
+            .unwrap();
+        assert_eq!(env.pop_int().unwrap(), 3);
+    }
+
+    #[test]
+    fn clone_for_thread() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        env.run_str("X ← ⇡5\nF ← /+ +X").unwrap();
+        env.push(1);
+        let f = env.bound_functions().remove("F").unwrap();
+        let clone = env.clone_for_thread();
+        assert!(clone.stack().is_empty());
+        assert_eq!(clone.asm.instrs.as_ptr(), env.asm.instrs.as_ptr());
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let mut env = env.clone_for_thread();
+                let f = f.clone();
+                std::thread::spawn(move || {
+                    env.push(i);
+                    env.call(f).unwrap();
+                    env.pop_int().unwrap()
+                })
+            })
+            .collect();
+        let res: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(res, [10, 15, 20, 25]);
+        assert_eq!(env.pop_int().unwrap(), 1);
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        use super::*;
+        let code = "X ← 5\nY ← ⇡3\n1 2";
+        let mut env = Uiua::with_safe_sys();
+        env.run_str(code).unwrap();
+        let json = env.snapshot().to_json();
+        let expected: Vec<f64> = (0..3).map(|_| random()).collect();
+
+        let mut env = Uiua::with_safe_sys();
+        env.asm = Compiler::new().load_str(code).unwrap().finish();
+        env.restore(Snapshot::from_json(&json).unwrap()).unwrap();
+        let randoms: Vec<f64> = (0..3).map(|_| random()).collect();
+        assert_eq!(randoms, expected);
+        assert_eq!(env.bound_values()["X"], 5.into());
+        assert_eq!(env.bound_values()["Y"], Value::from_iter([0.0, 1.0, 2.0]));
+        assert_eq!(env.take_stack(), [2.into(), 1.into()]);
+
+        let mut env = Uiua::with_safe_sys().with_stack_snapshots(true);
+        assert!(env.run_str("10 20\n⊡ 5 ⇡⌊×2⚂").is_err());
+        let snapshot = env.take_error_snapshot().unwrap();
+        assert_eq!(snapshot.stack().len(), 2);
+        env.restore(snapshot).unwrap();
+        env.run_str("⊂").unwrap();
+        assert_eq!(env.pop_nums().unwrap(), [10.0, 20.0]);
+    }
+
+    #[test]
+    fn coverage() {
+        use super::*;
+        let code = "F ← ×2\nG ← ÷2\n≡F [1 2 3]";
+        let mut env = Uiua::with_safe_sys().with_coverage(true);
+        env.compile_run(|comp| comp.pre_eval_mode(PreEvalMode::Lazy).load_str(code))
+            .unwrap();
+        let coverage = env.coverage().unwrap();
+        let lines = coverage.lines(&env.asm);
+        let lines: Vec<_> = lines[&InputSrc::Str(0)].clone().into_iter().collect();
+        assert_eq!(lines, [(1, 3), (2, 0), (3, 1)]);
+        assert!(coverage.lcov(&env.asm).is_empty());
+        let html = coverage.html(&env.asm);
+        assert!(html.contains("2/3 lines"));
+    }
+
+    #[test]
+    fn deny_warnings() {
+        use super::*;
+        let mut comp = Compiler::new();
+        let code = "F ↚ +1\nF ↚ +2\nF 3";
+        assert!(comp.deny_warnings(true).load_str(code).is_err());
+        let mut comp = Compiler::new();
+        let code = "F ↚ +1 # Allow warnings!\nF ↚ +2\nF 3";
+        assert!(comp.deny_warnings(true).load_str(code).is_ok());
+    }
+
+    #[test]
+    fn parallel_rows() {
+        use super::*;
+        let run = |code: &str, parallel: bool| {
+            let mut env = Uiua::with_safe_sys().with_parallelism(parallel);
+            env.run_str(code).map(|_| env.take_stack())
+        };
+        for code in [
+            "F ← ≡(/+×2⇡)\nF ⇡2000",
+            "F ← ≡⊃(⇌|/+)\nF ↯2000_3⇡6000",
+            "F ← ∵(⊂⟜¯)\nF ⇡50_50",
+            "F ← ⊕□\nF ◿1500⇡3000 ⇡3000",
+        ] {
+            assert_eq!(
+                run(code, true).unwrap(),
+                run(code, false).unwrap(),
+                "{code}"
+            );
+        }
+        let code = "F ← ≡(⊡:⇡1500)\nF ⇡2000";
+        let err = run(code, true).unwrap_err();
+        assert!(err.to_string().contains("1500"), "{err}");
+        assert_eq!(err.to_string(), run(code, false).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn parallel_random() {
+        use super::*;
+        let run = |seed: u64, threads: usize, parallel: bool, n: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                seed_random(seed);
+                let mut env = Uiua::with_safe_sys().with_parallelism(parallel);
+                env.run_str(&format!("F ← ≡(+⚂)\nF ⇡{n}")).unwrap();
+                let Value::Num(arr) = env.pop("result").unwrap() else {
+                    panic!("Result should be numbers");
+                };
+                arr.data.to_vec()
+            })
+        };
+        let a = run(1, 4, true, 2000);
+        assert_eq!(a, run(1, 4, true, 2000));
+        assert_eq!(a, run(1, 1, true, 2000));
+        assert_eq!(a, run(1, 4, false, 2000));
+        assert_eq!(a[..10], run(1, 4, true, 10));
+        assert_ne!(a, run(2, 4, true, 2000));
+        let fracs: std::collections::HashSet<u64> = a.iter().map(|n| n.fract().to_bits()).collect();
+        assert_eq!(fracs.len(), a.len());
+    }
+
+    #[test]
+    fn failed_binding_not_unused() {
+        use super::*;
+        let mut comp = Compiler::new();
+        assert!(comp.load_str("C ← foo 1\nS ← +\nS 1 2").is_err());
+        let diagnostics = comp.take_diagnostics();
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diag| !diag.message.contains("never used")),
+            "{diagnostics:?}"
+        );
+    }
+
+    #[test]
+    fn macro_expansion_report() {
+        use super::*;
+        let code = "G! ←^ $\"+_ [1 2]\" ⊢\nH! ←^ $\"G!(_)\" ⊢\nH!([1 2 3])";
+        let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+            panic!("Macro code should have failed");
+        };
+        let report = err.report().to_string();
+        for expected in [
+            "in code generated by H!",
+            "in code generated by G!",
+            "+[1 2 3] [1 2]",
+        ] {
+            if !report.contains(expected) {
+                panic!("Report does not contain {expected:?}:\n{report}");
+            }
+        }
+    }
+
+    #[test]
+    fn rich_output() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        let code = r#"↯2_3⇡6 "<svg></svg>" ÷255 ⊞+.⇡40 "hi""#;
+        env.run_str(code).unwrap();
+        let values = env.take_stack();
+        let kinds: Vec<_> = (values.iter())
+            .map(|val| RichOutput::from_value(val, 44100).map(|output| output.kind))
+            .collect();
+        #[cfg(feature = "image")]
+        let image = Some(RichOutputKind::Image);
+        #[cfg(not(feature = "image"))]
+        let image = None;
+        let expected = [
+            None,
+            image,
+            Some(RichOutputKind::Plot),
+            Some(RichOutputKind::Table),
+        ];
+        assert_eq!(kinds, expected);
+        let table = RichOutput::from_value(&values[3], 44100).unwrap();
+        assert_eq!(table.text(), Some("0\t1\t2\n3\t4\t5\n"));
+    }
+
+    #[test]
+    fn did_you_mean() {
+        use super::*;
+        for (code, suggestion) in [
+            ("revrse 1_2", "Did you mean `⇌ reverse`?"),
+            ("Foo ← 1\nFoa", "Did you mean `Foo`?"),
+            ("Fooo ← 1\nFoob ← 2\nFoo", "Did you mean `Foob` or `Fooo`?"),
+        ] {
+            let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+                panic!("{code:?} should fail");
+            };
+            assert!(err.to_string().contains(suggestion), "{err}");
+        }
+        for code in ["Xyz", "A ← 1\nB"] {
+            let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+                panic!("{code:?} should fail");
+            };
+            assert!(!err.to_string().contains("Did you mean"), "{err}");
+        }
+    }
+
+    #[test]
+    fn primitive_aliases() {
+        use super::*;
+        let mut comp = Compiler::new();
+        comp.alias_primitive("sum", Primitive::Add)
+            .alias_primitive("fold", Primitive::Reduce)
+            .deprecated_alias("count", Primitive::Len, "use `length` instead");
+        comp.load_str("fold sum [1 2 3]\ncount [4 5]\ncount [6]")
+            .unwrap();
+        let warnings: Vec<String> = (comp.take_diagnostics().into_iter())
+            .filter(|diag| diag.kind == DiagnosticKind::Warning)
+            .map(|diag| diag.message)
+            .collect();
+        assert_eq!(
+            warnings,
+            ["`count` is a deprecated alias for ⧻ length \
+            and will be removed in the future, use `length` instead"]
+        );
+        let mut env = Uiua::with_safe_sys();
+        env.run_compiler(&mut comp).unwrap();
+        let stack: Vec<f64> = (env.take_stack().into_iter())
+            .map(|val| val.as_num(&env, "").unwrap())
+            .collect();
+        assert_eq!(stack, [6.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn disassemble() {
+        use super::*;
+        let asm = Compiler::new()
+            .load_str("F ← +1×2\nX ← [1 2 3]\nF ⊙X ⇡4")
+            .unwrap()
+            .finish();
+        let listing = asm.disassemble();
+        for section in ["TOP LEVEL", "FUNCTIONS", "BINDINGS", "CONSTANTS"] {
+            assert!(listing.contains(section), "missing {section}:\n{listing}");
+        }
+        assert!(listing.contains("F |1"), "{listing}");
+        assert!(listing.contains("×"), "{listing}");
+        assert!(listing.contains("X const number [3]"), "{listing}");
+        assert!(listing.contains("# 1:7"), "{listing}");
+    }
+
+    #[test]
+    fn optimization_report() {
+        use super::*;
+        let code = "F ← ≡(/+×2)\nG ← /+⊞(++)\nH ← [⍥(×10⚂)]\nI ← ≡/+\nJ ← /+⊞×";
+        let report = |enabled: bool| -> Vec<String> {
+            let mut comp = Compiler::new();
+            comp.optimization_report(enabled).load_str(code).unwrap();
+            (comp.take_diagnostics().into_iter())
+                .filter(|diag| diag.kind == DiagnosticKind::Advice)
+                .map(|diag| diag.message)
+                .collect()
+        };
+        assert!(report(false).is_empty());
+        let messages = report(true);
+        for expected in [
+            "rows was not fused with the reduce",
+            "reduce was not fused with the table because the tabled function has signature |3",
+            "array of random numbers was not generated all at once",
+            "not evaluated at compile time because ⚂ random is not pure",
+        ] {
+            assert!(
+                messages.iter().any(|message| message.contains(expected)),
+                "missing {expected:?} in {messages:#?}"
+            );
+        }
+        assert_eq!(messages.len(), 4, "{messages:#?}");
+    }
+
+    #[test]
+    fn shape_mismatch_note() {
+        use super::*;
+        let Err(err) = Uiua::with_safe_sys().run_str("+ [1 2] [1 2 3]") else {
+            panic!("shapes should not match");
+        };
+        let message = err.to_string();
+        assert!(message.contains("  [2]\n  [3]\n   ^\n"), "{message}");
+        assert!(message.contains("⬚ fill"), "{message}");
+        let Err(err) = Uiua::with_safe_sys().run_str("+ ↯3_4 0 ↯2_3_4 0") else {
+            panic!("shapes should not match");
+        };
+        assert!(err.to_string().contains("`☇2`"), "{err}");
+    }
+
+    #[test]
+    fn instr_history() {
+        use super::*;
+        let code = "+ [1 2] ⇡+3⌊⚂";
+        let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+            panic!("shapes should not match");
+        };
+        assert!(err.instr_history.is_none());
+        let Err(err) = Uiua::with_safe_sys().with_instr_history(3).run_str(code) else {
+            panic!("shapes should not match");
+        };
+        let history = err.instr_history.as_ref().expect("no history was recorded");
+        let history: Vec<_> = history.iter().map(|r| r.instr.as_str()).collect();
+        assert_eq!(history, ["⇡", "push [1 2]", "+"]);
+        let range = &err.instr_history.as_ref().unwrap()[0];
+        assert_eq!(range.shapes.len(), 1);
+        assert_eq!(range.span.as_ref().unwrap().start.col, 9);
+        let report = err.report().color(false).to_string();
+        assert!(report.contains("Recent instructions"), "{report}");
+    }
+
+    #[test]
+    fn stack_snapshot() {
+        use super::*;
+        let code = "\"abc\" ↯2_3 ⇡6 ⇡20\n+ [1 2] ⇡+3⌊⚂";
+        let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+            panic!("shapes should not match");
+        };
+        assert!(err.stack_snapshot.is_none());
+        let Err(err) = Uiua::with_safe_sys()
+            .with_stack_snapshots(true)
+            .run_str(code)
+        else {
+            panic!("shapes should not match");
+        };
+        let snapshot = err.stack_snapshot.as_ref().expect("no snapshot was taken");
+        let snapshot: Vec<_> = snapshot.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            snapshot,
+            [
+                "number [20] [0 1 2 3 4 5 6 7 …]",
+                "number [2 × 3] [0 1 2 3 4 5]",
+                "character [3] abc"
+            ]
+        );
+        let report = err.report().color(false).to_string();
+        assert!(report.contains("number [2 × 3]"), "{report}");
+    }
+
+    #[test]
+    fn semantic_spans_and_symbols() {
+        use super::lsp::*;
+        let code = "# Add one\nF ← +1\nX ← 5\nF X \"hi\"";
+        let spans: Vec<_> = (semantic_spans(code).into_iter())
+            .map(|sp| (&code[sp.range], sp.class, sp.primitive))
+            .collect();
+        let expected = [
+            ("# Add one", SemanticClass::Comment, None),
+            ("F", SemanticClass::MonadicFunction, None),
+            (" ← ", SemanticClass::Delimiter, None),
+            (
+                "+",
+                SemanticClass::DyadicFunction,
+                Some(crate::Primitive::Add),
+            ),
+            ("1", SemanticClass::Number, None),
+            ("X", SemanticClass::Constant, None),
+            (" ← ", SemanticClass::Delimiter, None),
+            ("5", SemanticClass::Number, None),
+            ("F", SemanticClass::MonadicFunction, None),
+            ("X", SemanticClass::Constant, None),
+            ("\"hi\"", SemanticClass::String, None),
+        ];
+        assert_eq!(spans, expected);
+        let symbols = symbols(code);
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].name, "F");
+        assert_eq!(&code[symbols[0].range.clone()], "F ← +1");
+        assert_eq!(symbols[0].signature, Some((1, 1).into()));
+        assert_eq!(symbols[0].comment.as_deref(), Some("Add one"));
+        assert_eq!(symbols[1].class, Some(SemanticClass::Constant));
+    }
+
+    #[test]
+    #[cfg(feature = "capi")]
+    fn capi() {
+        use super::capi::*;
+        use std::ffi::{CStr, CString};
+        unsafe {
+            let rt = uiua_new();
+            let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+            let shape = [2, 3];
+            assert_eq!(uiua_push_array(rt, data.as_ptr(), shape.as_ptr(), 2), 0);
+            let code = CString::new("/+").unwrap();
+            assert_eq!(uiua_run_str(rt, code.as_ptr()), 0);
+            assert_eq!(uiua_top_rank(rt), 1);
+            let mut shape = [0];
+            assert_eq!(uiua_top_shape(rt, shape.as_mut_ptr()), 0);
+            assert_eq!(shape, [3]);
+            let mut out = [0.0; 3];
+            assert_eq!(uiua_pop_array(rt, out.as_mut_ptr(), 3), 0);
+            assert_eq!(out, [5.0, 7.0, 9.0]);
+            let code = CString::new("+1").unwrap();
+            assert_ne!(uiua_run_str(rt, code.as_ptr()), 0);
+            assert!(!uiua_last_error(rt).is_null());
+            let code = CString::new("$\"_ world\" \"Hello\"").unwrap();
+            assert_eq!(uiua_run_str(rt, code.as_ptr()), 0);
+            assert!(uiua_last_error(rt).is_null());
+            let s = uiua_pop_string(rt);
+            assert_eq!(CStr::from_ptr(s).to_str().unwrap(), "Hello world");
+            uiua_free_string(s);
+            assert_eq!(uiua_stack_len(rt), 0);
+            uiua_free(rt);
+        }
+    }
+
+    #[test]
+    fn top_level_signature_warning() {
+        use super::*;
+        let mut comp = Compiler::new();
+        comp.type_check(true).load_str("1\n+ 2\n×").unwrap();
+        let diagnostics = comp.take_diagnostics();
+        assert!(
+            (diagnostics.iter()).any(|diag| diag.message.contains("will fail at runtime")),
+            "{diagnostics:?}"
+        );
+        let mut comp = Compiler::new();
+        comp.type_check(true).load_str("1\n+ 2\n¯").unwrap();
+        let diagnostics = comp.take_diagnostics();
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn email_validation() {
+        use super::*;
+        let send = |keys: &str, values: &str| {
+            let code = format!(
+                "&smtp map {{\"server\" \"from\" \"to\" {keys}}} \
+                {{\"smtp.example.com\" \"me@example.com\" {values}}}"
+            );
+            let Err(err) = Uiua::with_safe_sys().run_str(&code) else {
+                panic!("Sending email should have failed");
+            };
+            err.to_string()
+        };
+        let err = send("", "\"you@example.com\"");
+        assert!(err.contains("not supported"), "{err}");
+        let err = send("", "\"you@example.com>\\r\\nDATA\\r\\n\"");
+        assert!(err.contains("line breaks"), "{err}");
+        let err = send(
+            "\"security\" \"user\" \"password\"",
+            "\"you@example.com\" \"none\" \"me\" \"hunter2\"",
+        );
+        assert!(err.contains("without TLS"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn kv_store() {
+        use super::*;
+        let code = r#"
+KvPath ← "target/units-test.kv"
+Kv ← &kvo KvPath
+&kvp "b" {1 "x" [2 3]} Kv
+&kvp "a/1" ℂ1 2 Kv
+&kvp "a/2" map 1_2 3_4 Kv
+⍤⟜≍: {1 "x" [2 3]} &kvg "b" Kv
+⍤⟜≍: map {"a/1" "a/2"} {ℂ1 2 map 1_2 3_4} &kvs "a/" Kv
+&kvd "b" Kv
+⍤⟜≍: 0 ⍣&kvg⋅⋅0 "b" Kv
+&cl Kv
+⍤⟜≍: 4 get 2 &kvg "a/2" &kvo KvPath
+&fde KvPath
+"#;
+        if let Err(e) = Uiua::with_native_sys().run_str(code) {
+            panic!("{}", e.report());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "native_sys", feature = "tls"))]
+    fn http_cache() {
+        use crate::http_cache::{cached_request, ResponseHead};
+        use std::cell::Cell;
+        let dir = std::env::temp_dir().join(format!("uiua-http-cache-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        let origin = "https://example.com:443";
+        let fresh = "HTTP/1.1 200 OK\r\nCache-Control: max-age=60\r\n\r\nfresh";
+        let get = |target: &str, headers: &str, response: &str| {
+            let sent = Cell::new(0);
+            let request = format!("GET {target} HTTP/1.0\r\nHost: example.com\r\n{headers}\r\n");
+            for _ in 0..2 {
+                let res = cached_request(Some(&dir), origin, &request, |_| {
+                    sent.set(sent.get() + 1);
+                    Ok(response.into())
+                });
+                assert!(res.unwrap().ends_with("fresh"));
+            }
+            sent.get()
+        };
+        // Fresh responses are reused
+        assert_eq!(get("/a", "", fresh), 1);
+        // Without a cache directory, nothing is cached
+        let request = "GET /a HTTP/1.0\r\nHost: example.com\r\n\r\n";
+        let res = cached_request(None, origin, request, |_| Err("unreachable".into()));
+        assert!(res.is_err());
+        // Requests that may carry secrets are not cached
+        assert_eq!(get("/b?key=secret", "", fresh), 2);
+        assert_eq!(get("/c", "X-Api-Key: secret\r\n", fresh), 2);
+        assert_eq!(get("/d", "Authorization: Bearer secret\r\n", fresh), 2);
+        // Private and varying responses are not cached
+        let private = "HTTP/1.1 200 OK\r\nCache-Control: private, max-age=60\r\n\r\nfresh";
+        assert_eq!(get("/e", "", private), 2);
+        let vary = "HTTP/1.1 200 OK\r\nCache-Control: max-age=60\r\nVary: *\r\n\r\nfresh";
+        assert_eq!(get("/f", "", vary), 2);
+        // Stale responses are marked when the server cannot be reached
+        let stale = "HTTP/1.1 200 OK\r\nCache-Control: max-age=0\r\n\r\nfresh";
+        assert_eq!(get("/g", "", stale), 2);
+        let request = "GET /g HTTP/1.0\r\nHost: example.com\r\n\r\n";
+        let res = cached_request(Some(&dir), origin, request, |_| Err("unreachable".into()));
+        let res = res.unwrap();
+        assert!(ResponseHead::parse(&res).unwrap().stale, "{res}");
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn http_module_import() {
+        use super::*;
+        let code = "~ \"http://example.com/lib.ua\"";
+        let Err(err) = Uiua::with_safe_sys().run_str(code) else {
+            panic!("Importing from an http:// URL should have failed");
+        };
+        assert!(err.to_string().contains("https://"), "{err}");
+    }
+
     #[test]
     fn lsp_spans() {
         use super::*;
@@ -7,12 +7,15 @@ use std::{
     slice,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     algorithm::invert::{invert_instrs, under_instrs},
     ast::{Item, Modifier, PlaceholderOp, Ref, RefComponent, Word},
     ident_modifier_args, instrs_are_pure,
     lex::{CodeSpan, Sp},
     parse::parse,
+    primitive::PrimClass,
     ArraySwizzle, Assembly, BindingInfo, BindingKind, Compiler, DocComment, Ident, InputSrc,
     Inputs, PreEvalMode, Primitive, Purity, SafeSys, Shape, Signature, StackSwizzle, SysBackend,
     UiuaError, Value, CONSTANTS,
@@ -103,6 +106,154 @@ pub fn spans_with_backend(input: &str, backend: impl SysBackend) -> (Vec<Sp<Span
     (spanner.items_spans(&items), spanner.asm.inputs)
 }
 
+/// The semantic class of a span of Uiua code
+///
+/// These are the classes the language server uses for semantic highlighting.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SemanticClass {
+    Comment,
+    Number,
+    String,
+    Label,
+    Signature,
+    Delimiter,
+    Placeholder,
+    StackFunction,
+    NoadicFunction,
+    MonadicFunction,
+    DyadicFunction,
+    TriadicFunction,
+    TetradicFunction,
+    MonadicModifier,
+    DyadicModifier,
+    TriadicModifier,
+    Module,
+    Constant,
+}
+
+impl SemanticClass {
+    fn function(args: usize) -> Option<Self> {
+        Some(match args {
+            0 => SemanticClass::NoadicFunction,
+            1 => SemanticClass::MonadicFunction,
+            2 => SemanticClass::DyadicFunction,
+            3 => SemanticClass::TriadicFunction,
+            4 => SemanticClass::TetradicFunction,
+            _ => return None,
+        })
+    }
+    fn modifier(args: usize) -> Option<Self> {
+        Some(match args {
+            1 => SemanticClass::MonadicModifier,
+            2 => SemanticClass::DyadicModifier,
+            3 => SemanticClass::TriadicModifier,
+            _ => return None,
+        })
+    }
+    /// Get the semantic class of a primitive
+    pub fn of_primitive(prim: Primitive) -> Option<Self> {
+        match prim.class() {
+            PrimClass::Stack | PrimClass::Planet if prim.modifier_args().is_none() => {
+                Some(SemanticClass::StackFunction)
+            }
+            _ => match prim.modifier_args() {
+                Some(margs) => Self::modifier(margs),
+                None => Self::function(prim.args()?),
+            },
+        }
+    }
+    fn of_binding(kind: &BindingDocsKind) -> Option<Self> {
+        match kind {
+            BindingDocsKind::Constant(_) => Some(SemanticClass::Constant),
+            BindingDocsKind::Function { sig, .. } => Self::function(sig.args),
+            BindingDocsKind::Modifier(margs) => Self::modifier(*margs),
+            BindingDocsKind::Module => Some(SemanticClass::Module),
+        }
+    }
+}
+
+impl SpanKind {
+    /// Get the semantic class of this kind of span
+    pub fn semantic_class(&self) -> Option<SemanticClass> {
+        match self {
+            SpanKind::Primitive(prim) => SemanticClass::of_primitive(*prim),
+            SpanKind::String => Some(SemanticClass::String),
+            SpanKind::Number => Some(SemanticClass::Number),
+            SpanKind::Comment | SpanKind::OutputComment => Some(SemanticClass::Comment),
+            SpanKind::Label => Some(SemanticClass::Label),
+            SpanKind::Signature => Some(SemanticClass::Signature),
+            SpanKind::Delimiter | SpanKind::FuncDelim(_) => Some(SemanticClass::Delimiter),
+            SpanKind::Placeholder(_) => Some(SemanticClass::Placeholder),
+            SpanKind::Ident(Some(docs)) => SemanticClass::of_binding(&docs.kind),
+            SpanKind::StackSwizzle(sw) => SemanticClass::function(sw.signature().args),
+            SpanKind::ArraySwizzle(_) => Some(SemanticClass::MonadicFunction),
+            SpanKind::Ident(None) | SpanKind::Strand | SpanKind::Whitespace => None,
+        }
+    }
+}
+
+/// A classified span of Uiua code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SemanticSpan {
+    /// The byte range of the span in the code
+    pub range: std::ops::Range<usize>,
+    /// The semantic class of the span
+    pub class: SemanticClass,
+    /// The primitive, if the span is one
+    pub primitive: Option<Primitive>,
+}
+
+/// A binding defined in Uiua code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symbol {
+    /// The name of the binding
+    pub name: String,
+    /// The byte range of the binding's name in the code
+    pub name_range: std::ops::Range<usize>,
+    /// The byte range of the whole binding in the code
+    pub range: std::ops::Range<usize>,
+    /// The semantic class of the binding
+    pub class: Option<SemanticClass>,
+    /// The signature of the binding, if it is a function
+    pub signature: Option<Signature>,
+    /// Whether the binding is public
+    pub public: bool,
+    /// The binding's doc comment
+    pub comment: Option<String>,
+}
+
+/// Get the semantic classes of spans of Uiua code
+///
+/// Spans are sorted and given as byte ranges into the code.
+/// Spans that have no semantic class, like whitespace, are omitted.
+pub fn semantic_spans(input: &str) -> Vec<SemanticSpan> {
+    let (spans, _) = spans(input);
+    (spans.into_iter())
+        .filter_map(|sp| {
+            Some(SemanticSpan {
+                range: sp.span.byte_range(),
+                class: sp.value.semantic_class()?,
+                primitive: match sp.value {
+                    SpanKind::Primitive(prim) => Some(prim),
+                    _ => None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Get the bindings defined in Uiua code, in the order they are defined
+pub fn symbols(input: &str) -> Vec<Symbol> {
+    let src = InputSrc::Str(0);
+    let (items, _, _) = parse(input, src.clone(), &mut Inputs::default());
+    let spanner = Spanner::new(src, input, SafeSys::default());
+    let mut symbols = Vec::new();
+    spanner.items_symbols(&items, &mut symbols);
+    symbols
+}
+
 /// Metadata for code for use in IDE tools
 #[derive(Debug, Clone, Default)]
 pub struct CodeMeta {
@@ -190,6 +341,16 @@ impl Spanner {
                     }
                     spans.extend(self.words_spans(&binding.words));
                 }
+                Item::Destructure(destructure) => {
+                    for name in &destructure.names {
+                        let binding_docs = self
+                            .binding_docs(&name.span)
+                            .or_else(|| self.reference_docs(&name.span));
+                        spans.push(name.span.clone().sp(SpanKind::Ident(binding_docs)));
+                    }
+                    spans.push(destructure.arrow_span.clone().sp(SpanKind::Delimiter));
+                    spans.extend(self.words_spans(&destructure.words));
+                }
                 Item::Import(import) => {
                     if let Some(name) = &import.name {
                         let binding_docs = self.binding_docs(&name.span);
@@ -205,12 +366,66 @@ impl Spanner {
                         }
                     }
                 }
+                Item::Data(data) => {
+                    spans.push(data.tilde_span.clone().sp(SpanKind::Delimiter));
+                    let binding_docs = self.binding_docs(&data.name.span);
+                    spans.push(data.name.span.clone().sp(SpanKind::Ident(binding_docs)));
+                    for variant in &data.variants {
+                        spans.push(variant.bar_span.clone().sp(SpanKind::Delimiter));
+                        spans.push(variant.name.span.clone().sp(SpanKind::Ident(None)));
+                        for field in &variant.fields {
+                            spans.push(field.span.clone().sp(SpanKind::Ident(None)));
+                        }
+                    }
+                }
             }
         }
         spans.sort_by_key(|sp| sp.span.start);
         spans
     }
 
+    fn items_symbols(&self, items: &[Item], symbols: &mut Vec<Symbol>) {
+        for item in items {
+            match item {
+                Item::TestScope(items) => self.items_symbols(&items.value, symbols),
+                Item::Words(_) => {}
+                Item::Binding(binding) => {
+                    symbols.push(self.binding_symbol(&binding.name, binding.span()))
+                }
+                Item::Destructure(destructure) => {
+                    for name in &destructure.names {
+                        symbols.push(self.binding_symbol(name, destructure.span()));
+                    }
+                }
+                Item::Import(import) => {
+                    if let Some(name) = &import.name {
+                        let range = name.span.clone().merge(import.path.span.clone());
+                        symbols.push(self.binding_symbol(name, range));
+                    }
+                }
+                Item::Data(data) => symbols.push(self.binding_symbol(&data.name, data.span())),
+            }
+        }
+    }
+
+    fn binding_symbol(&self, name: &Sp<Ident>, range: CodeSpan) -> Symbol {
+        let binfo = self.asm.bindings.iter().find(|b| b.span == name.span);
+        let docs = binfo.map(|binfo| self.make_binding_docs(binfo));
+        Symbol {
+            name: name.value.to_string(),
+            name_range: name.span.byte_range(),
+            range: range.byte_range(),
+            class: (docs.as_ref()).and_then(|docs| SemanticClass::of_binding(&docs.kind)),
+            signature: match docs.as_ref().map(|docs| &docs.kind) {
+                Some(BindingDocsKind::Function { sig, .. }) => Some(*sig),
+                _ => None,
+            },
+            public: binfo.map_or(true, |binfo| binfo.public),
+            comment: (binfo.and_then(|binfo| binfo.comment.as_ref()))
+                .map(|comment| comment.text.to_string()),
+        }
+    }
+
     fn binding_docs(&self, span: &CodeSpan) -> Option<BindingDocs> {
         for binding in &self.asm.bindings {
             if binding.span != *span {
@@ -475,6 +690,7 @@ mod server {
     use std::{
         env::current_dir,
         path::{Path, PathBuf},
+        time::Duration,
     };
 
     use dashmap::DashMap;
@@ -490,11 +706,14 @@ mod server {
     use super::*;
 
     use crate::{
+        ast::{Binding, Func},
+        error::{macro_expansion_message, macro_expansions},
         format::{format_str, FormatConfig},
         is_ident_char,
         lex::{lex, Loc},
-        primitive::{PrimClass, PrimDocFragment},
-        AsciiToken, Assembly, BindingInfo, NativeSys, PrimDocLine, Span, Token, UiuaErrorKind,
+        primitive::PrimDocFragment,
+        AsciiToken, Assembly, BindingInfo, Inputs, NativeSys, PrimDocLine, Span, Token, Uiua,
+        UiuaErrorKind,
     };
 
     pub struct LspDoc {
@@ -534,6 +753,69 @@ mod server {
         }
     }
 
+    impl LspDoc {
+        /// Show the values a function binding produces, either from top-level
+        /// lines that call it or by evaluating it if it takes no arguments
+        fn binding_values_markdown(&self, docs: &BindingDocs) -> Option<String> {
+            let BindingDocsKind::Function { sig, pure, .. } = docs.kind else {
+                return None;
+            };
+            let index = (self.asm.bindings.iter()).position(|b| b.span == docs.src_span)?;
+            let show = |values: &[Value], md: &mut String| {
+                for val in values {
+                    let s = val.show();
+                    let s = if s.len() < 250 { s } else { val.shape_string() };
+                    for line in s.lines() {
+                        md.push_str("\n# ");
+                        md.push_str(line);
+                    }
+                }
+            };
+            // Cached values from top-level calls
+            let mut calls: Vec<&CodeSpan> = (self.code_meta.top_level_values.keys())
+                .filter(|line| {
+                    self.code_meta.global_references.iter().any(|(name, idx)| {
+                        *idx == index
+                            && name.span.src == line.src
+                            && line.start.byte_pos <= name.span.start.byte_pos
+                            && name.span.end.byte_pos <= line.end.byte_pos
+                    })
+                })
+                .collect();
+            calls.sort();
+            let mut md = String::new();
+            for line in calls.into_iter().take(3) {
+                md.push_str(if md.is_empty() { "```uiua\n" } else { "\n" });
+                line.as_str(&self.asm.inputs, |s| md.push_str(s));
+                show(&self.code_meta.top_level_values[line], &mut md);
+            }
+            // Evaluate functions that take no arguments
+            if md.is_empty() && pure && sig.args == 0 && sig.outputs > 0 {
+                let BindingKind::Func(f) = &self.asm.bindings[index].kind else {
+                    return None;
+                };
+                let mut asm = self.asm.clone();
+                asm.top_slices.clear();
+                let mut env = Uiua::with_safe_sys().with_execution_limit(Duration::from_millis(40));
+                env.run_asm(asm).ok()?;
+                env.call(f.clone()).ok()?;
+                let values = env.take_stack();
+                if values.iter().any(|v| v.element_count() > 1000) {
+                    return None;
+                }
+                md.push_str("```uiua\n");
+                docs.src_span.as_str(&self.asm.inputs, |s| md.push_str(s));
+                show(&values, &mut md);
+            }
+            if md.is_empty() {
+                None
+            } else {
+                md.push_str("\n```");
+                Some(md)
+            }
+        }
+    }
+
     #[doc(hidden)]
     pub fn run_language_server() {
         #[cfg(feature = "native_sys")]
@@ -692,6 +974,25 @@ mod server {
         }
 
         async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+            let config = self
+                .client
+                .configuration(vec![ConfigurationItem {
+                    scope_uri: Some(
+                        params
+                            .text_document_position_params
+                            .text_document
+                            .uri
+                            .clone(),
+                    ),
+                    section: Some("uiua.hover.values".into()),
+                }])
+                .await
+                .unwrap_or_default();
+            let show_values = if let [serde_json::Value::Bool(show_values)] = config.as_slice() {
+                *show_values
+            } else {
+                true
+            };
             let Some(doc) =
                 (self.docs).get(&params.text_document_position_params.text_document.uri)
             else {
@@ -832,6 +1133,12 @@ mod server {
                     }
                     value.push_str(&comment.text);
                 }
+                if show_values {
+                    if let Some(values) = doc.binding_values_markdown(docs) {
+                        value.push_str("\n\n");
+                        value.push_str(&values);
+                    }
+                }
                 Hover {
                     contents: HoverContents::Markup(MarkupContent {
                         kind: MarkupKind::Markdown,
@@ -1216,50 +1523,23 @@ mod server {
             let mut prev_line = 0;
             let mut prev_char = 0;
             for sp in &doc.spans {
-                let token_type = match &sp.value {
-                    SpanKind::String => UIUA_STRING_STT,
-                    SpanKind::Number => UIUA_NUMBER_STT,
-                    SpanKind::Comment => SemanticTokenType::COMMENT,
-                    SpanKind::Primitive(p) => match p.class() {
-                        PrimClass::Stack | PrimClass::Planet if p.modifier_args().is_none() => {
-                            STACK_FUNCTION_STT
-                        }
-                        _ if p.modifier_args() == Some(1) => MONADIC_MODIFIER_STT,
-                        _ if p.modifier_args() == Some(2) => DYADIC_MODIFIER_STT,
-                        _ if p.modifier_args() == Some(3) => TRIADIC_MODIFIER_STT,
-                        _ if p.args() == Some(0) => NOADIC_FUNCTION_STT,
-                        _ if p.args() == Some(1) => MONADIC_FUNCTION_STT,
-                        _ if p.args() == Some(2) => DYADIC_FUNCTION_STT,
-                        _ if p.args() == Some(3) => TRIADIC_FUNCTION_STT,
-                        _ if p.args() == Some(4) => TETRADIC_FUNCTION_STT,
-                        _ => continue,
-                    },
-                    SpanKind::Ident(Some(docs)) => match docs.kind {
-                        BindingDocsKind::Constant(_) => continue,
-                        BindingDocsKind::Function { sig, .. } => match sig.args {
-                            0 => NOADIC_FUNCTION_STT,
-                            1 => MONADIC_FUNCTION_STT,
-                            2 => DYADIC_FUNCTION_STT,
-                            3 => TRIADIC_FUNCTION_STT,
-                            4 => TETRADIC_FUNCTION_STT,
-                            _ => continue,
-                        },
-                        BindingDocsKind::Modifier(margs) => match margs {
-                            1 => MONADIC_MODIFIER_STT,
-                            2 => DYADIC_MODIFIER_STT,
-                            3 => TRIADIC_MODIFIER_STT,
-                            _ => continue,
-                        },
-                        BindingDocsKind::Module => MODULE_STT,
-                    },
-                    SpanKind::StackSwizzle(sw) => match sw.signature().args {
-                        1 => MONADIC_FUNCTION_STT,
-                        2 => DYADIC_FUNCTION_STT,
-                        3 => TRIADIC_FUNCTION_STT,
-                        4 => TETRADIC_FUNCTION_STT,
-                        _ => continue,
-                    },
-                    SpanKind::ArraySwizzle(_) => MONADIC_FUNCTION_STT,
+                if let SpanKind::OutputComment = sp.value {
+                    continue;
+                }
+                let token_type = match sp.value.semantic_class() {
+                    Some(SemanticClass::Comment) => SemanticTokenType::COMMENT,
+                    Some(SemanticClass::Number) => UIUA_NUMBER_STT,
+                    Some(SemanticClass::String) => UIUA_STRING_STT,
+                    Some(SemanticClass::StackFunction) => STACK_FUNCTION_STT,
+                    Some(SemanticClass::NoadicFunction) => NOADIC_FUNCTION_STT,
+                    Some(SemanticClass::MonadicFunction) => MONADIC_FUNCTION_STT,
+                    Some(SemanticClass::DyadicFunction) => DYADIC_FUNCTION_STT,
+                    Some(SemanticClass::TriadicFunction) => TRIADIC_FUNCTION_STT,
+                    Some(SemanticClass::TetradicFunction) => TETRADIC_FUNCTION_STT,
+                    Some(SemanticClass::MonadicModifier) => MONADIC_MODIFIER_STT,
+                    Some(SemanticClass::DyadicModifier) => DYADIC_MODIFIER_STT,
+                    Some(SemanticClass::TriadicModifier) => TRIADIC_MODIFIER_STT,
+                    Some(SemanticClass::Module) => MODULE_STT,
                     _ => continue,
                 };
                 let mut token_type = UIUA_SEMANTIC_TOKEN_TYPES
@@ -1463,6 +1743,12 @@ mod server {
                 }));
             }
 
+            actions.extend(refactor_actions(
+                &doc,
+                &params.text_document.uri,
+                params.range,
+            ));
+
             Ok(if actions.is_empty() {
                 None
             } else {
@@ -1628,32 +1914,43 @@ mod server {
                                 }
                             }
                         };
+                        let (range, message) =
+                            macro_aware_range(span, message.value.clone(), &doc.asm.inputs);
                         diagnostics.push(Diagnostic {
                             severity: Some(DiagnosticSeverity::ERROR),
-                            range: uiua_span_to_lsp(span),
-                            message: message.value.clone(),
+                            range,
+                            message,
                             ..Default::default()
                         });
                     }
                     UiuaErrorKind::Parse(errors, _) => {
                         for err in errors {
+                            let (range, message) = macro_aware_range(
+                                &err.span,
+                                err.value.to_string(),
+                                &doc.asm.inputs,
+                            );
                             diagnostics.push(Diagnostic {
                                 severity: Some(DiagnosticSeverity::ERROR),
-                                range: uiua_span_to_lsp(&err.span),
-                                message: err.value.to_string(),
+                                range,
+                                message,
                                 ..Default::default()
                             });
                         }
                     }
-                    UiuaErrorKind::Throw(value, span, _) => diagnostics.push(Diagnostic {
-                        severity: Some(DiagnosticSeverity::ERROR),
-                        range: uiua_span_to_lsp(match span {
-                            Span::Code(span) => span,
-                            Span::Builtin => continue,
-                        }),
-                        message: value.format(),
-                        ..Default::default()
-                    }),
+                    UiuaErrorKind::Throw(value, span, _) => {
+                        let Span::Code(span) = span else {
+                            continue;
+                        };
+                        let (range, message) =
+                            macro_aware_range(span, value.format(), &doc.asm.inputs);
+                        diagnostics.push(Diagnostic {
+                            severity: Some(DiagnosticSeverity::ERROR),
+                            range,
+                            message,
+                            ..Default::default()
+                        })
+                    }
                     _ => {}
                 }
             }
@@ -1665,10 +1962,12 @@ mod server {
                     | crate::DiagnosticKind::Style
                     | crate::DiagnosticKind::Info => DiagnosticSeverity::INFORMATION,
                 };
+                let (range, message) =
+                    macro_aware_range(&diag.span, diag.message.clone(), &doc.asm.inputs);
                 diagnostics.push(Diagnostic {
                     severity: Some(sev),
-                    range: uiua_span_to_lsp(&diag.span),
-                    message: diag.message.clone(),
+                    range,
+                    message,
                     ..Default::default()
                 });
             }
@@ -1931,6 +2230,377 @@ mod server {
         path.canonicalize().unwrap_or(path)
     }
 
+    /// Get the code actions that refactor the code at a range
+    fn refactor_actions(doc: &LspDoc, uri: &Url, range: Range) -> Vec<CodeActionOrCommand> {
+        let (line, col) = lsp_pos_to_uiua(range.start);
+        let path = uri_path(uri);
+        let mut actions = Vec::new();
+        let mut top_lines = Vec::new();
+        item_lines(&doc.items, &mut top_lines);
+
+        // Extract selection into a named binding
+        let end = lsp_pos_to_uiua(range.end);
+        if end != (line, col) {
+            if let Some((item_line, span)) = top_lines.iter().find_map(|(item_line, words)| {
+                Some((*item_line, selected_words(words, (line, col), end)?))
+            }) {
+                let name = unused_binding_name(&doc.input, "Extracted");
+                let body = &doc.input[span.byte_range()];
+                actions.push(edit_action(
+                    format!("Extract selection into {name}"),
+                    CodeActionKind::REFACTOR_EXTRACT,
+                    uri,
+                    vec![
+                        insert_binding_edit(item_line, &name, body),
+                        replace_edit(&doc.input, &span, &name),
+                    ],
+                ));
+            }
+        }
+
+        // Convert inline function to named function
+        if let Some((item_line, span, func)) = top_lines.iter().find_map(|(item_line, words)| {
+            innermost_word(words, line, col, &|word| match &word.value {
+                Word::Func(func) if func.closed => Some((*item_line, &word.span, func)),
+                _ => None,
+            })
+        }) {
+            let name = unused_binding_name(&doc.input, "Func");
+            let text = &doc.input[span.byte_range()];
+            let body = if func.lines.len() == 1 {
+                text[1..text.len() - 1].trim()
+            } else {
+                text
+            };
+            actions.push(edit_action(
+                format!("Convert to named function {name}"),
+                CodeActionKind::REFACTOR_EXTRACT,
+                uri,
+                vec![
+                    insert_binding_edit(item_line, &name, body),
+                    replace_edit(&doc.input, span, &name),
+                ],
+            ));
+        }
+
+        // Convert named function to inline function
+        for (name, index) in &doc.code_meta.global_references {
+            if !name.span.contains_line_col(line, col) || name.span.src != path {
+                continue;
+            }
+            let info = &doc.asm.bindings[*index];
+            if !matches!(info.kind, BindingKind::Func(_)) {
+                continue;
+            }
+            let Some(binding) = find_binding(&doc.items, |b| b.name.span == info.span) else {
+                continue;
+            };
+            let Some(body) = binding_body(&doc.input, binding, true) else {
+                continue;
+            };
+            actions.push(edit_action(
+                "Convert to inline function".into(),
+                CodeActionKind::REFACTOR_INLINE,
+                uri,
+                vec![replace_edit(&doc.input, &name.span, &format_snippet(&body))],
+            ));
+            break;
+        }
+
+        // Inline binding
+        if let Some(binding) = find_binding(&doc.items, |b| {
+            b.name.span.contains_line_col(line, col) && b.name.span.src == path
+        }) {
+            let index = (doc.asm.bindings.iter()).position(|b| b.span == binding.name.span);
+            let kind = index.map(|i| &doc.asm.bindings[i].kind);
+            let references: Vec<_> = (doc.code_meta.global_references.iter())
+                .filter(|(name, idx)| Some(**idx) == index && name.span.src == path)
+                .map(|(name, _)| &name.span)
+                .collect();
+            if !binding.array_macro
+                && ident_modifier_args(&binding.name.value) == 0
+                && matches!(kind, Some(BindingKind::Const(_) | BindingKind::Func(_)))
+                && !references.is_empty()
+            {
+                if let Some(body) = binding_body(&doc.input, binding, false) {
+                    let body = format_snippet(&body);
+                    let end_line = binding.span().end.line as u32;
+                    let mut edits = vec![TextEdit {
+                        range: Range::new(
+                            Position::new(binding.name.span.start.line as u32 - 1, 0),
+                            Position::new(end_line, 0),
+                        ),
+                        new_text: String::new(),
+                    }];
+                    edits.extend(
+                        references
+                            .into_iter()
+                            .map(|span| replace_edit(&doc.input, span, &body)),
+                    );
+                    actions.push(edit_action(
+                        format!("Inline binding {}", binding.name.value),
+                        CodeActionKind::REFACTOR_INLINE,
+                        uri,
+                        edits,
+                    ));
+                }
+            }
+        }
+
+        // Swap modifier functions
+        if let Some((modifier, [a, b])) = top_lines.iter().find_map(|(_, words)| {
+            innermost_word(words, line, col, &|word| {
+                let Word::Modified(m) = &word.value else {
+                    return None;
+                };
+                let operands: Vec<_> = m.code_operands().collect();
+                let spans = match operands.as_slice() {
+                    [a, b] => [a.span.clone(), b.span.clone()],
+                    [Sp {
+                        value: Word::Pack(pack),
+                        ..
+                    }] if pack.branches.len() == 2 => [
+                        branch_span(&pack.branches[0].value)?,
+                        branch_span(&pack.branches[1].value)?,
+                    ],
+                    _ => return None,
+                };
+                Some((&m.modifier.value, spans))
+            })
+        }) {
+            actions.push(edit_action(
+                format!("Swap {modifier} functions"),
+                CodeActionKind::REFACTOR_REWRITE,
+                uri,
+                vec![
+                    TextEdit {
+                        range: uiua_span_to_lsp(&a),
+                        new_text: doc.input[b.byte_range()].into(),
+                    },
+                    TextEdit {
+                        range: uiua_span_to_lsp(&b),
+                        new_text: doc.input[a.byte_range()].into(),
+                    },
+                ],
+            ));
+        }
+
+        actions
+    }
+
+    /// Make a code action that edits a single document
+    fn edit_action(
+        title: String,
+        kind: CodeActionKind,
+        uri: &Url,
+        edits: Vec<TextEdit>,
+    ) -> CodeActionOrCommand {
+        CodeActionOrCommand::CodeAction(CodeAction {
+            title,
+            kind: Some(kind),
+            edit: Some(WorkspaceEdit {
+                changes: Some([(uri.clone(), edits)].into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Format a snippet of generated code, falling back to the snippet itself
+    fn format_snippet(code: &str) -> String {
+        match format_str(code, &FormatConfig::find().unwrap_or_default()) {
+            Ok(formatted) => formatted.output.trim_end().into(),
+            Err(_) => code.into(),
+        }
+    }
+
+    /// Replace a span with some code, separating it from adjacent words if necessary
+    fn replace_edit(input: &str, span: &CodeSpan, new_text: &str) -> TextEdit {
+        let joins = |a: Option<char>, b: Option<char>| {
+            a.zip(b)
+                .is_some_and(|(a, b)| a.is_alphanumeric() && b.is_alphanumeric())
+        };
+        let range = span.byte_range();
+        let mut text = String::new();
+        if joins(
+            input[..range.start].chars().next_back(),
+            new_text.chars().next(),
+        ) {
+            text.push(' ');
+        }
+        text.push_str(new_text);
+        if joins(
+            new_text.chars().next_back(),
+            input[range.end..].chars().next(),
+        ) {
+            text.push(' ');
+        }
+        TextEdit {
+            range: uiua_span_to_lsp(span),
+            new_text: text,
+        }
+    }
+
+    /// Insert a new binding on the line before the given line
+    fn insert_binding_edit(line: usize, name: &str, body: &str) -> TextEdit {
+        let pos = Position::new(line as u32 - 1, 0);
+        TextEdit {
+            range: Range::new(pos, pos),
+            new_text: format!("{}\n", format_snippet(&format!("{name} ← {body}"))),
+        }
+    }
+
+    /// Find a binding name that is not yet used in the code
+    fn unused_binding_name(input: &str, base: &str) -> String {
+        let is_subscript = |c: char| ('₀'..='₉').contains(&c);
+        let used = |name: &str| {
+            input.match_indices(name).any(|(i, _)| {
+                !input[..i].ends_with(is_ident_char)
+                    && !input[i + name.len()..].starts_with(|c| is_ident_char(c) || is_subscript(c))
+            })
+        };
+        let mut name = base.to_string();
+        let mut n = 0;
+        while used(&name) {
+            // Names cannot contain digits, so count with letters instead
+            n += 1;
+            let mut suffix = String::new();
+            let mut i = n;
+            while i > 0 {
+                suffix.insert(0, (b'A' + ((i - 1) % 26) as u8) as char);
+                i = (i - 1) / 26;
+            }
+            name = format!("{base}{suffix}");
+        }
+        name
+    }
+
+    /// Collect the top-level lines of words in some items,
+    /// along with the line each one's item starts on
+    fn item_lines<'a>(items: &'a [Item], lines: &mut Vec<(usize, &'a [Sp<Word>])>) {
+        for item in items {
+            match item {
+                Item::Words(words) => {
+                    for line in words {
+                        if let Some(first) = line.first() {
+                            lines.push((first.span.start.line as usize, line));
+                        }
+                    }
+                }
+                Item::Binding(binding) => {
+                    let start = binding.name.span.start.line as usize;
+                    let mut code = binding.words.iter().filter(|w| w.value.is_code());
+                    if let (
+                        Some(Sp {
+                            value: Word::Func(func),
+                            ..
+                        }),
+                        None,
+                    ) = (code.next(), code.next())
+                    {
+                        // Bindings to a single function are already named
+                        lines.extend(func.lines.iter().map(|line| (start, line.as_slice())));
+                    } else {
+                        lines.push((start, &binding.words));
+                    }
+                }
+                Item::Destructure(des) => {
+                    lines.push((des.pattern_span.start.line as usize, &des.words))
+                }
+                Item::Import(_) | Item::Data(_) => {}
+                Item::TestScope(scope) => item_lines(&scope.value, lines),
+            }
+        }
+    }
+
+    /// Find a binding in some items
+    fn find_binding(items: &[Item], f: impl Fn(&Binding) -> bool + Copy) -> Option<&Binding> {
+        items.iter().find_map(|item| match item {
+            Item::Binding(binding) if f(binding) => Some(binding),
+            Item::TestScope(scope) => find_binding(&scope.value, f),
+            _ => None,
+        })
+    }
+
+    /// Get the code of a binding as it would be written in place of a reference to it
+    fn binding_body(input: &str, binding: &Binding, always_func: bool) -> Option<String> {
+        let code: Vec<_> = binding.words.iter().filter(|w| w.value.is_code()).collect();
+        let span = (code.first()?.span.clone()).merge(code.last()?.span.clone());
+        let body = &input[span.byte_range()];
+        Some(if let Some(sig) = &binding.signature {
+            format!("({} {body})", &input[sig.span.byte_range()])
+        } else if code.len() == 1 && (!always_func || matches!(code[0].value, Word::Func(_))) {
+            body.into()
+        } else {
+            format!("({body})")
+        })
+    }
+
+    /// Get the span of the code in a function pack branch
+    fn branch_span(func: &Func) -> Option<CodeSpan> {
+        let mut code = func.lines.iter().flatten().filter(|w| w.value.is_code());
+        let first = code.next()?;
+        let last = code.next_back().unwrap_or(first);
+        let start = func.signature.as_ref().map_or(&first.span, |sig| &sig.span);
+        Some(start.clone().merge(last.span.clone()))
+    }
+
+    /// The lines of words nested directly inside a word
+    fn nested_lines(word: &Word) -> Vec<&[Sp<Word>]> {
+        match word {
+            Word::Func(func) => func.lines.iter().map(Vec::as_slice).collect(),
+            Word::Pack(pack) => (pack.branches.iter())
+                .flat_map(|branch| &branch.value.lines)
+                .map(Vec::as_slice)
+                .collect(),
+            Word::Array(arr) => arr.lines.iter().map(Vec::as_slice).collect(),
+            Word::Modified(m) => m.operands.iter().map(slice::from_ref).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Find the innermost word at a position that satisfies some predicate
+    fn innermost_word<'a, T>(
+        words: &'a [Sp<Word>],
+        line: usize,
+        col: usize,
+        f: &impl Fn(&'a Sp<Word>) -> Option<T>,
+    ) -> Option<T> {
+        let word =
+            (words.iter()).find(|w| w.value.is_code() && w.span.contains_line_col(line, col))?;
+        (nested_lines(&word.value).into_iter())
+            .find_map(|line_words| innermost_word(line_words, line, col, f))
+            .or_else(|| f(word))
+    }
+
+    /// Get the span of a run of sibling words that exactly covers a selection
+    fn selected_words(
+        words: &[Sp<Word>],
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Option<CodeSpan> {
+        let mut selected: Option<CodeSpan> = None;
+        for word in words.iter().filter(|w| w.value.is_code()) {
+            let word_start = (word.span.start.line as usize, word.span.start.col as usize);
+            let word_end = (word.span.end.line as usize, word.span.end.col as usize);
+            if word_end <= start || word_start >= end {
+                continue;
+            }
+            if word_start >= start && word_end <= end {
+                selected = Some(match selected {
+                    Some(span) => span.merge(word.span.clone()),
+                    None => word.span.clone(),
+                });
+            } else if selected.is_none() && word_start <= start && word_end >= end {
+                return (nested_lines(&word.value).into_iter())
+                    .find_map(|line_words| selected_words(line_words, start, end));
+            } else {
+                return None;
+            }
+        }
+        selected
+    }
+
     fn lsp_pos_to_uiua(pos: Position) -> (usize, usize) {
         (pos.line as usize + 1, pos.character as usize + 1)
     }
@@ -1947,6 +2617,20 @@ mod server {
         uiua_locs_to_lsp(span.start, span.end)
     }
 
+    /// Point spans in macro-generated code at the macro's call site,
+    /// describing the expansion chain in the message
+    fn macro_aware_range(span: &CodeSpan, mut message: String, inputs: &Inputs) -> (Range, String) {
+        let (call, expansions) = macro_expansions(span.clone());
+        for generated in &expansions {
+            message.push('\n');
+            message.push_str(&macro_expansion_message(inputs, generated));
+            if let Some(code) = generated.try_as_str(inputs, |s| s.to_string()) {
+                message.push_str(&format!(": `{code}`"));
+            }
+        }
+        (uiua_span_to_lsp(&call), message)
+    }
+
     fn doc_frag_markdown(md: &mut String, frag: &PrimDocFragment) {
         match frag {
             PrimDocFragment::Text(text) => md.push_str(text),
@@ -2018,4 +2702,123 @@ mod server {
         }
         value
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::fs;
+
+        use super::*;
+
+        /// Apply the refactor whose title starts with `title` at the first
+        /// occurrence of `selection` in the input, returning the new code
+        fn refactor(input: &str, selection: &str, title: &str) -> String {
+            let file_name = format!("uiua_refactor_{}.ua", title.replace(' ', "_"));
+            let path = std::env::temp_dir().join(file_name);
+            fs::write(&path, input).unwrap();
+            let uri = Url::from_file_path(&path).unwrap();
+            let doc = LspDoc::new(&path, input.into());
+            let start = input.find(selection).unwrap();
+            let end = start + selection.len();
+            let range = Range::new(position(input, start), position(input, end));
+            let actions = refactor_actions(&doc, &uri, range);
+            let _ = fs::remove_file(&path);
+            let mut edits = (actions.into_iter())
+                .find_map(|action| match action {
+                    CodeActionOrCommand::CodeAction(action) if action.title.starts_with(title) => {
+                        action.edit?.changes?.remove(&uri)
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| panic!("No {title:?} action for {selection:?} in {input:?}"));
+            edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+            let mut output = input.to_string();
+            for edit in edits.into_iter().rev() {
+                let range = offset(input, edit.range.start)..offset(input, edit.range.end);
+                output.replace_range(range, &edit.new_text);
+            }
+            output
+        }
+
+        fn position(input: &str, byte: usize) -> Position {
+            let before = &input[..byte];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            let line = before.matches('\n').count();
+            Position::new(line as u32, before[line_start..].chars().count() as u32)
+        }
+
+        fn offset(input: &str, pos: Position) -> usize {
+            let line_start: usize = (input.split_inclusive('\n'))
+                .take(pos.line as usize)
+                .map(str::len)
+                .sum();
+            let line = &input[line_start..];
+            line_start
+                + (line.char_indices().nth(pos.character as usize)).map_or(line.len(), |(i, _)| i)
+        }
+
+        fn run(code: &str) -> Vec<Value> {
+            let mut env = Uiua::with_safe_sys();
+            if let Err(e) = env.run_str(code) {
+                panic!("{code:?} failed: {e}");
+            }
+            env.take_stack()
+        }
+
+        #[test]
+        fn extract_selection() {
+            let input = "×2 +1 5\n";
+            let output = refactor(input, "+1", "Extract selection");
+            assert_eq!(output, "Extracted ← +1\n×2 Extracted 5\n");
+            assert_eq!(run(input), run(&output));
+
+            let input = "Extracted ← 1\n×2 +Extracted 5\n";
+            let output = refactor(input, "+Extracted", "Extract selection");
+            assert_eq!(
+                output,
+                "Extracted ← 1\nExtractedA ← +Extracted\n×2 ExtractedA 5\n"
+            );
+            assert_eq!(run(input), run(&output));
+        }
+
+        #[test]
+        fn convert_to_named_function() {
+            let input = "≡(+1) [1 2 3]\n";
+            let output = refactor(input, "+", "Convert to named function");
+            assert_eq!(output, "Func ← +1\n≡Func [1 2 3]\n");
+            assert_eq!(run(input), run(&output));
+        }
+
+        #[test]
+        fn convert_to_inline_function() {
+            let input = "F ← +1\n≡F [1 2 3]\n";
+            let output = refactor(input, "F [", "Convert to inline function");
+            assert_eq!(output, "F ← +1\n≡(+1) [1 2 3]\n");
+            assert_eq!(run(input), run(&output));
+        }
+
+        #[test]
+        fn inline_binding() {
+            let input = "X ← 5\n+X X\n";
+            let output = refactor(input, "X ←", "Inline binding");
+            assert_eq!(output, "+5 5\n");
+            assert_eq!(run(input), run(&output));
+
+            let input = "F ← +1\nF F 5\n";
+            let output = refactor(input, "F ←", "Inline binding");
+            assert_eq!(output, "(+1) (+1) 5\n");
+            assert_eq!(run(input), run(&output));
+        }
+
+        #[test]
+        fn swap_modifier_functions() {
+            for (input, expected) in [("⊃(+1|×2) 5\n", "⊃(×2|+1) 5\n"), ("⊃+× 2 3\n", "⊃×+ 2 3\n")]
+            {
+                let output = refactor(input, "⊃", "Swap");
+                assert_eq!(output, expected);
+                let mut swapped = run(input);
+                swapped.reverse();
+                assert_eq!(run(&output), swapped);
+            }
+        }
+    }
 }
@@ -21,8 +21,8 @@ use rustyline::{error::ReadlineError, DefaultEditor};
 use uiua::{
     format::{format_file, format_str, FormatConfig, FormatConfigSource},
     lsp::BindingDocsKind,
-    Assembly, Compiler, NativeSys, PrimClass, RunMode, SpanKind, Uiua, UiuaError, UiuaErrorKind,
-    UiuaResult, Value,
+    Assembly, Compiler, NativeSys, PreEvalMode, PrimClass, RunMode, SpanKind, Uiua, UiuaError,
+    UiuaErrorKind, UiuaResult, Value,
 };
 
 fn main() {
@@ -64,6 +64,7 @@ fn run() -> UiuaResult {
     if let Some(asm) = &*uiua::stand::STAND_ASM {
         let mut rt = Uiua::with_native_sys().with_args(env::args().skip(1).collect());
         rt.run_asm(asm)?;
+        rt.run_exit_hooks()?;
         print_stack(&rt.take_stack(), true);
         return Ok(());
     }
@@ -115,6 +116,11 @@ fn run() -> UiuaResult {
                 no_color,
                 formatter_options,
                 time_instrs,
+                verbose_errors,
+                instr_history,
+                type_check,
+                deny_warnings,
+                optimization_report,
                 mode,
                 #[cfg(feature = "audio")]
                 audio_options,
@@ -136,7 +142,9 @@ fn run() -> UiuaResult {
                 let mut rt = Uiua::with_native_sys()
                     .with_file_path(&path)
                     .with_args(args)
-                    .time_instrs(time_instrs);
+                    .time_instrs(time_instrs)
+                    .with_stack_snapshots(verbose_errors)
+                    .with_instr_history(instr_history.unwrap_or(0));
                 if path.extension().is_some_and(|ext| ext == "uasm") {
                     let uasm = match fs::read_to_string(&path) {
                         Ok(json) => json,
@@ -152,7 +160,14 @@ fn run() -> UiuaResult {
                             return Ok(());
                         }
                     };
+                    for asset in assembly.changed_assets(&NativeSys) {
+                        eprintln!(
+                            "Warning: embedded file {} has changed since the assembly was built",
+                            asset.path.display()
+                        );
+                    }
                     rt.run_asm(assembly)?;
+                    rt.run_exit_hooks()?;
                 } else {
                     if !no_format {
                         let config = FormatConfig::from_source(
@@ -163,12 +178,22 @@ fn run() -> UiuaResult {
                     }
                     let mode = mode.unwrap_or(RunMode::Normal);
                     rt.compile_run(|comp| {
-                        comp.mode(mode).print_diagnostics(true).load_file(&path)
+                        comp.mode(mode)
+                            .type_check(type_check)
+                            .deny_warnings(deny_warnings)
+                            .optimization_report(optimization_report)
+                            .print_diagnostics(true)
+                            .load_file(&path)
                     })?;
+                    rt.run_exit_hooks()?;
                 }
                 print_stack(&rt.take_stack(), !no_color);
             }
-            App::Build { path, output } => {
+            App::Build {
+                path,
+                output,
+                disassemble,
+            } => {
                 let path = if let Some(path) = path {
                     path
                 } else {
@@ -184,6 +209,17 @@ fn run() -> UiuaResult {
                     .print_diagnostics(true)
                     .load_file(&path)?
                     .finish();
+                if disassemble {
+                    let listing = assembly.disassemble();
+                    if let Some(output) = output {
+                        if let Err(e) = fs::write(output, listing) {
+                            eprintln!("Failed to write listing: {e}");
+                        }
+                    } else {
+                        print!("{listing}");
+                    }
+                    return Ok(());
+                }
                 let output = output.unwrap_or_else(|| path.with_extension("uasm"));
                 let uasm = assembly.to_uasm();
                 if let Err(e) = fs::write(output, uasm) {
@@ -210,6 +246,7 @@ fn run() -> UiuaResult {
             App::Test {
                 path,
                 formatter_options,
+                coverage,
                 args,
             } => {
                 let path = if let Some(path) = path {
@@ -228,12 +265,30 @@ fn run() -> UiuaResult {
                 format_file(&path, &config)?;
                 let mut rt = Uiua::with_native_sys()
                     .with_file_path(&path)
-                    .with_args(args);
-                rt.compile_run(|comp| {
+                    .with_args(args)
+                    .with_coverage(coverage.is_some());
+                let res = rt.compile_run(|comp| {
+                    if coverage.is_some() {
+                        // Code evaluated at compile time would not be counted
+                        comp.pre_eval_mode(PreEvalMode::Lazy);
+                    }
                     comp.mode(RunMode::Test)
                         .print_diagnostics(true)
                         .load_file(path)
-                })?;
+                });
+                if let Some((report_path, coverage)) = coverage.zip(rt.coverage()) {
+                    let report = if (report_path.extension())
+                        .is_some_and(|ext| ext == "html" || ext == "htm")
+                    {
+                        coverage.html(&rt.asm)
+                    } else {
+                        coverage.lcov(&rt.asm)
+                    };
+                    if let Err(e) = fs::write(&report_path, report) {
+                        eprintln!("Failed to write coverage report: {e}");
+                    }
+                }
+                res?;
                 println!("No failures!");
             }
             App::Watch {
@@ -258,6 +313,29 @@ fn run() -> UiuaResult {
             }
             #[cfg(feature = "lsp")]
             App::Lsp => uiua::lsp::run_language_server(),
+            #[cfg(feature = "jupyter")]
+            App::Kernel {
+                connection_file,
+                install,
+            } => {
+                if install {
+                    match uiua::jupyter::install_kernel_spec() {
+                        Ok(dir) => println!("Installed the Uiua kernel to {}", dir.display()),
+                        Err(e) => {
+                            eprintln!("Failed to install the kernel: {e}");
+                            exit(1);
+                        }
+                    }
+                } else if let Some(path) = connection_file {
+                    if let Err(e) = uiua::jupyter::run_kernel(&path) {
+                        eprintln!("Failed to run the kernel: {e}");
+                        exit(1);
+                    }
+                } else {
+                    eprintln!("Either a connection file or --install is required");
+                    exit(1);
+                }
+            }
             App::Repl {
                 file,
                 formatter_options,
@@ -289,37 +367,41 @@ fn run() -> UiuaResult {
                     eprintln!("{} does not exist", main.display());
                     exit(1);
                 }
-                match uiua::stand::build_exe(&main) {
-                    Ok(bytes) => {
-                        let name = name
-                            .or_else(|| {
-                                env::current_dir().ok().and_then(|p| {
-                                    p.file_stem().map(|p| p.to_string_lossy().into_owned())
-                                })
-                            })
-                            .unwrap_or_else(|| "program".into());
-                        let path = PathBuf::from(name).with_extension(env::consts::EXE_EXTENSION);
-                        #[allow(clippy::needless_borrows_for_generic_args)]
-                        if let Err(e) = fs::write(&path, bytes) {
-                            eprintln!("Failed to write executable: {e}");
+                let bytes = if main.extension().is_some_and(|ext| ext == "uasm") {
+                    // Bundle an already-built assembly
+                    let asm = fs::read_to_string(&main)
+                        .map_err(|e| format!("Failed to read assembly: {e}"))
+                        .and_then(|uasm| Assembly::from_uasm(&uasm))
+                        .and_then(|asm| {
+                            uiua::stand::bundle_current_exe(&asm).map_err(|e| e.to_string())
+                        });
+                    match asm {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            eprintln!("Failed to build executable: {e}");
                             exit(1);
                         }
-                        // Set executable permissions on Unix
-                        #[cfg(unix)]
-                        if let Err(e) = (|| {
-                            use std::os::unix::fs::PermissionsExt;
-                            let mut perms = fs::metadata(&path)?.permissions();
-                            perms.set_mode(0o755);
-                            fs::set_permissions(&path, perms)
-                        })() {
-                            eprintln!("Failed to set executable permissions: {e}");
+                    }
+                } else {
+                    match uiua::stand::build_exe(&main) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            eprintln!("Failed to build executable: {e}");
                             exit(1);
                         }
                     }
-                    Err(e) => {
-                        eprintln!("Failed to build executable: {e}");
-                        exit(1);
-                    }
+                };
+                let name = name
+                    .or_else(|| {
+                        env::current_dir()
+                            .ok()
+                            .and_then(|p| p.file_stem().map(|p| p.to_string_lossy().into_owned()))
+                    })
+                    .unwrap_or_else(|| "program".into());
+                let path = PathBuf::from(name).with_extension(env::consts::EXE_EXTENSION);
+                if let Err(e) = uiua::stand::write_exe(&path, &bytes) {
+                    eprintln!("Failed to write executable: {e}");
+                    exit(1);
                 }
             }
         },
@@ -588,6 +670,21 @@ enum App {
         formatter_options: FormatterOptions,
         #[clap(long, help = "Emit the duration of each instruction's execution")]
         time_instrs: bool,
+        #[clap(long, help = "Show a snapshot of the stack when an error occurs")]
+        verbose_errors: bool,
+        #[clap(
+            long,
+            value_name = "COUNT",
+            help = "Show the last COUNT instructions executed before an error, \
+                    with the shapes of the top stack values after each"
+        )]
+        instr_history: Option<usize>,
+        #[clap(long, help = "Warn about type and shape mismatches before running")]
+        type_check: bool,
+        #[clap(long, help = "Treat warnings as errors")]
+        deny_warnings: bool,
+        #[clap(long, help = "Report optimizations that could not be applied")]
+        optimization_report: bool,
         #[clap(long, help = "Run the file in a specific mode")]
         mode: Option<RunMode>,
         #[cfg(feature = "audio")]
@@ -601,6 +698,11 @@ enum App {
         path: Option<PathBuf>,
         #[clap(short, long, help = "The path to the output file")]
         output: Option<PathBuf>,
+        #[clap(
+            long,
+            help = "Print a readable listing of the compiled code instead of writing the assembly"
+        )]
+        disassemble: bool,
     },
     #[clap(about = "Evaluate an expression and print its output")]
     Eval {
@@ -618,6 +720,11 @@ enum App {
         path: Option<PathBuf>,
         #[clap(flatten)]
         formatter_options: FormatterOptions,
+        #[clap(
+            long,
+            help = "Write a coverage report to a file, as HTML if it ends in .html and as LCOV otherwise"
+        )]
+        coverage: Option<PathBuf>,
         #[clap(trailing_var_arg = true, help = "Arguments to pass to the program")]
         args: Vec<String>,
     },
@@ -647,6 +754,14 @@ enum App {
     #[cfg(feature = "lsp")]
     #[clap(about = "Run the Language Server")]
     Lsp,
+    #[cfg(feature = "jupyter")]
+    #[clap(about = "Run the Jupyter kernel")]
+    Kernel {
+        #[clap(help = "The connection file passed by Jupyter")]
+        connection_file: Option<PathBuf>,
+        #[clap(long, help = "Install the kernel so Jupyter can find it")]
+        install: bool,
+    },
     #[clap(about = "Run the Uiua interpreter in a REPL")]
     Repl {
         #[clap(help = "A Uiua file to run before the REPL starts")]
@@ -669,7 +784,7 @@ enum App {
     #[cfg(feature = "stand")]
     #[clap(about = "Create a standalone executable")]
     Stand {
-        #[clap(help = "The main file of the program")]
+        #[clap(help = "The main file of the program, or an assembly built with `uiua build`")]
         main: Option<PathBuf>,
         #[clap(short = 'o', long, help = "The name of the output executable")]
         name: Option<String>,
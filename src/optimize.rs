@@ -1,8 +1,11 @@
-use std::fmt;
+use std::{collections::HashSet, fmt, iter::repeat};
 
 use ecow::EcoVec;
 
-use crate::{Assembly, ImplPrimitive, Instr, Primitive};
+use crate::{
+    algorithm::view::{ViewChain, ViewOp},
+    Assembly, FuncSlice, ImplPrimitive, Instr, Primitive,
+};
 
 pub(crate) fn optimize_instrs_mut(
     instrs: &mut EcoVec<Instr>,
@@ -160,6 +163,15 @@ pub(crate) fn optimize_instrs_mut(
                 instrs.pop();
             }
         }
+        // Fused strided operations
+        ([.., last], new) if fused_view(last, &new).is_some() => {
+            let chain = fused_view(last, &new).unwrap();
+            let span = match last {
+                Instr::Prim(_, span) | Instr::ImplPrim(_, span) => *span,
+                _ => unreachable!(),
+            };
+            *last = Instr::ImplPrim(View(chain), span);
+        }
         // Sorting
         ([.., Instr::Prim(Dup, _), Instr::Prim(Rise, _)], Instr::Prim(Select, span)) => {
             instrs.pop();
@@ -280,6 +292,41 @@ pub(crate) fn optimize_instrs_mut(
     }
 }
 
+/// Fuse two instructions into a single strided copy
+///
+/// Only fuses if the view starts with a transpose and does more than transpose
+fn fused_view(a: &Instr, b: &Instr) -> Option<ViewChain> {
+    use ImplPrimitive::*;
+    use Primitive::*;
+    let mut chain = match a {
+        Instr::Prim(Transpose, _) => ViewChain::new([ViewOp::Transpose])?,
+        &Instr::ImplPrim(TransposeN(n), _) => transpose_chain(n)?,
+        &Instr::ImplPrim(View(chain), _) => chain,
+        _ => return None,
+    };
+    let ops = match b {
+        Instr::Prim(Transpose, _) => ViewChain::new([ViewOp::Transpose])?,
+        &Instr::ImplPrim(TransposeN(n), _) => transpose_chain(n)?,
+        Instr::Prim(Reverse, _) => ViewChain::new([ViewOp::Reverse])?,
+        Instr::Prim(First, _) => ViewChain::new([ViewOp::First])?,
+        Instr::ImplPrim(Last, _) => ViewChain::new([ViewOp::Last])?,
+        _ => return None,
+    };
+    for &op in ops.ops() {
+        chain.push(op)?;
+    }
+    (!chain.is_transpose_only()).then_some(chain)
+}
+
+fn transpose_chain(n: i32) -> Option<ViewChain> {
+    let op = if n < 0 {
+        ViewOp::UnTranspose
+    } else {
+        ViewOp::Transpose
+    };
+    ViewChain::new(repeat(op).take(n.unsigned_abs() as usize))
+}
+
 pub(crate) fn optimize_instrs<I>(instrs: I, maximal: bool, asm: &Assembly) -> EcoVec<Instr>
 where
     I: IntoIterator<Item = Instr> + fmt::Debug,
@@ -297,3 +344,91 @@ where
     // println!("to       {:?}", new);
     new
 }
+
+/// Find places in some instructions where an optimization could not be applied
+///
+/// Function bodies are searched as well.
+/// Returns the span index of each place along with an explanation.
+pub(crate) fn missed_optimizations(instrs: &[Instr], asm: &Assembly) -> Vec<(usize, String)> {
+    let mut missed = Vec::new();
+    missed_optimizations_impl(instrs, asm, &mut HashSet::new(), &mut missed);
+    missed
+}
+
+fn missed_optimizations_impl(
+    instrs: &[Instr],
+    asm: &Assembly,
+    visited: &mut HashSet<FuncSlice>,
+    missed: &mut Vec<(usize, String)>,
+) {
+    use Primitive::*;
+    for (i, instr) in instrs.iter().enumerate() {
+        if let Instr::PushFunc(f) = instr {
+            if visited.insert(f.slice) {
+                missed_optimizations_impl(f.instrs(asm), asm, visited, missed);
+            }
+        }
+        // These patterns are only left in optimized code if fusing them failed
+        match (&instrs[..i], instr) {
+            // Reduce table
+            (
+                [.., Instr::PushFunc(g), Instr::Prim(Table, _), Instr::PushFunc(f)],
+                Instr::Prim(Reduce, span),
+            ) => {
+                let (which, sig) = if f.signature() != (2, 1) {
+                    ("reduced", f.signature())
+                } else {
+                    ("tabled", g.signature())
+                };
+                missed.push((
+                    *span,
+                    format!(
+                        "This reduce was not fused with the table because \
+                        the {which} function has signature {sig} rather than |2"
+                    ),
+                ));
+            }
+            // Adjacent
+            ([.., Instr::Prim(Windows, _), Instr::PushFunc(f)], Instr::Prim(Rows, span)) => {
+                if let [Instr::PushFunc(reduced_f), Instr::Prim(Reduce, _)] = f.instrs(asm) {
+                    missed.push((
+                        *span,
+                        format!(
+                            "This rows was not fused with the windows because \
+                            the reduced function has signature {} rather than |2",
+                            reduced_f.signature()
+                        ),
+                    ));
+                }
+            }
+            // Reduce depth
+            ([.., Instr::PushFunc(f)], Instr::Prim(Rows, span)) => {
+                if let [_, _, .., Instr::PushFunc(_), Instr::Prim(Reduce, _)] = f.instrs(asm) {
+                    missed.push((
+                        *span,
+                        "This rows was not fused with the reduce inside it because \
+                        its function does more than reduce"
+                            .into(),
+                    ));
+                }
+            }
+            // End array repeat rand
+            (
+                [.., Instr::PushFunc(f), Instr::Prim(Repeat, span)],
+                Instr::EndArray { boxed: false, .. },
+            ) if f.as_primitive(asm) != Some(Rand)
+                && (f.instrs(asm).iter()).any(|instr| matches!(instr, Instr::Prim(Rand, _))) =>
+            {
+                missed.push((
+                    *span,
+                    format!(
+                        "This array of random numbers was not generated all at once \
+                        because the repeated function does more than {}",
+                        Rand.format()
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Encoding and decoding of [OSC](https://opensoundcontrol.stanford.edu/spec-1_0.html) packets
+
+/// An argument of an OSC message
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OscArg {
+    Int(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    Str(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+}
+
+/// An OSC message
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+/// Encode a message as an OSC packet
+pub(crate) fn encode(message: &OscMessage) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    push_str(&mut bytes, &message.address);
+    let mut tags = String::from(",");
+    for arg in &message.args {
+        tags.push(match arg {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::Long(_) => 'h',
+            OscArg::Double(_) => 'd',
+            OscArg::Str(_) => 's',
+            OscArg::Blob(_) => 'b',
+            OscArg::Bool(true) => 'T',
+            OscArg::Bool(false) => 'F',
+            OscArg::Nil => 'N',
+        });
+    }
+    push_str(&mut bytes, &tags);
+    for arg in &message.args {
+        match arg {
+            OscArg::Int(i) => bytes.extend(i.to_be_bytes()),
+            OscArg::Float(f) => bytes.extend(f.to_be_bytes()),
+            OscArg::Long(i) => bytes.extend(i.to_be_bytes()),
+            OscArg::Double(f) => bytes.extend(f.to_be_bytes()),
+            OscArg::Str(s) => push_str(&mut bytes, s),
+            OscArg::Blob(blob) => {
+                bytes.extend((blob.len() as i32).to_be_bytes());
+                bytes.extend(blob);
+                pad(&mut bytes);
+            }
+            OscArg::Bool(_) | OscArg::Nil => {}
+        }
+    }
+    bytes
+}
+
+/// Decode an OSC packet into its messages
+///
+/// Bundles are flattened, and their time tags are ignored.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Vec<OscMessage>, String> {
+    let mut messages = Vec::new();
+    decode_into(bytes, &mut messages)?;
+    Ok(messages)
+}
+
+fn decode_into(bytes: &[u8], messages: &mut Vec<OscMessage>) -> Result<(), String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if bytes.starts_with(b"#bundle\0") {
+        reader.pos = 16;
+        while reader.pos < bytes.len() {
+            let len = reader.int()?;
+            let len = usize::try_from(len).map_err(|_| "Invalid OSC bundle element size")?;
+            decode_into(reader.take(len)?, messages)?;
+        }
+        return Ok(());
+    }
+    let address = reader.str()?;
+    if !address.starts_with('/') {
+        return Err(format!("Invalid OSC address {address:?}"));
+    }
+    // Very old implementations may omit the type tags
+    if reader.pos == bytes.len() {
+        messages.push(OscMessage {
+            address,
+            args: Vec::new(),
+        });
+        return Ok(());
+    }
+    let tags = reader.str()?;
+    let tags = (tags.strip_prefix(',')).ok_or_else(|| format!("Invalid OSC type tags {tags:?}"))?;
+    let mut args = Vec::with_capacity(tags.len());
+    for tag in tags.chars() {
+        args.push(match tag {
+            'i' => OscArg::Int(reader.int()?),
+            'f' => OscArg::Float(f32::from_be_bytes(reader.array()?)),
+            'h' => OscArg::Long(i64::from_be_bytes(reader.array()?)),
+            'd' => OscArg::Double(f64::from_be_bytes(reader.array()?)),
+            's' | 'S' => OscArg::Str(reader.str()?),
+            'b' => {
+                let len = reader.int()?;
+                let len = usize::try_from(len).map_err(|_| "Invalid OSC blob size")?;
+                let blob = reader.take(len)?.to_vec();
+                reader.pos = reader.pos.next_multiple_of(4);
+                OscArg::Blob(blob)
+            }
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'N' | 'I' => OscArg::Nil,
+            tag => return Err(format!("Unsupported OSC type tag {tag:?}")),
+        });
+    }
+    messages.push(OscMessage { address, args });
+    Ok(())
+}
+
+/// Push a null-terminated string padded to a multiple of 4 bytes
+fn push_str(bytes: &mut Vec<u8>, s: &str) {
+    bytes.extend(s.as_bytes());
+    bytes.push(0);
+    pad(bytes);
+}
+
+fn pad(bytes: &mut Vec<u8>) {
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = (self.pos.checked_add(n))
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or("OSC packet ended unexpectedly")?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+    fn int(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+    fn str(&mut self) -> Result<String, String> {
+        let rest = &self.bytes[self.pos.min(self.bytes.len())..];
+        let len = (rest.iter().position(|&b| b == 0)).ok_or("Unterminated OSC string")?;
+        let s = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos = (self.pos + len + 1).next_multiple_of(4);
+        Ok(s)
+    }
+}
+
+#[test]
+fn osc_round_trip() {
+    let message = OscMessage {
+        address: "/synth/note".into(),
+        args: vec![
+            OscArg::Int(60),
+            OscArg::Float(0.5),
+            OscArg::Str("piano".into()),
+            OscArg::Blob(vec![1, 2, 3]),
+            OscArg::Bool(true),
+        ],
+    };
+    let packet = encode(&message);
+    assert_eq!(packet.len() % 4, 0);
+    assert_eq!(decode(&packet).unwrap(), [message.clone()]);
+    // Bundles are flattened
+    let mut bundle = b"#bundle\0".to_vec();
+    bundle.extend(1u64.to_be_bytes());
+    for _ in 0..2 {
+        bundle.extend((packet.len() as i32).to_be_bytes());
+        bundle.extend(&packet);
+    }
+    assert_eq!(decode(&bundle).unwrap(), [message.clone(), message]);
+}
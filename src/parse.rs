@@ -1,6 +1,6 @@
 //! The Uiua parser
 
-use std::{error::Error, f64::consts::PI, fmt, mem::replace};
+use std::{collections::HashMap, error::Error, f64::consts::PI, fmt, mem::replace};
 
 use ecow::EcoString;
 
@@ -8,7 +8,7 @@ use crate::{
     ast::*,
     function::{FunctionId, Signature},
     lex::{AsciiToken::*, Token::*, *},
-    Diagnostic, DiagnosticKind, Ident, Inputs, Primitive,
+    Diagnostic, DiagnosticKind, Ident, Inputs, Primitive, PrimitiveAlias,
 };
 
 /// An error that occurred while parsing
@@ -117,7 +117,17 @@ pub fn parse(
     src: impl IntoInputSrc,
     inputs: &mut Inputs,
 ) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
-    let (tokens, lex_errors, src) = lex(input, src, inputs);
+    parse_with_aliases(input, src, inputs, &HashMap::new())
+}
+
+/// Parse Uiua code into an AST, resolving the given names to primitives
+pub(crate) fn parse_with_aliases(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &mut Inputs,
+    aliases: &HashMap<Ident, PrimitiveAlias>,
+) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
+    let (tokens, lex_errors, src) = lex_with_aliases(input, src, inputs, aliases);
     fn parse(
         input: &str,
         inputs: &mut Inputs,
@@ -300,8 +310,12 @@ impl<'i> Parser<'i> {
         self.try_spaces();
         Some(if let Some(binding) = self.try_binding() {
             Item::Binding(binding)
+        } else if let Some(destructure) = self.try_destructure() {
+            Item::Destructure(destructure)
         } else if let Some(import) = self.try_import() {
             Item::Import(import)
+        } else if let Some(data) = self.try_data_def() {
+            Item::Data(data)
         } else {
             let lines = self.multiline_words(true);
             // Convert multiline words into multiple items
@@ -337,6 +351,13 @@ impl<'i> Parser<'i> {
     fn try_binding_init(&mut self) -> Option<(Sp<Ident>, CodeSpan, bool, bool)> {
         let start = self.index;
         let name = self.try_ident()?;
+        let Some((arrow_span, public, array_macro)) = self.try_binding_arrow() else {
+            self.index = start;
+            return None;
+        };
+        Some((name, arrow_span, public, array_macro))
+    }
+    fn try_binding_arrow(&mut self) -> Option<(CodeSpan, bool, bool)> {
         // Left arrow
         let arrow_span = self.try_spaces().map(|w| w.span);
         let (glyph_span, public) = if let Some(span) = self
@@ -351,7 +372,6 @@ impl<'i> Parser<'i> {
         {
             (span, false)
         } else {
-            self.index = start;
             return None;
         };
         let mut arrow_span = if let Some(arrow_span) = arrow_span {
@@ -368,7 +388,7 @@ impl<'i> Parser<'i> {
         if let Some(span) = self.try_spaces().map(|w| w.span) {
             arrow_span = arrow_span.merge(span);
         }
-        Some((name, arrow_span, public, array_macro))
+        Some((arrow_span, public, array_macro))
     }
     fn try_import_init(&mut self) -> Option<(Option<Sp<Ident>>, CodeSpan, Sp<String>)> {
         let start = self.index;
@@ -418,6 +438,50 @@ impl<'i> Parser<'i> {
             signature,
         })
     }
+    #[allow(clippy::type_complexity)]
+    fn try_destructure_init(&mut self) -> Option<(Vec<Sp<Ident>>, bool, CodeSpan, CodeSpan, bool)> {
+        let start = self.index;
+        let (open_span, boxes) = if let Some(span) = self.try_exact(OpenBracket.into()) {
+            (span, false)
+        } else {
+            (self.try_exact(OpenCurly.into())?, true)
+        };
+        // Names
+        let mut names = Vec::new();
+        self.try_spaces();
+        while let Some(name) = self.try_ident() {
+            names.push(name);
+            self.try_spaces();
+        }
+        let close = if boxes { CloseCurly } else { CloseBracket };
+        let close_span = self.try_exact(close.into());
+        // Left arrow
+        let arrow = self.try_binding_arrow();
+        let (Some(close_span), Some((arrow_span, public, false)), false) =
+            (close_span, arrow, names.is_empty())
+        else {
+            self.index = start;
+            return None;
+        };
+        let pattern_span = open_span.merge(close_span);
+        Some((names, boxes, pattern_span, arrow_span, public))
+    }
+    fn try_destructure(&mut self) -> Option<Destructure> {
+        let (names, boxes, pattern_span, arrow_span, public) = self.try_destructure_init()?;
+        // Words
+        let words = self.try_words().unwrap_or_default();
+        for name in &names {
+            self.validate_binding_name(name);
+        }
+        Some(Destructure {
+            names,
+            boxes,
+            pattern_span,
+            arrow_span,
+            public,
+            words,
+        })
+    }
     fn validate_binding_name(&mut self, name: &Sp<Ident>) {
         if name.value.contains('&') {
             self.errors
@@ -503,6 +567,62 @@ impl<'i> Parser<'i> {
             lines,
         })
     }
+    fn try_data_def(&mut self) -> Option<DataDef> {
+        let start = self.index;
+        let tilde_span = self.try_exact(Tilde.into())?;
+        self.try_spaces();
+        let Some(name) = self.try_ident() else {
+            self.index = start;
+            return None;
+        };
+        self.validate_binding_name(&name);
+        let mut variants = Vec::new();
+        loop {
+            // Variants may continue on the following lines
+            let checkpoint = self.index;
+            self.try_spaces();
+            while self.try_exact(Newline).is_some() {
+                self.try_spaces();
+            }
+            let Some(bar_span) = self.try_exact(Bar.into()) else {
+                self.index = checkpoint;
+                break;
+            };
+            self.try_spaces();
+            let Some(name) = self.try_ident() else {
+                self.errors.push(self.expected([Expectation::ItemName]));
+                break;
+            };
+            self.validate_binding_name(&name);
+            self.try_spaces();
+            let mut fields = Vec::new();
+            let mut fields_span = None;
+            if let Some(open_span) = self.try_exact(OpenCurly.into()) {
+                self.try_spaces();
+                while let Some(field) = self.try_ident() {
+                    self.validate_binding_name(&field);
+                    fields.push(field);
+                    self.try_spaces();
+                }
+                let close_span = self.expect_close(CloseCurly.into()).span;
+                fields_span = Some(open_span.merge(close_span));
+            }
+            variants.push(DataVariant {
+                bar_span,
+                name,
+                fields,
+                fields_span,
+            });
+        }
+        if variants.is_empty() {
+            self.errors.push(self.expected([Bar]));
+        }
+        Some(DataDef {
+            tilde_span,
+            name,
+            variants,
+        })
+    }
     fn try_ident(&mut self) -> Option<Sp<Ident>> {
         let span = self.try_exact(Token::Ident)?;
         let s: Ident = canonicalize_exclams(&self.input[span.byte_range()]);
@@ -649,7 +769,9 @@ impl<'i> Parser<'i> {
         loop {
             let curr = self.index;
             if check_for_bindings
-                && (self.try_binding_init().is_some() || self.try_import_init().is_some())
+                && (self.try_binding_init().is_some()
+                    || self.try_destructure_init().is_some()
+                    || self.try_import_init().is_some())
             {
                 self.index = curr;
                 break;
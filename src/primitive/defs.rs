@@ -1141,6 +1141,35 @@ primitive!(
     /// ex: ↘ 5 ↯3_3⇡9
     /// ex: ↘ ¯5 ↯3_3⇡9
     (2, Drop, DyadicArray, ("drop", '↘')),
+    /// Take the first n elements of an array, filling with the given value
+    ///
+    /// The first argument is the fill value. Taking more than the length of the array pads it with that value.
+    /// ex: # Experimental!
+    ///   : padtake 0 5 [1 2 3]
+    /// ex: # Experimental!
+    ///   : padtake @- ¯5 "abc"
+    /// ex: # Experimental!
+    ///   : padtake 0 3_3 [1_2 3_4]
+    /// This is like [fill][take], but it neither uses nor changes the [fill] value set outside of it.
+    /// This makes it useful in functions that need to pad arrays regardless of how they are called.
+    /// ex: # Experimental!
+    ///   : ⬚1(padtake 0 4) [1 2]
+    (3, FillTake, DyadicArray, "padtake"),
+    /// Drop the first n elements of an array, filling the rest with the given value
+    ///
+    /// The first argument is the fill value. Unlike [drop], the result keeps the [shape] of the original array.
+    /// The dropped rows are replaced by fill values at the other end.
+    /// ex: # Experimental!
+    ///   : paddrop 0 2 [1 2 3 4 5]
+    /// ex: # Experimental!
+    ///   : paddrop 0 ¯2 [1 2 3 4 5]
+    /// Dropping more than the length of the array leaves only fill values.
+    /// ex: # Experimental!
+    ///   : paddrop 0 7 [1 2 3]
+    /// ex: # Experimental!
+    ///   : paddrop 0 1_¯1 ↯3_3⇡9
+    /// As with [padtake], the [fill] value set outside of it is neither used nor changed.
+    (3, FillDrop, DyadicArray, "paddrop"),
     /// Rotate the elements of an array by n
     ///
     /// ex: ↻1 ⇡5
@@ -1174,9 +1203,26 @@ primitive!(
     /// This can be useful when you want to get horizontal windows.
     /// ex: ◫¯1_2 ↯4_4⇡16
     ///
+    /// A stride can be given along with the window size to skip windows. The size and stride can be a boxed pair.
+    /// ex: ◫{3 2} ⇡9
+    /// ex: ◫{2 2} ⇡9
+    /// For multi-dimensional windows, the size and stride can be the rows of a rank-2 array.
+    /// ex: ◫[2_2 2_2] ↯4_4⇡16
+    /// A third item or row gives a dilation, which skips elements within each window. This is useful for convolutions.
+    /// ex: ◫{3 1 2} ⇡9
+    /// ex: ◫[2_2 1_1 2_2] ↯4_4⇡16
+    /// Only the strided windows are ever created.
+    ///
     /// Usually, [windows] "materialzes" the windows. This means that the windows are copied into a new array. While this is very general, it can be slow and wasteful if you end up immediately reducing the windows.
     /// For this reason, the pattern `rows``reduce``F``windows` is optimized for scalar window sizes to [reduce] windows as they are generated.
     /// ex: ≡/+◫ 5 [1 8 2 9 3 0 2 4 4 5 1] # Fast!
+    ///
+    /// [un][windows] reconstructs an array from its windows. This only works for scalar window sizes.
+    /// ex: °(◫3) [1_2_3 2_3_4 3_4_5]
+    /// The windows must overlap consistently.
+    /// ex! °(◫3) [1_2_3 2_9_4 3_4_5]
+    /// [under][windows] modifies the windows and puts them back together. Only the first window and the last row of each other window are used.
+    /// ex: ⍜◫≡(×10) 2 [1 2 3 4]
     (2, Windows, DyadicArray, ("windows", '◫')),
     /// Discard or copy some rows of an array
     ///
@@ -1393,6 +1439,10 @@ primitive!(
     /// [scan]ning with [add] and then using [group] can split by a delimiter while keeping the delimiter.
     /// ex: ⊕□\+=@    . "Everyday man's on the block"
     ///   : ⊕□\+↻¯1=@ . "Everyday man's on the block"
+    ///
+    /// [un][scan] is supported for some functions. [un][scan][add] gets the differences between adjacent rows.
+    /// ex: °\+ [1 3 6 10]
+    /// ex: °\- \- [3 1 4 1 5]
     (1[1], Scan, AggregatingModifier, ("scan", '\\')),
     /// Apply a function to each element of an array or arrays
     ///
@@ -1472,6 +1522,25 @@ primitive!(
     /// ex: {⍜ {⊙⊙∘}⍚⊂    1_2 3_4_5 6_7_8_9 10}
     ///   : {⍜⊙{⊙⊙∘}⍚⊂ 10 1_2 3_4_5 6_7_8_9   }
     ([1], Inventory, IteratingModifier, ("inventory", '⍚')),
+    /// Apply a function to each list along an axis of an array
+    ///
+    /// The function is called on every 1-dimensional slice of the array along the given axis.
+    /// ex: # Experimental!
+    ///   : along⇌ 1 [1_2_3 4_5_6]
+    /// ex: # Experimental!
+    ///   : along⇌ 0 [1_2_3 4_5_6]
+    /// Negative axes count from the end.
+    /// ex: # Experimental!
+    ///   : along(/+) ¯1 [1_2_3 4_5_6]
+    /// If the function returns a scalar, the axis is removed.
+    /// ex: # Experimental!
+    ///   : along(/+) 1 ↯2_3_4⇡24
+    /// If it returns a list, the list's axis replaces the original axis.
+    /// ex: # Experimental!
+    ///   : along(↙2⊏⍖.) 1 [3_1_4 1_5_9 2_6_5]
+    ///
+    /// This is equivalent to moving the axis to the end with [transpose], applying the function with [rows] at the right depth, and moving the axis back.
+    (2[1], Along, IteratingModifier, "along"),
     /// Repeat a function a number of times
     ///
     /// ex: ⍥(+2)5 0
@@ -1520,6 +1589,29 @@ primitive!(
     ///
     /// [group] is closely related to [partition].
     (2[1], Group, AggregatingModifier, ("group", '⊕')),
+    /// Reduce each group of rows with a function
+    ///
+    /// This gives the same result as [group][reduce], but it reduces the rows as it goes instead of building each group first.
+    /// ex: # Experimental!
+    ///   : reducegroups+ [0 1 0 2 1] [1 2 3 4 5]
+    /// ex: # Experimental!
+    ///   : reducegroups↥ [0 1 0 2 1] [1 2 3 4 5]
+    /// Rows can be arrays.
+    /// ex: # Experimental!
+    ///   : reducegroups↧ [1 0 1] [1_5 2_3 0_9]
+    /// [add], [maximum], and [minimum] are especially fast on numbers. Other dyadic functions can be used as well.
+    /// ex: # Experimental!
+    ///   : reducegroups⊂ [0 1 0 1] "abcd"
+    /// If the function is [length], the rows in each group are counted.
+    /// ex: # Experimental!
+    ///   : reducegroups⧻ [0 2 0 2 2] "abcde"
+    ///
+    /// Empty groups are given the function's identity if it has one, or the [fill] value otherwise.
+    /// ex: # Experimental!
+    ///   : reducegroups+ [0 2] [1 2]
+    /// ex: # Experimental!
+    ///   : ⬚0groupreduce(+×2) [0 2] [1 2]
+    (2[1], GroupReduce, AggregatingModifier, "reducegroups"),
     /// Group sequential sections of an array
     ///
     /// The most common use of [partition] is to split an array by a delimiter.
@@ -1765,7 +1857,39 @@ primitive!(
     ///   : ◌⍜F(×10) 5
     ///
     /// Inverses set with [setund] cannot be used with [un]. For simpler inverse defining, see [setinv].
+    /// To define both kinds of inverses at once, see [obverse].
     ([3], SetUnder, InversionModifier, "setund"),
+    /// Define a function along with its inverses
+    ///
+    /// [obverse] takes a function pack of 2 to 4 functions. The first function is always the normal function, which is called when the function is used outside of [un] or [under].
+    ///
+    /// With 2 functions, the second function is the inverse. It is used by both [un] and [under].
+    /// ex: # Experimental!
+    ///   : F ← obverse(+1|-1)
+    ///   : F 5
+    ///   : °F 5
+    ///   : ⍜F(×10) 5
+    /// With 3 functions, the second and third functions are the "do" and "undo" parts of an [under], the same as with [setund].
+    /// Any extra outputs of the "do" function are saved as context for the "undo" function.
+    /// ex: # Experimental!
+    ///   : F ← obverse(+|⟜+|-)
+    ///   : ⍜F(×10) 1 2
+    /// If the "do" function has the same signature as the normal function, the "undo" function will also be used by [un].
+    /// ex: # Experimental!
+    ///   : Enc ← obverse(utf|utf|°utf)
+    ///   : °Enc [72 105]
+    /// With 4 functions, the second function is the [un]-inverse, and the third and fourth functions are the [under] parts.
+    /// Here, [under] restores the original value instead of un-adding.
+    /// ex: # Experimental!
+    ///   : F ← obverse(+1|-1|⟜(+1)|⊙◌)
+    ///   : °F 5
+    ///   : ⍜F(×10) 5
+    ///
+    /// This allows bindings that transform data to be used with [un] and [under], just like built-in functions.
+    /// ex: # Experimental!
+    ///   : Cents ← obverse(×100|÷100)
+    ///   : ⍜Cents⁅ 1.2345
+    ([1], Obverse, InversionModifier, "obverse"),
     /// Operate on a transformed array, then reverse the transformation
     ///
     /// This is a more powerful version of [un].
@@ -1893,6 +2017,43 @@ primitive!(
     ///   : c ← (°□⊡2°◌)
     ///   : ⬚{⊙⊙∘}(×b+c×a a) 2 3 4
     ([2], Fill, OtherModifier, ("fill", '⬚')),
+    /// Set a function that computes fill values from their positions
+    ///
+    /// Unlike [fill], which sets a single fill value, [lazyfill]'s first function is only called when an element is missing.
+    /// It is called with the index of the missing row or element in the original array, and it should return the value to put there.
+    /// ex: # Experimental!
+    ///   : lazyfill(×10⊢)↙6 [1 2 3]
+    /// ex: # Experimental!
+    ///   : lazyfill(⋅0)↙¯5 [1 2 3]
+    /// If the function takes 2 arguments, it is also passed the array being filled.
+    /// This allows padding that depends on the array, like clamping to the edge.
+    /// ex: # Experimental!
+    ///   : lazyfill(⊡⊃(↥0↧⊙(-1⧻)|⋅∘))◫3 [1 2 3 4]
+    /// If the array has more than one axis, the function can return either a row or a scalar, which is repeated.
+    /// ex: # Experimental!
+    ///   : lazyfill(⊢)↙4 [1_2 3_4]
+    ///
+    /// [lazyfill] is currently only used by [take] and [windows]. Other functions see no fill value.
+    /// The function is called with the fill value removed.
+    ([2], LazyFill, OtherModifier, "lazyfill"),
+    /// Call a function with pervasive functions broadcasting length-1 axes
+    ///
+    /// Normally, pervasive functions require that the [shape] of one array is a prefix of the other's.
+    /// Inside [broadcast], an axis of length 1 is stretched to match the corresponding axis of the other array.
+    /// ex: # Experimental!
+    ///   : broadcast+ [1_2_3] [10 20]
+    /// ex: # Experimental!
+    ///   : broadcast× [1 2 3] [4_5_6]
+    /// This is similar to broadcasting in NumPy, but as with other pervasive operations, axes are matched from the front.
+    /// ex: # Experimental!
+    ///   : broadcast+ ↯2_1_3 0 ↯1_4_3 1
+    /// Arrays whose shapes already agree are not affected.
+    /// ex: # Experimental!
+    ///   : broadcast- 1 [1_2 3_4]
+    /// Length-1 axes are only stretched if no other axes disagree.
+    /// ex! # Experimental!
+    ///   : broadcast+ [1_2_3] [4_5 6_7]
+    ([1], Broadcast, OtherModifier, "broadcast"),
     /// Call a function and catch errors
     ///
     /// If the first function errors, the second function is called with the original arguments and the error value.
@@ -2110,6 +2271,57 @@ primitive!(
     /// ex: deal⚂ [1 2 3 4 5]
     /// ex: deal⚂ [1_2 3_4 5_6 7_8]
     (2, Deal, Misc, "deal", Impure),
+    /// Randomly reorder the rows of an array
+    ///
+    /// ex: # Experimental!
+    ///   : shuffle [1 2 3 4 5]
+    /// ex: # Experimental!
+    ///   : shuffle [1_2 3_4 5_6 7_8]
+    /// Every ordering is equally likely.
+    (1, Shuffle, Misc, "shuffle", Impure),
+    /// Randomly pick some rows of an array without replacement
+    ///
+    /// The first argument is the number of rows to pick.
+    /// ex: # Experimental!
+    ///   : sample 3 [1 2 3 4 5 6 7 8]
+    /// ex: # Experimental!
+    ///   : sample 2 [1_2 3_4 5_6 7_8]
+    /// No row is picked more than once, so the number of rows must not be greater than the [length] of the array.
+    /// ex! # Experimental!
+    ///   : sample 5 [1 2 3]
+    /// To pick rows with replacement, use [choose] with equal weights.
+    /// ex: # Experimental!
+    ///   : ⊏ choose 5 ↯:1⧻. [1 2 3]
+    (2, Sample, Misc, "sample", Impure),
+    /// Randomly pick indices according to a list of weights
+    ///
+    /// The first argument is the number of indices to pick. The second is a list of weights, one for each index.
+    /// Indices are picked with replacement, and each index is picked with a probability proportional to its weight.
+    /// ex: # Experimental!
+    ///   : choose 10 [0.7 0.2 0.1]
+    /// The weights do not need to sum to `1`.
+    /// ex: # Experimental!
+    ///   : ⊕⧻. choose 1000 [1 2 5]
+    /// Use [select] to pick rows of an array with the indices.
+    /// ex: # Experimental!
+    ///   : ⊏:"abc" choose 8 [3 1 1]
+    (2, Choose, Misc, "choose", Impure),
+    /// Generate a random version 4 UUID
+    ///
+    /// The result is a string in the standard hyphenated form.
+    /// ex: # Experimental!
+    ///   : uuid
+    /// Unlike [random], [uuid] uses the operating system's secure source of randomness, so it is not affected by seeding.
+    (0, Uuid, Misc, "uuid", Impure),
+    /// Generate cryptographically secure random bytes
+    ///
+    /// The argument is the number of bytes to generate.
+    /// ex: # Experimental!
+    ///   : entropy 16
+    /// Unlike [random], [entropy] uses the operating system's secure source of randomness, so it is suitable for generating tokens and keys.
+    /// ex: # Experimental!
+    ///   : ⊏:"0123456789abcdef" ♭⍉[⊃(⌊÷16|◿16)] entropy 8
+    (1, Entropy, Misc, "entropy", Impure),
     /// Match a regex pattern
     ///
     /// Returns a rank-2 array of [box]ed strings, with one string per matching group and one row per match
@@ -2163,6 +2375,220 @@ primitive!(
     /// [under][now] can be used to time a function.
     /// ex: ⍜now(5&sl1)
     (0, Now, Misc, "now", Impure),
+    /// Get the name of the way an array's elements are stored
+    ///
+    /// Numbers may be stored as 64-bit floats (`"num"`), as 32-bit floats (`"float"`), as unsigned bytes (`"byte"`), as 64-bit integers (`"int"`), as 64-bit integers with wrapping arithmetic (`"wrapping"`), or as complex numbers (`"complex"`).
+    /// Characters and boxes are stored as `"char"` and `"box"`.
+    /// ex: # Experimental!
+    ///   : storage [1 2 3]
+    /// ex: # Experimental!
+    ///   : storage [1.5 2 3]
+    /// ex: # Experimental!
+    ///   : storage ℂ1 2
+    /// ex: # Experimental!
+    ///   : storage "hi"
+    /// Uiua decides how to store numbers automatically. [store] overrides this.
+    (1, Storage, Misc, "storage"),
+    /// Change the way an array of numbers is stored
+    ///
+    /// The first argument is the name of the storage type, one of `"byte"`, `"int"`, `"wrapping"`, `"float"`, `"num"`, or `"complex"`.
+    /// ex: # Experimental!
+    ///   : storage store "byte" [1 2 3]
+    /// ex: # Experimental!
+    ///   : storage store "int" [1 2 3]
+    /// ex: # Experimental!
+    ///   : storage store "num" [1 2 3]
+    /// ex: # Experimental!
+    ///   : storage store "complex" [1 2 3]
+    /// An error is thrown if the numbers cannot be stored without losing information.
+    /// ex! # Experimental!
+    ///   : store "byte" [1 2 300]
+    /// ex! # Experimental!
+    ///   : store "byte" [1 2.5]
+    /// Uiua normally chooses the storage type of constants automatically, storing small natural numbers as bytes.
+    /// [store]d arrays keep their storage type until an operation produces a new array.
+    /// ex: # Experimental!
+    ///   : X ← store "num" [1 2 3]
+    ///   : storage X
+    /// `"int"` arrays do exact arithmetic on integers too large to be represented exactly as floats.
+    /// Numbers combined with them are converted to integers if they are all whole.
+    /// ex: # Experimental!
+    ///   : +1 store "int" 9007199254740992
+    ///   : +1 9007199254740992
+    /// Integer [add], [subtract], and [multiply] throw an error on overflow.
+    /// ex! # Experimental!
+    ///   : ×10 store "int" 1000000000000000000
+    /// [modulo] by 0 gives NaN like it does for `"num"` arrays, so the result is a `"num"` array.
+    /// ex: # Experimental!
+    ///   : ◿0 store "int" [1 2 3]
+    /// `"wrapping"` arrays are `"int"` arrays whose arithmetic wraps around on overflow instead.
+    /// ex: # Experimental!
+    ///   : ×10 store "wrapping" 1000000000000000000
+    /// `"float"` arrays use half the memory of `"num"` arrays. Numbers are rounded to the nearest 32-bit float, and math on them stays in 32-bit floats.
+    /// ex: # Experimental!
+    ///   : storage ×2 store "float" [1 2 3]
+    /// ex: # Experimental!
+    ///   : store "num" store "float" 0.1
+    /// Combining `"float"` and `"int"` arrays gives a `"num"` array, so large integers are not rounded.
+    /// ex: # Experimental!
+    ///   : + store "float" 0 store "int" 123456789
+    /// Numbers too large for a 32-bit float cannot be stored as one.
+    /// ex! # Experimental!
+    ///   : store "float" 1e100
+    (2, Store, Misc, "store"),
+    /// Get the approximate memory used by the interpreter's values
+    ///
+    /// The result is a [map] with these keys:
+    /// - `"stack"` - the bytes used by values on the stack
+    /// - `"temp"` - the bytes used by values on the temporary stacks, such as those of [dip]
+    /// - `"constants"` - the bytes used by bound constants
+    /// - `"total"` - the sum of the above
+    /// - `"values"` - a list of the bytes used by each value on the stack, starting from the top
+    /// ex: # Experimental!
+    ///   : memory ⇡100 "hello"
+    /// Use [get] to get a specific entry.
+    /// ex: # Experimental!
+    ///   : °□get "values" memory ⇡100 "hello"
+    /// The sizes are approximate. Values that share data are each counted in full.
+    (0, Memory, Misc, "memory", Impure),
+    /// Get a hash of a value
+    ///
+    /// The result is a 64-bit hash as a string of 16 hexadecimal digits.
+    /// ex: # Experimental!
+    ///   : hash [1 2 3]
+    /// Values that [match] have the same hash, regardless of how their numbers are stored.
+    /// ex: # Experimental!
+    ///   : ≍∩hash [1 2 3] store "num" [1 2 3]
+    /// Values that do not [match] almost always have different hashes.
+    /// ex: # Experimental!
+    ///   : ≍∩hash [1 2 3] [1 2 4]
+    /// The hash of a value is the same on every platform and across runs, so it can be used for caches, deduplication keys, and content addressing.
+    /// However, hashes are only guaranteed to be stable within a single version of Uiua. They may change between versions.
+    /// [hash] is not cryptographically secure.
+    (1, Hash, Misc, "hash"),
+    /// Embed the contents of a file
+    ///
+    /// The argument is a path to a file. The result is the file's bytes.
+    /// If the path is a constant, the file is read when the code is compiled and its contents become a constant in the compiled program.
+    /// This lets a program carry images, data tables, and other assets without needing them to be present when it is run.
+    /// ex: # Experimental!
+    ///   : embed "example.txt"
+    ///   : °utf .
+    /// Relative paths are relative to the file that contains the code, just like `~` imports.
+    /// If the path is not a constant, the file is read when [embed] is called, much like [&frab].
+    ///
+    /// The compiler records a hash of each embedded file. A compiled assembly can be checked for embedded files that have changed since it was built.
+    (1, Embed, Misc, "embed", Impure),
+    /// Get the label of a value
+    ///
+    /// Values can be given a label with `$` followed by a name.
+    /// [label] returns the label as a string, or an empty string if the value has no label.
+    /// ex: # Experimental!
+    ///   : label $foo [1 2 3]
+    /// ex: # Experimental!
+    ///   : label [1 2 3]
+    /// Labels are kept by pervasive operations. The first argument's label takes precedence.
+    /// ex: # Experimental!
+    ///   : label + $a 1 $b 2
+    /// [couple] combines different labels.
+    /// ex: # Experimental!
+    ///   : ⊟ $x [1 2] $y [3 4]
+    ///
+    /// Use [relabel] to set a label.
+    (1, Label, Misc, "label"),
+    /// Set the label of a value
+    ///
+    /// The first argument is the new label as a string. An empty string removes the label.
+    /// ex: # Experimental!
+    ///   : relabel "foo" [1 2 3]
+    /// ex: # Experimental!
+    ///   : relabel "" $foo [1 2 3]
+    /// Unlike `$`, the label does not need to be known when the code is written.
+    /// ex: # Experimental!
+    ///   : relabel $"item_" 3 ⇡3
+    ///
+    /// Use [label] to get a label.
+    (2, Relabel, Misc, "relabel"),
+    /// Describe the differences between two values
+    ///
+    /// The result is a string with a line for each difference. It is empty if the values [match].
+    /// ex: # Experimental!
+    ///   : showdiff 5 8
+    /// Rows are aligned so that inserted and removed rows are shown as such.
+    /// `-` marks a row only in the first argument, `+` a row only in the second, and `~` a row that changed.
+    /// Each line starts with the index of the row.
+    /// ex: # Experimental!
+    ///   : showdiff [1 2 3 4] [1 3 4 5]
+    /// ex: # Experimental!
+    ///   : showdiff ["cat" "dog" "emu"] ["cat" "dig" "emu" "yak"]
+    /// Changed numbers also show how much they changed by.
+    /// ex: # Experimental!
+    ///   : showdiff [1_2 3_4 5_6] [1_2 3_5 5_6]
+    /// Values whose rows cannot be compared are shown whole.
+    /// ex: # Experimental!
+    ///   : showdiff [1 2 3] [1_2 3_4]
+    /// [showdiff] is useful for making failing tests easier to understand.
+    /// ex! # Experimental!
+    ///   : ⍤⊃showdiff≍ [1 2 3] [1 5 3]
+    (2, Diff, Misc, "showdiff"),
+    /// Check that a value matches a schema
+    ///
+    /// The first argument is the schema. The second is the value to check.
+    /// The result is a table of errors with a row for each problem found. Each row has a path to the part of the value that is wrong and a message describing what is wrong.
+    /// If the value matches the schema, the table is empty.
+    ///
+    /// The simplest schema is the name of a type.
+    /// ex: # Experimental!
+    ///   : validate "natural" [1 2 3]
+    /// ex: # Experimental!
+    ///   : validate "natural" [1 ¯2 3]
+    /// The types are `"any"`, `"number"`, `"integer"`, `"natural"`, `"boolean"`, `"complex"`, `"char"`, `"string"`, `"box"`, and `"map"`.
+    ///
+    /// A schema can also be a [map] with any of these keys:
+    /// - `"type"` - the name of a type, as above
+    /// - `"shape"` - a list of the lengths of each axis, where a negative number allows any length
+    /// - `"rank"` - the number of axes
+    /// - `"keys"` - a [map] from keys that the value, which must be a [map], must have to schemas for their values
+    /// - `"optional"` - a list of the keys in `"keys"` that may be missing
+    /// - `"rows"` - a schema that every row of the value must match
+    /// ex: # Experimental!
+    ///   : validate map {"type" "shape"} {"number" [¯1 2]} [1_2 3_4 5_6]
+    /// ex: # Experimental!
+    ///   : validate map {"type" "shape"} {"number" [¯1 2]} [1_2_3 4_5_6]
+    ///
+    /// Scalar boxes are unboxed before they are checked, so schemas and values decoded from [json] can be used directly.
+    /// ex: # Experimental!
+    ///   : Schema ← °json $ {"keys": {"name": "string", "age": "natural", "tags": {"rows": "string"}},
+    ///   :                $  "optional": ["tags"]}
+    ///   : validate Schema °json $ {"name": "Sam", "age": 29}
+    ///   : validate Schema °json $ {"name": 5, "tags": ["a", 1]}
+    (2, Validate, Misc, "validate"),
+    /// Check whether IP addresses are in a CIDR network
+    ///
+    /// The first argument is a network in CIDR notation, like `"10.0.0.0/8"`.
+    /// The second argument is an address as a string or as a list of bytes like [un][ip] returns.
+    /// ex: # Experimental!
+    ///   : cidr "192.168.0.0/16" "192.168.4.20"
+    ///   : cidr "192.168.0.0/16" [10 0 0 1]
+    /// If the second argument is a list of addresses, the result is a mask.
+    /// ex: # Experimental!
+    ///   : cidr "10.0.0.0/8" {"10.1.2.3" "11.0.0.1" "::1"}
+    /// IPv6 networks work too. IPv4 addresses are never in IPv6 networks, and vice versa.
+    /// ex: # Experimental!
+    ///   : cidr "2001:db8::/32" {"2001:db8::1" "2001:db9::1"}
+    (2, Cidr, Misc, "cidr"),
+    /// Split a CIDR network into subnets
+    ///
+    /// The first argument is the prefix length of the subnets. The result is a list of boxed subnets in CIDR notation.
+    /// ex: # Experimental!
+    ///   : subnets 24 "10.0.0.0/22"
+    /// A prefix length of `32` for IPv4 or `128` for IPv6 lists every address in the network.
+    /// ex: # Experimental!
+    ///   : subnets 32 "192.168.1.0/30"
+    /// Host bits of the network's address are ignored.
+    /// ex: # Experimental!
+    ///   : subnets 26 "172.16.5.77/24"
+    (2, Subnets, Misc, "subnets"),
     /// The number of radians in a quarter circle
     ///
     /// Equivalent to `divide``2``pi` or `divide``4``tau`
@@ -2319,6 +2745,36 @@ primitive!(
     ///
     /// See also: [insert], [has], [get]
     (2, Remove, Map, "remove"),
+    /// Group the rows of an array into a map from each index to its group
+    ///
+    /// See [map] for an overview of map arrays.
+    ///
+    /// This is like [group][box], but each group is keyed by its index. Indices that do not appear do not make empty groups.
+    /// ex: # Experimental!
+    ///   : keyedgroup [3 0 3 1 0] "abcde"
+    /// Negative indices are ignored, as with [group]. The keys are sorted.
+    /// ex: # Experimental!
+    ///   : keyedgroup [5 ¯1 2 5] [1 2 3 4]
+    /// The result can be used with [get] without a parallel list of indices.
+    /// ex: # Experimental!
+    ///   : get 3 keyedgroup [3 0 3 1 0] "abcde"
+    ///
+    /// See also: [keyedpartition]
+    (2, GroupMap, Map, "keyedgroup"),
+    /// Partition the rows of an array into a map from each marker to its group
+    ///
+    /// See [map] for an overview of map arrays.
+    ///
+    /// This is like [partition][box], but each group is keyed by its marker. Markers less than `1` are ignored, as with [partition].
+    /// ex: # Experimental!
+    ///   : keyedpartition [1 1 0 2 2 2] [1 2 3 4 5 6]
+    /// Because map keys are unique, runs with the same marker are joined into a single group, even if they are not adjacent.
+    /// The keys are in the order in which their markers first appear.
+    /// ex: # Experimental!
+    ///   : keyedpartition [2 2 1 0 2] "hello"
+    ///
+    /// See also: [keyedgroup]
+    (2, PartitionMap, Map, "keyedpartition"),
     /// Debug print all stack values without popping them
     ///
     /// This is equivalent to [dump][identity], but is easier to type.
@@ -2403,6 +2859,53 @@ primitive!(
     /// At the moment, this is only useful for debugging.
     /// While theoretically, it could be used in a macro to choose a branch of a switch function appropriate for the function, this is not yet possible because of the way that macros and signature checking work.
     (0(2)[1], Sig, OtherModifier, "signature"),
+    /// Get information about a function
+    ///
+    /// The result is a map with the keys `"name"`, `"args"`, `"outputs"`, and `"span"`.
+    /// ex: # Experimental!
+    ///   : F ← +1
+    ///   : reflect F
+    /// `args` and `outputs` are the same as the numbers given by [signature].
+    /// ex: # Experimental!
+    ///   : get "args" reflect(⊃+-)
+    /// `name` is the name of the binding or primitive, if there is one. Otherwise, it is an empty string.
+    /// ex: # Experimental!
+    ///   : get "name" reflect⊟
+    /// ex: # Experimental!
+    ///   : get "name" reflect(+1)
+    /// `span` is the location where the function was defined.
+    ///
+    /// Because the result is a normal value, it can be used to validate functions passed to a macro.
+    /// ex: # Experimental!
+    ///   : Binary! ← ^! ⍤"Function must be dyadic" =2 °□get "args" reflect^!^.
+    ///   : Binary!+ 1 2
+    (0[1], Reflect, OtherModifier, "reflect"),
+    /// Call a function by name
+    ///
+    /// [dispatch] takes a string and calls the function with that name.
+    /// The operand can be a function pack of named functions.
+    /// ex: # Experimental!
+    ///   : Inc ← +1
+    ///   : Dbl ← ×2
+    ///   : dispatch(Inc|Dbl) "Dbl" 5
+    /// This makes it easy to build command tables.
+    /// ex: # Experimental!
+    ///   : Inc ← +1
+    ///   : Dbl ← ×2
+    ///   : ≡(dispatch(Inc|Dbl)°□)⊙¤ {"Inc" "Dbl" "Inc"} 5
+    /// If the name is not found, an error is thrown.
+    /// ex! # Experimental!
+    ///   : Inc ← +1
+    ///   : Dbl ← ×2
+    ///   : dispatch(Inc|Dbl) "Sqr" 5
+    ///
+    /// If the operand is a module, any public function in the module can be called.
+    /// ex: # Experimental!
+    ///   : Ex ~ "example"
+    ///   : dispatch Ex "Span" 3 6
+    ///
+    /// Like a switch function, the functions' signatures must be compatible.
+    ([1], Dispatch, OtherModifier, "dispatch"),
     /// Run the Fast Fourier Transform on an array
     ///
     /// The Fast Fourier Transform (FFT) is an optmized algorithm for computing the Discrete Fourier Transform (DFT). The DFT is a transformation that converts a signal from the time domain to the frequency domain.
@@ -2427,6 +2930,608 @@ primitive!(
     ///   : ⬚0↙ &asr °⊚       # Put 1 in buffer for each frequency
     ///   : ◌°ℂ °fft          # Run inverse FFT and get the real part
     (1, Fft, Misc, "fft"),
+    /// Render an audio synthesis graph
+    ///
+    /// The first argument is a node. The second argument is the number of seconds to render.
+    /// The result is a list of samples at the sample rate of [&asr].
+    /// ex: # Experimental!
+    ///   : synth {"sine" 220} 1
+    /// A node is a boxed list whose first item is the name of the node. The remaining items are its parameters.
+    /// Any parameter that is a node can also be a number.
+    /// - `{"sine" freq}`, `{"saw" freq}`, `{"square" freq}`, `{"triangle" freq}` - oscillators
+    /// - `{"noise"}` - white noise
+    /// - `{"adsr" attack decay sustain release length}` - an envelope that is held for `length` seconds and then released
+    /// - `{"seq" step values}` - cycle through a list of numbers, changing every `step` seconds
+    /// - `{"loop" period node}` - restart a node every `period` seconds
+    /// - `{"lowpass" node cutoff}`, `{"highpass" node cutoff}` - filters
+    /// - `{"delay" node time feedback mix}` - an echo. `feedback` and `mix` default to `0.5`.
+    /// - `{"+" nodes...}`, `{"×" nodes...}` - add or multiply nodes
+    ///
+    /// Nodes can be combined to make more interesting sounds.
+    /// ex: # Experimental!
+    ///   : synth {"lowpass" {"saw" 110} 800} 1
+    /// ex: # Experimental!
+    ///   : Note ← {"loop" 0.25 {"adsr" 0.01 0.05 0.5 0.1 0.1}}
+    ///   : synth {"×" 0.5 Note {"square" {"seq" 0.25 [220 277 330 440]}}} 2
+    /// Parameters can be modulated by other nodes.
+    /// ex: # Experimental!
+    ///   : synth {"×" 0.5 {"sine" {"+" 440 {"×" 20 {"sine" 6}}}}} 2
+    /// Use [&asyn] to stream a graph to the audio output instead.
+    (2, Synth, Misc, "synth", Impure),
+    /// Get the cross product of two arrays of vectors
+    ///
+    /// The vectors are along the last axis of each array. They must have length `3` or `2`.
+    /// ex: # Experimental!
+    ///   : cross [1 0 0] [0 1 0]
+    /// ex: # Experimental!
+    ///   : cross [1 2 3] [4 5 6]
+    /// The cross product of `2`-vectors is the `z` component of the cross product of the vectors extended to 3 dimensions.
+    /// ex: # Experimental!
+    ///   : cross [1 0] [1 1]
+    /// If one argument is a single vector, it is crossed with every vector in the other.
+    /// ex: # Experimental!
+    ///   : cross [0 0 1] [1_0_0 0_1_0 1_1_0]
+    (2, Cross, Misc, "cross"),
+    /// Get the norm of an array of vectors
+    ///
+    /// The vectors are along the last axis of the array.
+    /// The first argument is the order of the norm. Use `1` for the taxicab norm, `2` for the Euclidean norm, or `infinity` for the maximum norm.
+    /// ex: # Experimental!
+    ///   : norm 2 [3 4]
+    /// ex: # Experimental!
+    ///   : norm 1 [3 ¯4]
+    /// ex: # Experimental!
+    ///   : norm ∞ [3 ¯4]
+    /// ex: # Experimental!
+    ///   : norm 2 [3_4 5_12 1_1]
+    /// [norm] also works on [complex] numbers.
+    /// ex: # Experimental!
+    ///   : norm 2 [ℂ4 3 ℂ0 1]
+    (2, Norm, Misc, "norm"),
+    /// Scale an array of vectors to have a length of `1`
+    ///
+    /// The vectors are along the last axis of the array. Each vector is divided by its Euclidean [norm].
+    /// ex: # Experimental!
+    ///   : normalize [3 4]
+    /// ex: # Experimental!
+    ///   : normalize [1_1 0_5 ¯2_0]
+    /// Zero vectors are left unchanged.
+    /// ex: # Experimental!
+    ///   : normalize [0 0]
+    (1, Normalize, Misc, "normalize"),
+    /// Get the Euclidean distance between every pair of vectors in two arrays
+    ///
+    /// Both arguments must be single vectors or lists of vectors.
+    /// The result has a row for each vector in the first argument and a column for each vector in the second.
+    /// ex: # Experimental!
+    ///   : distances [0_0 3_4] [0_0 1_0 0_2]
+    /// This is the same as [table]ing a distance function, but it is much faster for large arrays.
+    /// ex: # Experimental!
+    ///   : ⊞(√/+ⁿ2-) [0_0 3_4] [0_0 1_0 0_2]
+    /// A single vector argument does not add an axis to the result.
+    /// ex: # Experimental!
+    ///   : distances [0 0] [0_0 1_0 0_2]
+    (2, Distances, Misc, "distances"),
+    /// Get the great-circle distance between geographic coordinates
+    ///
+    /// Coordinates are lists of a latitude and a longitude in degrees. The result is in meters.
+    /// ex: # Experimental!
+    ///   : haversine [51.5072 ¯0.1276] [48.8566 2.3522] # London to Paris
+    /// If one argument is a single coordinate, the distance to every coordinate in the other is found.
+    /// ex: # Experimental!
+    ///   : ⁅÷1000 haversine [0 0] [0_1 1_0 0_180]
+    /// The Earth is treated as a sphere, so distances can be off by up to about 0.5%.
+    (2, Haversine, Misc, "haversine"),
+    /// Get the initial bearing from one geographic coordinate to another
+    ///
+    /// Coordinates are lists of a latitude and a longitude in degrees. The result is in degrees clockwise from north.
+    /// ex: # Experimental!
+    ///   : bearing [51.5072 ¯0.1276] [48.8566 2.3522] # London to Paris
+    /// Like [haversine], a single coordinate is paired with every coordinate in the other argument.
+    /// ex: # Experimental!
+    ///   : bearing [0 0] [1_0 0_1 ¯1_0 0_¯1]
+    (2, Bearing, Misc, "bearing"),
+    /// Project geographic coordinates with Web Mercator
+    ///
+    /// Coordinates are lists of a latitude and a longitude in degrees. The result is a list of an x and y position in meters, as used by most web maps.
+    /// ex: # Experimental!
+    ///   : mercator [51.5072 ¯0.1276]
+    /// [un][mercator] converts positions back to coordinates.
+    /// ex: # Experimental!
+    ///   : °mercator mercator [40.7128 ¯74.006]
+    (1, Mercator, Misc, "mercator"),
+    /// Check whether points are inside a polygon
+    ///
+    /// The first argument is a list of the polygon's vertices. The second argument is a point or an array of points.
+    /// ex: # Experimental!
+    ///   : inpolygon [0_0 0_4 4_4 4_0] [2 2]
+    ///   : inpolygon [0_0 0_4 4_4 4_0] [1_1 5_5 3_¯1]
+    /// Points can be geographic coordinates or positions in any other plane. Edges are straight lines between vertices.
+    (2, InPolygon, Misc, "inpolygon"),
+    /// Give numbers a unit
+    ///
+    /// The first argument is a unit string. The second argument is an array of numbers.
+    /// The resulting quantity is converted to SI base units.
+    /// ex: # Experimental!
+    ///   : unit "km/h" 90
+    /// Units propagate through pervasive arithmetic.
+    /// ex: # Experimental!
+    ///   : ÷ unit "s" 10 unit "m" 100
+    ///   : × unit "kg" 2 unit "m/s^2" 9.8
+    /// Adding, subtracting, or comparing quantities with different units is an error.
+    /// ex! # Experimental!
+    ///   : + unit "s" 1 unit "m" 1
+    /// [un] with a unit gets the numbers of a quantity in that unit.
+    /// ex: # Experimental!
+    ///   : °(unit "mi/h") unit "km/h" 100
+    /// Units can be SI base units, derived units like `N`, `J`, `W`, `V`, and `Pa`, or common units like `min`, `h`, `L`, `ft`, and `lb`. SI units can have prefixes from `p` to `T`.
+    /// Units are joined with `*`, `·`, or spaces, and are divided with `/`. Exponents are written with `^` or superscripts.
+    /// ex: # Experimental!
+    ///   : unit "kW·h" 1
+    ///   : unit "ft²" 1
+    (2, Unit, Misc, "unit"),
+    /// Create an interval array
+    ///
+    /// The last axis of the argument must hold pairs of lower and upper bounds.
+    /// ex: # Experimental!
+    ///   : interval [1 2]
+    /// A scalar becomes an interval with equal bounds.
+    /// ex: # Experimental!
+    ///   : interval 5
+    /// Pervasive functions treat each pair as a single number whose true value is somewhere between the bounds. The result contains every possible value.
+    /// ex: # Experimental!
+    ///   : + interval [1 2] interval [10 20]
+    ///   : × interval [¯1 2] interval [3 4]
+    ///   : ⁿ2 interval [¯3 2]
+    /// Bounds are rounded outward, so floating-point error can never make an interval too narrow.
+    /// ex: # Experimental!
+    ///   : + interval 0.1 interval 0.2
+    /// Comparisons are only true if they are true for every pair of values in the intervals.
+    /// ex: # Experimental!
+    ///   : < 3 interval [1 2]
+    ///   : < 2 interval [1 3]
+    /// [un][interval] gets the bounds as a normal array.
+    /// ex: # Experimental!
+    ///   : °interval ÷3 interval 1
+    (1, Interval, Misc, "interval"),
+    /// Integrate an ordinary differential equation
+    ///
+    /// Expects a function and 2 arrays.
+    /// The first array is the initial state. The second array is a list of times.
+    /// The function should return the derivative of the state. It is passed the state, and if it takes 2 arguments, the time below it.
+    /// The result has a row for the state at each time.
+    /// Here, we integrate exponential growth.
+    /// ex: # Experimental!
+    ///   : ode∘ 1 [0 1 2]
+    /// The state can be an array of any shape. Here, we simulate a simple harmonic oscillator whose state is a position and a velocity.
+    /// ex: # Experimental!
+    ///   : ⁅ ode(⊂⊃(⊡1|¯⊡0)) [1 0] ×η⇡5
+    /// A function with 2 arguments gets the time as well.
+    /// ex: # Experimental!
+    ///   : ode(×2◌) 0 [0 1 2 3] # y' = 2t
+    /// The first time is the time of the initial state. Times can decrease to integrate backwards.
+    ///
+    /// [ode] uses the adaptive Dormand–Prince Runge–Kutta method with a relative tolerance of `1e-8`.
+    (2[1], Ode, Misc, "ode"),
+    /// Evaluate a polynomial
+    ///
+    /// The first argument is a list of coefficients, starting with the constant term. The second argument is the array of values to evaluate the polynomial at.
+    /// ex: # Experimental!
+    ///   : polyval [1 2 3] 2 # 1 + 2x + 3x²
+    ///   : polyval [1 2 3] [0 1 2 3]
+    /// Coefficients and values may be complex.
+    /// ex: # Experimental!
+    ///   : polyval [1 0 1] i
+    (2, PolyVal, Misc, "polyval"),
+    /// Multiply two polynomials
+    ///
+    /// Polynomials are lists of coefficients, starting with the constant term.
+    /// ex: # Experimental!
+    ///   : polymul [1 1] [¯1 1] # (1 + x)(x - 1)
+    ///   : polymul [1 2 3] [4 5]
+    (2, PolyMul, Misc, "polymul"),
+    /// Divide two polynomials
+    ///
+    /// Polynomials are lists of coefficients, starting with the constant term.
+    /// The second argument is divided by the first. The quotient is returned on top of the remainder.
+    /// ex: # Experimental!
+    ///   : polydiv [¯1 1] [¯1 0 1] # (x² - 1) / (x - 1)
+    ///   : polydiv [1 1] [1 2 3 4]
+    (2(2), PolyDiv, Misc, "polydiv"),
+    /// Find the roots of a polynomial
+    ///
+    /// The argument is a list of real coefficients, starting with the constant term.
+    /// ex: # Experimental!
+    ///   : polyroots [6 ¯5 1] # x² - 5x + 6
+    /// Complex roots return a complex array.
+    /// ex: # Experimental!
+    ///   : polyroots [1 0 1]
+    /// Roots are the eigenvalues of the polynomial's companion matrix, so they may have small floating-point errors.
+    (1, PolyRoots, Misc, "polyroots"),
+    /// Fit a polynomial to points with least squares
+    ///
+    /// Expects a degree, a list of x values, and a list of y values.
+    /// The result is a list of coefficients, starting with the constant term.
+    /// ex: # Experimental!
+    ///   : polyfit 1 [0 1 2] [1 3 5]
+    ///   : ÷1e3⁅×1e3 polyfit 2 [0 1 2 3 4] [1.1 1.9 5.2 9.8 17.1]
+    /// There must be at least as many distinct x values as coefficients.
+    /// ex! # Experimental!
+    ///   : polyfit 2 [1 1 2] [1 2 3]
+    (3, PolyFit, Misc, "polyfit"),
+    /// Get the probability density of a distribution
+    ///
+    /// Expects the name of a distribution, a list of its parameters, and an array of values.
+    /// The distributions are:
+    /// - `"normal"` - takes a mean and standard deviation, or no parameters for the standard normal distribution
+    /// - `"t"` - Student's t-distribution, takes degrees of freedom
+    /// - `"chisq"` - the chi-squared distribution, takes degrees of freedom
+    /// - `"binomial"` - takes a number of trials and a probability of success
+    /// ex: # Experimental!
+    ///   : pdf "normal" [] [¯1 0 1]
+    ///   : pdf "normal" [10 2] 10
+    /// For the discrete binomial distribution, this is the probability of each number of successes.
+    /// ex: # Experimental!
+    ///   : pdf "binomial" [4 0.5] ⇡5
+    (3, Pdf, Misc, "pdf"),
+    /// Get the cumulative probability of a distribution
+    ///
+    /// Expects the name of a distribution, a list of its parameters, and an array of values.
+    /// The result is the probability of a value less than or equal to each value.
+    /// See [pdf] for the available distributions.
+    /// ex: # Experimental!
+    ///   : cdf "normal" [] [¯1.96 0 1.96]
+    ///   : cdf "t" 10 2.228
+    ///   : cdf "chisq" 3 7.815
+    ///   : cdf "binomial" [10 0.5] 8
+    /// Two-tailed p-values can be computed from a test statistic.
+    /// ex: # Experimental!
+    ///   : ×2 ¯-1 cdf "t" 20 2.5
+    (3, Cdf, Misc, "cdf"),
+    /// Get the value at which a distribution reaches a cumulative probability
+    ///
+    /// Expects the name of a distribution, a list of its parameters, and an array of probabilities.
+    /// This is the inverse of [cdf].
+    /// See [pdf] for the available distributions.
+    /// ex: # Experimental!
+    ///   : quantile "normal" [] 0.975
+    ///   : quantile "t" 10 [0.025 0.975]
+    ///   : quantile "chisq" 3 0.95
+    /// For the discrete binomial distribution, the result is the smallest number of successes whose cumulative probability is at least the probability.
+    /// ex: # Experimental!
+    ///   : quantile "binomial" [10 0.5] 0.5
+    (3, Quantile, Misc, "quantile"),
+    /// Get the mean of the rows of an array
+    ///
+    /// ex: # Experimental!
+    ///   : mean [1 2 3 4]
+    /// Statistics are computed along the first axis, so the result for a matrix has the mean of each column.
+    /// ex: # Experimental!
+    ///   : mean [1_2 3_4 5_9]
+    /// Use [rows] to compute statistics along the last axis instead.
+    /// ex: # Experimental!
+    ///   : ≡mean [1_2 3_4 5_9]
+    /// Unlike `÷⊃⧻/+`, [mean] computes a running mean, which is more accurate for large arrays.
+    (1, Mean, Misc, "mean"),
+    /// Get the sample variance of the rows of an array
+    ///
+    /// The variance is computed in a single pass using Welford's method. It is divided by one less than the number of rows.
+    /// ex: # Experimental!
+    ///   : variance [2 4 4 4 5 5 7 9]
+    ///   : variance [1_10 2_20 3_30]
+    (1, Variance, Misc, "variance"),
+    /// Get the sample standard deviation of the rows of an array
+    ///
+    /// This is the square root of the [variance].
+    /// ex: # Experimental!
+    ///   : stddev [2 4 4 4 5 5 7 9]
+    ///   : stddev [1_10 2_20 3_30]
+    (1, StdDev, Misc, "stddev"),
+    /// Get the skewness of the rows of an array
+    ///
+    /// The skewness is positive if the values have a longer tail above the mean, and negative if they have a longer tail below it.
+    /// ex: # Experimental!
+    ///   : skewness [1 2 3 10]
+    ///   : skewness [1 2 3]
+    /// This is the sample skewness, the third central moment divided by the second central moment to the power of 3/2.
+    (1, Skewness, Misc, "skewness"),
+    /// Get the most common row of an array
+    ///
+    /// ex: # Experimental!
+    ///   : mode [1 2 3 2 1 2]
+    ///   : mode "mississippi"
+    ///   : mode [1_2 3_4 1_2]
+    /// If rows are tied, the one that appears first is chosen.
+    /// ex: # Experimental!
+    ///   : mode [3 1 1 3]
+    (1, Mode, Misc, "mode"),
+    /// Normalize the rows of an array to z-scores
+    ///
+    /// Each value has the [mean] subtracted and is divided by the [stddev]. Both are computed along the first axis.
+    /// ex: # Experimental!
+    ///   : zscore [2 4 4 4 5 5 7 9]
+    ///   : zscore [1_10 2_20 3_30]
+    (1, ZScore, Misc, "zscore"),
+    /// Render a string template
+    ///
+    /// Expects a template string and a context, which is usually a map with string keys.
+    /// `{{name}}` is replaced with the value of `name` in the context. Values are formatted like in format strings.
+    /// ex: # Experimental!
+    ///   : template "Hello, {{name}}!" map {"name"} {"World"}
+    ///   : template "{{a}} + {{b}} = {{c}}" map {"a" "b" "c"} {1 2 3}
+    /// Special HTML characters are escaped. Use `{{{name}}}` or `{{&name}}` to insert a value as-is.
+    /// ex: # Experimental!
+    ///   : template "{{x}} {{{x}}}" map {"x"} {"<b>"}
+    /// `{{#name}}` starts a section that ends at `{{/name}}`.
+    /// If the value is a list, the section is rendered for each row. Maps in the list become the context of each row, and `{{.}}` is the row itself.
+    /// ex: # Experimental!
+    ///   : template "{{#xs}}<{{.}}>{{/xs}}" map {"xs"} {[1 2 3]}
+    ///   : template "{{#people}}{{name}} is {{age}}. {{/people}}" map {"people"} {{
+    ///   :   map {"name" "age"} {"Alice" 30}
+    ///   :   map {"name" "age"} {"Bob" 25}
+    ///   : }}
+    /// Otherwise, the section is a conditional. It is skipped if the value is `0`, empty, or missing. `{{^name}}` starts an inverted section that is only rendered if the value would be skipped.
+    /// ex: # Experimental!
+    ///   : template "{{#ok}}yes{{/ok}}{{^ok}}no{{/ok}}" map {"ok"} {1}
+    ///   : template "{{#ok}}yes{{/ok}}{{^ok}}no{{/ok}}" map {"ok"} {0}
+    /// Names are looked up in the contexts of enclosing sections if they are not in the innermost one. Using a variable that is not in any context is an error.
+    /// ex! # Experimental!
+    ///   : template "{{missing}}" map {"x"} {1}
+    (2, Template, Misc, "template"),
+    /// Lay out an array as a text table
+    ///
+    /// Expects a map of options and an array.
+    /// The array can be a rank-2 array, whose rows are the table's rows, or a map, whose keys are the column headers and whose values are the columns.
+    /// ex: # Experimental!
+    ///   : &p texttable [] ↯3_2 {"Name" "Age" "Alice" 30 "Bob" 25}
+    ///   : &p texttable [] map {"Name" "Age"} {{"Alice" "Bob"} [30 25]}
+    /// Numeric columns are right-aligned. Cells that are not strings are formatted like normal output.
+    ///
+    /// The options are:
+    /// - `"border"` - whether to draw a border, default `1`
+    /// - `"width"` - the maximum width of each column, or a list of maximum widths for each column. Longer cells are truncated.
+    /// ex: # Experimental!
+    ///   : &p texttable map {"border"} [0] ↯2_2 {"id" "description" 1 "A long description"}
+    ///   : &p texttable map {"width"} [8] ↯2_2 {"id" "description" 1 "A long description"}
+    (2, TextTable, Misc, "texttable"),
+    /// Convolve an array with a kernel
+    ///
+    /// Expects a boundary, a kernel, and an array. The result has the same shape as the array.
+    /// ex: # Experimental!
+    ///   : convolve 0 [1 1 1] [1 2 3 4 5]
+    /// The kernel is centered on each element of the array. The boundary determines the elements past the edges of the array.
+    /// A number boundary fills with that number. `"wrap"` wraps around to the other side, and `"clamp"` repeats the nearest edge element.
+    /// ex: # Experimental!
+    ///   : convolve 10 [1 1 1] [1 2 3 4 5]
+    ///   : convolve "wrap" [1 1 1] [1 2 3 4 5]
+    ///   : convolve "clamp" [1 1 1] [1 2 3 4 5]
+    /// As in mathematical convolution, the kernel is reversed along every axis. For cross-correlation, reverse the kernel first with [under][deshape][reverse].
+    /// ex: # Experimental!
+    ///   : convolve 0 [1 2 3] [0 0 1 0 0]
+    ///   : convolve 0 ⍜♭⇌ [1 2 3] [0 0 1 0 0]
+    /// A kernel of rank `n` convolves along the first `n` axes of the array, so a rank-2 kernel can blur each channel of an image.
+    /// ex: # Experimental!
+    ///   : convolve 0 [1_1 1_1] ↯3_3 ⇡9
+    ///   : convolve "clamp" ↯3_3 1 ↯3_3_2 ⇡18
+    /// This is much faster than using [rows] and [reduce] on the [windows] of the array.
+    (3, Convolve, Misc, "convolve"),
+    /// Translate code from another array language into Uiua
+    ///
+    /// Expects the name of a language and the code to translate. The languages are `"apl"`, `"j"`, and `"bqn"`.
+    /// The result is a string of Uiua code.
+    /// ex: # Experimental!
+    ///   : translate "apl" "+/⍳10"
+    ///   : translate "j" "2 3 $ i. 6"
+    ///   : translate "bqn" "×´1‿2‿3‿4"
+    /// Use [quote] to compile the translated code at compile time.
+    /// ex: # Experimental!
+    ///   : quote(translate "apl" "+/⍳10")
+    ///   : quote(translate "bqn" "1‿2‿3 +⌜ 10‿20")
+    /// If the code is just a function, it is translated into that function.
+    /// ex: # Experimental!
+    ///   : quote(translate "j" "+/") [1 2 3]
+    /// Only a subset of each language can be translated. This includes numbers, strings, strands, lists, most primitive functions, and the most common modifiers. Names, assignment, user-defined functions, and trains are not supported.
+    /// ex! # Experimental!
+    ///   : translate "apl" "x←5"
+    /// APL code is translated with `⎕IO←1`, so indices start at 1.
+    /// ex: # Experimental!
+    ///   : quote(translate "apl" "⍳5")
+    ///   : quote(translate "apl" "5 6 7⍳6")
+    /// Functions that work along the last axis in APL are translated to work along the last axis in Uiua.
+    /// ex: # Experimental!
+    ///   : quote(translate "apl" "+/2 3⍴⍳6")
+    (2, Translate, Misc, "translate"),
+    /// Map numbers to colors to make an image
+    ///
+    /// The first argument is the colormap. It can be the name of a built-in colormap, either `"viridis"` or `"grayscale"`.
+    /// ex: # Experimental!
+    ///   : colormap "viridis" ⊞× . ÷⟜⇡100
+    /// By default, the values of the array are normalized so that the smallest value gets the first color and the largest value gets the last color.
+    /// ex: # Experimental!
+    ///   : colormap "grayscale" [1 2 3 4 5]
+    /// The colormap can also be a list of RGB or RGBA colors. Values are interpolated between them.
+    /// ex: # Experimental!
+    ///   : colormap [0_0_1 1_1_1 1_0_0] ⊞-. ⇡50
+    /// Use [fill] to set the range of values explicitly instead of normalizing. Values outside the range are clamped.
+    /// ex: # Experimental!
+    ///   : ⬚[0 10]colormap "grayscale" [1 2 3 4 5]
+    /// The result has one more axis than the input array, so a rank-`2` array produces an image.
+    /// ex: # Experimental!
+    ///   : colormap "viridis" ⊞(+∩(∿÷10)) . ⇡100
+    (2, Colormap, Misc, "colormap"),
+    /// Resize an image
+    ///
+    /// The first argument is the new height and width. The second argument is an image, either a rank-`2` array of grayscale values or a rank-`3` array of pixels.
+    /// ex: # Experimental!
+    ///   : resize [128 256] Logo
+    /// A single number scales the image by that factor.
+    /// ex: # Experimental!
+    ///   : resize 0.25 Lena
+    /// By default, pixels are interpolated bilinearly. To use a different interpolation method, pass a box list of the method's name and the size. The methods are `"nearest"`, `"bilinear"`, and `"bicubic"`.
+    /// ex: # Experimental!
+    ///   : resize {"nearest" [4 6]} [1_0 0_1]
+    /// ex: # Experimental!
+    ///   : resize {"bicubic" 64} [1_0 0_1]
+    (2, Resize, Misc, "resize"),
+    /// Crop an image
+    ///
+    /// The first argument is a list of the row, column, height, and width of the rectangle to keep.
+    /// ex: # Experimental!
+    ///   : crop [200 220 150 150] Lena
+    /// Parts of the rectangle that are outside the image are filled with the [fill] value. Use a list to fill with a color.
+    /// ex: # Experimental!
+    ///   : ⬚[1 0 0]crop [¯50 ¯50 200 200] Lena
+    (2, Crop, Misc, "crop"),
+    /// Rotate an image by an angle
+    ///
+    /// The first argument is the angle in radians. Positive angles rotate counterclockwise.
+    /// The image is rotated around its center, and the result is the same size as the image.
+    /// ex: # Experimental!
+    ///   : turn ÷6π Logo
+    /// Parts of the result that are outside the original image are filled with the [fill] value or `0`.
+    /// ex: # Experimental!
+    ///   : ⬚[0 0 0 1]turn ¯÷4π Logo
+    /// Like [resize], the interpolation method can be chosen with a box list.
+    /// ex: # Experimental!
+    ///   : turn {"nearest" ÷4π} Lena
+    (2, Turn, Misc, "turn"),
+    /// Apply an affine transformation to an image
+    ///
+    /// The first argument is a `2×3` or `3×3` matrix that maps the row and column of each pixel in the image to its position in the result.
+    /// The result is the same size as the image.
+    /// ex: # Experimental!
+    ///   : warp [1_0.5_¯128 0_1_0] Lena
+    /// ex: # Experimental!
+    ///   : warp [0.5_0_0 0_1.5_0] Logo
+    /// Like [turn], parts of the result that are outside the original image are filled with the [fill] value or `0`, and the interpolation method can be chosen with a box list.
+    /// ex: # Experimental!
+    ///   : ⬚1warp {"nearest" [1_0_10 0_1_20]} Lena
+    (2, Warp, Misc, "warp"),
+    /// Erode an image
+    ///
+    /// Each value becomes the minimum of the values in its neighborhood.
+    /// The first argument is the structuring element that defines the neighborhood. A number makes a square of that size.
+    /// ex: # Experimental!
+    ///   : erode 3 ⬚0(↻¯2_¯2↙9_9) ↯5_5 1
+    /// The structuring element can also be a mask. Its center is its middle cell.
+    /// ex: # Experimental!
+    ///   : erode [0_1_0 1_1_1 0_1_0] ⬚0(↻¯2_¯2↙9_9) ↯5_5 1
+    /// Parts of the neighborhood outside the array are ignored. Images with color channels are eroded channel by channel.
+    /// ex: # Experimental!
+    ///   : erode 5 Lena
+    (2, Erode, Misc, "erode"),
+    /// Dilate an image
+    ///
+    /// Each value becomes the maximum of the values in its neighborhood.
+    /// The structuring element works the same as with [erode].
+    /// ex: # Experimental!
+    ///   : dilate 3 ⬚0↙7_7 [1_0 0_0]
+    /// ex: # Experimental!
+    ///   : dilate [0_1_0 1_1_1 0_1_0] ⬚0(↻¯3_¯3↙7_7) [[1]]
+    /// Eroding and then dilating removes small specks.
+    /// ex: # Experimental!
+    ///   : ↥ °⊚[0_8 8_0 8_8] ⬚0(↻¯2_¯2↙9_9) ↯5_5 1
+    ///   : dilate 3 erode 3 .
+    (2, Dilate, Misc, "dilate"),
+    /// Replace each value in an image with the median of its neighborhood
+    ///
+    /// The structuring element works the same as with [erode].
+    /// This removes noise while keeping edges sharp.
+    /// ex: # Experimental!
+    ///   : medianfilter 3 [1_1_1_5 1_9_1_5 1_1_1_5 1_1_1_5]
+    /// ex: # Experimental!
+    ///   : medianfilter 5 Lena
+    (2, MedianFilter, Misc, "medianfilter"),
+    /// Blur an image with a Gaussian kernel
+    ///
+    /// The first argument is the standard deviation of the kernel, in pixels.
+    /// ex: # Experimental!
+    ///   : blur 4 Logo
+    /// ex: # Experimental!
+    ///   : blur 1 ⬚0(↻¯3_¯3↙7_7) [[1]]
+    /// Pixels at the edges are extended outward.
+    (2, Blur, Misc, "blur"),
+    /// Label the connected regions of a mask
+    ///
+    /// The first argument is the connectivity, the number of neighbors each cell is connected to.
+    /// For a rank-`2` array, `4` connects cells that share an edge, and `8` also connects cells that share a corner.
+    /// Each region of non-zero cells gets a different label, starting from `1`. Zero cells are labeled `0`.
+    /// The number of regions is also returned.
+    /// ex: # Experimental!
+    ///   : regions 4 [1_1_0_0 0_0_0_1 0_1_0_1 1_0_0_1]
+    /// ex: # Experimental!
+    ///   : regions 8 [1_1_0_0 0_0_0_1 0_1_0_1 1_0_0_1]
+    /// For a rank-`3` array, the connectivity can be `6`, `18`, or `26`.
+    /// ex: # Experimental!
+    ///   : ◌regions 6 [[1_0 0_0] [1_0 0_1]]
+    /// Labels work well with [group]. Here, we find the size of each region.
+    /// ex: # Experimental!
+    ///   : ↘1⊕⧻⊸∘ ♭ ◌regions 8 [1_1_0_0 0_0_0_1 0_1_0_1 1_0_0_1]
+    (2(2), Regions, Misc, "regions"),
+    /// Find the cells connected to some seed cells
+    ///
+    /// The first argument is the index of a seed cell or a list of seed indices.
+    /// The result is a mask of the cells that can be reached from a seed by moving between neighboring cells with the same value as the seed. Cells that share an edge are neighbors, but cells that only share a corner are not.
+    /// ex: # Experimental!
+    ///   : floodfill [0 0] [1_1_0_0 1_0_0_1 0_0_1_1 1_0_1_0]
+    /// ex: # Experimental!
+    ///   : floodfill [0_2 3_0] [1_1_0_0 1_0_0_1 0_0_1_1 1_0_1_0]
+    /// This works on character grids too.
+    /// ex: # Experimental!
+    ///   : floodfill 1_1 ⊜∘⊸≠@\n "#.#.\n#..#\n####"
+    /// To paint the filled cells, use the mask to select them.
+    /// ex: # Experimental!
+    ///   : ⍜⊡≡⋅9 ⊚ floodfill [0 2] . [1_1_0_0 1_0_0_1 0_0_1_1 1_0_1_0]
+    (2, FloodFill, Misc, "floodfill"),
+    /// Trace the contours of the regions of a mask
+    ///
+    /// The result is a list of boxed lists of coordinates. There is one list for each region of non-zero cells, where cells that share a corner are in the same region.
+    /// Each list traces the outer boundary of its region clockwise, starting from the region's top-left cell.
+    /// ex: # Experimental!
+    ///   : contours [1_1_0_0 1_0_0_1 0_0_1_1 1_0_1_0]
+    /// ex: # Experimental!
+    ///   : contours [0_1_0 1_1_1 0_1_0]
+    /// Holes in a region are not traced.
+    /// ex: # Experimental!
+    ///   : contours [1_1_1 1_0_1 1_1_1]
+    (1, Contours, Misc, "contours"),
+    /// Draw shapes into an image
+    ///
+    /// The first argument is a shape or a list of shapes. A shape is a boxed list whose first item is the name of the shape. The remaining items are its parameters.
+    /// Points are given as a row and column.
+    /// - `{"line" start end width}` - a line segment. `width` is optional.
+    /// - `{"circle" center radius width}` - the outline of a circle. `width` is optional.
+    /// - `{"fillcircle" center radius}` - a filled circle
+    /// - `{"polygon" points width}` - the outline of a polygon. `width` is optional.
+    /// - `{"fillpolygon" points}` - a filled polygon
+    /// ex: # Experimental!
+    ///   : draw {"line" [1 1] [8 12]} ↯10_14 0
+    /// ex: # Experimental!
+    ///   : draw {{"circle" [10 10] 7} {"fillcircle" [10 10] 3}} ↯21_21 0
+    /// Shapes are drawn with `1` by default. Use [fill] to choose a color.
+    /// ex: # Experimental!
+    ///   : ⬚[1 0.5 0]draw {"fillpolygon" [5_50 95_10 95_90]} ↯100_100_3 0
+    /// Shapes inside a `"smooth"` shape are anti-aliased.
+    /// ex: # Experimental!
+    ///   : ⬚[0 0 1]draw {"smooth" {"circle" [50 50] 40 3} {"line" [10 10] [90 80] 2}} ↯100_100_3 1
+    (2, Draw, Misc, "draw"),
+    /// Render text into an image
+    ///
+    /// The first argument is the size of the text in pixels. The second argument is the text.
+    /// The result is a mask of how much each pixel is covered by the text.
+    /// ex: # Experimental!
+    ///   : text 32 "Hello, World!"
+    /// Multiple lines are rendered one below the other.
+    /// ex: # Experimental!
+    ///   : text 32 "Hello\nWorld!"
+    /// If a color is given with [fill], the result is an image with an alpha channel.
+    /// ex: # Experimental!
+    ///   : ⬚[1 0.5 0]text 48 "Uiua"
+    /// To use a different font, pass a box list of the bytes of a font file and the size.
+    /// ex! # Experimental!
+    ///   : text {&frab "font.ttf" 32} "Hello"
+    /// The default font is Uiua386.
+    (2, Text, Misc, "text"),
     /// Find shortest paths in a graph
     ///
     /// Expects 3 functions and at least 1 value.
@@ -2474,6 +3579,56 @@ primitive!(
     ///
     /// [astar] is designed to be maximally flexible, so it can be used with graphs or grids or any other structure.
     ((2)[3], Astar, Misc, "astar"),
+    /// Label the connected components of a graph
+    ///
+    /// The graph can be an adjacency matrix or an adjacency list.
+    /// In an adjacency matrix, a nonzero number at row `i` and column `j` is an edge from node `i` to node `j`.
+    /// An adjacency list is a list of boxed lists, one for each node, of the nodes that it has edges to.
+    /// Each node is labeled with the index of its component. Components are numbered in order of their lowest node.
+    /// ex: # Experimental!
+    ///   : components [0_1_0_0 1_0_0_0 0_0_0_1 0_0_1_0]
+    /// ex: # Experimental!
+    ///   : components {[1] [] [3] [] []}
+    /// Edges are followed in both directions, so the components of a directed graph are its weakly connected components.
+    (1, Components, Misc, "components"),
+    /// Get the order in which a breadth-first search visits the nodes of a graph
+    ///
+    /// The first argument is the start node. The second is the graph. See [components] for how graphs are represented.
+    /// Only nodes reachable from the start node are visited. Neighbors are visited in increasing order.
+    /// ex: # Experimental!
+    ///   : bfs 0 {[1 2] [3] [3] [] []}
+    (2, Bfs, Misc, "bfs"),
+    /// Get the order in which a depth-first search visits the nodes of a graph
+    ///
+    /// The first argument is the start node. The second is the graph. See [components] for how graphs are represented.
+    /// Only nodes reachable from the start node are visited. Neighbors are visited in increasing order.
+    /// ex: # Experimental!
+    ///   : dfs 0 {[1 2] [3] [3] [] []}
+    (2, Dfs, Misc, "dfs"),
+    /// Get the shortest distances from a node to every node in a graph
+    ///
+    /// The first argument is the start node. The second is the graph. See [components] for how graphs are represented.
+    /// The numbers in an adjacency matrix are the weights of the edges. Edges in an adjacency list have a weight of `1`.
+    /// Unreachable nodes have a distance of [infinity].
+    /// ex: # Experimental!
+    ///   : dijkstra 0 [0_4_1_0 0_0_0_1 0_2_0_5 0_0_0_0]
+    /// ex: # Experimental!
+    ///   : dijkstra 0 {[1 2] [3] [3] [] []}
+    /// Edge weights must not be negative.
+    /// ex! # Experimental!
+    ///   : dijkstra 0 [0_¯1 0_0]
+    /// For more complex pathfinding, see [astar].
+    (2, Dijkstra, Misc, "dijkstra"),
+    /// Sort the nodes of a directed graph so that every edge goes from an earlier node to a later one
+    ///
+    /// See [components] for how graphs are represented.
+    /// When there is a choice, the lowest node comes first.
+    /// ex: # Experimental!
+    ///   : toposort {[] [0] [0 1] []}
+    /// It is an error if the graph has a cycle.
+    /// ex! # Experimental!
+    ///   : toposort {[1] [0]}
+    (1, Toposort, Misc, "toposort"),
     /// Encode an array into a JSON string
     ///
     /// ex: json [1 2 3]
@@ -2512,6 +3667,153 @@ primitive!(
     /// You can easily create a [map] with the headers as keys.
     /// ex: map⊙(⍉⋕)°⊂ °csv "#,Count\n1,5\n2,21\n3,8\n"
     (1, Csv, Encoding, "csv"),
+    /// Encode a tree of elements into an XML string
+    ///
+    /// [un][xml] parses XML or HTML into a list of nodes.
+    /// A text node is a string. An element is a [map] with the keys `"tag"`, `"attributes"`, and `"children"`.
+    /// `"attributes"` is a [map] of strings, and `"children"` is a list of boxed nodes.
+    /// ex: # Experimental!
+    ///   : °xml "<p class='intro'>Hello, <b>world</b>!</p>"
+    /// ex: # Experimental!
+    ///   : °□⊡2 °□⊢ °xml "<a href=\"https://uiua.org\">Uiua</a>"
+    /// Comments, declarations, and text that is only whitespace are skipped. Entities like `&amp;` are decoded.
+    ///
+    /// The parser is lenient so that it can handle real-world HTML.
+    /// Unclosed elements are closed when a parent element closes.
+    /// Elements like `<li>` and `<td>` are also closed when a sibling starts, and elements like `<br>` and `<img>` never have children.
+    /// The content of `<script>` and `<style>` elements is not parsed.
+    /// If the input starts with an `<?xml` declaration, these HTML rules are not used.
+    /// ex: # Experimental!
+    ///   : xml °xml "<ul><li>One<li>Two<br>Three</ul>"
+    ///
+    /// [xml] converts nodes back into a string. It accepts a single node or a list of them.
+    /// The `"attributes"` and `"children"` keys may be omitted. Elements without children are self-closing.
+    /// ex: # Experimental!
+    ///   : xml map {"tag" "children"} {"p" {"1 < 2" map {"tag"} {"br"}}}
+    /// ex: # Experimental!
+    ///   : xml °xml "<a  href='x'>Hi<br></a>"
+    (1, Xml, Encoding, "xml"),
+    /// Encode a map into a TOML string
+    ///
+    /// [TOML](https://toml.io/) is a configuration file format.
+    /// Arrays are converted the same way as with [json], but the value must be a [map].
+    /// ex: # Experimental!
+    ///   : &p toml map {"name" "version"} {"uiua" "0.12.0"}
+    /// You can use [un][toml] to decode a TOML string into a [map].
+    /// ex: # Experimental!
+    ///   : °toml "name = \"uiua\"\nauthors = [\"Kai\"]\n[dependencies]\nfoo = 1"
+    /// Dates and times decode to strings.
+    (1, Toml, Encoding, "toml"),
+    /// Encode an array into a YAML string
+    ///
+    /// [YAML](https://yaml.org/) is a configuration file format.
+    /// Arrays are converted the same way as with [json].
+    /// ex: # Experimental!
+    ///   : &p yaml map {"name" "tags"} {"uiua" {"array" "stack"}}
+    /// You can use [un][yaml] to decode a YAML string into an array.
+    /// ex: # Experimental!
+    ///   : °yaml "name: uiua\ntags: [array, stack]"
+    /// Tags are ignored, and map keys that are numbers or booleans decode to strings.
+    (1, Yaml, Encoding, "yaml"),
+    /// Build a URL string from a map of its components
+    ///
+    /// [un][url] parses a URL into a [map] with the keys `"scheme"`, `"user"`, `"host"`, `"port"`, `"path"`, `"query"`, and `"fragment"`.
+    /// ex: # Experimental!
+    ///   : °url "https://uiua.org:443/docs/url?q=1&tag=new#top"
+    /// Components that are not in the URL are empty, and `"port"` is `NaN`.
+    /// `"path"` is a list of segments. An absolute path starts with an empty segment.
+    /// `"query"` is a [map] from query keys to values.
+    /// Components are percent-decoded, and `+` in the query decodes to a space.
+    /// ex: # Experimental!
+    ///   : °□get □"query" °url "/search?q=uiua+docs&lang=en%2Dus"
+    ///
+    /// [url] builds a URL from the components, percent-encoding them where needed.
+    /// Missing keys are treated as empty. `"path"` may also be a single string.
+    /// ex: # Experimental!
+    ///   : url map {"scheme" "host" "path"} {"https" "uiua.org" {"" "docs" "a b"}}
+    /// This makes it easy to change a part of a URL with [under].
+    /// ex: # Experimental!
+    ///   : ⍜°url(insert □"port" □8080) "http://localhost/index.html"
+    ///   : ⍜(°□get □"query" °url|insert □"page" □"2") "https://example.com/list?sort=new"
+    (1, Url, Encoding, "url"),
+    /// Percent-encode a string
+    ///
+    /// Every character except for letters, digits, and `-._~` is encoded.
+    /// ex: # Experimental!
+    ///   : percent "Hello, World! ∧"
+    /// [un][percent] decodes a percent-encoded string.
+    /// ex: # Experimental!
+    ///   : °percent "a%20b%2Fc%E2%88%A7"
+    (1, Percent, Encoding, "percent"),
+    /// Format an IP address as a string
+    ///
+    /// The address is a list of `4` bytes for IPv4 or `16` bytes for IPv6.
+    /// ex: # Experimental!
+    ///   : ip [192 168 0 1]
+    ///   : ip ⬚0↙16 [32 1 13 184]
+    /// [un][ip] parses an IP address into its bytes.
+    /// ex: # Experimental!
+    ///   : °ip "10.20.30.40"
+    ///   : °ip "::ffff:1.2.3.4"
+    /// This allows arithmetic on addresses with [under].
+    /// ex: # Experimental!
+    ///   : ⍜(°ip|⍜(⊢⇌)(+1)) "192.168.0.7"
+    /// [cidr] and [subnets] work with networks of addresses.
+    (1, Ip, Encoding, "ip"),
+    /// Render an array as an HTML table
+    ///
+    /// The input array must be at most rank-`2`. Each row of the array becomes a row of the table.
+    /// ex: # Experimental!
+    ///   : &p html ↯2_3⇡6
+    /// A string is a single cell, and each row of a character matrix is a cell.
+    ///
+    /// Boxed values are unboxed, and strings are shown without quotes.
+    /// Numbers are formatted the same way they are shown in output.
+    /// ex: # Experimental!
+    ///   : &p html {"Pi" π "Third" ÷3 1}
+    /// If the array is a [map], its keys are used as the table's headers, and each value is a column.
+    /// ex: # Experimental!
+    ///   : &p html map {"Name" "Age"} {{"Alice" "Bob"} [31 27]}
+    /// Special characters in cells are escaped.
+    /// ex: # Experimental!
+    ///   : &p html {"<b>" "a&b"}
+    /// [markdown] renders a Markdown table instead.
+    (1, Html, Encoding, "html"),
+    /// Render an array as a Markdown table
+    ///
+    /// The input array must be at most rank-`2`. Each row of the array becomes a row of the table.
+    /// ex: # Experimental!
+    ///   : &p markdown ↯2_3⇡6
+    /// Boxed values are unboxed, and strings are shown without quotes.
+    /// Numbers are formatted the same way they are shown in output.
+    /// ex: # Experimental!
+    ///   : &p markdown {"Pi" π "Third" ÷3 1}
+    /// If the array is a [map], its keys are used as the table's headers, and each value is a column.
+    /// Otherwise, the header row is left empty.
+    /// ex: # Experimental!
+    ///   : &p markdown map {"Name" "Age"} {{"Alice" "Bob"} [31 27]}
+    /// [html] renders an HTML table instead.
+    (1, Markdown, Encoding, "markdown"),
+    /// Parse Uiua code into an array representation of its syntax tree
+    ///
+    /// The result is a list of nodes. Each node is a list of boxes whose first element is the kind of the node.
+    /// ex: # Experimental!
+    ///   : ast "+1 2"
+    /// ex: # Experimental!
+    ///   : ast "F ← /+⇡"
+    /// Nested functions and modifiers are represented as nested nodes.
+    /// ex: # Experimental!
+    ///   : °□⊢ ast "≡(×2)"
+    /// Parts of the code that do not have their own node kind are represented as `"code"` nodes containing the original source.
+    ///
+    /// [un][ast] converts an array representation back into code.
+    /// ex: # Experimental!
+    ///   : °ast ast "+1 2"
+    /// This allows writing Uiua programs that analyze or generate Uiua code.
+    /// Here, we add a [negate] to the beginning of the first line.
+    /// ex: # Experimental!
+    ///   : °ast ⍜(°□⊡1°□⊢)(⊂□{"primitive" "negate"}) ast "+1 2"
+    (1, Ast, Encoding, "ast"),
     /// Encode an array into XLSX bytes
     ///
     /// XLSX is a spreadsheet format that can be edited in programs like Microsoft Excel, Google Sheets, and LibreOffice Calc.
@@ -2527,6 +3829,19 @@ primitive!(
     /// While it is not useful to display the output bytes here, we can see how the result of decoding works:
     /// ex: °xlsx xlsx . ↯3_6⇡18
     (1, Xlsx, Encoding, "xlsx"),
+    /// Encode an array into CBOR bytes
+    ///
+    /// [CBOR](https://cbor.io/) is a compact binary format with the same data model as JSON.
+    /// Arrays are encoded the same way as with [json], but the output is a byte array.
+    /// ex: # Experimental!
+    ///   : cbor [1 2 3]
+    /// ex: # Experimental!
+    ///   : cbor "Hi!"
+    /// You can use [un][cbor] to decode CBOR bytes back into an array.
+    /// ex: # Experimental!
+    ///   : °cbor cbor map {"hey" "there" "buddy"} {1 2 [3 4 5]}
+    /// CBOR byte strings decode to lists of numbers, and tags are ignored.
+    (1, Cbor, Encoding, "cbor"),
     /// Convert a value to its code representation
     ///
     /// ex: repr π
@@ -2549,6 +3864,38 @@ primitive!(
     ///   : ⍜⊜□⍚(⊂@,)∊," \n" repr # add commas
     ///   : &p ⍜▽∵⋅@-=@¯.         # replace negate glyphs with minus signs
     (1, Repr, Misc, "repr"),
+    /// Run a string of code in an isolated interpreter
+    ///
+    /// The first argument is the code to run. The second argument is a list of values to put on the isolated interpreter's stack before running the code.
+    /// The result is a list of all the values left on the stack.
+    /// ex: # Experimental!
+    ///   : eval "+" {1 2}
+    /// ex: # Experimental!
+    ///   : eval "⊃+×" {3 4}
+    /// A single value that is not a list of boxes will be passed as-is.
+    /// ex: # Experimental!
+    ///   : eval "/+" [1 2 3]
+    /// The first value in the list will be on the top of the stack.
+    /// ex: # Experimental!
+    ///   : eval "-" {1 10}
+    ///
+    /// The code cannot access any bindings or system functions outside of itself.
+    /// ex! # Experimental!
+    ///   : X ← 5
+    ///   : eval "+X" 1
+    /// The code has a limited amount of time to run, which is never more than the time left for the calling code.
+    /// The code also cannot create very large arrays.
+    /// ex! # Experimental!
+    ///   : eval "↯1e9 0" {}
+    ///
+    /// If the code fails to compile or run, [eval] will throw an error that can be caught with [try].
+    /// ex: # Experimental!
+    ///   : ⍣eval⋅⋅"failed" "+" {1}
+    /// This makes [eval] useful for safely evaluating formulas entered by a user.
+    /// ex: # Experimental!
+    ///   : Formula ← "×2+1"
+    ///   : ≡(°□⊢eval Formula) ⇡5
+    (2, Eval, Misc, "eval", Impure),
 );
 
 macro_rules! impl_primitive {
@@ -2570,6 +3917,7 @@ macro_rules! impl_primitive {
             TransposeN(i32),
             ReduceDepth(usize),
             TraceN(usize, bool),
+            View(ViewChain),
         }
 
         impl ImplPrimitive {
@@ -2579,6 +3927,7 @@ macro_rules! impl_primitive {
                     ImplPrimitive::TransposeN(_) => 1,
                     ImplPrimitive::ReduceDepth(_) => 1,
                     ImplPrimitive::TraceN(n, _) => *n,
+                    ImplPrimitive::View(_) => 1,
                 }
             }
             pub fn outputs(&self) -> usize {
@@ -2619,6 +3968,7 @@ impl_primitive!(
     (1, UnParse),
     (1, UnFix),
     (1[1], UnScan),
+    (2, UnWindows),
     (1(2), UnMap),
     (0(0), UnStack, Impure),
     (0[1], UnDump, Impure),
@@ -2629,8 +3979,19 @@ impl_primitive!(
     (1(2), UnKeep),
     (1, UnJson),
     (1, UnCsv),
+    (1, UnAst),
     (1, UnXlsx),
+    (1, UnCbor),
+    (1, UnXml),
+    (1, UnToml),
+    (1, UnYaml),
+    (1, UnUrl),
+    (1, UnPercent),
+    (1, UnIp),
     (1, UnFft),
+    (1, UnMercator),
+    (2, UnUnit),
+    (1, UnInterval),
     (2(0), MatchPattern),
     // Unders
     (1, UndoFix),
@@ -2643,6 +4004,7 @@ impl_primitive!(
     (3, UndoKeep),
     (3, UndoRerank),
     (2, UndoReshape),
+    (2, UndoWindows),
     (2, UndoWhere),
     (3(2), UndoJoin),
     (1[1], UndoPartition1),
@@ -2652,6 +4014,10 @@ impl_primitive!(
     (4, UndoInsert),
     (3, UndoRemove),
     (1(0), TryClose),
+    (2(0), UndoFReadAll, Mutating),
+    // Other
+    (2, DispatchIndex),
+    (2, Destructure),
     // Optimizations
     (1, Last),
     (1, FirstMinIndex),
@@ -16,6 +16,7 @@ use std::{
         INFINITY,
     },
     fmt,
+    hash::{Hash, Hasher},
     sync::{
         atomic::{self, AtomicUsize},
         OnceLock,
@@ -28,14 +29,14 @@ use rand::prelude::*;
 use serde::*;
 
 use crate::{
-    algorithm::{self, invert, loops, reduce, table, zip},
+    algorithm::{self, invert, lazy_fill, loops, reduce, table, view::ViewChain, zip},
     array::Array,
     boxed::Boxed,
     check::instrs_signature,
     lex::AsciiToken,
     sys::*,
     value::*,
-    FunctionId, Signature, Uiua, UiuaErrorKind, UiuaResult,
+    BindingKind, FunctionId, Signature, Uiua, UiuaErrorKind, UiuaResult,
 };
 
 /// Categories of primitives
@@ -171,13 +172,25 @@ impl fmt::Display for ImplPrimitive {
             UnJoin | UnJoinPattern => write!(f, "{Un}{Join}"),
             UnKeep => write!(f, "{Un}{Keep}"),
             UnScan => write!(f, "{Un}{Scan}"),
+            UnWindows => write!(f, "{Un}{Windows}"),
             UnStack => write!(f, "{Un}{Stack}"),
             UnDump => write!(f, "{Un}{Dump}"),
             UnBox => write!(f, "{Un}{Box}"),
             UnJson => write!(f, "{Un}{Json}"),
             UnCsv => write!(f, "{Un}{Csv}"),
+            UnAst => write!(f, "{Un}{Ast}"),
             UnXlsx => write!(f, "{Un}{Xlsx}"),
+            UnCbor => write!(f, "{Un}{Cbor}"),
+            UnXml => write!(f, "{Un}{Xml}"),
+            UnToml => write!(f, "{Un}{Toml}"),
+            UnYaml => write!(f, "{Un}{Yaml}"),
+            UnUrl => write!(f, "{Un}{Url}"),
+            UnPercent => write!(f, "{Un}{Percent}"),
+            UnIp => write!(f, "{Un}{Ip}"),
             UnFft => write!(f, "{Un}{Fft}"),
+            UnMercator => write!(f, "{Un}{Mercator}"),
+            UnUnit => write!(f, "{Un}{Unit}"),
+            UnInterval => write!(f, "{Un}{Interval}"),
             UndoTake => write!(f, "{Under}{Take}"),
             UndoDrop => write!(f, "{Under}{Drop}"),
             UndoSelect => write!(f, "{Under}{Select}"),
@@ -188,6 +201,7 @@ impl fmt::Display for ImplPrimitive {
             UndoPartition1 | UndpPartition2 => write!(f, "{Under}{Partition}"),
             UndoGroup1 | UndoGroup2 => write!(f, "{Under}{Group}"),
             TryClose => write!(f, "{}", Sys(SysOp::Close)),
+            UndoFReadAll => write!(f, "{}", Sys(SysOp::FWriteAll)),
             Asin => write!(f, "{Un}{Sin}"),
             Last => write!(f, "{First}{Reverse}"),
             UndoFix => write!(f, "{Under}{Fix}"),
@@ -196,6 +210,7 @@ impl fmt::Display for ImplPrimitive {
             UndoKeep => write!(f, "{Under}{Keep}"),
             UndoRerank => write!(f, "{Under}{Rerank}"),
             UndoReshape => write!(f, "{Un}{Reshape}"),
+            UndoWindows => write!(f, "{Under}{Windows}"),
             UndoJoin => write!(f, "{Under}{Join}"),
             FirstMinIndex => write!(f, "{First}{Rise}"),
             FirstMaxIndex => write!(f, "{First}{Fall}"),
@@ -215,6 +230,8 @@ impl fmt::Display for ImplPrimitive {
             MatchPattern => write!(f, "pattern match"),
             EndRandArray => write!(f, "[{Repeat}{Rand}"),
             AstarFirst => write!(f, "{First}{Astar}"),
+            DispatchIndex => write!(f, "{Dispatch}"),
+            Destructure => write!(f, "destructure"),
             &ReduceDepth(n) => {
                 for _ in 0..n {
                     write!(f, "{Rows}")?;
@@ -237,6 +254,7 @@ impl fmt::Display for ImplPrimitive {
                 }
                 Ok(())
             }
+            View(chain) => write!(f, "{chain}"),
             &TraceN(n, inverse) => {
                 if inverse {
                     write!(f, "{Un}")?;
@@ -337,6 +355,11 @@ impl Primitive {
     pub fn is_modifier(&self) -> bool {
         self.modifier_args().is_some()
     }
+    /// Check if this primitive's only impurity is using the random number generator
+    pub(crate) fn is_random(&self) -> bool {
+        use Primitive::*;
+        matches!(self, Rand | Shuffle | Sample | Choose)
+    }
     /// Check if this primitive is a constant
     pub fn is_constant(&self) -> bool {
         self.constant().is_some()
@@ -386,9 +409,43 @@ impl Primitive {
         use SysOp::*;
         matches!(
             self,
-            (Coordinate | Astar | Fft | Triangle | Case)
-                | Sys(Ffi | MemCopy | MemFree | TlsListen)
-                | (Stringify | Quote | Sig)
+            (Coordinate
+                | Astar
+                | Fft
+                | Synth
+                | Triangle
+                | Case
+                | Ast
+                | Eval
+                | Html
+                | Markdown
+                | Colormap)
+                | (Cross | Norm | Normalize | Distances)
+                | (Resize | Crop | Turn | Warp)
+                | (Erode
+                    | Dilate
+                    | MedianFilter
+                    | Blur
+                    | Regions
+                    | FloodFill
+                    | Contours
+                    | Draw
+                    | Text)
+                | (Haversine | Bearing | Mercator | InPolygon)
+                | (Unit | Interval | Ode)
+                | (PolyVal | PolyMul | PolyDiv | PolyRoots | PolyFit)
+                | (Pdf | Cdf | Quantile)
+                | (Mean | Variance | StdDev | Skewness | Mode | ZScore)
+                | (Template | TextTable | Convolve | Translate)
+                | (Components | Bfs | Dfs | Dijkstra | Toposort)
+                | (Shuffle | Sample | Choose | Uuid | Entropy | Memory | Storage | Store)
+                | (Hash | Embed)
+                | (Label | Relabel | Diff | Validate | Cbor | Xml | Toml | Yaml | Url | Percent)
+                | (Ip | Cidr | Subnets)
+                | Sys(Ffi | MemCopy | MemFree | TlsListen | AudioSynth)
+                | (Stringify | Quote | Sig | Reflect | Dispatch | Obverse | LazyFill | Along)
+                | (Broadcast | FillTake | FillDrop | GroupMap | PartitionMap)
+                | GroupReduce
         )
     }
     /// Check if this primitive is deprecated
@@ -564,15 +621,41 @@ impl Primitive {
             Primitive::Join => env.dyadic_oo_env(|a, b, env| a.join(b, true, env))?,
             Primitive::Transpose => env.monadic_mut(Value::transpose)?,
             Primitive::Keep => env.dyadic_oo_env(Value::keep)?,
-            Primitive::Take => env.dyadic_oo_env(Value::take)?,
+            Primitive::Take => {
+                if let Some(fill) = env.fill_function() {
+                    lazy_fill::take(fill, env)?
+                } else {
+                    env.dyadic_oo_env(Value::take)?
+                }
+            }
             Primitive::Drop => env.dyadic_oo_env(Value::drop)?,
+            Primitive::FillTake => {
+                let fill = env.pop(1)?;
+                let index = env.pop(2)?;
+                let from = env.pop(3)?;
+                let taken = env.with_fill(fill, |env| index.take(from, env))?;
+                env.push(taken);
+            }
+            Primitive::FillDrop => {
+                let fill = env.pop(1)?;
+                let index = env.pop(2)?;
+                let from = env.pop(3)?;
+                let dropped = env.with_fill(fill, |env| index.fill_drop(from, env))?;
+                env.push(dropped);
+            }
             Primitive::Rotate => env.dyadic_ro_env(Value::rotate)?,
             Primitive::Couple => env.dyadic_oo_env(Value::couple)?,
             Primitive::Rise => env.monadic_ref(Value::rise)?,
             Primitive::Fall => env.monadic_ref(Value::fall)?,
             Primitive::Pick => env.dyadic_oo_env(Value::pick)?,
             Primitive::Select => env.dyadic_or_env(Value::select)?,
-            Primitive::Windows => env.dyadic_rr_env(Value::windows)?,
+            Primitive::Windows => {
+                if let Some(fill) = env.fill_function() {
+                    lazy_fill::windows(fill, env)?
+                } else {
+                    env.dyadic_rr_env(Value::windows)?
+                }
+            }
             Primitive::Where => env.monadic_ref_env(Value::wher)?,
             Primitive::Classify => env.monadic_ref(Value::classify)?,
             Primitive::Deduplicate => env.monadic_mut_env(Value::deduplicate)?,
@@ -605,11 +688,14 @@ impl Primitive {
             Primitive::Fold => reduce::fold(env)?,
             Primitive::Each => zip::each(env)?,
             Primitive::Rows => zip::rows(env)?,
+            Primitive::Along => zip::along(env)?,
             Primitive::Table => table::table(env)?,
             Primitive::Inventory => zip::inventory(env)?,
             Primitive::Repeat => loops::repeat(env)?,
             Primitive::Do => loops::do_(env)?,
             Primitive::Group => loops::group(env)?,
+            Primitive::GroupReduce => loops::group_reduce(env)?,
+            Primitive::GroupMap | Primitive::PartitionMap => loops::keyed_groups(*self, env)?,
             Primitive::Partition => loops::partition(env)?,
             Primitive::Triangle => table::triangle(env)?,
             Primitive::Reshape => {
@@ -672,6 +758,30 @@ impl Primitive {
                     env.call(f)
                 })?;
             }
+            Primitive::LazyFill => {
+                let fill = env.pop_function()?;
+                let f = env.pop_function()?;
+                let sig = fill.signature();
+                if sig.outputs != 1 || !(1..=2).contains(&sig.args) {
+                    return Err(env.error(format!(
+                        "{} function must have signature {} or {}, but its signature is {}",
+                        Primitive::LazyFill.format(),
+                        Signature::new(1, 1),
+                        Signature::new(2, 1),
+                        sig
+                    )));
+                }
+                env.with_fill_function(fill, |env| {
+                    if matches!(f.id, FunctionId::Named(_)) {
+                        env.use_fill();
+                    }
+                    env.call(f)
+                })?;
+            }
+            Primitive::Broadcast => {
+                let f = env.pop_function()?;
+                env.with_broadcasting(|env| env.call(f))?;
+            }
             Primitive::Try => algorithm::try_(env)?,
             Primitive::Case => {
                 let f = env.pop_function()?;
@@ -709,6 +819,87 @@ impl Primitive {
                 rows.shuffle(&mut SmallRng::seed_from_u64(seed));
                 env.push(Value::from_row_values_infallible(rows));
             }
+            Primitive::Shuffle => {
+                let arr = env.pop(1)?;
+                let mut indices: Vec<usize> = (0..arr.row_count()).collect();
+                RNG.with(|rng| indices.shuffle(&mut *rng.borrow_mut()));
+                env.push(select_rows(arr, &indices));
+            }
+            Primitive::Sample => {
+                let k = env
+                    .pop(1)?
+                    .as_nat(env, "Sample count must be a natural number")?;
+                let arr = env.pop(2)?;
+                let len = arr.row_count();
+                if k > len {
+                    return Err(env.error(format!(
+                        "Cannot sample {k} rows from an array with {len} rows"
+                    )));
+                }
+                let mut indices: Vec<usize> = (0..len).collect();
+                RNG.with(|rng| indices.partial_shuffle(&mut *rng.borrow_mut(), k));
+                indices.truncate(k);
+                env.push(select_rows(arr, &indices));
+            }
+            Primitive::Choose => choose(env)?,
+            Primitive::Memory => memory(env)?,
+            Primitive::Storage => {
+                let val = env.pop(1)?;
+                env.push(val.storage_name());
+            }
+            Primitive::Store => {
+                let storage = env
+                    .pop(1)?
+                    .as_string(env, "Storage type must be a string")?;
+                let val = env.pop(2)?;
+                env.push(val.store(&storage, env)?);
+            }
+            Primitive::Uuid => {
+                let mut bytes = [0u8; 16];
+                secure_random_bytes(&mut bytes, env)?;
+                // Set the version and variant bits
+                bytes[6] = (bytes[6] & 0x0f) | 0x40;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                let mut uuid = String::with_capacity(36);
+                for (i, b) in bytes.iter().enumerate() {
+                    if let 4 | 6 | 8 | 10 = i {
+                        uuid.push('-');
+                    }
+                    uuid.push_str(&format!("{b:02x}"));
+                }
+                env.push(uuid);
+            }
+            Primitive::Entropy => {
+                let n = env
+                    .pop(1)?
+                    .as_nat(env, "Byte count must be a natural number")?;
+                algorithm::validate_size::<u8>([n], env)?;
+                let mut bytes = vec![0u8; n];
+                secure_random_bytes(&mut bytes, env)?;
+                env.push(Array::<u8>::from_iter(bytes));
+            }
+            Primitive::Hash => {
+                let val = env.pop(1)?;
+                let mut hasher = StableHasher::default();
+                val.hash(&mut hasher);
+                env.push(format!("{:016x}", hasher.finish()));
+            }
+            Primitive::Embed => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let bytes =
+                    read_asset(&*env.rt.backend, path.as_ref()).map_err(|e| env.error(e))?;
+                env.push(Array::<u8>::from_iter(bytes));
+            }
+            Primitive::Label => {
+                let val = env.pop(1)?;
+                env.push(val.meta().label.as_deref().unwrap_or_default().to_string());
+            }
+            Primitive::Relabel => {
+                let label = env.pop(1)?.as_string(env, "Label must be a string")?;
+                let mut val = env.pop(2)?;
+                val.meta_mut().label = (!label.is_empty()).then(|| label.into());
+                env.push(val);
+            }
             Primitive::Tag => {
                 static NEXT_TAG: AtomicUsize = AtomicUsize::new(0);
                 let tag = NEXT_TAG.fetch_add(1, atomic::Ordering::Relaxed);
@@ -822,14 +1013,92 @@ impl Primitive {
             Primitive::Regex => regex(env)?,
             Primitive::Json => env.monadic_ref_env(Value::to_json_string)?,
             Primitive::Csv => env.monadic_ref_env(Value::to_csv)?,
+            Primitive::Xml => env.monadic_ref_env(Value::to_xml_string)?,
+            Primitive::Toml => env.monadic_ref_env(Value::to_toml_string)?,
+            Primitive::Yaml => env.monadic_ref_env(Value::to_yaml_string)?,
+            Primitive::Url => env.monadic_ref_env(Value::to_url_string)?,
+            Primitive::Percent => env.monadic_ref_env(Value::percent_encode)?,
+            Primitive::Ip => env.monadic_ref_env(Value::to_ip_string)?,
+            Primitive::Html => env.monadic_ref_env(Value::to_html_table)?,
+            Primitive::Markdown => env.monadic_ref_env(Value::to_markdown_table)?,
+            Primitive::Eval => algorithm::eval(env)?,
+            Primitive::Ast => {
+                let code = env.pop(1)?.as_string(env, "Code must be a string")?;
+                let ast = Value::from_code_ast(&code, env)?;
+                env.push(ast);
+            }
             Primitive::Xlsx => {
                 env.monadic_ref_env(|value, env| value.to_xlsx(env).map(EcoVec::from))?
             }
+            Primitive::Cbor => {
+                env.monadic_ref_env(|value, env| value.to_cbor(env).map(EcoVec::from))?
+            }
             Primitive::Astar => algorithm::astar(env)?,
             Primitive::Fft => algorithm::fft(env)?,
+            Primitive::Synth => algorithm::dsp::synth(env)?,
+            Primitive::Diff => algorithm::diff::diff(env)?,
+            Primitive::Validate => algorithm::schema::validate(env)?,
+            Primitive::Cidr => algorithm::ip::cidr(env)?,
+            Primitive::Subnets => algorithm::ip::subnets(env)?,
+            Primitive::Cross => algorithm::vector::cross(env)?,
+            Primitive::Norm => algorithm::vector::norm(env)?,
+            Primitive::Normalize => algorithm::vector::normalize(env)?,
+            Primitive::Distances => algorithm::vector::distances(env)?,
+            Primitive::Components => algorithm::graph::components(env)?,
+            Primitive::Bfs => algorithm::graph::bfs(env)?,
+            Primitive::Dfs => algorithm::graph::dfs(env)?,
+            Primitive::Dijkstra => algorithm::graph::dijkstra(env)?,
+            Primitive::Toposort => algorithm::graph::toposort(env)?,
+            Primitive::Colormap => algorithm::colormap(env)?,
+            Primitive::Resize => algorithm::image::resize(env)?,
+            Primitive::Crop => algorithm::image::crop(env)?,
+            Primitive::Turn => algorithm::image::turn(env)?,
+            Primitive::Warp => algorithm::image::warp(env)?,
+            Primitive::Erode => algorithm::image::erode(env)?,
+            Primitive::Dilate => algorithm::image::dilate(env)?,
+            Primitive::MedianFilter => algorithm::image::median_filter(env)?,
+            Primitive::Blur => algorithm::image::blur(env)?,
+            Primitive::Regions => algorithm::image::regions(env)?,
+            Primitive::FloodFill => algorithm::image::flood_fill(env)?,
+            Primitive::Contours => algorithm::image::contours(env)?,
+            Primitive::Draw => algorithm::image::draw(env)?,
+            Primitive::Text => algorithm::image::text(env)?,
+            Primitive::Haversine => algorithm::geo::haversine(env)?,
+            Primitive::Bearing => algorithm::geo::bearing(env)?,
+            Primitive::Mercator => algorithm::geo::mercator(env)?,
+            Primitive::InPolygon => algorithm::geo::in_polygon(env)?,
+            Primitive::Unit => algorithm::quantity::unit(env)?,
+            Primitive::Interval => algorithm::interval::interval(env)?,
+            Primitive::Ode => algorithm::ode::ode(env)?,
+            Primitive::PolyVal => algorithm::poly::polyval(env)?,
+            Primitive::PolyMul => algorithm::poly::polymul(env)?,
+            Primitive::PolyDiv => algorithm::poly::polydiv(env)?,
+            Primitive::PolyRoots => algorithm::poly::polyroots(env)?,
+            Primitive::PolyFit => algorithm::poly::polyfit(env)?,
+            Primitive::Pdf => algorithm::distribution::pdf(env)?,
+            Primitive::Cdf => algorithm::distribution::cdf(env)?,
+            Primitive::Quantile => algorithm::distribution::quantile(env)?,
+            Primitive::Mean => algorithm::stats::mean(env)?,
+            Primitive::Variance => algorithm::stats::variance(env)?,
+            Primitive::StdDev => algorithm::stats::stddev(env)?,
+            Primitive::Skewness => algorithm::stats::skewness(env)?,
+            Primitive::Mode => algorithm::stats::mode(env)?,
+            Primitive::ZScore => algorithm::stats::zscore(env)?,
+            Primitive::Template => algorithm::template::template(env)?,
+            Primitive::TextTable => algorithm::text_table::text_table(env)?,
+            Primitive::Convolve => {
+                let boundary = env.pop(1)?;
+                let kernel = env.pop(2)?;
+                let xs = env.pop(3)?;
+                env.push(xs.convolve(kernel, boundary, env)?);
+            }
+            Primitive::Translate => algorithm::translate::translate(env)?,
             Primitive::Stringify
             | Primitive::Quote
             | Primitive::Sig
+            | Primitive::Reflect
+            | Primitive::Dispatch
+            | Primitive::Obverse
             | Primitive::Comptime
             | Primitive::Dip
             | Primitive::On
@@ -853,6 +1122,11 @@ impl Primitive {
 }
 
 impl ImplPrimitive {
+    /// Check if this primitive's only impurity is using the random number generator
+    pub(crate) fn is_random(&self) -> bool {
+        use ImplPrimitive::*;
+        matches!(self, ReplaceRand | ReplaceRand2 | EndRandArray)
+    }
     pub(crate) fn run(&self, env: &mut Uiua) -> UiuaResult {
         match self {
             ImplPrimitive::UnPop => {
@@ -939,6 +1213,57 @@ impl ImplPrimitive {
             ImplPrimitive::UndoGroup1 => loops::undo_group_part1(env)?,
             ImplPrimitive::UndoGroup2 => loops::undo_group_part2(env)?,
             ImplPrimitive::TryClose => _ = SysOp::Close.run(env),
+            ImplPrimitive::UndoFReadAll => {
+                crate::sys::write_all(env, crate::sys::WriteMode::Replace)?
+            }
+            ImplPrimitive::Destructure => {
+                let names = env.pop(1)?;
+                let val = env.pop(2)?;
+                let count = names.row_count();
+                if val.map_keys().is_some() {
+                    // Get the fields of a map by name
+                    let mut fields = Vec::with_capacity(count);
+                    for name in names.into_rows() {
+                        let key = if val.has_key(&name, env)? {
+                            name
+                        } else {
+                            name.unboxed()
+                        };
+                        if !val.has_key(&key, env)? {
+                            return Err(env.error(format!("Map has no field {}", key.format())));
+                        }
+                        fields.push(val.get(&key, env)?);
+                    }
+                    env.push(Value::from_row_values(fields, env)?);
+                } else if val.rank() == 0 {
+                    return Err(
+                        env.error(format!("Cannot destructure a scalar into {count} names"))
+                    );
+                } else if val.row_count() != count {
+                    return Err(env.error(format!(
+                        "Cannot destructure an array with {} rows into {count} names",
+                        val.row_count()
+                    )));
+                } else {
+                    env.push(val);
+                }
+            }
+            ImplPrimitive::DispatchIndex => {
+                let names = env.pop(1)?;
+                let name =
+                    (env.pop("dispatch name")?).as_string(env, "Dispatch name must be a string")?;
+                let names: Vec<String> = (names.into_rows())
+                    .map(|row| row.unboxed().as_string(env, ""))
+                    .collect::<UiuaResult<_>>()?;
+                let Some(index) = names.iter().position(|n| *n == name) else {
+                    return Err(env.error(format!(
+                        "No function named `{name}` to dispatch to. \
+                        Available functions are {}",
+                        names.join(", ")
+                    )));
+                };
+                env.push(index);
+            }
             ImplPrimitive::UndoJoin => {
                 let a_shape = env.pop(1)?;
                 let b_shape = env.pop(2)?;
@@ -984,6 +1309,8 @@ impl ImplPrimitive {
             ImplPrimitive::UnFix => env.monadic_mut_env(Value::unfix)?,
             ImplPrimitive::UndoFix => env.monadic_mut(Value::undo_fix)?,
             ImplPrimitive::UnScan => reduce::unscan(env)?,
+            ImplPrimitive::UnWindows => env.dyadic_ro_env(Value::unwindows)?,
+            ImplPrimitive::UndoWindows => env.dyadic_ro_env(Value::undo_windows)?,
             ImplPrimitive::TraceN(n, inverse) => trace_n(env, *n, *inverse)?,
             ImplPrimitive::UnStack => stack(env, true)?,
             ImplPrimitive::UnDump => dump(env, true)?,
@@ -997,6 +1324,29 @@ impl ImplPrimitive {
                 let val = Value::from_json_string(&json, env)?;
                 env.push(val);
             }
+            ImplPrimitive::UnXml => {
+                let xml = env.pop(1)?.as_string(env, "XML expects a string")?;
+                let val = Value::from_xml_string(&xml, env)?;
+                env.push(val);
+            }
+            ImplPrimitive::UnToml => {
+                let toml = env.pop(1)?.as_string(env, "TOML expects a string")?;
+                let val = Value::from_toml_string(&toml, env)?;
+                env.push(val);
+            }
+            ImplPrimitive::UnYaml => {
+                let yaml = env.pop(1)?.as_string(env, "YAML expects a string")?;
+                let val = Value::from_yaml_string(&yaml, env)?;
+                env.push(val);
+            }
+            ImplPrimitive::UnUrl => {
+                let url = env.pop(1)?.as_string(env, "URL expects a string")?;
+                let val = Value::from_url_string(&url, env)?;
+                env.push(val);
+            }
+            ImplPrimitive::UnPercent => env.monadic_ref_env(Value::percent_decode)?,
+            ImplPrimitive::UnIp => env.monadic_ref_env(Value::parse_ip)?,
+            ImplPrimitive::UnAst => env.monadic_ref_env(Value::to_code_ast)?,
             ImplPrimitive::UnCsv => {
                 let csv = env.pop(1)?.as_string(env, "CSV expects a string")?;
                 let val = Value::from_csv(&csv, env)?;
@@ -1007,7 +1357,15 @@ impl ImplPrimitive {
                 let val = Value::from_xlsx(&xlsx, env)?;
                 env.push(val);
             }
+            ImplPrimitive::UnCbor => {
+                let cbor = env.pop(1)?.as_bytes(env, "CBOR expects bytes")?;
+                let val = Value::from_cbor(&cbor, env)?;
+                env.push(val);
+            }
             ImplPrimitive::UnFft => algorithm::unfft(env)?,
+            ImplPrimitive::UnMercator => algorithm::geo::unmercator(env)?,
+            ImplPrimitive::UnUnit => algorithm::quantity::unit_inverse(env)?,
+            ImplPrimitive::UnInterval => algorithm::interval::uninterval(env)?,
             ImplPrimitive::UndoInsert => {
                 let key = env.pop(1)?;
                 let _value = env.pop(2)?;
@@ -1057,6 +1415,17 @@ impl ImplPrimitive {
             ImplPrimitive::AstarFirst => algorithm::astar_first(env)?,
             &ImplPrimitive::ReduceDepth(depth) => reduce::reduce(depth, env)?,
             &ImplPrimitive::TransposeN(n) => env.monadic_mut(|val| val.transpose_depth(0, n))?,
+            &ImplPrimitive::View(chain) => {
+                let val = env.pop(1)?;
+                if let Some(val) = val.view_chain(chain) {
+                    env.push(val);
+                } else {
+                    env.push(val);
+                    for op in chain.ops() {
+                        op.run(env)?;
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -1113,6 +1482,181 @@ pub fn seed_random(seed: u64) {
     RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(seed));
 }
 
+/// Draw a seed for another random number generator from this thread's
+pub(crate) fn random_seed() -> u64 {
+    RNG.with(|rng| rng.borrow_mut().gen())
+}
+
+/// Call a function with the random number generator seeded, restoring its previous state afterward
+pub(crate) fn with_random_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let prev = RNG.with(|rng| rng.replace(SmallRng::seed_from_u64(seed)));
+    let res = f();
+    RNG.with(|rng| *rng.borrow_mut() = prev);
+    res
+}
+
+/// Reseed the random number generator from itself, returning the new seed
+pub(crate) fn reseed_random() -> u64 {
+    RNG.with(|rng| {
+        let mut rng = rng.borrow_mut();
+        let seed = rng.gen();
+        *rng = SmallRng::seed_from_u64(seed);
+        seed
+    })
+}
+
+fn memory(env: &mut Uiua) -> UiuaResult {
+    let values: Vec<usize> = env.stack().iter().rev().map(Value::byte_size).collect();
+    let stack: usize = values.iter().sum();
+    let temp: usize = env.temp_stack_values().map(Value::byte_size).sum();
+    let constants: usize = (env.asm.bindings.iter())
+        .filter_map(|binding| match &binding.kind {
+            BindingKind::Const(Some(value)) => Some(value.byte_size()),
+            _ => None,
+        })
+        .sum();
+    let keys: Array<Boxed> = ["stack", "temp", "constants", "total", "values"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut info: Value = Array::<Boxed>::from_iter([
+        Boxed(stack.into()),
+        Boxed(temp.into()),
+        Boxed(constants.into()),
+        Boxed((stack + temp + constants).into()),
+        Boxed(
+            values
+                .into_iter()
+                .map(|n| n as f64)
+                .collect::<Array<f64>>()
+                .into(),
+        ),
+    ])
+    .into();
+    info.map(keys.into(), env)?;
+    env.push(info);
+    Ok(())
+}
+
+/// Fill a buffer with bytes from the operating system's secure random source
+fn secure_random_bytes(bytes: &mut [u8], env: &Uiua) -> UiuaResult {
+    rand::rngs::OsRng
+        .try_fill_bytes(bytes)
+        .map_err(|e| env.error(format!("Failed to generate secure random bytes: {e}")))
+}
+
+/// A 64-bit FNV-1a hasher whose output does not depend on the platform
+///
+/// Integers are always written as little-endian, and `usize`s are widened to 64 bits.
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes());
+    }
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+    fn write_i16(&mut self, n: i16) {
+        self.write_u16(n as u16);
+    }
+    fn write_i32(&mut self, n: i32) {
+        self.write_u32(n as u32);
+    }
+    fn write_i64(&mut self, n: i64) {
+        self.write_u64(n as u64);
+    }
+    fn write_i128(&mut self, n: i128) {
+        self.write_u128(n as u128);
+    }
+    fn write_isize(&mut self, n: isize) {
+        self.write_u64(n as u64);
+    }
+}
+
+fn select_rows(arr: Value, indices: &[usize]) -> Value {
+    let rows: Vec<Value> = indices.iter().map(|&i| arr.row(i)).collect();
+    if rows.is_empty() {
+        arr.first_dim_zero()
+    } else {
+        Value::from_row_values_infallible(rows)
+    }
+}
+
+/// Weighted random choice using Vose's alias method
+fn choose(env: &mut Uiua) -> UiuaResult {
+    let k = env
+        .pop(1)?
+        .as_nat(env, "Choice count must be a natural number")?;
+    algorithm::validate_size::<f64>([k], env)?;
+    let weights = env
+        .pop(2)?
+        .as_nums(env, "Weights must be a list of numbers")?;
+    if weights.iter().any(|&w| !(w >= 0.0 && w.is_finite())) {
+        return Err(env.error("Weights must be non-negative and finite"));
+    }
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return Err(env.error("Weights must have a positive sum"));
+    }
+    // Build the probability and alias tables
+    let n = weights.len();
+    let mut prob: Vec<f64> = weights.iter().map(|&w| w * n as f64 / total).collect();
+    let mut alias: Vec<usize> = (0..n).collect();
+    let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| prob[i] < 1.0);
+    while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+        alias[s] = l;
+        prob[l] -= 1.0 - prob[s];
+        if prob[l] < 1.0 {
+            large.pop();
+            small.push(l);
+        }
+    }
+    // Leftovers are only off from 1 by rounding error
+    for i in small.into_iter().chain(large) {
+        prob[i] = 1.0;
+    }
+    let indices: EcoVec<f64> = RNG.with(|rng| {
+        let mut rng = rng.borrow_mut();
+        (0..k)
+            .map(|_| {
+                let i = rng.gen_range(0..n);
+                let chosen = if rng.gen::<f64>() < prob[i] {
+                    i
+                } else {
+                    alias[i]
+                };
+                chosen as f64
+            })
+            .collect()
+    });
+    env.push(indices);
+    Ok(())
+}
+
 fn trace(env: &mut Uiua, inverse: bool) -> UiuaResult {
     let val = env.pop(1)?;
     let span: String = if inverse {
@@ -1590,8 +2134,9 @@ mod tests {
             for line in &prim.doc().lines {
                 if let PrimDocLine::Example(ex) = line {
                     if [
-                        "&sl", "&tcpc", "&tlsc", "&ast", "&clset", "&fo", "&fc", "&fde", "&ftr",
-                        "&fld", "&fif", "&fras",
+                        "&sl", "&tcpc", "&tlsc", "&smtp", "&sec", "&shm", "&kv", "&osc", "&mid",
+                        "&ast", "&asyn", "&clset", "&fo", "&fc", "&fde", "&ftr", "&fld", "&fif",
+                        "&fras", "&ffl", "&ffc",
                     ]
                     .iter()
                     .any(|prim| ex.input.contains(prim))
@@ -1687,6 +2232,15 @@ mod tests {
         assert_eq!(Primitive::from_format_name_multi("foo"), None);
     }
 
+    #[test]
+    fn multinames_are_not_shadowed() {
+        // These used to format as multiple primitives,
+        // so no primitive can take them as its name
+        let input = "filltake\nfilldrop\ngroupmap\npartitionmap\ngroupreduce\ndiff";
+        let output = crate::format::format_str(input, &Default::default()).unwrap();
+        assert_eq!(output.output, "⬚↙\n⬚↘\n⊕map\n⊜map\n⊕/\n⊙∘⊃¤\n");
+    }
+
     #[cfg(test)]
     #[test]
     fn gen_grammar_file() {
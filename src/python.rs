@@ -0,0 +1,226 @@
+//! Python bindings for Uiua
+//!
+//! Enable the `python` feature and build the library as a Python extension module with
+//! ```sh
+//! cargo rustc --lib --release --features python --crate-type cdylib
+//! ```
+//! Then rename the built library to `uiua.so` (`uiua.pyd` on Windows) and place it where Python can import it.
+//!
+//! ```python
+//! import numpy as np
+//! import uiua
+//!
+//! rt = uiua.Uiua()
+//! rt.push(np.arange(12.0).reshape(3, 4))
+//! rt.run("/+")
+//! print(rt.pop()) # [12. 15. 18. 21.]
+//!
+//! rt.bind("Double", 1, 1, lambda x: x * 2)
+//! print(uiua.run("Double ⇡3")) # [array([0., 2., 4.])]
+//! ```
+//!
+//! Numeric arrays are converted to and from NumPy arrays of `float64`.
+//! Character arrays of rank 0 or 1 are converted to and from strings.
+//! Lists of boxes are converted to and from Python lists.
+
+use ecow::EcoVec;
+use numpy::{
+    ndarray::{ArrayD, IxDyn},
+    Complex64, IntoPyArray, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods,
+};
+use pyo3::{
+    exceptions::PyRuntimeError,
+    prelude::*,
+    types::{PyComplex, PyList, PyString, PyTuple},
+};
+
+use crate::{Array, Boxed, Compiler, Shape, Uiua, UiuaError, Value};
+
+fn py_err(e: UiuaError) -> PyErr {
+    PyRuntimeError::new_err(e.report().color(false).to_string())
+}
+
+/// Convert a Uiua value into a Python object
+fn value_to_py<'py>(py: Python<'py>, value: Value) -> PyResult<Bound<'py, PyAny>> {
+    let shape: Vec<usize> = value.shape().iter().copied().collect();
+    let array = |data: Vec<f64>| -> PyResult<Bound<'py, PyAny>> {
+        if shape.is_empty() {
+            return Ok(data[0].into_pyobject(py)?.into_any());
+        }
+        let array = ArrayD::from_shape_vec(IxDyn(&shape), data)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(array.into_pyarray(py).into_any())
+    };
+    match value {
+        Value::Num(arr) => array(arr.data.into_iter().collect()),
+        Value::Byte(arr) => array(arr.data.into_iter().map(|b| b as f64).collect()),
+        Value::Int(arr) => array(arr.data.into_iter().map(|i| i as f64).collect()),
+        Value::Float(arr) => array(arr.data.into_iter().map(|f| f as f64).collect()),
+        Value::Complex(arr) => {
+            if shape.is_empty() {
+                let c = arr.data[0];
+                return Ok(PyComplex::from_doubles(py, c.re, c.im).into_any());
+            }
+            let data: Vec<Complex64> = (arr.data.into_iter())
+                .map(|c| Complex64::new(c.re, c.im))
+                .collect();
+            let array = ArrayD::from_shape_vec(IxDyn(&shape), data)
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            Ok(array.into_pyarray(py).into_any())
+        }
+        Value::Char(arr) if arr.rank() <= 1 => {
+            let s: String = arr.data.into_iter().collect();
+            Ok(PyString::new(py, &s).into_any())
+        }
+        Value::Box(arr) if arr.rank() == 0 => value_to_py(py, arr.data[0].0.clone()),
+        Value::Box(arr) if arr.rank() == 1 => {
+            let items = (arr.data.into_iter())
+                .map(|Boxed(val)| value_to_py(py, val))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, items)?.into_any())
+        }
+        value => Err(PyRuntimeError::new_err(format!(
+            "Cannot convert {} array of shape {} to a Python object",
+            value.type_name(),
+            value.shape()
+        ))),
+    }
+}
+
+/// Convert a Python object into a Uiua value
+fn py_to_value(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if let Ok(s) = obj.cast::<PyString>() {
+        return Ok(s.to_str()?.into());
+    }
+    if let Ok(n) = obj.extract::<f64>() {
+        return Ok(n.into());
+    }
+    if let Ok(arr) = obj.cast::<PyArrayDyn<f64>>() {
+        let shape = Shape::from(arr.shape());
+        let data: EcoVec<f64> = arr.readonly().as_array().iter().copied().collect();
+        return Ok(Array::new(shape, data).into());
+    }
+    if let Ok(arr) = obj.cast::<PyArrayDyn<i64>>() {
+        let shape = Shape::from(arr.shape());
+        let data: EcoVec<f64> = (arr.readonly().as_array().iter())
+            .map(|&n| n as f64)
+            .collect();
+        return Ok(Array::new(shape, data).into());
+    }
+    if let Ok(list) = obj.cast::<PyList>() {
+        let items = (list.iter())
+            .map(|item| py_to_value(&item).map(Boxed))
+            .collect::<PyResult<Array<Boxed>>>()?;
+        return Ok(items.into());
+    }
+    Err(PyRuntimeError::new_err(format!(
+        "Cannot convert {} to a Uiua value",
+        obj.get_type().name()?
+    )))
+}
+
+/// A Uiua runtime
+///
+/// Bindings defined by one call to `run` are available in later calls.
+#[pyclass(name = "Uiua", unsendable)]
+pub struct PyUiua {
+    uiua: Uiua,
+    comp: Compiler,
+}
+
+#[pymethods]
+impl PyUiua {
+    #[new]
+    fn new() -> Self {
+        PyUiua {
+            uiua: Uiua::with_safe_sys(),
+            comp: Compiler::new(),
+        }
+    }
+    /// Compile and run some code on the runtime's stack
+    fn run(&mut self, code: &str) -> PyResult<()> {
+        self.comp.load_str(code).map_err(py_err)?;
+        self.uiua.run_compiler(&mut self.comp).map_err(py_err)
+    }
+    /// Push a value onto the stack
+    fn push(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.uiua.push(py_to_value(value)?);
+        Ok(())
+    }
+    /// Pop a value from the stack
+    fn pop<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let value = self.uiua.pop("value").map_err(py_err)?;
+        value_to_py(py, value)
+    }
+    /// Get the values on the stack, with the top of the stack last
+    fn stack<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        (self.uiua.stack().iter())
+            .map(|val| value_to_py(py, val.clone()))
+            .collect()
+    }
+    /// Bind a Python function to a name that can be called from Uiua code
+    ///
+    /// The function is called with `args` values, the first being the top of the stack.
+    /// If `outputs` is greater than 1, the function must return a tuple.
+    fn bind(&mut self, name: &str, args: usize, outputs: usize, f: Py<PyAny>) -> PyResult<()> {
+        self.comp
+            .create_bind_function(name, (args, outputs), move |env| {
+                Python::attach(|py| {
+                    let mut vals = Vec::with_capacity(args);
+                    for i in 0..args {
+                        let val = env.pop(i + 1)?;
+                        vals.push(value_to_py(py, val).map_err(|e| env.error(e.to_string()))?);
+                    }
+                    let args = PyTuple::new(py, vals).map_err(|e| env.error(e.to_string()))?;
+                    let res = (f.call1(py, args)).map_err(|e| env.error(e.to_string()))?;
+                    let res = res.bind(py);
+                    let results = if outputs == 1 {
+                        vec![res.clone()]
+                    } else if outputs == 0 {
+                        Vec::new()
+                    } else {
+                        let tuple = (res.cast::<PyTuple>())
+                            .map_err(|_| env.error("Python function must return a tuple"))?;
+                        tuple.iter().collect()
+                    };
+                    if results.len() != outputs {
+                        return Err(env.error(format!(
+                            "Python function returned {} values, but {outputs} were expected",
+                            results.len()
+                        )));
+                    }
+                    for res in results.into_iter().rev() {
+                        let val = py_to_value(&res).map_err(|e| env.error(e.to_string()))?;
+                        env.push(val);
+                    }
+                    Ok(())
+                })
+            })
+            .map_err(py_err)
+    }
+}
+
+/// Run some code with the given arguments, returning the stack with the top of the stack first
+#[pyfunction]
+#[pyo3(signature = (code, *args))]
+fn run<'py>(
+    py: Python<'py>,
+    code: &str,
+    args: &Bound<'py, PyTuple>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let mut rt = PyUiua::new();
+    for arg in args.iter().rev() {
+        rt.push(&arg)?;
+    }
+    rt.run(code)?;
+    let mut stack = rt.stack(py)?;
+    stack.reverse();
+    Ok(stack)
+}
+
+#[pymodule(name = "uiua")]
+fn uiua_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyUiua>()?;
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    Ok(())
+}
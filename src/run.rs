@@ -3,7 +3,7 @@
 use std::{
     cell::RefCell,
     cmp::Ordering,
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     fmt,
     hash::Hash,
     mem::{size_of, take},
@@ -19,6 +19,7 @@ use std::{
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use enum_iterator::{all, Sequence};
 use instant::Duration;
+use serde::{Deserialize, Serialize};
 use thread_local::ThreadLocal;
 
 use crate::{
@@ -29,9 +30,9 @@ use crate::{
     function::*,
     lex::Span,
     value::Value,
-    Assembly, BindingKind, CodeSpan, Compiler, Complex, Ident, Inputs, IntoSysBackend, LocalName,
-    Primitive, SafeSys, SysBackend, SysOp, TraceFrame, UiuaError, UiuaErrorKind, UiuaResult,
-    VERSION,
+    Assembly, BindingKind, CodeSpan, Compiler, Complex, Coverage, Ident, Inputs, InstrRecord,
+    IntoSysBackend, LocalName, Primitive, SafeSys, Shape, SysBackend, SysOp, TraceFrame, UiuaError,
+    UiuaErrorKind, UiuaResult, ValueSnapshot, VERSION,
 };
 
 /// The Uiua interpreter
@@ -59,14 +60,20 @@ pub(crate) struct Runtime {
     recur_stack: Vec<usize>,
     /// The fill stack
     fill_stack: Vec<Fill>,
+    /// Whether pervasive functions stretch length-1 axes
+    broadcasting: bool,
     /// A limit on the execution duration in milliseconds
     pub(crate) execution_limit: Option<f64>,
     /// The time at which execution started
     pub(crate) execution_start: f64,
+    /// A limit on the size in bytes of any single array
+    pub(crate) max_array_bytes: Option<usize>,
     /// Whether to print the time taken to execute each instruction
     time_instrs: bool,
     /// The time at which the last instruction was executed
     last_time: f64,
+    /// Whether to attach a snapshot of the stack to runtime errors
+    stack_snapshots: bool,
     /// Arguments passed from the command line
     cli_arguments: Vec<String>,
     /// File that was passed to the interpreter for execution
@@ -79,10 +86,86 @@ pub(crate) struct Runtime {
     pub(crate) output_comments: HashMap<usize, Vec<Vec<Value>>>,
     /// Memoized values
     pub(crate) memo: Arc<ThreadLocal<RefCell<MemoMap>>>,
+    /// Functions to call when the program exits
+    pub(crate) exit_hooks: ExitHooks,
+    /// The state of the runtime when the last error occurred
+    error_snapshot: Option<Snapshot>,
+    /// Instruction execution counts, if coverage is enabled
+    coverage: Option<Coverage>,
+    /// Recently executed instructions, if enabled
+    instr_history: Option<InstrHistory>,
+    /// Whether pure functions may be called on many rows in parallel
+    parallel: bool,
+}
+
+/// A bounded record of the most recently executed instructions
+#[derive(Clone)]
+struct InstrHistory {
+    limit: usize,
+    /// Instruction indices and the shapes of the top stack values
+    entries: VecDeque<(usize, Vec<Shape>)>,
+}
+
+impl InstrHistory {
+    /// The number of top stack values whose shapes are recorded
+    const SHAPES: usize = 3;
+    fn new(limit: usize) -> Self {
+        InstrHistory {
+            limit,
+            entries: VecDeque::with_capacity(limit),
+        }
+    }
+    fn record(&mut self, instr: usize, stack: &[Value]) {
+        if self.entries.len() == self.limit {
+            self.entries.pop_front();
+        }
+        let shapes = stack.iter().rev().take(Self::SHAPES);
+        (self.entries).push_back((instr, shapes.map(|val| val.shape().clone()).collect()));
+    }
+    fn records(&self, asm: &Assembly) -> Vec<InstrRecord> {
+        (self.entries.iter())
+            .map(|(i, shapes)| {
+                let instr = &asm.instrs[*i];
+                let span = instr.span().and_then(|span| match &asm.spans[span] {
+                    Span::Code(span) => Some(span.clone()),
+                    Span::Builtin => None,
+                });
+                InstrRecord {
+                    instr: format!("{instr:?}"),
+                    span,
+                    shapes: shapes.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Functions registered with [`SysOp::AtExit`]
+///
+/// These belong to the runtime that registered them,
+/// so they are not copied when it is cloned and are only ever called once.
+#[derive(Default)]
+pub(crate) struct ExitHooks(pub(crate) Vec<Function>);
+
+impl Clone for ExitHooks {
+    fn clone(&self) -> Self {
+        ExitHooks(Vec::new())
+    }
 }
 
 type MemoMap = HashMap<FunctionId, HashMap<Vec<Value>, Vec<Value>>>;
 
+impl Drop for Uiua {
+    fn drop(&mut self) {
+        if self.rt.exit_hooks.0.is_empty() {
+            return;
+        }
+        if let Err(e) = self.run_exit_hooks() {
+            _ = (self.rt.backend).print_str_stderr(&format!("{}\n", e.report()));
+        }
+    }
+}
+
 impl AsRef<Assembly> for Uiua {
     fn as_ref(&self) -> &Assembly {
         &self.asm
@@ -173,9 +256,38 @@ impl FromStr for RunMode {
     }
 }
 
+/// A saved copy of a runtime's state
+///
+/// Created with [`Uiua::snapshot`] and restored with [`Uiua::restore`].
+/// It contains the stack, the values of bindings, and the state of the random number generator.
+/// Functions on the function stack and in progress are not saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    stack: Vec<Value>,
+    bindings: Vec<(usize, Value)>,
+    rng_seed: u64,
+}
+
+impl Snapshot {
+    /// Get the saved stack, bottom first
+    pub fn stack(&self) -> &[Value] {
+        &self.stack
+    }
+    /// Serialize the snapshot to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+    /// Deserialize a snapshot from JSON
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
+
 #[derive(Clone)]
 struct Fill {
     value: Value,
+    /// A function that computes fill values from their positions
+    func: Option<Function>,
     removed: Arc<AtomicBool>,
 }
 
@@ -205,16 +317,24 @@ impl Default for Runtime {
             }],
             recur_stack: Vec::new(),
             fill_stack: Vec::new(),
+            broadcasting: false,
             backend: Arc::new(SafeSys::default()),
             time_instrs: false,
             last_time: 0.0,
+            stack_snapshots: false,
             cli_arguments: Vec::new(),
             cli_file_path: PathBuf::new(),
             execution_limit: None,
             execution_start: 0.0,
+            max_array_bytes: None,
             thread: ThisThread::default(),
             output_comments: HashMap::new(),
             memo: Arc::new(ThreadLocal::new()),
+            exit_hooks: ExitHooks::default(),
+            error_snapshot: None,
+            coverage: None,
+            instr_history: None,
+            parallel: true,
         }
     }
 }
@@ -240,8 +360,8 @@ impl Uiua {
         }
     }
     /// Build an assembly
-    pub fn build(self) -> Assembly {
-        self.asm
+    pub fn build(mut self) -> Assembly {
+        take(&mut self.asm)
     }
     /// Get a reference to the system backend
     pub fn backend(&self) -> &dyn SysBackend {
@@ -263,11 +383,164 @@ impl Uiua {
     pub fn take_asm(&mut self) -> Assembly {
         take(&mut self.asm)
     }
+    /// Save the runtime's state
+    ///
+    /// The snapshot can be serialized and later given to [`Uiua::restore`] to continue from this point.
+    ///
+    /// To make the random number generator's state saveable, it is reseeded from itself.
+    /// Random numbers generated after the snapshot is taken are the same as those generated after it is restored.
+    pub fn snapshot(&self) -> Snapshot {
+        let bindings = (self.asm.bindings.iter().enumerate())
+            .filter_map(|(i, binding)| match &binding.kind {
+                BindingKind::Const(Some(val)) => Some((i, val.clone())),
+                _ => None,
+            })
+            .collect();
+        Snapshot {
+            stack: self.rt.stack.clone(),
+            bindings,
+            rng_seed: crate::reseed_random(),
+        }
+    }
+    /// Restore a state saved with [`Uiua::snapshot`]
+    ///
+    /// The stack is replaced with the snapshot's stack.
+    /// The runtime must have the same assembly as the one the snapshot was taken from,
+    /// or at least one that defines the same bindings first.
+    pub fn restore(&mut self, snapshot: Snapshot) -> UiuaResult {
+        for (i, _) in &snapshot.bindings {
+            if !matches!(
+                self.asm.bindings.get(*i).map(|b| &b.kind),
+                Some(BindingKind::Const(_))
+            ) {
+                return Err(self.error(format!(
+                    "Snapshot has a value for binding {i}, \
+                    but the assembly has no constant binding there"
+                )));
+            }
+        }
+        let bindings = self.asm.bindings.make_mut();
+        for (i, val) in snapshot.bindings {
+            bindings[i].kind = BindingKind::Const(Some(val));
+        }
+        self.take_stack();
+        self.rt.stack = snapshot.stack;
+        crate::seed_random(snapshot.rng_seed);
+        Ok(())
+    }
+    /// Take the state of the runtime when the last error occurred
+    ///
+    /// This is only saved if enabled with [`Uiua::with_stack_snapshots`].
+    /// Restoring it with [`Uiua::restore`] allows running more code from the point of failure.
+    pub fn take_error_snapshot(&mut self) -> Option<Snapshot> {
+        self.rt.error_snapshot.take()
+    }
+    /// Create a runtime that can run this one's code on another thread
+    ///
+    /// The new runtime shares this one's compiled assembly, including bound values and interned constants,
+    /// as well as its system backend, command line arguments, and settings.
+    /// Nothing is recompiled, and the assembly's instructions are reference-counted rather than copied.
+    ///
+    /// The new runtime starts with empty stacks, no fills, no child threads, and no [`SysOp::AtExit`] functions.
+    /// Its execution limit, if any, starts counting from when it is created.
+    ///
+    /// Random numbers are generated per thread, so each thread gets its own random sequence.
+    /// [`Value`]s are immutable once shared, so values returned by one runtime can be passed to another.
+    /// The only state that runtimes can affect across threads is whatever the system backend exposes, such as files.
+    pub fn clone_for_thread(&self) -> Self {
+        Uiua {
+            asm: self.asm.clone(),
+            rt: Runtime {
+                time_instrs: self.rt.time_instrs,
+                stack_snapshots: self.rt.stack_snapshots,
+                cli_arguments: self.rt.cli_arguments.clone(),
+                cli_file_path: self.rt.cli_file_path.clone(),
+                backend: self.rt.backend.clone(),
+                execution_limit: self.rt.execution_limit,
+                max_array_bytes: self.rt.max_array_bytes,
+                execution_start: instant::now(),
+                memo: self.rt.memo.clone(),
+                instr_history: (self.rt.instr_history.as_ref())
+                    .map(|history| InstrHistory::new(history.limit)),
+                parallel: self.rt.parallel,
+                ..Runtime::default()
+            },
+        }
+    }
+    /// Create a runtime to call functions on a worker thread as part of this one's execution
+    pub(crate) fn clone_for_worker(&self) -> Self {
+        let mut env = self.clone_for_thread();
+        env.rt.execution_start = self.rt.execution_start;
+        env.rt.recur_stack = self.rt.recur_stack.clone();
+        env.rt.broadcasting = self.rt.broadcasting;
+        env
+    }
+    /// Whether calls to a function on `count` rows should be split across threads
+    ///
+    /// Only functions that leave the temp stacks alone and are pure, other than generating random numbers,
+    /// are called in parallel, and not while anything is recording individual instructions.
+    pub(crate) fn should_call_in_parallel(&self, f: &Function, count: usize) -> bool {
+        const THRESHOLD: usize = 1000;
+        if cfg!(target_arch = "wasm32")
+            || !self.rt.parallel
+            || count <= THRESHOLD
+            || self.rt.time_instrs
+            || self.rt.coverage.is_some()
+            || self.rt.instr_history.is_some()
+        {
+            return false;
+        }
+        let instrs = f.instrs(&self.asm);
+        instrs_temp_signatures(instrs)
+            .is_ok_and(|sigs| sigs.iter().all(|sig| *sig == Signature::new(0, 0)))
+            && instrs_are_pure_or_random(instrs, &self.asm)
+    }
     /// Set whether to emit the time taken to execute each instruction
     pub fn time_instrs(mut self, time_instrs: bool) -> Self {
         self.rt.time_instrs = time_instrs;
         self
     }
+    /// Set whether to attach a snapshot of the stack to runtime errors
+    ///
+    /// The snapshot is available in [`UiuaError::stack_snapshot`] and is shown in the error's report.
+    /// The full state of the runtime is also saved and can be retrieved with [`Uiua::take_error_snapshot`].
+    pub fn with_stack_snapshots(mut self, stack_snapshots: bool) -> Self {
+        self.rt.stack_snapshots = stack_snapshots;
+        self
+    }
+    /// Record the last `limit` instructions executed and the shapes of the values at the top of the stack after each
+    ///
+    /// When an error occurs, the record is available in [`UiuaError::instr_history`] and is shown in the error's report.
+    /// A limit of `0` disables recording.
+    pub fn with_instr_history(mut self, limit: usize) -> Self {
+        self.rt.instr_history = (limit > 0).then(|| InstrHistory::new(limit));
+        self
+    }
+    /// Set whether pure functions may be called on the rows of large arrays in parallel
+    ///
+    /// This is enabled by default. Disabling it can make profiling easier.
+    ///
+    /// When a function that generates random numbers is called on many rows, each row gets its own
+    /// generator seeded from this thread's, whether or not this is enabled.
+    /// This keeps random results from depending on whether or how the rows are split across threads.
+    pub fn with_parallelism(mut self, parallel: bool) -> Self {
+        self.rt.parallel = parallel;
+        self
+    }
+    /// Set whether to count how many times each instruction is executed
+    ///
+    /// The counts can be retrieved with [`Uiua::coverage`].
+    /// Code run on threads spawned by the program is not counted.
+    pub fn with_coverage(mut self, coverage: bool) -> Self {
+        self.rt.coverage = coverage.then(Coverage::default);
+        self
+    }
+    /// Get the instruction execution counts, if enabled with [`Uiua::with_coverage`]
+    ///
+    /// The counts are for the instructions of the current assembly.
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.rt.coverage.as_ref()
+    }
     /// Limit the execution duration
     pub fn with_execution_limit(mut self, limit: Duration) -> Self {
         self.rt.execution_limit = Some(limit.as_millis() as f64);
@@ -338,13 +611,24 @@ impl Uiua {
         fn run_asm(env: &mut Uiua, asm: Assembly) -> UiuaResult {
             env.asm = asm;
             env.rt.execution_start = instant::now();
-            let res = env.run_top_slices();
+            let mut res = env.run_top_slices();
             if res.is_err() {
+                let error_snapshot = env.rt.stack_snapshots.then(|| env.snapshot());
+                // The runtime is about to be reset, so this is the program's exit
+                if let Err(e) = env.run_exit_hooks() {
+                    res = res.map_err(|error| UiuaError::from_multi([error, e]));
+                }
                 env.rt = Runtime {
                     backend: env.rt.backend.clone(),
                     execution_limit: env.rt.execution_limit,
+                    max_array_bytes: env.rt.max_array_bytes,
                     time_instrs: env.rt.time_instrs,
+                    stack_snapshots: env.rt.stack_snapshots,
                     output_comments: env.rt.output_comments.clone(),
+                    error_snapshot,
+                    coverage: env.rt.coverage.take(),
+                    instr_history: (env.rt.instr_history.as_ref())
+                        .map(|history| InstrHistory::new(history.limit)),
                     ..Runtime::default()
                 };
             }
@@ -368,6 +652,25 @@ impl Uiua {
         self.asm.top_slices = top_slices;
         res
     }
+    /// Call the functions registered with [`SysOp::AtExit`], most recent first
+    ///
+    /// Every function is called even if one fails. The first error is returned.
+    /// This is done automatically when the runtime is dropped or an error occurs,
+    /// but calling it explicitly allows the error to be handled and the output to be collected.
+    pub fn run_exit_hooks(&mut self) -> UiuaResult {
+        let mut res = Ok(());
+        while let Some(f) = self.rt.exit_hooks.0.pop() {
+            let height = self.rt.stack.len();
+            let hook_res = self
+                .catching_crash("", |env| env.call(f))
+                .and_then(|res| res);
+            self.truncate_stack(height);
+            if res.is_ok() {
+                res = hook_res;
+            }
+        }
+        res
+    }
     fn catching_crash<T>(
         &mut self,
         input: impl fmt::Display,
@@ -443,6 +746,9 @@ code:
             // }
             // println!("\n    {:?}", instr);
 
+            if let Some(coverage) = &mut self.rt.coverage {
+                coverage.hit(i);
+            }
             if self.rt.time_instrs {
                 formatted_instr = format!("{instr:?}");
                 self.rt.last_time = instant::now();
@@ -671,7 +977,19 @@ code:
                 );
                 self.rt.last_time = instant::now();
             }
-            if let Err(err) = res {
+            if let Some(history) = &mut self.rt.instr_history {
+                history.record(i, &self.rt.stack);
+            }
+            if let Err(mut err) = res {
+                if self.rt.stack_snapshots && err.stack_snapshot.is_none() {
+                    err.stack_snapshot =
+                        Some(self.rt.stack.iter().map(ValueSnapshot::new).collect());
+                }
+                if let Some(history) = &self.rt.instr_history {
+                    if err.instr_history.is_none() {
+                        err.instr_history = Some(history.records(&self.asm));
+                    }
+                }
                 // Trace errors
                 let frame = self.rt.call_stack.pop().unwrap();
                 return Err(self.trace_error(err, frame));
@@ -998,6 +1316,10 @@ code:
     pub fn stack(&self) -> &[Value] {
         &self.rt.stack
     }
+    /// Get the values on the temporary stacks
+    pub(crate) fn temp_stack_values(&self) -> impl Iterator<Item = &Value> {
+        self.rt.temp_stacks.iter().flatten()
+    }
     /// Get a mutable reference to the stack data
     pub fn stack_mut(&mut self) -> &mut [Value] {
         &mut self.rt.stack
@@ -1168,6 +1490,10 @@ code:
             Some(Value::Num(_)) => Err(self.fill_error(true)),
             Some(Value::Byte(n)) if n.rank() == 0 => Ok(n.data[0] as f64),
             Some(Value::Byte(_)) => Err(self.fill_error(true)),
+            Some(Value::Int(n)) if n.rank() == 0 => Ok(n.data[0] as f64),
+            Some(Value::Int(_)) => Err(self.fill_error(true)),
+            Some(Value::Float(n)) if n.rank() == 0 => Ok(n.data[0].into()),
+            Some(Value::Float(_)) => Err(self.fill_error(true)),
             _ => Err(self.fill_error(false)),
         }
     }
@@ -1175,6 +1501,53 @@ code:
         match self.value_fill() {
             Some(Value::Num(n)) => Ok(n.clone()),
             Some(Value::Byte(n)) => Ok(n.convert_ref()),
+            Some(Value::Int(n)) => Ok(n.convert_ref_with(|n| n as f64)),
+            Some(Value::Float(n)) => Ok(n.convert_ref()),
+            _ => Err(self.fill_error(false)),
+        }
+    }
+    pub(crate) fn float_scalar_fill(&self) -> Result<f32, &'static str> {
+        match self.value_fill() {
+            Some(Value::Float(n)) if n.rank() == 0 => Ok(n.data[0]),
+            Some(Value::Float(_)) => Err(self.fill_error(true)),
+            _ => self.num_scalar_fill().map(|n| n as f32),
+        }
+    }
+    pub(crate) fn float_array_fill(&self) -> Result<Array<f32>, &'static str> {
+        match self.value_fill() {
+            Some(Value::Float(n)) => Ok(n.clone()),
+            _ => (self.num_array_fill()).map(|n| n.convert_with(|n| n as f32)),
+        }
+    }
+    pub(crate) fn int_scalar_fill(&self) -> Result<i64, &'static str> {
+        match self.value_fill() {
+            Some(Value::Int(n)) if n.rank() == 0 => Ok(n.data[0]),
+            Some(Value::Int(_)) => Err(self.fill_error(true)),
+            Some(Value::Num(n)) if n.rank() == 0 && n.data[0].fract() == 0.0 => {
+                Ok(n.data[0] as i64)
+            }
+            Some(Value::Num(n)) if n.rank() == 0 => Err(self.fill_error(false)),
+            Some(Value::Num(_)) => Err(self.fill_error(true)),
+            Some(Value::Byte(n)) if n.rank() == 0 => Ok(n.data[0].into()),
+            Some(Value::Byte(_)) => Err(self.fill_error(true)),
+            Some(Value::Float(n)) if n.rank() == 0 && n.data[0].fract() == 0.0 => {
+                Ok(n.data[0] as i64)
+            }
+            Some(Value::Float(n)) if n.rank() == 0 => Err(self.fill_error(false)),
+            Some(Value::Float(_)) => Err(self.fill_error(true)),
+            _ => Err(self.fill_error(false)),
+        }
+    }
+    pub(crate) fn int_array_fill(&self) -> Result<Array<i64>, &'static str> {
+        match self.value_fill() {
+            Some(Value::Int(n)) => Ok(n.clone()),
+            Some(Value::Num(n)) if n.data.iter().all(|n| n.fract() == 0.0) => {
+                Ok(n.convert_ref_with(|n| n as i64))
+            }
+            Some(Value::Byte(n)) => Ok(n.convert_ref()),
+            Some(Value::Float(n)) if n.data.iter().all(|n| n.fract() == 0.0) => {
+                Ok(n.convert_ref_with(|n| n as i64))
+            }
             _ => Err(self.fill_error(false)),
         }
     }
@@ -1191,6 +1564,19 @@ code:
             Some(Value::Num(_)) => Err(self.fill_error(true)),
             Some(Value::Byte(n)) if n.rank() == 0 => Ok(n.data[0]),
             Some(Value::Byte(_)) => Err(self.fill_error(true)),
+            Some(Value::Int(n)) if n.rank() == 0 => {
+                u8::try_from(n.data[0]).map_err(|_| self.fill_error(false))
+            }
+            Some(Value::Int(_)) => Err(self.fill_error(true)),
+            Some(Value::Float(n))
+                if n.rank() == 0
+                    && n.data[0].fract() == 0.0
+                    && (0.0..=255.0).contains(&n.data[0]) =>
+            {
+                Ok(n.data[0] as u8)
+            }
+            Some(Value::Float(n)) if n.rank() == 0 => Err(self.fill_error(false)),
+            Some(Value::Float(_)) => Err(self.fill_error(true)),
             _ => Err(self.fill_error(false)),
         }
     }
@@ -1235,6 +1621,10 @@ code:
             Some(Value::Num(_)) => Err(self.fill_error(true)),
             Some(Value::Byte(n)) if n.rank() == 0 => Ok(Complex::new(n.data[0] as f64, 0.0)),
             Some(Value::Byte(_)) => Err(self.fill_error(true)),
+            Some(Value::Int(n)) if n.rank() == 0 => Ok(Complex::new(n.data[0] as f64, 0.0)),
+            Some(Value::Int(_)) => Err(self.fill_error(true)),
+            Some(Value::Float(n)) if n.rank() == 0 => Ok(Complex::new(n.data[0].into(), 0.0)),
+            Some(Value::Float(_)) => Err(self.fill_error(true)),
             Some(Value::Complex(c)) if c.rank() == 0 => Ok(c.data[0]),
             Some(Value::Complex(_)) => Err(self.fill_error(true)),
             _ => Err(self.fill_error(false)),
@@ -1244,6 +1634,8 @@ code:
         match self.value_fill() {
             Some(Value::Num(n)) => Ok(n.convert_ref()),
             Some(Value::Byte(n)) => Ok(n.convert_ref()),
+            Some(Value::Int(n)) => Ok(n.convert_ref_with(|n| Complex::new(n as f64, 0.0))),
+            Some(Value::Float(n)) => Ok(n.convert_ref()),
             Some(Value::Complex(c)) => Ok(c.clone()),
             _ => Err(self.fill_error(false)),
         }
@@ -1251,16 +1643,27 @@ code:
     pub(crate) fn value_fill(&self) -> Option<&Value> {
         (self.rt.fill_stack.iter().rev())
             .find(|fill| !fill.removed())
+            .filter(|fill| fill.func.is_none())
             .map(|fill| &fill.value)
     }
     pub(crate) fn last_fill(&self) -> Option<&Value> {
-        self.rt.fill_stack.last().map(|fill| &fill.value)
+        (self.rt.fill_stack.last())
+            .filter(|fill| fill.func.is_none())
+            .map(|fill| &fill.value)
+    }
+    /// Get the function set by [`Primitive::LazyFill`] if it is the current fill
+    pub(crate) fn fill_function(&self) -> Option<Function> {
+        (self.rt.fill_stack.iter().rev())
+            .find(|fill| !fill.removed())
+            .and_then(|fill| fill.func.clone())
     }
     fn fill_error(&self, scalar: bool) -> &'static str {
         if scalar {
             match self.value_fill() {
                 Some(Value::Num(_)) => ". A number fill is set, but is is not a scalar.",
-                Some(Value::Byte(_)) => ". A number fill is set, but is is not a scalar.",
+                Some(Value::Byte(_) | Value::Int(_) | Value::Float(_)) => {
+                    ". A number fill is set, but is is not a scalar."
+                }
                 Some(Value::Char(_)) => ". A character fill is set, but is is not a scalar.",
                 Some(Value::Complex(_)) => ". A complex fill is set, but is is not a scalar.",
                 Some(Value::Box(_)) => ". A box fill is set, but is is not a scalar.",
@@ -1269,7 +1672,9 @@ code:
         } else {
             match self.value_fill() {
                 Some(Value::Num(_)) => ". A number fill is set, but the array is not numbers.",
-                Some(Value::Byte(_)) => ". A number fill is set, but the array is not numbers.",
+                Some(Value::Byte(_) | Value::Int(_) | Value::Float(_)) => {
+                    ". A number fill is set, but the array is not numbers."
+                }
                 Some(Value::Char(_)) => {
                     ". A character fill is set, but the array is not characters."
                 }
@@ -1294,6 +1699,22 @@ code:
     ) -> UiuaResult<T> {
         self.rt.fill_stack.push(Fill {
             value,
+            func: None,
+            removed: Arc::new(false.into()),
+        });
+        let res = in_ctx(self);
+        self.rt.fill_stack.pop();
+        res
+    }
+    /// Do something with a fill function set
+    pub(crate) fn with_fill_function<T>(
+        &mut self,
+        func: Function,
+        in_ctx: impl FnOnce(&mut Self) -> UiuaResult<T>,
+    ) -> UiuaResult<T> {
+        self.rt.fill_stack.push(Fill {
+            value: Value::default(),
+            func: Some(func),
             removed: Arc::new(false.into()),
         });
         let res = in_ctx(self);
@@ -1310,6 +1731,21 @@ code:
         self.rt.fill_stack[pos].set_removed(false);
         res
     }
+    /// Whether pervasive functions stretch length-1 axes to match
+    pub(crate) fn broadcasting(&self) -> bool {
+        self.rt.broadcasting
+    }
+    /// Do something with [`Primitive::Broadcast`]ing enabled
+    pub(crate) fn with_broadcasting<T>(
+        &mut self,
+        in_ctx: impl FnOnce(&mut Self) -> UiuaResult<T>,
+    ) -> UiuaResult<T> {
+        let prev = self.rt.broadcasting;
+        self.rt.broadcasting = true;
+        let res = in_ctx(self);
+        self.rt.broadcasting = prev;
+        res
+    }
     pub(crate) fn without_fill_but(
         &mut self,
         n: usize,
@@ -1387,17 +1823,25 @@ code:
                 temp_stacks: [Vec::new(), Vec::new()],
                 array_stack: Vec::new(),
                 fill_stack: Vec::new(),
+                broadcasting: false,
                 recur_stack: self.rt.recur_stack.clone(),
                 call_stack: Vec::new(),
                 time_instrs: self.rt.time_instrs,
                 last_time: self.rt.last_time,
+                stack_snapshots: self.rt.stack_snapshots,
                 cli_arguments: self.rt.cli_arguments.clone(),
                 cli_file_path: self.rt.cli_file_path.clone(),
                 backend: self.rt.backend.clone(),
                 execution_limit: self.rt.execution_limit,
+                max_array_bytes: self.rt.max_array_bytes,
                 execution_start: self.rt.execution_start,
                 output_comments: HashMap::new(),
                 memo: self.rt.memo.clone(),
+                exit_hooks: ExitHooks::default(),
+                error_snapshot: None,
+                coverage: None,
+                instr_history: None,
+                parallel: self.rt.parallel,
                 thread,
             },
         };
@@ -0,0 +1,191 @@
+//! A minimal SMTP client for sending email
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    sync::Arc,
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rustls::{pki_types::ServerName, ClientConfig, ClientConnection, StreamOwned};
+
+use crate::{Email, SmtpSecurity};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+enum Connection {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl Connection {
+    fn stream(&mut self) -> &mut dyn Stream {
+        match self {
+            Connection::Plain(stream) => stream,
+            Connection::Tls(stream) => stream.as_mut(),
+        }
+    }
+    /// Read a possibly multiline reply, returning its code and text
+    fn reply(&mut self) -> Result<(u16, String), String> {
+        let mut text = String::new();
+        loop {
+            let mut line = Vec::new();
+            let mut byte = [0];
+            while !line.ends_with(b"\r\n") {
+                let n = (self.stream().read(&mut byte)).map_err(|e| e.to_string())?;
+                if n == 0 {
+                    return Err("SMTP server closed the connection".into());
+                }
+                line.push(byte[0]);
+            }
+            let line = String::from_utf8_lossy(&line[..line.len() - 2]).into_owned();
+            let code = (line.get(..3))
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(|| format!("Invalid SMTP reply {line:?}"))?;
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(line.get(4..).unwrap_or(""));
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, text));
+            }
+        }
+    }
+    /// Expect a reply with a code in the given class, like `2` for `2xx`
+    fn expect(&mut self, class: u16, after: &str) -> Result<String, String> {
+        let (code, text) = self.reply()?;
+        if code / 100 != class {
+            return Err(format!("SMTP server rejected {after}: {code} {text}"));
+        }
+        Ok(text)
+    }
+    fn command(&mut self, command: &str, class: u16) -> Result<String, String> {
+        self.send(&format!("{command}\r\n"))?;
+        let name = command.split([' ', ':']).next().unwrap_or(command);
+        self.expect(class, name)
+    }
+    fn send(&mut self, data: &str) -> Result<(), String> {
+        let stream = self.stream();
+        stream
+            .write_all(data.as_bytes())
+            .map_err(|e| e.to_string())?;
+        stream.flush().map_err(|e| e.to_string())
+    }
+    fn into_tls(self, host: &str) -> Result<Self, String> {
+        let stream = match self {
+            Connection::Plain(stream) => stream,
+            tls => return Ok(tls),
+        };
+        let root_store =
+            rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let config = ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+        let name = ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+        let conn = ClientConnection::new(Arc::new(config), name).map_err(|e| e.to_string())?;
+        Ok(Connection::Tls(Box::new(StreamOwned::new(conn, stream))))
+    }
+}
+
+/// Get the bare address from an address that may have a display name
+fn bare_address(address: &str) -> &str {
+    match address.rsplit_once('<') {
+        Some((_, rest)) => rest.split('>').next().unwrap_or(rest).trim(),
+        None => address.trim(),
+    }
+}
+
+/// Encode a header value if it is not plain ASCII
+fn header_value(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        value.into()
+    } else {
+        format!("=?UTF-8?B?{}?=", BASE64.encode(value))
+    }
+}
+
+/// Build the message data, with dot-stuffing and a terminating line
+fn message(email: &Email) -> String {
+    let mut data = String::new();
+    data.push_str(&format!("From: {}\r\n", header_value(&email.from)));
+    if !email.to.is_empty() {
+        data.push_str(&format!("To: {}\r\n", email.to.join(", ")));
+    }
+    if !email.cc.is_empty() {
+        data.push_str(&format!("Cc: {}\r\n", email.cc.join(", ")));
+    }
+    data.push_str(&format!("Subject: {}\r\n", header_value(&email.subject)));
+    data.push_str("MIME-Version: 1.0\r\n");
+    data.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+    data.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+    for line in email.body.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push_str(".\r\n");
+    data
+}
+
+pub(crate) fn send(email: &Email) -> Result<(), String> {
+    if email.credentials.is_some() && email.security == SmtpSecurity::None {
+        return Err("Refusing to log in to an SMTP server without TLS".into());
+    }
+    let addresses = [&email.from].into_iter().chain(&email.to);
+    if (addresses.chain(&email.cc).chain(&email.bcc)).any(|address| address.contains(['\r', '\n']))
+    {
+        return Err("Email addresses may not contain line breaks".into());
+    }
+    let addr = email.server_addr();
+    let host = addr
+        .rsplit_once(':')
+        .map_or(addr.as_str(), |(host, _)| host);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let stream = TcpStream::connect(&addr).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_write_timeout(Some(TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut conn = Connection::Plain(stream);
+    if email.security == SmtpSecurity::Tls {
+        conn = conn.into_tls(host)?;
+    }
+    conn.expect(2, "the connection")?;
+    let mut extensions = conn.command("EHLO localhost", 2)?;
+    if email.security == SmtpSecurity::StartTls {
+        conn.command("STARTTLS", 2)?;
+        conn = conn.into_tls(host)?;
+        extensions = conn.command("EHLO localhost", 2)?;
+    }
+    if let Some((user, password)) = &email.credentials {
+        let auth = (extensions.lines())
+            .find_map(|line| line.strip_prefix("AUTH "))
+            .unwrap_or("");
+        if auth.split_whitespace().any(|mech| mech == "PLAIN") {
+            let token = BASE64.encode(format!("\0{user}\0{password}"));
+            conn.command(&format!("AUTH PLAIN {token}"), 2)?;
+        } else {
+            conn.command("AUTH LOGIN", 3)?;
+            conn.command(&BASE64.encode(user), 3)?;
+            conn.send(&format!("{}\r\n", BASE64.encode(password)))?;
+            conn.expect(2, "login")?;
+        }
+    }
+    conn.command(&format!("MAIL FROM:<{}>", bare_address(&email.from)), 2)?;
+    for recipient in email.to.iter().chain(&email.cc).chain(&email.bcc) {
+        conn.command(&format!("RCPT TO:<{}>", bare_address(recipient)), 2)?;
+    }
+    conn.command("DATA", 3)?;
+    conn.send(&message(email))?;
+    conn.expect(2, "the message")?;
+    _ = conn.command("QUIT", 2);
+    Ok(())
+}
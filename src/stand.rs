@@ -1,3 +1,8 @@
+//! Bundle compiled Uiua programs into standalone executables
+//!
+//! A standalone executable is a copy of the interpreter with a serialized [`Assembly`] appended to it.
+//! When the executable starts, it checks itself for an assembly and runs it if there is one.
+
 use std::{env, fs, io, path::Path};
 
 use once_cell::sync::Lazy;
@@ -6,36 +11,82 @@ use crate::{Assembly, Compiler, NativeSys, UiuaResult};
 
 const STAND_DATA_SIGNATURE: &[u8] = b"Uiua standalone";
 
+/// Compile a file and bundle it with the current executable
 pub fn build_exe(root: &Path) -> UiuaResult<Vec<u8>> {
     let asm = Compiler::with_backend(NativeSys).load_file(root)?.finish();
-    // Serialize the files
+    bundle_current_exe(&asm).map_err(|e| crate::UiuaErrorKind::Load(root.into(), e.into()).into())
+}
+
+/// Bundle an assembly with the current executable
+///
+/// If the current executable is itself standalone, its assembly is replaced.
+pub fn bundle_current_exe(asm: &Assembly) -> io::Result<Vec<u8>> {
+    let exe = fs::read(env::current_exe()?)?;
+    Ok(bundle(interpreter_bytes(&exe), asm))
+}
+
+/// Bundle an assembly with the bytes of an interpreter executable
+///
+/// The interpreter must have been built with the `stand` feature.
+pub fn bundle(interpreter: &[u8], asm: &Assembly) -> Vec<u8> {
     let asm_bytes = asm.to_uasm().into_bytes();
-    // Append the files to the current exe
-    let mut bytes = env::current_exe()
-        .and_then(fs::read)
-        .unwrap_or_else(|e| panic!("Unable to read current exe: {e}"));
+    let mut bytes = interpreter.to_vec();
     bytes.extend_from_slice(&asm_bytes);
-    // Append the length of the serialized files and a signature
+    // Append the length of the serialized assembly and a signature
     bytes.extend((asm_bytes.len() as u64).to_le_bytes());
     bytes.extend(STAND_DATA_SIGNATURE);
-    Ok(bytes)
+    bytes
+}
+
+/// Write a standalone executable to a file and make it executable
+pub fn write_exe(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    fs::write(path, bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    Ok(())
 }
 
-fn load_asm() -> io::Result<Option<Assembly>> {
-    // Read the current exe
-    let bytes = fs::read(env::current_exe()?)?;
-    // Check if it is a standalone exe
-    let Some(bytes) = bytes.strip_suffix(STAND_DATA_SIGNATURE) else {
+/// Split executable bytes into the interpreter and the serialized assembly, if there is one
+fn split_exe(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let bytes = bytes.strip_suffix(STAND_DATA_SIGNATURE)?;
+    let len_start = bytes.len().checked_sub(8)?;
+    let (bytes, len_bytes) = bytes.split_at(len_start);
+    let asm_len = u64::from_le_bytes(len_bytes.try_into().unwrap());
+    let start = bytes.len().checked_sub(usize::try_from(asm_len).ok()?)?;
+    Some(bytes.split_at(start))
+}
+
+/// Get the bytes of the interpreter without any bundled assembly
+fn interpreter_bytes(exe: &[u8]) -> &[u8] {
+    split_exe(exe).map_or(exe, |(interpreter, _)| interpreter)
+}
+
+/// Read the assembly bundled with an executable, if there is one
+pub fn read_asm(exe: &[u8]) -> Result<Option<Assembly>, String> {
+    let Some((_, asm_bytes)) = split_exe(exe) else {
         return Ok(None);
     };
-    // Get the length of the serialized files
-    let (bytes, len_bytes) = bytes.split_at(bytes.len() - 8);
-    let asm_len = u64::from_le_bytes(len_bytes.try_into().unwrap());
-    let start = bytes.len() - asm_len as usize;
-    // Deserialize the files
-    let uasm: String = std::str::from_utf8(&bytes[start..]).unwrap().to_string();
-    let asm = Assembly::from_uasm(&uasm).unwrap();
-    Ok(Some(asm))
+    let uasm = std::str::from_utf8(asm_bytes)
+        .map_err(|e| format!("Bundled assembly is not valid UTF-8: {e}"))?;
+    Assembly::from_uasm(uasm).map(Some)
+}
+
+fn load_asm() -> Result<Option<Assembly>, String> {
+    let exe = env::current_exe()
+        .and_then(fs::read)
+        .map_err(|e| format!("Unable to read current exe: {e}"))?;
+    read_asm(&exe)
 }
 
-pub static STAND_ASM: Lazy<Option<Assembly>> = Lazy::new(|| load_asm().unwrap());
+/// The assembly bundled with the current executable, if there is one
+pub static STAND_ASM: Lazy<Option<Assembly>> = Lazy::new(|| {
+    load_asm().unwrap_or_else(|e| {
+        eprintln!("Failed to load standalone program: {e}");
+        None
+    })
+});
@@ -18,8 +18,11 @@ use parking_lot::Mutex;
 use serde::*;
 
 use crate::{
-    algorithm::validate_size, cowslice::cowslice, primitive::PrimDoc, Array, Boxed, FfiType,
-    Purity, Signature, Uiua, UiuaResult, Value,
+    algorithm::{dsp::Synth, validate_size},
+    cowslice::cowslice,
+    osc::{self, OscArg, OscMessage},
+    primitive::PrimDoc,
+    Array, Boxed, FfiType, Function, Purity, Signature, Uiua, UiuaResult, Value,
 };
 
 /// The text of Uiua's example module
@@ -52,6 +55,19 @@ pub fn example_txt<T>(f: impl FnOnce(&mut String) -> T) -> T {
     f(&mut S.lock())
 }
 
+/// Read a file for `embed`, falling back to the built-in example files
+pub(crate) fn read_asset(backend: &dyn SysBackend, path: &Path) -> Result<Vec<u8>, String> {
+    backend.file_read_all(path).or_else(|e| {
+        if path.ends_with("example.ua") {
+            Ok(EXAMPLE_UA.as_bytes().to_vec())
+        } else if path.ends_with("example.txt") {
+            Ok(EXAMPLE_TXT.as_bytes().to_vec())
+        } else {
+            Err(e)
+        }
+    })
+}
+
 macro_rules! sys_op {
     ($(
         #[doc = $doc_rust:literal]
@@ -169,6 +185,15 @@ sys_op! {
     (1(0), Prin, StdIO, "&pf", "print and flush", Mutating),
     /// Print a value to stdout followed by a newline
     (1(0), Print, StdIO, "&p", "print with newline", Mutating),
+    /// Report the progress of a long computation
+    ///
+    /// Expects a message and the fraction of the work that is done, from `0` to `1`.
+    /// In the terminal, this draws a progress bar on stderr that is redrawn by each report.
+    /// A fraction of `1` finishes the bar.
+    /// Programs that embed Uiua can receive these reports to show progress in their own way.
+    /// Where progress cannot be shown, [&prog] does nothing.
+    /// ex: ≡(&prog "Working" ÷10) ⇡11
+    (2(0), Progress, StdIO, "&prog", "progress", Mutating),
     /// Read a line from stdin
     ///
     /// The normal output is a string.
@@ -181,7 +206,17 @@ sys_op! {
     /// Height comes first so that the array can be used as a shape in [reshape].
     (0, TermSize, Env, "&ts", "terminal size", Mutating),
     /// Exit the program with a status code
+    ///
+    /// Functions registered with [&atexit] are called first.
     (1(0), Exit, Misc, "&exit", "exit", Mutating),
+    /// Register a function to be called when the program exits
+    ///
+    /// The function must take no arguments. Any values it returns are discarded.
+    /// Registered functions are called in reverse order when the program finishes, when it is stopped by an error, or when [&exit] is called.
+    /// This makes [&atexit] useful for releasing resources like temporary files, sockets, or hardware.
+    /// ex: &atexit(&p "Goodbye!")
+    ///   : &p "Hello!"
+    (0(0)[1], AtExit, Misc, "&atexit", "at exit", Mutating),
     /// Set the terminal to raw mode
     ///
     /// Expects a boolean.
@@ -220,6 +255,23 @@ sys_op! {
     /// Using [&cl] on *all 3* handles will kill the child process.
     /// [under][&runs] calls [&cl] on all 3 streams automatically.
     (1(3), RunStream, Command, "&runs", "run command stream", Mutating),
+    /// Check whether a child process has finished without waiting for it
+    ///
+    /// Expects any of the handles returned by [&runs].
+    /// Returns the exit code of the command if it has finished, or [infinity] if it is still running.
+    /// On Unix-like systems, a command that was ended by a signal has the negative of the signal number as its exit code.
+    (1, ChildStatus, Command, "&chst", "child status", Mutating),
+    /// Kill a child process
+    ///
+    /// Expects any of the handles returned by [&runs].
+    /// The handles still need to be closed with [&cl].
+    (1(0), ChildKill, Command, "&chk", "child kill", Mutating),
+    /// Get the resources used by a child process
+    ///
+    /// Expects any of the handles returned by [&runs].
+    /// Returns a map with the CPU time the command has used in seconds at `"cpu"` and its resident memory in bytes at `"memory"`.
+    /// This is currently only supported on Linux.
+    (1, ChildResources, Command, "&chr", "child resources", Mutating),
     /// Change the current directory
     (1(0), ChangeDirectory, Filesystem, "&cd", "change directory", Mutating),
     /// Get the contents of the clipboard
@@ -236,11 +288,136 @@ sys_op! {
     ///
     /// See also: [&clget]
     (1(0), ClipboardSet, Misc, "&clset", "set clipboard contents", Mutating),
+    /// Get a secret from the system keyring
+    ///
+    /// Expects the name of the secret. Returns the secret as a string.
+    /// It is an error if there is no secret with that name.
+    /// ex: &secset "api-token" "hunter2"
+    ///   : &secget "api-token"
+    /// If the system has no keyring available, secrets are only kept in memory until the program exits.
+    /// This is always the case on the web.
+    ///
+    /// See also: [&secset], [&secdel]
+    (1, SecretGet, Misc, "&secget", "get secret"),
+    /// Store a secret in the system keyring
+    ///
+    /// Expects the name of the secret and the secret as a string.
+    /// An existing secret with the same name is replaced.
+    /// ex: &secset "api-token" "hunter2"
+    ///
+    /// Storing secrets in the keyring keeps them out of source code and config files.
+    /// A script can ask for a secret the first time it is needed and read it from the keyring afterward.
+    /// ex: Token ← ⍣&secget(⟜&secset ⊙(&sc &pf "Token: ")) "api-token"
+    ///
+    /// See also: [&secget], [&secdel]
+    (2(0), SecretSet, Misc, "&secset", "set secret", Mutating),
+    /// Delete a secret from the system keyring
+    ///
+    /// Expects the name of the secret. It is not an error if there is no secret with that name.
+    /// ex: &secset "api-token" "hunter2"
+    ///   : &secdel "api-token"
+    ///   : ⍣&secget"gone" "api-token"
+    ///
+    /// See also: [&secget], [&secset]
+    (1(0), SecretDelete, Misc, "&secdel", "delete secret", Mutating),
+    /// Open a named region of shared memory
+    ///
+    /// Expects a name and a size in bytes. Returns a handle to the region.
+    /// If no region with the name exists, one is created and filled with zeros.
+    /// If the region is smaller than the size, it is grown. A size of `0` opens an existing region at its current size.
+    /// ex: &shmo "frames" 1024
+    /// Any number of Uiua programs, or other programs, can open the same region at once.
+    /// Data written to the region with [&shmw] can be read by all of them with [&shmr] without any serialization.
+    /// The region is backed by a file named `uiua-shm-` followed by the name, in `/dev/shm` on Linux and in the temporary directory elsewhere. Other programs run by the same user can map that file to share the region.
+    /// The name cannot contain `/`, `\`, or `..`.
+    ///
+    /// Access to the region can be synchronized by locking its handle with [&flx], [&fls], [&ftl], and [&ful].
+    /// ex: ⍜&flx(&shmw 0 [1 2 3]) &shmo "frames" 1024
+    ///
+    /// Close the handle with [&cl]. The region lasts until it is deleted with [&shmd].
+    (2, SharedMemOpen, Misc, "&shmo", "shared memory - open", Mutating),
+    /// Read bytes from a region of shared memory
+    ///
+    /// Expects an offset, a count, and a handle from [&shmo].
+    /// Using [infinity] as the count will read until the end of the region.
+    /// ex: &shmr 0 4 &shmo "frames" 1024
+    /// ex: ⧻&shmr 0 ∞ &shmo "frames" 1024
+    (3, SharedMemRead, Misc, "&shmr", "shared memory - read"),
+    /// Write bytes to a region of shared memory
+    ///
+    /// Expects an offset, a list of bytes, and a handle from [&shmo].
+    /// It is an error to write past the end of the region.
+    /// ex: &shmw 0 [1 2 3] . &shmo "frames" 1024
+    ///   : &shmr 0 5
+    /// Strings are written as UTF-8.
+    (3(0), SharedMemWrite, Misc, "&shmw", "shared memory - write", Mutating),
+    /// Delete a named region of shared memory
+    ///
+    /// Programs that have the region open can keep using it until they close it with [&cl].
+    /// It is not an error if there is no region with the name.
+    /// ex: &shmd "frames"
+    (1(0), SharedMemDelete, Misc, "&shmd", "shared memory - delete", Mutating),
+    /// Open a persistent key-value store
+    ///
+    /// Expects a path to the store's file. Returns a handle to the store.
+    /// If the file does not exist, an empty store is created.
+    /// ex: &kvo "example.kv"
+    /// Keys are strings, and values can be any array. Each value is stored in a compact binary format.
+    /// Unlike a JSON or CBOR file, updating one entry does not rewrite the whole store, so stateful tools can keep their state across runs cheaply.
+    /// Writes are durable as soon as [&kvp] or [&kvd] returns.
+    ///
+    /// Only one handle to a store can be open at a time. Close the handle with [&cl].
+    ///
+    /// See also: [&kvg], [&kvp], [&kvd], [&kvs]
+    (1, KvOpen, Misc, "&kvo", "key-value store - open", Mutating),
+    /// Get a value from a key-value store
+    ///
+    /// Expects a key and a handle from [&kvo].
+    /// It is an error if there is no entry with the key.
+    /// ex: &kvp "count" 5 . &kvo "example.kv"
+    ///   : &kvg "count"
+    /// [try] can be used to provide a default.
+    /// ex: ⍣&kvg⋅⋅0 "visits" &kvo "example.kv"
+    (2, KvGet, Misc, "&kvg", "key-value store - get"),
+    /// Put a value into a key-value store
+    ///
+    /// Expects a key, a value, and a handle from [&kvo].
+    /// An existing value with the same key is replaced.
+    /// ex: &kvp "user" {"Alice" 27} . &kvo "example.kv"
+    ///   : &kvg "user"
+    (3(0), KvPut, Misc, "&kvp", "key-value store - put", Mutating),
+    /// Delete an entry from a key-value store
+    ///
+    /// Expects a key and a handle from [&kvo]. It is not an error if there is no entry with the key.
+    /// ex: &kvd "count" &kvo "example.kv"
+    (2(0), KvDelete, Misc, "&kvd", "key-value store - delete", Mutating),
+    /// Get all entries in a key-value store whose keys start with a prefix
+    ///
+    /// Expects a prefix and a handle from [&kvo]. Returns a map of keys to boxed values, sorted by key.
+    /// An empty prefix gets every entry.
+    /// ex: &kvp "user/2" "Bob" . &kvp "user/1" "Alice" . &kvo "example.kv"
+    ///   : &kvs "user/"
+    (2, KvScan, Misc, "&kvs", "key-value store - scan"),
     /// Sleep for n seconds
     ///
     /// On the web, this example will hang for 1 second.
     /// ex: ⚂ &sl 1
+    ///
+    /// Fractional durations are supported down to the nanosecond. The duration is rounded to the nearest nanosecond.
+    /// The native interpreter sleeps for most of the duration and then waits out the last millisecond precisely, so even sub-millisecond sleeps are accurate.
+    /// ex: &sl 0.0005
+    ///
+    /// See also: [&clk]
     (1(0), Sleep, Misc, "&sl", "sleep", Mutating),
+    /// Get the time in seconds from a monotonic clock
+    ///
+    /// The clock starts at an arbitrary point and only ever moves forward.
+    /// Unlike [now], it is not affected by changes to the system time, so it is better suited for measuring durations.
+    /// It has nanosecond resolution where the system supports it.
+    /// ex: -⊙&clk &sl 0.01 &clk
+    /// [under][&clk] can be used to time a function.
+    /// ex: ⍜&clk(&sl0.01)
+    (0, MonotonicClock, Misc, "&clk", "monotonic clock"),
     /// Read characters formed by at most n bytes from a stream
     ///
     /// Expects a count and a stream handle.
@@ -333,6 +510,7 @@ sys_op! {
     ///
     /// ex: &fras "example.txt"
     /// You can use [under][&fras] to write back to the file after modifying the string.
+    /// The new contents are written to a temporary file which then replaces the original, so an interrupted write will not leave the file half-written.
     /// ex: ⍜&fras(⊂:"\n# Wow!") "example.txt"
     ///   : &p&fras "example.txt"
     ///
@@ -344,6 +522,7 @@ sys_op! {
     ///
     /// ex: &frab "example.txt"
     /// You can use [under][&frab] to write back to the file after modifying the array.
+    /// As with [&fras], the file is replaced all at once.
     /// ex: ⍜&frab(⊂:-@\0"\n# Wow!") "example.txt"
     ///   : &p&fras "example.txt"
     ///
@@ -359,6 +538,92 @@ sys_op! {
     ///   : &fwa Path +@A⇡26
     ///   : &fras Path
     (2(0), FWriteAll, Filesystem, "&fwa", "file - write all", Mutating),
+    /// Atomically replace the entire contents of a file with an array
+    ///
+    /// Expects a path and a rank-`1` array of either numbers or characters.
+    /// The contents are written to a temporary file, which is synced to disk and then renamed over the original.
+    /// If the program is interrupted partway through, the file will have either its old contents or its new ones.
+    /// This makes [&frpa] a good choice for files that store a program's state.
+    /// ex: &frpa "state.txt" "count: 5"
+    ///   : &fras "state.txt"
+    ///
+    /// See [&fwa] for writing to a file directly.
+    (2(0), FReplaceAll, Filesystem, "&frpa", "file - replace all", Mutating),
+    /// Append the contents of an array to the end of a file
+    ///
+    /// Expects a path and a rank-`1` array of either numbers or characters.
+    /// The file will be created if it does not exist.
+    /// The file is locked while it is written to, so appends from multiple programs will not be interleaved.
+    /// ex: &fapa "log.txt" "one\n"
+    ///   : &fapa "log.txt" "two\n"
+    ///   : &fras "log.txt"
+    (2(0), FAppendAll, Filesystem, "&fapa", "file - append all", Mutating),
+    /// Make sure that everything written to a file handle is stored on disk
+    ///
+    /// Expects a handle from [&fo] or [&fc].
+    /// The operating system may keep written data in memory for a while before storing it.
+    /// [&fsy] waits until the data is stored, so that it is not lost if the system crashes.
+    /// ex: &cl &fsy &w "Hello, world!" . . &fc "file.txt"
+    ///   : &fras "file.txt"
+    (1(0), FSync, Filesystem, "&fsy", "file - sync", Mutating),
+    /// Take a shared lock on a file handle
+    ///
+    /// Expects a handle from [&fo] or [&fc] and returns it.
+    /// Any number of programs can hold a shared lock on a file at once, but none of them can while another holds an exclusive lock with [&flx].
+    /// If the file is already locked exclusively, [&fls] waits until it is unlocked.
+    /// Locks are advisory, so they only coordinate programs that also lock the file.
+    /// [under][&fls] calls [&ful] automatically.
+    /// ex: ⍜&fls(&rs ∞) &fo "example.txt"
+    ///
+    /// Closing a file with [&cl] also releases its lock.
+    /// See [&ftl] for locking without waiting.
+    (1, FLockShared, Filesystem, "&fls", "file - lock shared", Mutating),
+    /// Take an exclusive lock on a file handle
+    ///
+    /// Expects a handle from [&fo] or [&fc] and returns it.
+    /// Only one program can hold an exclusive lock on a file at once.
+    /// If the file is already locked, [&flx] waits until it is unlocked.
+    /// Locks are advisory, so they only coordinate programs that also lock the file.
+    /// [under][&flx] calls [&ful] automatically.
+    /// ex: ⍜&flx(&w "Hello, world!") &fc "file.txt"
+    ///   : &fras "file.txt"
+    ///
+    /// Closing a file with [&cl] also releases its lock.
+    /// See [&ftl] for locking without waiting.
+    (1, FLockExclusive, Filesystem, "&flx", "file - lock exclusive", Mutating),
+    /// Try to lock a file handle without waiting
+    ///
+    /// Expects a boolean for whether the lock is exclusive and a handle from [&fo] or [&fc].
+    /// Returns whether the lock was taken.
+    /// If the file is already locked in a way that conflicts, [&ftl] returns `0` right away rather than waiting like [&fls] and [&flx].
+    /// ex: &ftl 1 &fc "file.txt"
+    (2, FTryLock, Filesystem, "&ftl", "file - try lock", Mutating),
+    /// Release a lock on a file handle
+    ///
+    /// Expects a handle that was locked with [&fls], [&flx], or [&ftl].
+    /// ex: &ful &flx &fc "file.txt"
+    (1(0), FUnlock, Filesystem, "&ful", "file - unlock", Mutating),
+    /// Fold a function over the lines of a file
+    ///
+    /// Expects a path and an initial accumulator.
+    /// The function is called with each line as a rank-`1` character array and the current accumulator, and must return the new accumulator.
+    /// The file is read one line at a time, so it is never loaded into memory all at once.
+    /// Line endings are not included in the lines.
+    /// ex: &ffl(+1◌) "example.txt" 0
+    /// ex: &ffl(↥⧻) "example.txt" 0
+    ///
+    /// See [&ffc] for folding over fixed-size chunks of bytes.
+    (2[1], FFoldLines, Filesystem, "&ffl", "file - fold lines"),
+    /// Fold a function over fixed-size chunks of a file
+    ///
+    /// Expects a chunk size, a path, and an initial accumulator.
+    /// The function is called with each chunk as a rank-`1` byte array and the current accumulator, and must return the new accumulator.
+    /// The last chunk may be shorter than the chunk size.
+    /// The file is read one chunk at a time, so it is never loaded into memory all at once.
+    /// ex: &ffc(+/+) 16 "example.txt" 0
+    ///
+    /// See [&ffl] for folding over lines.
+    (3[1], FFoldChunks, Filesystem, "&ffc", "file - fold chunks"),
     /// Decode an image from a byte array
     ///
     /// Returns the image format as a string and a rank-`3` numeric array.
@@ -494,6 +759,53 @@ sys_op! {
     /// On the web, this will simply use the function to generate a fixed amount of audio.
     /// How long the audio is can be configure in the editor settings.
     (0(0)[1], AudioStream, Audio, "&ast", "audio - stream", Mutating),
+    /// Stream an audio synthesis graph
+    ///
+    /// Expects a node like [synth] takes.
+    /// The graph is rendered without running any Uiua code, so it is much faster than computing each sample with [&ast].
+    /// ex: # Experimental!
+    ///   : &asyn {"×" 0.3 {"lowpass" {"saw" {"seq" 0.5 [110 165 147 98]}} 600}}
+    /// On the web, this will simply render a fixed amount of audio, like [&ast].
+    (1(0), AudioSynth, Audio, "&asyn", "audio - synth", Mutating),
+    /// Get the names of the available MIDI ports
+    ///
+    /// Returns a list of the input ports and a list of the output ports.
+    /// ex: &midp
+    /// Open a port with [&midi] or [&mido].
+    (0(2), MidiPorts, Audio, "&midp", "midi - ports"),
+    /// Open a MIDI input port
+    ///
+    /// Expects part of the name of a port. The first port whose name contains it is opened.
+    /// An empty string opens the first port.
+    /// Returns a handle that can be used with [&midr].
+    /// ex: &midi "Keystation"
+    /// Messages received by the port are kept until they are read. Close the port with [&cl].
+    (1, MidiInput, Audio, "&midi", "midi - open input", Mutating),
+    /// Open a MIDI output port
+    ///
+    /// Expects part of the name of a port. The first port whose name contains it is opened.
+    /// An empty string opens the first port.
+    /// Returns a handle that can be used with [&midw].
+    /// ex: &mido "Synth"
+    /// Close the port with [&cl].
+    (1, MidiOutput, Audio, "&mido", "midi - open output", Mutating),
+    /// Receive messages from a MIDI input port
+    ///
+    /// Expects a handle from [&midi].
+    /// Returns a list of boxed byte lists, one for each message received since the last call.
+    /// [&midr] does not wait for messages, so it can be called regularly, such as once per frame or audio buffer.
+    /// ex: &midr &midi ""
+    (1, MidiReceive, Audio, "&midr", "midi - receive", Mutating),
+    /// Send a message to a MIDI output port
+    ///
+    /// Expects a message as a list of bytes and a handle from [&mido].
+    /// If the message is rank `2`, each row is sent as a separate message.
+    /// ex: # Play middle C
+    ///   : M ← &mido ""
+    ///   : &midw [144 60 100] M
+    ///   : &sl 0.5
+    ///   : &midw [128 60 0] M
+    (2(0), MidiSend, Audio, "&midw", "midi - send", Mutating),
     /// Create a TCP listener and bind it to an address
     ///
     /// Use [&tcpa] on the returned handle to accept connections.
@@ -558,7 +870,59 @@ sys_op! {
     /// - 2 trailing newlines (if there is no body)
     /// - The HTTP version
     /// - The `Host` header (if not defined)
+    ///
+    /// In the native interpreter, setting the `UIUA_HTTP_CACHE` environment variable to `1` caches responses to `GET` requests on disk according to their `Cache-Control`, `Vary`, `ETag`, and `Last-Modified` headers.
+    /// A fresh cached response is returned without sending the request. A stale one is revalidated. If the server cannot be reached, the stale response is returned with a `Warning: 111` header.
+    /// Requests with a query string or with headers other than `Host`, `User-Agent`, `Accept`, `Accept-Language`, `Accept-Encoding`, and `Connection` are never cached, nor are `private` responses.
+    /// The cache is kept in the directory set by the `UIUA_CACHE_DIR` environment variable, or in the system's cache directory.
     (2, HttpsWrite, Tcp, "&httpsw", "https - Make an HTTP(S) request", Mutating),
+    /// Send an email with SMTP
+    ///
+    /// Expects a [map] describing the email. It may have the following keys:
+    /// - `"server"` - The SMTP server's address, like `"smtp.example.com:587"`
+    /// - `"user"` and `"password"` - Credentials to log in with, if the server requires them
+    /// - `"security"` - `"tls"`, `"starttls"`, or `"none"`
+    /// - `"from"` - The sender's address
+    /// - `"to"`, `"cc"`, and `"bcc"` - A recipient's address or a list of them
+    /// - `"subject"` - The subject line
+    /// - `"body"` - The plain text of the email
+    ///
+    /// `"server"`, `"from"`, and at least one of `"to"`, `"cc"`, or `"bcc"` are required.
+    /// A user and password can only be given with `"tls"` or `"starttls"` security.
+    /// Addresses may include a display name, like `"Uiua <uiua@example.com>"`.
+    ///
+    /// By default, the connection is upgraded to TLS with `STARTTLS`, or is TLS from the start if the port is `465`.
+    /// If the server has no port, it defaults to `465` for `"tls"`, `587` for `"starttls"`, and `25` for `"none"`.
+    /// ex: &smtp map {"server" "user" "password" "from" "to" "subject" "body"} {
+    ///   :   "smtp.example.com" "me" "hunter2"
+    ///   :   "Me <me@example.com>" {"you@example.com" "them@example.com"}
+    ///   :   "Build finished" "All tests passed!"
+    ///   : }
+    (1(0), Smtp, Tcp, "&smtp", "smtp - send email", Mutating),
+    /// Send an OSC message over UDP
+    ///
+    /// Expects the address of the receiver, the OSC address of the message, and the arguments of the message.
+    /// The arguments are usually a list of boxes. Integers are sent as 32-bit integers, other numbers as 32-bit floats, strings as strings, and lists of bytes as blobs.
+    /// ex: &oscs "127.0.0.1:57120" "/note" {60 0.5 "piano"}
+    /// A list of numbers sends each number as an argument.
+    /// ex: &oscs "127.0.0.1:57120" "/xy" [0.25 0.75]
+    ///
+    /// OSC, or Open Sound Control, is used by many synthesizers, audio workstations, and live coding environments.
+    (3(0), OscSend, Tcp, "&oscs", "osc - send", Mutating),
+    /// Listen for OSC messages on a UDP address
+    ///
+    /// Returns a handle that can be used with [&oscr].
+    /// ex: &oscl "0.0.0.0:9000"
+    /// Messages received on the address are kept until they are read. Close the handle with [&cl].
+    (1, OscListen, Tcp, "&oscl", "osc - listen", Mutating),
+    /// Receive OSC messages
+    ///
+    /// Expects a handle from [&oscl].
+    /// Returns a list of the OSC addresses of the messages received since the last call and a list of their boxed arguments.
+    /// Messages in bundles are returned individually.
+    /// [&oscr] does not wait for messages, so it can be called regularly, such as once per frame or audio buffer.
+    /// ex: &oscr &oscl "0.0.0.0:9000"
+    (1(2), OscReceive, Tcp, "&oscr", "osc - receive", Mutating),
     /// Call a foreign function interface
     ///
     /// *Warning ⚠️: Using FFI is deeply unsafe. Calling a function incorrectly is undefined behavior.*
@@ -729,6 +1093,11 @@ pub enum HandleKind {
     ChildStdin(String),
     ChildStdout(String),
     ChildStderr(String),
+    SharedMemory(String),
+    KvStore(PathBuf),
+    OscSocket(SocketAddr),
+    MidiInput(String),
+    MidiOutput(String),
 }
 
 impl fmt::Display for HandleKind {
@@ -742,10 +1111,201 @@ impl fmt::Display for HandleKind {
             Self::ChildStdin(com) => write!(f, "stdin {com}"),
             Self::ChildStdout(com) => write!(f, "stdout {com}"),
             Self::ChildStderr(com) => write!(f, "stderr {com}"),
+            Self::SharedMemory(name) => write!(f, "shared memory {name}"),
+            Self::KvStore(path) => write!(f, "key-value store {}", path.display()),
+            Self::OscSocket(addr) => write!(f, "osc socket {addr}"),
+            Self::MidiInput(port) => write!(f, "midi input {port}"),
+            Self::MidiOutput(port) => write!(f, "midi output {port}"),
+        }
+    }
+}
+
+/// The kind of a [`RichOutput`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RichOutputKind {
+    /// A still image
+    Image,
+    /// An animated image
+    Animation,
+    /// Audio
+    Audio,
+    /// A table of values
+    Table,
+    /// A plot or other vector graphic
+    Plot,
+}
+
+impl fmt::Display for RichOutputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Image => write!(f, "images"),
+            Self::Animation => write!(f, "animations"),
+            Self::Audio => write!(f, "audio"),
+            Self::Table => write!(f, "tables"),
+            Self::Plot => write!(f, "plots"),
         }
     }
 }
 
+/// An email to send with [`SysBackend::send_email`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Email {
+    /// The SMTP server's address, with or without a port
+    pub server: String,
+    /// How the connection to the server is secured
+    pub security: SmtpSecurity,
+    /// The username and password to log in with
+    pub credentials: Option<(String, String)>,
+    /// The sender's address
+    pub from: String,
+    /// The addresses of the main recipients
+    pub to: Vec<String>,
+    /// The addresses of the copied recipients
+    pub cc: Vec<String>,
+    /// The addresses of the hidden recipients
+    pub bcc: Vec<String>,
+    /// The subject line
+    pub subject: String,
+    /// The plain text body
+    pub body: String,
+}
+
+impl Email {
+    /// Get the server's address, adding the default port for the security if there is none
+    pub fn server_addr(&self) -> String {
+        if self
+            .server
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+        {
+            return self.server.clone();
+        }
+        let port = match self.security {
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::None => 25,
+        };
+        format!("{}:{port}", self.server)
+    }
+}
+
+/// How the connection to an SMTP server is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// The connection uses TLS from the start
+    Tls,
+    /// The connection is upgraded to TLS with the `STARTTLS` command
+    #[default]
+    StartTls,
+    /// The connection is not encrypted
+    None,
+}
+
+/// Output that a host environment can present however it sees fit
+///
+/// The data is encoded in the format described by the MIME type.
+/// Images are `image/png`, animations are `image/gif`, audio is `audio/wav`,
+/// tables are `text/tab-separated-values`, and plots are `image/svg+xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RichOutput {
+    /// The kind of output
+    pub kind: RichOutputKind,
+    /// The MIME type of the data
+    pub mime: &'static str,
+    /// The encoded data
+    pub data: Vec<u8>,
+}
+
+impl RichOutput {
+    /// Create new rich output
+    pub fn new(kind: RichOutputKind, mime: &'static str, data: Vec<u8>) -> Self {
+        Self { kind, mime, data }
+    }
+    /// Get the data as a string if it is text
+    pub fn text(&self) -> Option<&str> {
+        match self.kind {
+            RichOutputKind::Table | RichOutputKind::Plot => std::str::from_utf8(&self.data).ok(),
+            _ => None,
+        }
+    }
+    /// Guess the richest way to present a value
+    ///
+    /// Returns `None` if the value is best shown as text.
+    #[allow(unused_variables)]
+    pub fn from_value(value: &Value, audio_sample_rate: u32) -> Option<Self> {
+        #[allow(dead_code)]
+        const MIN_AUTO_IMAGE_DIM: usize = 30;
+        let shape = value.shape();
+        // Audio
+        #[cfg(feature = "audio_encode")]
+        if shape
+            .last()
+            .is_some_and(|&n| n >= audio_sample_rate as usize / 4)
+        {
+            if let Ok(bytes) = value_to_wav_bytes(value, audio_sample_rate) {
+                return Some(Self::new(RichOutputKind::Audio, "audio/wav", bytes));
+            }
+        }
+        // Image
+        #[cfg(feature = "image")]
+        if let Ok(image) = value_to_image(value) {
+            if image.width() >= MIN_AUTO_IMAGE_DIM as u32
+                && image.height() >= MIN_AUTO_IMAGE_DIM as u32
+            {
+                if let Ok(bytes) = image_to_bytes(&image, ImageOutputFormat::Png) {
+                    return Some(Self::new(RichOutputKind::Image, "image/png", bytes));
+                }
+            }
+        }
+        // Animation
+        #[cfg(feature = "gif")]
+        if let &[f, h, w] | &[f, h, w, _] = shape.dims() {
+            if h >= MIN_AUTO_IMAGE_DIM && w >= MIN_AUTO_IMAGE_DIM && f >= 5 {
+                if let Ok(bytes) = value_to_gif_bytes(value, 16.0) {
+                    return Some(Self::new(RichOutputKind::Animation, "image/gif", bytes));
+                }
+            }
+        }
+        // Plot
+        if let Value::Char(arr) = value {
+            if arr.rank() == 1 {
+                let mut svg: String = arr.data.iter().collect();
+                if svg.starts_with("<svg") && svg.ends_with("</svg>") {
+                    if !svg.contains("xmlns") {
+                        svg = svg.replacen("<svg", "<svg xmlns=\"http://www.w3.org/2000/svg\"", 1);
+                    }
+                    let data = svg.into_bytes();
+                    return Some(Self::new(RichOutputKind::Plot, "image/svg+xml", data));
+                }
+            }
+        }
+        // Table
+        if shape.len() == 2
+            && matches!(
+                value,
+                Value::Num(_)
+                    | Value::Byte(_)
+                    | Value::Int(_)
+                    | Value::Float(_)
+                    | Value::Complex(_)
+            )
+        {
+            let mut tsv = String::new();
+            for row in value.rows() {
+                let cells: Vec<String> = row.rows().map(|cell| cell.format()).collect();
+                tsv.push_str(&cells.join("\t"));
+                tsv.push('\n');
+            }
+            return Some(Self::new(
+                RichOutputKind::Table,
+                "text/tab-separated-values",
+                tsv.into(),
+            ));
+        }
+        None
+    }
+}
+
 /// Trait for defining a system backend
 #[allow(unused_variables)]
 pub trait SysBackend: Any + Send + Sync + 'static {
@@ -875,6 +1435,36 @@ pub trait SysBackend: Any + Send + Sync + 'static {
         self.close(handle)?;
         Ok(())
     }
+    /// Replace all the bytes of a file
+    ///
+    /// Unlike [`SysBackend::file_write_all`], this should never leave the file partially written.
+    fn file_replace_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        self.file_write_all(path, contents)
+    }
+    /// Append bytes to the end of a file, creating it if it does not exist
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        Err("Appending to files is not supported in this environment".into())
+    }
+    /// Wait until everything written to a file handle is stored
+    fn sync(&self, handle: Handle) -> Result<(), String> {
+        Err("Syncing files is not supported in this environment".into())
+    }
+    /// Lock a file
+    ///
+    /// Returns whether the lock was taken, which is always `true` if `block` is set
+    fn lock_file(&self, handle: Handle, exclusive: bool, block: bool) -> Result<bool, String> {
+        Err("Locking files is not supported in this environment".into())
+    }
+    /// Unlock a file
+    fn unlock_file(&self, handle: Handle) -> Result<(), String> {
+        Err("Locking files is not supported in this environment".into())
+    }
+    /// Report the progress of a long computation
+    ///
+    /// `fraction` is from `0` to `1`. Backends that cannot show progress may ignore it.
+    fn report_progress(&self, message: &str, fraction: f64) -> Result<(), String> {
+        Ok(())
+    }
     /// Get the clipboard contents
     fn clipboard(&self) -> Result<String, String> {
         Err("Getting the clipboard is not supported in this environment".into())
@@ -883,22 +1473,124 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn set_clipboard(&self, contents: &str) -> Result<(), String> {
         Err("Setting the clipboard is not supported in this environment".into())
     }
+    /// Get a secret from the keyring, or `None` if there is no secret with the name
+    fn secret(&self, name: &str) -> Result<Option<String>, String> {
+        Err("Secret storage is not supported in this environment".into())
+    }
+    /// Store a secret in the keyring
+    fn set_secret(&self, name: &str, secret: &str) -> Result<(), String> {
+        Err("Secret storage is not supported in this environment".into())
+    }
+    /// Delete a secret from the keyring
+    fn delete_secret(&self, name: &str) -> Result<(), String> {
+        Err("Secret storage is not supported in this environment".into())
+    }
+    /// Open a named region of shared memory, creating or growing it to at least `size` bytes
+    fn shared_memory_open(&self, name: &str, size: usize) -> Result<Handle, String> {
+        Err("Shared memory is not supported in this environment".into())
+    }
+    /// Read bytes from a region of shared memory
+    ///
+    /// If `count` is `None`, read until the end of the region
+    fn shared_memory_read(
+        &self,
+        handle: Handle,
+        offset: usize,
+        count: Option<usize>,
+    ) -> Result<Vec<u8>, String> {
+        Err("Shared memory is not supported in this environment".into())
+    }
+    /// Write bytes to a region of shared memory
+    fn shared_memory_write(
+        &self,
+        handle: Handle,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<(), String> {
+        Err("Shared memory is not supported in this environment".into())
+    }
+    /// Delete a named region of shared memory
+    fn shared_memory_delete(&self, name: &str) -> Result<(), String> {
+        Err("Shared memory is not supported in this environment".into())
+    }
+    /// Open a persistent key-value store, creating it if it does not exist
+    fn kv_open(&self, path: &Path) -> Result<Handle, String> {
+        Err("Key-value stores are not supported in this environment".into())
+    }
+    /// Get a value from a key-value store, or `None` if there is no entry with the key
+    fn kv_get(&self, handle: Handle, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Err("Key-value stores are not supported in this environment".into())
+    }
+    /// Put a value into a key-value store
+    fn kv_put(&self, handle: Handle, key: &str, value: &[u8]) -> Result<(), String> {
+        Err("Key-value stores are not supported in this environment".into())
+    }
+    /// Delete an entry from a key-value store
+    fn kv_delete(&self, handle: Handle, key: &str) -> Result<(), String> {
+        Err("Key-value stores are not supported in this environment".into())
+    }
+    /// Get all entries in a key-value store whose keys start with a prefix, sorted by key
+    fn kv_scan(&self, handle: Handle, prefix: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+        Err("Key-value stores are not supported in this environment".into())
+    }
+    /// Send an OSC packet over UDP
+    fn osc_send(&self, addr: &str, packet: &[u8]) -> Result<(), String> {
+        Err("OSC is not supported in this environment".into())
+    }
+    /// Bind a UDP socket for receiving OSC packets
+    fn osc_listen(&self, addr: &str) -> Result<Handle, String> {
+        Err("OSC is not supported in this environment".into())
+    }
+    /// Get the address an OSC socket is bound to
+    fn osc_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
+        Err("OSC is not supported in this environment".into())
+    }
+    /// Receive all OSC packets that have arrived without waiting
+    fn osc_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String> {
+        Err("OSC is not supported in this environment".into())
+    }
+    /// Get the names of the MIDI input and output ports
+    fn midi_ports(&self) -> Result<(Vec<String>, Vec<String>), String> {
+        Err("MIDI is not supported in this environment".into())
+    }
+    /// Open the first MIDI input port whose name contains `port`
+    ///
+    /// Returns the handle and the full name of the port
+    fn midi_open_input(&self, port: &str) -> Result<(Handle, String), String> {
+        Err("MIDI is not supported in this environment".into())
+    }
+    /// Open the first MIDI output port whose name contains `port`
+    ///
+    /// Returns the handle and the full name of the port
+    fn midi_open_output(&self, port: &str) -> Result<(Handle, String), String> {
+        Err("MIDI is not supported in this environment".into())
+    }
+    /// Receive all MIDI messages that have arrived without waiting
+    fn midi_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String> {
+        Err("MIDI is not supported in this environment".into())
+    }
+    /// Send a MIDI message
+    fn midi_send(&self, handle: Handle, message: &[u8]) -> Result<(), String> {
+        Err("MIDI is not supported in this environment".into())
+    }
     /// Sleep the current thread for `seconds` seconds
     fn sleep(&self, seconds: f64) -> Result<(), String> {
         Err("Sleeping is not supported in this environment".into())
     }
-    /// Show an image
-    #[cfg(feature = "image")]
-    fn show_image(&self, image: DynamicImage) -> Result<(), String> {
-        Err("Showing images not supported in this environment".into())
-    }
-    /// Show a GIF
-    fn show_gif(&self, gif_bytes: Vec<u8>) -> Result<(), String> {
-        Err("Showing gifs not supported in this environment".into())
+    /// Get the time in seconds since an arbitrary fixed point
+    ///
+    /// Unlike [`Primitive::Now`](crate::Primitive::Now), this clock must never jump or go backwards
+    fn monotonic_time(&self) -> Result<f64, String> {
+        Err("A monotonic clock is not supported in this environment".into())
     }
-    /// Play audio from WAV bytes
-    fn play_audio(&self, wave_bytes: Vec<u8>) -> Result<(), String> {
-        Err("Playing audio not supported in this environment".into())
+    /// Present rich output such as images, audio, or tables
+    ///
+    /// How the output is presented is up to the backend.
+    fn show_rich(&self, output: RichOutput) -> Result<(), String> {
+        Err(format!(
+            "Showing {} is not supported in this environment",
+            output.kind
+        ))
     }
     /// Get the audio sample rate
     fn audio_sample_rate(&self) -> u32 {
@@ -976,6 +1668,18 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn run_command_stream(&self, command: &str, args: &[&str]) -> Result<[Handle; 3], String> {
         Err("Running streamed commands is not supported in this environment".into())
     }
+    /// Get the exit code of a child process, or `None` if it is still running
+    fn child_status(&self, handle: Handle) -> Result<Option<i32>, String> {
+        Err("Checking child processes is not supported in this environment".into())
+    }
+    /// Kill a child process
+    fn child_kill(&self, handle: Handle) -> Result<(), String> {
+        Err("Killing child processes is not supported in this environment".into())
+    }
+    /// Get the CPU time in seconds and the resident memory in bytes used by a child process
+    fn child_resources(&self, handle: Handle) -> Result<(f64, f64), String> {
+        Err("Getting child process resources is not supported in this environment".into())
+    }
     /// Change the current directory
     fn change_directory(&self, path: &str) -> Result<(), String> {
         Err("Changing directories is not supported in this environment".into())
@@ -984,6 +1688,10 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
         Err("Making HTTPS requests is not supported in this environment".into())
     }
+    /// Send an email with SMTP
+    fn send_email(&self, email: &Email) -> Result<(), String> {
+        Err("Sending email is not supported in this environment".into())
+    }
     /// Call a foreign function interface
     fn ffi(
         &self,
@@ -1009,6 +1717,12 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn load_git_module(&self, url: &str, branch: Option<&str>) -> Result<PathBuf, String> {
         Err("Loading git modules is not supported in this environment".into())
     }
+    /// Load a module from an HTTPS URL
+    ///
+    /// The returned path should be loadable via [`SysBackend::file_read_all`]
+    fn load_url_module(&self, url: &str) -> Result<PathBuf, String> {
+        Err("Loading modules from URLs is not supported in this environment".into())
+    }
 }
 
 impl fmt::Debug for dyn SysBackend {
@@ -1103,6 +1817,16 @@ impl SysOp {
                     .print_str_stdout("\n")
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::Progress => {
+                let message = env.pop(1)?.as_string(env, "Message must be a string")?;
+                let fraction = env.pop(2)?.as_num(env, "Progress must be a number")?;
+                if fraction.is_nan() {
+                    return Err(env.error("Progress cannot be NaN"));
+                }
+                (env.rt.backend)
+                    .report_progress(&message, fraction.clamp(0.0, 1.0))
+                    .map_err(|e| env.error(e))?;
+            }
             SysOp::ScanLine => {
                 if let Some(line) = env.rt.backend.scan_line_stdin().map_err(|e| env.error(e))? {
                     env.push(line);
@@ -1116,6 +1840,7 @@ impl SysOp {
             }
             SysOp::Exit => {
                 let status = env.pop(1)?.as_int(env, "Status must be an integer")? as i32;
+                env.run_exit_hooks()?;
                 (env.rt.backend).exit(status).map_err(|e| env.error(e))?;
             }
             SysOp::RawMode => {
@@ -1285,6 +2010,8 @@ impl SysOp {
                         let delim_bytes: Vec<u8> = match delim {
                             Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
                             Value::Byte(arr) => arr.data.into(),
+                            Value::Int(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+                            Value::Float(arr) => arr.data.iter().map(|&x| x as u8).collect(),
                             Value::Char(arr) => {
                                 is_string = true;
                                 arr.data.iter().collect::<String>().into()
@@ -1322,6 +2049,24 @@ impl SysOp {
                                 .map_err(|e| env.error(e))?;
                             env.push(Array::from(bytes.as_slice()));
                         }
+                        Value::Int(arr) => {
+                            let delim: Vec<u8> = arr.data.iter().map(|&x| x as u8).collect();
+                            let bytes = env
+                                .rt
+                                .backend
+                                .read_until(handle, &delim)
+                                .map_err(|e| env.error(e))?;
+                            env.push(Array::from(bytes.as_slice()));
+                        }
+                        Value::Float(arr) => {
+                            let delim: Vec<u8> = arr.data.iter().map(|&x| x as u8).collect();
+                            let bytes = env
+                                .rt
+                                .backend
+                                .read_until(handle, &delim)
+                                .map_err(|e| env.error(e))?;
+                            env.push(Array::from(bytes.as_slice()));
+                        }
                         Value::Char(arr) => {
                             let delim: Vec<u8> = arr.data.iter().collect::<String>().into();
                             let bytes = env
@@ -1342,6 +2087,8 @@ impl SysOp {
                 let bytes: Vec<u8> = match data {
                     Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
                     Value::Byte(arr) => arr.data.into(),
+                    Value::Int(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+                    Value::Float(arr) => arr.data.iter().map(|&x| x as u8).collect(),
                     Value::Complex(_) => return Err(env.error("Cannot write complex array")),
                     Value::Char(arr) => arr.data.iter().collect::<String>().into(),
                     Value::Box(_) => return Err(env.error("Cannot write box array")),
@@ -1365,6 +2112,60 @@ impl SysOp {
                         .map_err(|e| env.error(e))?,
                 }
             }
+            SysOp::AtExit => {
+                let f = env.pop_function()?;
+                if f.signature().args != 0 {
+                    return Err(env.error(format!(
+                        "{}'s function must take no arguments, but its signature is {}",
+                        self.name(),
+                        f.signature()
+                    )));
+                }
+                env.rt.exit_hooks.0.push(f);
+            }
+            SysOp::FFoldLines => {
+                let f = env.pop_function()?;
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                fold_file(*self, f, &path, env, |handle, env| {
+                    let mut line = (env.rt.backend)
+                        .read_until(handle, b"\n")
+                        .map_err(|e| env.error(e))?;
+                    if line.is_empty() {
+                        return Ok(None);
+                    }
+                    if line.ends_with(b"\n") {
+                        line.pop();
+                        if line.ends_with(b"\r") {
+                            line.pop();
+                        }
+                    }
+                    let line = String::from_utf8(line).map_err(|e| env.error(e))?;
+                    Ok(Some(line.into()))
+                })?;
+            }
+            SysOp::FFoldChunks => {
+                let f = env.pop_function()?;
+                let size = env
+                    .pop(1)?
+                    .as_nat(env, "Chunk size must be a natural number")?;
+                if size == 0 {
+                    return Err(env.error("Chunk size must be positive"));
+                }
+                let path = env.pop(2)?.as_string(env, "Path must be a string")?;
+                fold_file(*self, f, &path, env, |handle, env| {
+                    let mut chunk = Vec::with_capacity(size);
+                    while chunk.len() < size {
+                        let bytes = (env.rt.backend)
+                            .read(handle, size - chunk.len())
+                            .map_err(|e| env.error(e))?;
+                        if bytes.is_empty() {
+                            break;
+                        }
+                        chunk.extend(bytes);
+                    }
+                    Ok((!chunk.is_empty()).then(|| Array::from(chunk.as_slice()).into()))
+                })?;
+            }
             SysOp::FReadAllStr => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let bytes = (env.rt.backend)
@@ -1391,32 +2192,36 @@ impl SysOp {
                 let bytes = bytes.into_iter().map(Into::into);
                 env.push(Array::<u8>::from_iter(bytes));
             }
-            SysOp::FWriteAll => {
-                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                let data = env.pop(2)?;
-                let bytes: Vec<u8> = match data {
-                    Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
-                    Value::Byte(arr) => arr.data.into(),
-
-                    Value::Complex(_) => {
-                        return Err(env.error("Cannot write complex array to file"))
-                    }
-                    Value::Char(arr) => arr.data.iter().collect::<String>().into(),
-                    Value::Box(_) => return Err(env.error("Cannot write box array to file")),
-                };
+            SysOp::FWriteAll => write_all(env, WriteMode::Overwrite)?,
+            SysOp::FReplaceAll => write_all(env, WriteMode::Replace)?,
+            SysOp::FAppendAll => write_all(env, WriteMode::Append)?,
+            SysOp::FLockShared | SysOp::FLockExclusive => {
+                let handle = env.pop(1)?;
+                let exclusive = *self == SysOp::FLockExclusive;
                 (env.rt.backend)
-                    .file_write_all(path.as_ref(), &bytes)
-                    .or_else(|e| {
-                        if path == "example.ua" {
-                            let new_ex = String::from_utf8(bytes).map_err(|e| e.to_string())?;
-                            example_ua(move |ex| *ex = new_ex);
-                            Ok(())
-                        } else {
-                            Err(e)
-                        }
-                    })
+                    .lock_file(handle.as_handle(env, "")?, exclusive, true)
+                    .map_err(|e| env.error(e))?;
+                env.push(handle);
+            }
+            SysOp::FTryLock => {
+                let exclusive = env.pop(1)?.as_bool(env, "Exclusivity must be a boolean")?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let locked = (env.rt.backend)
+                    .lock_file(handle, exclusive, false)
+                    .map_err(|e| env.error(e))?;
+                env.push(locked);
+            }
+            SysOp::FUnlock => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                env.rt
+                    .backend
+                    .unlock_file(handle)
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::FSync => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                env.rt.backend.sync(handle).map_err(|e| env.error(e))?;
+            }
             SysOp::FExists => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let exists = env.rt.backend.file_exists(&path);
@@ -1501,8 +2306,10 @@ impl SysOp {
                 #[cfg(feature = "image")]
                 {
                     let value = env.pop(1)?;
-                    let image = value_to_image(&value).map_err(|e| env.error(e))?;
-                    env.rt.backend.show_image(image).map_err(|e| env.error(e))?;
+                    let bytes = value_to_image_bytes(&value, ImageOutputFormat::Png)
+                        .map_err(|e| env.error(e))?;
+                    let output = RichOutput::new(RichOutputKind::Image, "image/png", bytes);
+                    env.rt.backend.show_rich(output).map_err(|e| env.error(e))?;
                 }
                 #[cfg(not(feature = "image"))]
                 return Err(env.error("Image encoding is not supported in this environment"));
@@ -1538,7 +2345,8 @@ impl SysOp {
                     let delay = env.pop(1)?.as_num(env, "Delay must be a number")?;
                     let value = env.pop(2)?;
                     let bytes = value_to_gif_bytes(&value, delay).map_err(|e| env.error(e))?;
-                    env.rt.backend.show_gif(bytes).map_err(|e| env.error(e))?;
+                    let output = RichOutput::new(RichOutputKind::Animation, "image/gif", bytes);
+                    env.rt.backend.show_rich(output).map_err(|e| env.error(e))?;
                 }
                 #[cfg(not(feature = "gif"))]
                 return Err(env.error("GIF encoding is not supported in this environment"));
@@ -1600,7 +2408,8 @@ impl SysOp {
                     let value = env.pop(1)?;
                     let bytes = value_to_wav_bytes(&value, env.rt.backend.audio_sample_rate())
                         .map_err(|e| env.error(e))?;
-                    env.rt.backend.play_audio(bytes).map_err(|e| env.error(e))?;
+                    let output = RichOutput::new(RichOutputKind::Audio, "audio/wav", bytes);
+                    env.rt.backend.show_rich(output).map_err(|e| env.error(e))?;
                 }
                 #[cfg(not(feature = "audio_encode"))]
                 return Err(env.error("Audio encoding is not supported in this environment"));
@@ -1647,6 +2456,21 @@ impl SysOp {
                     return Err(env.error(e));
                 }
             }
+            SysOp::AudioSynth => {
+                let node = env.pop(1)?;
+                let sample_rate = env.rt.backend.audio_sample_rate();
+                let mut synth = Synth::from_value(&node, sample_rate, env)?;
+                if let Err(e) = env.rt.backend.stream_audio(Box::new(move |time_array| {
+                    Ok((time_array.iter())
+                        .map(|_| {
+                            let sample = synth.sample();
+                            [sample, sample]
+                        })
+                        .collect())
+                })) {
+                    return Err(env.error(e));
+                }
+            }
             SysOp::ClipboardGet => {
                 let contents = env.rt.backend.clipboard().map_err(|e| env.error(e))?;
                 env.push(contents);
@@ -1657,6 +2481,163 @@ impl SysOp {
                     .set_clipboard(&contents)
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::SecretGet => {
+                let name = env.pop(1)?.as_string(env, "Secret name must be a string")?;
+                let secret = (env.rt.backend)
+                    .secret(&name)
+                    .map_err(|e| env.error(e))?
+                    .ok_or_else(|| env.error(format!("No secret named {name:?}")))?;
+                env.push(secret);
+            }
+            SysOp::SecretSet => {
+                let name = env.pop(1)?.as_string(env, "Secret name must be a string")?;
+                let secret = env.pop(2)?.as_string(env, "Secret must be a string")?;
+                (env.rt.backend)
+                    .set_secret(&name, &secret)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::SecretDelete => {
+                let name = env.pop(1)?.as_string(env, "Secret name must be a string")?;
+                (env.rt.backend)
+                    .delete_secret(&name)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::MidiPorts => {
+                let (inputs, outputs) = env.rt.backend.midi_ports().map_err(|e| env.error(e))?;
+                env.push(Array::<Boxed>::from_iter(outputs));
+                env.push(Array::<Boxed>::from_iter(inputs));
+            }
+            SysOp::MidiInput | SysOp::MidiOutput => {
+                let port = env.pop(1)?.as_string(env, "MIDI port must be a string")?;
+                let handle = if *self == SysOp::MidiInput {
+                    let (handle, name) = (env.rt.backend)
+                        .midi_open_input(&port)
+                        .map_err(|e| env.error(e))?;
+                    handle.value(HandleKind::MidiInput(name))
+                } else {
+                    let (handle, name) = (env.rt.backend)
+                        .midi_open_output(&port)
+                        .map_err(|e| env.error(e))?;
+                    handle.value(HandleKind::MidiOutput(name))
+                };
+                env.push(handle);
+            }
+            SysOp::MidiReceive => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let messages = (env.rt.backend)
+                    .midi_receive(handle)
+                    .map_err(|e| env.error(e))?;
+                let messages = messages
+                    .into_iter()
+                    .map(|message| Boxed(Array::<u8>::from_iter(message).into()));
+                env.push(Array::<Boxed>::from_iter(messages));
+            }
+            SysOp::MidiSend => {
+                let message = env.pop(1)?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let messages = if message.rank() == 2 {
+                    message.into_rows().collect()
+                } else {
+                    vec![message]
+                };
+                for message in messages {
+                    let bytes = message.as_bytes(env, "MIDI message must be a list of bytes")?;
+                    (env.rt.backend)
+                        .midi_send(handle, &bytes)
+                        .map_err(|e| env.error(e))?;
+                }
+            }
+            SysOp::SharedMemOpen => {
+                let name = env
+                    .pop(1)?
+                    .as_string(env, "Shared memory name must be a string")?;
+                let size = env.pop(2)?.as_nat(env, "Size must be a natural number")?;
+                validate_size::<u8>([size], env)?;
+                let handle = (env.rt.backend)
+                    .shared_memory_open(&name, size)
+                    .map_err(|e| env.error(e))?
+                    .value(HandleKind::SharedMemory(name));
+                env.push(handle);
+            }
+            SysOp::SharedMemRead => {
+                let offset = env.pop(1)?.as_nat(env, "Offset must be a natural number")?;
+                let count = env
+                    .pop(2)?
+                    .as_nat_or_inf(env, "Count must be a natural number")?;
+                let handle = env.pop(3)?.as_handle(env, "")?;
+                let bytes = (env.rt.backend)
+                    .shared_memory_read(handle, offset, count)
+                    .map_err(|e| env.error(e))?;
+                env.push(Array::<u8>::from_iter(bytes));
+            }
+            SysOp::SharedMemWrite => {
+                let offset = env.pop(1)?.as_nat(env, "Offset must be a natural number")?;
+                let bytes = match env.pop(2)? {
+                    Value::Char(arr) => arr.data.iter().collect::<String>().into_bytes(),
+                    val => val.as_bytes(env, "Contents must be a list of bytes or a string")?,
+                };
+                let handle = env.pop(3)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .shared_memory_write(handle, offset, &bytes)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::SharedMemDelete => {
+                let name = env
+                    .pop(1)?
+                    .as_string(env, "Shared memory name must be a string")?;
+                (env.rt.backend)
+                    .shared_memory_delete(&name)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::KvOpen => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let path = PathBuf::from(path);
+                let handle = (env.rt.backend)
+                    .kv_open(&path)
+                    .map_err(|e| env.error(e))?
+                    .value(HandleKind::KvStore(path));
+                env.push(handle);
+            }
+            SysOp::KvGet => {
+                let key = env.pop(1)?.as_string(env, "Key must be a string")?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let bytes = (env.rt.backend)
+                    .kv_get(handle, &key)
+                    .map_err(|e| env.error(e))?
+                    .ok_or_else(|| env.error(format!("No entry with key {key:?}")))?;
+                env.push(Value::from_binary(&bytes, env)?);
+            }
+            SysOp::KvPut => {
+                let key = env.pop(1)?.as_string(env, "Key must be a string")?;
+                let bytes = env.pop(2)?.to_binary(env)?;
+                let handle = env.pop(3)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .kv_put(handle, &key, &bytes)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::KvDelete => {
+                let key = env.pop(1)?.as_string(env, "Key must be a string")?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .kv_delete(handle, &key)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::KvScan => {
+                let prefix = env.pop(1)?.as_string(env, "Prefix must be a string")?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let entries = (env.rt.backend)
+                    .kv_scan(handle, &prefix)
+                    .map_err(|e| env.error(e))?;
+                let mut keys = Vec::with_capacity(entries.len());
+                let mut values = Vec::with_capacity(entries.len());
+                for (key, bytes) in entries {
+                    keys.push(key);
+                    values.push(Boxed(Value::from_binary(&bytes, env)?));
+                }
+                let mut values = Value::from(Array::from_iter(values));
+                values.map(Array::<Boxed>::from_iter(keys).into(), env)?;
+                env.push(values);
+            }
             SysOp::Sleep => {
                 let mut seconds = env
                     .pop(1)?
@@ -1668,6 +2649,12 @@ impl SysOp {
                 }
                 env.rt.backend.sleep(seconds).map_err(|e| env.error(e))?;
             }
+            SysOp::MonotonicClock => {
+                let time = (env.rt.backend)
+                    .monotonic_time()
+                    .map_err(|e| env.error(e))?;
+                env.push(time);
+            }
             SysOp::TcpListen => {
                 let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
                 let handle = (env.rt.backend)
@@ -1766,6 +2753,50 @@ impl SysOp {
                     .map_err(|e| env.error(e))?;
                 env.push(res);
             }
+            SysOp::Smtp => {
+                let email = value_to_email(&env.pop(1)?, env)?;
+                (env.rt.backend)
+                    .send_email(&email)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::OscSend => {
+                let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
+                let address = env.pop(2)?.as_string(env, "OSC address must be a string")?;
+                let args = value_to_osc_args(env.pop(3)?, env)?;
+                let packet = osc::encode(&OscMessage { address, args });
+                (env.rt.backend)
+                    .osc_send(&addr, &packet)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::OscListen => {
+                let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
+                let handle = (env.rt.backend)
+                    .osc_listen(&addr)
+                    .map_err(|e| env.error(e))?;
+                let sock_addr = (env.rt.backend)
+                    .osc_addr(handle)
+                    .map_err(|e| env.error(e))?;
+                env.push(handle.value(HandleKind::OscSocket(sock_addr)));
+            }
+            SysOp::OscReceive => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let packets = (env.rt.backend)
+                    .osc_receive(handle)
+                    .map_err(|e| env.error(e))?;
+                let mut addresses = Vec::new();
+                let mut args = Vec::new();
+                for packet in packets {
+                    // Malformed packets are dropped, as UDP does with corrupted ones
+                    for message in osc::decode(&packet).unwrap_or_default() {
+                        addresses.push(message.address);
+                        let message_args = message.args.into_iter().map(osc_arg_to_value);
+                        let message_args = Array::<Boxed>::from_iter(message_args.map(Boxed));
+                        args.push(Boxed(message_args.into()));
+                    }
+                }
+                env.push(Array::<Boxed>::from_iter(args));
+                env.push(Array::<Boxed>::from_iter(addresses));
+            }
             SysOp::Close => {
                 let handle = env.pop(1)?.as_handle(env, "")?;
                 env.rt.backend.close(handle).map_err(|e| env.error(e))?;
@@ -1806,6 +2837,32 @@ impl SysOp {
                     env.push(handle.value(kind(command.clone())));
                 }
             }
+            SysOp::ChildStatus => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let status = env
+                    .rt
+                    .backend
+                    .child_status(handle)
+                    .map_err(|e| env.error(e))?;
+                env.push(status.map_or(f64::INFINITY, f64::from));
+            }
+            SysOp::ChildKill => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                env.rt
+                    .backend
+                    .child_kill(handle)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::ChildResources => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let (cpu, memory) = (env.rt.backend)
+                    .child_resources(handle)
+                    .map_err(|e| env.error(e))?;
+                let keys = Array::<Boxed>::from_iter(["cpu", "memory"].map(String::from));
+                let mut resources = Value::from_iter([cpu, memory]);
+                resources.map(keys.into(), env)?;
+                env.push(resources);
+            }
             SysOp::ChangeDirectory => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 (env.rt.backend)
@@ -1887,6 +2944,85 @@ impl SysOp {
     }
 }
 
+/// How [`write_all`] writes to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WriteMode {
+    /// Truncate the file and write to it
+    Overwrite,
+    /// Replace the file all at once
+    Replace,
+    /// Add to the end of the file
+    Append,
+}
+
+/// Write the contents of an array to a file
+pub(crate) fn write_all(env: &mut Uiua, mode: WriteMode) -> UiuaResult {
+    let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+    let data = env.pop(2)?;
+    let bytes: Vec<u8> = match data {
+        Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+        Value::Byte(arr) => arr.data.into(),
+        Value::Int(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+        Value::Float(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+        Value::Complex(_) => return Err(env.error("Cannot write complex array to file")),
+        Value::Char(arr) => arr.data.iter().collect::<String>().into(),
+        Value::Box(_) => return Err(env.error("Cannot write box array to file")),
+    };
+    let backend = &env.rt.backend;
+    match mode {
+        WriteMode::Overwrite => backend.file_write_all(path.as_ref(), &bytes),
+        WriteMode::Replace => backend.file_replace_all(path.as_ref(), &bytes),
+        WriteMode::Append => backend.file_append_all(path.as_ref(), &bytes),
+    }
+    .or_else(|e| {
+        if path == "example.ua" {
+            let new_ex = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+            example_ua(move |ex| match mode {
+                WriteMode::Append => ex.push_str(&new_ex),
+                _ => *ex = new_ex,
+            });
+            Ok(())
+        } else {
+            Err(e)
+        }
+    })
+    .map_err(|e| env.error(e))
+}
+
+/// Fold a function over the pieces of a file
+///
+/// `next` reads the next piece from the file handle, returning `None` at the end of the file.
+fn fold_file(
+    op: SysOp,
+    f: Function,
+    path: &str,
+    env: &mut Uiua,
+    mut next: impl FnMut(Handle, &mut Uiua) -> UiuaResult<Option<Value>>,
+) -> UiuaResult {
+    if f.signature() != (2, 1) {
+        return Err(env.error(format!(
+            "{}'s function's signature must be {}, but it is {}",
+            op.name(),
+            Signature::new(2, 1),
+            f.signature()
+        )));
+    }
+    let acc = env.pop("accumulator")?;
+    let handle = (env.rt.backend)
+        .open_file(path.as_ref(), false)
+        .map_err(|e| env.error(e))?;
+    env.push(acc);
+    let res = (|| {
+        while let Some(piece) = next(handle, env)? {
+            env.push(piece);
+            env.call(f.clone())?;
+        }
+        Ok(())
+    })();
+    env.rt.backend.close(handle).map_err(|e| env.error(e))?;
+    res
+}
+
 fn value_to_command(value: &Value, env: &Uiua) -> UiuaResult<(String, Vec<String>)> {
     let mut strings = Vec::new();
     match value {
@@ -1934,6 +3070,18 @@ fn value_to_command(value: &Value, env: &Uiua) -> UiuaResult<(String, Vec<String
                 value.type_name_plural()
             )))
         }
+        Value::Int(_) => {
+            return Err(env.error(format!(
+                "Command must be a string or box array, but it is {}",
+                value.type_name_plural()
+            )))
+        }
+        Value::Float(_) => {
+            return Err(env.error(format!(
+                "Command must be a string or box array, but it is {}",
+                value.type_name_plural()
+            )))
+        }
         Value::Byte(_) => {
             return Err(env.error(format!(
                 "Command must be a string or box array, but it is {}",
@@ -1955,6 +3103,144 @@ fn value_to_command(value: &Value, env: &Uiua) -> UiuaResult<(String, Vec<String
     Ok((command, strings))
 }
 
+fn value_to_osc_args(value: Value, env: &Uiua) -> UiuaResult<Vec<OscArg>> {
+    fn arg(value: Value, env: &Uiua) -> UiuaResult<OscArg> {
+        Ok(match value {
+            Value::Char(arr) if arr.rank() <= 1 => OscArg::Str(arr.data.iter().collect()),
+            Value::Byte(arr) if arr.rank() == 1 => OscArg::Blob(arr.data.into()),
+            value if value.rank() == 0 => {
+                let n = value.as_num(env, "OSC arguments must be numbers, strings, or bytes")?;
+                if n.fract() == 0.0 && (i32::MIN as f64..=i32::MAX as f64).contains(&n) {
+                    OscArg::Int(n as i32)
+                } else {
+                    OscArg::Float(n as f32)
+                }
+            }
+            value => {
+                return Err(env.error(format!(
+                    "OSC arguments must be numbers, strings, or bytes, \
+                    but one is an array of shape {}",
+                    value.shape()
+                )))
+            }
+        })
+    }
+    match value {
+        Value::Box(arr) if arr.rank() == 1 => (arr.data.into_iter())
+            .map(|Boxed(val)| arg(val, env))
+            .collect(),
+        Value::Box(arr) if arr.rank() == 0 => {
+            let Boxed(val) = arr.data.into_iter().next().unwrap();
+            Ok(vec![arg(val, env)?])
+        }
+        value @ (Value::Char(_) | Value::Byte(_)) if value.rank() <= 1 => {
+            Ok(vec![arg(value, env)?])
+        }
+        value if value.rank() == 1 => value.into_rows().map(|val| arg(val, env)).collect(),
+        value => Ok(vec![arg(value, env)?]),
+    }
+}
+
+fn osc_arg_to_value(arg: OscArg) -> Value {
+    match arg {
+        OscArg::Int(i) => i.into(),
+        OscArg::Float(f) => f64::from(f).into(),
+        OscArg::Long(i) => (i as f64).into(),
+        OscArg::Double(f) => f.into(),
+        OscArg::Str(s) => s.into(),
+        OscArg::Blob(bytes) => Array::<u8>::from_iter(bytes).into(),
+        OscArg::Bool(b) => b.into(),
+        OscArg::Nil => Value::default(),
+    }
+}
+
+fn value_to_email(value: &Value, env: &Uiua) -> UiuaResult<Email> {
+    if !value.is_map() {
+        return Err(env.error(format!(
+            "Email must be a map, but it is {}",
+            value.type_name_plural()
+        )));
+    }
+    let addresses = |value: &Value| -> UiuaResult<Vec<String>> {
+        match value {
+            Value::Char(arr) if arr.rank() <= 1 => Ok(vec![arr.data.iter().collect()]),
+            _ => (value.rows())
+                .map(|row| {
+                    row.unboxed()
+                        .as_string(env, "Email addresses must be strings")
+                })
+                .collect(),
+        }
+    };
+    let mut email = Email::default();
+    let (mut user, mut password, mut security) = (None, None, None);
+    for (key, value) in value.map_kv() {
+        let value = value.unboxed();
+        let key = key.unboxed().as_string(env, "Email keys must be strings")?;
+        let string = |requirement| value.as_string(env, requirement);
+        match key.as_str() {
+            "server" => email.server = string("Email server must be a string")?,
+            "user" => user = Some(string("Email user must be a string")?),
+            "password" => password = Some(string("Email password must be a string")?),
+            "security" => security = Some(string("Email security must be a string")?),
+            "from" => email.from = string("Email sender must be a string")?,
+            "to" => email.to = addresses(&value)?,
+            "cc" => email.cc = addresses(&value)?,
+            "bcc" => email.bcc = addresses(&value)?,
+            "subject" => email.subject = string("Email subject must be a string")?,
+            "body" => email.body = string("Email body must be a string")?,
+            key => return Err(env.error(format!("Unknown email key {key:?}"))),
+        }
+    }
+    email.security = match security.as_deref() {
+        Some("tls") => SmtpSecurity::Tls,
+        Some("starttls") => SmtpSecurity::StartTls,
+        Some("none") => SmtpSecurity::None,
+        Some(security) => {
+            return Err(env.error(format!(
+                "Email security must be \"tls\", \"starttls\", or \"none\", \
+                but it is {security:?}"
+            )))
+        }
+        None if email.server.ends_with(":465") => SmtpSecurity::Tls,
+        None => SmtpSecurity::StartTls,
+    };
+    email.credentials = match (user, password) {
+        (Some(user), Some(password)) => Some((user, password)),
+        (None, None) => None,
+        _ => return Err(env.error("Email user and password must be given together")),
+    };
+    if email.server.is_empty() {
+        return Err(env.error("Email must have a server"));
+    }
+    if email.from.is_empty() {
+        return Err(env.error("Email must have a sender"));
+    }
+    if email.to.is_empty() && email.cc.is_empty() && email.bcc.is_empty() {
+        return Err(env.error("Email must have at least one recipient"));
+    }
+    // Line breaks in addresses could inject SMTP commands or headers
+    for address in [&email.from]
+        .into_iter()
+        .chain(&email.to)
+        .chain(&email.cc)
+        .chain(&email.bcc)
+    {
+        if address.contains(['\r', '\n']) {
+            return Err(env.error(format!(
+                "Email addresses may not contain line breaks, but {address:?} does"
+            )));
+        }
+    }
+    if email.credentials.is_some() && email.security == SmtpSecurity::None {
+        return Err(env.error(
+            "Email user and password cannot be sent without TLS. \
+            Use \"tls\" or \"starttls\" security instead of \"none\".",
+        ));
+    }
+    Ok(email)
+}
+
 #[doc(hidden)]
 #[cfg(feature = "image")]
 pub fn value_to_image_bytes(value: &Value, format: ImageOutputFormat) -> Result<Vec<u8>, String> {
@@ -2008,6 +3294,8 @@ pub fn value_to_image(value: &Value) -> Result<DynamicImage, String> {
     let bytes = match value {
         Value::Num(nums) => nums.data.iter().map(|f| (*f * 255.0) as u8).collect(),
         Value::Byte(bytes) => bytes.data.iter().map(|&b| (b > 0) as u8 * 255).collect(),
+        Value::Int(ints) => ints.data.iter().map(|&i| (i > 0) as u8 * 255).collect(),
+        Value::Float(floats) => floats.data.iter().map(|&f| (f * 255.0) as u8).collect(),
         _ => return Err("Image must be a numeric array".into()),
     };
     #[allow(clippy::match_ref_pats)]
@@ -2042,6 +3330,8 @@ pub fn value_to_sample(audio: &Value) -> Result<Vec<[f32; 2]>, String> {
     let unrolled: Vec<f32> = match audio {
         Value::Num(nums) => nums.data.iter().map(|&f| f as f32).collect(),
         Value::Byte(byte) => byte.data.iter().map(|&b| b as f32).collect(),
+        Value::Int(ints) => ints.data.iter().map(|&i| i as f32).collect(),
+        Value::Float(floats) => floats.data.to_vec(),
         _ => return Err("Audio must be a numeric array".into()),
     };
     let (length, mut channels) = match audio.rank() {
@@ -2082,6 +3372,8 @@ pub fn value_to_audio_channels(audio: &Value) -> Result<Vec<Vec<f64>>, String> {
     let interleaved: Vec<f64> = match audio {
         Value::Num(nums) => nums.data.iter().copied().collect(),
         Value::Byte(byte) => byte.data.iter().map(|&b| b as f64).collect(),
+        Value::Int(ints) => ints.data.iter().map(|&i| i as f64).collect(),
+        Value::Float(floats) => floats.data.iter().map(|&f| f as f64).collect(),
         _ => return Err("Audio must be a numeric array".into()),
     };
     let (length, mut channels) = match audio.rank() {
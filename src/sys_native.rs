@@ -2,23 +2,45 @@ use std::{
     any::Any,
     env,
     fs::{self, File, OpenOptions},
-    io::{stderr, stdin, stdout, BufReader, Read, Write},
+    io::{self, stderr, stdin, stdout, BufRead, BufReader, IsTerminal, Read, Write},
     net::*,
     path::{Path, PathBuf},
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, Stdio},
     slice,
     sync::{
         atomic::{self, AtomicBool, AtomicU64},
-        Arc,
+        mpsc, Arc,
     },
-    thread::sleep,
-    time::Duration,
+    thread::{self, sleep, JoinHandle},
+    time::{Duration, Instant},
 };
 
-use crate::{Handle, SysBackend};
+#[cfg(feature = "midi")]
+use std::{collections::HashMap, mem::take};
+
+#[cfg(feature = "smtp")]
+use crate::Email;
+use crate::{Handle, RichOutput, RichOutputKind, SysBackend};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 
+/// The keyring service that secrets are stored under
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "uiua";
+
+/// Whether a keyring error means that secrets should be kept in memory instead
+#[cfg(feature = "keyring")]
+fn keyring_unavailable(e: &keyring::Error) -> bool {
+    matches!(
+        e,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+/// The table that key-value store entries are kept in
+#[cfg(feature = "kv")]
+const KV_TABLE: redb::TableDefinition<&str, &[u8]> = redb::TableDefinition::new("uiua");
+
 /// The defualt native system backend
 #[derive(Default)]
 pub struct NativeSys;
@@ -36,17 +58,41 @@ struct GlobalNativeSys {
     tls_sockets: DashMap<Handle, TlsSocket>,
     hostnames: DashMap<Handle, String>,
     git_paths: DashMap<String, Result<PathBuf, String>>,
+    #[cfg(feature = "tls")]
+    url_paths: DashMap<String, Result<PathBuf, String>>,
     #[cfg(feature = "audio")]
     audio_stream_time: parking_lot::Mutex<Option<f64>>,
     #[cfg(feature = "audio")]
     audio_time_socket: parking_lot::Mutex<Option<std::sync::Arc<std::net::UdpSocket>>>,
     colored_errors: DashMap<String, String>,
+    /// Secrets that could not be stored in the keyring
+    secrets: DashMap<String, String>,
+    /// Mapped shared memory regions, whose files are also in `files`
+    shared_memory: DashMap<Handle, memmap2::MmapRaw>,
+    osc_sockets: DashMap<Handle, UdpSocket>,
+    /// Locks held on files, keyed by the file's handle
+    file_locks: DashMap<Handle, FileLock>,
+    #[cfg(feature = "kv")]
+    kv_stores: DashMap<Handle, redb::Database>,
+    #[cfg(feature = "midi")]
+    midi_inputs: parking_lot::Mutex<HashMap<Handle, MidiIn>>,
+    #[cfg(feature = "midi")]
+    midi_outputs: parking_lot::Mutex<HashMap<Handle, midir::MidiOutputConnection>>,
     #[cfg(feature = "ffi")]
     ffi: crate::FfiState,
     #[cfg(all(feature = "gif", feature = "invoke"))]
     gifs_child: parking_lot::Mutex<Option<Child>>,
 }
 
+/// A lock held on a file
+///
+/// `fd-lock` only releases a lock when its guard is dropped,
+/// so the guard is kept on a thread that waits to be told to unlock.
+struct FileLock {
+    unlock: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
 enum SysStream<'a> {
     File(dashmap::mapref::one::RefMut<'a, Handle, BufReader<File>>),
     ChildStdin(dashmap::mapref::one::RefMut<'a, Handle, ChildStream<ChildStdin>>),
@@ -56,15 +102,34 @@ enum SysStream<'a> {
     TlsSocket(dashmap::mapref::one::Ref<'a, Handle, TlsSocket>),
 }
 
+/// An open MIDI input port and the messages it has received
+#[cfg(feature = "midi")]
+struct MidiIn {
+    _conn: midir::MidiInputConnection<()>,
+    messages: Arc<parking_lot::Mutex<Vec<Vec<u8>>>>,
+}
+
+/// Find the first MIDI port whose name contains `port`
+#[cfg(feature = "midi")]
+fn find_midi_port<T: midir::MidiIO>(io: &T, port: &str) -> Result<(T::Port, String), String> {
+    for p in io.ports() {
+        let name = io.port_name(&p).map_err(|e| e.to_string())?;
+        if name.contains(port) {
+            return Ok((p, name));
+        }
+    }
+    Err(format!("No MIDI port matching {port:?}"))
+}
+
 struct ChildStream<T> {
     stream: T,
-    child: Arc<Child>,
+    child: Arc<parking_lot::Mutex<Child>>,
 }
 
 impl<T> Drop for ChildStream<T> {
     fn drop(&mut self) {
         if let Some(child) = Arc::get_mut(&mut self.child) {
-            _ = child.kill();
+            _ = child.get_mut().kill();
         }
     }
 }
@@ -141,11 +206,23 @@ impl Default for GlobalNativeSys {
             tls_sockets: DashMap::new(),
             hostnames: DashMap::new(),
             git_paths: DashMap::new(),
+            #[cfg(feature = "tls")]
+            url_paths: DashMap::new(),
             #[cfg(feature = "audio")]
             audio_stream_time: parking_lot::Mutex::new(None),
             #[cfg(feature = "audio")]
             audio_time_socket: parking_lot::Mutex::new(None),
             colored_errors: DashMap::new(),
+            secrets: DashMap::new(),
+            shared_memory: DashMap::new(),
+            osc_sockets: DashMap::new(),
+            file_locks: DashMap::new(),
+            #[cfg(feature = "kv")]
+            kv_stores: DashMap::new(),
+            #[cfg(feature = "midi")]
+            midi_inputs: Default::default(),
+            #[cfg(feature = "midi")]
+            midi_outputs: Default::default(),
             #[cfg(feature = "ffi")]
             ffi: Default::default(),
             #[cfg(all(feature = "gif", feature = "invoke"))]
@@ -188,6 +265,35 @@ impl GlobalNativeSys {
             return Err("Invalid file handle".to_string());
         })
     }
+    /// Get the child process that a stream handle belongs to
+    fn get_child(&self, handle: Handle) -> Result<Arc<parking_lot::Mutex<Child>>, String> {
+        if let Some(stream) = self.child_stdins.get(&handle) {
+            Ok(stream.child.clone())
+        } else if let Some(stream) = self.child_stdouts.get(&handle) {
+            Ok(stream.child.clone())
+        } else if let Some(stream) = self.child_stderrs.get(&handle) {
+            Ok(stream.child.clone())
+        } else {
+            Err("Invalid child process handle".into())
+        }
+    }
+    /// Get another handle to an open file
+    ///
+    /// This is for blocking operations, which should not hold a reference into the file map.
+    /// The new handle shares the file's position and locks.
+    fn file_clone(&self, handle: Handle) -> Result<File, String> {
+        let file = self.files.get(&handle).ok_or("Invalid file handle")?;
+        file.get_ref().try_clone().map_err(|e| e.to_string())
+    }
+    /// Release the lock held on a file, returning whether there was one
+    fn release_file_lock(&self, handle: Handle) -> bool {
+        let Some((_, lock)) = self.file_locks.remove(&handle) else {
+            return false;
+        };
+        _ = lock.unlock.send(());
+        _ = lock.thread.join();
+        true
+    }
     fn get_tcp_listener<T>(&self, handle: Handle, f: impl FnOnce(&TcpListener) -> T) -> Option<T> {
         if let Some(listener) = self.tcp_listeners.get(&handle) {
             Some(f(&listener))
@@ -206,6 +312,22 @@ impl GlobalNativeSys {
 
 static NATIVE_SYS: Lazy<GlobalNativeSys> = Lazy::new(Default::default);
 
+/// Get the path of the file that backs a named region of shared memory
+///
+/// Other programs can map this file to share memory with Uiua programs that use `&shmo`.
+pub fn shared_memory_path(name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\', '\0']) || name.contains("..") {
+        return Err(format!("Invalid shared memory name {name:?}"));
+    }
+    let dir = Path::new("/dev/shm");
+    let dir = if cfg!(target_os = "linux") && dir.is_dir() {
+        dir.to_path_buf()
+    } else {
+        env::temp_dir()
+    };
+    Ok(dir.join(format!("uiua-shm-{name}")))
+}
+
 #[cfg(all(feature = "audio", feature = "binary"))]
 #[doc(hidden)]
 pub fn set_audio_stream_time(time: f64) {
@@ -254,6 +376,27 @@ impl SysBackend for NativeSys {
         stderr.write_all(s.as_bytes()).map_err(|e| e.to_string())?;
         stderr.flush().map_err(|e| e.to_string())
     }
+    fn report_progress(&self, message: &str, fraction: f64) -> Result<(), String> {
+        let mut stderr = stderr().lock();
+        if !output_enabled() || !stderr.is_terminal() {
+            return Ok(());
+        }
+        let width = term_size::dimensions().map_or(80, |(w, _)| w);
+        let percent = format!("{:>3.0}%", fraction * 100.0);
+        // Leave room for the brackets, the percentage, and the message
+        let bar_width = (width.saturating_sub(message.chars().count() + percent.len() + 5)).min(40);
+        let filled = (fraction * bar_width as f64).round() as usize;
+        let bar = format!("{}{}", "█".repeat(filled), " ".repeat(bar_width - filled));
+        // Clear the line and draw the bar in place of the previous one
+        let mut report = format!("\r\x1b[2K[{bar}] {percent} {message}");
+        if fraction >= 1.0 {
+            report.push('\n');
+        }
+        stderr
+            .write_all(report.as_bytes())
+            .map_err(|e| e.to_string())?;
+        stderr.flush().map_err(|e| e.to_string())
+    }
     fn print_str_trace(&self, s: &str) {
         if !output_enabled() {
             return;
@@ -356,6 +499,96 @@ impl SysBackend for NativeSys {
         NATIVE_SYS.files.insert(handle, BufReader::new(file));
         Ok(handle)
     }
+    fn file_replace_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        // Write to a temporary file next to the target and rename it into place,
+        // so the file has either its old contents or its new ones
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.into());
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&temp)?;
+            file.write_all(contents)?;
+            if let Ok(meta) = fs::metadata(&path) {
+                file.set_permissions(meta.permissions())?;
+            }
+            file.sync_all()?;
+            fs::rename(&temp, &path)?;
+            // Sync the directory so that the rename itself is stored
+            #[cfg(unix)]
+            {
+                let dir = (path.parent())
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                File::open(dir)?.sync_all()?;
+            }
+            Ok(())
+        };
+        write().map_err(|e| {
+            _ = fs::remove_file(&temp);
+            format!("{e} {}", path.display())
+        })
+    }
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        let file = (OpenOptions::new().create(true).append(true))
+            .open(path)
+            .map_err(|e| format!("{e} {}", path.display()))?;
+        // Hold an exclusive lock so that other programs' appends are not interleaved with this one
+        let mut lock = fd_lock::RwLock::new(file);
+        let mut file = lock.write().map_err(|e| e.to_string())?;
+        file.write_all(contents).map_err(|e| e.to_string())
+    }
+    fn sync(&self, handle: Handle) -> Result<(), String> {
+        match NATIVE_SYS.get_stream(handle)? {
+            SysStream::File(file) => file.get_ref().sync_all().map_err(|e| e.to_string()),
+            _ => Err("Only file handles can be synced".into()),
+        }
+    }
+    fn lock_file(&self, handle: Handle, exclusive: bool, block: bool) -> Result<bool, String> {
+        let file = NATIVE_SYS.file_clone(handle)?;
+        // Locking an already locked handle replaces its lock
+        NATIVE_SYS.release_file_lock(handle);
+        let (taken_send, taken_recv) = mpsc::channel();
+        let (unlock, unlock_recv) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let hold = |taken: io::Result<()>| {
+                let held = taken.is_ok();
+                _ = taken_send.send(taken);
+                if held {
+                    _ = unlock_recv.recv();
+                }
+            };
+            let mut lock = fd_lock::RwLock::new(file);
+            if exclusive {
+                match if block {
+                    lock.write()
+                } else {
+                    lock.try_write()
+                } {
+                    Ok(_guard) => hold(Ok(())),
+                    Err(e) => hold(Err(e)),
+                }
+            } else {
+                match if block { lock.read() } else { lock.try_read() } {
+                    Ok(_guard) => hold(Ok(())),
+                    Err(e) => hold(Err(e)),
+                }
+            }
+        });
+        match taken_recv.recv().map_err(|e| e.to_string())? {
+            Ok(()) => {
+                (NATIVE_SYS.file_locks).insert(handle, FileLock { unlock, thread });
+                Ok(true)
+            }
+            Err(e) if !block && e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+    fn unlock_file(&self, handle: Handle) -> Result<(), String> {
+        if !NATIVE_SYS.release_file_lock(handle) && !NATIVE_SYS.files.contains_key(&handle) {
+            return Err("Invalid file handle".into());
+        }
+        Ok(())
+    }
     fn delete(&self, path: &str) -> Result<(), String> {
         let path = Path::new(path);
         if !path.exists() {
@@ -442,6 +675,33 @@ impl SysBackend for NativeSys {
             }
         })
     }
+    fn read_until(&self, handle: Handle, delim: &[u8]) -> Result<Vec<u8>, String> {
+        let mut buffer = Vec::new();
+        // Files are buffered, so they can be searched for the delimiter directly
+        if let (SysStream::File(mut file), Some(&last)) =
+            (NATIVE_SYS.get_stream(handle)?, delim.last())
+        {
+            loop {
+                let n = file
+                    .read_until(last, &mut buffer)
+                    .map_err(|e| e.to_string())?;
+                if n == 0 || buffer.ends_with(delim) {
+                    return Ok(buffer);
+                }
+            }
+        }
+        loop {
+            let bytes = self.read(handle, 1)?;
+            if bytes.is_empty() {
+                break;
+            }
+            buffer.extend_from_slice(&bytes);
+            if buffer.ends_with(delim) {
+                break;
+            }
+        }
+        Ok(buffer)
+    }
     fn write(&self, handle: Handle, conts: &[u8]) -> Result<(), String> {
         let mut conts = conts;
         let colored;
@@ -485,73 +745,304 @@ impl SysBackend for NativeSys {
             Err(e) => Err(format!("Failed to get clipboard provider: {e}")),
         }
     }
-    fn sleep(&self, seconds: f64) -> Result<(), String> {
-        sleep(Duration::from_secs_f64(seconds));
+    fn secret(&self, name: &str) -> Result<Option<String>, String> {
+        #[cfg(feature = "keyring")]
+        match keyring::Entry::new(KEYRING_SERVICE, name).and_then(|entry| entry.get_password()) {
+            Ok(secret) => return Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => {}
+            Err(e) if keyring_unavailable(&e) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        Ok(NATIVE_SYS.secrets.get(name).map(|secret| secret.clone()))
+    }
+    fn set_secret(&self, name: &str, secret: &str) -> Result<(), String> {
+        #[cfg(feature = "keyring")]
+        match keyring::Entry::new(KEYRING_SERVICE, name)
+            .and_then(|entry| entry.set_password(secret))
+        {
+            Ok(()) => {
+                NATIVE_SYS.secrets.remove(name);
+                return Ok(());
+            }
+            Err(e) if keyring_unavailable(&e) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        NATIVE_SYS.secrets.insert(name.into(), secret.into());
         Ok(())
     }
-    #[cfg(all(feature = "terminal_image", feature = "image"))]
-    fn show_image(&self, image: image::DynamicImage) -> Result<(), String> {
-        let (width, height) = if let Some((w, h)) = term_size::dimensions() {
-            let (tw, th) = (w as u32, h.saturating_sub(1) as u32);
-            let (iw, ih) = (image.width(), image.height() / 2);
-            let scaled_to_height = (iw * th / ih.max(1), th);
-            let scaled_to_width = (tw, ih * tw / iw.max(1));
-            let (w, h) = if scaled_to_height.0 <= tw {
-                scaled_to_height
-            } else {
-                scaled_to_width
-            };
-            (Some(w), Some(h))
-        } else {
-            (None, None)
+    fn delete_secret(&self, name: &str) -> Result<(), String> {
+        #[cfg(feature = "keyring")]
+        match keyring::Entry::new(KEYRING_SERVICE, name).and_then(|entry| entry.delete_credential())
+        {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) if keyring_unavailable(&e) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        NATIVE_SYS.secrets.remove(name);
+        Ok(())
+    }
+    fn shared_memory_open(&self, name: &str, size: usize) -> Result<Handle, String> {
+        let path = shared_memory_path(name)?;
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+        // The directory is shared with other users, so the file must not be
+        // a link planted there to redirect writes
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options
+                .mode(0o600)
+                .custom_flags(rustix::fs::OFlags::NOFOLLOW.bits() as i32);
+        }
+        let file = (options.open(&path))
+            .map_err(|e| format!("Failed to open shared memory {name:?}: {e}"))?;
+        let meta = file.metadata().map_err(|e| e.to_string())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if !meta.is_file()
+                || meta.nlink() != 1
+                || meta.uid() != rustix::process::getuid().as_raw()
+            {
+                return Err(format!(
+                    "Shared memory {name:?} is backed by a file \
+                    that is not a regular file owned by this user"
+                ));
+            }
+        }
+        if meta.len() < size as u64 {
+            file.set_len(size as u64).map_err(|e| e.to_string())?;
+        }
+        // The region may be changed by other processes at any time,
+        // so it is only accessed through raw pointers
+        let map = memmap2::MmapRaw::map_raw(&file)
+            .map_err(|e| format!("Failed to map shared memory {name:?}: {e}"))?;
+        let handle = NATIVE_SYS.new_handle();
+        NATIVE_SYS.files.insert(handle, BufReader::new(file));
+        NATIVE_SYS.shared_memory.insert(handle, map);
+        Ok(handle)
+    }
+    fn shared_memory_read(
+        &self,
+        handle: Handle,
+        offset: usize,
+        count: Option<usize>,
+    ) -> Result<Vec<u8>, String> {
+        let map = (NATIVE_SYS.shared_memory.get(&handle)).ok_or("Invalid shared memory handle")?;
+        let start = offset.min(map.len());
+        let end = count.map_or(map.len(), |count| {
+            start.saturating_add(count).min(map.len())
+        });
+        let ptr = map.as_ptr();
+        // Safety: The range is within the mapping, and each byte is read with a volatile
+        // read because other processes may write to it at the same time
+        Ok((start..end)
+            .map(|i| unsafe { ptr.add(i).read_volatile() })
+            .collect())
+    }
+    fn shared_memory_write(
+        &self,
+        handle: Handle,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<(), String> {
+        let map = (NATIVE_SYS.shared_memory.get(&handle)).ok_or("Invalid shared memory handle")?;
+        let len = map.len();
+        if offset
+            .checked_add(bytes.len())
+            .map_or(true, |end| end > len)
+        {
+            return Err(format!(
+                "Cannot write {} bytes at offset {offset} \
+                to shared memory of {len} bytes",
+                bytes.len()
+            ));
+        }
+        let ptr = map.as_mut_ptr();
+        // Safety: The range is within the mapping, and each byte is written with a volatile
+        // write because other processes may access it at the same time
+        for (i, &byte) in bytes.iter().enumerate() {
+            unsafe { ptr.add(offset + i).write_volatile(byte) };
+        }
+        Ok(())
+    }
+    fn shared_memory_delete(&self, name: &str) -> Result<(), String> {
+        match fs::remove_file(shared_memory_path(name)?) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+    #[cfg(feature = "kv")]
+    fn kv_open(&self, path: &Path) -> Result<Handle, String> {
+        let db = redb::Database::create(path)
+            .map_err(|e| format!("Failed to open key-value store {}: {e}", path.display()))?;
+        let handle = NATIVE_SYS.new_handle();
+        NATIVE_SYS.kv_stores.insert(handle, db);
+        Ok(handle)
+    }
+    #[cfg(feature = "kv")]
+    fn kv_get(&self, handle: Handle, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let db = (NATIVE_SYS.kv_stores.get(&handle)).ok_or("Invalid key-value store handle")?;
+        let txn = db.begin_read().map_err(|e| e.to_string())?;
+        let table = match txn.open_table(KV_TABLE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(e.to_string()),
         };
-        viuer::print(
-            &image,
-            &viuer::Config {
-                width,
-                height,
-                absolute_offset: false,
-                transparent: true,
-                ..Default::default()
-            },
-        )
-        .map(drop)
-        .map_err(|e| format!("Failed to show image: {e}"))
+        let entry = table.get(key).map_err(|e| e.to_string())?;
+        Ok(entry.map(|bytes| bytes.value().to_vec()))
     }
-    #[cfg(all(feature = "gif", feature = "invoke"))]
-    fn show_gif(&self, gif_bytes: Vec<u8>) -> Result<(), String> {
-        (move || -> std::io::Result<()> {
-            let temp_path = std::env::temp_dir().join("show.gif");
-            fs::write(&temp_path, gif_bytes)?;
-            let commands = open::commands(&temp_path);
-            if let Some(mut command) = commands.into_iter().next() {
-                if let Some(mut child) = NATIVE_SYS
-                    .gifs_child
-                    .lock()
-                    .replace(command.arg(&temp_path).spawn()?)
-                {
-                    child.kill()?;
-                }
+    #[cfg(feature = "kv")]
+    fn kv_put(&self, handle: Handle, key: &str, value: &[u8]) -> Result<(), String> {
+        let db = (NATIVE_SYS.kv_stores.get(&handle)).ok_or("Invalid key-value store handle")?;
+        let txn = db.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = txn.open_table(KV_TABLE).map_err(|e| e.to_string())?;
+            table.insert(key, value).map_err(|e| e.to_string())?;
+        }
+        txn.commit().map_err(|e| e.to_string())
+    }
+    #[cfg(feature = "kv")]
+    fn kv_delete(&self, handle: Handle, key: &str) -> Result<(), String> {
+        let db = (NATIVE_SYS.kv_stores.get(&handle)).ok_or("Invalid key-value store handle")?;
+        let txn = db.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = txn.open_table(KV_TABLE).map_err(|e| e.to_string())?;
+            table.remove(key).map_err(|e| e.to_string())?;
+        }
+        txn.commit().map_err(|e| e.to_string())
+    }
+    #[cfg(feature = "kv")]
+    fn kv_scan(&self, handle: Handle, prefix: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let db = (NATIVE_SYS.kv_stores.get(&handle)).ok_or("Invalid key-value store handle")?;
+        let txn = db.begin_read().map_err(|e| e.to_string())?;
+        let table = match txn.open_table(KV_TABLE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut entries = Vec::new();
+        // Keys are sorted, so the matching keys are contiguous
+        for entry in table.range(prefix..).map_err(|e| e.to_string())? {
+            let (key, bytes) = entry.map_err(|e| e.to_string())?;
+            if !key.value().starts_with(prefix) {
+                break;
             }
-            Ok(())
-        })()
-        .map_err(|e| e.to_string())
+            entries.push((key.value().to_string(), bytes.value().to_vec()));
+        }
+        Ok(entries)
     }
-    #[cfg(feature = "audio")]
-    fn play_audio(&self, wav_bytes: Vec<u8>) -> Result<(), String> {
-        use hodaun::*;
-        match default_output::<Stereo>() {
-            Ok(mut mixer) => {
-                match wav::WavSource::new(std::collections::VecDeque::from(wav_bytes)) {
-                    Ok(source) => {
-                        mixer.add(source.resample());
-                        mixer.block();
-                        Ok(())
+    fn sleep(&self, seconds: f64) -> Result<(), String> {
+        // The OS may wake the thread late, so the last stretch is spun
+        const SPIN_TIME: Duration = Duration::from_millis(1);
+        let duration = Duration::from_nanos((seconds * 1e9).round() as u64);
+        let Some(deadline) = Instant::now().checked_add(duration) else {
+            sleep(duration);
+            return Ok(());
+        };
+        if let Some(coarse) = duration.checked_sub(SPIN_TIME) {
+            sleep(coarse);
+        }
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+        Ok(())
+    }
+    fn monotonic_time(&self) -> Result<f64, String> {
+        static START: Lazy<Instant> = Lazy::new(Instant::now);
+        Ok(START.elapsed().as_secs_f64())
+    }
+    fn show_rich(&self, output: RichOutput) -> Result<(), String> {
+        match output.kind {
+            #[cfg(all(feature = "terminal_image", feature = "image"))]
+            RichOutputKind::Image => {
+                let image = image::load_from_memory(&output.data)
+                    .map_err(|e| format!("Failed to show image: {e}"))?;
+                let (width, height) = if let Some((w, h)) = term_size::dimensions() {
+                    let (tw, th) = (w as u32, h.saturating_sub(1) as u32);
+                    let (iw, ih) = (image.width(), image.height() / 2);
+                    let scaled_to_height = (iw * th / ih.max(1), th);
+                    let scaled_to_width = (tw, ih * tw / iw.max(1));
+                    let (w, h) = if scaled_to_height.0 <= tw {
+                        scaled_to_height
+                    } else {
+                        scaled_to_width
+                    };
+                    (Some(w), Some(h))
+                } else {
+                    (None, None)
+                };
+                viuer::print(
+                    &image,
+                    &viuer::Config {
+                        width,
+                        height,
+                        absolute_offset: false,
+                        transparent: true,
+                        ..Default::default()
+                    },
+                )
+                .map(drop)
+                .map_err(|e| format!("Failed to show image: {e}"))
+            }
+            #[cfg(all(feature = "gif", feature = "invoke"))]
+            RichOutputKind::Animation => (move || -> std::io::Result<()> {
+                let temp_path = std::env::temp_dir().join("show.gif");
+                fs::write(&temp_path, output.data)?;
+                let commands = open::commands(&temp_path);
+                if let Some(mut command) = commands.into_iter().next() {
+                    if let Some(mut child) = NATIVE_SYS
+                        .gifs_child
+                        .lock()
+                        .replace(command.arg(&temp_path).spawn()?)
+                    {
+                        child.kill()?;
                     }
-                    Err(e) => Err(format!("Failed to read wav bytes: {e}")),
+                }
+                Ok(())
+            })()
+            .map_err(|e| e.to_string()),
+            #[cfg(feature = "audio")]
+            RichOutputKind::Audio => {
+                use hodaun::*;
+                match default_output::<Stereo>() {
+                    Ok(mut mixer) => {
+                        match wav::WavSource::new(std::collections::VecDeque::from(output.data)) {
+                            Ok(source) => {
+                                mixer.add(source.resample());
+                                mixer.block();
+                                Ok(())
+                            }
+                            Err(e) => Err(format!("Failed to read wav bytes: {e}")),
+                        }
+                    }
+                    Err(e) => Err(format!("Failed to initialize audio output stream: {e}")),
                 }
             }
-            Err(e) => Err(format!("Failed to initialize audio output stream: {e}").to_string()),
+            RichOutputKind::Table => {
+                let text = output.text().unwrap_or_default();
+                let rows: Vec<Vec<&str>> = text.lines().map(|l| l.split('\t').collect()).collect();
+                let mut widths = Vec::new();
+                for row in &rows {
+                    widths.resize(widths.len().max(row.len()), 0);
+                    for (width, cell) in widths.iter_mut().zip(row) {
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+                let mut table = String::new();
+                for row in rows {
+                    let cells: Vec<String> = (row.iter().zip(&widths))
+                        .map(|(cell, &width)| format!("{cell:>width$}"))
+                        .collect();
+                    table.push_str(&cells.join(" "));
+                    table.push('\n');
+                }
+                self.print_str_stdout(&table)
+            }
+            kind => Err(format!(
+                "Showing {kind} is not supported in this environment"
+            )),
         }
     }
     #[cfg(feature = "audio")]
@@ -709,6 +1200,106 @@ impl SysBackend for NativeSys {
         );
         Ok(handle)
     }
+    #[cfg(feature = "smtp")]
+    fn send_email(&self, email: &Email) -> Result<(), String> {
+        crate::smtp::send(email)
+    }
+    fn osc_send(&self, addr: &str, packet: &[u8]) -> Result<(), String> {
+        let addr = (addr.to_socket_addrs())
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| format!("Unable to resolve {addr}"))?;
+        let local: SocketAddr = if addr.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local).map_err(|e| e.to_string())?;
+        socket.send_to(packet, addr).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+    fn osc_listen(&self, addr: &str) -> Result<Handle, String> {
+        let socket = UdpSocket::bind(addr).map_err(|e| e.to_string())?;
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        NATIVE_SYS.osc_sockets.insert(handle, socket);
+        Ok(handle)
+    }
+    fn osc_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
+        let socket = (NATIVE_SYS.osc_sockets.get(&handle)).ok_or("Invalid OSC socket handle")?;
+        socket.local_addr().map_err(|e| e.to_string())
+    }
+    fn osc_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String> {
+        let socket = (NATIVE_SYS.osc_sockets.get(&handle)).ok_or("Invalid OSC socket handle")?;
+        let mut packets = Vec::new();
+        let mut buf = vec![0; 65536];
+        loop {
+            match socket.recv(&mut buf) {
+                Ok(n) => packets.push(buf[..n].to_vec()),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Ok(packets)
+    }
+    #[cfg(feature = "midi")]
+    fn midi_ports(&self) -> Result<(Vec<String>, Vec<String>), String> {
+        use midir::MidiIO;
+        fn names<T: MidiIO>(io: &T) -> Result<Vec<String>, String> {
+            (io.ports().iter())
+                .map(|port| io.port_name(port).map_err(|e| e.to_string()))
+                .collect()
+        }
+        let input = midir::MidiInput::new("uiua").map_err(|e| e.to_string())?;
+        let output = midir::MidiOutput::new("uiua").map_err(|e| e.to_string())?;
+        Ok((names(&input)?, names(&output)?))
+    }
+    #[cfg(feature = "midi")]
+    fn midi_open_input(&self, port: &str) -> Result<(Handle, String), String> {
+        let input = midir::MidiInput::new("uiua").map_err(|e| e.to_string())?;
+        let (port, name) = find_midi_port(&input, port)?;
+        let messages = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let received = messages.clone();
+        let conn = input
+            .connect(
+                &port,
+                "uiua-input",
+                move |_, message, _| received.lock().push(message.to_vec()),
+                (),
+            )
+            .map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        let midi_in = MidiIn {
+            _conn: conn,
+            messages,
+        };
+        NATIVE_SYS.midi_inputs.lock().insert(handle, midi_in);
+        Ok((handle, name))
+    }
+    #[cfg(feature = "midi")]
+    fn midi_open_output(&self, port: &str) -> Result<(Handle, String), String> {
+        let output = midir::MidiOutput::new("uiua").map_err(|e| e.to_string())?;
+        let (port, name) = find_midi_port(&output, port)?;
+        let conn = (output.connect(&port, "uiua-output")).map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        NATIVE_SYS.midi_outputs.lock().insert(handle, conn);
+        Ok((handle, name))
+    }
+    #[cfg(feature = "midi")]
+    fn midi_receive(&self, handle: Handle) -> Result<Vec<Vec<u8>>, String> {
+        let inputs = NATIVE_SYS.midi_inputs.lock();
+        let input = inputs.get(&handle).ok_or("Invalid MIDI input handle")?;
+        let messages = take(&mut *input.messages.lock());
+        Ok(messages)
+    }
+    #[cfg(feature = "midi")]
+    fn midi_send(&self, handle: Handle, message: &[u8]) -> Result<(), String> {
+        let mut outputs = NATIVE_SYS.midi_outputs.lock();
+        let output = outputs
+            .get_mut(&handle)
+            .ok_or("Invalid MIDI output handle")?;
+        output.send(message).map_err(|e| e.to_string())
+    }
     fn tcp_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
         (NATIVE_SYS.get_tcp_stream(handle, |s| s.peer_addr()))
             .or_else(|| NATIVE_SYS.get_tcp_listener(handle, |l| l.local_addr()))
@@ -742,7 +1333,11 @@ impl SysBackend for NativeSys {
             .map_err(|e| e.to_string())
     }
     fn close(&self, handle: Handle) -> Result<(), String> {
-        if NATIVE_SYS.child_stdins.remove(&handle).is_some()
+        NATIVE_SYS.release_file_lock(handle);
+        if let Some((_, map)) = NATIVE_SYS.shared_memory.remove(&handle) {
+            NATIVE_SYS.files.remove(&handle);
+            map.flush_async().map_err(|e| e.to_string())
+        } else if NATIVE_SYS.child_stdins.remove(&handle).is_some()
             || NATIVE_SYS.child_stdouts.remove(&handle).is_some()
             || NATIVE_SYS.child_stderrs.remove(&handle).is_some()
         {
@@ -760,7 +1355,22 @@ impl SysBackend for NativeSys {
         {
             NATIVE_SYS.hostnames.remove(&handle);
             Ok(())
+        } else if NATIVE_SYS.osc_sockets.remove(&handle).is_some() {
+            Ok(())
         } else {
+            #[cfg(feature = "kv")]
+            if NATIVE_SYS.kv_stores.remove(&handle).is_some() {
+                return Ok(());
+            }
+            #[cfg(feature = "midi")]
+            if NATIVE_SYS.midi_inputs.lock().remove(&handle).is_some() {
+                return Ok(());
+            }
+            #[cfg(feature = "midi")]
+            if let Some(conn) = NATIVE_SYS.midi_outputs.lock().remove(&handle) {
+                conn.close();
+                return Ok(());
+            }
             Err("Invalid stream handle".to_string())
         }
     }
@@ -803,7 +1413,7 @@ impl SysBackend for NativeSys {
         let stdin = child.stdin.take().unwrap();
         let stdout = child.stdout.take().unwrap();
         let stderr = child.stderr.take().unwrap();
-        let child = Arc::new(child);
+        let child = Arc::new(parking_lot::Mutex::new(child));
         let stdin_handle = NATIVE_SYS.new_handle();
         NATIVE_SYS.child_stdins.insert(
             stdin_handle,
@@ -830,13 +1440,49 @@ impl SysBackend for NativeSys {
         );
         Ok([stdin_handle, stdout_handle, stderr_handle])
     }
+    fn child_status(&self, handle: Handle) -> Result<Option<i32>, String> {
+        let child = NATIVE_SYS.get_child(handle)?;
+        let status = child.lock().try_wait().map_err(|e| e.to_string())?;
+        Ok(status.map(|status| {
+            #[cfg(unix)]
+            if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+                return -signal;
+            }
+            status.code().unwrap_or(0)
+        }))
+    }
+    fn child_kill(&self, handle: Handle) -> Result<(), String> {
+        let child = NATIVE_SYS.get_child(handle)?;
+        let mut child = child.lock();
+        child.kill().map_err(|e| e.to_string())?;
+        // Wait so that the process does not linger as a zombie
+        child.wait().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+    #[cfg(target_os = "linux")]
+    fn child_resources(&self, handle: Handle) -> Result<(f64, f64), String> {
+        let pid = NATIVE_SYS.get_child(handle)?.lock().id();
+        let stat = fs::read_to_string(format!("/proc/{pid}/stat"))
+            .map_err(|_| "The child process has finished".to_string())?;
+        // The command name may contain spaces, so the fields are counted from after it
+        let fields: Vec<&str> = (stat.rsplit_once(')'))
+            .map(|(_, rest)| rest.split_whitespace().collect())
+            .unwrap_or_default();
+        let field = |n: usize| -> Result<f64, String> {
+            (fields.get(n - 3))
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(|| "Invalid process information".to_string())
+        };
+        let ticks = rustix::param::clock_ticks_per_second() as f64;
+        let cpu = (field(14)? + field(15)?) / ticks;
+        let memory = field(24)? * rustix::param::page_size() as f64;
+        Ok((cpu, memory))
+    }
     fn change_directory(&self, path: &str) -> Result<(), String> {
         env::set_current_dir(path).map_err(|e| e.to_string())
     }
     #[cfg(feature = "tls")]
     fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
-        use std::io;
-
         let host = (NATIVE_SYS.hostnames.get(&handle))
             .ok_or_else(|| "Invalid tcp socket handle".to_string())?
             .clone();
@@ -844,42 +1490,13 @@ impl SysBackend for NativeSys {
 
         let stream = (NATIVE_SYS.tcp_sockets.get(&handle))
             .ok_or_else(|| "Invalid tcp socket handle".to_string())?;
-        let mut stream = &*stream;
-
-        let mut buffer = Vec::new();
-        let port = stream.peer_addr().map(|a| a.port());
-        if let Ok(443) = port {
-            static CLIENT_CONFIG: Lazy<std::sync::Arc<rustls::ClientConfig>> = Lazy::new(|| {
-                let mut store = rustls::RootCertStore::empty();
-                store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-                rustls::ClientConfig::builder()
-                    .with_root_certificates(store)
-                    .with_no_client_auth()
-                    .into()
-            });
-
-            let server_name =
-                rustls::pki_types::ServerName::try_from(host).map_err(|e| e.to_string())?;
-            let mut conn = rustls::ClientConnection::new(CLIENT_CONFIG.clone(), server_name)
-                .map_err(|e| e.to_string())?;
-            let mut tls = rustls::Stream::new(&mut conn, &mut stream);
-            tls.write_all(request.as_bytes())
-                .map_err(|e| e.to_string())?;
-            match tls.read_to_end(&mut buffer) {
-                Ok(_) => {}
-                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
-                Err(e) => return Err(e.to_string()),
-            }
-        } else {
-            (stream.write_all(request.as_bytes())).map_err(|e| e.to_string())?;
-            stream.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-        }
-
-        let s = String::from_utf8(buffer).map_err(|e| {
-            "Error converting HTTP Response to utf-8: ".to_string() + &e.to_string()
-        })?;
-
-        Ok(s)
+        let port = stream.peer_addr().map_err(|e| e.to_string())?.port();
+        let tls = port == 443;
+        let origin = format!("{}://{host}:{port}", if tls { "https" } else { "http" });
+        let cache_dir = crate::http_cache::http_cache_dir();
+        crate::http_cache::cached_request(cache_dir.as_deref(), &origin, &request, |request| {
+            send_http(&stream, &host, request, tls)
+        })
     }
     #[cfg(feature = "ffi")]
     fn ffi(
@@ -976,6 +1593,137 @@ impl SysBackend for NativeSys {
         NATIVE_SYS.git_paths.insert(url.to_string(), res.clone());
         res
     }
+    #[cfg(feature = "tls")]
+    fn load_url_module(&self, url: &str) -> Result<PathBuf, String> {
+        if let Some(path) = NATIVE_SYS.url_paths.get(url) {
+            return path.clone();
+        }
+        let res = (|| {
+            let rest = (url.split_once("://")).map_or(url, |(_, rest)| rest);
+            let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+            // The module is saved under its host and path, with the port separated by `_`
+            let mut path = PathBuf::from("uiua-modules");
+            for (i, part) in rest.split('/').filter(|part| !part.is_empty()).enumerate() {
+                let part = if i == 0 {
+                    part.replace(':', "_")
+                } else {
+                    part.into()
+                };
+                if part == "." || part == ".." || part.contains(['\\', ':']) {
+                    return Err(format!("Invalid module URL {url:?}"));
+                }
+                path.push(part);
+            }
+            if !url.ends_with(".ua") {
+                path.push("lib.ua");
+            }
+            let contents = http_get_url(url)?;
+            // Only write the file if it changed so that file watchers are not triggered
+            if fs::read_to_string(&path).map_or(true, |old| old != contents) {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, contents).map_err(|e| e.to_string())?;
+            }
+            Ok(path)
+        })();
+        NATIVE_SYS.url_paths.insert(url.to_string(), res.clone());
+        res
+    }
+}
+
+/// Send an HTTP request on a TCP stream and read the whole response
+#[cfg(feature = "tls")]
+fn send_http(
+    mut stream: &TcpStream,
+    host: &str,
+    request: &str,
+    tls: bool,
+) -> Result<String, String> {
+    use std::io;
+
+    let mut buffer = Vec::new();
+    if tls {
+        static CLIENT_CONFIG: Lazy<std::sync::Arc<rustls::ClientConfig>> = Lazy::new(|| {
+            let mut store = rustls::RootCertStore::empty();
+            store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            rustls::ClientConfig::builder()
+                .with_root_certificates(store)
+                .with_no_client_auth()
+                .into()
+        });
+
+        let server_name =
+            rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+        let mut conn = rustls::ClientConnection::new(CLIENT_CONFIG.clone(), server_name)
+            .map_err(|e| e.to_string())?;
+        let mut tls = rustls::Stream::new(&mut conn, &mut stream);
+        tls.write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+        match tls.read_to_end(&mut buffer) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    } else {
+        (stream.write_all(request.as_bytes())).map_err(|e| e.to_string())?;
+        stream.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+    }
+
+    String::from_utf8(buffer)
+        .map_err(|e| "Error converting HTTP Response to utf-8: ".to_string() + &e.to_string())
+}
+
+/// Make a GET request to an HTTPS URL and return the body of the response
+///
+/// Redirects are followed, but only to other HTTPS URLs. Responses go through the HTTP cache.
+#[cfg(feature = "tls")]
+fn http_get_url(url: &str) -> Result<String, String> {
+    let mut url = url.to_string();
+    for _ in 0..5 {
+        let Some(rest) = url.strip_prefix("https://") else {
+            return Err(format!(
+                "Modules can only be loaded from https:// URLs, not {url:?}"
+            ));
+        };
+        let (authority, target) = rest.find('/').map_or((rest, "/"), |i| rest.split_at(i));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port: u16 =
+                    (port.parse()).map_err(|_| format!("Invalid port in URL {url:?}"))?;
+                (host, port)
+            }
+            None => (authority, 443),
+        };
+        let request = format!("GET {target} HTTP/1.0\r\nHost: {host}\r\nUser-Agent: uiua\r\n\r\n");
+        let response = crate::http_cache::cached_request(
+            crate::http_cache::http_cache_dir().as_deref(),
+            &format!("https://{host}:{port}"),
+            &request,
+            |request| {
+                let stream = TcpStream::connect((host, port))
+                    .map_err(|e| format!("Failed to connect to {host}: {e}"))?;
+                send_http(&stream, host, request, true)
+            },
+        )?;
+        let head = crate::http_cache::ResponseHead::parse(&response)
+            .ok_or_else(|| format!("Invalid HTTP response from {url}"))?;
+        if head.stale {
+            eprintln!("Warning: {url} could not be reached, so a stale cached copy is being used");
+        }
+        match (head.status, head.location) {
+            (200, _) => return Ok(response[head.body_start..].to_string()),
+            (301 | 302 | 303 | 307 | 308, Some(location)) => {
+                url = if location.starts_with('/') {
+                    format!("https://{authority}{location}")
+                } else {
+                    location
+                };
+            }
+            (status, _) => return Err(format!("Request to {url} failed with status {status}")),
+        }
+    }
+    Err(format!("Too many redirects when requesting {url}"))
 }
 
 /// Takes an HTTP request, validates it, and fixes it (if possible) by adding
@@ -11,7 +11,13 @@ use ecow::{EcoString, EcoVec};
 use serde::*;
 
 use crate::{
-    algorithm::{map::MapKeys, pervade::*, ErrorContext, FillContext},
+    algorithm::{
+        interval,
+        map::MapKeys,
+        pervade::*,
+        quantity::{self, Unit},
+        simd, validate_size_impl, ErrorContext, FillContext,
+    },
     array::*,
     cowslice::CowSlice,
     grid_fmt::GridFmt,
@@ -29,6 +35,10 @@ pub enum Value {
     Byte(Array<u8>),
     /// Common number array
     Num(Array<f64>),
+    /// Integer array
+    Int(Array<i64>),
+    /// Single-precision number array
+    Float(Array<f32>),
     /// Complex number array
     Complex(Array<Complex>),
     /// Common character array
@@ -60,6 +70,8 @@ impl Value {
         match self {
             Self::Num(_) => f64::TYPE_ID,
             Self::Byte(_) => u8::TYPE_ID,
+            Self::Int(_) => i64::TYPE_ID,
+            Self::Float(_) => f32::TYPE_ID,
             Self::Complex(_) => Complex::TYPE_ID,
             Self::Char(_) => char::TYPE_ID,
             Self::Box(_) => Boxed::TYPE_ID,
@@ -72,6 +84,13 @@ impl Value {
             _ => None,
         }
     }
+    /// Get a reference to a possible integer array
+    pub fn as_int_array(&self) -> Option<&Array<i64>> {
+        match self {
+            Self::Int(array) => Some(array),
+            _ => None,
+        }
+    }
     /// Get a reference to a possible byte array
     pub fn as_byte_array(&self) -> Option<&Array<u8>> {
         match self {
@@ -102,6 +121,8 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.rows().map(Value::from)),
             Self::Byte(array) => Box::new(array.rows().map(Value::from)),
+            Self::Int(array) => Box::new(array.rows().map(Value::from)),
+            Self::Float(array) => Box::new(array.rows().map(Value::from)),
             Self::Complex(array) => Box::new(array.rows().map(Value::from)),
             Self::Char(array) => Box::new(array.rows().map(Value::from)),
             Self::Box(array) => Box::new(array.rows().map(Value::from)),
@@ -115,6 +136,8 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
             Self::Byte(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
+            Self::Int(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
+            Self::Float(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
             Self::Complex(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
             Self::Char(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
             Self::Box(array) => Box::new(array.row_shaped_slices(row_shape).map(Value::from)),
@@ -128,6 +151,10 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.into_row_shaped_slices(row_shape).map(Value::from)),
             Self::Byte(array) => Box::new(array.into_row_shaped_slices(row_shape).map(Value::from)),
+            Self::Int(array) => Box::new(array.into_row_shaped_slices(row_shape).map(Value::from)),
+            Self::Float(array) => {
+                Box::new(array.into_row_shaped_slices(row_shape).map(Value::from))
+            }
             Self::Complex(array) => {
                 Box::new(array.into_row_shaped_slices(row_shape).map(Value::from))
             }
@@ -140,6 +167,8 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.into_rows().map(Value::from)),
             Self::Byte(array) => Box::new(array.into_rows().map(Value::from)),
+            Self::Int(array) => Box::new(array.into_rows().map(Value::from)),
+            Self::Float(array) => Box::new(array.into_rows().map(Value::from)),
             Self::Complex(array) => Box::new(array.into_rows().map(Value::from)),
             Self::Char(array) => Box::new(array.into_rows().map(Value::from)),
             Self::Box(array) => Box::new(array.into_rows().map(Value::from)),
@@ -150,6 +179,8 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.data.iter().copied().map(Value::from)),
             Self::Byte(array) => Box::new(array.data.iter().copied().map(Value::from)),
+            Self::Int(array) => Box::new(array.data.iter().copied().map(Value::from)),
+            Self::Float(array) => Box::new(array.data.iter().copied().map(Value::from)),
             Self::Complex(array) => Box::new(array.data.iter().copied().map(Value::from)),
             Self::Char(array) => Box::new(array.data.iter().copied().map(Value::from)),
             Self::Box(array) => Box::new(array.data.iter().cloned().map(Value::from)),
@@ -160,6 +191,8 @@ impl Value {
         match self {
             Self::Num(array) => Box::new(array.data.into_iter().map(Value::from)),
             Self::Byte(array) => Box::new(array.data.into_iter().map(Value::from)),
+            Self::Int(array) => Box::new(array.data.into_iter().map(Value::from)),
+            Self::Float(array) => Box::new(array.data.into_iter().map(Value::from)),
             Self::Complex(array) => Box::new(array.data.into_iter().map(Value::from)),
             Self::Char(array) => Box::new(array.data.into_iter().map(Value::from)),
             Self::Box(array) => Box::new(array.data.into_iter().map(Value::from)),
@@ -170,6 +203,8 @@ impl Value {
         match self {
             Self::Num(_) => "number",
             Self::Byte(_) => "number",
+            Self::Int(_) => "number",
+            Self::Float(_) => "number",
             Self::Complex(_) => "complex",
             Self::Char(_) => "character",
             Self::Box(_) => "box",
@@ -180,6 +215,8 @@ impl Value {
         match self {
             Self::Num(_) => "numbers",
             Self::Byte(_) => "numbers",
+            Self::Int(_) => "numbers",
+            Self::Float(_) => "numbers",
             Self::Complex(_) => "complexes",
             Self::Char(_) => "characters",
             Self::Box(_) => "boxes",
@@ -203,6 +240,14 @@ impl Value {
                 .byte_scalar_fill()
                 .unwrap_or_else(|_| u8::proxy())
                 .into(),
+            Self::Int(_) => env
+                .int_scalar_fill()
+                .unwrap_or_else(|_| i64::proxy())
+                .into(),
+            Self::Float(_) => env
+                .float_scalar_fill()
+                .unwrap_or_else(|_| f32::proxy())
+                .into(),
             Self::Complex(_) => env
                 .complex_scalar_fill()
                 .unwrap_or_else(|_| Complex::proxy())
@@ -238,6 +283,20 @@ impl Value {
                     .collect::<CowSlice<_>>(),
             )
             .into(),
+            Self::Int(_) => Array::new(
+                shape,
+                repeat(env.int_scalar_fill().unwrap_or_else(|_| i64::proxy()))
+                    .take(elem_count)
+                    .collect::<CowSlice<_>>(),
+            )
+            .into(),
+            Self::Float(_) => Array::new(
+                shape,
+                repeat(env.float_scalar_fill().unwrap_or_else(|_| f32::proxy()))
+                    .take(elem_count)
+                    .collect::<CowSlice<_>>(),
+            )
+            .into(),
             Self::Complex(_) => Array::new(
                 shape,
                 repeat(
@@ -268,6 +327,8 @@ impl Value {
         match self {
             Self::Num(array) => array.first_dim_zero().into(),
             Self::Byte(array) => array.first_dim_zero().into(),
+            Self::Int(array) => array.first_dim_zero().into(),
+            Self::Float(array) => array.first_dim_zero().into(),
             Self::Complex(array) => array.first_dim_zero().into(),
             Self::Char(array) => array.first_dim_zero().into(),
             Self::Box(array) => array.first_dim_zero().into(),
@@ -281,6 +342,8 @@ impl Value {
         match self {
             Self::Num(array) => array.pop_row().map(Value::from),
             Self::Byte(array) => array.pop_row().map(Value::from),
+            Self::Int(array) => array.pop_row().map(Value::from),
+            Self::Float(array) => array.pop_row().map(Value::from),
             Self::Complex(array) => array.pop_row().map(Value::from),
             Self::Char(array) => array.pop_row().map(Value::from),
             Self::Box(array) => array.pop_row().map(Value::from),
@@ -290,11 +353,28 @@ impl Value {
         match self {
             Self::Num(_) => size_of::<f64>(),
             Self::Byte(_) => size_of::<u8>(),
+            Self::Int(_) => size_of::<i64>(),
+            Self::Float(_) => size_of::<f32>(),
             Self::Complex(_) => size_of::<Complex>(),
             Self::Char(_) => size_of::<char>(),
             Self::Box(_) => size_of::<Boxed>(),
         }
     }
+    /// Get the approximate number of bytes used by the value
+    ///
+    /// Data shared between values is counted for each value
+    pub fn byte_size(&self) -> usize {
+        let mut size = size_of::<Self>()
+            + self.rank() * size_of::<usize>()
+            + self.element_count() * self.elem_size();
+        if let Value::Box(arr) = self {
+            size += arr.data.iter().map(|b| b.0.byte_size()).sum::<usize>();
+        }
+        if let Some(keys) = self.map_keys() {
+            size += keys.keys.byte_size() + keys.keys.row_count() * size_of::<usize>();
+        }
+        size
+    }
 }
 
 #[repr(C)]
@@ -346,6 +426,10 @@ impl Value {
     pub fn take_map_keys(&mut self) -> Option<MapKeys> {
         unsafe { self.repr_mut() }.arr.take_map_keys()
     }
+    /// Take the unit from the value
+    pub fn take_unit(&mut self) -> Option<Unit> {
+        unsafe { self.repr_mut() }.arr.take_unit()
+    }
     /// Take the persistent metadata from the value
     pub fn take_per_meta(&mut self) -> PersistentMeta {
         unsafe { self.repr_mut() }.arr.take_per_meta()
@@ -399,6 +483,8 @@ impl Value {
         match self {
             Value::Num(arr) => arr.row(i).into(),
             Value::Byte(arr) => arr.row(i).into(),
+            Value::Int(arr) => arr.row(i).into(),
+            Value::Float(arr) => arr.row(i).into(),
             Value::Complex(arr) => arr.row(i).into(),
             Value::Char(arr) => arr.row(i).into(),
             Value::Box(arr) => arr.row(i).into(),
@@ -409,15 +495,20 @@ impl Value {
         match self {
             Value::Num(arr) => arr.depth_row(depth, i).into(),
             Value::Byte(arr) => arr.depth_row(depth, i).into(),
+            Value::Int(arr) => arr.depth_row(depth, i).into(),
+            Value::Float(arr) => arr.depth_row(depth, i).into(),
             Value::Complex(arr) => arr.depth_row(depth, i).into(),
             Value::Char(arr) => arr.depth_row(depth, i).into(),
             Value::Box(arr) => arr.depth_row(depth, i).into(),
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn generic_into<T>(
         self,
         n: impl FnOnce(Array<f64>) -> T,
         _b: impl FnOnce(Array<u8>) -> T,
+        _i: impl FnOnce(Array<i64>) -> T,
+        _fl: impl FnOnce(Array<f32>) -> T,
         _co: impl FnOnce(Array<Complex>) -> T,
         ch: impl FnOnce(Array<char>) -> T,
         f: impl FnOnce(Array<Boxed>) -> T,
@@ -425,15 +516,20 @@ impl Value {
         match self {
             Self::Num(array) => n(array),
             Self::Byte(array) => _b(array),
+            Self::Int(array) => _i(array),
+            Self::Float(array) => _fl(array),
             Self::Complex(array) => _co(array),
             Self::Char(array) => ch(array),
             Self::Box(array) => f(array),
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn generic_ref<'a, T: 'a>(
         &'a self,
         n: impl FnOnce(&'a Array<f64>) -> T,
         _b: impl FnOnce(&'a Array<u8>) -> T,
+        _i: impl FnOnce(&'a Array<i64>) -> T,
+        _fl: impl FnOnce(&'a Array<f32>) -> T,
         _co: impl FnOnce(&'a Array<Complex>) -> T,
         ch: impl FnOnce(&'a Array<char>) -> T,
         f: impl FnOnce(&'a Array<Boxed>) -> T,
@@ -441,15 +537,20 @@ impl Value {
         match self {
             Self::Num(array) => n(array),
             Self::Byte(array) => _b(array),
+            Self::Int(array) => _i(array),
+            Self::Float(array) => _fl(array),
             Self::Complex(array) => _co(array),
             Self::Char(array) => ch(array),
             Self::Box(array) => f(array),
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn generic_ref_env<'a, T: 'a>(
         &'a self,
         n: impl FnOnce(&'a Array<f64>, &Uiua) -> UiuaResult<T>,
         b: impl FnOnce(&'a Array<u8>, &Uiua) -> UiuaResult<T>,
+        i: impl FnOnce(&'a Array<i64>, &Uiua) -> UiuaResult<T>,
+        fl: impl FnOnce(&'a Array<f32>, &Uiua) -> UiuaResult<T>,
         co: impl FnOnce(&'a Array<Complex>, &Uiua) -> UiuaResult<T>,
         ch: impl FnOnce(&'a Array<char>, &Uiua) -> UiuaResult<T>,
         f: impl FnOnce(&'a Array<Boxed>, &Uiua) -> UiuaResult<T>,
@@ -458,15 +559,20 @@ impl Value {
         self.generic_ref(
             |a| n(a, env),
             |a| b(a, env),
+            |a| i(a, env),
+            |a| fl(a, env),
             |a| co(a, env),
             |a| ch(a, env),
             |a| f(a, env),
         )
     }
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn generic_mut_shallow<T>(
         &mut self,
         n: impl FnOnce(&mut Array<f64>) -> T,
         _b: impl FnOnce(&mut Array<u8>) -> T,
+        _i: impl FnOnce(&mut Array<i64>) -> T,
+        _fl: impl FnOnce(&mut Array<f32>) -> T,
         _co: impl FnOnce(&mut Array<Complex>) -> T,
         ch: impl FnOnce(&mut Array<char>) -> T,
         f: impl FnOnce(&mut Array<Boxed>) -> T,
@@ -474,15 +580,20 @@ impl Value {
         match self {
             Self::Num(array) => n(array),
             Self::Byte(array) => _b(array),
+            Self::Int(array) => _i(array),
+            Self::Float(array) => _fl(array),
             Self::Complex(array) => _co(array),
             Self::Char(array) => ch(array),
             Self::Box(array) => f(array),
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn generic_mut_deep<T>(
         &mut self,
         n: impl FnOnce(&mut Array<f64>) -> T,
         _b: impl FnOnce(&mut Array<u8>) -> T,
+        _i: impl FnOnce(&mut Array<i64>) -> T,
+        _fl: impl FnOnce(&mut Array<f32>) -> T,
         _co: impl FnOnce(&mut Array<Complex>) -> T,
         ch: impl FnOnce(&mut Array<char>) -> T,
         f: impl FnOnce(&mut Array<Boxed>) -> T,
@@ -490,11 +601,13 @@ impl Value {
         match self {
             Self::Num(array) => n(array),
             Self::Byte(array) => _b(array),
+            Self::Int(array) => _i(array),
+            Self::Float(array) => _fl(array),
             Self::Complex(array) => _co(array),
             Self::Char(array) => ch(array),
             Self::Box(array) => {
                 if let Some(Boxed(value)) = array.as_scalar_mut() {
-                    value.generic_mut_deep(n, _b, _co, ch, f)
+                    value.generic_mut_deep(n, _b, _i, _fl, _co, ch, f)
                 } else {
                     f(array)
                 }
@@ -507,6 +620,8 @@ impl Value {
         other: Self,
         n: impl FnOnce(Array<f64>, Array<f64>) -> Result<T, E>,
         _b: impl FnOnce(Array<u8>, Array<u8>) -> Result<T, E>,
+        _i: impl FnOnce(Array<i64>, Array<i64>) -> Result<T, E>,
+        _fl: impl FnOnce(Array<f32>, Array<f32>) -> Result<T, E>,
         _co: impl FnOnce(Array<Complex>, Array<Complex>) -> Result<T, E>,
         ch: impl FnOnce(Array<char>, Array<char>) -> Result<T, E>,
         f: impl FnOnce(Array<Boxed>, Array<Boxed>) -> Result<T, E>,
@@ -517,11 +632,25 @@ impl Value {
             (Self::Byte(a), Self::Byte(b)) => _b(a, b),
             (Self::Byte(a), Self::Num(b)) => n(a.convert(), b),
             (Self::Num(a), Self::Byte(b)) => n(a, b.convert()),
+            (Self::Int(a), Self::Int(b)) => _i(a, b),
+            (Self::Int(a), Self::Byte(b)) => _i(a, b.convert()),
+            (Self::Byte(a), Self::Int(b)) => _i(a.convert(), b),
+            (Self::Int(a), Self::Num(b)) => n(a.convert_with(|n| n as f64), b),
+            (Self::Num(a), Self::Int(b)) => n(a, b.convert_with(|n| n as f64)),
+            (Self::Float(a), Self::Float(b)) => _fl(a, b),
+            (Self::Float(a), Self::Int(b)) => n(a.convert(), b.convert_with(|n| n as f64)),
+            (Self::Int(a), Self::Float(b)) => n(a.convert_with(|n| n as f64), b.convert()),
+            (Self::Float(a), b @ (Self::Num(_) | Self::Byte(_))) => _fl(a, b.into_f32_array()),
+            (a @ (Self::Num(_) | Self::Byte(_)), Self::Float(b)) => _fl(a.into_f32_array(), b),
             (Self::Complex(a), Self::Complex(b)) => _co(a, b),
             (Self::Complex(a), Self::Num(b)) => _co(a, b.convert()),
             (Self::Num(a), Self::Complex(b)) => _co(a.convert(), b),
             (Self::Complex(a), Self::Byte(b)) => _co(a, b.convert()),
             (Self::Byte(a), Self::Complex(b)) => _co(a.convert(), b),
+            (Self::Complex(a), Self::Int(b)) => _co(a, b.convert()),
+            (Self::Int(a), Self::Complex(b)) => _co(a.convert(), b),
+            (Self::Complex(a), Self::Float(b)) => _co(a, b.convert()),
+            (Self::Float(a), Self::Complex(b)) => _co(a.convert(), b),
             (Self::Char(a), Self::Char(b)) => ch(a, b),
             (Self::Box(a), Self::Box(b)) => f(a, b),
             (Self::Box(a), b) => f(a, b.coerce_to_boxes()),
@@ -535,6 +664,8 @@ impl Value {
         other: &Self,
         n: impl FnOnce(&Array<f64>, &Array<f64>) -> Result<T, E>,
         _b: impl FnOnce(&Array<u8>, &Array<u8>) -> Result<T, E>,
+        _i: impl FnOnce(&Array<i64>, &Array<i64>) -> Result<T, E>,
+        _fl: impl FnOnce(&Array<f32>, &Array<f32>) -> Result<T, E>,
         _co: impl FnOnce(&Array<Complex>, &Array<Complex>) -> Result<T, E>,
         ch: impl FnOnce(&Array<char>, &Array<char>) -> Result<T, E>,
         f: impl FnOnce(&Array<Boxed>, &Array<Boxed>) -> Result<T, E>,
@@ -545,11 +676,35 @@ impl Value {
             (Self::Byte(a), Self::Byte(b)) => _b(a, b),
             (Self::Byte(a), Self::Num(b)) => n(&a.convert_ref(), b),
             (Self::Num(a), Self::Byte(b)) => n(a, &b.convert_ref()),
+            (Self::Int(a), Self::Int(b)) => _i(a, b),
+            (Self::Int(a), Self::Byte(b)) => _i(a, &b.convert_ref()),
+            (Self::Byte(a), Self::Int(b)) => _i(&a.convert_ref(), b),
+            (Self::Int(a), Self::Num(b)) => n(&a.convert_ref_with(|n| n as f64), b),
+            (Self::Num(a), Self::Int(b)) => n(a, &b.convert_ref_with(|n| n as f64)),
+            (Self::Float(a), Self::Float(b)) => _fl(a, b),
+            (Self::Float(a), Self::Int(b)) => n(
+                &a.convert_ref_with(|n| n as f64),
+                &b.convert_ref_with(|n| n as f64),
+            ),
+            (Self::Int(a), Self::Float(b)) => n(
+                &a.convert_ref_with(|n| n as f64),
+                &b.convert_ref_with(|n| n as f64),
+            ),
+            (Self::Float(a), b @ (Self::Num(_) | Self::Byte(_))) => {
+                _fl(a, &b.clone().into_f32_array())
+            }
+            (a @ (Self::Num(_) | Self::Byte(_)), Self::Float(b)) => {
+                _fl(&a.clone().into_f32_array(), b)
+            }
             (Self::Complex(a), Self::Complex(b)) => _co(a, b),
             (Self::Complex(a), Self::Num(b)) => _co(a, &b.convert_ref()),
             (Self::Num(a), Self::Complex(b)) => _co(&a.convert_ref(), b),
             (Self::Complex(a), Self::Byte(b)) => _co(a, &b.convert_ref()),
             (Self::Byte(a), Self::Complex(b)) => _co(&a.convert_ref(), b),
+            (Self::Complex(a), Self::Int(b)) => _co(a, &b.convert_ref()),
+            (Self::Int(a), Self::Complex(b)) => _co(&a.convert_ref(), b),
+            (Self::Complex(a), Self::Float(b)) => _co(a, &b.convert_ref()),
+            (Self::Float(a), Self::Complex(b)) => _co(&a.convert_ref(), b),
             (Self::Char(a), Self::Char(b)) => ch(a, b),
             (Self::Box(a), Self::Box(b)) => f(a, b),
             (Self::Box(a), b) => f(a, &b.coerce_as_boxes()),
@@ -563,6 +718,8 @@ impl Value {
         other: Self,
         n: impl FnOnce(&mut Array<f64>, Array<f64>) -> Result<T, E>,
         _b: impl FnOnce(&mut Array<u8>, Array<u8>) -> Result<T, E>,
+        _i: impl FnOnce(&mut Array<i64>, Array<i64>) -> Result<T, E>,
+        _fl: impl FnOnce(&mut Array<f32>, Array<f32>) -> Result<T, E>,
         _co: impl FnOnce(&mut Array<Complex>, Array<Complex>) -> Result<T, E>,
         ch: impl FnOnce(&mut Array<char>, Array<char>) -> Result<T, E>,
         f: impl FnOnce(&mut Array<Boxed>, Array<Boxed>) -> Result<T, E>,
@@ -578,6 +735,41 @@ impl Value {
                 res
             }
             (Self::Num(a), Self::Byte(b)) => n(a, b.convert_ref()),
+            (Self::Int(a), Self::Int(b)) => _i(a, b),
+            (Self::Int(a), Self::Byte(b)) => _i(a, b.convert_ref()),
+            (Self::Byte(a), Self::Int(b)) => {
+                let mut a_int = a.convert_ref();
+                let res = _i(&mut a_int, b);
+                *self = a_int.into();
+                res
+            }
+            (Self::Int(a), Self::Num(b)) => {
+                let mut a_num = a.convert_ref_with(|n| n as f64);
+                let res = n(&mut a_num, b);
+                *self = a_num.into();
+                res
+            }
+            (Self::Num(a), Self::Int(b)) => n(a, b.convert_ref_with(|n| n as f64)),
+            (Self::Float(a), Self::Float(b)) => _fl(a, b),
+            (Self::Float(a), Self::Int(b)) => {
+                let mut a_num = a.convert_ref_with(|n| n as f64);
+                let res = n(&mut a_num, b.convert_with(|n| n as f64));
+                *self = a_num.into();
+                res
+            }
+            (Self::Int(a), Self::Float(b)) => {
+                let mut a_num = a.convert_ref_with(|n| n as f64);
+                let res = n(&mut a_num, b.convert_with(|n| n as f64));
+                *self = a_num.into();
+                res
+            }
+            (Self::Float(a), b @ (Self::Num(_) | Self::Byte(_))) => _fl(a, b.into_f32_array()),
+            (a @ (Self::Num(_) | Self::Byte(_)), Self::Float(b)) => {
+                let mut a_float = take(a).into_f32_array();
+                let res = _fl(&mut a_float, b);
+                *self = a_float.into();
+                res
+            }
             (Self::Complex(a), Self::Complex(b)) => _co(a, b),
             (Self::Complex(a), Self::Num(b)) => _co(a, b.convert_ref()),
             (Self::Num(a), Self::Complex(b)) => {
@@ -593,6 +785,20 @@ impl Value {
                 *self = a_comp.into();
                 res
             }
+            (Self::Complex(a), Self::Int(b)) => _co(a, b.convert_ref()),
+            (Self::Int(a), Self::Complex(b)) => {
+                let mut a_comp = a.convert_ref();
+                let res = _co(&mut a_comp, b);
+                *self = a_comp.into();
+                res
+            }
+            (Self::Complex(a), Self::Float(b)) => _co(a, b.convert_ref()),
+            (Self::Float(a), Self::Complex(b)) => {
+                let mut a_comp = a.convert_ref();
+                let res = _co(&mut a_comp, b);
+                *self = a_comp.into();
+                res
+            }
             (Self::Char(a), Self::Char(b)) => ch(a, b),
             (Self::Box(a), b) => f(a, b.coerce_to_boxes()),
             (a, Self::Box(b)) => {
@@ -609,6 +815,8 @@ impl Value {
         match self {
             Self::Num(arr) => arr.data.reserve_min(min),
             Self::Byte(arr) => arr.data.reserve_min(min),
+            Self::Int(arr) => arr.data.reserve_min(min),
+            Self::Float(arr) => arr.data.reserve_min(min),
             Self::Complex(arr) => arr.data.reserve_min(min),
             Self::Char(arr) => arr.data.reserve_min(min),
             Self::Box(arr) => arr.data.reserve_min(min),
@@ -710,6 +918,24 @@ impl Value {
                     return Err(env.error(format!("{requirement}, but it is {num}")));
                 }
             }
+            Value::Int(ints) => {
+                if ints.rank() > 0 {
+                    return Err(
+                        env.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                let num = ints.data[0];
+                if num == 0 {
+                    false
+                } else if num == 1 {
+                    true
+                } else {
+                    return Err(env.error(format!("{requirement}, but it is {num}")));
+                }
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref_with(f64::from)).as_bool(env, requirement)
+            }
             value => {
                 return Err(env.error(format!("{requirement}, but it is {}", value.type_name())))
             }
@@ -766,6 +992,22 @@ impl Value {
                 }
                 Some(bytes.data[0] as usize)
             }
+            Value::Int(ints) => {
+                if ints.rank() > 0 {
+                    return Err(
+                        env.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                let num = ints.data[0];
+                if num < 0 {
+                    return Err(env.error(format!("{requirement}, but it is negative")));
+                }
+                Some(num as usize)
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref_with(f64::from))
+                    .as_nat_or_inf(env, requirement)
+            }
             value => {
                 return Err(env.error(format!("{requirement}, but it is {}", value.type_name())))
             }
@@ -805,6 +1047,17 @@ impl Value {
                 }
                 bytes.data[0] as isize
             }
+            Value::Int(ints) => {
+                if ints.rank() > 0 {
+                    return Err(
+                        env.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                ints.data[0] as isize
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref_with(f64::from)).as_int(env, requirement)
+            }
             value => {
                 return Err(env.error(format!("{requirement}, but it is {}", value.type_name())))
             }
@@ -834,6 +1087,17 @@ impl Value {
                 }
                 bytes.data[0] as f64
             }
+            Value::Int(ints) => {
+                if ints.rank() > 0 {
+                    return Err(
+                        env.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                ints.data[0] as f64
+            }
+            Value::Float(floats) => {
+                return Value::from(floats.convert_ref_with(f64::from)).as_num(env, requirement)
+            }
             value => {
                 return Err(env.error(format!("{requirement}, but it is {}", value.type_name())))
             }
@@ -1008,6 +1272,38 @@ impl Value {
                 }
                 result
             }
+            Value::Int(ints) => {
+                if ints.rank() > 1 {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(ints.row_count());
+                for &int in &ints.data {
+                    let num = int as f64;
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                result
+            }
+            Value::Float(floats) => {
+                if floats.rank() > 1 {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", floats.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(floats.row_count());
+                for &float in &floats.data {
+                    let num = f64::from(float);
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                result
+            }
             value => {
                 return Err(ctx.error(format!(
                     "{requirement}, but it is {}",
@@ -1060,6 +1356,38 @@ impl Value {
                 }
                 with(&result, &bytes.shape)
             }
+            Value::Int(ints) => {
+                if ints.rank() > 1 && !allow_non_list {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(ints.row_count());
+                for &int in &ints.data {
+                    let num = int as f64;
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                with(&result, &ints.shape)
+            }
+            Value::Float(floats) => {
+                if floats.rank() > 1 && !allow_non_list {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", floats.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(floats.row_count());
+                for &float in &floats.data {
+                    let num = f64::from(float);
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                with(&result, &floats.shape)
+            }
             Value::Box(mut arr) => {
                 for Boxed(b) in &mut arr.data {
                     *b = take(b).into_number_list_with(
@@ -1125,6 +1453,38 @@ impl Value {
                 }
                 with(&result, &bytes.shape, other)
             }
+            Value::Int(ints) => {
+                if ints.rank() > 1 && !allow_non_list {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", ints.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(ints.row_count());
+                for &int in &ints.data {
+                    let num = int as f64;
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                with(&result, &ints.shape, other)
+            }
+            Value::Float(floats) => {
+                if floats.rank() > 1 && !allow_non_list {
+                    return Err(
+                        ctx.error(format!("{requirement}, but its rank is {}", floats.rank()))
+                    );
+                }
+                let mut result = Vec::with_capacity(floats.row_count());
+                for &float in &floats.data {
+                    let num = f64::from(float);
+                    if !test(num) {
+                        return Err(ctx.error(requirement));
+                    }
+                    result.push(convert(num));
+                }
+                with(&result, &floats.shape, other)
+            }
             Value::Box(mut arr) => {
                 if !arr.shape.starts_with(other.shape()) {
                     return Err(ctx.error(format!(
@@ -1221,6 +1581,39 @@ impl Value {
                 }
                 Array::new(self.shape().clone(), result)
             }
+            Value::Int(ints) => {
+                if !test_shape(self.shape()) {
+                    return Err(
+                        env.error(format!("{requirement}, but its shape is {}", ints.shape()))
+                    );
+                }
+                let mut result = EcoVec::with_capacity(ints.element_count());
+                for &int in &ints.data {
+                    let num = int as f64;
+                    if !test_num(num) {
+                        return Err(env.error(requirement));
+                    }
+                    result.push(convert_num(num));
+                }
+                Array::new(self.shape().clone(), result)
+            }
+            Value::Float(floats) => {
+                if !test_shape(self.shape()) {
+                    return Err(env.error(format!(
+                        "{requirement}, but its shape is {}",
+                        floats.shape()
+                    )));
+                }
+                let mut result = EcoVec::with_capacity(floats.element_count());
+                for &float in &floats.data {
+                    let num = f64::from(float);
+                    if !test_num(num) {
+                        return Err(env.error(requirement));
+                    }
+                    result.push(convert_num(num));
+                }
+                Array::new(self.shape().clone(), result)
+            }
             value => {
                 return Err(env.error(format!(
                     "{requirement}, but its type is {}",
@@ -1277,6 +1670,18 @@ impl Value {
                 }
                 a.data.into_iter().map(|f| f as u8).collect()
             }
+            Value::Int(a) => {
+                if a.rank() != 1 {
+                    return Err(env.error(format!("{requirement}, but its rank is {}", a.rank())));
+                }
+                a.data.into_iter().map(|i| i as u8).collect()
+            }
+            Value::Float(a) => {
+                if a.rank() != 1 {
+                    return Err(env.error(format!("{requirement}, but its rank is {}", a.rank())));
+                }
+                a.data.into_iter().map(|f| f as u8).collect()
+            }
             Value::Char(a) => {
                 if a.rank() != 1 {
                     return Err(env.error(format!("{requirement}, but its rank is {}", a.rank())));
@@ -1374,7 +1779,12 @@ impl Value {
     /// Turn a number array into a byte array if no information is lost.
     ///
     /// Also sets the boolean flag if the array contains only 0s and 1s.
+    ///
+    /// Arrays whose storage was set with [`Primitive::Store`](crate::Primitive::Store) are not changed.
     pub fn compress(&mut self) {
+        if self.meta().flags.contains(ArrayFlags::FIXED_STORAGE) {
+            return;
+        }
         match self {
             Value::Num(nums) => {
                 let mut compress = true;
@@ -1418,11 +1828,158 @@ impl Value {
             _ => {}
         }
     }
+    /// Get the name of the value's storage type
+    pub fn storage_name(&self) -> &'static str {
+        match self {
+            Value::Num(_) => "num",
+            Value::Byte(_) => "byte",
+            Value::Int(_) if self.meta().flags.is_wrapping() => "wrapping",
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Complex(_) => "complex",
+            Value::Char(_) => "char",
+            Value::Box(_) => "box",
+        }
+    }
+    /// Convert a numeric value to the given storage type
+    ///
+    /// Errors if information would be lost
+    pub(crate) fn store(self, storage: &str, env: &Uiua) -> UiuaResult<Self> {
+        let article = if storage.starts_with(['a', 'e', 'i', 'o', 'u']) {
+            "an"
+        } else {
+            "a"
+        };
+        let lossy = |n: &dyn fmt::Display| {
+            env.error(format!(
+                "Cannot store {n} as {article} {storage} without losing information"
+            ))
+        };
+        // Wrapping arrays are integer arrays with a flag
+        let wrapping = storage == "wrapping";
+        let mut value: Value = match (if wrapping { "int" } else { storage }, self) {
+            (_, val @ (Value::Char(_) | Value::Box(_))) => {
+                return Err(env.error(format!(
+                    "Cannot change the storage of a {} array",
+                    val.type_name()
+                )))
+            }
+            ("num", Value::Num(arr)) => arr.into(),
+            ("num", Value::Byte(arr)) => arr.convert::<f64>().into(),
+            ("num", Value::Float(arr)) => arr.convert::<f64>().into(),
+            ("num", Value::Int(arr)) => {
+                if let Some(n) = arr.data.iter().find(|&&n| !int_fits_f64(n)) {
+                    return Err(lossy(n));
+                }
+                arr.convert_with(|n| n as f64).into()
+            }
+            ("num", Value::Complex(arr)) => {
+                if let Some(c) = arr.data.iter().find(|c| c.im != 0.0) {
+                    return Err(lossy(c));
+                }
+                arr.convert_with(|c| c.re).into()
+            }
+            ("byte", Value::Byte(arr)) => arr.into(),
+            ("byte", Value::Int(arr)) => {
+                if let Some(n) = arr.data.iter().find(|&&n| u8::try_from(n).is_err()) {
+                    return Err(lossy(n));
+                }
+                arr.convert_with(|n| n as u8).into()
+            }
+            ("byte", val @ (Value::Num(_) | Value::Float(_) | Value::Complex(_))) => {
+                let Value::Num(arr) = val.store("num", env)? else {
+                    unreachable!()
+                };
+                let fits = |n: f64| n.fract() == 0.0 && (0.0..=u8::MAX as f64).contains(&n);
+                if let Some(n) = arr.data.iter().find(|&&n| !fits(n)) {
+                    return Err(lossy(n));
+                }
+                arr.convert_with(|n| n as u8).into()
+            }
+            ("int", Value::Int(arr)) => arr.into(),
+            ("int", Value::Byte(arr)) => arr.convert::<i64>().into(),
+            ("int", val @ (Value::Num(_) | Value::Float(_) | Value::Complex(_))) => {
+                let Value::Num(arr) = val.store("num", env)? else {
+                    unreachable!()
+                };
+                if let Some(n) = arr.data.iter().find(|&&n| !float_fits_i64(n)) {
+                    return Err(lossy(n));
+                }
+                arr.convert_with(|n| n as i64).into()
+            }
+            ("complex", Value::Num(arr)) => arr.convert::<Complex>().into(),
+            ("complex", Value::Byte(arr)) => arr.convert::<Complex>().into(),
+            ("complex", Value::Float(arr)) => arr.convert::<Complex>().into(),
+            ("complex", Value::Int(arr)) => {
+                if let Some(n) = arr.data.iter().find(|&&n| !int_fits_f64(n)) {
+                    return Err(lossy(n));
+                }
+                arr.convert::<Complex>().into()
+            }
+            ("complex", Value::Complex(arr)) => arr.into(),
+            // Rounding to the nearest `f32` is the point of this storage, so only overflow is an error
+            ("float", Value::Num(arr)) => {
+                let overflows = |n: f64| n.is_finite() && (n as f32).is_infinite();
+                if let Some(n) = arr.data.iter().find(|&&n| overflows(n)) {
+                    return Err(lossy(n));
+                }
+                arr.convert_with(|n| n as f32).into()
+            }
+            ("float", val @ (Value::Byte(_) | Value::Int(_) | Value::Float(_))) => {
+                val.into_f32_array().into()
+            }
+            ("float", Value::Complex(arr)) => {
+                if let Some(c) = arr.data.iter().find(|c| c.im != 0.0) {
+                    return Err(lossy(c));
+                }
+                Value::from(arr.convert_with(|c| c.re)).store("float", env)?
+            }
+            (storage, _) => {
+                return Err(env.error(format!(
+                    "Unknown storage type {storage:?}. \
+                    Valid storage types are \"byte\", \"int\", \"wrapping\", \"float\", \"num\", and \"complex\"."
+                )))
+            }
+        };
+        let flags = &mut value.meta_mut().flags;
+        flags.set(ArrayFlags::FIXED_STORAGE, true);
+        flags.set(ArrayFlags::WRAPPING, wrapping);
+        Ok(value)
+    }
+    /// Convert a real number array to single-precision numbers
+    ///
+    /// # Panics
+    /// Panics if the value is not a real number array
+    pub(crate) fn into_f32_array(self) -> Array<f32> {
+        match self {
+            Value::Num(arr) => arr.convert_with(|n| n as f32),
+            Value::Byte(arr) => arr.convert(),
+            Value::Int(arr) => arr.convert_with(|n| n as f32),
+            Value::Float(arr) => arr,
+            val => unreachable!("Cannot convert {} array to f32", val.type_name()),
+        }
+    }
+    /// Convert a number array to complex numbers
+    ///
+    /// # Panics
+    /// Panics if the value is not a number array
+    pub(crate) fn into_complex_array(self) -> Array<Complex> {
+        match self {
+            Value::Num(arr) => arr.convert(),
+            Value::Byte(arr) => arr.convert(),
+            Value::Int(arr) => arr.convert(),
+            Value::Float(arr) => arr.convert(),
+            Value::Complex(arr) => arr,
+            val => unreachable!("Cannot convert {} array to complex", val.type_name()),
+        }
+    }
     /// Convert to a box array by boxing every element
     pub fn coerce_to_boxes(self) -> Array<Boxed> {
         match self {
             Value::Num(arr) => arr.convert_with(|v| Boxed(Value::from(v))),
             Value::Byte(arr) => arr.convert_with(|v| Boxed(Value::from(v))),
+            Value::Int(arr) => arr.convert_with(|v| Boxed(Array::from(v).into())),
+            Value::Float(arr) => arr.convert_with(|v| Boxed(Value::from(v))),
             Value::Complex(arr) => arr.convert_with(|v| Boxed(Value::from(v))),
             Value::Char(arr) => arr.convert_with(|v| Boxed(Value::from(v))),
             Value::Box(arr) => arr,
@@ -1433,6 +1990,8 @@ impl Value {
         match self {
             Value::Num(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Value::from(v)))),
             Value::Byte(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Value::from(v)))),
+            Value::Int(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Array::from(v).into()))),
+            Value::Float(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Value::from(v)))),
             Value::Complex(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Value::from(v)))),
             Value::Char(arr) => Cow::Owned(arr.convert_ref_with(|v| Boxed(Value::from(v)))),
             Value::Box(arr) => Cow::Borrowed(arr),
@@ -1448,6 +2007,8 @@ impl Value {
         Ok(result)
     }
     /// Propogate values' labels accross an operation
+    ///
+    /// The first value's label takes precedence
     pub fn keep_labels(
         mut self,
         mut other: Self,
@@ -1456,7 +2017,7 @@ impl Value {
         let label = self.take_label();
         let other_label = other.take_label();
         let mut result = f(self, other)?;
-        if let Some(label) = label.xor(other_label) {
+        if let Some(label) = label.or(other_label) {
             result.meta_mut().label = Some(label);
         }
         Ok(result)
@@ -1498,6 +2059,17 @@ impl Value {
     }
 }
 
+/// Whether an integer can be converted to an `f64` without losing precision
+pub(crate) fn int_fits_f64(n: i64) -> bool {
+    n as f64 as i128 == n as i128
+}
+
+/// Whether a number is an integer that can be converted to an `i64`
+pub(crate) fn float_fits_i64(n: f64) -> bool {
+    // `i64::MAX as f64` rounds up to 2^63, which does not fit
+    n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64
+}
+
 macro_rules! value_from {
     ($ty:ty, $variant:ident) => {
         impl From<$ty> for Value {
@@ -1505,6 +2077,12 @@ macro_rules! value_from {
                 Self::$variant(Array::from(item))
             }
         }
+        value_from_collections!($ty, $variant);
+    };
+}
+
+macro_rules! value_from_collections {
+    ($ty:ty, $variant:ident) => {
         impl From<Array<$ty>> for Value {
             fn from(array: Array<$ty>) -> Self {
                 Self::$variant(array)
@@ -1548,6 +2126,9 @@ value_from!(u8, Byte);
 value_from!(char, Char);
 value_from!(Boxed, Box);
 value_from!(Complex, Complex);
+value_from!(f32, Float);
+// Scalar `i64`s are converted to numbers
+value_from_collections!(i64, Int);
 
 impl FromIterator<usize> for Value {
     fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
@@ -1603,10 +2184,20 @@ macro_rules! value_un_impl {
     ($name:ident, $(
         $([$(|$meta:ident| $pred:expr,)* $in_place:ident, $f:ident])?
         $(($make_new:ident, $f2:ident))?
+        $({$checked:ident, $f3:ident $(, $wrapping:ident)?})?
     ),* $(,)?) => {
         impl Value {
             #[allow(clippy::redundant_closure_call)]
-            pub(crate) fn $name(self, env: &Uiua) -> UiuaResult<Self> {
+            pub(crate) fn $name(mut self, env: &Uiua) -> UiuaResult<Self> {
+                if let Some(unit) = self.take_unit() {
+                    let unit = quantity::monadic_unit(stringify!($name), unit, env)?;
+                    let mut val = self.$name(env)?;
+                    quantity::set_unit(&mut val, unit);
+                    return Ok(val);
+                }
+                if self.meta().flags.is_interval() {
+                    return interval::monadic(stringify!($name), self, env);
+                }
                 self.keep_meta(|val| Ok(match val {
                     $($(Self::$in_place(mut array) $(if (|$meta: &ArrayMeta| $pred)(array.meta()))* => {
                         for val in &mut array.data {
@@ -1621,6 +2212,17 @@ macro_rules! value_un_impl {
                         }
                         (array.shape, new).into()
                     },)*)*
+                    $($(Self::$checked(mut array) => {
+                        let wrapping = array.meta().flags.is_wrapping();
+                        for val in &mut array.data {
+                            *val = $(if wrapping {
+                                $name::$wrapping(*val)
+                            } else)* {
+                                $name::$f3(*val, env)?
+                            };
+                        }
+                        array.into()
+                    },)*)*
                     Value::Box(mut array) => {
                         let mut new_data = EcoVec::with_capacity(array.element_count());
                         for b in array.data {
@@ -1641,6 +2243,8 @@ value_un_impl!(
     scalar_neg,
     [Num, num],
     (Byte, byte),
+    {Int, int, wrapping_int},
+    [Float, float],
     [Complex, com],
     [Char, char]
 );
@@ -1649,37 +2253,102 @@ value_un_impl!(
     [Num, num],
     [|meta| meta.flags.is_boolean(), Byte, bool],
     (Byte, byte),
+    {Int, int, wrapping_int},
+    [Float, float],
     [Complex, com]
 );
 value_un_impl!(
     scalar_abs,
     [Num, num],
     (Byte, byte),
+    {Int, int, wrapping_int},
+    [Float, float],
     (Complex, com),
     [Char, char]
 );
-value_un_impl!(sign, [Num, num], [Byte, byte], [Complex, com], (Char, char));
+value_un_impl!(
+    sign,
+    [Num, num],
+    [Byte, byte],
+    [Int, int],
+    [Float, float],
+    [Complex, com],
+    (Char, char)
+);
 value_un_impl!(
     sqrt,
     [Num, num],
     [|meta| meta.flags.is_boolean(), Byte, bool],
     (Byte, byte),
+    (Int, int),
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    sin,
+    [Num, num],
+    (Byte, byte),
+    (Int, int),
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    cos,
+    [Num, num],
+    (Byte, byte),
+    (Int, int),
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    asin,
+    [Num, num],
+    (Byte, byte),
+    (Int, int),
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    floor,
+    [Num, num],
+    [Byte, byte],
+    [Int, int],
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    ceil,
+    [Num, num],
+    [Byte, byte],
+    [Int, int],
+    [Float, float],
+    [Complex, com]
+);
+value_un_impl!(
+    round,
+    [Num, num],
+    [Byte, byte],
+    [Int, int],
+    [Float, float],
     [Complex, com]
 );
-value_un_impl!(sin, [Num, num], (Byte, byte), [Complex, com]);
-value_un_impl!(cos, [Num, num], (Byte, byte), [Complex, com]);
-value_un_impl!(asin, [Num, num], (Byte, byte), [Complex, com]);
-value_un_impl!(floor, [Num, num], [Byte, byte], [Complex, com]);
-value_un_impl!(ceil, [Num, num], [Byte, byte], [Complex, com]);
-value_un_impl!(round, [Num, num], [Byte, byte], [Complex, com]);
 value_un_impl!(
     complex_re,
     [Num, generic],
     [Byte, generic],
+    [Int, generic],
+    [Float, generic],
     (Complex, com),
     [Char, generic]
 );
-value_un_impl!(complex_im, [Num, num], [Byte, byte], (Complex, com));
+value_un_impl!(
+    complex_im,
+    [Num, num],
+    [Byte, byte],
+    [Int, int],
+    [Float, float],
+    (Complex, com)
+);
 
 impl Value {
     /// Get the `absolute value` of a value
@@ -1748,6 +2417,51 @@ impl Value {
     }
 }
 
+impl Value {
+    /// Bring integer and single-precision arrays to a common storage with the other operand
+    ///
+    /// Numbers that are all exact integers are converted to integers so that
+    /// arithmetic stays exact. Otherwise, the integers are converted to numbers.
+    ///
+    /// Single-precision arrays keep their storage when combined with numbers or bytes.
+    /// Combined with integers, both become numbers so that large integers keep their precision.
+    fn unify_storage(a: Self, b: Self) -> (Self, Self) {
+        fn nums_as_ints(nums: &Array<f64>) -> Option<Array<i64>> {
+            (nums.data.iter().all(|&n| float_fits_i64(n)))
+                .then(|| nums.convert_ref_with(|n| n as i64))
+        }
+        match (a, b) {
+            (Value::Int(a), Value::Num(b)) => match nums_as_ints(&b) {
+                Some(b) => (a.into(), b.into()),
+                None => (a.convert_with(|n| n as f64).into(), b.into()),
+            },
+            (Value::Num(a), Value::Int(b)) => match nums_as_ints(&a) {
+                Some(a) => (a.into(), b.into()),
+                None => (a.into(), b.convert_with(|n| n as f64).into()),
+            },
+            (Value::Int(a), b @ Value::Char(_)) => (a.convert_with(|n| n as f64).into(), b),
+            (a @ Value::Char(_), Value::Int(b)) => (a, b.convert_with(|n| n as f64).into()),
+            (Value::Float(a), Value::Int(b)) => (
+                a.convert_with(|n| n as f64).into(),
+                b.convert_with(|n| n as f64).into(),
+            ),
+            (Value::Int(a), Value::Float(b)) => (
+                a.convert_with(|n| n as f64).into(),
+                b.convert_with(|n| n as f64).into(),
+            ),
+            (a @ Value::Float(_), b @ (Value::Num(_) | Value::Byte(_))) => {
+                (a, b.into_f32_array().into())
+            }
+            (a @ (Value::Num(_) | Value::Byte(_)), b @ Value::Float(_)) => {
+                (a.into_f32_array().into(), b)
+            }
+            (Value::Float(a), b @ Value::Char(_)) => (a.convert_with(|n| n as f64).into(), b),
+            (a @ Value::Char(_), Value::Float(b)) => (a, b.convert_with(|n| n as f64).into()),
+            (a, b) => (a, b),
+        }
+    }
+}
+
 macro_rules! val_retry {
     (Byte, $env:expr) => {
         $env.num_scalar_fill().is_ok()
@@ -1761,11 +2475,28 @@ macro_rules! value_bin_impl {
     ($name:ident, $(
         $(($na:ident, $nb:ident, $f1:ident $(, $retry:ident)? ))*
         $([$(|$meta:ident| $pred:expr,)* $ip:ident, $f2:ident $(, $retry2:ident)? $(, $reset_meta:literal)?])*
+        $({$ia:ident, $ib:ident, $f3:ident $(, $wrapping:ident)? $(; $as_num:ident)?})*
+        $(<$fa:ident, $fb:ident, $f4:ident>)*
     ),* ) => {
         impl Value {
             #[allow(unreachable_patterns, unused_mut, clippy::wrong_self_convention)]
-            pub(crate) fn $name(self, other: Self, a_depth: usize, b_depth: usize, env: &Uiua) -> UiuaResult<Self> {
-                self.keep_metas(other, |a, b| { Ok(match (a, b) {
+            pub(crate) fn $name(mut self, mut other: Self, a_depth: usize, b_depth: usize, env: &Uiua) -> UiuaResult<Self> {
+                if self.meta().unit.is_some() || other.meta().unit.is_some() {
+                    let (a_unit, b_unit) = (self.take_unit(), other.take_unit());
+                    let unit = quantity::dyadic_unit(stringify!($name), &self, a_unit, b_unit, env)?;
+                    let mut val = self.$name(other, a_depth, b_depth, env)?;
+                    quantity::set_unit(&mut val, unit);
+                    return Ok(val);
+                }
+                if self.meta().flags.is_interval() || other.meta().flags.is_interval() {
+                    return interval::dyadic(stringify!($name), self, other, a_depth, b_depth, env);
+                }
+                self.keep_metas(other, |a, b| {
+                let (a, b) = match simd::dyadic(simd::simd_op!($name), a, b, a_depth, b_depth) {
+                    Ok(val) => return Ok(val),
+                    Err((a, b)) => (a, b),
+                };
+                Ok(match Value::unify_storage(a, b) {
                     $($((Value::$ip(mut a), Value::$ip(mut b)) $(if {
                         let f = |$meta: &ArrayMeta| $pred;
                         f(a.meta()) && f(b.meta())
@@ -1813,6 +2544,34 @@ macro_rules! value_bin_impl {
                         val.reset_meta_flags();
                         val
                     },)*)*
+                    $($((Value::$ia(a), Value::$ib(b)) => {
+                        $(if $name::$as_num(&a) {
+                            let a = a.convert_with(|x| x.to_f64());
+                            let b = b.convert_with(|x| x.to_f64());
+                            let mut val: Value = bin_pervade(a, b, a_depth, b_depth, env, InfalliblePervasiveFn::new($name::num_num))?.into();
+                            val.reset_meta_flags();
+                            return Ok(val);
+                        })*
+                        let wrapping = a.meta().flags.is_wrapping() || b.meta().flags.is_wrapping();
+                        let (a, b) = (a.convert::<i64>(), b.convert::<i64>());
+                        let mut val: Value = $(if wrapping {
+                            bin_pervade(a, b, a_depth, b_depth, env, InfalliblePervasiveFn::new($name::$wrapping))?.into()
+                        } else)* {
+                            bin_pervade(a, b, a_depth, b_depth, env, FalliblePerasiveFn::new($name::$f3))?.into()
+                        };
+                        val.reset_meta_flags();
+                        if wrapping {
+                            val.meta_mut().flags.set(ArrayFlags::WRAPPING, true);
+                        }
+                        val
+                    },)*)*
+                    $($((Value::$fa(a), Value::$fb(b)) => {
+                        let a = a.convert_with(|x| x.to_f64());
+                        let b = b.convert_with(|x| x.to_f64());
+                        let mut val: Value = bin_pervade(a, b, a_depth, b_depth, env, InfalliblePervasiveFn::new($name::$f4))?.into();
+                        val.reset_meta_flags();
+                        val
+                    },)*)*
                     (Value::Box(a), Value::Box(b)) => {
                         let (a, b) = match (a.into_unboxed(), b.into_unboxed()) {
                             (Ok(a), Ok(b)) => return Ok(Boxed(Value::$name(a, b, a_depth, b_depth, env)?).into()),
@@ -1863,12 +2622,23 @@ macro_rules! value_bin_math_impl {
             (Num, Complex, x_com),
             (Complex, Byte, com_x),
             (Byte, Complex, x_com),
+            (Complex, Int, com_x),
+            (Int, Complex, x_com),
+            [Float, float_float],
+            (Complex, Float, com_x),
+            (Float, Complex, x_com),
+            <Int, Int, num_num>,
+            <Int, Byte, num_num>,
+            <Byte, Int, num_num>,
         );
     };
 }
 
 value_bin_math_impl!(
     add,
+    {Int, Int, int_int, wrapping_int_int},
+    {Int, Byte, int_int, wrapping_int_int},
+    {Byte, Int, int_int, wrapping_int_int},
     (Num, Char, num_char),
     (Char, Num, char_num),
     (Byte, Char, byte_char),
@@ -1883,12 +2653,18 @@ value_bin_math_impl!(
 );
 value_bin_math_impl!(
     sub,
+    {Int, Int, int_int, wrapping_int_int},
+    {Int, Byte, int_int, wrapping_int_int},
+    {Byte, Int, int_int, wrapping_int_int},
     (Num, Char, num_char),
     (Char, Char, char_char),
     (Byte, Char, byte_char),
 );
 value_bin_math_impl!(
     mul,
+    {Int, Int, int_int, wrapping_int_int},
+    {Int, Byte, int_int, wrapping_int_int},
+    {Byte, Int, int_int, wrapping_int_int},
     (Num, Char, num_char),
     (Char, Num, char_num),
     (Byte, Char, byte_char),
@@ -1896,18 +2672,30 @@ value_bin_math_impl!(
     [|meta| meta.flags.is_boolean(), Byte, bool_bool, num_num],
 );
 value_bin_math_impl!(div, (Num, Char, num_char), (Byte, Char, byte_char),);
-value_bin_math_impl!(modulus, (Complex, Complex, com_com));
+value_bin_math_impl!(
+    modulus,
+    (Complex, Complex, com_com),
+    {Int, Int, int_int; zero_divisor},
+    {Int, Byte, int_int; zero_divisor},
+    {Byte, Int, int_int; zero_divisor},
+);
 value_bin_math_impl!(pow);
 value_bin_math_impl!(log);
 value_bin_math_impl!(atan2);
 value_bin_math_impl!(
     min,
     [Char, char_char],
+    {Int, Int, int_int},
+    {Int, Byte, int_int},
+    {Byte, Int, int_int},
     [|meta| meta.flags.is_boolean(), Byte, bool_bool, num_num],
 );
 value_bin_math_impl!(
     max,
     [Char, char_char],
+    {Int, Int, int_int},
+    {Int, Byte, int_int},
+    {Byte, Int, int_int},
     [|meta| meta.flags.is_boolean(), Byte, bool_bool, num_num],
 );
 
@@ -1922,6 +2710,14 @@ value_bin_impl!(
     (Num, Complex, x_com),
     (Complex, Byte, com_x),
     (Byte, Complex, x_com),
+    (Complex, Int, com_x),
+    (Int, Complex, x_com),
+    (Complex, Float, com_x),
+    (Float, Complex, x_com),
+    <Int, Int, num_num>,
+    <Int, Byte, num_num>,
+    <Byte, Int, num_num>,
+    <Float, Float, num_num>,
 );
 
 macro_rules! eq_impls {
@@ -1941,6 +2737,14 @@ macro_rules! eq_impls {
                 (Num, Complex, x_com),
                 (Complex, Byte, com_x),
                 (Byte, Complex, x_com),
+                [Int, same_type],
+                (Int, Byte, cross),
+                (Byte, Int, cross),
+                (Complex, Int, com_x),
+                (Int, Complex, x_com),
+                [Float, same_type],
+                (Complex, Float, com_x),
+                (Float, Complex, x_com),
                 // Type comparable
                 (Num, Char, always_less),
                 (Byte, Char, always_less),
@@ -1968,6 +2772,14 @@ macro_rules! cmp_impls {
                 (Num, Complex, x_com),
                 (Complex, Byte, com_x),
                 (Byte, Complex, x_com),
+                [Int, same_type],
+                (Int, Byte, cross),
+                (Byte, Int, cross),
+                (Complex, Int, com_x),
+                (Int, Complex, x_com),
+                [Float, same_type],
+                (Complex, Float, com_x),
+                (Float, Complex, x_com),
                 // Type comparable
                 (Num, Char, always_less),
                 (Byte, Char, always_less),
@@ -2001,6 +2813,18 @@ impl PartialEq for Value {
             (Value::Box(a), Value::Box(b)) => a == b,
             (Value::Num(a), Value::Byte(b)) => a == b,
             (Value::Byte(a), Value::Num(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Int(a), Value::Num(b)) => a == b,
+            (Value::Num(a), Value::Int(b)) => a == b,
+            (Value::Int(a), Value::Byte(b)) => a == b,
+            (Value::Byte(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Float(a), Value::Num(b)) => a == b,
+            (Value::Num(a), Value::Float(b)) => a == b,
+            (Value::Float(a), Value::Byte(b)) => a == b,
+            (Value::Byte(a), Value::Float(b)) => a == b,
+            (Value::Float(a), Value::Int(b)) => a == b,
+            (Value::Int(a), Value::Float(b)) => a == b,
             _ => false,
         }
     }
@@ -2028,10 +2852,26 @@ impl Ord for Value {
             (Value::Box(a), Value::Box(b)) => a.cmp(b),
             (Value::Num(a), Value::Byte(b)) => a.partial_cmp(b).unwrap(),
             (Value::Byte(a), Value::Num(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.cmp(b),
+            (Value::Int(a), Value::Num(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Num(a), Value::Int(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Int(a), Value::Byte(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Byte(a), Value::Int(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Float(a), Value::Num(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Num(a), Value::Float(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Float(a), Value::Byte(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Byte(a), Value::Float(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Float(a), Value::Int(b)) => a.partial_cmp(b).unwrap(),
+            (Value::Int(a), Value::Float(b)) => a.partial_cmp(b).unwrap(),
             (Value::Num(_), _) => Ordering::Less,
             (_, Value::Num(_)) => Ordering::Greater,
             (Value::Byte(_), _) => Ordering::Less,
             (_, Value::Byte(_)) => Ordering::Greater,
+            (Value::Int(_), _) => Ordering::Less,
+            (_, Value::Int(_)) => Ordering::Greater,
+            (Value::Float(_), _) => Ordering::Less,
+            (_, Value::Float(_)) => Ordering::Greater,
             (Value::Complex(_), _) => Ordering::Less,
             (_, Value::Complex(_)) => Ordering::Greater,
             (Value::Char(_), _) => Ordering::Less,
@@ -2045,6 +2885,8 @@ impl Hash for Value {
         match self {
             Value::Num(arr) => arr.hash(state),
             Value::Byte(arr) => arr.hash(state),
+            Value::Int(arr) => arr.hash(state),
+            Value::Float(arr) => arr.hash(state),
             Value::Complex(arr) => arr.hash(state),
             Value::Char(arr) => arr.hash(state),
             Value::Box(arr) => arr.hash(state),
@@ -2057,6 +2899,8 @@ impl fmt::Debug for Value {
         match self {
             Self::Num(array) => array.fmt(f),
             Self::Byte(array) => array.fmt(f),
+            Self::Int(array) => array.fmt(f),
+            Self::Float(array) => array.fmt(f),
             Self::Complex(array) => array.fmt(f),
             Self::Char(array) => array.fmt(f),
             Self::Box(array) => array.fmt(f),
@@ -2074,6 +2918,9 @@ impl fmt::Display for Value {
     }
 }
 
+/// A builder for a value made of rows that are added one at a time
+///
+/// This is equivalent to [`Value::from_row_values`], but the rows do not all need to exist at once.
 #[derive(Clone, Default)]
 pub(crate) struct ValueBuilder {
     value: Option<Value>,
@@ -2089,18 +2936,30 @@ impl ValueBuilder {
             capacity,
         }
     }
-    pub fn add_row<C: FillContext>(&mut self, mut row: Value, ctx: &C) -> Result<(), C::Error> {
-        if let Some(value) = &mut self.value {
-            value.append(row, false, ctx)?;
-        } else {
-            row.reserve_min(self.capacity);
-            row.shape_mut().insert(0, 1);
-            self.value = Some(row);
+    pub fn add_row<C: FillContext>(&mut self, row: Value, ctx: &C) -> Result<(), C::Error> {
+        match (&mut self.value, self.rows) {
+            (None, _) => self.value = Some(row),
+            (Some(value), 1) => {
+                let row_elems = value.shape().elements();
+                validate_size_impl(row.elem_size(), [self.capacity, row_elems])
+                    .map_err(|e| ctx.error(e))?;
+                value.reserve_min(self.capacity * row_elems);
+                value.couple_impl(row, ctx)?;
+            }
+            (Some(value), _) => value.append(row, false, ctx)?,
         }
         self.rows += 1;
         Ok(())
     }
     pub fn finish(self) -> Value {
-        self.value.unwrap_or_default()
+        match self.value {
+            Some(mut value) => {
+                if self.rows == 1 {
+                    value.shape_mut().insert(0, 1);
+                }
+                value
+            }
+            None => Value::default(),
+        }
     }
 }
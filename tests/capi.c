@@ -0,0 +1,56 @@
+/* Smoke test for the C API
+ *
+ * Build and run with
+ *     cargo build --lib --features capi
+ *     cc tests/capi.c -Iinclude -Ltarget/debug -luiua -o target/capi
+ *     LD_LIBRARY_PATH=target/debug ./target/capi
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "uiua.h"
+
+#define CHECK(cond) \
+    if (!(cond)) { \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+        return 1; \
+    }
+
+int main(void) {
+    UiuaRuntime *rt = uiua_new();
+    CHECK(rt != NULL);
+
+    double data[] = {1, 2, 3, 4, 5, 6};
+    size_t shape[] = {2, 3};
+    CHECK(uiua_push_array(rt, data, shape, 2) == 0);
+    CHECK(uiua_run_str(rt, "/+") == 0);
+    CHECK(uiua_top_rank(rt) == 1);
+    size_t out_shape[1];
+    CHECK(uiua_top_shape(rt, out_shape) == 0);
+    CHECK(out_shape[0] == 3);
+    double sums[3];
+    CHECK(uiua_pop_array(rt, sums, 3) == 0);
+    CHECK(sums[0] == 5 && sums[1] == 7 && sums[2] == 9);
+
+    CHECK(uiua_push_number(rt, 2) == 0);
+    CHECK(uiua_run_str(rt, "ⁿ10") == 0);
+    double n;
+    CHECK(uiua_pop_number(rt, &n) == 0);
+    CHECK(n == 1024);
+
+    CHECK(uiua_run_str(rt, "+1") != 0);
+    CHECK(uiua_last_error(rt) != NULL);
+
+    CHECK(uiua_push_string(rt, "Hello") == 0);
+    CHECK(uiua_run_str(rt, "$\"_ world\"") == 0);
+    CHECK(uiua_last_error(rt) == NULL);
+    char *s = uiua_pop_string(rt);
+    CHECK(s != NULL && strcmp(s, "Hello world") == 0);
+    uiua_free_string(s);
+
+    CHECK(uiua_stack_len(rt) == 0);
+    uiua_free(rt);
+    printf("C API smoke test passed\n");
+    return 0;
+}